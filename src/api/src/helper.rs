@@ -43,7 +43,8 @@ use greptime_proto::v1::greptime_request::Request;
 use greptime_proto::v1::query_request::Query;
 use greptime_proto::v1::value::ValueData;
 use greptime_proto::v1::{
-    ColumnDataTypeExtension, DdlRequest, DecimalTypeExtension, QueryRequest, Row, SemanticType,
+    ColumnDataTypeExtension, DdlRequest, DecimalTypeExtension, JsonTypeExtension, QueryRequest,
+    Row, SemanticType,
 };
 use paste::paste;
 use snafu::prelude::*;
@@ -104,7 +105,17 @@ impl From<ColumnDataTypeWrapper> for ConcreteDataType {
             ColumnDataType::Uint64 => ConcreteDataType::uint64_datatype(),
             ColumnDataType::Float32 => ConcreteDataType::float32_datatype(),
             ColumnDataType::Float64 => ConcreteDataType::float64_datatype(),
-            ColumnDataType::Binary => ConcreteDataType::binary_datatype(),
+            ColumnDataType::Binary => {
+                if let Some(TypeExt::JsonType(_)) = datatype_wrapper
+                    .datatype_ext
+                    .as_ref()
+                    .and_then(|datatype_ext| datatype_ext.type_ext.as_ref())
+                {
+                    ConcreteDataType::json_datatype()
+                } else {
+                    ConcreteDataType::binary_datatype()
+                }
+            }
             ColumnDataType::String => ConcreteDataType::string_datatype(),
             ColumnDataType::Date => ConcreteDataType::date_datatype(),
             ColumnDataType::Datetime => ConcreteDataType::datetime_datatype(),
@@ -231,6 +242,15 @@ impl ColumnDataTypeWrapper {
             }),
         }
     }
+
+    pub fn json_datatype() -> Self {
+        ColumnDataTypeWrapper {
+            datatype: ColumnDataType::Binary,
+            datatype_ext: Some(ColumnDataTypeExtension {
+                type_ext: Some(TypeExt::JsonType(JsonTypeExtension::JsonBinary.into())),
+            }),
+        }
+    }
 }
 
 impl TryFrom<ConcreteDataType> for ColumnDataTypeWrapper {
@@ -249,7 +269,9 @@ impl TryFrom<ConcreteDataType> for ColumnDataTypeWrapper {
             ConcreteDataType::UInt64(_) => ColumnDataType::Uint64,
             ConcreteDataType::Float32(_) => ColumnDataType::Float32,
             ConcreteDataType::Float64(_) => ColumnDataType::Float64,
-            ConcreteDataType::Binary(_) => ColumnDataType::Binary,
+            // JSON values are transmitted in the binary JSONB format, the JSON type is kept
+            // in the type extension.
+            ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) => ColumnDataType::Binary,
             ConcreteDataType::String(_) => ColumnDataType::String,
             ConcreteDataType::Date(_) => ColumnDataType::Date,
             ConcreteDataType::DateTime(_) => ColumnDataType::Datetime,
@@ -294,6 +316,9 @@ impl TryFrom<ConcreteDataType> for ColumnDataTypeWrapper {
                         })),
                     })
             }
+            ColumnDataType::Binary if datatype.is_json() => Some(ColumnDataTypeExtension {
+                type_ext: Some(TypeExt::JsonType(JsonTypeExtension::JsonBinary.into())),
+            }),
             _ => None,
         };
         Ok(Self {
@@ -633,7 +658,9 @@ pub fn pb_values_to_vector_ref(data_type: &ConcreteDataType, values: Values) ->
         ConcreteDataType::UInt64(_) => Arc::new(UInt64Vector::from_vec(values.u64_values)),
         ConcreteDataType::Float32(_) => Arc::new(Float32Vector::from_vec(values.f32_values)),
         ConcreteDataType::Float64(_) => Arc::new(Float64Vector::from_vec(values.f64_values)),
-        ConcreteDataType::Binary(_) => Arc::new(BinaryVector::from(values.binary_values)),
+        ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) => {
+            Arc::new(BinaryVector::from(values.binary_values))
+        }
         ConcreteDataType::String(_) => Arc::new(StringVector::from_vec(values.string_values)),
         ConcreteDataType::Date(_) => Arc::new(DateVector::from_vec(values.date_values)),
         ConcreteDataType::DateTime(_) => Arc::new(DateTimeVector::from_vec(values.datetime_values)),
@@ -773,7 +800,7 @@ pub fn pb_values_to_values(data_type: &ConcreteDataType, values: Values) -> Vec<
             .into_iter()
             .map(|val| val.into())
             .collect(),
-        ConcreteDataType::Binary(_) => values
+        ConcreteDataType::Binary(_) | ConcreteDataType::Json(_) => values
             .binary_values
             .into_iter()
             .map(|val| val.into())
@@ -1270,6 +1297,10 @@ mod tests {
             ConcreteDataType::binary_datatype(),
             ColumnDataTypeWrapper::binary_datatype().into()
         );
+        assert_eq!(
+            ConcreteDataType::json_datatype(),
+            ColumnDataTypeWrapper::json_datatype().into()
+        );
         assert_eq!(
             ConcreteDataType::string_datatype(),
             ColumnDataTypeWrapper::string_datatype().into()
@@ -1362,6 +1393,10 @@ mod tests {
             ColumnDataTypeWrapper::binary_datatype(),
             ConcreteDataType::binary_datatype().try_into().unwrap()
         );
+        assert_eq!(
+            ColumnDataTypeWrapper::json_datatype(),
+            ConcreteDataType::json_datatype().try_into().unwrap()
+        );
         assert_eq!(
             ColumnDataTypeWrapper::string_datatype(),
             ConcreteDataType::string_datatype().try_into().unwrap()
@@ -2032,6 +2067,18 @@ mod tests {
             column1.datatype_extension,
             &ConcreteDataType::boolean_datatype(),
         ));
+
+        let (datatype, datatype_ext) = ColumnDataTypeWrapper::json_datatype().to_parts();
+        assert!(is_column_type_value_eq(
+            datatype as i32,
+            datatype_ext,
+            &ConcreteDataType::json_datatype(),
+        ));
+        assert!(!is_column_type_value_eq(
+            ColumnDataType::Binary as i32,
+            None,
+            &ConcreteDataType::json_datatype(),
+        ));
     }
 
     #[test]
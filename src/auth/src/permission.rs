@@ -30,6 +30,8 @@ pub enum PermissionReq<'a> {
     PromStoreWrite,
     PromStoreRead,
     Otlp,
+    BulkInsert,
+    TableSchema,
 }
 
 #[derive(Debug)]
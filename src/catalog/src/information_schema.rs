@@ -17,6 +17,7 @@ mod key_column_usage;
 mod memory_table;
 mod partitions;
 mod predicate;
+mod region_index_builds;
 mod region_peers;
 mod runtime_metrics;
 pub mod schemata;
@@ -50,6 +51,7 @@ use crate::error::Result;
 use crate::information_schema::key_column_usage::InformationSchemaKeyColumnUsage;
 use crate::information_schema::memory_table::{get_schema_columns, MemoryTable};
 use crate::information_schema::partitions::InformationSchemaPartitions;
+use crate::information_schema::region_index_builds::InformationSchemaRegionIndexBuilds;
 use crate::information_schema::region_peers::InformationSchemaRegionPeers;
 use crate::information_schema::runtime_metrics::InformationSchemaMetrics;
 use crate::information_schema::schemata::InformationSchemaSchemata;
@@ -165,6 +167,10 @@ impl InformationSchemaProvider {
                 REGION_PEERS.to_string(),
                 self.build_table(REGION_PEERS).unwrap(),
             );
+            tables.insert(
+                REGION_INDEX_BUILDS.to_string(),
+                self.build_table(REGION_INDEX_BUILDS).unwrap(),
+            );
         }
 
         tables.insert(TABLES.to_string(), self.build_table(TABLES).unwrap());
@@ -243,6 +249,10 @@ impl InformationSchemaProvider {
                 self.catalog_name.clone(),
                 self.catalog_manager.clone(),
             )) as _),
+            REGION_INDEX_BUILDS => Some(Arc::new(InformationSchemaRegionIndexBuilds::new(
+                self.catalog_name.clone(),
+                self.catalog_manager.clone(),
+            )) as _),
             _ => None,
         }
     }
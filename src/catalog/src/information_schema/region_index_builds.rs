@@ -0,0 +1,354 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::pin::pin;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use common_catalog::consts::INFORMATION_SCHEMA_REGION_INDEX_BUILDS_TABLE_ID;
+use common_error::ext::BoxedError;
+use common_meta::datanode_manager::DatanodeManagerRef;
+use common_meta::peer::Peer;
+use common_query::physical_plan::TaskContext;
+use common_recordbatch::adapter::RecordBatchStreamAdapter;
+use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
+use common_telemetry::warn;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter as DfRecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream as DfPartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream as DfSendableRecordBatchStream;
+use datatypes::prelude::{ConcreteDataType, ScalarVectorBuilder, VectorRef};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::timestamp::TimestampMillisecond;
+use datatypes::value::Value;
+use datatypes::vectors::{
+    StringVectorBuilder, TimestampMillisecondVectorBuilder, UInt64VectorBuilder,
+};
+use futures::{StreamExt, TryStreamExt};
+use snafu::{OptionExt, ResultExt};
+use store_api::region_engine::{IndexBuildProgressInfo, RegionIndexBuildProgress};
+use store_api::region_request::{RegionAction, RegionActionOutput};
+use store_api::storage::{RegionId, ScanRequest, TableId};
+use table::metadata::TableType;
+
+use super::REGION_INDEX_BUILDS;
+use crate::error::{
+    CreateRecordBatchSnafu, FindRegionRoutesSnafu, InternalSnafu, Result,
+    UpgradeWeakCatalogManagerRefSnafu,
+};
+use crate::information_schema::{InformationTable, Predicates};
+use crate::kvbackend::KvBackendCatalogManager;
+use crate::CatalogManager;
+
+const REGION_ID: &str = "region_id";
+const PEER_ID: &str = "peer_id";
+const STATUS: &str = "status";
+const TOTAL_FILES: &str = "total_files";
+const PROCESSED_FILES: &str = "processed_files";
+const BUILT_FILES: &str = "built_files";
+const FAILED_FILES: &str = "failed_files";
+const START_TIME: &str = "start_time";
+const INIT_CAPACITY: usize = 42;
+
+/// The `REGION_INDEX_BUILDS` table provides the progress of the latest index build job
+/// of each region, which is scheduled by the `build_region_index` function. Including fields:
+///
+/// - `region_id`: the region id
+/// - `peer_id`: the datanode peer id of the region leader
+/// - `status`: the job status, `RUNNING` or `FINISHED`.
+/// - `total_files`: number of SSTs in the region when the job started.
+/// - `processed_files`: number of SSTs checked by the job.
+/// - `built_files`: number of SSTs whose new indexes are committed.
+/// - `failed_files`: number of SSTs that failed to build indexes.
+/// - `start_time`: the start time of the job.
+///
+/// Regions that never build indexes are not in the table.
+pub(super) struct InformationSchemaRegionIndexBuilds {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+}
+
+impl InformationSchemaRegionIndexBuilds {
+    pub(super) fn new(catalog_name: String, catalog_manager: Weak<dyn CatalogManager>) -> Self {
+        Self {
+            schema: Self::schema(),
+            catalog_name,
+            catalog_manager,
+        }
+    }
+
+    pub(crate) fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            ColumnSchema::new(REGION_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PEER_ID, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(STATUS, ConcreteDataType::string_datatype(), false),
+            ColumnSchema::new(TOTAL_FILES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(PROCESSED_FILES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(BUILT_FILES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(FAILED_FILES, ConcreteDataType::uint64_datatype(), false),
+            ColumnSchema::new(
+                START_TIME,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]))
+    }
+
+    fn builder(&self) -> InformationSchemaRegionIndexBuildsBuilder {
+        InformationSchemaRegionIndexBuildsBuilder::new(
+            self.schema.clone(),
+            self.catalog_name.clone(),
+            self.catalog_manager.clone(),
+        )
+    }
+}
+
+impl InformationTable for InformationSchemaRegionIndexBuilds {
+    fn table_id(&self) -> TableId {
+        INFORMATION_SCHEMA_REGION_INDEX_BUILDS_TABLE_ID
+    }
+
+    fn table_name(&self) -> &'static str {
+        REGION_INDEX_BUILDS
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn to_stream(&self, request: ScanRequest) -> Result<SendableRecordBatchStream> {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        let stream = Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_index_builds(Some(request))
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ));
+        Ok(Box::pin(
+            RecordBatchStreamAdapter::try_new(stream)
+                .map_err(BoxedError::new)
+                .context(InternalSnafu)?,
+        ))
+    }
+}
+
+struct InformationSchemaRegionIndexBuildsBuilder {
+    schema: SchemaRef,
+    catalog_name: String,
+    catalog_manager: Weak<dyn CatalogManager>,
+
+    region_ids: UInt64VectorBuilder,
+    peer_ids: UInt64VectorBuilder,
+    statuses: StringVectorBuilder,
+    total_files: UInt64VectorBuilder,
+    processed_files: UInt64VectorBuilder,
+    built_files: UInt64VectorBuilder,
+    failed_files: UInt64VectorBuilder,
+    start_times: TimestampMillisecondVectorBuilder,
+}
+
+impl InformationSchemaRegionIndexBuildsBuilder {
+    fn new(
+        schema: SchemaRef,
+        catalog_name: String,
+        catalog_manager: Weak<dyn CatalogManager>,
+    ) -> Self {
+        Self {
+            schema,
+            catalog_name,
+            catalog_manager,
+            region_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            peer_ids: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            statuses: StringVectorBuilder::with_capacity(INIT_CAPACITY),
+            total_files: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            processed_files: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            built_files: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            failed_files: UInt64VectorBuilder::with_capacity(INIT_CAPACITY),
+            start_times: TimestampMillisecondVectorBuilder::with_capacity(INIT_CAPACITY),
+        }
+    }
+
+    /// Construct the `information_schema.greptime_region_index_builds` virtual table
+    async fn make_region_index_builds(
+        &mut self,
+        request: Option<ScanRequest>,
+    ) -> Result<RecordBatch> {
+        let catalog_name = self.catalog_name.clone();
+        let catalog_manager = self
+            .catalog_manager
+            .upgrade()
+            .context(UpgradeWeakCatalogManagerRefSnafu)?;
+
+        // Only the frontend can send requests to datanodes.
+        let Some(kv_catalog_manager) = catalog_manager
+            .as_any()
+            .downcast_ref::<KvBackendCatalogManager>()
+        else {
+            return self.finish();
+        };
+        let Some(datanode_manager) = kv_catalog_manager.datanode_manager() else {
+            return self.finish();
+        };
+        let partition_manager = kv_catalog_manager.partition_manager();
+
+        let predicates = Predicates::from_scan_request(&request);
+        // Ids of regions to query, grouped by their leader peers.
+        let mut peer_regions: HashMap<Peer, Vec<RegionId>> = HashMap::new();
+
+        for schema_name in catalog_manager.schema_names(&catalog_name).await? {
+            let table_id_stream = catalog_manager
+                .tables(&catalog_name, &schema_name)
+                .await
+                .try_filter_map(|t| async move {
+                    let table_info = t.table_info();
+                    if table_info.table_type == TableType::Temporary {
+                        Ok(None)
+                    } else {
+                        Ok(Some(table_info.ident.table_id))
+                    }
+                });
+
+            const BATCH_SIZE: usize = 128;
+
+            // Split table ids into chunks
+            let mut table_id_chunks = pin!(table_id_stream.ready_chunks(BATCH_SIZE));
+
+            while let Some(table_ids) = table_id_chunks.next().await {
+                let table_ids = table_ids.into_iter().collect::<Result<Vec<_>>>()?;
+
+                let table_routes = partition_manager
+                    .batch_find_region_routes(&table_ids)
+                    .await
+                    .context(FindRegionRoutesSnafu)?;
+
+                for route in table_routes.values().flatten() {
+                    let region_id = route.region.id;
+                    let row = [(REGION_ID, &Value::from(region_id.as_u64()))];
+                    if !predicates.eval(&row) {
+                        continue;
+                    }
+                    if let Some(peer) = &route.leader_peer {
+                        peer_regions
+                            .entry(peer.clone())
+                            .or_default()
+                            .push(region_id);
+                    }
+                }
+            }
+        }
+
+        // Sends one request to each datanode.
+        let progress_futures = peer_regions
+            .into_iter()
+            .map(|(peer, region_ids)| index_build_progress(&datanode_manager, peer, region_ids));
+        for (peer_id, progresses) in futures::future::join_all(progress_futures).await {
+            for progress in progresses {
+                self.add_progress(progress.region_id, peer_id, &progress.progress);
+            }
+        }
+
+        self.finish()
+    }
+
+    fn add_progress(
+        &mut self,
+        region_id: RegionId,
+        peer_id: u64,
+        progress: &IndexBuildProgressInfo,
+    ) {
+        let status = if progress.finished {
+            "FINISHED"
+        } else {
+            "RUNNING"
+        };
+
+        self.region_ids.push(Some(region_id.as_u64()));
+        self.peer_ids.push(Some(peer_id));
+        self.statuses.push(Some(status));
+        self.total_files.push(Some(progress.total_files));
+        self.processed_files.push(Some(progress.processed_files));
+        self.built_files.push(Some(progress.built_files));
+        self.failed_files.push(Some(progress.failed_files));
+        self.start_times
+            .push(Some(TimestampMillisecond::new(progress.start_time_millis)));
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(self.region_ids.finish()),
+            Arc::new(self.peer_ids.finish()),
+            Arc::new(self.statuses.finish()),
+            Arc::new(self.total_files.finish()),
+            Arc::new(self.processed_files.finish()),
+            Arc::new(self.built_files.finish()),
+            Arc::new(self.failed_files.finish()),
+            Arc::new(self.start_times.finish()),
+        ];
+        RecordBatch::new(self.schema.clone(), columns).context(CreateRecordBatchSnafu)
+    }
+}
+
+/// Gets the index build progress of `region_ids` from their leader `peer`.
+///
+/// Returns an empty list if the leader is unavailable, so a down datanode doesn't
+/// fail the whole query. Regions that never build indexes are not in the list.
+async fn index_build_progress(
+    datanode_manager: &DatanodeManagerRef,
+    peer: Peer,
+    region_ids: Vec<RegionId>,
+) -> (u64, Vec<RegionIndexBuildProgress>) {
+    let action = RegionAction::IndexBuildProgress { region_ids };
+    let progresses = match datanode_manager
+        .datanode(&peer)
+        .await
+        .handle_action(action)
+        .await
+    {
+        Ok(RegionActionOutput::IndexBuildProgress(progresses)) => progresses,
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            warn!(e; "Failed to get index build progress of regions, peer: {}", peer.id);
+            Vec::new()
+        }
+    };
+
+    (peer.id, progresses)
+}
+
+impl DfPartitionStream for InformationSchemaRegionIndexBuilds {
+    fn schema(&self) -> &ArrowSchemaRef {
+        self.schema.arrow_schema()
+    }
+
+    fn execute(&self, _: Arc<TaskContext>) -> DfSendableRecordBatchStream {
+        let schema = self.schema.arrow_schema().clone();
+        let mut builder = self.builder();
+        Box::pin(DfRecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::once(async move {
+                builder
+                    .make_region_index_builds(None)
+                    .await
+                    .map(|x| x.into_df_record_batch())
+                    .map_err(Into::into)
+            }),
+        ))
+    }
+}
@@ -24,6 +24,7 @@ use common_catalog::consts::{
 use common_catalog::format_full_table_name;
 use common_error::ext::BoxedError;
 use common_meta::cache_invalidator::{CacheInvalidator, CacheInvalidatorRef, Context};
+use common_meta::datanode_manager::DatanodeManagerRef;
 use common_meta::error::Result as MetaResult;
 use common_meta::key::catalog_name::CatalogNameKey;
 use common_meta::key::schema_name::SchemaNameKey;
@@ -64,6 +65,8 @@ pub struct KvBackendCatalogManager {
     cache_invalidator: CacheInvalidatorRef,
     partition_manager: PartitionRuleManagerRef,
     table_metadata_manager: TableMetadataManagerRef,
+    /// Sends region actions to datanodes, e.g. to get the progress of index builds.
+    datanode_manager: Option<DatanodeManagerRef>,
     /// A sub-CatalogManager that handles system tables
     system_catalog: SystemCatalog,
     table_cache: AsyncCache<String, TableRef>,
@@ -106,10 +109,15 @@ const TABLE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
 const TABLE_CACHE_TTI: Duration = Duration::from_secs(5 * 60);
 
 impl KvBackendCatalogManager {
-    pub fn new(backend: KvBackendRef, cache_invalidator: CacheInvalidatorRef) -> Arc<Self> {
+    pub fn new(
+        backend: KvBackendRef,
+        cache_invalidator: CacheInvalidatorRef,
+        datanode_manager: Option<DatanodeManagerRef>,
+    ) -> Arc<Self> {
         Arc::new_cyclic(|me| Self {
             partition_manager: Arc::new(PartitionRuleManager::new(backend.clone())),
             table_metadata_manager: Arc::new(TableMetadataManager::new(backend)),
+            datanode_manager,
             cache_invalidator,
             system_catalog: SystemCatalog {
                 catalog_manager: me.clone(),
@@ -133,6 +141,10 @@ impl KvBackendCatalogManager {
     pub fn table_metadata_manager_ref(&self) -> &TableMetadataManagerRef {
         &self.table_metadata_manager
     }
+
+    pub fn datanode_manager(&self) -> Option<DatanodeManagerRef> {
+        self.datanode_manager.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -78,6 +78,20 @@ pub enum Error {
 
     #[snafu(display("Failed to send request with streaming: {}", err_msg))]
     ClientStreaming { err_msg: String, location: Location },
+
+    #[snafu(display("Failed to encode region action"))]
+    EncodeRegionAction {
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to decode output of region action"))]
+    DecodeRegionActionOutput {
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -89,7 +103,9 @@ impl ErrorExt for Error {
             | Error::ColumnDataType { .. }
             | Error::MissingField { .. }
             | Error::IllegalDatabaseResponse { .. }
-            | Error::ClientStreaming { .. } => StatusCode::Internal,
+            | Error::ClientStreaming { .. }
+            | Error::EncodeRegionAction { .. }
+            | Error::DecodeRegionActionOutput { .. } => StatusCode::Internal,
 
             Error::Server { code, .. } => *code,
             Error::FlightGet { source, .. }
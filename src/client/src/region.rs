@@ -17,7 +17,7 @@ use std::sync::Arc;
 use api::v1::region::{QueryRequest, RegionRequest, RegionResponse};
 use api::v1::ResponseHeader;
 use arc_swap::ArcSwapOption;
-use arrow_flight::Ticket;
+use arrow_flight::{Action, Ticket};
 use async_stream::stream;
 use async_trait::async_trait;
 use common_error::ext::{BoxedError, ErrorExt};
@@ -31,11 +31,13 @@ use common_telemetry::error;
 use common_telemetry::tracing_context::TracingContext;
 use prost::Message;
 use snafu::{location, Location, OptionExt, ResultExt};
+use store_api::region_request::{RegionAction, RegionActionOutput};
 use tokio_stream::StreamExt;
 
 use crate::error::{
-    self, ConvertFlightDataSnafu, IllegalDatabaseResponseSnafu, IllegalFlightMessagesSnafu,
-    MissingFieldSnafu, Result, ServerSnafu,
+    self, ConvertFlightDataSnafu, DecodeRegionActionOutputSnafu, EncodeRegionActionSnafu,
+    IllegalDatabaseResponseSnafu, IllegalFlightMessagesSnafu, MissingFieldSnafu, Result,
+    ServerSnafu,
 };
 use crate::{metrics, Client, Error};
 
@@ -70,6 +72,13 @@ impl Datanode for RegionRequester {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn handle_action(&self, action: RegionAction) -> MetaResult<RegionActionOutput> {
+        self.do_action_inner(action)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
 }
 
 impl RegionRequester {
@@ -165,6 +174,39 @@ impl RegionRequester {
         Ok(Box::pin(record_batch_stream))
     }
 
+    /// Sends the [RegionAction] by the Flight `do_action` API.
+    pub async fn do_action_inner(&self, action: RegionAction) -> Result<RegionActionOutput> {
+        let action = Action {
+            r#type: RegionAction::FLIGHT_ACTION_TYPE.to_string(),
+            body: serde_json::to_vec(&action)
+                .context(EncodeRegionActionSnafu)?
+                .into(),
+        };
+
+        let mut flight_client = self.client.make_flight_client()?;
+        let mut results = flight_client
+            .mut_inner()
+            .do_action(action)
+            .await
+            .map_err(|e| {
+                let code = e.code();
+                let err: error::Error = e.into();
+                error::Error::RegionServer {
+                    code,
+                    source: BoxedError::new(err),
+                }
+            })?
+            .into_inner();
+
+        let result = results
+            .message()
+            .await?
+            .context(IllegalFlightMessagesSnafu {
+                reason: "Expect the response of action not to be empty",
+            })?;
+        serde_json::from_slice(&result.body).context(DecodeRegionActionOutputSnafu)
+    }
+
     async fn handle_inner(&self, request: RegionRequest) -> Result<AffectedRows> {
         let request_type = request
             .body
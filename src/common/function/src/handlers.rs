@@ -16,9 +16,10 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_base::AffectedRows;
-use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureStateResponse};
+use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureDetail, ProcedureStateResponse};
 use common_query::error::Result;
 use session::context::QueryContextRef;
+use store_api::region_engine::{OrphanFilesInfo, SnapshotInfo};
 use store_api::storage::RegionId;
 use table::requests::{CompactTableRequest, DeleteRequest, FlushTableRequest, InsertRequest};
 
@@ -52,6 +53,45 @@ pub trait TableMutationHandler: Send + Sync {
         region_id: RegionId,
         ctx: QueryContextRef,
     ) -> Result<AffectedRows>;
+
+    /// Create a snapshot named `name` of a table region.
+    async fn create_region_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+        ctx: QueryContextRef,
+    ) -> Result<SnapshotInfo>;
+
+    /// Drop the snapshot named `name` of a table region.
+    async fn drop_region_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+        ctx: QueryContextRef,
+    ) -> Result<()>;
+
+    /// List snapshots of a table region.
+    async fn list_region_snapshots(
+        &self,
+        region_id: RegionId,
+        ctx: QueryContextRef,
+    ) -> Result<Vec<SnapshotInfo>>;
+
+    /// Collect orphan files of a table region, only report them if `dry_run` is true.
+    async fn gc_region_orphan_files(
+        &self,
+        region_id: RegionId,
+        dry_run: bool,
+        ctx: QueryContextRef,
+    ) -> Result<OrphanFilesInfo>;
+
+    /// Build indexes for existing SSTs of a table region, rebuild all indexes if `rebuild_all` is true.
+    async fn build_region_index(
+        &self,
+        region_id: RegionId,
+        rebuild_all: bool,
+        ctx: QueryContextRef,
+    ) -> Result<()>;
 }
 
 /// A trait for handling procedure service requests in `QueryEngine`.
@@ -62,6 +102,12 @@ pub trait ProcedureServiceHandler: Send + Sync {
 
     /// Query the procedure' state by its id
     async fn query_procedure_state(&self, pid: &str) -> Result<ProcedureStateResponse>;
+
+    /// Cancel the procedure by its id
+    async fn cancel_procedure(&self, pid: &str) -> Result<()>;
+
+    /// List procedures in the procedure manager
+    async fn list_procedures(&self) -> Result<Vec<ProcedureDetail>>;
 }
 
 pub type TableMutationHandlerRef = Arc<dyn TableMutationHandler>;
@@ -15,6 +15,7 @@
 pub mod aggregate;
 pub(crate) mod date;
 pub mod expression;
+pub(crate) mod json;
 pub mod math;
 pub mod numpy;
 #[cfg(test)]
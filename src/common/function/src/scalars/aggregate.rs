@@ -15,11 +15,13 @@
 mod argmax;
 mod argmin;
 mod diff;
+mod hll;
 mod mean;
 mod percentile;
 mod polyval;
 mod scipy_stats_norm_cdf;
 mod scipy_stats_norm_pdf;
+mod uddsketch;
 
 use std::sync::Arc;
 
@@ -27,11 +29,19 @@ pub use argmax::ArgmaxAccumulatorCreator;
 pub use argmin::ArgminAccumulatorCreator;
 use common_query::logical_plan::AggregateFunctionCreatorRef;
 pub use diff::DiffAccumulatorCreator;
+pub use hll::{
+    ApproxDistinctAccumulatorCreator, HllAccumulatorCreator, HllCountFunction,
+    HllMergeAccumulatorCreator,
+};
 pub use mean::MeanAccumulatorCreator;
 pub use percentile::PercentileAccumulatorCreator;
 pub use polyval::PolyvalAccumulatorCreator;
 pub use scipy_stats_norm_cdf::ScipyStatsNormCdfAccumulatorCreator;
 pub use scipy_stats_norm_pdf::ScipyStatsNormPdfAccumulatorCreator;
+pub use uddsketch::{
+    ApproxPercentileAccumulatorCreator, UddSketchAccumulatorCreator, UddSketchCalcFunction,
+    UddSketchMergeAccumulatorCreator,
+};
 
 use crate::function_registry::FunctionRegistry;
 
@@ -94,5 +104,15 @@ impl AggregateFunctions {
         register_aggr_func!("percentile", 2, PercentileAccumulatorCreator);
         register_aggr_func!("scipystatsnormcdf", 2, ScipyStatsNormCdfAccumulatorCreator);
         register_aggr_func!("scipystatsnormpdf", 2, ScipyStatsNormPdfAccumulatorCreator);
+        register_aggr_func!("approx_distinct", 1, ApproxDistinctAccumulatorCreator);
+        register_aggr_func!("hll", 1, HllAccumulatorCreator);
+        register_aggr_func!("hll_merge", 1, HllMergeAccumulatorCreator);
+        register_aggr_func!("approx_percentile", 2, ApproxPercentileAccumulatorCreator);
+        register_aggr_func!("uddsketch", 1, UddSketchAccumulatorCreator);
+        register_aggr_func!("uddsketch_merge", 1, UddSketchMergeAccumulatorCreator);
+
+        // Scalar functions to read the sketches built by the aggregate functions above.
+        registry.register(Arc::new(HllCountFunction));
+        registry.register(Arc::new(UddSketchCalcFunction));
     }
 }
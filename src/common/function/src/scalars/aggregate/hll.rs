@@ -0,0 +1,593 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HyperLogLog based approximate distinct counting.
+//!
+//! - `approx_distinct(x)` returns the approximate number of distinct values of `x`.
+//! - `hll(x)` returns the HyperLogLog sketch of `x` in binary, which can be stored
+//!   in a rollup table.
+//! - `hll_merge(sketch)` merges binary sketches into a new sketch.
+//! - `hll_count(sketch)` is a scalar function that returns the cardinality of a sketch.
+
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, InvalidFuncArgsSnafu, Result,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use common_time::timestamp::TimeUnit;
+use datatypes::prelude::*;
+use datatypes::vectors::UInt64Vector;
+use snafu::ensure;
+use twox_hash::XxHash64;
+
+use crate::function::{Function, FunctionContext};
+
+/// Number of bits of the hash to choose the register.
+const HLL_PRECISION: u8 = 14;
+/// Number of registers.
+const HLL_NUM_REGISTERS: usize = 1 << HLL_PRECISION;
+/// Version of the binary format of the sketch.
+const HLL_FORMAT_VERSION: u8 = 1;
+
+/// Seed of the hasher. Changing it makes sketches stored before unmergeable
+/// with new sketches.
+const HLL_HASH_SEED: u64 = 0x4859_5045_524c_4f47;
+
+/// Encodes `value` into bytes that don't depend on the process or the compiler, so
+/// sketches built by different nodes and versions can be merged. Each encoding
+/// starts with a tag of the type so values of different types are distinct.
+fn encode_value(value: ValueRef, buf: &mut Vec<u8>) {
+    fn time_unit_tag(unit: TimeUnit) -> u8 {
+        match unit {
+            TimeUnit::Second => 0,
+            TimeUnit::Millisecond => 1,
+            TimeUnit::Microsecond => 2,
+            TimeUnit::Nanosecond => 3,
+        }
+    }
+
+    buf.clear();
+    match value {
+        ValueRef::Null => buf.push(0),
+        ValueRef::Boolean(v) => buf.extend_from_slice(&[1, v as u8]),
+        // Integers are widened so the same number has the same encoding.
+        ValueRef::UInt8(v) => encode_unsigned(v as u64, buf),
+        ValueRef::UInt16(v) => encode_unsigned(v as u64, buf),
+        ValueRef::UInt32(v) => encode_unsigned(v as u64, buf),
+        ValueRef::UInt64(v) => encode_unsigned(v, buf),
+        ValueRef::Int8(v) => encode_signed(v as i64, buf),
+        ValueRef::Int16(v) => encode_signed(v as i64, buf),
+        ValueRef::Int32(v) => encode_signed(v as i64, buf),
+        ValueRef::Int64(v) => encode_signed(v, buf),
+        ValueRef::Float32(v) => encode_float(v.0 as f64, buf),
+        ValueRef::Float64(v) => encode_float(v.0, buf),
+        ValueRef::Decimal128(v) => {
+            buf.push(5);
+            buf.extend_from_slice(&v.val().to_le_bytes());
+            buf.push(v.scale() as u8);
+        }
+        ValueRef::String(v) => {
+            buf.push(6);
+            buf.extend_from_slice(v.as_bytes());
+        }
+        ValueRef::Binary(v) => {
+            buf.push(7);
+            buf.extend_from_slice(v);
+        }
+        ValueRef::Date(v) => {
+            buf.push(8);
+            buf.extend_from_slice(&v.val().to_le_bytes());
+        }
+        ValueRef::DateTime(v) => {
+            buf.push(9);
+            buf.extend_from_slice(&v.val().to_le_bytes());
+        }
+        ValueRef::Timestamp(v) => {
+            buf.extend_from_slice(&[10, time_unit_tag(v.unit())]);
+            buf.extend_from_slice(&v.value().to_le_bytes());
+        }
+        ValueRef::Time(v) => {
+            buf.extend_from_slice(&[11, time_unit_tag(*v.unit())]);
+            buf.extend_from_slice(&v.value().to_le_bytes());
+        }
+        ValueRef::Duration(v) => {
+            buf.extend_from_slice(&[12, time_unit_tag(v.unit())]);
+            buf.extend_from_slice(&v.value().to_le_bytes());
+        }
+        ValueRef::Interval(v) => {
+            buf.push(13);
+            buf.extend_from_slice(&v.to_i128().to_le_bytes());
+        }
+        ValueRef::List(_) => {
+            buf.push(14);
+            buf.extend_from_slice(Value::from(value).to_string().as_bytes());
+        }
+    }
+}
+
+fn encode_unsigned(v: u64, buf: &mut Vec<u8>) {
+    buf.push(2);
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn encode_signed(v: i64, buf: &mut Vec<u8>) {
+    buf.push(3);
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+fn encode_float(v: f64, buf: &mut Vec<u8>) {
+    // Normalizes -0.0 and NaNs.
+    let v = if v == 0.0 {
+        0.0
+    } else if v.is_nan() {
+        f64::NAN
+    } else {
+        v
+    };
+    buf.push(4);
+    buf.extend_from_slice(&v.to_bits().to_le_bytes());
+}
+
+/// A dense HyperLogLog sketch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> HyperLogLog {
+        HyperLogLog {
+            registers: vec![0; HLL_NUM_REGISTERS],
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// Adds a value to the sketch, `buf` is a buffer to encode the value.
+    pub(crate) fn insert(&mut self, value: ValueRef, buf: &mut Vec<u8>) {
+        encode_value(value, buf);
+        let mut hasher = XxHash64::with_seed(HLL_HASH_SEED);
+        hasher.write(buf);
+        self.insert_hash(hasher.finish());
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // The sentinel bit bounds the rank to `64 - HLL_PRECISION + 1`.
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Merges another sketch into this sketch.
+    pub(crate) fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    /// Returns the estimated number of distinct values.
+    pub(crate) fn count(&self) -> u64 {
+        let m = HLL_NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0usize), |(sum, zeros), register| {
+                (
+                    sum + 1.0 / (1u64 << register) as f64,
+                    zeros + (*register == 0) as usize,
+                )
+            });
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Uses linear counting for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+
+    /// Encodes the sketch into bytes.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + self.registers.len());
+        bytes.push(HLL_FORMAT_VERSION);
+        bytes.push(HLL_PRECISION);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    /// Decodes the sketch from bytes encoded by [HyperLogLog::to_bytes].
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Result<HyperLogLog> {
+        ensure!(
+            bytes.len() == 2 + HLL_NUM_REGISTERS
+                && bytes[0] == HLL_FORMAT_VERSION
+                && bytes[1] == HLL_PRECISION,
+            InvalidFuncArgsSnafu {
+                err_msg: format!("invalid HyperLogLog sketch of {} bytes", bytes.len()),
+            }
+        );
+
+        Ok(HyperLogLog {
+            registers: bytes[2..].to_vec(),
+        })
+    }
+
+    /// Merges sketches in the binary `column` into this sketch, ignoring nulls.
+    fn merge_column(&mut self, column: &VectorRef) -> Result<()> {
+        for i in 0..column.len() {
+            match column.get_ref(i) {
+                ValueRef::Null => {}
+                ValueRef::Binary(bytes) => self.merge(&HyperLogLog::try_from_bytes(bytes)?),
+                other => {
+                    return BadAccumulatorImplSnafu {
+                        err_msg: format!("expect binary sketch, got {:?}", other.data_type()),
+                    }
+                    .fail()
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accumulator of the HyperLogLog based aggregate functions.
+#[derive(Debug, Default)]
+pub struct HllAccumulator {
+    hll: HyperLogLog,
+    /// Whether the input values are sketches to merge.
+    merge_input: bool,
+    /// Whether to evaluate the cardinality instead of the sketch.
+    output_count: bool,
+}
+
+impl Accumulator for HllAccumulator {
+    fn state(&self) -> Result<Vec<Value>> {
+        Ok(vec![Value::from(self.hll.to_bytes())])
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(values.len() == 1, InvalidInputStateSnafu);
+        let column = &values[0];
+        if self.merge_input {
+            return self.hll.merge_column(column);
+        }
+
+        let mut buf = Vec::new();
+        for i in 0..column.len() {
+            let value = column.get_ref(i);
+            if !value.is_null() {
+                self.hll.insert(value, &mut buf);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            states.len() == 1,
+            BadAccumulatorImplSnafu {
+                err_msg: "expect 1 state in `merge_batch`",
+            }
+        );
+        self.hll.merge_column(&states[0])
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        if self.output_count {
+            Ok(Value::from(self.hll.count()))
+        } else {
+            Ok(Value::from(self.hll.to_bytes()))
+        }
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxDistinctAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxDistinctAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |_types: &[ConcreteDataType]| {
+            Ok(Box::new(HllAccumulator {
+                output_count: true,
+                ..Default::default()
+            }))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::uint64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct HllAccumulatorCreator {}
+
+impl AggregateFunctionCreator for HllAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction =
+            Arc::new(move |_types: &[ConcreteDataType]| Ok(Box::<HllAccumulator>::default()));
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::binary_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct HllMergeAccumulatorCreator {}
+
+impl AggregateFunctionCreator for HllMergeAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            let input_type = &types[0];
+            if !matches!(input_type, ConcreteDataType::Binary(_)) {
+                let err_msg = format!(
+                    "\"HLL_MERGE\" aggregate function not support data type {:?}",
+                    input_type.logical_type_id(),
+                );
+                return CreateAccumulatorSnafu { err_msg }.fail();
+            }
+            Ok(Box::new(HllAccumulator {
+                merge_input: true,
+                ..Default::default()
+            }))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::binary_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+/// Returns the cardinality of a HyperLogLog sketch, such as `hll_count(hll(x))`.
+#[derive(Clone, Debug, Default)]
+pub struct HllCountFunction;
+
+impl fmt::Display for HllCountFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HLL_COUNT")
+    }
+}
+
+impl Function for HllCountFunction {
+    fn name(&self) -> &str {
+        "hll_count"
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::uint64_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::uniform(
+            1,
+            vec![ConcreteDataType::binary_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 1, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let column = &columns[0];
+        let counts = (0..column.len())
+            .map(|i| match column.get_ref(i) {
+                ValueRef::Binary(bytes) => {
+                    HyperLogLog::try_from_bytes(bytes).map(|hll| Some(hll.count()))
+                }
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(UInt64Vector::from(counts)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::{BinaryVector, ConstantVector, Int64Vector, StringVector};
+
+    use super::*;
+
+    fn hll_of(values: impl IntoIterator<Item = i64>) -> HyperLogLog {
+        let mut hll = HyperLogLog::default();
+        let mut buf = Vec::new();
+        for v in values {
+            hll.insert(ValueRef::Int64(v), &mut buf);
+        }
+        hll
+    }
+
+    #[test]
+    fn test_encode_value() {
+        let mut buf = Vec::new();
+        encode_value(ValueRef::Int32(-2), &mut buf);
+        assert_eq!(vec![3, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], buf);
+        let mut other = Vec::new();
+        encode_value(ValueRef::Int64(-2), &mut other);
+        assert_eq!(buf, other);
+
+        encode_value(ValueRef::String("ab"), &mut buf);
+        assert_eq!(vec![6, b'a', b'b'], buf);
+        encode_value(ValueRef::Binary(b"ab"), &mut other);
+        assert_ne!(buf, other);
+
+        encode_value(ValueRef::Float64((-0.0).into()), &mut buf);
+        encode_value(ValueRef::Float64(0.0.into()), &mut other);
+        assert_eq!(buf, other);
+    }
+
+    #[test]
+    fn test_hll_stable_hash() {
+        // The registers must not change across versions, otherwise stored sketches
+        // can't be merged with new sketches.
+        let hll = hll_of([1]);
+        let registers = hll
+            .registers
+            .iter()
+            .enumerate()
+            .filter(|(_, register)| **register > 0)
+            .map(|(i, register)| (i, *register))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(8136, 1)], registers);
+    }
+
+    #[test]
+    fn test_hll_count() {
+        assert_eq!(0, HyperLogLog::default().count());
+        assert_eq!(1, hll_of([7, 7, 7]).count());
+        assert_approx(100, hll_of(0..100).count());
+        assert_approx(100_000, hll_of(0..100_000).count());
+    }
+
+    #[test]
+    fn test_hll_merge_and_bytes() {
+        let mut hll = hll_of(0..50_000);
+        hll.merge(&hll_of(25_000..100_000));
+        assert_approx(100_000, hll.count());
+        assert_eq!(hll_of(0..100_000), hll);
+
+        let decoded = HyperLogLog::try_from_bytes(&hll.to_bytes()).unwrap();
+        assert_eq!(hll, decoded);
+        assert!(HyperLogLog::try_from_bytes(&[HLL_FORMAT_VERSION, HLL_PRECISION]).is_err());
+    }
+
+    #[test]
+    fn test_approx_distinct() {
+        let mut acc = HllAccumulator {
+            output_count: true,
+            ..Default::default()
+        };
+        acc.update_batch(&[]).unwrap();
+        assert_eq!(Value::from(0u64), acc.evaluate().unwrap());
+
+        let v: Vec<VectorRef> = vec![Arc::new(StringVector::from(vec![
+            Some("a"),
+            None,
+            Some("b"),
+            Some("a"),
+        ]))];
+        acc.update_batch(&v).unwrap();
+        assert_eq!(Value::from(2u64), acc.evaluate().unwrap());
+
+        let v: Vec<VectorRef> = vec![Arc::new(ConstantVector::new(
+            Arc::new(StringVector::from(vec!["c"])),
+            10,
+        ))];
+        acc.update_batch(&v).unwrap();
+        assert_eq!(Value::from(3u64), acc.evaluate().unwrap());
+    }
+
+    #[test]
+    fn test_hll_state_and_merge() {
+        let mut acc1 = HllAccumulator::default();
+        let v: Vec<VectorRef> = vec![Arc::new(Int64Vector::from_vec((0..1000).collect()))];
+        acc1.update_batch(&v).unwrap();
+        let mut acc2 = HllAccumulator::default();
+        let v: Vec<VectorRef> = vec![Arc::new(Int64Vector::from_vec((500..2000).collect()))];
+        acc2.update_batch(&v).unwrap();
+
+        // Merges sketches stored in a binary column.
+        let sketches = [acc1.evaluate().unwrap(), acc2.evaluate().unwrap()]
+            .into_iter()
+            .map(|v| match v {
+                Value::Binary(bytes) => Some(bytes.to_vec()),
+                _ => unreachable!(),
+            })
+            .chain([None])
+            .collect::<Vec<_>>();
+        let sketches: VectorRef = Arc::new(BinaryVector::from(sketches));
+        let mut merge = HllAccumulator {
+            merge_input: true,
+            ..Default::default()
+        };
+        merge.update_batch(&[sketches.clone()]).unwrap();
+        let Value::Binary(merged) = merge.evaluate().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(
+            hll_of(0..2000),
+            HyperLogLog::try_from_bytes(&merged).unwrap()
+        );
+
+        // Merges intermediate states.
+        let mut acc = HllAccumulator {
+            output_count: true,
+            ..Default::default()
+        };
+        acc.merge_batch(&[sketches.clone()]).unwrap();
+        let Value::UInt64(count) = acc.evaluate().unwrap() else {
+            unreachable!()
+        };
+        assert_approx(2000, count);
+
+        let counts = HllCountFunction
+            .eval(FunctionContext::default(), &[sketches])
+            .unwrap();
+        assert_eq!(3, counts.len());
+        assert_eq!(Value::from(hll_of(0..1000).count()), counts.get(0));
+        assert_eq!(Value::from(hll_of(500..2000).count()), counts.get(1));
+        assert!(counts.get(2).is_null());
+    }
+}
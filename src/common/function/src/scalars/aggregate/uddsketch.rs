@@ -0,0 +1,830 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! UDDSketch based approximate percentiles, see <https://arxiv.org/abs/2004.08604>.
+//!
+//! - `approx_percentile(x, p)` returns the approximate `p`-th percentile of `x`, where
+//!   `p` is in `[0, 1]`.
+//! - `uddsketch(x)` returns the UDDSketch of `x` in binary, which can be stored
+//!   in a rollup table.
+//! - `uddsketch_merge(sketch)` merges binary sketches into a new sketch.
+//! - `uddsketch_calc(p, sketch)` is a scalar function that returns the `p`-th
+//!   percentile of a sketch.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use common_macro::{as_aggr_func_creator, AggrFuncTypeStore};
+use common_query::error::{
+    BadAccumulatorImplSnafu, CreateAccumulatorSnafu, InvalidFuncArgsSnafu, InvalidInputColSnafu,
+    Result, TypeCastSnafu,
+};
+use common_query::logical_plan::{Accumulator, AggregateFunctionCreator};
+use common_query::prelude::*;
+use datatypes::arrow::array::AsArray;
+use datatypes::arrow::compute::kernels::cast;
+use datatypes::arrow::datatypes::{DataType, Float64Type};
+use datatypes::prelude::*;
+use datatypes::value::OrderedFloat;
+use datatypes::vectors::Float64Vector;
+use snafu::{ensure, ResultExt};
+
+use crate::function::{Function, FunctionContext};
+
+/// Maximum number of buckets of a sketch.
+const DEFAULT_MAX_BUCKETS: u32 = 200;
+/// Initial relative error of a sketch.
+const DEFAULT_INITIAL_ERROR: f64 = 0.001;
+/// Version of the binary format of the sketch.
+const UDDSKETCH_FORMAT_VERSION: u8 = 1;
+/// Minimum number of buckets of a sketch. Collapsing buckets stops shrinking a
+/// sketch once each sign only has buckets with keys 0 and 1, so a smaller limit
+/// would collapse forever.
+const MIN_MAX_BUCKETS: u32 = 4;
+/// Maximum number of collapses of a valid sketch. The squared bucket base
+/// overflows long before it with any valid initial error.
+const MAX_COLLAPSES: u32 = 64;
+
+/// A UDDSketch whose buckets grow exponentially. The sketch collapses adjacent
+/// buckets once the number of buckets exceeds the limit, which doubles the bucket
+/// width in log space and bounds the memory usage at the cost of accuracy.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UddSketch {
+    initial_error: f64,
+    max_buckets: u32,
+    /// Number of times the buckets have been collapsed.
+    collapses: u32,
+    /// Counts of positive values by bucket keys.
+    positive: BTreeMap<i32, u64>,
+    /// Counts of negative values by bucket keys of their absolute values.
+    negative: BTreeMap<i32, u64>,
+    zero_count: u64,
+    count: u64,
+}
+
+impl Default for UddSketch {
+    fn default() -> UddSketch {
+        UddSketch {
+            initial_error: DEFAULT_INITIAL_ERROR,
+            max_buckets: DEFAULT_MAX_BUCKETS,
+            collapses: 0,
+            positive: BTreeMap::new(),
+            negative: BTreeMap::new(),
+            zero_count: 0,
+            count: 0,
+        }
+    }
+}
+
+impl UddSketch {
+    /// Returns the base of the buckets, values in the bucket `k` are in
+    /// `(gamma^(k-1), gamma^k]`.
+    fn gamma(&self) -> f64 {
+        let mut gamma = (1.0 + self.initial_error) / (1.0 - self.initial_error);
+        for _ in 0..self.collapses {
+            gamma *= gamma;
+        }
+        gamma
+    }
+
+    /// Adds a value to the sketch, NaN and infinite values are ignored.
+    pub(crate) fn insert(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+
+        if value == 0.0 {
+            self.zero_count += 1;
+        } else {
+            let key = (value.abs().ln() / self.gamma().ln()).ceil() as i32;
+            let buckets = if value > 0.0 {
+                &mut self.positive
+            } else {
+                &mut self.negative
+            };
+            *buckets.entry(key).or_default() += 1;
+        }
+        self.count += 1;
+        self.compact();
+    }
+
+    /// Merges another sketch into this sketch.
+    pub(crate) fn merge(&mut self, other: &UddSketch) -> Result<()> {
+        ensure!(
+            self.initial_error == other.initial_error,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "cannot merge UDDSketch with initial error {} into {}",
+                    other.initial_error, self.initial_error
+                ),
+            }
+        );
+        ensure!(
+            self.max_buckets == other.max_buckets,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "cannot merge UDDSketch with max buckets {} into {}",
+                    other.max_buckets, self.max_buckets
+                ),
+            }
+        );
+
+        while self.collapses < other.collapses {
+            self.collapse();
+        }
+        let mut other = other.clone();
+        while other.collapses < self.collapses {
+            other.collapse();
+        }
+        for (key, count) in other.positive {
+            *self.positive.entry(key).or_default() += count;
+        }
+        for (key, count) in other.negative {
+            *self.negative.entry(key).or_default() += count;
+        }
+        self.zero_count += other.zero_count;
+        self.count += other.count;
+        self.compact();
+        Ok(())
+    }
+
+    /// Returns the estimated `q`-quantile, `q` must be in `[0, 1]`.
+    ///
+    /// Returns `None` if the sketch is empty.
+    pub(crate) fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let gamma = self.gamma();
+        let estimate = |key: i32| 2.0 * gamma.powi(key) / (gamma + 1.0);
+        let rank = (q * (self.count - 1) as f64).floor() as u64;
+        let mut seen = 0;
+        // Visits buckets from the smallest value to the largest value.
+        for (key, count) in self.negative.iter().rev() {
+            seen += count;
+            if seen > rank {
+                return Some(-estimate(*key));
+            }
+        }
+        seen += self.zero_count;
+        if seen > rank {
+            return Some(0.0);
+        }
+        for (key, count) in &self.positive {
+            seen += count;
+            if seen > rank {
+                return Some(estimate(*key));
+            }
+        }
+        // Safety: counts of buckets sum up to `self.count`.
+        unreachable!()
+    }
+
+    /// Collapses buckets until the number of buckets doesn't exceed the limit.
+    fn compact(&mut self) {
+        while self.positive.len() + self.negative.len() > self.max_buckets as usize {
+            self.collapse();
+        }
+    }
+
+    /// Merges every two adjacent buckets into one bucket.
+    fn collapse(&mut self) {
+        let collapse_buckets = |buckets: &mut BTreeMap<i32, u64>| {
+            let mut collapsed = BTreeMap::new();
+            for (key, count) in std::mem::take(buckets) {
+                // The new key is `ceil(key / 2)`.
+                *collapsed.entry((key + 1).div_euclid(2)).or_default() += count;
+            }
+            *buckets = collapsed;
+        };
+        collapse_buckets(&mut self.positive);
+        collapse_buckets(&mut self.negative);
+        self.collapses += 1;
+    }
+
+    /// Encodes the sketch into bytes.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let num_buckets = self.positive.len() + self.negative.len();
+        let mut bytes = Vec::with_capacity(41 + num_buckets * 12);
+        bytes.push(UDDSKETCH_FORMAT_VERSION);
+        bytes.extend_from_slice(&self.initial_error.to_le_bytes());
+        bytes.extend_from_slice(&self.max_buckets.to_le_bytes());
+        bytes.extend_from_slice(&self.collapses.to_le_bytes());
+        bytes.extend_from_slice(&self.zero_count.to_le_bytes());
+        bytes.extend_from_slice(&self.count.to_le_bytes());
+        for buckets in [&self.positive, &self.negative] {
+            bytes.extend_from_slice(&(buckets.len() as u32).to_le_bytes());
+            for (key, count) in buckets {
+                bytes.extend_from_slice(&key.to_le_bytes());
+                bytes.extend_from_slice(&count.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decodes the sketch from bytes encoded by [UddSketch::to_bytes].
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Result<UddSketch> {
+        let invalid = || {
+            InvalidFuncArgsSnafu {
+                err_msg: format!("invalid UDDSketch of {} bytes", bytes.len()),
+            }
+            .build()
+        };
+        let mut reader = ByteReader { bytes };
+        macro_rules! read {
+            ($ty: ty) => {
+                <$ty>::from_le_bytes(reader.read().ok_or_else(invalid)?)
+            };
+        }
+
+        if read!(u8) != UDDSKETCH_FORMAT_VERSION {
+            return Err(invalid());
+        }
+        let mut sketch = UddSketch {
+            initial_error: read!(f64),
+            max_buckets: read!(u32),
+            collapses: read!(u32),
+            zero_count: read!(u64),
+            count: read!(u64),
+            ..Default::default()
+        };
+        for buckets in [&mut sketch.positive, &mut sketch.negative] {
+            let num_buckets = read!(u32);
+            for _ in 0..num_buckets {
+                let key = read!(i32);
+                let _ = buckets.insert(key, read!(u64));
+            }
+        }
+
+        let total = sketch
+            .positive
+            .values()
+            .chain(sketch.negative.values())
+            .sum::<u64>()
+            + sketch.zero_count;
+        if !reader.bytes.is_empty()
+            || total != sketch.count
+            || !(sketch.initial_error > 0.0 && sketch.initial_error < 1.0)
+            || sketch.max_buckets < MIN_MAX_BUCKETS
+            || sketch.positive.len() + sketch.negative.len() > sketch.max_buckets as usize
+            || sketch.collapses > MAX_COLLAPSES
+            || !sketch.gamma().is_finite()
+        {
+            return Err(invalid());
+        }
+        Ok(sketch)
+    }
+
+    /// Merges sketches in the binary `column` into this sketch, ignoring nulls.
+    fn merge_column(&mut self, column: &VectorRef) -> Result<()> {
+        for i in 0..column.len() {
+            match column.get_ref(i) {
+                ValueRef::Null => {}
+                ValueRef::Binary(bytes) => self.merge(&UddSketch::try_from_bytes(bytes)?)?,
+                other => {
+                    return BadAccumulatorImplSnafu {
+                        err_msg: format!("expect binary sketch, got {:?}", other.data_type()),
+                    }
+                    .fail()
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads fixed size arrays from bytes.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl ByteReader<'_> {
+    fn read<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.bytes.len() < N {
+            return None;
+        }
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+        head.try_into().ok()
+    }
+}
+
+/// Returns the percentile `p` in the `column` if it is a constant in `[0, 1]`.
+fn percentile_of(column: &VectorRef) -> Result<Option<f64>> {
+    let mut percentile = None;
+    for i in 0..column.len() {
+        let p = match column.get(i) {
+            Value::Float64(OrderedFloat(p)) => p,
+            _ => return InvalidInputColSnafu.fail(),
+        };
+        ensure!(
+            percentile.is_none() || percentile == Some(p),
+            InvalidInputColSnafu
+        );
+        ensure!(
+            (0.0..=1.0).contains(&p),
+            InvalidFuncArgsSnafu {
+                err_msg: format!("percentile {p} is not in [0, 1]"),
+            }
+        );
+        percentile = Some(p);
+    }
+    Ok(percentile)
+}
+
+/// Accumulator of the UDDSketch based aggregate functions.
+#[derive(Debug, Default)]
+pub struct UddSketchAccumulator {
+    sketch: UddSketch,
+    /// Whether the input values are sketches to merge.
+    merge_input: bool,
+    /// Whether to evaluate the percentile instead of the sketch.
+    output_percentile: bool,
+    /// The percentile to evaluate, known after receiving the first batch.
+    percentile: Option<f64>,
+}
+
+impl UddSketchAccumulator {
+    fn set_percentile(&mut self, percentile: Option<f64>) -> Result<()> {
+        let Some(p) = percentile else {
+            return Ok(());
+        };
+        if let Some(current) = self.percentile {
+            ensure!(current == p, InvalidInputColSnafu);
+        } else {
+            self.percentile = Some(p);
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for UddSketchAccumulator {
+    fn state(&self) -> Result<Vec<Value>> {
+        let mut state = vec![Value::from(self.sketch.to_bytes())];
+        if self.output_percentile {
+            state.push(self.percentile.into());
+        }
+        Ok(state)
+    }
+
+    fn update_batch(&mut self, values: &[VectorRef]) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let expect_len = if self.output_percentile { 2 } else { 1 };
+        ensure!(values.len() == expect_len, InvalidInputStateSnafu);
+        if self.output_percentile {
+            self.set_percentile(percentile_of(&values[1])?)?;
+        }
+
+        let column = &values[0];
+        if self.merge_input {
+            return self.sketch.merge_column(column);
+        }
+
+        let array =
+            cast::cast(&column.to_arrow_array(), &DataType::Float64).context(TypeCastSnafu {
+                typ: DataType::Float64,
+            })?;
+        for value in array.as_primitive::<Float64Type>().iter().flatten() {
+            self.sketch.insert(value);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[VectorRef]) -> Result<()> {
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        let expect_len = if self.output_percentile { 2 } else { 1 };
+        ensure!(
+            states.len() == expect_len,
+            BadAccumulatorImplSnafu {
+                err_msg: format!("expect {expect_len} states in `merge_batch`"),
+            }
+        );
+        if self.output_percentile {
+            // The percentile is null in states of accumulators without input.
+            let percentile = (0..states[1].len())
+                .map(|i| states[1].get(i))
+                .find_map(|v| match v {
+                    Value::Float64(OrderedFloat(p)) => Some(p),
+                    _ => None,
+                });
+            self.set_percentile(percentile)?;
+        }
+        self.sketch.merge_column(&states[0])
+    }
+
+    fn evaluate(&self) -> Result<Value> {
+        if !self.output_percentile {
+            return Ok(Value::from(self.sketch.to_bytes()));
+        }
+
+        let value = self
+            .percentile
+            .and_then(|p| self.sketch.quantile(p))
+            .map(Value::from)
+            .unwrap_or(Value::Null);
+        Ok(value)
+    }
+}
+
+fn ensure_numeric_input(function: &str, input_type: &ConcreteDataType) -> Result<()> {
+    if input_type.is_numeric() {
+        return Ok(());
+    }
+    let err_msg = format!(
+        "\"{function}\" aggregate function not support data type {:?}",
+        input_type.logical_type_id(),
+    );
+    CreateAccumulatorSnafu { err_msg }.fail()
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct ApproxPercentileAccumulatorCreator {}
+
+impl AggregateFunctionCreator for ApproxPercentileAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            ensure_numeric_input("APPROX_PERCENTILE", &types[0])?;
+            Ok(Box::new(UddSketchAccumulator {
+                output_percentile: true,
+                ..Default::default()
+            }))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 2, InvalidInputStateSnafu);
+        Ok(vec![
+            ConcreteDataType::binary_datatype(),
+            ConcreteDataType::float64_datatype(),
+        ])
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct UddSketchAccumulatorCreator {}
+
+impl AggregateFunctionCreator for UddSketchAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            ensure_numeric_input("UDDSKETCH", &types[0])?;
+            Ok(Box::<UddSketchAccumulator>::default())
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::binary_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+#[as_aggr_func_creator]
+#[derive(Debug, Default, AggrFuncTypeStore)]
+pub struct UddSketchMergeAccumulatorCreator {}
+
+impl AggregateFunctionCreator for UddSketchMergeAccumulatorCreator {
+    fn creator(&self) -> AccumulatorCreatorFunction {
+        let creator: AccumulatorCreatorFunction = Arc::new(move |types: &[ConcreteDataType]| {
+            let input_type = &types[0];
+            if !matches!(input_type, ConcreteDataType::Binary(_)) {
+                let err_msg = format!(
+                    "\"UDDSKETCH_MERGE\" aggregate function not support data type {:?}",
+                    input_type.logical_type_id(),
+                );
+                return CreateAccumulatorSnafu { err_msg }.fail();
+            }
+            Ok(Box::new(UddSketchAccumulator {
+                merge_input: true,
+                ..Default::default()
+            }))
+        });
+        creator
+    }
+
+    fn output_type(&self) -> Result<ConcreteDataType> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(ConcreteDataType::binary_datatype())
+    }
+
+    fn state_types(&self) -> Result<Vec<ConcreteDataType>> {
+        let input_types = self.input_types()?;
+        ensure!(input_types.len() == 1, InvalidInputStateSnafu);
+        Ok(vec![ConcreteDataType::binary_datatype()])
+    }
+}
+
+/// Returns the percentile of a UDDSketch, such as `uddsketch_calc(0.99, uddsketch(x))`.
+#[derive(Clone, Debug, Default)]
+pub struct UddSketchCalcFunction;
+
+impl fmt::Display for UddSketchCalcFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UDDSKETCH_CALC")
+    }
+}
+
+impl Function for UddSketchCalcFunction {
+    fn name(&self) -> &str {
+        "uddsketch_calc"
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::float64_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![
+                ConcreteDataType::float64_datatype(),
+                ConcreteDataType::binary_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 2, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+
+        let (percentiles, sketches) = (&columns[0], &columns[1]);
+        let values = (0..sketches.len())
+            .map(|i| {
+                let p = match percentiles.get(i) {
+                    Value::Float64(OrderedFloat(p)) => p,
+                    Value::Null => return Ok(None),
+                    other => {
+                        return InvalidFuncArgsSnafu {
+                            err_msg: format!(
+                                "percentile must be a float64, got {:?}",
+                                other.data_type()
+                            ),
+                        }
+                        .fail()
+                    }
+                };
+                let bytes = match sketches.get_ref(i) {
+                    ValueRef::Binary(bytes) => bytes,
+                    ValueRef::Null => return Ok(None),
+                    other => {
+                        return InvalidFuncArgsSnafu {
+                            err_msg: format!("expect binary sketch, got {:?}", other.data_type()),
+                        }
+                        .fail()
+                    }
+                };
+                ensure!(
+                    (0.0..=1.0).contains(&p),
+                    InvalidFuncArgsSnafu {
+                        err_msg: format!("percentile {p} is not in [0, 1]"),
+                    }
+                );
+                Ok(UddSketch::try_from_bytes(bytes)?.quantile(p))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(Float64Vector::from(values)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::vectors::{BinaryVector, ConstantVector, Int32Vector};
+
+    use super::*;
+
+    fn sketch_of(values: impl IntoIterator<Item = f64>) -> UddSketch {
+        let mut sketch = UddSketch::default();
+        for v in values {
+            sketch.insert(v);
+        }
+        sketch
+    }
+
+    fn assert_relative_error(sketch: &UddSketch, expect: f64, actual: f64) {
+        let gamma = sketch.gamma();
+        let max_error = (gamma - 1.0) / (gamma + 1.0);
+        let error = (actual - expect).abs() / expect.abs();
+        assert!(
+            error <= max_error + 1e-9,
+            "expect: {expect}, actual: {actual}, max error: {max_error}"
+        );
+    }
+
+    #[test]
+    fn test_uddsketch_quantile() {
+        assert_eq!(None, UddSketch::default().quantile(0.5));
+
+        let sketch = sketch_of([-10.0, 0.0, 0.0, 5.0, f64::NAN, f64::INFINITY]);
+        assert_eq!(4, sketch.count);
+        assert_relative_error(&sketch, -10.0, sketch.quantile(0.0).unwrap());
+        assert_eq!(Some(0.0), sketch.quantile(0.5));
+        assert_relative_error(&sketch, 5.0, sketch.quantile(1.0).unwrap());
+
+        // Values from 1 to 10000 need more buckets than the limit.
+        let sketch = sketch_of((1..=10000).map(|v| v as f64));
+        assert!(sketch.collapses > 0);
+        assert!(sketch.positive.len() <= DEFAULT_MAX_BUCKETS as usize);
+        for (q, expect) in [(0.0, 1.0), (0.5, 5000.0), (0.99, 9900.0), (1.0, 10000.0)] {
+            assert_relative_error(&sketch, expect, sketch.quantile(q).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_uddsketch_merge_and_bytes() {
+        let mut sketch = sketch_of((1..=100).map(|v| v as f64));
+        let other = sketch_of((101..=10000).map(|v| -v as f64));
+        sketch.merge(&other).unwrap();
+        assert_eq!(10000, sketch.count);
+        assert_eq!(other.collapses, sketch.collapses);
+        assert_relative_error(&sketch, -10000.0, sketch.quantile(0.0).unwrap());
+        assert_relative_error(&sketch, 100.0, sketch.quantile(1.0).unwrap());
+
+        let decoded = UddSketch::try_from_bytes(&sketch.to_bytes()).unwrap();
+        assert_eq!(sketch, decoded);
+        let bytes = sketch.to_bytes();
+        assert!(UddSketch::try_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(UddSketch::try_from_bytes(&[]).is_err());
+
+        let other = UddSketch {
+            initial_error: 0.01,
+            ..Default::default()
+        };
+        assert!(sketch.merge(&other).is_err());
+        let other = UddSketch {
+            max_buckets: DEFAULT_MAX_BUCKETS * 2,
+            ..Default::default()
+        };
+        assert!(sketch.merge(&other).is_err());
+    }
+
+    #[test]
+    fn test_uddsketch_invalid_bytes() {
+        let bytes = sketch_of([1.0, 2.0]).to_bytes();
+        // Offsets of `max_buckets` and `collapses`.
+        let with_field = |offset: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            bytes
+        };
+
+        assert!(UddSketch::try_from_bytes(&with_field(9, 0)).is_err());
+        assert!(UddSketch::try_from_bytes(&with_field(9, 1)).is_err());
+        assert!(UddSketch::try_from_bytes(&with_field(13, u32::MAX)).is_err());
+        // The bucket base overflows.
+        assert!(UddSketch::try_from_bytes(&with_field(13, 32)).is_err());
+        assert!(UddSketch::try_from_bytes(&with_field(13, 3)).is_ok());
+    }
+
+    #[test]
+    fn test_approx_percentile() {
+        let mut acc = UddSketchAccumulator {
+            output_percentile: true,
+            ..Default::default()
+        };
+        acc.update_batch(&[]).unwrap();
+        assert_eq!(Value::Null, acc.evaluate().unwrap());
+
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from(vec![Some(1), None, Some(2), Some(3)])),
+            Arc::new(ConstantVector::new(
+                Arc::new(Float64Vector::from_vec(vec![0.5])),
+                4,
+            )),
+        ];
+        acc.update_batch(&v).unwrap();
+        let Value::Float64(OrderedFloat(p50)) = acc.evaluate().unwrap() else {
+            unreachable!()
+        };
+        assert_relative_error(&acc.sketch, 2.0, p50);
+
+        // The percentile must be a constant in [0, 1].
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from_vec(vec![1])),
+            Arc::new(Float64Vector::from_vec(vec![0.9])),
+        ];
+        assert!(acc.update_batch(&v).is_err());
+        let mut acc = UddSketchAccumulator {
+            output_percentile: true,
+            ..Default::default()
+        };
+        let v: Vec<VectorRef> = vec![
+            Arc::new(Int32Vector::from_vec(vec![1])),
+            Arc::new(Float64Vector::from_vec(vec![50.0])),
+        ];
+        assert!(acc.update_batch(&v).is_err());
+    }
+
+    #[test]
+    fn test_uddsketch_state_and_merge() {
+        let mut acc1 = UddSketchAccumulator::default();
+        let v: Vec<VectorRef> = vec![Arc::new(Float64Vector::from_vec(
+            (1..=500).map(|v| v as f64).collect(),
+        ))];
+        acc1.update_batch(&v).unwrap();
+        let mut acc2 = UddSketchAccumulator::default();
+        let v: Vec<VectorRef> = vec![Arc::new(Float64Vector::from_vec(
+            (501..=1000).map(|v| v as f64).collect(),
+        ))];
+        acc2.update_batch(&v).unwrap();
+
+        // Merges sketches stored in a binary column.
+        let sketches = [acc1.evaluate().unwrap(), acc2.evaluate().unwrap()]
+            .into_iter()
+            .map(|v| match v {
+                Value::Binary(bytes) => Some(bytes.to_vec()),
+                _ => unreachable!(),
+            })
+            .chain([None])
+            .collect::<Vec<_>>();
+        let sketches: VectorRef = Arc::new(BinaryVector::from(sketches));
+        let mut merge = UddSketchAccumulator {
+            merge_input: true,
+            ..Default::default()
+        };
+        merge.update_batch(&[sketches.clone()]).unwrap();
+        let Value::Binary(merged) = merge.evaluate().unwrap() else {
+            unreachable!()
+        };
+        let merged = UddSketch::try_from_bytes(&merged).unwrap();
+        assert_eq!(1000, merged.count);
+        assert_relative_error(&merged, 500.0, merged.quantile(0.5).unwrap());
+
+        // Merges intermediate states.
+        let mut acc = UddSketchAccumulator {
+            output_percentile: true,
+            ..Default::default()
+        };
+        let percentiles: VectorRef = Arc::new(Float64Vector::from(vec![None, Some(0.99), None]));
+        acc.merge_batch(&[sketches.clone(), percentiles]).unwrap();
+        let Value::Float64(OrderedFloat(p99)) = acc.evaluate().unwrap() else {
+            unreachable!()
+        };
+        assert_relative_error(&merged, 990.0, p99);
+
+        let percentiles: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(Float64Vector::from_vec(vec![1.0])),
+            3,
+        ));
+        let result = UddSketchCalcFunction
+            .eval(FunctionContext::default(), &[percentiles, sketches])
+            .unwrap();
+        assert_eq!(3, result.len());
+        let Value::Float64(OrderedFloat(max)) = result.get(1) else {
+            unreachable!()
+        };
+        assert_relative_error(&merged, 1000.0, max);
+        assert!(result.get(2).is_null());
+
+        let percentiles: VectorRef = Arc::new(ConstantVector::new(
+            Arc::new(Int32Vector::from_vec(vec![1])),
+            3,
+        ));
+        let sketches: VectorRef = Arc::new(BinaryVector::from(vec![Some(merged.to_bytes())]));
+        assert!(UddSketchCalcFunction
+            .eval(FunctionContext::default(), &[percentiles, sketches])
+            .is_err());
+    }
+}
@@ -15,6 +15,7 @@
 mod binary;
 mod ctx;
 mod is_null;
+mod matches;
 mod unary;
 
 use std::sync::Arc;
@@ -25,11 +26,13 @@ pub use unary::scalar_unary_op;
 
 use crate::function_registry::FunctionRegistry;
 use crate::scalars::expression::is_null::IsNullFunction;
+use crate::scalars::expression::matches::MatchesFunction;
 
 pub(crate) struct ExpressionFunction;
 
 impl ExpressionFunction {
     pub fn register(registry: &FunctionRegistry) {
         registry.register(Arc::new(IsNullFunction));
+        registry.register(Arc::new(MatchesFunction));
     }
 }
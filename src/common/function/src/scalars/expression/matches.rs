@@ -0,0 +1,147 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, InvalidInputTypeSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::VectorRef;
+use datatypes::vectors::BooleanVector;
+use index::full_text_index::tokenizer::{matches, MATCHES_FUNCTION_NAME};
+use snafu::{ensure, ResultExt};
+
+use crate::function::{Function, FunctionContext};
+
+/// Checks if the text contains all tokens of the query.
+///
+/// Texts are split into lowercase tokens of alphanumeric characters, the
+/// same way the full-text index does, so the index can prune the rows.
+#[derive(Clone, Debug, Default)]
+pub struct MatchesFunction;
+
+impl Function for MatchesFunction {
+    fn name(&self) -> &str {
+        MATCHES_FUNCTION_NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::boolean_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::string_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let texts = &columns[0];
+        let queries = &columns[1];
+
+        let mut results = Vec::with_capacity(texts.len());
+        for i in 0..texts.len() {
+            let text = texts.get_ref(i);
+            let text = text.as_string().context(InvalidInputTypeSnafu {
+                err_msg: "The first argument of matches must be a string",
+            })?;
+            let query = queries.get_ref(i);
+            let query = query.as_string().context(InvalidInputTypeSnafu {
+                err_msg: "The second argument of matches must be a string",
+            })?;
+
+            let result = match (text, query) {
+                (Some(text), Some(query)) => Some(matches(text, query)),
+                _ => None,
+            };
+            results.push(result);
+        }
+
+        Ok(Arc::new(BooleanVector::from(results)))
+    }
+}
+
+impl Display for MatchesFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MATCHES")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::prelude::TypeSignature;
+    use datatypes::value::Value;
+    use datatypes::vectors::StringVector;
+
+    use super::*;
+
+    #[test]
+    fn test_matches_function() {
+        let f = MatchesFunction;
+
+        assert_eq!("matches", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[
+                ConcreteDataType::string_datatype(),
+                ConcreteDataType::string_datatype()
+            ])
+            .unwrap()
+        );
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Exact(valid_types),
+                             volatility: Volatility::Immutable
+                         } if valid_types == vec![ConcreteDataType::string_datatype(), ConcreteDataType::string_datatype()]
+        ));
+
+        let texts = vec![
+            Some("GET /api/v1: Connection timeout"),
+            Some("Connection reset"),
+            Some("connection TIMEOUT"),
+            None,
+        ];
+        let queries = vec![
+            Some("timeout connection"),
+            Some("timeout"),
+            Some("Timeout"),
+            Some("timeout"),
+        ];
+        let args: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(texts)),
+            Arc::new(StringVector::from(queries)),
+        ];
+        let vector = f.eval(FunctionContext::default(), &args).unwrap();
+
+        assert_eq!(4, vector.len());
+        assert_eq!(Value::Boolean(true), vector.get(0));
+        assert_eq!(Value::Boolean(false), vector.get(1));
+        assert_eq!(Value::Boolean(true), vector.get(2));
+        assert!(vector.get(3).is_null());
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+
+use common_query::error::{InvalidFuncArgsSnafu, InvalidInputTypeSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::VectorRef;
+use datatypes::vectors::{BooleanVector, Float64Vector, Int64Vector, StringVector};
+use snafu::{ensure, ResultExt};
+
+use crate::function::{Function, FunctionContext};
+
+/// Looks up `path` in the JSONB encoded `json` and returns the encoded sub value,
+/// or `None` if the path is invalid or nothing matches.
+fn get_json_by_path(json: &[u8], path: &str) -> Option<Vec<u8>> {
+    let json_path = jsonb::jsonpath::parse_json_path(path.as_bytes()).ok()?;
+    let mut sub_jsonb = Vec::new();
+    let mut sub_offsets = Vec::new();
+    jsonb::get_by_path(json, json_path, &mut sub_jsonb, &mut sub_offsets);
+    if sub_offsets.is_empty() {
+        None
+    } else {
+        Some(sub_jsonb)
+    }
+}
+
+/// Evaluates `get` on every `(json, path)` row of the two input columns.
+fn eval_json_get<T>(
+    name: &str,
+    columns: &[VectorRef],
+    get: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    ensure!(
+        columns.len() == 2,
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect exactly two, have: {}",
+                columns.len()
+            ),
+        }
+    );
+    let jsons = &columns[0];
+    let paths = &columns[1];
+
+    let mut results = Vec::with_capacity(jsons.len());
+    for i in 0..jsons.len() {
+        let json = jsons.get_ref(i);
+        let json = json.as_binary().context(InvalidInputTypeSnafu {
+            err_msg: format!("The first argument of {name} must be a JSON value"),
+        })?;
+        let path = paths.get_ref(i);
+        let path = path.as_string().context(InvalidInputTypeSnafu {
+            err_msg: format!("The second argument of {name} must be a string"),
+        })?;
+
+        let result = match (json, path) {
+            (Some(json), Some(path)) => get_json_by_path(json, path).and_then(|v| get(&v)),
+            _ => None,
+        };
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+macro_rules! json_get {
+    // e.g. name = JsonGetInt, type = Int64, vector = Int64Vector, convert = |v| jsonb::to_i64(v).ok()
+    ($name: ident, $type: ident, $vector: ident, $convert: expr, $doc:expr) => {
+        paste::paste! {
+            #[doc = $doc]
+            #[derive(Clone, Debug, Default)]
+            pub struct $name;
+
+            impl Function for $name {
+                fn name(&self) -> &str {
+                    stringify!([<$name:snake>])
+                }
+
+                fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+                    Ok(ConcreteDataType::[<$type:snake _datatype>]())
+                }
+
+                fn signature(&self) -> Signature {
+                    Signature::exact(
+                        vec![
+                            ConcreteDataType::json_datatype(),
+                            ConcreteDataType::string_datatype(),
+                        ],
+                        Volatility::Immutable,
+                    )
+                }
+
+                fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+                    let results = eval_json_get(self.name(), columns, $convert)?;
+                    Ok(std::sync::Arc::new($vector::from(results)))
+                }
+            }
+
+            impl Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{}", stringify!([<$name:snake>]).to_ascii_uppercase())
+                }
+            }
+        }
+    };
+}
+
+json_get!(
+    JsonGetInt,
+    Int64,
+    Int64Vector,
+    |v: &[u8]| jsonb::to_i64(v).ok(),
+    "Get the value from the JSONB by the given path and return it as an integer."
+);
+
+json_get!(
+    JsonGetFloat,
+    Float64,
+    Float64Vector,
+    |v: &[u8]| jsonb::to_f64(v).ok(),
+    "Get the value from the JSONB by the given path and return it as a float."
+);
+
+json_get!(
+    JsonGetBool,
+    Boolean,
+    BooleanVector,
+    |v: &[u8]| jsonb::to_bool(v).ok(),
+    "Get the value from the JSONB by the given path and return it as a boolean."
+);
+
+json_get!(
+    JsonGetString,
+    String,
+    StringVector,
+    |v: &[u8]| jsonb::to_str(v).ok(),
+    "Get the value from the JSONB by the given path and return it as a string."
+);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_query::prelude::TypeSignature;
+    use datatypes::types::parse_string_to_jsonb;
+    use datatypes::value::Value;
+    use datatypes::vectors::BinaryVector;
+
+    use super::*;
+
+    fn json_vector(jsons: &[&str]) -> VectorRef {
+        let jsonbs = jsons
+            .iter()
+            .map(|j| Some(parse_string_to_jsonb(j).unwrap()))
+            .collect::<Vec<_>>();
+        Arc::new(BinaryVector::from(jsonbs))
+    }
+
+    #[test]
+    fn test_json_get_int() {
+        let json_get_int = JsonGetInt;
+
+        assert_eq!("json_get_int", json_get_int.name());
+        assert_eq!(
+            ConcreteDataType::int64_datatype(),
+            json_get_int
+                .return_type(&[
+                    ConcreteDataType::json_datatype(),
+                    ConcreteDataType::string_datatype()
+                ])
+                .unwrap()
+        );
+        assert!(matches!(json_get_int.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Exact(valid_types),
+                             volatility: Volatility::Immutable
+                         } if valid_types == vec![ConcreteDataType::json_datatype(), ConcreteDataType::string_datatype()]
+        ));
+
+        let jsons = json_vector(&[
+            r#"{"a": {"b": 2}, "b": 2, "c": 3}"#,
+            r#"{"a": 4, "b": {"c": 6}, "c": 6}"#,
+            r#"{"a": 7, "b": 8, "c": {"a": 7}}"#,
+        ]);
+        let paths = Arc::new(StringVector::from(vec!["$.a.b", "$.a", "$.c"]));
+
+        let vector = json_get_int
+            .eval(FunctionContext::default(), &[jsons, paths])
+            .unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::Int64(2), vector.get(0));
+        assert_eq!(Value::Int64(4), vector.get(1));
+        assert!(vector.get(2).is_null());
+    }
+
+    #[test]
+    fn test_json_get_float() {
+        let json_get_float = JsonGetFloat;
+        assert_eq!("json_get_float", json_get_float.name());
+
+        let jsons = json_vector(&[r#"{"a": {"b": 2.1}}"#, r#"{"a": 4.4}"#, r#"{"a": "x"}"#]);
+        let paths = Arc::new(StringVector::from(vec!["$.a.b", "$.a", "$.a"]));
+
+        let vector = json_get_float
+            .eval(FunctionContext::default(), &[jsons, paths])
+            .unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::from(2.1f64), vector.get(0));
+        assert_eq!(Value::from(4.4f64), vector.get(1));
+        assert!(vector.get(2).is_null());
+    }
+
+    #[test]
+    fn test_json_get_bool() {
+        let json_get_bool = JsonGetBool;
+        assert_eq!("json_get_bool", json_get_bool.name());
+
+        let jsons = json_vector(&[r#"{"a": {"b": true}}"#, r#"{"a": false}"#, r#"{"a": 1}"#]);
+        let paths = Arc::new(StringVector::from(vec!["$.a.b", "$.a", "$.b"]));
+
+        let vector = json_get_bool
+            .eval(FunctionContext::default(), &[jsons, paths])
+            .unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::Boolean(true), vector.get(0));
+        assert_eq!(Value::Boolean(false), vector.get(1));
+        assert!(vector.get(2).is_null());
+    }
+
+    #[test]
+    fn test_json_get_string() {
+        let json_get_string = JsonGetString;
+        assert_eq!("json_get_string", json_get_string.name());
+
+        let jsons = json_vector(&[r#"{"a": {"b": "a"}}"#, r#"{"a": "d"}"#, r#"{"a": "x"}"#]);
+        let paths = Arc::new(StringVector::from(vec!["$.a.b", "$.a", "$.b"]));
+
+        let vector = json_get_string
+            .eval(FunctionContext::default(), &[jsons, paths])
+            .unwrap();
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::from("a"), vector.get(0));
+        assert_eq!(Value::from("d"), vector.get(1));
+        assert!(vector.get(2).is_null());
+    }
+}
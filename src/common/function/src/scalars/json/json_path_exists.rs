@@ -0,0 +1,163 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, InvalidInputTypeSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::VectorRef;
+use datatypes::vectors::BooleanVector;
+use snafu::{ensure, ResultExt};
+
+use crate::function::{Function, FunctionContext};
+
+/// Check if the given JSON data contains the given JSON path.
+#[derive(Clone, Debug, Default)]
+pub struct JsonPathExistsFunction;
+
+const NAME: &str = "json_path_exists";
+
+impl Function for JsonPathExistsFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::boolean_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![
+                ConcreteDataType::json_datatype(),
+                ConcreteDataType::string_datatype(),
+            ],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 2,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly two, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let jsons = &columns[0];
+        let paths = &columns[1];
+
+        let mut results = Vec::with_capacity(jsons.len());
+        for i in 0..jsons.len() {
+            let json = jsons.get_ref(i);
+            let json = json.as_binary().context(InvalidInputTypeSnafu {
+                err_msg: "The first argument of json_path_exists must be a JSON value",
+            })?;
+            let path = paths.get_ref(i);
+            let path = path.as_string().context(InvalidInputTypeSnafu {
+                err_msg: "The second argument of json_path_exists must be a string",
+            })?;
+
+            let result = match (json, path) {
+                (Some(json), Some(path)) => {
+                    let json_path =
+                        jsonb::jsonpath::parse_json_path(path.as_bytes()).map_err(|e| {
+                            InvalidFuncArgsSnafu {
+                                err_msg: format!("Invalid JSON path {path}: {e}"),
+                            }
+                            .build()
+                        })?;
+                    Some(jsonb::path_exists(json, json_path))
+                }
+                _ => None,
+            };
+            results.push(result);
+        }
+
+        Ok(Arc::new(BooleanVector::from(results)))
+    }
+}
+
+impl Display for JsonPathExistsFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JSON_PATH_EXISTS")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::prelude::TypeSignature;
+    use datatypes::types::parse_string_to_jsonb;
+    use datatypes::value::Value;
+    use datatypes::vectors::{BinaryVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_json_path_exists_function() {
+        let json_path_exists = JsonPathExistsFunction;
+
+        assert_eq!("json_path_exists", json_path_exists.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            json_path_exists
+                .return_type(&[
+                    ConcreteDataType::json_datatype(),
+                    ConcreteDataType::string_datatype()
+                ])
+                .unwrap()
+        );
+        assert!(matches!(json_path_exists.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Exact(valid_types),
+                             volatility: Volatility::Immutable
+                         } if valid_types == vec![ConcreteDataType::json_datatype(), ConcreteDataType::string_datatype()]
+        ));
+
+        let jsons = [
+            r#"{"a": {"b": 2}, "b": 2, "c": 3}"#,
+            r#"{"a": 4, "b": {"c": 6}, "c": 6}"#,
+            r#"[1, 2, 3]"#,
+        ]
+        .iter()
+        .map(|j| Some(parse_string_to_jsonb(j).unwrap()))
+        .collect::<Vec<_>>();
+        let paths = vec![Some("$.a.b"), Some("$.a.b"), None];
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(BinaryVector::from(jsons)),
+            Arc::new(StringVector::from(paths)),
+        ];
+        let vector = json_path_exists
+            .eval(FunctionContext::default(), &args)
+            .unwrap();
+
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::Boolean(true), vector.get(0));
+        assert_eq!(Value::Boolean(false), vector.get(1));
+        assert!(vector.get(2).is_null());
+
+        let args: Vec<VectorRef> = vec![
+            args[0].clone(),
+            Arc::new(StringVector::from(vec!["$.[", "$.a", "$.a"])),
+        ];
+        assert!(json_path_exists
+            .eval(FunctionContext::default(), &args)
+            .is_err());
+    }
+}
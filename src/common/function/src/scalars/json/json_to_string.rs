@@ -0,0 +1,138 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use common_query::error::{InvalidFuncArgsSnafu, InvalidInputTypeSnafu, Result};
+use common_query::prelude::{Signature, Volatility};
+use datatypes::data_type::ConcreteDataType;
+use datatypes::prelude::VectorRef;
+use datatypes::types::jsonb_to_string;
+use datatypes::vectors::StringVector;
+use snafu::{ensure, ResultExt};
+
+use crate::function::{Function, FunctionContext};
+
+/// Converts the `JSONB` into `String`. It's useful for displaying JSONB content.
+#[derive(Clone, Debug, Default)]
+pub struct JsonToStringFunction;
+
+const NAME: &str = "json_to_string";
+
+impl Function for JsonToStringFunction {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn return_type(&self, _input_types: &[ConcreteDataType]) -> Result<ConcreteDataType> {
+        Ok(ConcreteDataType::string_datatype())
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::exact(
+            vec![ConcreteDataType::json_datatype()],
+            Volatility::Immutable,
+        )
+    }
+
+    fn eval(&self, _func_ctx: FunctionContext, columns: &[VectorRef]) -> Result<VectorRef> {
+        ensure!(
+            columns.len() == 1,
+            InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect exactly one, have: {}",
+                    columns.len()
+                ),
+            }
+        );
+        let jsons = &columns[0];
+
+        let mut results = Vec::with_capacity(jsons.len());
+        for i in 0..jsons.len() {
+            let json = jsons.get_ref(i);
+            let json = json.as_binary().context(InvalidInputTypeSnafu {
+                err_msg: "The argument of json_to_string must be a JSON value",
+            })?;
+            let result = json
+                .map(|json| {
+                    jsonb_to_string(json).context(InvalidInputTypeSnafu {
+                        err_msg: "Illegal JSONB value",
+                    })
+                })
+                .transpose()?;
+            results.push(result);
+        }
+
+        Ok(Arc::new(StringVector::from(results)))
+    }
+}
+
+impl Display for JsonToStringFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JSON_TO_STRING")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::prelude::TypeSignature;
+    use datatypes::types::parse_string_to_jsonb;
+    use datatypes::value::Value;
+    use datatypes::vectors::BinaryVector;
+
+    use super::*;
+
+    #[test]
+    fn test_json_to_string_function() {
+        let json_to_string = JsonToStringFunction;
+
+        assert_eq!("json_to_string", json_to_string.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            json_to_string
+                .return_type(&[ConcreteDataType::json_datatype()])
+                .unwrap()
+        );
+        assert!(matches!(json_to_string.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Exact(valid_types),
+                             volatility: Volatility::Immutable
+                         } if valid_types == vec![ConcreteDataType::json_datatype()]
+        ));
+
+        let jsons = vec![
+            Some(parse_string_to_jsonb(r#"{"a": {"b": 2}, "b": 2, "c": 3}"#).unwrap()),
+            Some(parse_string_to_jsonb(r#"[1, 2, 3]"#).unwrap()),
+            None,
+        ];
+        let args: Vec<VectorRef> = vec![Arc::new(BinaryVector::from(jsons))];
+        let vector = json_to_string
+            .eval(FunctionContext::default(), &args)
+            .unwrap();
+
+        assert_eq!(3, vector.len());
+        assert_eq!(Value::from(r#"{"a":{"b":2},"b":2,"c":3}"#), vector.get(0));
+        assert_eq!(Value::from("[1,2,3]"), vector.get(1));
+        assert!(vector.get(2).is_null());
+
+        // Bytes that are not a valid JSONB value.
+        let args: Vec<VectorRef> = vec![Arc::new(BinaryVector::from(vec![Some(
+            b"invalid".to_vec(),
+        )]))];
+        assert!(json_to_string
+            .eval(FunctionContext::default(), &args)
+            .is_err());
+    }
+}
@@ -33,9 +33,12 @@ impl FunctionState {
         use api::v1::meta::ProcedureStatus;
         use async_trait::async_trait;
         use common_base::AffectedRows;
-        use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureStateResponse};
+        use common_meta::rpc::procedure::{
+            MigrateRegionRequest, ProcedureDetail, ProcedureStateResponse,
+        };
         use common_query::error::Result;
         use session::context::QueryContextRef;
+        use store_api::region_engine::{OrphanFileInfo, OrphanFilesInfo, SnapshotInfo};
         use store_api::storage::RegionId;
         use table::requests::{
             CompactTableRequest, DeleteRequest, FlushTableRequest, InsertRequest,
@@ -62,6 +65,22 @@ impl FunctionState {
                     ..Default::default()
                 })
             }
+
+            async fn cancel_procedure(&self, _pid: &str) -> Result<()> {
+                Ok(())
+            }
+
+            async fn list_procedures(&self) -> Result<Vec<ProcedureDetail>> {
+                Ok(vec![ProcedureDetail {
+                    id: "9f805a1f-05f7-490c-9f91-bd56e3cc54c1".to_string(),
+                    type_name: "test_procedure".to_string(),
+                    parent_id: None,
+                    start_time_ms: 1000,
+                    lock_keys: vec!["test_key".to_string()],
+                    status: "Running".to_string(),
+                    error: None,
+                }])
+            }
         }
 
         #[async_trait]
@@ -113,6 +132,64 @@ impl FunctionState {
             ) -> Result<AffectedRows> {
                 Ok(ROWS)
             }
+
+            async fn create_region_snapshot(
+                &self,
+                _region_id: RegionId,
+                name: String,
+                _ctx: QueryContextRef,
+            ) -> Result<SnapshotInfo> {
+                Ok(SnapshotInfo {
+                    name,
+                    manifest_version: 42,
+                    created_at: 1000,
+                })
+            }
+
+            async fn drop_region_snapshot(
+                &self,
+                _region_id: RegionId,
+                _name: String,
+                _ctx: QueryContextRef,
+            ) -> Result<()> {
+                Ok(())
+            }
+
+            async fn list_region_snapshots(
+                &self,
+                _region_id: RegionId,
+                _ctx: QueryContextRef,
+            ) -> Result<Vec<SnapshotInfo>> {
+                Ok(vec![SnapshotInfo {
+                    name: "s1".to_string(),
+                    manifest_version: 42,
+                    created_at: 1000,
+                }])
+            }
+
+            async fn gc_region_orphan_files(
+                &self,
+                _region_id: RegionId,
+                dry_run: bool,
+                _ctx: QueryContextRef,
+            ) -> Result<OrphanFilesInfo> {
+                Ok(OrphanFilesInfo {
+                    files: vec![OrphanFileInfo {
+                        path: "data/1.parquet".to_string(),
+                        file_size: 16,
+                    }],
+                    dry_run,
+                })
+            }
+
+            async fn build_region_index(
+                &self,
+                _region_id: RegionId,
+                _rebuild_all: bool,
+                _ctx: QueryContextRef,
+            ) -> Result<()> {
+                Ok(())
+            }
         }
 
         Self {
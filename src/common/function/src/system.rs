@@ -13,7 +13,9 @@
 // limitations under the License.
 
 mod build;
+mod cancel_procedure;
 mod database;
+mod list_procedures;
 mod procedure_state;
 mod timezone;
 mod version;
@@ -21,7 +23,9 @@ mod version;
 use std::sync::Arc;
 
 use build::BuildFunction;
+use cancel_procedure::CancelProcedureFunction;
 use database::DatabaseFunction;
+use list_procedures::ListProceduresFunction;
 use procedure_state::ProcedureStateFunction;
 use timezone::TimezoneFunction;
 use version::VersionFunction;
@@ -37,5 +41,7 @@ impl SystemFunction {
         registry.register(Arc::new(DatabaseFunction));
         registry.register(Arc::new(TimezoneFunction));
         registry.register(Arc::new(ProcedureStateFunction));
+        registry.register(Arc::new(CancelProcedureFunction));
+        registry.register(Arc::new(ListProceduresFunction));
     }
 }
@@ -0,0 +1,127 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_macro::admin_fn;
+use common_query::error::Error::ThreadJoin;
+use common_query::error::{
+    InvalidFuncArgsSnafu, MissingProcedureServiceHandlerSnafu, Result,
+    UnsupportedInputDataTypeSnafu,
+};
+use common_query::prelude::{Signature, Volatility};
+use common_telemetry::error;
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use session::context::QueryContextRef;
+use snafu::{ensure, Location, OptionExt};
+
+use crate::ensure_greptime;
+use crate::function::{Function, FunctionContext};
+use crate::handlers::ProcedureServiceHandlerRef;
+
+/// A function to cancel a running procedure by its id.
+/// Such as `cancel_procedure(pid)`.
+#[admin_fn(
+    name = "CancelProcedureFunction",
+    display_name = "cancel_procedure",
+    sig_fn = "signature",
+    ret = "boolean"
+)]
+pub(crate) async fn cancel_procedure(
+    procedure_service_handler: &ProcedureServiceHandlerRef,
+    _ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    ensure!(
+        params.len() == 1,
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 1, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let ValueRef::String(pid) = params[0] else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "cancel_procedure",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+
+    procedure_service_handler.cancel_procedure(pid).await?;
+
+    Ok(Value::from(true))
+}
+
+fn signature() -> Signature {
+    Signature::uniform(
+        1,
+        vec![ConcreteDataType::string_datatype()],
+        Volatility::Volatile,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_query::prelude::TypeSignature;
+    use datatypes::vectors::{BooleanVector, StringVector};
+
+    use super::*;
+
+    #[test]
+    fn test_cancel_procedure_misc() {
+        let f = CancelProcedureFunction;
+        assert_eq!("cancel_procedure", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Uniform(1, valid_types),
+                             volatility: Volatility::Volatile
+                         } if valid_types == vec![ConcreteDataType::string_datatype()]
+        ));
+    }
+
+    #[test]
+    fn test_missing_procedure_service() {
+        let f = CancelProcedureFunction;
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from_slice(&["pid"]))];
+
+        let result = f.eval(FunctionContext::default(), &args).unwrap_err();
+        assert_eq!(
+            "Missing ProcedureServiceHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_cancel_procedure() {
+        let f = CancelProcedureFunction;
+
+        let args: Vec<VectorRef> = vec![Arc::new(StringVector::from_slice(&["pid"]))];
+
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+
+        let expect: VectorRef = Arc::new(BooleanVector::from(vec![true]));
+        assert_eq!(expect, result);
+    }
+}
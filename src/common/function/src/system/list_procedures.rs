@@ -0,0 +1,113 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_macro::admin_fn;
+use common_query::error::Error::ThreadJoin;
+use common_query::error::{
+    InvalidFuncArgsSnafu, MissingProcedureServiceHandlerSnafu, Result, SerializeJsonSnafu,
+};
+use common_query::prelude::{Signature, Volatility};
+use common_telemetry::error;
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use session::context::QueryContextRef;
+use snafu::{ensure, Location, OptionExt, ResultExt};
+
+use crate::ensure_greptime;
+use crate::function::{Function, FunctionContext};
+use crate::handlers::ProcedureServiceHandlerRef;
+
+/// A function to list running and failed procedures as a JSON array.
+/// Such as `list_procedures()`.
+#[admin_fn(
+    name = "ListProceduresFunction",
+    display_name = "list_procedures",
+    sig_fn = "signature",
+    ret = "string"
+)]
+pub(crate) async fn list_procedures(
+    procedure_service_handler: &ProcedureServiceHandlerRef,
+    _ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    ensure!(
+        params.is_empty(),
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 0, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let procedures = procedure_service_handler.list_procedures().await?;
+    let json = serde_json::to_string(&procedures).context(SerializeJsonSnafu)?;
+
+    Ok(Value::from(json))
+}
+
+fn signature() -> Signature {
+    Signature::uniform(0, vec![], Volatility::Volatile)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_query::prelude::TypeSignature;
+    use datatypes::vectors::StringVector;
+
+    use super::*;
+
+    #[test]
+    fn test_list_procedures_misc() {
+        let f = ListProceduresFunction;
+        assert_eq!("list_procedures", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::Uniform(0, valid_types),
+                             volatility: Volatility::Volatile
+                         } if valid_types.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_missing_procedure_service() {
+        let f = ListProceduresFunction;
+
+        let result = f.eval(FunctionContext::default(), &[]).unwrap_err();
+        assert_eq!(
+            "Missing ProcedureServiceHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_list_procedures() {
+        let f = ListProceduresFunction;
+
+        let result = f.eval(FunctionContext::mock(), &[]).unwrap();
+
+        let expect: VectorRef = Arc::new(StringVector::from(vec![
+            "[{\"id\":\"9f805a1f-05f7-490c-9f91-bd56e3cc54c1\",\"type_name\":\"test_procedure\",\"start_time_ms\":1000,\"lock_keys\":[\"test_key\"],\"status\":\"Running\"}]",
+        ]));
+        assert_eq!(expect, result);
+    }
+}
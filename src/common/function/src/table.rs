@@ -12,15 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod build_region_index;
 mod flush_compact_region;
 mod flush_compact_table;
+mod gc_region;
 mod migrate_region;
+mod region_snapshot;
 
 use std::sync::Arc;
 
+use build_region_index::BuildRegionIndexFunction;
 use flush_compact_region::{CompactRegionFunction, FlushRegionFunction};
 use flush_compact_table::{CompactTableFunction, FlushTableFunction};
+use gc_region::GcRegionOrphanFilesFunction;
 use migrate_region::MigrateRegionFunction;
+use region_snapshot::{
+    CreateRegionSnapshotFunction, DropRegionSnapshotFunction, ListRegionSnapshotsFunction,
+};
 
 use crate::function_registry::FunctionRegistry;
 
@@ -35,5 +43,10 @@ impl TableFunction {
         registry.register(Arc::new(CompactRegionFunction));
         registry.register(Arc::new(FlushTableFunction));
         registry.register(Arc::new(CompactTableFunction));
+        registry.register(Arc::new(CreateRegionSnapshotFunction));
+        registry.register(Arc::new(DropRegionSnapshotFunction));
+        registry.register(Arc::new(ListRegionSnapshotsFunction));
+        registry.register(Arc::new(GcRegionOrphanFilesFunction));
+        registry.register(Arc::new(BuildRegionIndexFunction));
     }
 }
@@ -0,0 +1,146 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_macro::admin_fn;
+use common_query::error::Error::ThreadJoin;
+use common_query::error::{
+    InvalidFuncArgsSnafu, MissingTableMutationHandlerSnafu, Result, UnsupportedInputDataTypeSnafu,
+};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
+use common_telemetry::error;
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use session::context::QueryContextRef;
+use snafu::{Location, OptionExt};
+use store_api::storage::RegionId;
+
+use crate::ensure_greptime;
+use crate::function::{Function, FunctionContext};
+use crate::handlers::TableMutationHandlerRef;
+use crate::helper::cast_u64;
+
+/// A function to schedule a background job that builds indexes for existing SSTs of
+/// a region. The progress of the job is in `information_schema.greptime_region_index_builds`.
+///
+/// - `build_region_index(region_id)` builds missing or outdated indexes.
+/// - `build_region_index(region_id, rebuild_all)` rebuilds indexes of all SSTs if `rebuild_all` is true.
+#[admin_fn(
+    name = "BuildRegionIndexFunction",
+    display_name = "build_region_index",
+    sig_fn = "signature",
+    ret = "boolean"
+)]
+pub(crate) async fn build_region_index(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    let (region_id, rebuild_all) = match params {
+        [region_id] => (cast_u64(region_id)?, Some(false)),
+        [region_id, ValueRef::Boolean(rebuild_all)] => (cast_u64(region_id)?, Some(*rebuild_all)),
+        [_, _] => (None, None),
+        _ => {
+            return InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 1 or 2, have: {}",
+                    params.len()
+                ),
+            }
+            .fail();
+        }
+    };
+    let (Some(region_id), Some(rebuild_all)) = (region_id, rebuild_all) else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "build_region_index",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+
+    table_mutation_handler
+        .build_region_index(
+            RegionId::from_u64(region_id),
+            rebuild_all,
+            query_ctx.clone(),
+        )
+        .await?;
+
+    Ok(Value::from(true))
+}
+
+fn signature() -> Signature {
+    let mut signatures = vec![
+        // build_region_index(region_id)
+        TypeSignature::Uniform(1, ConcreteDataType::numerics()),
+    ];
+    // build_region_index(region_id, rebuild_all)
+    signatures.extend(ConcreteDataType::numerics().into_iter().map(|region_id| {
+        TypeSignature::Exact(vec![region_id, ConcreteDataType::boolean_datatype()])
+    }));
+    Signature::one_of(signatures, Volatility::Immutable)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::{BooleanVector, UInt64Vector};
+
+    use super::*;
+
+    #[test]
+    fn test_build_region_index_misc() {
+        let f = BuildRegionIndexFunction;
+        assert_eq!("build_region_index", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::OneOf(sigs),
+                             volatility: Volatility::Immutable
+                         } if sigs.len() == ConcreteDataType::numerics().len() + 1));
+    }
+
+    #[test]
+    fn test_build_region_index_missing_table_mutation() {
+        let f = BuildRegionIndexFunction;
+        let args: Vec<VectorRef> = vec![Arc::new(UInt64Vector::from_slice([99]))];
+        let result = f.eval(FunctionContext::default(), &args).unwrap_err();
+        assert_eq!(
+            "Missing TableMutationHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_build_region_index() {
+        let f = BuildRegionIndexFunction;
+        let expect: VectorRef = Arc::new(BooleanVector::from(vec![true]));
+
+        let args: Vec<VectorRef> = vec![Arc::new(UInt64Vector::from_slice([99]))];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        assert_eq!(expect, result);
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(BooleanVector::from(vec![true])),
+        ];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        assert_eq!(expect, result);
+    }
+}
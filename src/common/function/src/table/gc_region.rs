@@ -0,0 +1,148 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_macro::admin_fn;
+use common_query::error::Error::ThreadJoin;
+use common_query::error::{
+    InvalidFuncArgsSnafu, MissingTableMutationHandlerSnafu, Result, SerializeJsonSnafu,
+    UnsupportedInputDataTypeSnafu,
+};
+use common_query::prelude::{Signature, TypeSignature, Volatility};
+use common_telemetry::error;
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use session::context::QueryContextRef;
+use snafu::{Location, OptionExt, ResultExt};
+use store_api::storage::RegionId;
+
+use crate::ensure_greptime;
+use crate::function::{Function, FunctionContext};
+use crate::handlers::TableMutationHandlerRef;
+use crate::helper::cast_u64;
+
+/// A function to collect files under the region directory that are not referenced by
+/// the region. Returns the orphan files as a JSON object.
+///
+/// - `gc_region_orphan_files(region_id)` deletes the orphan files.
+/// - `gc_region_orphan_files(region_id, dry_run)` only reports them if `dry_run` is true.
+#[admin_fn(
+    name = "GcRegionOrphanFilesFunction",
+    display_name = "gc_region_orphan_files",
+    sig_fn = "signature",
+    ret = "string"
+)]
+pub(crate) async fn gc_region_orphan_files(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    let (region_id, dry_run) = match params {
+        [region_id] => (cast_u64(region_id)?, Some(false)),
+        [region_id, ValueRef::Boolean(dry_run)] => (cast_u64(region_id)?, Some(*dry_run)),
+        [_, _] => (None, None),
+        _ => {
+            return InvalidFuncArgsSnafu {
+                err_msg: format!(
+                    "The length of the args is not correct, expect 1 or 2, have: {}",
+                    params.len()
+                ),
+            }
+            .fail();
+        }
+    };
+    let (Some(region_id), Some(dry_run)) = (region_id, dry_run) else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "gc_region_orphan_files",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+
+    let orphan_files = table_mutation_handler
+        .gc_region_orphan_files(RegionId::from_u64(region_id), dry_run, query_ctx.clone())
+        .await?;
+    let json = serde_json::to_string(&orphan_files).context(SerializeJsonSnafu)?;
+
+    Ok(Value::from(json))
+}
+
+fn signature() -> Signature {
+    let mut signatures = vec![
+        // gc_region_orphan_files(region_id)
+        TypeSignature::Uniform(1, ConcreteDataType::numerics()),
+    ];
+    // gc_region_orphan_files(region_id, dry_run)
+    signatures.extend(ConcreteDataType::numerics().into_iter().map(|region_id| {
+        TypeSignature::Exact(vec![region_id, ConcreteDataType::boolean_datatype()])
+    }));
+    Signature::one_of(signatures, Volatility::Immutable)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::{BooleanVector, StringVector, UInt64Vector};
+
+    use super::*;
+
+    #[test]
+    fn test_gc_region_orphan_files_misc() {
+        let f = GcRegionOrphanFilesFunction;
+        assert_eq!("gc_region_orphan_files", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        assert!(matches!(f.signature(),
+                         Signature {
+                             type_signature: TypeSignature::OneOf(sigs),
+                             volatility: Volatility::Immutable
+                         } if sigs.len() == ConcreteDataType::numerics().len() + 1));
+    }
+
+    #[test]
+    fn test_gc_region_orphan_files_missing_table_mutation() {
+        let f = GcRegionOrphanFilesFunction;
+        let args: Vec<VectorRef> = vec![Arc::new(UInt64Vector::from_slice([99]))];
+        let result = f.eval(FunctionContext::default(), &args).unwrap_err();
+        assert_eq!(
+            "Missing TableMutationHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_gc_region_orphan_files() {
+        let f = GcRegionOrphanFilesFunction;
+        let args: Vec<VectorRef> = vec![Arc::new(UInt64Vector::from_slice([99]))];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec![
+            r#"{"files":[{"path":"data/1.parquet","file_size":16}],"dry_run":false}"#,
+        ]));
+        assert_eq!(expect, result);
+
+        let args: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(BooleanVector::from(vec![true])),
+        ];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec![
+            r#"{"files":[{"path":"data/1.parquet","file_size":16}],"dry_run":true}"#,
+        ]));
+        assert_eq!(expect, result);
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_macro::admin_fn;
+use common_query::error::Error::ThreadJoin;
+use common_query::error::{
+    InvalidFuncArgsSnafu, MissingTableMutationHandlerSnafu, Result, SerializeJsonSnafu,
+    UnsupportedInputDataTypeSnafu,
+};
+use common_query::prelude::{Signature, Volatility};
+use common_telemetry::error;
+use datatypes::prelude::*;
+use datatypes::vectors::VectorRef;
+use session::context::QueryContextRef;
+use snafu::{ensure, Location, OptionExt, ResultExt};
+use store_api::storage::RegionId;
+
+use crate::ensure_greptime;
+use crate::function::{Function, FunctionContext};
+use crate::handlers::TableMutationHandlerRef;
+use crate::helper::{cast_u64, one_of_sigs2};
+
+/// Parses `(region_id, name)` from `params` of the function `function`.
+fn region_id_and_name(function: &str, params: &[ValueRef<'_>]) -> Result<(RegionId, String)> {
+    ensure!(
+        params.len() == 2,
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 2, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let (Some(region_id), ValueRef::String(name)) = (cast_u64(&params[0])?, params[1]) else {
+        return UnsupportedInputDataTypeSnafu {
+            function,
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+
+    Ok((RegionId::from_u64(region_id), name.to_string()))
+}
+
+/// A function to create a snapshot of a region, such as `create_region_snapshot(region_id, name)`.
+/// Returns the manifest version the snapshot is taken at.
+#[admin_fn(
+    name = "CreateRegionSnapshotFunction",
+    display_name = "create_region_snapshot",
+    sig_fn = "region_id_and_name_signature",
+    ret = "uint64"
+)]
+pub(crate) async fn create_region_snapshot(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    let (region_id, name) = region_id_and_name("create_region_snapshot", params)?;
+
+    let snapshot = table_mutation_handler
+        .create_region_snapshot(region_id, name, query_ctx.clone())
+        .await?;
+
+    Ok(Value::from(snapshot.manifest_version))
+}
+
+/// A function to drop a snapshot of a region, such as `drop_region_snapshot(region_id, name)`.
+#[admin_fn(
+    name = "DropRegionSnapshotFunction",
+    display_name = "drop_region_snapshot",
+    sig_fn = "region_id_and_name_signature",
+    ret = "boolean"
+)]
+pub(crate) async fn drop_region_snapshot(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    let (region_id, name) = region_id_and_name("drop_region_snapshot", params)?;
+
+    table_mutation_handler
+        .drop_region_snapshot(region_id, name, query_ctx.clone())
+        .await?;
+
+    Ok(Value::from(true))
+}
+
+/// A function to list snapshots of a region as a JSON array, such as `list_region_snapshots(region_id)`.
+#[admin_fn(
+    name = "ListRegionSnapshotsFunction",
+    display_name = "list_region_snapshots",
+    sig_fn = "region_id_signature",
+    ret = "string"
+)]
+pub(crate) async fn list_region_snapshots(
+    table_mutation_handler: &TableMutationHandlerRef,
+    query_ctx: &QueryContextRef,
+    params: &[ValueRef<'_>],
+) -> Result<Value> {
+    ensure!(
+        params.len() == 1,
+        InvalidFuncArgsSnafu {
+            err_msg: format!(
+                "The length of the args is not correct, expect 1, have: {}",
+                params.len()
+            ),
+        }
+    );
+
+    let Some(region_id) = cast_u64(&params[0])? else {
+        return UnsupportedInputDataTypeSnafu {
+            function: "list_region_snapshots",
+            datatypes: params.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+        }
+        .fail();
+    };
+
+    let snapshots = table_mutation_handler
+        .list_region_snapshots(RegionId::from_u64(region_id), query_ctx.clone())
+        .await?;
+    let json = serde_json::to_string(&snapshots).context(SerializeJsonSnafu)?;
+
+    Ok(Value::from(json))
+}
+
+fn region_id_and_name_signature() -> Signature {
+    one_of_sigs2(
+        ConcreteDataType::numerics(),
+        vec![ConcreteDataType::string_datatype()],
+    )
+}
+
+fn region_id_signature() -> Signature {
+    Signature::uniform(1, ConcreteDataType::numerics(), Volatility::Immutable)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::vectors::{BooleanVector, StringVector, UInt64Vector};
+
+    use super::*;
+
+    fn region_id_and_name_args() -> Vec<VectorRef> {
+        vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(StringVector::from_slice(&["s1"])),
+        ]
+    }
+
+    #[test]
+    fn test_region_snapshot_misc() {
+        let f = CreateRegionSnapshotFunction;
+        assert_eq!("create_region_snapshot", f.name());
+        assert_eq!(
+            ConcreteDataType::uint64_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        let f = DropRegionSnapshotFunction;
+        assert_eq!("drop_region_snapshot", f.name());
+        assert_eq!(
+            ConcreteDataType::boolean_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+        let f = ListRegionSnapshotsFunction;
+        assert_eq!("list_region_snapshots", f.name());
+        assert_eq!(
+            ConcreteDataType::string_datatype(),
+            f.return_type(&[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_region_snapshot_missing_table_mutation() {
+        let f = CreateRegionSnapshotFunction;
+        let result = f
+            .eval(FunctionContext::default(), &region_id_and_name_args())
+            .unwrap_err();
+        assert_eq!(
+            "Missing TableMutationHandler, not expected",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn test_region_snapshot() {
+        let f = CreateRegionSnapshotFunction;
+        let result = f
+            .eval(FunctionContext::mock(), &region_id_and_name_args())
+            .unwrap();
+        let expect: VectorRef = Arc::new(UInt64Vector::from_slice([42]));
+        assert_eq!(expect, result);
+
+        let f = DropRegionSnapshotFunction;
+        let result = f
+            .eval(FunctionContext::mock(), &region_id_and_name_args())
+            .unwrap();
+        let expect: VectorRef = Arc::new(BooleanVector::from(vec![true]));
+        assert_eq!(expect, result);
+
+        let f = ListRegionSnapshotsFunction;
+        let args: Vec<VectorRef> = vec![Arc::new(UInt64Vector::from_slice([99]))];
+        let result = f.eval(FunctionContext::mock(), &args).unwrap();
+        let expect: VectorRef = Arc::new(StringVector::from(vec![
+            r#"[{"name":"s1","manifest_version":42,"created_at":1000}]"#,
+        ]));
+        assert_eq!(expect, result);
+
+        // Wrong type of the name.
+        let f = CreateRegionSnapshotFunction;
+        let args: Vec<VectorRef> = vec![
+            Arc::new(UInt64Vector::from_slice([99])),
+            Arc::new(UInt64Vector::from_slice([1])),
+        ];
+        assert!(f.eval(FunctionContext::mock(), &args).is_err());
+    }
+}
@@ -17,8 +17,9 @@ use std::sync::Arc;
 use api::v1::region::{QueryRequest, RegionRequest};
 pub use common_base::AffectedRows;
 use common_recordbatch::SendableRecordBatchStream;
+use store_api::region_request::{RegionAction, RegionActionOutput};
 
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::peer::Peer;
 
 /// The trait for handling requests to datanode.
@@ -29,6 +30,14 @@ pub trait Datanode: Send + Sync {
 
     /// Handles query requests
     async fn handle_query(&self, request: QueryRequest) -> Result<SendableRecordBatchStream>;
+
+    /// Handles administrative actions on regions.
+    async fn handle_action(&self, _action: RegionAction) -> Result<RegionActionOutput> {
+        UnsupportedSnafu {
+            operation: "region action",
+        }
+        .fail()
+    }
 }
 
 pub type DatanodeRef = Arc<dyn Datanode>;
@@ -21,12 +21,14 @@ use store_api::storage::{RegionNumber, TableId};
 use self::table_meta::TableMetadataAllocatorRef;
 use crate::cache_invalidator::CacheInvalidatorRef;
 use crate::datanode_manager::DatanodeManagerRef;
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::key::table_route::TableRouteValue;
 use crate::key::TableMetadataManagerRef;
 use crate::region_keeper::MemoryRegionKeeperRef;
 use crate::rpc::ddl::{SubmitDdlTaskRequest, SubmitDdlTaskResponse};
-use crate::rpc::procedure::{MigrateRegionRequest, MigrateRegionResponse, ProcedureStateResponse};
+use crate::rpc::procedure::{
+    MigrateRegionRequest, MigrateRegionResponse, ProcedureDetail, ProcedureStateResponse,
+};
 
 pub mod alter_table;
 pub mod create_logical_tables;
@@ -70,6 +72,22 @@ pub trait ProcedureExecutor: Send + Sync {
         ctx: &ExecutorContext,
         pid: &str,
     ) -> Result<ProcedureStateResponse>;
+
+    /// Cancel the procedure by its id
+    async fn cancel_procedure(&self, _ctx: &ExecutorContext, _pid: &str) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "cancel_procedure",
+        }
+        .fail()
+    }
+
+    /// List procedures in the procedure manager
+    async fn list_procedures(&self, _ctx: &ExecutorContext) -> Result<Vec<ProcedureDetail>> {
+        UnsupportedSnafu {
+            operation: "list_procedures",
+        }
+        .fail()
+    }
 }
 
 pub type ProcedureExecutorRef = Arc<dyn ProcedureExecutor>;
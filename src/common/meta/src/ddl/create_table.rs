@@ -15,9 +15,10 @@
 use std::collections::HashMap;
 
 use api::v1::region::region_request::Body as PbRegionRequest;
-use api::v1::region::{RegionRequest, RegionRequestHeader};
+use api::v1::region::{DropRequest as PbDropRegionRequest, RegionRequest, RegionRequestHeader};
 use async_trait::async_trait;
-use common_error::ext::BoxedError;
+use common_error::ext::{BoxedError, ErrorExt};
+use common_error::status_code::StatusCode;
 use common_procedure::error::{
     ExternalSnafu, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
 };
@@ -193,12 +194,21 @@ impl CreateTableProcedure {
     ///   - [Code::Unavailable](tonic::status::Code::Unavailable)
     pub async fn on_datanode_create_regions(&mut self) -> Result<Status> {
         // Safety: the table route must be allocated.
-        match self.table_route()?.clone() {
-            TableRouteValue::Physical(x) => {
-                let region_routes = x.region_routes.clone();
-                let request_builder = self.new_region_request_builder(None)?;
-                self.create_regions(&region_routes, request_builder).await
-            }
+        let physical_table_id = match self.table_route()? {
+            TableRouteValue::Physical(_) => None,
+            TableRouteValue::Logical(x) => Some(x.physical_table_id()),
+        };
+        let region_routes = self.region_routes().await?;
+        let request_builder = self.new_region_request_builder(physical_table_id)?;
+
+        self.create_regions(&region_routes, request_builder).await
+    }
+
+    /// Returns the region routes to create regions on, they are the routes of the
+    /// physical table if the table is a logical table.
+    async fn region_routes(&self) -> Result<Vec<RegionRoute>> {
+        match self.table_route()? {
+            TableRouteValue::Physical(x) => Ok(x.region_routes.clone()),
             TableRouteValue::Logical(x) => {
                 let physical_table_id = x.physical_table_id();
 
@@ -211,11 +221,8 @@ impl CreateTableProcedure {
                     .context(TableRouteNotFoundSnafu {
                         table_id: physical_table_id,
                     })?;
-                let region_routes = &physical_table_route.region_routes;
-
-                let request_builder = self.new_region_request_builder(Some(physical_table_id))?;
 
-                self.create_regions(region_routes, request_builder).await
+                Ok(physical_table_route.region_routes)
             }
         }
     }
@@ -313,6 +320,71 @@ impl CreateTableProcedure {
 
         Ok(Status::done_with_output(table_id))
     }
+
+    /// Drops the regions created on datanodes if the table metadata is not created.
+    ///
+    /// Regions that are not created yet are ignored, so it's safe to rollback
+    /// a procedure failed in any state.
+    async fn rollback_datanode_regions(&mut self) -> Result<()> {
+        // Nothing to rollback if the table id and route are not allocated.
+        if self.creator.data.table_route.is_none() {
+            return Ok(());
+        }
+
+        let table_id = self.table_id();
+        // The table is created, the regions must be kept.
+        if self
+            .context
+            .table_metadata_manager
+            .table_info_manager()
+            .get(table_id)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let region_routes = self.region_routes().await?;
+        let leaders = find_leaders(&region_routes);
+        let mut drop_region_tasks = Vec::with_capacity(leaders.len());
+
+        for datanode in leaders {
+            let requester = self.context.datanode_manager.datanode(&datanode).await;
+
+            for region_number in find_leader_regions(&region_routes, &datanode) {
+                let request = RegionRequest {
+                    header: Some(RegionRequestHeader {
+                        tracing_context: TracingContext::from_current_span().to_w3c(),
+                        ..Default::default()
+                    }),
+                    body: Some(PbRegionRequest::Drop(PbDropRegionRequest {
+                        region_id: RegionId::new(table_id, region_number).as_u64(),
+                    })),
+                };
+
+                let datanode = datanode.clone();
+                let requester = requester.clone();
+                drop_region_tasks.push(async move {
+                    if let Err(err) = requester.handle(request).await {
+                        if err.status_code() != StatusCode::RegionNotFound {
+                            return Err(add_peer_context_if_needed(datanode)(err));
+                        }
+                    }
+                    Ok(())
+                });
+            }
+        }
+
+        join_all(drop_region_tasks)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        // Deregisters the opening regions.
+        self.creator.opening_regions.clear();
+        info!("Rolled back regions of table {table_id}");
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -336,6 +408,16 @@ impl Procedure for CreateTableProcedure {
         .map_err(handle_retry_error)
     }
 
+    async fn rollback(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<()> {
+        self.rollback_datanode_regions()
+            .await
+            .map_err(handle_retry_error)
+    }
+
+    fn rollback_supported(&self) -> bool {
+        true
+    }
+
     fn dump(&self) -> ProcedureResult<String> {
         serde_json::to_string(&self.creator.data).context(ToJsonSnafu)
     }
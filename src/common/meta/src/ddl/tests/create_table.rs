@@ -14,9 +14,10 @@
 
 use std::assert_matches::assert_matches;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use api::v1::meta::Partition;
+use api::v1::region::region_request::Body as RegionRequestBody;
 use api::v1::region::{QueryRequest, RegionRequest};
 use api::v1::{ColumnDataType, SemanticType};
 use common_error::ext::{BoxedError, ErrorExt};
@@ -326,3 +327,91 @@ async fn test_on_create_metadata() {
     let table_id = status.downcast_output_ref::<u32>().unwrap();
     assert_eq!(*table_id, 1024);
 }
+
+#[derive(Clone, Default)]
+pub struct RecordingDatanodeHandler {
+    requests: Arc<Mutex<Vec<RegionRequest>>>,
+}
+
+#[async_trait::async_trait]
+impl MockDatanodeHandler for RecordingDatanodeHandler {
+    async fn handle(&self, _peer: &Peer, request: RegionRequest) -> Result<AffectedRows> {
+        self.requests.lock().unwrap().push(request);
+        Ok(0)
+    }
+
+    async fn handle_query(
+        &self,
+        _peer: &Peer,
+        _request: QueryRequest,
+    ) -> Result<SendableRecordBatchStream> {
+        unreachable!()
+    }
+}
+
+impl RecordingDatanodeHandler {
+    fn take_region_ids(&self, is_drop: bool) -> Vec<u64> {
+        self.requests
+            .lock()
+            .unwrap()
+            .drain(..)
+            .filter_map(|request| match request.body.unwrap() {
+                RegionRequestBody::Create(req) if !is_drop => Some(req.region_id),
+                RegionRequestBody::Drop(req) if is_drop => Some(req.region_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_rollback_created_regions() {
+    common_telemetry::init_default_ut_logging();
+    let handler = RecordingDatanodeHandler::default();
+    let datanode_manager = Arc::new(MockDatanodeManager::new(handler.clone()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let cluster_id = 1;
+    let task = test_create_table_task("foo");
+    let mut procedure = CreateTableProcedure::new(cluster_id, task, ddl_context);
+    assert!(procedure.rollback_supported());
+    let ctx = ProcedureContext {
+        procedure_id: ProcedureId::random(),
+        provider: Arc::new(MockContextProvider::default()),
+    };
+    // Nothing to rollback before the table is allocated.
+    procedure.rollback(&ctx).await.unwrap();
+    assert!(handler.take_region_ids(true).is_empty());
+
+    procedure.on_prepare().await.unwrap();
+    procedure.execute(&ctx).await.unwrap();
+    let created = handler.take_region_ids(false);
+    assert!(!created.is_empty());
+
+    // Drops the created regions.
+    procedure.rollback(&ctx).await.unwrap();
+    assert_eq!(created, handler.take_region_ids(true));
+    assert!(procedure.creator.opening_regions.is_empty());
+}
+
+#[tokio::test]
+async fn test_rollback_after_metadata_created() {
+    common_telemetry::init_default_ut_logging();
+    let handler = RecordingDatanodeHandler::default();
+    let datanode_manager = Arc::new(MockDatanodeManager::new(handler.clone()));
+    let ddl_context = new_ddl_context(datanode_manager);
+    let cluster_id = 1;
+    let task = test_create_table_task("foo");
+    let mut procedure = CreateTableProcedure::new(cluster_id, task, ddl_context);
+    procedure.on_prepare().await.unwrap();
+    let ctx = ProcedureContext {
+        procedure_id: ProcedureId::random(),
+        provider: Arc::new(MockContextProvider::default()),
+    };
+    procedure.execute(&ctx).await.unwrap();
+    procedure.execute(&ctx).await.unwrap();
+    let _ = handler.take_region_ids(false);
+
+    // The table is created, regions must be kept.
+    procedure.rollback(&ctx).await.unwrap();
+    assert!(handler.take_region_ids(true).is_empty());
+}
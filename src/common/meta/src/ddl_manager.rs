@@ -47,7 +47,9 @@ use crate::rpc::ddl::{
     TruncateTableTask,
 };
 use crate::rpc::procedure;
-use crate::rpc::procedure::{MigrateRegionRequest, MigrateRegionResponse, ProcedureStateResponse};
+use crate::rpc::procedure::{
+    MigrateRegionRequest, MigrateRegionResponse, ProcedureDetail, ProcedureStateResponse,
+};
 use crate::rpc::router::RegionRoute;
 use crate::table_name::TableName;
 use crate::ClusterId;
@@ -600,6 +602,26 @@ impl ProcedureExecutor for DdlManager {
 
         Ok(procedure::procedure_state_to_pb_response(&state))
     }
+
+    async fn cancel_procedure(&self, _ctx: &ExecutorContext, pid: &str) -> Result<()> {
+        let pid = ProcedureId::parse_str(pid)
+            .with_context(|_| error::ParseProcedureIdSnafu { key: pid })?;
+
+        self.procedure_manager
+            .cancel(pid)
+            .await
+            .context(error::CancelProcedureSnafu)
+    }
+
+    async fn list_procedures(&self, _ctx: &ExecutorContext) -> Result<Vec<ProcedureDetail>> {
+        let procedures = self
+            .procedure_manager
+            .list_procedures()
+            .await
+            .context(error::QueryProcedureSnafu)?;
+
+        Ok(procedures.into_iter().map(ProcedureDetail::from).collect())
+    }
 }
 
 #[cfg(test)]
@@ -106,6 +106,12 @@ pub enum Error {
         source: common_procedure::Error,
     },
 
+    #[snafu(display("Failed to cancel procedure"))]
+    CancelProcedure {
+        location: Location,
+        source: common_procedure::Error,
+    },
+
     #[snafu(display("Procedure not found: {pid}"))]
     ProcedureNotFound { location: Location, pid: String },
 
@@ -454,6 +460,7 @@ impl ErrorExt for Error {
 
             SubmitProcedure { source, .. }
             | QueryProcedure { source, .. }
+            | CancelProcedure { source, .. }
             | WaitProcedure { source, .. } => source.status_code(),
             RegisterProcedureLoader { source, .. } => source.status_code(),
             External { source, .. } => source.status_code(),
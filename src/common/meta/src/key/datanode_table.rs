@@ -165,6 +165,21 @@ impl DatanodeTableManager {
         Box::pin(stream.map(|kv| kv.map(|kv| kv.1)))
     }
 
+    /// Returns the table values of all datanodes.
+    pub fn all_tables(&self) -> BoxStream<'static, Result<DatanodeTableValue>> {
+        let start_key = format!("{}/", DATANODE_TABLE_KEY_PREFIX);
+        let req = RangeRequest::new().with_prefix(start_key.as_bytes());
+
+        let stream = PaginationStream::new(
+            self.kv_backend.clone(),
+            req,
+            DEFAULT_PAGE_SIZE,
+            Arc::new(datanode_table_value_decoder),
+        );
+
+        Box::pin(stream.map(|kv| kv.map(|kv| kv.1)))
+    }
+
     /// Builds the create datanode table transactions. It only executes while the primary keys comparing successes.
     pub fn build_create_txn(
         &self,
@@ -19,7 +19,8 @@ use api::v1::meta::{
     ProcedureId as PbProcedureId, ProcedureStateResponse as PbProcedureStateResponse,
     ProcedureStatus as PbProcedureStatus,
 };
-use common_procedure::{ProcedureId, ProcedureState};
+use common_procedure::{ProcedureId, ProcedureInfo, ProcedureState};
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 
 use crate::error::{ParseProcedureIdSnafu, Result};
@@ -33,6 +34,35 @@ pub struct MigrateRegionRequest {
     pub replay_timeout: Duration,
 }
 
+/// Serializable details of a procedure, it's converted from [`ProcedureInfo`]
+/// so it can be sent from the metasrv to frontends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcedureDetail {
+    pub id: String,
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    pub start_time_ms: i64,
+    pub lock_keys: Vec<String>,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<ProcedureInfo> for ProcedureDetail {
+    fn from(info: ProcedureInfo) -> Self {
+        Self {
+            id: info.id.to_string(),
+            type_name: info.type_name,
+            parent_id: info.parent_id.map(|id| id.to_string()),
+            start_time_ms: info.start_time_ms,
+            lock_keys: info.lock_keys,
+            status: info.state.as_str_name().to_string(),
+            error: info.state.error().map(|e| e.to_string()),
+        }
+    }
+}
+
 /// Cast the protobuf [`ProcedureId`] to common [`ProcedureId`].
 pub fn pb_pid_to_pid(pid: &PbProcedureId) -> Result<ProcedureId> {
     ProcedureId::parse_str(&String::from_utf8_lossy(&pid.key)).with_context(|_| {
@@ -55,6 +85,8 @@ pub fn procedure_state_to_pb_response(state: &ProcedureState) -> PbProcedureStat
         ProcedureState::Running => (PbProcedureStatus::Running, String::default()),
         ProcedureState::Done { .. } => (PbProcedureStatus::Done, String::default()),
         ProcedureState::Retrying { error } => (PbProcedureStatus::Retrying, error.to_string()),
+        // The procedure is still running until the rollback finishes.
+        ProcedureState::RollingBack { error } => (PbProcedureStatus::Running, error.to_string()),
         ProcedureState::Failed { error } => (PbProcedureStatus::Failed, error.to_string()),
     };
 
@@ -83,6 +115,32 @@ mod tests {
         assert_eq!(pid, pb_pid_to_pid(&pb_pid).unwrap());
     }
 
+    #[test]
+    fn test_procedure_detail() {
+        let id = ProcedureId::random();
+        let info = ProcedureInfo {
+            id,
+            type_name: "test".to_string(),
+            parent_id: None,
+            start_time_ms: 1000,
+            lock_keys: vec!["key".to_string()],
+            state: ProcedureState::failed(Arc::new(Error::ManagerNotStart {
+                location: Location::default(),
+            })),
+        };
+        let detail = ProcedureDetail::from(info);
+        assert_eq!(id.to_string(), detail.id);
+        assert_eq!("Failed", detail.status);
+        assert_eq!(
+            Some("Procedure Manager is stopped"),
+            detail.error.as_deref()
+        );
+
+        let json = serde_json::to_string(&detail).unwrap();
+        assert!(!json.contains("parent_id"));
+        assert_eq!(detail, serde_json::from_str(&json).unwrap());
+    }
+
     #[test]
     fn test_procedure_state_to_pb_response() {
         let state = ProcedureState::Running;
@@ -104,6 +162,15 @@ mod tests {
         assert_eq!(PbProcedureStatus::Retrying as i32, resp.status);
         assert_eq!("Procedure Manager is stopped", resp.error);
 
+        let state = ProcedureState::RollingBack {
+            error: Arc::new(Error::ManagerNotStart {
+                location: Location::default(),
+            }),
+        };
+        let resp = procedure_state_to_pb_response(&state);
+        assert_eq!(PbProcedureStatus::Running as i32, resp.status);
+        assert_eq!("Procedure Manager is stopped", resp.error);
+
         let state = ProcedureState::Failed {
             error: Arc::new(Error::ManagerNotStart {
                 location: Location::default(),
@@ -110,6 +110,36 @@ pub enum Error {
         procedure_id: ProcedureId,
     },
 
+    #[snafu(display(
+        "Procedure rollback exceeded max times, procedure_id: {}",
+        procedure_id
+    ))]
+    RollbackTimesExceeded {
+        source: Arc<Error>,
+        procedure_id: ProcedureId,
+    },
+
+    #[snafu(display("Rollback is not supported"))]
+    RollbackNotSupported { location: Location },
+
+    #[snafu(display("Procedure {} is cancelled", procedure_id))]
+    ProcedureCancelled {
+        procedure_id: ProcedureId,
+        location: Location,
+    },
+
+    #[snafu(display("Procedure {} not found", procedure_id))]
+    ProcedureNotFound {
+        procedure_id: ProcedureId,
+        location: Location,
+    },
+
+    #[snafu(display("Procedure {} is already finished", procedure_id))]
+    ProcedureFinished {
+        procedure_id: ProcedureId,
+        location: Location,
+    },
+
     #[snafu(display("Corrupted data, error: "))]
     CorruptedData {
         #[snafu(source)]
@@ -150,12 +180,16 @@ impl ErrorExt for Error {
             | Error::DeleteState { .. }
             | Error::FromJson { .. }
             | Error::RetryTimesExceeded { .. }
+            | Error::RollbackTimesExceeded { .. }
             | Error::RetryLater { .. }
             | Error::WaitWatcher { .. }
             | Error::ManagerNotStart { .. } => StatusCode::Internal,
-            Error::LoaderConflict { .. } | Error::DuplicateProcedure { .. } => {
-                StatusCode::InvalidArguments
-            }
+            Error::LoaderConflict { .. }
+            | Error::DuplicateProcedure { .. }
+            | Error::ProcedureNotFound { .. }
+            | Error::ProcedureFinished { .. } => StatusCode::InvalidArguments,
+            Error::RollbackNotSupported { .. } => StatusCode::Unsupported,
+            Error::ProcedureCancelled { .. } => StatusCode::Cancelled,
             Error::ProcedurePanic { .. } | Error::CorruptedData { .. } => StatusCode::Unexpected,
             Error::ProcedureExec { source, .. } => source.status_code(),
             Error::StartRemoveOutdatedMetaTask { source, .. }
@@ -25,21 +25,22 @@ use backon::ExponentialBuilder;
 use common_runtime::{RepeatedTask, TaskFunction};
 use common_telemetry::tracing_context::{FutureExt, TracingContext};
 use common_telemetry::{info, logging, tracing};
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use tokio::sync::watch::{self, Receiver, Sender};
 use tokio::sync::{Mutex as TokioMutex, Notify};
 
 use self::rwlock::KeyRwLock;
 use crate::error::{
-    DuplicateProcedureSnafu, Error, LoaderConflictSnafu, ManagerNotStartSnafu, Result,
-    StartRemoveOutdatedMetaTaskSnafu, StopRemoveOutdatedMetaTaskSnafu,
+    DuplicateProcedureSnafu, Error, LoaderConflictSnafu, ManagerNotStartSnafu,
+    ProcedureFinishedSnafu, ProcedureNotFoundSnafu, Result, StartRemoveOutdatedMetaTaskSnafu,
+    StopRemoveOutdatedMetaTaskSnafu,
 };
 use crate::local::runner::Runner;
 use crate::procedure::BoxedProcedureLoader;
 use crate::store::{ProcedureMessage, ProcedureStore, StateStoreRef};
 use crate::{
-    BoxedProcedure, ContextProvider, LockKey, ProcedureId, ProcedureManager, ProcedureState,
-    ProcedureWithId, Watcher,
+    BoxedProcedure, ContextProvider, LockKey, ProcedureId, ProcedureInfo, ProcedureManager,
+    ProcedureState, ProcedureWithId, Watcher,
 };
 
 /// The expired time of a procedure's metadata.
@@ -57,8 +58,14 @@ const META_TTL: Duration = Duration::from_secs(60 * 10);
 pub(crate) struct ProcedureMeta {
     /// Id of this procedure.
     id: ProcedureId,
+    /// Type name of this procedure.
+    type_name: String,
     /// Parent procedure id.
     parent_id: Option<ProcedureId>,
+    /// Unix timestamp in milliseconds when the procedure is submitted.
+    start_time_ms: i64,
+    /// Whether the procedure is cancelled.
+    cancelled: AtomicBool,
     /// Notify to wait for subprocedures.
     child_notify: Notify,
     /// Lock required by this procedure.
@@ -72,11 +79,19 @@ pub(crate) struct ProcedureMeta {
 }
 
 impl ProcedureMeta {
-    fn new(id: ProcedureId, parent_id: Option<ProcedureId>, lock_key: LockKey) -> ProcedureMeta {
+    fn new(
+        id: ProcedureId,
+        type_name: &str,
+        parent_id: Option<ProcedureId>,
+        lock_key: LockKey,
+    ) -> ProcedureMeta {
         let (state_sender, state_receiver) = watch::channel(ProcedureState::Running);
         ProcedureMeta {
             id,
+            type_name: type_name.to_string(),
             parent_id,
+            start_time_ms: common_time::util::current_time_millis(),
+            cancelled: AtomicBool::new(false),
             child_notify: Notify::new(),
             lock_key,
             state_sender,
@@ -112,6 +127,34 @@ impl ProcedureMeta {
     fn num_children(&self) -> usize {
         self.children.lock().unwrap().len()
     }
+
+    /// Marks the procedure as cancelled.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        // Wakes up the procedure if it is waiting for subprocedures.
+        self.child_notify.notify_one();
+    }
+
+    /// Returns true if the procedure is cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the [ProcedureInfo] of the procedure.
+    fn info(&self) -> ProcedureInfo {
+        ProcedureInfo {
+            id: self.id,
+            type_name: self.type_name.clone(),
+            parent_id: self.parent_id,
+            start_time_ms: self.start_time_ms,
+            lock_keys: self
+                .lock_key
+                .keys_to_lock()
+                .map(|key| key.as_string().clone())
+                .collect(),
+            state: self.state(),
+        }
+    }
 }
 
 /// Reference counted pointer to [ProcedureMeta].
@@ -213,6 +256,35 @@ impl ManagerContext {
             .map(|meta| meta.state_receiver.clone())
     }
 
+    /// Cancels the procedure with specific `procedure_id` and its subprocedures.
+    fn cancel(&self, procedure_id: ProcedureId) -> Result<()> {
+        let meta = {
+            let procedures = self.procedures.read().unwrap();
+            procedures
+                .get(&procedure_id)
+                .cloned()
+                .context(ProcedureNotFoundSnafu { procedure_id })?
+        };
+        ensure!(
+            !meta.state().is_finished(),
+            ProcedureFinishedSnafu { procedure_id }
+        );
+
+        let mut metas = Vec::new();
+        self.find_procedures(&self.procedures_in_tree(&meta), &mut metas);
+        for meta in metas {
+            meta.cancel();
+        }
+
+        Ok(())
+    }
+
+    /// Returns [ProcedureInfo] of all procedures.
+    fn list_procedures(&self) -> Vec<ProcedureInfo> {
+        let procedures = self.procedures.read().unwrap();
+        procedures.values().map(|meta| meta.info()).collect()
+    }
+
     /// Notify a suspended parent procedure with specific `procedure_id` by its subprocedure.
     fn notify_by_subprocedure(&self, procedure_id: ProcedureId) {
         let procedures = self.procedures.read().unwrap();
@@ -429,7 +501,12 @@ impl LocalManager {
     ) -> Result<Watcher> {
         ensure!(self.manager_ctx.running(), ManagerNotStartSnafu);
 
-        let meta = Arc::new(ProcedureMeta::new(procedure_id, None, procedure.lock_key()));
+        let meta = Arc::new(ProcedureMeta::new(
+            procedure_id,
+            procedure.type_name(),
+            None,
+            procedure.lock_key(),
+        ));
         let runner = Runner {
             meta: meta.clone(),
             procedure,
@@ -597,6 +674,14 @@ impl ProcedureManager for LocalManager {
     fn procedure_watcher(&self, procedure_id: ProcedureId) -> Option<Watcher> {
         self.manager_ctx.watcher(procedure_id)
     }
+
+    async fn cancel(&self, procedure_id: ProcedureId) -> Result<()> {
+        self.manager_ctx.cancel(procedure_id)
+    }
+
+    async fn list_procedures(&self) -> Result<Vec<ProcedureInfo>> {
+        Ok(self.manager_ctx.list_procedures())
+    }
 }
 
 struct RemoveOutdatedMetaFunction {
@@ -626,7 +711,7 @@ pub(crate) mod test_util {
     use super::*;
 
     pub(crate) fn procedure_meta_for_test() -> ProcedureMeta {
-        ProcedureMeta::new(ProcedureId::random(), None, LockKey::default())
+        ProcedureMeta::new(ProcedureId::random(), "", None, LockKey::default())
     }
 
     pub(crate) fn new_object_store(dir: &TempDir) -> ObjectStore {
@@ -939,6 +1024,100 @@ mod tests {
         check_procedure(MockProcedure { panic: true }).await;
     }
 
+    #[tokio::test]
+    async fn test_cancel_procedure() {
+        let dir = create_temp_dir("cancel");
+        let config = ManagerConfig {
+            parent_path: "data/".to_string(),
+            max_retry_times: 3,
+            retry_delay: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let state_store = Arc::new(ObjectStateStore::new(test_util::new_object_store(&dir)));
+        let manager = LocalManager::new(config, state_store);
+        manager.manager_ctx.start();
+
+        #[derive(Debug)]
+        struct MockProcedure {
+            rolled_back: Arc<AtomicBool>,
+        }
+
+        #[async_trait]
+        impl Procedure for MockProcedure {
+            fn type_name(&self) -> &str {
+                "MockProcedure"
+            }
+
+            async fn execute(&mut self, _ctx: &Context) -> Result<Status> {
+                // Never finishes unless it is cancelled.
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(Status::executing(false))
+            }
+
+            async fn rollback(&mut self, _ctx: &Context) -> Result<()> {
+                self.rolled_back.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+
+            fn rollback_supported(&self) -> bool {
+                true
+            }
+
+            fn dump(&self) -> Result<String> {
+                Ok(String::new())
+            }
+
+            fn lock_key(&self) -> LockKey {
+                LockKey::single_exclusive("test.cancel")
+            }
+        }
+
+        let rolled_back = Arc::new(AtomicBool::new(false));
+        let procedure_id = ProcedureId::random();
+        let mut watcher = manager
+            .submit(ProcedureWithId {
+                id: procedure_id,
+                procedure: Box::new(MockProcedure {
+                    rolled_back: rolled_back.clone(),
+                }),
+            })
+            .await
+            .unwrap();
+
+        let procedures = manager.list_procedures().await.unwrap();
+        assert_eq!(1, procedures.len());
+        assert_eq!(procedure_id, procedures[0].id);
+        assert_eq!("MockProcedure", procedures[0].type_name);
+        assert_eq!(vec!["test.cancel".to_string()], procedures[0].lock_keys);
+        assert!(procedures[0].state.is_running());
+
+        manager.cancel(procedure_id).await.unwrap();
+        while !watcher.borrow().is_failed() {
+            watcher.changed().await.unwrap();
+        }
+        assert!(rolled_back.load(Ordering::Relaxed));
+        let state = manager
+            .procedure_state(procedure_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_matches!(
+            state.error().unwrap().as_ref(),
+            Error::ProcedureCancelled { .. }
+        );
+
+        // Cancels a finished procedure.
+        assert_matches!(
+            manager.cancel(procedure_id).await.unwrap_err(),
+            Error::ProcedureFinished { .. }
+        );
+        // Cancels a procedure that doesn't exist.
+        assert_matches!(
+            manager.cancel(ProcedureId::random()).await.unwrap_err(),
+            Error::ProcedureNotFound { .. }
+        );
+    }
+
     #[tokio::test]
     async fn test_procedure_manager_stopped() {
         let dir = create_temp_dir("procedure_manager_stopped");
@@ -24,7 +24,6 @@ use crate::error::{self, ProcedurePanicSnafu, Result};
 use crate::local::{ManagerContext, ProcedureMeta, ProcedureMetaRef};
 use crate::procedure::{Output, StringKey};
 use crate::store::ProcedureStore;
-use crate::ProcedureState::Retrying;
 use crate::{BoxedProcedure, Context, Error, ProcedureId, ProcedureState, ProcedureWithId, Status};
 
 #[derive(Debug)]
@@ -224,15 +223,22 @@ impl Runner {
                     if let Some(d) = retry.next() {
                         self.wait_on_err(d, retry_times).await;
                     } else {
-                        assert!(self.meta.state().is_retrying());
-                        if let Retrying { error } = self.meta.state() {
-                            self.meta.set_state(ProcedureState::failed(Arc::new(
-                                Error::RetryTimesExceeded {
-                                    source: error,
-                                    procedure_id: self.meta.id,
-                                },
-                            )))
-                        }
+                        let procedure_id = self.meta.id;
+                        let error = match self.meta.state() {
+                            ProcedureState::Retrying { error } => Error::RetryTimesExceeded {
+                                source: error,
+                                procedure_id,
+                            },
+                            ProcedureState::RollingBack { error } => Error::RollbackTimesExceeded {
+                                source: error,
+                                procedure_id,
+                            },
+                            state => unreachable!(
+                                "Procedure {} retries in unexpected state {:?}",
+                                procedure_id, state
+                            ),
+                        };
+                        self.meta.set_state(ProcedureState::failed(Arc::new(error)));
                         return;
                     }
                 }
@@ -240,10 +246,28 @@ impl Runner {
         }
     }
 
-    async fn rollback(&mut self, error: Arc<Error>) -> ExecResult {
+    /// Rolls back the procedure that fails with `error`.
+    ///
+    /// It invokes [Procedure::rollback](crate::Procedure::rollback) if the procedure
+    /// supports rollback and then writes the rollback key to the store.
+    async fn rollback(&mut self, ctx: &Context, error: Arc<Error>) -> ExecResult {
+        if self.procedure.rollback_supported() {
+            self.meta
+                .set_state(ProcedureState::rolling_back(error.clone()));
+            if let Err(e) = self.procedure.rollback(ctx).await {
+                logging::error!(
+                    e; "Failed to rollback procedure {}-{}",
+                    self.procedure.type_name(),
+                    self.meta.id
+                );
+                self.rolling_back = true;
+                return ExecResult::RetryLater;
+            }
+        }
+
         if let Err(e) = self.rollback_procedure().await {
             self.rolling_back = true;
-            self.meta.set_state(ProcedureState::retrying(Arc::new(e)));
+            self.meta.set_state(ProcedureState::rolling_back(error));
             return ExecResult::RetryLater;
         }
         self.meta.set_state(ProcedureState::failed(error));
@@ -256,7 +280,19 @@ impl Runner {
             // We can definitely get the previous error here.
             let state = self.meta.state();
             let err = state.error().unwrap();
-            return self.rollback(err.clone()).await;
+            return self.rollback(ctx, err.clone()).await;
+        }
+        if self.meta.is_cancelled() {
+            logging::info!(
+                "Procedure {}-{} is cancelled",
+                self.procedure.type_name(),
+                self.meta.id
+            );
+            let err = error::ProcedureCancelledSnafu {
+                procedure_id: self.meta.id,
+            }
+            .build();
+            return self.rollback(ctx, Arc::new(err)).await;
         }
         match self.procedure.execute(ctx).await {
             Ok(status) => {
@@ -324,7 +360,7 @@ impl Runner {
                 }
 
                 // Write rollback key so we can skip this procedure while recovering procedures.
-                self.rollback(Arc::new(e)).await
+                self.rollback(ctx, Arc::new(e)).await
             }
         }
     }
@@ -350,6 +386,7 @@ impl Runner {
 
         let meta = Arc::new(ProcedureMeta::new(
             procedure_id,
+            procedure.type_name(),
             Some(self.meta.id),
             procedure.lock_key(),
         ));
@@ -496,6 +533,7 @@ impl Runner {
 
 #[cfg(test)]
 mod tests {
+    use std::assert_matches::assert_matches;
     use std::sync::Arc;
 
     use async_trait::async_trait;
@@ -913,6 +951,120 @@ mod tests {
         .await;
     }
 
+    #[derive(Debug)]
+    struct RollbackProcedure {
+        /// Number of times the rollback fails before it succeeds.
+        rollback_failures: usize,
+        rollback_times: usize,
+    }
+
+    #[async_trait]
+    impl Procedure for RollbackProcedure {
+        fn type_name(&self) -> &str {
+            "RollbackProcedure"
+        }
+
+        async fn execute(&mut self, _ctx: &Context) -> Result<Status> {
+            Err(Error::external(MockError::new(StatusCode::Unexpected)))
+        }
+
+        async fn rollback(&mut self, _ctx: &Context) -> Result<()> {
+            self.rollback_times += 1;
+            if self.rollback_times <= self.rollback_failures {
+                return Err(Error::external(MockError::new(StatusCode::Internal)));
+            }
+            Ok(())
+        }
+
+        fn rollback_supported(&self) -> bool {
+            true
+        }
+
+        fn dump(&self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn lock_key(&self) -> LockKey {
+            LockKey::single_exclusive("catalog.schema.table")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_on_error_rollback() {
+        let procedure = RollbackProcedure {
+            rollback_failures: 1,
+            rollback_times: 0,
+        };
+
+        let dir = create_temp_dir("rollback");
+        let meta = test_util::procedure_meta_for_test();
+        let meta = Arc::new(meta);
+        let ctx = context_without_provider(meta.id);
+        let object_store = test_util::new_object_store(&dir);
+        let procedure_store = Arc::new(ProcedureStore::from_object_store(object_store.clone()));
+        let mut runner = new_runner(meta.clone(), Box::new(procedure), procedure_store.clone());
+        runner.manager_ctx.start();
+
+        // The first rollback fails.
+        let res = runner.execute_once(&ctx).await;
+        assert!(res.is_retry_later(), "{res:?}");
+        assert!(meta.state().is_rolling_back());
+        check_files(&object_store, &procedure_store, ctx.procedure_id, &[]).await;
+
+        // Retries the rollback instead of executing the procedure again.
+        let res = runner.execute_once(&ctx).await;
+        assert!(res.is_failed(), "{res:?}");
+        assert!(meta.state().is_failed());
+        // The state holds the error of the execution instead of the rollback.
+        assert_eq!(
+            StatusCode::Unexpected,
+            meta.state().error().unwrap().status_code()
+        );
+        check_files(
+            &object_store,
+            &procedure_store,
+            ctx.procedure_id,
+            &["0000000000.rollback"],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_cancelled() {
+        let exec_fn = |_| async { Ok(Status::executing(true)) }.boxed();
+        let normal = ProcedureAdapter {
+            data: "normal".to_string(),
+            lock_key: LockKey::single_exclusive("catalog.schema.table"),
+            exec_fn,
+        };
+
+        let dir = create_temp_dir("cancelled");
+        let meta = normal.new_meta(ROOT_ID);
+        let ctx = context_without_provider(meta.id);
+        let object_store = test_util::new_object_store(&dir);
+        let procedure_store = Arc::new(ProcedureStore::from_object_store(object_store.clone()));
+        let mut runner = new_runner(meta.clone(), Box::new(normal), procedure_store.clone());
+        runner.manager_ctx.start();
+
+        let res = runner.execute_once(&ctx).await;
+        assert!(res.is_continue(), "{res:?}");
+
+        meta.cancel();
+        let res = runner.execute_once(&ctx).await;
+        assert!(res.is_failed(), "{res:?}");
+        assert_matches!(
+            meta.state().error().unwrap().as_ref(),
+            Error::ProcedureCancelled { .. }
+        );
+        check_files(
+            &object_store,
+            &procedure_store,
+            ctx.procedure_id,
+            &["0000000000.step", "0000000001.rollback"],
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn test_execute_on_retry_later_error() {
         let mut times = 0;
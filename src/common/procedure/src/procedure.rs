@@ -23,7 +23,7 @@ use smallvec::{smallvec, SmallVec};
 use snafu::{ResultExt, Snafu};
 use uuid::Uuid;
 
-use crate::error::{Error, Result};
+use crate::error::{self, Error, Result};
 use crate::watcher::Watcher;
 
 pub type Output = Arc<dyn Any + Send + Sync>;
@@ -125,6 +125,19 @@ pub trait Procedure: Send + Sync {
     /// The implementation must be idempotent.
     async fn execute(&mut self, ctx: &Context) -> Result<Status>;
 
+    /// Rollback the procedure after it fails or is cancelled.
+    ///
+    /// The framework only calls this method if [Procedure::rollback_supported] returns true.
+    /// The implementation must be idempotent.
+    async fn rollback(&mut self, _ctx: &Context) -> Result<()> {
+        error::RollbackNotSupportedSnafu {}.fail()
+    }
+
+    /// Returns true if the procedure supports rollback.
+    fn rollback_supported(&self) -> bool {
+        false
+    }
+
     /// Dump the state of the procedure to a string.
     fn dump(&self) -> Result<String>;
 
@@ -142,6 +155,14 @@ impl<T: Procedure + ?Sized> Procedure for Box<T> {
         (**self).execute(ctx).await
     }
 
+    async fn rollback(&mut self, ctx: &Context) -> Result<()> {
+        (**self).rollback(ctx).await
+    }
+
+    fn rollback_supported(&self) -> bool {
+        (**self).rollback_supported()
+    }
+
     fn dump(&self) -> Result<String> {
         (**self).dump()
     }
@@ -289,6 +310,8 @@ pub enum ProcedureState {
     Done { output: Option<Output> },
     /// The procedure is failed and can be retried.
     Retrying { error: Arc<Error> },
+    /// The procedure is failed or cancelled and is rolling back.
+    RollingBack { error: Arc<Error> },
     /// The procedure is failed and cannot proceed anymore.
     Failed { error: Arc<Error> },
 }
@@ -304,6 +327,11 @@ impl ProcedureState {
         ProcedureState::Retrying { error }
     }
 
+    /// Returns a [ProcedureState] with rolling back state.
+    pub fn rolling_back(error: Arc<Error>) -> ProcedureState {
+        ProcedureState::RollingBack { error }
+    }
+
     /// Returns true if the procedure state is running.
     pub fn is_running(&self) -> bool {
         matches!(self, ProcedureState::Running)
@@ -324,16 +352,55 @@ impl ProcedureState {
         matches!(self, ProcedureState::Retrying { .. })
     }
 
+    /// Returns true if the procedure state is rolling back.
+    pub fn is_rolling_back(&self) -> bool {
+        matches!(self, ProcedureState::RollingBack { .. })
+    }
+
+    /// Returns true if the procedure is finished, whether it is done or failed.
+    pub fn is_finished(&self) -> bool {
+        self.is_done() || self.is_failed()
+    }
+
+    /// Returns the name of the state.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ProcedureState::Running => "Running",
+            ProcedureState::Done { .. } => "Done",
+            ProcedureState::Retrying { .. } => "Retrying",
+            ProcedureState::RollingBack { .. } => "RollingBack",
+            ProcedureState::Failed { .. } => "Failed",
+        }
+    }
+
     /// Returns the error.
     pub fn error(&self) -> Option<&Arc<Error>> {
         match self {
             ProcedureState::Failed { error } => Some(error),
             ProcedureState::Retrying { error } => Some(error),
+            ProcedureState::RollingBack { error } => Some(error),
             _ => None,
         }
     }
 }
 
+/// Information of a procedure in the [ProcedureManager].
+#[derive(Debug, Clone)]
+pub struct ProcedureInfo {
+    /// Id of the procedure.
+    pub id: ProcedureId,
+    /// Type name of the procedure.
+    pub type_name: String,
+    /// Id of the parent procedure, `None` if this is a root procedure.
+    pub parent_id: Option<ProcedureId>,
+    /// Unix timestamp in milliseconds when the procedure is submitted.
+    pub start_time_ms: i64,
+    /// Keys locked by the procedure.
+    pub lock_keys: Vec<String>,
+    /// Current state of the procedure.
+    pub state: ProcedureState,
+}
+
 // TODO(yingwen): Shutdown
 /// `ProcedureManager` executes [Procedure] submitted to it.
 #[async_trait]
@@ -363,6 +430,16 @@ pub trait ProcedureManager: Send + Sync + 'static {
 
     /// Returns a [Watcher] to watch [ProcedureState] of specific procedure.
     fn procedure_watcher(&self, procedure_id: ProcedureId) -> Option<Watcher>;
+
+    /// Cancels the procedure with specific `procedure_id` and its subprocedures.
+    ///
+    /// A cancelled procedure stops after its current step and rolls back if it
+    /// supports rollback.
+    async fn cancel(&self, procedure_id: ProcedureId) -> Result<()>;
+
+    /// Lists procedures in the manager, including running procedures and finished
+    /// procedures whose metadata are not removed yet.
+    async fn list_procedures(&self) -> Result<Vec<ProcedureInfo>>;
 }
 
 /// Ref-counted pointer to the [ProcedureManager].
@@ -450,12 +527,23 @@ mod tests {
     fn test_procedure_state() {
         assert!(ProcedureState::Running.is_running());
         assert!(ProcedureState::Running.error().is_none());
+        assert!(!ProcedureState::Running.is_finished());
         assert!(ProcedureState::Done { output: None }.is_done());
+        assert!(ProcedureState::Done { output: None }.is_finished());
 
         let state = ProcedureState::failed(Arc::new(Error::external(MockError::new(
             StatusCode::Unexpected,
         ))));
         assert!(state.is_failed());
+        assert!(state.is_finished());
+        let _ = state.error().unwrap();
+
+        let state = ProcedureState::rolling_back(Arc::new(Error::external(MockError::new(
+            StatusCode::Unexpected,
+        ))));
+        assert!(state.is_rolling_back());
+        assert!(!state.is_finished());
+        assert_eq!("RollingBack", state.as_str_name());
         let _ = state.error().unwrap();
     }
 }
@@ -37,6 +37,9 @@ pub async fn wait(watcher: &mut Watcher) -> Result<Option<Output>> {
             ProcedureState::Retrying { error } => {
                 debug!("retrying, source: {}", error)
             }
+            ProcedureState::RollingBack { error } => {
+                debug!("rolling back, source: {}", error)
+            }
         }
     }
 }
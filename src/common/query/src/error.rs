@@ -184,6 +184,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to serialize to json"))]
+    SerializeJson {
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
+
     #[snafu(display("Missing TableMutationHandler, not expected"))]
     MissingTableMutationHandler { location: Location },
 
@@ -225,6 +232,7 @@ impl ErrorExt for Error {
             | Error::MissingProcedureServiceHandler { .. }
             | Error::ExecuteRepeatedly { .. }
             | Error::ThreadJoin { .. }
+            | Error::SerializeJson { .. }
             | Error::GeneralDataFusion { .. } => StatusCode::Unexpected,
 
             Error::UnsupportedInputDataType { .. }
@@ -126,6 +126,21 @@ pub enum Error {
         error: datafusion::error::DataFusionError,
         location: Location,
     },
+
+    #[snafu(display("Failed to serialize time travel"))]
+    SerializeTimeTravel {
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to deserialize time travel: {}", uri))]
+    DeserializeTimeTravel {
+        uri: String,
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -144,11 +159,13 @@ impl ErrorExt for Error {
             | Error::EmptyExpr { .. }
             | Error::MissingField { .. }
             | Error::InvalidParameters { .. }
-            | Error::SchemaNotMatch { .. } => StatusCode::InvalidArguments,
+            | Error::SchemaNotMatch { .. }
+            | Error::DeserializeTimeTravel { .. } => StatusCode::InvalidArguments,
             Error::DFInternal { .. }
             | Error::Internal { .. }
             | Error::EncodeDfPlan { .. }
-            | Error::DecodeDfPlan { .. } => StatusCode::Internal,
+            | Error::DecodeDfPlan { .. }
+            | Error::SerializeTimeTravel { .. } => StatusCode::Internal,
             Error::ConvertDfSchema { source, .. } => source.status_code(),
             Error::ResolveTable { source, .. } => source.status_code(),
         }
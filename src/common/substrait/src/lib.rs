@@ -17,6 +17,7 @@
 mod df_substrait;
 pub mod error;
 pub mod extension_serializer;
+pub mod time_travel;
 
 use std::sync::Arc;
 
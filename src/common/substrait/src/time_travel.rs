@@ -0,0 +1,83 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Carries the [TimeTravel] of a table scan in an encoded substrait plan.
+//!
+//! The time travel is stored as an extension uri of the plan, which is ignored
+//! by the substrait consumer.
+
+use bytes::{Bytes, BytesMut};
+use prost::Message;
+use snafu::ResultExt;
+use store_api::storage::TimeTravel;
+use substrait_proto::proto::extensions::SimpleExtensionUri;
+use substrait_proto::proto::Plan;
+
+use crate::error::{
+    DecodeRelSnafu, DeserializeTimeTravelSnafu, EncodeRelSnafu, Result, SerializeTimeTravelSnafu,
+};
+
+const TIME_TRAVEL_URI_PREFIX: &str = "greptime:time_travel/";
+
+/// Attaches `time_travel` to the encoded substrait `plan`.
+pub fn encode_time_travel(plan: &[u8], time_travel: &TimeTravel) -> Result<Bytes> {
+    let mut plan = Plan::decode(plan).context(DecodeRelSnafu)?;
+    let json = serde_json::to_string(time_travel).context(SerializeTimeTravelSnafu)?;
+    let anchor = plan
+        .extension_uris
+        .iter()
+        .map(|uri| uri.extension_uri_anchor + 1)
+        .max()
+        .unwrap_or_default();
+    plan.extension_uris.push(SimpleExtensionUri {
+        extension_uri_anchor: anchor,
+        uri: format!("{TIME_TRAVEL_URI_PREFIX}{json}"),
+    });
+
+    let mut buf = BytesMut::new();
+    plan.encode(&mut buf).context(EncodeRelSnafu)?;
+    Ok(buf.freeze())
+}
+
+/// Returns the [TimeTravel] attached to the encoded substrait `plan`.
+pub fn decode_time_travel(plan: &[u8]) -> Result<Option<TimeTravel>> {
+    let plan = Plan::decode(plan).context(DecodeRelSnafu)?;
+    plan.extension_uris
+        .iter()
+        .find_map(|uri| uri.uri.strip_prefix(TIME_TRAVEL_URI_PREFIX))
+        .map(|json| serde_json::from_str(json).context(DeserializeTimeTravelSnafu { uri: json }))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_time_travel() {
+        let mut buf = BytesMut::new();
+        Plan::default().encode(&mut buf).unwrap();
+        let plan = buf.freeze();
+        assert_eq!(None, decode_time_travel(&plan).unwrap());
+
+        for time_travel in [
+            TimeTravel::Version(3),
+            TimeTravel::Timestamp(1000),
+            TimeTravel::Snapshot("s1".to_string()),
+        ] {
+            let encoded = encode_time_travel(&plan, &time_travel).unwrap();
+            assert_eq!(Some(time_travel), decode_time_travel(&encoded).unwrap());
+        }
+    }
+}
@@ -86,6 +86,7 @@ impl From<StandaloneWalConfig> for DatanodeWalConfig {
                 linger: config.linger,
                 consumer_wait_timeout: config.consumer_wait_timeout,
                 backoff: config.backoff,
+                ..Default::default()
             }),
         }
     }
@@ -199,6 +200,8 @@ mod tests {
                 base: 2,
                 deadline: Some(Duration::from_secs(60 * 5)),
             },
+            prune_obsolete_records: false,
+            checkpoint_interval: Duration::from_secs(60),
         };
         assert_eq!(datanode_wal_config, DatanodeWalConfig::Kafka(expected));
 
@@ -41,6 +41,16 @@ pub struct DatanodeKafkaConfig {
     /// The backoff config.
     #[serde(flatten, with = "backoff_prefix")]
     pub backoff: BackoffConfig,
+    /// Whether to delete records of a topic once they are obsolete for all regions
+    /// in the topic.
+    ///
+    /// Records are only deleted up to the minimum checkpoint of the regions. A region
+    /// gets its checkpoint when it's created or opened.
+    pub prune_obsolete_records: bool,
+    /// The interval of persisting checkpoints advanced by flushes to the metadata store
+    /// and pruning obsolete records.
+    #[serde(with = "humantime_serde")]
+    pub checkpoint_interval: Duration,
 }
 
 impl Default for DatanodeKafkaConfig {
@@ -53,6 +63,8 @@ impl Default for DatanodeKafkaConfig {
             linger: Duration::from_millis(200),
             consumer_wait_timeout: Duration::from_millis(100),
             backoff: BackoffConfig::default(),
+            prune_obsolete_records: false,
+            checkpoint_interval: Duration::from_secs(60),
         }
     }
 }
@@ -210,7 +210,9 @@ impl DatanodeBuilder {
             (Box::new(NoopRegionServerEventListener) as _, None)
         };
 
-        let region_server = self.new_region_server(region_event_listener).await?;
+        let region_server = self
+            .new_region_server(region_event_listener, kv_backend.clone())
+            .await?;
 
         let datanode_table_manager = DatanodeTableManager::new(kv_backend.clone());
         let table_values = datanode_table_manager
@@ -292,6 +294,7 @@ impl DatanodeBuilder {
     async fn new_region_server(
         &self,
         event_listener: RegionServerEventListenerRef,
+        kv_backend: KvBackendRef,
     ) -> Result<RegionServer> {
         let opts = &self.opts;
 
@@ -323,7 +326,7 @@ impl DatanodeBuilder {
         );
 
         let object_store_manager = Self::build_object_store_manager(opts).await?;
-        let engines = Self::build_store_engines(opts, object_store_manager).await?;
+        let engines = Self::build_store_engines(opts, object_store_manager, kv_backend).await?;
         for engine in engines {
             region_server.register_engine(engine);
         }
@@ -337,14 +340,19 @@ impl DatanodeBuilder {
     async fn build_store_engines(
         opts: &DatanodeOptions,
         object_store_manager: ObjectStoreManagerRef,
+        kv_backend: KvBackendRef,
     ) -> Result<Vec<RegionEngineRef>> {
         let mut engines = vec![];
         for engine in &opts.region_engine {
             match engine {
                 RegionEngineConfig::Mito(config) => {
-                    let mito_engine =
-                        Self::build_mito_engine(opts, object_store_manager.clone(), config.clone())
-                            .await?;
+                    let mito_engine = Self::build_mito_engine(
+                        opts,
+                        object_store_manager.clone(),
+                        config.clone(),
+                        kv_backend.clone(),
+                    )
+                    .await?;
 
                     let metric_engine = MetricEngine::new(mito_engine.clone());
                     engines.push(Arc::new(mito_engine) as _);
@@ -367,6 +375,7 @@ impl DatanodeBuilder {
         opts: &DatanodeOptions,
         object_store_manager: ObjectStoreManagerRef,
         config: MitoConfig,
+        kv_backend: KvBackendRef,
     ) -> Result<MitoEngine> {
         let mito_engine = match &opts.wal {
             DatanodeWalConfig::RaftEngine(raft_engine_config) => MitoEngine::new(
@@ -381,7 +390,7 @@ impl DatanodeBuilder {
             DatanodeWalConfig::Kafka(kafka_config) => MitoEngine::new(
                 &opts.storage.data_home,
                 config,
-                Self::build_kafka_log_store(kafka_config).await?,
+                Self::build_kafka_log_store(kafka_config, kv_backend).await?,
                 object_store_manager,
             )
             .await
@@ -418,8 +427,11 @@ impl DatanodeBuilder {
     }
 
     /// Builds [KafkaLogStore].
-    async fn build_kafka_log_store(config: &DatanodeKafkaConfig) -> Result<Arc<KafkaLogStore>> {
-        KafkaLogStore::try_new(config)
+    async fn build_kafka_log_store(
+        config: &DatanodeKafkaConfig,
+        kv_backend: KvBackendRef,
+    ) -> Result<Arc<KafkaLogStore>> {
+        KafkaLogStore::try_new(config, kv_backend)
             .await
             .map_err(Box::new)
             .context(OpenLogStoreSnafu)
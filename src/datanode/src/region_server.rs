@@ -20,7 +20,11 @@ use std::sync::{Arc, Mutex, RwLock};
 
 use api::v1::region::{region_request, QueryRequest, RegionResponse};
 use api::v1::{ResponseHeader, Status};
-use arrow_flight::{FlightData, Ticket};
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
+};
 use async_trait::async_trait;
 use bytes::Bytes;
 use common_error::ext::BoxedError;
@@ -47,18 +51,25 @@ use metric_engine::engine::MetricEngine;
 use prost::Message;
 use query::QueryEngineRef;
 use servers::error::{self as servers_error, ExecuteGrpcRequestSnafu, Result as ServerResult};
-use servers::grpc::flight::{FlightCraft, FlightRecordBatchStream, TonicStream};
+use servers::grpc::flight::{
+    FlightCraft, FlightCraftWrapper, FlightRecordBatchStream, TonicStream,
+};
 use servers::grpc::region_server::RegionServerHandler;
 use session::context::{QueryContextBuilder, QueryContextRef};
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::metric_engine_consts::{METRIC_ENGINE_NAME, PHYSICAL_TABLE_METADATA_KEY};
-use store_api::region_engine::{RegionEngineRef, RegionRole, SetReadonlyResponse};
-use store_api::region_request::{AffectedRows, RegionCloseRequest, RegionRequest};
-use store_api::storage::{RegionId, ScanRequest};
+use store_api::region_engine::{
+    RegionEngineRef, RegionIndexBuildProgress, RegionRole, SetReadonlyResponse,
+};
+use store_api::region_request::{
+    AffectedRows, RegionAction, RegionActionOutput, RegionCloseRequest, RegionRequest,
+};
+use store_api::storage::{RegionId, ScanRequest, TimeTravel};
+use substrait::time_travel::decode_time_travel;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::table::scan::StreamScanAdapter;
-use tonic::{Request, Response, Result as TonicResult};
+use tonic::{Request, Response, Result as TonicResult, Streaming};
 
 use crate::error::{
     self, BuildRegionRequestsSnafu, DecodeLogicalPlanSnafu, ExecuteLogicalPlanSnafu,
@@ -137,6 +148,69 @@ impl RegionServer {
         self.inner.handle_read(request).await
     }
 
+    /// Takes the administrative `action` on the region.
+    #[tracing::instrument(skip_all)]
+    pub async fn handle_action(&self, action: RegionAction) -> Result<RegionActionOutput> {
+        let region_id = match action {
+            RegionAction::IndexBuildProgress { region_ids } => {
+                return self.index_build_progress(region_ids);
+            }
+            // Safety: actions except `IndexBuildProgress` take a single region.
+            _ => action.region_id().unwrap(),
+        };
+        let engine = self
+            .find_engine(region_id)?
+            .with_context(|| RegionNotFoundSnafu { region_id })?;
+
+        let output = match action {
+            RegionAction::CreateSnapshot { name, .. } => engine
+                .create_snapshot(region_id, &name)
+                .await
+                .map(RegionActionOutput::Snapshot),
+            RegionAction::DropSnapshot { name, .. } => engine
+                .drop_snapshot(region_id, &name)
+                .await
+                .map(|_| RegionActionOutput::None),
+            RegionAction::ListSnapshots { .. } => engine
+                .list_snapshots(region_id)
+                .map(RegionActionOutput::Snapshots),
+            RegionAction::GcOrphanFiles { dry_run, .. } => engine
+                .gc_orphan_files(region_id, dry_run)
+                .await
+                .map(RegionActionOutput::OrphanFiles),
+            RegionAction::BuildIndex { rebuild_all, .. } => engine
+                .build_index(region_id, rebuild_all)
+                .await
+                .map(|_| RegionActionOutput::None),
+            // Handled above.
+            RegionAction::IndexBuildProgress { .. } => unreachable!(),
+        };
+        output.with_context(|_| HandleRegionRequestSnafu { region_id })
+    }
+
+    /// Gets the index build progress of `region_ids`.
+    ///
+    /// Skips regions that are not opened on this datanode.
+    fn index_build_progress(&self, region_ids: Vec<RegionId>) -> Result<RegionActionOutput> {
+        let mut progresses = Vec::new();
+        for region_id in region_ids {
+            let Ok(Some(engine)) = self.find_engine(region_id) else {
+                continue;
+            };
+            let progress = engine
+                .index_build_progress(region_id)
+                .with_context(|_| HandleRegionRequestSnafu { region_id })?;
+            if let Some(progress) = progress {
+                progresses.push(RegionIndexBuildProgress {
+                    region_id,
+                    progress,
+                });
+            }
+        }
+
+        Ok(RegionActionOutput::IndexBuildProgress(progresses))
+    }
+
     /// Returns all opened and reportable regions.
     ///
     /// Notes: except all metrics regions.
@@ -311,6 +385,124 @@ impl FlightCraft for RegionServer {
     }
 }
 
+/// The Flight service of the datanode. Besides queries served by [FlightCraft], it
+/// takes [RegionAction]s by `do_action`.
+pub struct RegionFlightService(FlightCraftWrapper<RegionServer>);
+
+impl RegionFlightService {
+    pub fn new(region_server: RegionServer) -> Self {
+        Self(FlightCraftWrapper(region_server))
+    }
+}
+
+#[async_trait]
+impl FlightService for RegionFlightService {
+    type HandshakeStream = TonicStream<HandshakeResponse>;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<Self::HandshakeStream>> {
+        self.0.handshake(request).await
+    }
+
+    type ListFlightsStream = TonicStream<FlightInfo>;
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> TonicResult<Response<Self::ListFlightsStream>> {
+        self.0.list_flights(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        self.0.get_flight_info(request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<SchemaResult>> {
+        self.0.get_schema(request).await
+    }
+
+    type DoGetStream = TonicStream<FlightData>;
+
+    async fn do_get(&self, request: Request<Ticket>) -> TonicResult<Response<Self::DoGetStream>> {
+        self.0.do_get(request).await
+    }
+
+    type DoPutStream = TonicStream<PutResult>;
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<Self::DoPutStream>> {
+        self.0.do_put(request).await
+    }
+
+    type DoExchangeStream = TonicStream<FlightData>;
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<Self::DoExchangeStream>> {
+        self.0.do_exchange(request).await
+    }
+
+    type DoActionStream = TonicStream<arrow_flight::Result>;
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        self.0 .0.handle_flight_action(request).await
+    }
+
+    type ListActionsStream = TonicStream<ActionType>;
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> TonicResult<Response<Self::ListActionsStream>> {
+        self.0.list_actions(request).await
+    }
+}
+
+impl RegionServer {
+    /// Takes the [RegionAction] in the body of the `request` and responds with a
+    /// single result carrying the JSON encoded [RegionActionOutput].
+    async fn handle_flight_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let action = request.into_inner();
+        ensure!(
+            action.r#type == RegionAction::FLIGHT_ACTION_TYPE,
+            servers_error::InvalidFlightActionSnafu {
+                reason: format!("unknown action type {}", action.r#type),
+            }
+        );
+        let region_action: RegionAction = serde_json::from_slice(&action.body).map_err(|e| {
+            servers_error::InvalidFlightActionSnafu {
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+
+        let output = self.handle_action(region_action).await?;
+        let body = serde_json::to_vec(&output).context(servers_error::ToJsonSnafu)?;
+        let stream: TonicStream<arrow_flight::Result> =
+            Box::pin(futures_util::stream::once(async move {
+                Ok(arrow_flight::Result { body: body.into() })
+            }));
+        Ok(Response::new(stream))
+    }
+}
+
 #[derive(Clone)]
 enum RegionEngineWithStatus {
     // An opening, or creating region.
@@ -633,9 +825,10 @@ impl RegionServerInner {
             return error::RegionNotReadySnafu { region_id }.fail();
         }
 
+        let time_travel = decode_time_travel(&plan).context(DecodeLogicalPlanSnafu)?;
         let table_provider = self
             .table_provider_factory
-            .create(region_id, region_status.into_engine())
+            .create(region_id, region_status.into_engine(), time_travel)
             .await?;
 
         let catalog_list = Arc::new(DummyCatalogList::with_table_provider(table_provider));
@@ -852,6 +1045,7 @@ impl TableProviderFactory for DummyTableProviderFactory {
         &self,
         region_id: RegionId,
         engine: RegionEngineRef,
+        time_travel: Option<TimeTravel>,
     ) -> Result<Arc<dyn TableProvider>> {
         let metadata =
             engine
@@ -865,17 +1059,23 @@ impl TableProviderFactory for DummyTableProviderFactory {
             region_id,
             engine,
             metadata,
-            scan_request: Default::default(),
+            scan_request: Arc::new(Mutex::new(ScanRequest {
+                time_travel,
+                ..Default::default()
+            })),
         }))
     }
 }
 
 #[async_trait]
 pub trait TableProviderFactory: Send + Sync {
+    /// Creates a table provider of the region, which reads the historical state
+    /// `time_travel` if it is set.
     async fn create(
         &self,
         region_id: RegionId,
         engine: RegionEngineRef,
+        time_travel: Option<TimeTravel>,
     ) -> Result<Arc<dyn TableProvider>>;
 }
 
@@ -1030,6 +1230,27 @@ mod tests {
         assert_eq!(err.status_code(), StatusCode::RegionNotReady);
     }
 
+    #[tokio::test]
+    async fn test_region_action() {
+        common_telemetry::init_default_ut_logging();
+
+        let mut mock_region_server = mock_region_server();
+        let (engine, _receiver) = MockRegionEngine::new();
+        mock_region_server.register_engine(engine.clone());
+
+        let region_id = RegionId::new(1, 1);
+        let action = RegionAction::ListSnapshots { region_id };
+        let err = mock_region_server
+            .handle_action(action.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::RegionNotFound);
+
+        mock_region_server.register_test_region(region_id, engine);
+        let err = mock_region_server.handle_action(action).await.unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::Unsupported);
+    }
+
     #[tokio::test]
     async fn test_region_request_failed() {
         common_telemetry::init_default_ut_logging();
@@ -15,6 +15,8 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use arrow_flight::flight_service_server::FlightServiceServer;
+use servers::add_service;
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::{GrpcServer, GrpcServerConfig};
 use servers::http::HttpServerBuilder;
@@ -24,7 +26,7 @@ use snafu::ResultExt;
 
 use crate::config::DatanodeOptions;
 use crate::error::{ParseAddrSnafu, Result};
-use crate::region_server::RegionServer;
+use crate::region_server::{RegionFlightService, RegionServer};
 
 pub struct DatanodeServiceBuilder<'a> {
     opts: &'a DatanodeOptions,
@@ -96,8 +98,12 @@ impl<'a> DatanodeServiceBuilder<'a> {
             max_send_message_size: opts.rpc_max_send_message_size.as_bytes() as usize,
         };
 
-        GrpcServerBuilder::new(config, region_server.runtime())
-            .flight_handler(Arc::new(region_server.clone()))
-            .region_server_handler(Arc::new(region_server.clone()))
+        let mut builder = GrpcServerBuilder::new(config, region_server.runtime())
+            .region_server_handler(Arc::new(region_server.clone()));
+        add_service!(
+            builder,
+            FlightServiceServer::new(RegionFlightService::new(region_server.clone()))
+        );
+        builder
     }
 }
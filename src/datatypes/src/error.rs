@@ -132,6 +132,21 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid JSON: {}", value))]
+    InvalidJson {
+        value: String,
+        #[snafu(source)]
+        error: jsonb::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to deserialize JSONB"))]
+    DeserializeJsonb {
+        #[snafu(source)]
+        error: jsonb::Error,
+        location: Location,
+    },
+
     #[snafu(display("Value exceeds the precision {} bound", precision))]
     ValueExceedsPrecision {
         precision: u8,
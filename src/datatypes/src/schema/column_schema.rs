@@ -22,6 +22,7 @@ use snafu::{ensure, ResultExt};
 use crate::data_type::{ConcreteDataType, DataType};
 use crate::error::{self, Error, Result};
 use crate::schema::constraint::ColumnDefaultConstraint;
+use crate::types::{JsonType, JSON_TYPE_NAME};
 use crate::value::Value;
 use crate::vectors::VectorRef;
 
@@ -32,6 +33,9 @@ pub const TIME_INDEX_KEY: &str = "greptime:time_index";
 pub const COMMENT_KEY: &str = "greptime:storage:comment";
 /// Key used to store default constraint in arrow field's metadata.
 const DEFAULT_CONSTRAINT_KEY: &str = "greptime:default_constraint";
+/// Key used to store the logical data type whose arrow type is shared with other types,
+/// such as JSON, in arrow field's metadata.
+pub const TYPE_KEY: &str = "greptime:type";
 
 /// Schema of a column, used as an immutable struct.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -223,8 +227,13 @@ impl TryFrom<&Field> for ColumnSchema {
     type Error = Error;
 
     fn try_from(field: &Field) -> Result<ColumnSchema> {
-        let data_type = ConcreteDataType::try_from(field.data_type())?;
+        let mut data_type = ConcreteDataType::try_from(field.data_type())?;
         let mut metadata = field.metadata().clone();
+        if let Some(type_name) = metadata.remove(TYPE_KEY) {
+            if type_name == JSON_TYPE_NAME && data_type == ConcreteDataType::binary_datatype() {
+                data_type = ConcreteDataType::Json(JsonType);
+            }
+        }
         let default_constraint = match metadata.remove(DEFAULT_CONSTRAINT_KEY) {
             Some(json) => {
                 Some(serde_json::from_str(&json).context(error::DeserializeSnafu { json })?)
@@ -263,6 +272,9 @@ impl TryFrom<&ColumnSchema> for Field {
                 }
             );
         }
+        if column_schema.data_type.is_json() {
+            let _ = metadata.insert(TYPE_KEY.to_string(), JSON_TYPE_NAME.to_string());
+        }
 
         Ok(Field::new(
             &column_schema.name,
@@ -452,4 +464,16 @@ mod tests {
         assert_eq!(formatted_int8, "test_column_1 Int8 null");
         assert_eq!(formatted_int32, "test_column_2 Int32 not null");
     }
+
+    #[test]
+    fn test_json_column_schema() {
+        let column_schema = ColumnSchema::new("json", ConcreteDataType::json_datatype(), true);
+        let field = Field::try_from(&column_schema).unwrap();
+        assert_eq!(ArrowDataType::LargeBinary, *field.data_type());
+        assert_eq!(JSON_TYPE_NAME, field.metadata().get(TYPE_KEY).unwrap());
+
+        let new_column_schema = ColumnSchema::try_from(&field).unwrap();
+        assert_eq!(column_schema, new_column_schema);
+        assert!(new_column_schema.metadata().is_empty());
+    }
 }
@@ -113,6 +113,7 @@ pub fn can_cast_type(src_value: &Value, dest_type: &ConcreteDataType) -> bool {
         ) => true,
 
         (String(_), Binary(_)) => true,
+        (String(_) | Binary(_), Json(_)) => true,
 
         // temporal types cast
         // Date type
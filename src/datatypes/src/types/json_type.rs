@@ -0,0 +1,130 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::DataType as ArrowDataType;
+use common_base::bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::data_type::{DataType, DataTypeRef};
+use crate::error::{DeserializeJsonbSnafu, InvalidJsonSnafu, Result};
+use crate::scalars::ScalarVectorBuilder;
+use crate::type_id::LogicalTypeId;
+use crate::value::Value;
+use crate::vectors::{BinaryVectorBuilder, MutableVector};
+
+pub const JSON_TYPE_NAME: &str = "Json";
+
+/// JSON type, values are stored in the binary JSONB format.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct JsonType;
+
+impl JsonType {
+    pub fn arc() -> DataTypeRef {
+        Arc::new(Self)
+    }
+}
+
+impl DataType for JsonType {
+    fn name(&self) -> String {
+        JSON_TYPE_NAME.to_string()
+    }
+
+    fn logical_type_id(&self) -> LogicalTypeId {
+        LogicalTypeId::Json
+    }
+
+    fn default_value(&self) -> Value {
+        Bytes::default().into()
+    }
+
+    fn as_arrow_type(&self) -> ArrowDataType {
+        ArrowDataType::LargeBinary
+    }
+
+    fn create_mutable_vector(&self, capacity: usize) -> Box<dyn MutableVector> {
+        Box::new(BinaryVectorBuilder::with_capacity(capacity))
+    }
+
+    fn try_cast(&self, from: Value) -> Option<Value> {
+        match from {
+            Value::Binary(v) => validate_jsonb(&v).ok().map(|_| Value::Binary(v)),
+            Value::String(v) => parse_string_to_jsonb(v.as_utf8())
+                .ok()
+                .map(|v| Value::Binary(v.into())),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a JSON text into the JSONB format.
+pub fn parse_string_to_jsonb(s: &str) -> Result<Vec<u8>> {
+    jsonb::parse_value(s.as_bytes())
+        .map(|json| json.to_vec())
+        .context(InvalidJsonSnafu { value: s })
+}
+
+/// Checks whether the bytes are a valid value in the JSONB format.
+pub fn validate_jsonb(bytes: &[u8]) -> Result<()> {
+    jsonb::from_slice(bytes)
+        .map(|_| ())
+        .context(DeserializeJsonbSnafu)
+}
+
+/// Converts a value in the JSONB format to a JSON text.
+pub fn jsonb_to_string(bytes: &[u8]) -> Result<String> {
+    jsonb::from_slice(bytes)
+        .map(|json| json.to_string())
+        .context(DeserializeJsonbSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_type() {
+        let t = JsonType;
+        assert_eq!("Json", t.name());
+        assert_eq!(LogicalTypeId::Json, t.logical_type_id());
+        assert_eq!(ArrowDataType::LargeBinary, t.as_arrow_type());
+    }
+
+    #[test]
+    fn test_jsonb_conversion() {
+        let json = r#"{"a":1,"b":[true,null,"c"]}"#;
+        let jsonb = parse_string_to_jsonb(json).unwrap();
+        assert_eq!(json, jsonb_to_string(&jsonb).unwrap());
+        assert!(parse_string_to_jsonb("{\"a\":").is_err());
+
+        let t = JsonType;
+        assert_eq!(
+            Some(Value::Binary(jsonb.clone().into())),
+            t.try_cast(Value::from(json))
+        );
+        assert_eq!(None, t.try_cast(Value::from("not json")));
+        assert_eq!(None, t.try_cast(Value::from(1)));
+
+        assert!(validate_jsonb(&jsonb).is_ok());
+        assert_eq!(
+            Some(Value::Binary(jsonb.clone().into())),
+            t.try_cast(Value::Binary(jsonb.into()))
+        );
+        let invalid = b"not jsonb".to_vec();
+        assert!(validate_jsonb(&invalid).is_err());
+        assert_eq!(None, t.try_cast(Value::Binary(invalid.into())));
+    }
+}
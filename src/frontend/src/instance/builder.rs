@@ -93,6 +93,7 @@ impl FrontendBuilder {
             kv_backend.clone(),
             self.cache_invalidator
                 .unwrap_or_else(|| Arc::new(DummyCacheInvalidator)),
+            Some(datanode_manager.clone()),
         );
 
         let partition_manager = Arc::new(PartitionRuleManager::new(kv_backend.clone()));
@@ -18,9 +18,13 @@ use api::v1::query_request::Query;
 use api::v1::{DeleteRequests, InsertRequests, RowDeleteRequests, RowInsertRequests};
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
+use common_error::ext::BoxedError;
 use common_meta::table_name::TableName;
 use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datatypes::schema::SchemaRef;
 use query::parser::PromQuery;
+use servers::error::{ExecuteGrpcRequestSnafu, Result as ServerResult};
 use servers::interceptor::{GrpcQueryInterceptor, GrpcQueryInterceptorRef};
 use servers::query_handler::grpc::GrpcQueryHandler;
 use servers::query_handler::sql::SqlQueryHandler;
@@ -28,7 +32,7 @@ use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 
 use crate::error::{
-    Error, IncompleteGrpcRequestSnafu, NotSupportedSnafu, PermissionSnafu, Result,
+    CatalogSnafu, Error, IncompleteGrpcRequestSnafu, NotSupportedSnafu, PermissionSnafu, Result,
     TableOperationSnafu,
 };
 use crate::instance::{attach_timer, Instance};
@@ -143,6 +147,68 @@ impl GrpcQueryHandler for Instance {
         let output = interceptor.post_execute(output, ctx)?;
         Ok(output)
     }
+
+    async fn put_record_batch(
+        &self,
+        table: &TableName,
+        record_batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> ServerResult<usize> {
+        self.handle_put_record_batch(table, record_batch, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteGrpcRequestSnafu)
+    }
+
+    async fn table_schema(
+        &self,
+        table: &TableName,
+        ctx: QueryContextRef,
+    ) -> ServerResult<Option<SchemaRef>> {
+        self.handle_table_schema(table, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteGrpcRequestSnafu)
+    }
+}
+
+impl Instance {
+    async fn handle_put_record_batch(
+        &self,
+        table: &TableName,
+        record_batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> Result<usize> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(ctx.current_user(), PermissionReq::BulkInsert)
+            .context(PermissionSnafu)?;
+
+        self.inserter
+            .handle_record_batch_insert(table, &record_batch, ctx)
+            .await
+            .context(TableOperationSnafu)
+    }
+
+    async fn handle_table_schema(
+        &self,
+        table: &TableName,
+        ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(ctx.current_user(), PermissionReq::TableSchema)
+            .context(PermissionSnafu)?;
+
+        let table = self
+            .catalog_manager
+            .table(&table.catalog_name, &table.schema_name, &table.table_name)
+            .await
+            .context(CatalogSnafu)?;
+        Ok(table.map(|table| table.schema()))
+    }
 }
 
 fn fill_catalog_and_schema_from_context(ddl_expr: &mut DdlExpr, ctx: &QueryContextRef) {
@@ -200,6 +266,17 @@ impl Instance {
             .context(TableOperationSnafu)
     }
 
+    pub async fn handle_log_inserts(
+        &self,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.inserter
+            .handle_log_inserts(requests, ctx, self.statement_executor.as_ref())
+            .await
+            .context(TableOperationSnafu)
+    }
+
     pub async fn handle_metric_row_inserts(
         &self,
         requests: RowInsertRequests,
@@ -15,6 +15,9 @@
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use common_error::ext::BoxedError;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
 };
@@ -29,7 +32,7 @@ use session::context::QueryContextRef;
 use snafu::ResultExt;
 
 use crate::instance::Instance;
-use crate::metrics::{OTLP_METRICS_ROWS, OTLP_TRACES_ROWS};
+use crate::metrics::{OTLP_LOGS_ROWS, OTLP_METRICS_ROWS, OTLP_TRACES_ROWS};
 
 #[async_trait]
 impl OpenTelemetryProtocolHandler for Instance {
@@ -94,4 +97,32 @@ impl OpenTelemetryProtocolHandler for Instance {
         };
         Ok(resp)
     }
+
+    async fn logs(
+        &self,
+        request: ExportLogsServiceRequest,
+        table_name: String,
+        ctx: QueryContextRef,
+    ) -> ServerResult<ExportLogsServiceResponse> {
+        self.plugins
+            .get::<PermissionCheckerRef>()
+            .as_ref()
+            .check_permission(ctx.current_user(), PermissionReq::Otlp)
+            .context(AuthSnafu)?;
+
+        let (requests, rows) = otlp::logs::to_grpc_insert_requests(request, table_name)?;
+
+        let _ = self
+            .handle_log_inserts(requests, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ExecuteGrpcQuerySnafu)?;
+
+        OTLP_LOGS_ROWS.inc_by(rows as u64);
+
+        let resp = ExportLogsServiceResponse {
+            partial_success: None,
+        };
+        Ok(resp)
+    }
 }
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use api::prom_store::remote::read_request::ResponseType;
 use api::prom_store::remote::{Query, QueryResult, ReadRequest, ReadResponse, WriteRequest};
+use async_stream::try_stream;
 use async_trait::async_trait;
 use auth::{PermissionChecker, PermissionCheckerRef, PermissionReq};
 use common_catalog::format_full_table_name;
@@ -24,14 +25,15 @@ use common_query::prelude::GREPTIME_PHYSICAL_TABLE;
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use common_telemetry::logging;
+use futures::{Stream, StreamExt};
 use operator::insert::InserterRef;
 use operator::statement::StatementExecutor;
 use prost::Message;
 use servers::error::{self, AuthSnafu, Result as ServerResult};
 use servers::http::prom_store::PHYSICAL_TABLE_PARAM;
-use servers::prom_store::{self, Metrics};
+use servers::prom_store::{self, ChunkedReadResponseEncoder, Metrics};
 use servers::query_handler::{
-    PromStoreProtocolHandler, PromStoreProtocolHandlerRef, PromStoreResponse,
+    PromStoreProtocolHandler, PromStoreProtocolHandlerRef, PromStoreResponse, PromStoreResponseBody,
 };
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
@@ -44,11 +46,11 @@ use crate::instance::Instance;
 use crate::metrics::PROM_STORE_REMOTE_WRITE_SAMPLES;
 
 const SAMPLES_RESPONSE_TYPE: i32 = ResponseType::Samples as i32;
+const STREAMED_XOR_CHUNKS_RESPONSE_TYPE: i32 = ResponseType::StreamedXorChunks as i32;
 
 #[inline]
 fn is_supported(response_type: i32) -> bool {
-    // Only supports samples response right now
-    response_type == SAMPLES_RESPONSE_TYPE
+    response_type == SAMPLES_RESPONSE_TYPE || response_type == STREAMED_XOR_CHUNKS_RESPONSE_TYPE
 }
 
 /// Negotiating the content type of the remote read response.
@@ -70,7 +72,7 @@ fn negotiate_response_type(accepted_response_types: &[i32]) -> ServerResult<Resp
             ),
         })?;
 
-    // It's safe to unwrap here, we known that it should be SAMPLES_RESPONSE_TYPE
+    // It's safe to unwrap here, we known that it is a supported response type
     Ok(ResponseType::try_from(*response_type).unwrap())
 }
 
@@ -86,6 +88,38 @@ async fn to_query_result(table_name: &str, output: Output) -> ServerResult<Query
     })
 }
 
+/// Encodes results of queries into frames of the `STREAMED_XOR_CHUNKS` response.
+///
+/// Queries are encoded one by one, and only one frame is buffered at a time, so
+/// the response isn't materialized in memory.
+fn to_chunked_frames(
+    results: Vec<(String, Output)>,
+) -> impl Stream<Item = ServerResult<Vec<u8>>> + Send {
+    try_stream! {
+        for (query_index, (table_name, output)) in results.into_iter().enumerate() {
+            let mut stream = match output {
+                Output::Stream(stream, _) => stream,
+                Output::RecordBatches(recordbatches) => recordbatches.as_stream(),
+                Output::AffectedRows(_) => unreachable!(),
+            };
+            let mut encoder = ChunkedReadResponseEncoder::new(
+                table_name,
+                query_index as i64,
+                prom_store::DEFAULT_MAX_BYTES_IN_FRAME,
+            );
+            while let Some(recordbatch) = stream.next().await {
+                let recordbatch = recordbatch.context(error::CollectRecordbatchSnafu)?;
+                for frame in encoder.encode(recordbatch)? {
+                    yield frame;
+                }
+            }
+            if let Some(frame) = encoder.finish() {
+                yield frame;
+            }
+        }
+    }
+}
+
 impl Instance {
     async fn handle_remote_query(
         &self,
@@ -94,6 +128,7 @@ impl Instance {
         schema_name: &str,
         table_name: &str,
         query: &Query,
+        sorted: bool,
     ) -> Result<Output> {
         let table = self
             .catalog_manager
@@ -111,8 +146,12 @@ impl Instance {
                 table_name: format_full_table_name(catalog_name, schema_name, table_name),
             })?;
 
-        let logical_plan =
-            prom_store::query_to_plan(dataframe, query).context(PromStoreRemoteQueryPlanSnafu)?;
+        let logical_plan = if sorted {
+            prom_store::query_to_sorted_plan(dataframe, query)
+        } else {
+            prom_store::query_to_plan(dataframe, query)
+        }
+        .context(PromStoreRemoteQueryPlanSnafu)?;
 
         logging::debug!(
             "Prometheus remote read, table: {}, logical plan: {}",
@@ -130,6 +169,7 @@ impl Instance {
         &self,
         ctx: QueryContextRef,
         queries: &[Query],
+        sorted: bool,
     ) -> ServerResult<Vec<(String, Output)>> {
         let mut results = Vec::with_capacity(queries.len());
 
@@ -140,7 +180,7 @@ impl Instance {
             let table_name = prom_store::table_name(query)?;
 
             let output = self
-                .handle_remote_query(&ctx, catalog_name, schema_name, &table_name, query)
+                .handle_remote_query(&ctx, catalog_name, schema_name, &table_name, query, sorted)
                 .await
                 .map_err(BoxedError::new)
                 .with_context(|_| error::ExecuteQuerySnafu {
@@ -204,7 +244,11 @@ impl PromStoreProtocolHandler for Instance {
         let response_type = negotiate_response_type(&request.accepted_response_types)?;
 
         // TODO(dennis): use read_hints to speedup query if possible
-        let results = self.handle_remote_queries(ctx, &request.queries).await?;
+        // Series must be sorted to be encoded into streamed chunks.
+        let sorted = response_type == ResponseType::StreamedXorChunks;
+        let results = self
+            .handle_remote_queries(ctx, &request.queries, sorted)
+            .await?;
 
         match response_type {
             ResponseType::Samples => {
@@ -220,14 +264,17 @@ impl PromStoreProtocolHandler for Instance {
                 // TODO(dennis): may consume too much memory, adds flow control
                 Ok(PromStoreResponse {
                     content_type: "application/x-protobuf".to_string(),
-                    content_encoding: "snappy".to_string(),
-                    body: prom_store::snappy_compress(&response.encode_to_vec())?,
+                    content_encoding: Some("snappy".to_string()),
+                    body: PromStoreResponseBody::Full(prom_store::snappy_compress(
+                        &response.encode_to_vec(),
+                    )?),
                 })
             }
-            ResponseType::StreamedXorChunks => error::NotSupportedSnafu {
-                feat: "streamed remote read",
-            }
-            .fail(),
+            ResponseType::StreamedXorChunks => Ok(PromStoreResponse {
+                content_type: prom_store::STREAMED_XOR_CHUNKS_CONTENT_TYPE.to_string(),
+                content_encoding: None,
+                body: PromStoreResponseBody::Stream(Box::pin(to_chunked_frames(results))),
+            }),
         }
     }
 
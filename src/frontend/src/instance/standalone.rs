@@ -27,6 +27,7 @@ use common_telemetry::tracing_context::{FutureExt, TracingContext};
 use datanode::region_server::RegionServer;
 use servers::grpc::region_server::RegionServerHandler;
 use snafu::{OptionExt, ResultExt};
+use store_api::region_request::{RegionAction, RegionActionOutput};
 
 use crate::error::{InvalidRegionRequestSnafu, InvokeRegionServerSnafu, Result};
 
@@ -96,4 +97,12 @@ impl Datanode for RegionInvoker {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn handle_action(&self, action: RegionAction) -> MetaResult<RegionActionOutput> {
+        self.region_server
+            .handle_action(action)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
 }
@@ -0,0 +1,155 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Bloom Filter Index Blob Format Specification
+//!
+//! A bloom filter index blob holds a bloom filter per segment of a single column:
+//!
+//! `filter₀ filter₁ ... filterₙ footer`
+//!
+//! - `filterᵢ`: Bits of the bloom filter of the i-th segment. All filters have
+//!   the same number of bits so they can be located by the segment id.
+//! - `footer`: `segment_row_count total_row_count num_bits num_hashes`, each of
+//!   them is a little-endian `u64`.
+//!
+//! Null values are not inserted into the filters.
+
+pub mod create;
+pub mod error;
+pub mod format;
+pub mod search;
+
+use std::hash::Hasher;
+
+use common_base::BitVec;
+use twox_hash::XxHash64;
+
+/// Size of the footer of a bloom filter index blob.
+const FOOTER_SIZE: u64 = 32;
+
+/// Default false positive probability of bloom filters.
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// A bloom filter that uses double hashing to derive the bit positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: BitVec,
+    num_hashes: u64,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter with `num_bits` bits and `num_hashes` hash functions.
+    /// `num_bits` should be a multiple of 8.
+    pub fn new(num_bits: u64, num_hashes: u64) -> Self {
+        Self {
+            bits: BitVec::repeat(false, num_bits as usize),
+            num_hashes,
+        }
+    }
+
+    /// Creates a filter from its bytes.
+    pub fn from_bytes(bytes: Vec<u8>, num_hashes: u64) -> Self {
+        Self {
+            bits: BitVec::from_vec(bytes),
+            num_hashes,
+        }
+    }
+
+    /// Returns the bytes of the filter.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bits.as_raw_slice()
+    }
+
+    /// Inserts a value into the filter.
+    pub fn insert(&mut self, value: &[u8]) {
+        let num_bits = self.bits.len() as u64;
+        for position in Self::positions(value, self.num_hashes, num_bits) {
+            self.bits.set(position, true);
+        }
+    }
+
+    /// Returns false if the value is definitely not in the filter.
+    pub fn contains(&self, value: &[u8]) -> bool {
+        let num_bits = self.bits.len() as u64;
+        Self::positions(value, self.num_hashes, num_bits).all(|position| self.bits[position])
+    }
+
+    /// Clears all bits of the filter.
+    pub fn clear(&mut self) {
+        self.bits.fill(false);
+    }
+
+    fn positions(value: &[u8], num_hashes: u64, num_bits: u64) -> impl Iterator<Item = usize> {
+        let h1 = hash_with_seed(value, 0);
+        let h2 = hash_with_seed(value, 1);
+        (0..num_hashes)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits.max(1)) as usize)
+    }
+}
+
+/// Returns the number of bits (a multiple of 8) and hash functions of a bloom filter
+/// holding `num_items` items with the false positive probability `fpp`.
+pub fn optimal_params(num_items: usize, fpp: f64) -> (u64, u64) {
+    let num_items = num_items.max(1) as f64;
+    let ln2 = std::f64::consts::LN_2;
+    let num_bits = (-num_items * fpp.ln() / (ln2 * ln2)).ceil().max(8.0) as u64;
+    let num_bits = num_bits.div_ceil(8) * 8;
+    let num_hashes = ((num_bits as f64 / num_items) * ln2).round().max(1.0) as u64;
+    (num_bits, num_hashes)
+}
+
+fn hash_with_seed(value: &[u8], seed: u64) -> u64 {
+    let mut hasher = XxHash64::with_seed(seed);
+    hasher.write(value);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimal_params() {
+        let (num_bits, num_hashes) = optimal_params(1024, 0.01);
+        assert_eq!(0, num_bits % 8);
+        assert!((9800..9850).contains(&num_bits), "{num_bits}");
+        assert_eq!(7, num_hashes);
+
+        let (num_bits, num_hashes) = optimal_params(0, 0.01);
+        assert_eq!(16, num_bits);
+        assert!(num_hashes >= 1);
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let (num_bits, num_hashes) = optimal_params(100, 0.01);
+        let mut filter = BloomFilter::new(num_bits, num_hashes);
+        for i in 0..100u32 {
+            filter.insert(&i.to_le_bytes());
+        }
+        for i in 0..100u32 {
+            assert!(filter.contains(&i.to_le_bytes()));
+        }
+        let false_positives = (100..10100u32)
+            .filter(|i| filter.contains(&i.to_le_bytes()))
+            .count();
+        assert!(false_positives < 300, "{false_positives}");
+
+        let restored = BloomFilter::from_bytes(filter.as_bytes().to_vec(), num_hashes);
+        assert_eq!(filter, restored);
+
+        filter.clear();
+        assert!(!filter.contains(&0u32.to_le_bytes()));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::num::NonZeroUsize;
+
+use futures::{AsyncWrite, AsyncWriteExt};
+use snafu::ResultExt;
+
+use crate::bloom_filter::error::{CloseSnafu, FlushSnafu, Result, WriteSnafu};
+use crate::bloom_filter::{optimal_params, BloomFilter, FOOTER_SIZE};
+
+/// Creates bloom filters of a column segment by segment and writes them as a blob.
+pub struct BloomFilterCreator {
+    /// Number of rows in a segment.
+    segment_row_count: NonZeroUsize,
+    /// Number of rows pushed.
+    row_count: usize,
+    /// Number of bits of each filter.
+    num_bits: u64,
+    /// Number of hash functions of each filter.
+    num_hashes: u64,
+    /// Filter of the current segment.
+    current_filter: BloomFilter,
+    /// Bytes of filters of finished segments.
+    finished_filters: Vec<u8>,
+}
+
+impl BloomFilterCreator {
+    /// Creates a new creator whose filters have the false positive probability `fpp`
+    /// when all rows of a segment are distinct.
+    pub fn new(segment_row_count: NonZeroUsize, fpp: f64) -> Self {
+        let (num_bits, num_hashes) = optimal_params(segment_row_count.get(), fpp);
+        Self {
+            segment_row_count,
+            row_count: 0,
+            num_bits,
+            num_hashes,
+            current_filter: BloomFilter::new(num_bits, num_hashes),
+            finished_filters: Vec::new(),
+        }
+    }
+
+    /// Pushes the encoded value of the next row, `None` for null.
+    pub fn push_value(&mut self, value: Option<&[u8]>) {
+        if self.row_count > 0 && self.row_count % self.segment_row_count.get() == 0 {
+            self.finish_segment();
+        }
+        self.row_count += 1;
+
+        if let Some(value) = value {
+            self.current_filter.insert(value);
+        }
+    }
+
+    /// Returns the number of rows pushed.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Returns the estimated memory usage in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.finished_filters.len() + self.current_filter.as_bytes().len()
+    }
+
+    /// Writes the index to `writer` and closes it.
+    pub async fn finish(&mut self, writer: &mut (impl AsyncWrite + Unpin + Send)) -> Result<()> {
+        // The last segment is finished lazily.
+        if self.row_count > 0 {
+            self.finish_segment();
+        }
+        let filters = std::mem::take(&mut self.finished_filters);
+        writer.write_all(&filters).await.context(WriteSnafu)?;
+
+        let mut footer = Vec::with_capacity(FOOTER_SIZE as usize);
+        footer.extend_from_slice(&(self.segment_row_count.get() as u64).to_le_bytes());
+        footer.extend_from_slice(&(self.row_count as u64).to_le_bytes());
+        footer.extend_from_slice(&self.num_bits.to_le_bytes());
+        footer.extend_from_slice(&self.num_hashes.to_le_bytes());
+        writer.write_all(&footer).await.context(WriteSnafu)?;
+
+        writer.flush().await.context(FlushSnafu)?;
+        writer.close().await.context(CloseSnafu)?;
+        Ok(())
+    }
+
+    fn finish_segment(&mut self) {
+        self.finished_filters
+            .extend_from_slice(self.current_filter.as_bytes());
+        self.current_filter.clear();
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::SeekFrom;
+
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use snafu::{ensure, ResultExt};
+
+use crate::bloom_filter::error::{
+    ReadSnafu, Result, SeekSnafu, UnexpectedBlobSizeSnafu, UnexpectedZeroNumBitsSnafu,
+    UnexpectedZeroSegmentRowCountSnafu,
+};
+use crate::bloom_filter::{BloomFilter, FOOTER_SIZE};
+
+/// Metadata of a bloom filter index blob, decoded from the footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BloomFilterIndexMeta {
+    pub segment_row_count: u64,
+    pub total_row_count: u64,
+    pub num_bits: u64,
+    pub num_hashes: u64,
+}
+
+impl BloomFilterIndexMeta {
+    /// Returns the number of segments in the index.
+    pub fn num_segments(&self) -> usize {
+        self.total_row_count.div_ceil(self.segment_row_count) as usize
+    }
+
+    /// Returns the size of a filter in bytes.
+    pub fn filter_size(&self) -> u64 {
+        self.num_bits / 8
+    }
+}
+
+/// Reads a bloom filter index blob.
+pub struct BloomFilterIndexBlobReader<R> {
+    source: R,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> BloomFilterIndexBlobReader<R> {
+    pub fn new(source: R) -> Self {
+        Self { source }
+    }
+
+    /// Reads the metadata from the footer.
+    pub async fn metadata(&mut self) -> Result<BloomFilterIndexMeta> {
+        let blob_size = self
+            .source
+            .seek(SeekFrom::End(0))
+            .await
+            .context(SeekSnafu)?;
+        ensure!(
+            blob_size >= FOOTER_SIZE,
+            UnexpectedBlobSizeSnafu {
+                min_blob_size: FOOTER_SIZE,
+                actual_blob_size: blob_size,
+            }
+        );
+
+        let mut footer = [0u8; FOOTER_SIZE as usize];
+        self.read_at(blob_size - FOOTER_SIZE, &mut footer).await?;
+        let read_u64 = |i: usize| {
+            // Safety: the footer has 4 u64.
+            u64::from_le_bytes(footer[i * 8..(i + 1) * 8].try_into().unwrap())
+        };
+        let meta = BloomFilterIndexMeta {
+            segment_row_count: read_u64(0),
+            total_row_count: read_u64(1),
+            num_bits: read_u64(2),
+            num_hashes: read_u64(3),
+        };
+        ensure!(
+            meta.segment_row_count > 0,
+            UnexpectedZeroSegmentRowCountSnafu
+        );
+        ensure!(meta.num_bits > 0, UnexpectedZeroNumBitsSnafu);
+
+        let min_blob_size = meta.num_segments() as u64 * meta.filter_size() + FOOTER_SIZE;
+        ensure!(
+            blob_size >= min_blob_size,
+            UnexpectedBlobSizeSnafu {
+                min_blob_size,
+                actual_blob_size: blob_size,
+            }
+        );
+        Ok(meta)
+    }
+
+    /// Reads filters of all segments.
+    pub async fn filters(&mut self, meta: &BloomFilterIndexMeta) -> Result<Vec<BloomFilter>> {
+        let filter_size = meta.filter_size() as usize;
+        let mut buf = vec![0u8; meta.num_segments() * filter_size];
+        self.read_at(0, &mut buf).await?;
+        Ok(buf
+            .chunks_exact(filter_size)
+            .map(|bytes| BloomFilter::from_bytes(bytes.to_vec(), meta.num_hashes))
+            .collect())
+    }
+
+    async fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.source
+            .seek(SeekFrom::Start(offset))
+            .await
+            .context(SeekSnafu)?;
+        self.source.read_exact(buf).await.context(ReadSnafu)
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::BitVec;
+use futures::{AsyncRead, AsyncSeek};
+
+use crate::bloom_filter::error::Result;
+use crate::bloom_filter::format::BloomFilterIndexBlobReader;
+
+/// Output of a bloom filter index search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilterSearchOutput {
+    /// Segments that may contain rows matching the predicates.
+    pub matched_segment_ids: BitVec,
+    /// Number of rows indexed.
+    pub total_row_count: usize,
+    /// Number of rows in a segment.
+    pub segment_row_count: usize,
+}
+
+/// Searches segments that may contain any value of each list in `predicates`.
+///
+/// Each list is the encoded values of an `IN` predicate, and lists are ANDed.
+pub async fn search<R: AsyncRead + AsyncSeek + Unpin + Send>(
+    reader: &mut BloomFilterIndexBlobReader<R>,
+    predicates: &[Vec<Vec<u8>>],
+) -> Result<BloomFilterSearchOutput> {
+    let meta = reader.metadata().await?;
+    let num_segments = meta.num_segments();
+    let mut matched_segment_ids = BitVec::repeat(true, num_segments);
+
+    if !predicates.is_empty() {
+        let filters = reader.filters(&meta).await?;
+        for (segment_id, filter) in filters.iter().enumerate() {
+            let matched = predicates
+                .iter()
+                .all(|values| values.iter().any(|value| filter.contains(value)));
+            matched_segment_ids.set(segment_id, matched);
+        }
+    }
+
+    Ok(BloomFilterSearchOutput {
+        matched_segment_ids,
+        total_row_count: meta.total_row_count as usize,
+        segment_row_count: meta.segment_row_count as usize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use futures::io::Cursor;
+
+    use super::*;
+    use crate::bloom_filter::create::BloomFilterCreator;
+    use crate::bloom_filter::DEFAULT_FALSE_POSITIVE_RATE;
+
+    async fn build_index(values: &[Option<&str>], segment_row_count: usize) -> Vec<u8> {
+        let mut creator = BloomFilterCreator::new(
+            NonZeroUsize::new(segment_row_count).unwrap(),
+            DEFAULT_FALSE_POSITIVE_RATE,
+        );
+        for value in values {
+            creator.push_value(value.map(str::as_bytes));
+        }
+        let mut blob = Cursor::new(Vec::new());
+        creator.finish(&mut blob).await.unwrap();
+        blob.into_inner()
+    }
+
+    async fn search_segments(blob: &[u8], predicates: &[&[&str]]) -> Vec<usize> {
+        let predicates: Vec<Vec<Vec<u8>>> = predicates
+            .iter()
+            .map(|values| values.iter().map(|v| v.as_bytes().to_vec()).collect())
+            .collect();
+        let mut reader = BloomFilterIndexBlobReader::new(Cursor::new(blob));
+        let output = search(&mut reader, &predicates).await.unwrap();
+        output.matched_segment_ids.iter_ones().collect()
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let blob = build_index(
+            &[
+                Some("trace-1"),
+                Some("trace-2"),
+                None,
+                Some("trace-3"),
+                Some("trace-1"),
+                Some("trace-4"),
+                Some("trace-5"),
+            ],
+            2,
+        )
+        .await;
+
+        assert_eq!(vec![0, 1, 2, 3], search_segments(&blob, &[]).await);
+        assert_eq!(vec![0, 2], search_segments(&blob, &[&["trace-1"]]).await);
+        assert_eq!(vec![1], search_segments(&blob, &[&["trace-3"]]).await);
+        assert_eq!(
+            vec![1, 3],
+            search_segments(&blob, &[&["trace-3", "trace-5"]]).await
+        );
+        assert_eq!(
+            vec![2],
+            search_segments(&blob, &[&["trace-1"], &["trace-4"]]).await
+        );
+        assert!(search_segments(&blob, &[&["trace-6"]]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_metadata() {
+        let blob = build_index(&[Some("a"), Some("b"), Some("c")], 2).await;
+        let mut reader = BloomFilterIndexBlobReader::new(Cursor::new(&blob));
+        let output = search(&mut reader, &[vec![b"c".to_vec()]]).await.unwrap();
+        assert_eq!(3, output.total_row_count);
+        assert_eq!(2, output.segment_row_count);
+        assert_eq!(
+            vec![1],
+            output.matched_segment_ids.iter_ones().collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_invalid_blob() {
+        let mut reader = BloomFilterIndexBlobReader::new(Cursor::new(vec![0u8; 8]));
+        assert!(search(&mut reader, &[]).await.is_err());
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Full-Text Index Blob Format Specification
+//!
+//! A full-text index blob indexes the tokens of a single string column:
+//!
+//! `bitmap₀ bitmap₁ ... bitmapₙ fst footer`
+//!
+//! - `bitmapᵢ`: Bitset of segments containing the i-th token.
+//! - `fst`: Finite State Transducer mapping each token to the offset and size of its
+//!   bitmap, packed as two `u32` into a `u64`.
+//! - `footer`: `segment_row_count total_row_count fst_offset fst_size`, each of them
+//!   is a little-endian `u64`.
+//!
+//! Tokens are produced by the [`tokenizer`], so queries must be tokenized in
+//! the same way.
+
+pub mod create;
+pub mod error;
+pub mod format;
+pub mod search;
+pub mod tokenizer;
+
+/// Size of the footer of a full-text index blob.
+const FOOTER_SIZE: u64 = 32;
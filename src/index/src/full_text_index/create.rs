@@ -0,0 +1,132 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+
+use common_base::BitVec;
+use fst::MapBuilder;
+use futures::{AsyncWrite, AsyncWriteExt};
+use snafu::{ensure, ResultExt};
+
+use crate::full_text_index::error::{
+    AbortedSnafu, CloseSnafu, FlushSnafu, FstInsertSnafu, Result, WriteSnafu,
+};
+use crate::full_text_index::tokenizer::tokenize;
+
+/// Creates a full-text index of a column in memory and writes it as a blob.
+pub struct FullTextIndexCreator {
+    /// Number of rows in a segment.
+    segment_row_count: NonZeroUsize,
+    /// Number of rows pushed.
+    row_count: usize,
+    /// Segments containing each token.
+    tokens: BTreeMap<String, BitVec>,
+    /// Estimated memory usage of `tokens`.
+    memory_usage: usize,
+    /// Whether the creator stops indexing.
+    aborted: bool,
+}
+
+impl FullTextIndexCreator {
+    pub fn new(segment_row_count: NonZeroUsize) -> Self {
+        Self {
+            segment_row_count,
+            row_count: 0,
+            tokens: BTreeMap::new(),
+            memory_usage: 0,
+            aborted: false,
+        }
+    }
+
+    /// Pushes the text of the next row, `None` for null.
+    pub fn push_text(&mut self, text: Option<&str>) {
+        let segment = self.row_count / self.segment_row_count.get();
+        self.row_count += 1;
+        let Some(text) = text.filter(|_| !self.aborted) else {
+            return;
+        };
+
+        for token in tokenize(text) {
+            let token_len = token.len();
+            let bitmap = self.tokens.entry(token).or_insert_with(|| {
+                self.memory_usage += token_len;
+                BitVec::new()
+            });
+            if bitmap.len() <= segment {
+                self.memory_usage += (segment + 1 - bitmap.len()).div_ceil(8);
+                bitmap.resize(segment + 1, false);
+            }
+            bitmap.set(segment, true);
+        }
+    }
+
+    /// Returns the number of rows pushed.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Returns the estimated memory usage in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Stops indexing and releases tokens in memory, e.g. when the index uses
+    /// too much memory. An aborted creator never writes the index.
+    pub fn abort(&mut self) {
+        self.tokens.clear();
+        self.memory_usage = 0;
+        self.aborted = true;
+    }
+
+    /// Returns whether the creator is aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// Writes the index to `writer` and closes it.
+    pub async fn finish(&mut self, writer: &mut (impl AsyncWrite + Unpin + Send)) -> Result<()> {
+        ensure!(!self.aborted, AbortedSnafu);
+
+        let num_segments = self.row_count.div_ceil(self.segment_row_count.get());
+        let mut written_size = 0u64;
+        let mut fst_builder = MapBuilder::memory();
+        for (token, mut bitmap) in std::mem::take(&mut self.tokens) {
+            bitmap.resize(num_segments, false);
+            let bytes = bitmap.into_vec();
+            writer.write_all(&bytes).await.context(WriteSnafu)?;
+
+            let value = bytemuck::cast::<[u32; 2], u64>([written_size as u32, bytes.len() as u32]);
+            fst_builder
+                .insert(token.as_bytes(), value)
+                .context(FstInsertSnafu)?;
+            written_size += bytes.len() as u64;
+        }
+        self.memory_usage = 0;
+
+        let fst_bytes = fst_builder.into_inner().context(FstInsertSnafu)?;
+        writer.write_all(&fst_bytes).await.context(WriteSnafu)?;
+
+        let mut footer = Vec::with_capacity(super::FOOTER_SIZE as usize);
+        footer.extend_from_slice(&(self.segment_row_count.get() as u64).to_le_bytes());
+        footer.extend_from_slice(&(self.row_count as u64).to_le_bytes());
+        footer.extend_from_slice(&written_size.to_le_bytes());
+        footer.extend_from_slice(&(fst_bytes.len() as u64).to_le_bytes());
+        writer.write_all(&footer).await.context(WriteSnafu)?;
+
+        writer.flush().await.context(FlushSnafu)?;
+        writer.close().await.context(CloseSnafu)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::SeekFrom;
+
+use common_base::BitVec;
+use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use snafu::{ensure, ResultExt};
+
+use crate::full_text_index::error::{
+    DecodeFstSnafu, ReadSnafu, Result, SeekSnafu, UnexpectedBlobSizeSnafu,
+    UnexpectedZeroSegmentRowCountSnafu,
+};
+use crate::full_text_index::FOOTER_SIZE;
+use crate::inverted_index::FstMap;
+
+/// Max gap in bytes between bitmaps to read them in one request.
+const MAX_COALESCE_GAP: u64 = 4096;
+
+/// Metadata of a full-text index blob, decoded from the footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullTextIndexMeta {
+    pub segment_row_count: u64,
+    pub total_row_count: u64,
+    pub fst_offset: u64,
+    pub fst_size: u64,
+}
+
+impl FullTextIndexMeta {
+    /// Returns the number of segments in the index.
+    pub fn num_segments(&self) -> usize {
+        self.total_row_count.div_ceil(self.segment_row_count) as usize
+    }
+}
+
+/// Reads a full-text index blob.
+pub struct FullTextIndexBlobReader<R> {
+    source: R,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin + Send> FullTextIndexBlobReader<R> {
+    pub fn new(source: R) -> Self {
+        Self { source }
+    }
+
+    /// Reads the metadata from the footer.
+    pub async fn metadata(&mut self) -> Result<FullTextIndexMeta> {
+        let blob_size = self
+            .source
+            .seek(SeekFrom::End(0))
+            .await
+            .context(SeekSnafu)?;
+        ensure!(
+            blob_size >= FOOTER_SIZE,
+            UnexpectedBlobSizeSnafu {
+                min_blob_size: FOOTER_SIZE,
+                actual_blob_size: blob_size,
+            }
+        );
+
+        let mut footer = [0u8; FOOTER_SIZE as usize];
+        self.read_at(blob_size - FOOTER_SIZE, &mut footer).await?;
+        let read_u64 = |i: usize| {
+            // Safety: the footer has 4 u64.
+            u64::from_le_bytes(footer[i * 8..(i + 1) * 8].try_into().unwrap())
+        };
+        let meta = FullTextIndexMeta {
+            segment_row_count: read_u64(0),
+            total_row_count: read_u64(1),
+            fst_offset: read_u64(2),
+            fst_size: read_u64(3),
+        };
+        ensure!(
+            meta.segment_row_count > 0,
+            UnexpectedZeroSegmentRowCountSnafu
+        );
+        ensure!(
+            meta.fst_offset + meta.fst_size + FOOTER_SIZE <= blob_size,
+            UnexpectedBlobSizeSnafu {
+                min_blob_size: meta.fst_offset + meta.fst_size + FOOTER_SIZE,
+                actual_blob_size: blob_size,
+            }
+        );
+        Ok(meta)
+    }
+
+    /// Reads the FST of tokens.
+    pub async fn fst(&mut self, meta: &FullTextIndexMeta) -> Result<FstMap> {
+        let mut buf = vec![0u8; meta.fst_size as usize];
+        self.read_at(meta.fst_offset, &mut buf).await?;
+        FstMap::new(buf).context(DecodeFstSnafu)
+    }
+
+    /// Reads the bitmap of segments of a token by the value in the FST.
+    pub async fn bitmap(&mut self, fst_value: u64) -> Result<BitVec> {
+        let [offset, size] = bytemuck::cast::<u64, [u32; 2]>(fst_value);
+        let mut buf = vec![0u8; size as usize];
+        self.read_at(offset as u64, &mut buf).await?;
+        Ok(BitVec::from_vec(buf))
+    }
+
+    /// Reads bitmaps of tokens by their values in the FST. Bitmaps close to each
+    /// other are read in one request.
+    pub async fn bitmaps(&mut self, fst_values: &[u64]) -> Result<Vec<BitVec>> {
+        let ranges: Vec<_> = fst_values
+            .iter()
+            .map(|value| {
+                let [offset, size] = bytemuck::cast::<u64, [u32; 2]>(*value);
+                (offset as u64, size as u64)
+            })
+            .collect();
+        let mut order: Vec<_> = (0..ranges.len()).collect();
+        order.sort_unstable_by_key(|i| ranges[*i].0);
+
+        let mut bitmaps = vec![BitVec::new(); ranges.len()];
+        let mut i = 0;
+        while i < order.len() {
+            let start = ranges[order[i]].0;
+            let mut end = start + ranges[order[i]].1;
+            let mut j = i + 1;
+            while j < order.len() && ranges[order[j]].0 <= end + MAX_COALESCE_GAP {
+                let (offset, size) = ranges[order[j]];
+                end = end.max(offset + size);
+                j += 1;
+            }
+
+            let mut buf = vec![0u8; (end - start) as usize];
+            self.read_at(start, &mut buf).await?;
+            for idx in &order[i..j] {
+                let (offset, size) = ranges[*idx];
+                let begin = (offset - start) as usize;
+                bitmaps[*idx] = BitVec::from_slice(&buf[begin..begin + size as usize]);
+            }
+            i = j;
+        }
+        Ok(bitmaps)
+    }
+
+    async fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.source
+            .seek(SeekFrom::Start(offset))
+            .await
+            .context(SeekSnafu)?;
+        self.source.read_exact(buf).await.context(ReadSnafu)
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::BitVec;
+use fst::Streamer;
+use futures::{AsyncRead, AsyncSeek};
+
+use crate::full_text_index::error::Result;
+use crate::full_text_index::format::FullTextIndexBlobReader;
+use crate::full_text_index::tokenizer::Term;
+
+/// Output of a full-text index search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FullTextSearchOutput {
+    /// Segments that may contain rows matching all terms.
+    pub matched_segment_ids: BitVec,
+    /// Number of rows indexed.
+    pub total_row_count: usize,
+    /// Number of rows in a segment.
+    pub segment_row_count: usize,
+}
+
+/// Searches segments containing all `terms` in a full-text index blob.
+///
+/// Segments are all matched if `terms` is empty.
+pub async fn search<R: AsyncRead + AsyncSeek + Unpin + Send>(
+    reader: &mut FullTextIndexBlobReader<R>,
+    terms: &[Term],
+) -> Result<FullTextSearchOutput> {
+    let meta = reader.metadata().await?;
+    let num_segments = meta.num_segments();
+    let mut matched_segment_ids = BitVec::repeat(true, num_segments);
+
+    if !terms.is_empty() {
+        let fst = reader.fst(&meta).await?;
+        for term in terms {
+            let values = match term {
+                Term::Token(token) => fst.get(token).into_iter().collect::<Vec<_>>(),
+                Term::Fragment(fragment) => {
+                    let mut values = Vec::new();
+                    let mut stream = fst.stream();
+                    while let Some((key, value)) = stream.next() {
+                        if contains(key, fragment.as_bytes()) {
+                            values.push(value);
+                        }
+                    }
+                    values
+                }
+            };
+
+            let mut term_segment_ids = BitVec::repeat(false, num_segments);
+            for mut bitmap in reader.bitmaps(&values).await? {
+                bitmap.resize(num_segments, false);
+                term_segment_ids |= bitmap;
+            }
+            matched_segment_ids &= term_segment_ids;
+            if matched_segment_ids.not_any() {
+                break;
+            }
+        }
+    }
+
+    Ok(FullTextSearchOutput {
+        matched_segment_ids,
+        total_row_count: meta.total_row_count as usize,
+        segment_row_count: meta.segment_row_count as usize,
+    })
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty() || haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use futures::io::Cursor;
+
+    use super::*;
+    use crate::full_text_index::create::FullTextIndexCreator;
+
+    async fn build_index(texts: &[Option<&str>], segment_row_count: usize) -> Vec<u8> {
+        let mut creator = FullTextIndexCreator::new(NonZeroUsize::new(segment_row_count).unwrap());
+        for text in texts {
+            creator.push_text(*text);
+        }
+        let mut blob = Cursor::new(Vec::new());
+        creator.finish(&mut blob).await.unwrap();
+        blob.into_inner()
+    }
+
+    async fn search_segments(blob: &[u8], terms: &[Term]) -> Vec<usize> {
+        let mut reader = FullTextIndexBlobReader::new(Cursor::new(blob));
+        let output = search(&mut reader, terms).await.unwrap();
+        output.matched_segment_ids.iter_ones().collect()
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let blob = build_index(
+            &[
+                Some("connection timeout"),
+                Some("ok"),
+                None,
+                Some("Disk full"),
+                Some("connection reset by peer"),
+                Some("ok"),
+                Some("request timeout"),
+            ],
+            2,
+        )
+        .await;
+
+        let token = |s: &str| Term::Token(s.to_string());
+        let fragment = |s: &str| Term::Fragment(s.to_string());
+        assert_eq!(vec![0, 1, 2, 3], search_segments(&blob, &[]).await);
+        assert_eq!(
+            vec![0, 3],
+            search_segments(&blob, &[token("timeout")]).await
+        );
+        assert_eq!(vec![1], search_segments(&blob, &[token("disk")]).await);
+        assert_eq!(
+            vec![0],
+            search_segments(&blob, &[token("connection"), token("timeout")]).await
+        );
+        assert_eq!(
+            Vec::<usize>::new(),
+            search_segments(&blob, &[token("time")]).await
+        );
+        assert_eq!(
+            vec![0, 3],
+            search_segments(&blob, &[fragment("time")]).await
+        );
+        assert_eq!(
+            vec![0, 2],
+            search_segments(&blob, &[fragment("conn"), fragment("e")]).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_metadata() {
+        let blob = build_index(&[Some("a"), Some("b"), Some("c")], 2).await;
+        let mut reader = FullTextIndexBlobReader::new(Cursor::new(&blob));
+        let output = search(&mut reader, &[Term::Token("c".to_string())])
+            .await
+            .unwrap();
+        assert_eq!(3, output.total_row_count);
+        assert_eq!(2, output.segment_row_count);
+        assert_eq!(
+            vec![1],
+            output.matched_segment_ids.iter_ones().collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_bitmaps() {
+        let blob = build_index(
+            &[Some("apple banana"), Some("cherry"), Some("apple durian")],
+            1,
+        )
+        .await;
+        let mut reader = FullTextIndexBlobReader::new(Cursor::new(&blob));
+        let meta = reader.metadata().await.unwrap();
+        let fst = reader.fst(&meta).await.unwrap();
+        let values: Vec<_> = ["durian", "apple", "cherry"]
+            .iter()
+            .map(|token| fst.get(token).unwrap())
+            .collect();
+        let bitmaps = reader.bitmaps(&values).await.unwrap();
+        assert_eq!(3, bitmaps.len());
+        for (value, bitmap) in values.iter().zip(bitmaps) {
+            assert_eq!(reader.bitmap(*value).await.unwrap(), bitmap);
+        }
+        assert!(reader.bitmaps(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_abort_creator() {
+        let mut creator = FullTextIndexCreator::new(NonZeroUsize::new(1).unwrap());
+        creator.push_text(Some("hello world"));
+        assert!(creator.memory_usage() > 0);
+
+        creator.abort();
+        assert!(creator.is_aborted());
+        assert_eq!(0, creator.memory_usage());
+        // Only counts rows.
+        creator.push_text(Some("hello"));
+        assert_eq!(2, creator.row_count());
+        assert_eq!(0, creator.memory_usage());
+        assert!(creator.finish(&mut Cursor::new(Vec::new())).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_invalid_blob() {
+        let mut reader = FullTextIndexBlobReader::new(Cursor::new(vec![0u8; 8]));
+        assert!(search(&mut reader, &[]).await.is_err());
+    }
+}
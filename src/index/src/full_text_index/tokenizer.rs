@@ -0,0 +1,158 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tokenizer shared by the full-text index and the `matches` function.
+
+/// Splits `text` into lowercase tokens of alphanumeric characters.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !is_token_char(c))
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Name of the function that matches a text against a full-text query.
+pub const MATCHES_FUNCTION_NAME: &str = "matches";
+
+/// Returns true if all tokens of `query` are in `text`.
+///
+/// This is the semantic of `matches(text, query)`. A query without tokens
+/// matches nothing.
+pub fn matches(text: &str, query: &str) -> bool {
+    let text_tokens: Vec<_> = tokenize(text).collect();
+    let mut query_tokens = tokenize(query).peekable();
+    if query_tokens.peek().is_none() {
+        return false;
+    }
+    query_tokens.all(|token| text_tokens.contains(&token))
+}
+
+/// A term a text must contain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    /// The text contains the token.
+    Token(String),
+    /// The text contains a token with this substring.
+    Fragment(String),
+}
+
+/// Returns terms a text must contain to match the SQL LIKE `pattern`.
+///
+/// `%` and `_` are wildcards and `\` escapes the next character. A token is
+/// only exact if it is surrounded by non-token characters or anchored at the
+/// start or end of the pattern. Returns an empty vec if the pattern can't
+/// prune anything.
+pub fn like_terms(pattern: &str) -> Vec<Term> {
+    // Splits the pattern into literals between wildcards.
+    let mut literals = vec![String::new()];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' | '_' => literals.push(String::new()),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    // Safety: `literals` is never empty.
+                    literals.last_mut().unwrap().push(escaped);
+                }
+            }
+            _ => literals.last_mut().unwrap().push(c),
+        }
+    }
+
+    let num_literals = literals.len();
+    let mut terms = Vec::new();
+    for (i, literal) in literals.iter().enumerate() {
+        let anchored_start = i == 0;
+        let anchored_end = i == num_literals - 1;
+        let tokens: Vec<_> = literal.split(|c: char| !is_token_char(c)).collect();
+        let num_tokens = tokens.len();
+        for (j, token) in tokens.into_iter().enumerate() {
+            if token.is_empty() {
+                continue;
+            }
+            let complete_start = j > 0 || anchored_start;
+            let complete_end = j < num_tokens - 1 || anchored_end;
+            let token = token.to_lowercase();
+            if complete_start && complete_end {
+                terms.push(Term::Token(token));
+            } else {
+                terms.push(Term::Fragment(token));
+            }
+        }
+    }
+    terms
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<_> = tokenize("GET /api/v1?id=42: Connection timeout!").collect();
+        assert_eq!(
+            vec!["get", "api", "v1", "id", "42", "connection", "timeout"],
+            tokens
+        );
+        assert_eq!(0, tokenize(" ,.!").count());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("Connection timeout", "timeout"));
+        assert!(matches("Connection timeout", "TIMEOUT connection"));
+        assert!(!matches("Connection timeout", "timeout error"));
+        assert!(!matches("Connection timeouts", "timeout"));
+        assert!(!matches("Connection timeout", ""));
+    }
+
+    #[test]
+    fn test_like_terms() {
+        assert_eq!(
+            vec![Term::Fragment("timeout".to_string())],
+            like_terms("%timeout%")
+        );
+        assert_eq!(
+            vec![
+                Term::Fragment("connection".to_string()),
+                Term::Token("timeout".to_string()),
+                Term::Fragment("err".to_string()),
+            ],
+            like_terms("%connection timeout err%")
+        );
+        assert_eq!(
+            vec![
+                Term::Token("error".to_string()),
+                Term::Fragment("time".to_string()),
+            ],
+            like_terms("Error: time%")
+        );
+        // Escaped wildcards are literals.
+        assert_eq!(
+            vec![Term::Token("a".to_string()), Term::Token("b".to_string())],
+            like_terms("a\\_b")
+        );
+        assert_eq!(
+            vec![
+                Term::Fragment("a".to_string()),
+                Term::Fragment("b".to_string())
+            ],
+            like_terms("%a_b%")
+        );
+        assert!(like_terms("%").is_empty());
+    }
+}
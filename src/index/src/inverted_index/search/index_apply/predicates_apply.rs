@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::mem::size_of;
+use std::collections::HashSet;
+use std::mem::{self, size_of};
 
 use async_trait::async_trait;
 use common_base::BitVec;
-use greptime_proto::v1::index::InvertedIndexMetas;
+use fst::Streamer;
+use greptime_proto::v1::index::{InvertedIndexMeta, InvertedIndexMetas};
 
 use crate::inverted_index::error::{IndexNotFoundSnafu, Result};
 use crate::inverted_index::format::reader::InvertedIndexReader;
@@ -27,16 +29,26 @@ use crate::inverted_index::search::fst_values_mapper::FstValuesMapper;
 use crate::inverted_index::search::index_apply::{
     ApplyOutput, IndexApplier, IndexNotFoundStrategy, SearchContext,
 };
-use crate::inverted_index::search::predicate::Predicate;
+use crate::inverted_index::search::predicate::{Predicate, PredicateExpr};
+use crate::inverted_index::FstMap;
 
 type IndexName = String;
 
+/// Maximum number of distinct values of an index to evaluate a negated predicate or
+/// `IS NOT NULL` against it. Such leaves read the bitmaps of almost all values, so
+/// an index with more values than this is skipped and all segments are matched.
+const MAX_NEGATED_FST_LEN: usize = 1024;
+
 /// `PredicatesIndexApplier` contains a collection of `FstApplier`s, each associated with an index name,
 /// to process and filter index data based on compiled predicates.
 pub struct PredicatesIndexApplier {
     /// A list of `FstApplier`s, each associated with a specific index name
     /// (e.g. a tag field uses its column name as index name)
     fst_appliers: Vec<(IndexName, Box<dyn FstApplier>)>,
+
+    /// A list of `ExprApplier`s compiled from predicate expressions, whose outputs
+    /// are intersected with the outputs of `fst_appliers`.
+    expr_appliers: Vec<ExprApplier>,
 }
 
 #[async_trait]
@@ -85,6 +97,18 @@ impl IndexApplier for PredicatesIndexApplier {
             bitmap &= bm;
         }
 
+        for expr_applier in &self.expr_appliers {
+            if bitmap.count_ones() == 0 {
+                break;
+            }
+
+            let bm = expr_applier
+                .apply(&context, &metadata, &mut *reader)
+                .await?;
+
+            bitmap &= bm;
+        }
+
         output.matched_segment_ids = bitmap;
         Ok(output)
     }
@@ -96,6 +120,10 @@ impl IndexApplier for PredicatesIndexApplier {
             size += name.capacity();
             size += fst_applier.memory_usage();
         }
+        size += self.expr_appliers.capacity() * size_of::<ExprApplier>();
+        for expr_applier in &self.expr_appliers {
+            size += expr_applier.memory_usage();
+        }
         size
     }
 }
@@ -103,7 +131,16 @@ impl IndexApplier for PredicatesIndexApplier {
 impl PredicatesIndexApplier {
     /// Constructs an instance of `PredicatesIndexApplier` based on a list of tag predicates.
     /// Chooses an appropriate `FstApplier` for each index name based on the nature of its predicates.
-    pub fn try_from(mut predicates: Vec<(IndexName, Vec<Predicate>)>) -> Result<Self> {
+    pub fn try_from(predicates: Vec<(IndexName, Vec<Predicate>)>) -> Result<Self> {
+        Self::try_new(predicates, vec![])
+    }
+
+    /// Constructs an instance of `PredicatesIndexApplier` based on a list of tag predicates
+    /// and a list of predicate expressions, all of which must be satisfied.
+    pub fn try_new(
+        mut predicates: Vec<(IndexName, Vec<Predicate>)>,
+        exprs: Vec<PredicateExpr>,
+    ) -> Result<Self> {
         let mut fst_appliers = Vec::with_capacity(predicates.len());
 
         // InList predicates are applied first to benefit from higher selectivity.
@@ -125,7 +162,15 @@ impl PredicatesIndexApplier {
             fst_appliers.push((tag_name, fst_applier));
         }
 
-        Ok(PredicatesIndexApplier { fst_appliers })
+        let expr_appliers = exprs
+            .into_iter()
+            .map(ExprApplier::try_from)
+            .collect::<Result<_>>()?;
+
+        Ok(PredicatesIndexApplier {
+            fst_appliers,
+            expr_appliers,
+        })
     }
 
     /// Creates a `BitVec` representing the full range of data in the index for initial scanning.
@@ -144,16 +189,244 @@ impl TryFrom<Vec<(String, Vec<Predicate>)>> for PredicatesIndexApplier {
     }
 }
 
+/// `ExprApplier` is a compiled [`PredicateExpr`]. Leaves of the expression are evaluated
+/// to bitmaps one by one, then the bitmaps are combined following the expression tree.
+struct ExprApplier {
+    /// Leaves of the expression in depth-first order.
+    leaves: Vec<LeafApplier>,
+
+    /// The expression tree referring to leaves by their positions in `leaves`.
+    tree: ExprTree,
+}
+
+enum ExprTree {
+    Leaf(usize),
+    And(Vec<ExprTree>),
+    Or(Vec<ExprTree>),
+}
+
+enum LeafApplier {
+    /// Matches segments containing values accepted by the `FstApplier`,
+    /// or non-null values rejected by it if `negated` is true.
+    Values {
+        index_name: IndexName,
+        fst_applier: Box<dyn FstApplier>,
+        negated: bool,
+    },
+
+    /// Matches segments containing nulls, or non-null values if `negated` is true.
+    IsNull {
+        index_name: IndexName,
+        negated: bool,
+    },
+}
+
+impl ExprApplier {
+    fn try_from(expr: PredicateExpr) -> Result<Self> {
+        let mut leaves = vec![];
+        let tree = Self::compile(expr, &mut leaves)?;
+        Ok(Self { leaves, tree })
+    }
+
+    fn compile(expr: PredicateExpr, leaves: &mut Vec<LeafApplier>) -> Result<ExprTree> {
+        let leaf = match expr {
+            PredicateExpr::Predicates {
+                index_name,
+                predicates,
+                negated,
+            } => {
+                // Same as `PredicatesIndexApplier`, prefers `KeysFstApplier` if there are InList predicates.
+                let fst_applier: Box<dyn FstApplier> =
+                    if predicates.iter().any(|p| matches!(p, Predicate::InList(_))) {
+                        Box::new(KeysFstApplier::try_from(predicates)?)
+                    } else {
+                        Box::new(IntersectionFstApplier::try_from(predicates)?)
+                    };
+                LeafApplier::Values {
+                    index_name,
+                    fst_applier,
+                    negated,
+                }
+            }
+            PredicateExpr::IsNull {
+                index_name,
+                negated,
+            } => LeafApplier::IsNull {
+                index_name,
+                negated,
+            },
+            PredicateExpr::And(exprs) => {
+                let children = exprs
+                    .into_iter()
+                    .map(|expr| Self::compile(expr, leaves))
+                    .collect::<Result<_>>()?;
+                return Ok(ExprTree::And(children));
+            }
+            PredicateExpr::Or(exprs) => {
+                let children = exprs
+                    .into_iter()
+                    .map(|expr| Self::compile(expr, leaves))
+                    .collect::<Result<_>>()?;
+                return Ok(ExprTree::Or(children));
+            }
+        };
+
+        leaves.push(leaf);
+        Ok(ExprTree::Leaf(leaves.len() - 1))
+    }
+
+    async fn apply(
+        &self,
+        context: &SearchContext,
+        metadata: &InvertedIndexMetas,
+        reader: &mut dyn InvertedIndexReader,
+    ) -> Result<BitVec> {
+        let full_range = PredicatesIndexApplier::bitmap_full_range(metadata);
+
+        let mut bitmaps = Vec::with_capacity(self.leaves.len());
+        for leaf in &self.leaves {
+            let bitmap = match metadata.metas.get(leaf.index_name()) {
+                Some(meta) => leaf.apply(meta, &mut *reader, &full_range).await?,
+                None => match context.index_not_found_strategy {
+                    // Evaluates the leaf as if the index only contains nulls.
+                    IndexNotFoundStrategy::ReturnEmpty => match leaf {
+                        LeafApplier::IsNull { negated: false, .. } => full_range.clone(),
+                        _ => BitVec::EMPTY,
+                    },
+                    IndexNotFoundStrategy::Ignore => full_range.clone(),
+                    IndexNotFoundStrategy::ThrowError => {
+                        return IndexNotFoundSnafu {
+                            name: leaf.index_name(),
+                        }
+                        .fail();
+                    }
+                },
+            };
+
+            // Aligns the bitmap to the number of segments, bitmaps read from the index
+            // may be padded or truncated.
+            let mut aligned = BitVec::repeat(false, full_range.len());
+            aligned |= bitmap;
+            bitmaps.push(aligned);
+        }
+
+        Ok(self.tree.combine(&mut bitmaps, &full_range))
+    }
+
+    fn memory_usage(&self) -> usize {
+        let mut size = self.leaves.capacity() * size_of::<LeafApplier>();
+        for leaf in &self.leaves {
+            size += leaf.index_name().len();
+            if let LeafApplier::Values { fst_applier, .. } = leaf {
+                size += fst_applier.memory_usage();
+            }
+        }
+        size
+    }
+}
+
+impl ExprTree {
+    /// Combines bitmaps of leaves, each of which is taken out of `bitmaps`.
+    fn combine(&self, bitmaps: &mut [BitVec], full_range: &BitVec) -> BitVec {
+        match self {
+            ExprTree::Leaf(i) => mem::take(&mut bitmaps[*i]),
+            ExprTree::And(children) => {
+                let mut bitmap = full_range.clone();
+                for child in children {
+                    bitmap &= child.combine(bitmaps, full_range);
+                }
+                bitmap
+            }
+            ExprTree::Or(children) => {
+                let mut bitmap = BitVec::repeat(false, full_range.len());
+                for child in children {
+                    bitmap |= child.combine(bitmaps, full_range);
+                }
+                bitmap
+            }
+        }
+    }
+}
+
+impl LeafApplier {
+    fn index_name(&self) -> &str {
+        match self {
+            LeafApplier::Values { index_name, .. } | LeafApplier::IsNull { index_name, .. } => {
+                index_name
+            }
+        }
+    }
+
+    /// Applies the leaf to the index. `full_range` is returned if the index is skipped.
+    async fn apply(
+        &self,
+        meta: &InvertedIndexMeta,
+        reader: &mut dyn InvertedIndexReader,
+        full_range: &BitVec,
+    ) -> Result<BitVec> {
+        let values = match self {
+            LeafApplier::Values {
+                fst_applier,
+                negated,
+                ..
+            } => {
+                let fst = reader.fst(meta).await?;
+                if *negated && fst.len() > MAX_NEGATED_FST_LEN {
+                    return Ok(full_range.clone());
+                }
+                let values = fst_applier.apply(&fst);
+                if *negated {
+                    // Segments containing other values satisfy the negation.
+                    let matched: HashSet<u64> = values.into_iter().collect();
+                    Self::all_values(&fst)
+                        .into_iter()
+                        .filter(|v| !matched.contains(v))
+                        .collect()
+                } else {
+                    values
+                }
+            }
+            LeafApplier::IsNull { negated: false, .. } => {
+                return reader
+                    .bitmap(
+                        meta,
+                        meta.relative_null_bitmap_offset,
+                        meta.null_bitmap_size,
+                    )
+                    .await;
+            }
+            LeafApplier::IsNull { negated: true, .. } => {
+                let fst = reader.fst(meta).await?;
+                if fst.len() > MAX_NEGATED_FST_LEN {
+                    return Ok(full_range.clone());
+                }
+                Self::all_values(&fst)
+            }
+        };
+
+        let mut mapper = FstValuesMapper::new(reader, meta);
+        mapper.map_values(&values).await
+    }
+
+    fn all_values(fst: &FstMap) -> Vec<u64> {
+        let mut values = Vec::with_capacity(fst.len());
+        let mut stream = fst.stream();
+        while let Some((_, value)) = stream.next() {
+            values.push(value);
+        }
+        values
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use common_base::bit_vec::prelude::*;
-    use greptime_proto::v1::index::InvertedIndexMeta;
 
     use super::*;
     use crate::inverted_index::error::Error;
     use crate::inverted_index::format::reader::MockInvertedIndexReader;
     use crate::inverted_index::search::fst_apply::MockFstApplier;
-    use crate::inverted_index::FstMap;
+    use crate::inverted_index::search::predicate::InListPredicate;
 
     fn s(s: &'static str) -> String {
         s.to_owned()
@@ -192,6 +465,7 @@ mod tests {
         // An index applier that point-gets "tag-0_value-0" on tag "tag-0"
         let applier = PredicatesIndexApplier {
             fst_appliers: vec![(s("tag-0"), key_fst_applier("tag-0_value-0"))],
+            expr_appliers: vec![],
         };
 
         // An index reader with a single tag "tag-0" and a corresponding value "tag-0_value-0"
@@ -246,6 +520,7 @@ mod tests {
                 (s("tag-0"), key_fst_applier("tag-0_value-0")),
                 (s("tag-1"), key_fst_applier("tag-1_value-a")),
             ],
+            expr_appliers: vec![],
         };
 
         // An index reader with two tags "tag-0" and "tag-1" and respective values "tag-0_value-0" and "tag-1_value-a"
@@ -282,6 +557,7 @@ mod tests {
     async fn test_index_applier_without_predicates() {
         let applier = PredicatesIndexApplier {
             fst_appliers: vec![],
+            expr_appliers: vec![],
         };
 
         let mut mock_reader: MockInvertedIndexReader = MockInvertedIndexReader::new();
@@ -315,6 +591,7 @@ mod tests {
 
         let applier = PredicatesIndexApplier {
             fst_appliers: vec![(s("tag-0"), Box::new(mock_fst_applier))],
+            expr_appliers: vec![],
         };
 
         let output = applier
@@ -336,6 +613,7 @@ mod tests {
 
         let applier = PredicatesIndexApplier {
             fst_appliers: vec![(s("tag-0"), Box::new(mock_fst_applier))],
+            expr_appliers: vec![],
         };
 
         let result = applier
@@ -381,6 +659,7 @@ mod tests {
 
         let applier = PredicatesIndexApplier {
             fst_appliers: vec![(s("tag-0"), Box::new(mock_fst_applier))],
+            expr_appliers: vec![],
         };
 
         assert_eq!(
@@ -388,4 +667,230 @@ mod tests {
             size_of::<(IndexName, Box<dyn FstApplier>)>() + 5 + 100
         );
     }
+
+    fn in_list_expr(tag: &'static str, value: &'static str, negated: bool) -> PredicateExpr {
+        PredicateExpr::Predicates {
+            index_name: s(tag),
+            predicates: vec![Predicate::InList(InListPredicate {
+                list: HashSet::from([value.as_bytes().to_vec()]),
+            })],
+            negated,
+        }
+    }
+
+    fn is_null_expr(tag: &'static str, negated: bool) -> PredicateExpr {
+        PredicateExpr::IsNull {
+            index_name: s(tag),
+            negated,
+        }
+    }
+
+    fn mock_reader_with_nulls() -> MockInvertedIndexReader {
+        let mut mock_reader = MockInvertedIndexReader::new();
+        mock_reader.expect_metadata().returning(|| {
+            let mut metas = mock_metas(["tag-0", "tag-1"]);
+            let meta = metas.metas.get_mut("tag-0").unwrap();
+            meta.relative_null_bitmap_offset = 0;
+            meta.null_bitmap_size = 1;
+            Ok(metas)
+        });
+        mock_reader
+            .expect_fst()
+            .returning(|meta| match meta.name.as_str() {
+                "tag-0" => Ok(FstMap::from_iter([
+                    (b"tag-0_value-0", fst_value(1, 1)),
+                    (b"tag-0_value-1", fst_value(2, 1)),
+                ])
+                .unwrap()),
+                "tag-1" => Ok(FstMap::from_iter([(b"tag-1_value-a", fst_value(3, 1))]).unwrap()),
+                _ => unreachable!(),
+            });
+        mock_reader.expect_bitmap().returning(|meta, offset, size| {
+            match (meta.name.as_str(), offset, size) {
+                ("tag-0", 0, 1) => Ok(bitvec![u8, Lsb0; 0, 0, 0, 0, 0, 0, 1, 1]),
+                ("tag-0", 1, 1) => Ok(bitvec![u8, Lsb0; 1, 0, 1, 0, 0, 0, 0, 0]),
+                ("tag-0", 2, 1) => Ok(bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 0, 0, 0]),
+                ("tag-1", 3, 1) => Ok(bitvec![u8, Lsb0; 0, 1, 0, 0, 1, 1, 1, 0]),
+                _ => unreachable!(),
+            }
+        });
+        mock_reader
+    }
+
+    #[tokio::test]
+    async fn test_index_applier_apply_exprs() {
+        let apply = |exprs: Vec<PredicateExpr>| async move {
+            let applier = PredicatesIndexApplier::try_new(vec![], exprs).unwrap();
+            let mut mock_reader = mock_reader_with_nulls();
+            applier
+                .apply(SearchContext::default(), &mut mock_reader)
+                .await
+                .unwrap()
+                .matched_segment_ids
+        };
+
+        // tag-0 = "tag-0_value-0" OR tag-1 = "tag-1_value-a"
+        let output = apply(vec![PredicateExpr::Or(vec![
+            in_list_expr("tag-0", "tag-0_value-0", false),
+            in_list_expr("tag-1", "tag-1_value-a", false),
+        ])])
+        .await;
+        assert_eq!(output, bitvec![u8, Lsb0; 1, 1, 1, 0, 1, 1, 1, 0]);
+
+        // tag-0 != "tag-0_value-0"
+        let output = apply(vec![in_list_expr("tag-0", "tag-0_value-0", true)]).await;
+        assert_eq!(output, bitvec![u8, Lsb0; 0, 1, 0, 1, 0, 0, 0, 0]);
+
+        // tag-0 IS NULL
+        let output = apply(vec![is_null_expr("tag-0", false)]).await;
+        assert_eq!(output, bitvec![u8, Lsb0; 0, 0, 0, 0, 0, 0, 1, 1]);
+
+        // tag-0 IS NOT NULL
+        let output = apply(vec![is_null_expr("tag-0", true)]).await;
+        assert_eq!(output, bitvec![u8, Lsb0; 1, 1, 1, 1, 0, 0, 0, 0]);
+
+        // (tag-0 IS NULL OR tag-0 != "tag-0_value-0") AND tag-1 = "tag-1_value-a"
+        let output = apply(vec![
+            PredicateExpr::Or(vec![
+                is_null_expr("tag-0", false),
+                in_list_expr("tag-0", "tag-0_value-0", true),
+            ]),
+            PredicateExpr::And(vec![in_list_expr("tag-1", "tag-1_value-a", false)]),
+        ])
+        .await;
+        assert_eq!(output, bitvec![u8, Lsb0; 0, 1, 0, 0, 0, 0, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_index_applier_apply_negated_exprs_on_high_cardinality_index() {
+        let apply = |exprs: Vec<PredicateExpr>| async move {
+            let applier = PredicatesIndexApplier::try_new(vec![], exprs).unwrap();
+            let mut mock_reader = MockInvertedIndexReader::new();
+            mock_reader
+                .expect_metadata()
+                .returning(|| Ok(mock_metas(["tag-0"])));
+            mock_reader.expect_fst().returning(|_| {
+                let keys = (0..=MAX_NEGATED_FST_LEN)
+                    .map(|i| (format!("tag-0_value-{i:05}"), fst_value(i as u32, 1)));
+                Ok(FstMap::from_iter(keys).unwrap())
+            });
+            // The index is skipped so no bitmap is read.
+            mock_reader.expect_bitmap().never();
+            applier
+                .apply(SearchContext::default(), &mut mock_reader)
+                .await
+                .unwrap()
+                .matched_segment_ids
+        };
+
+        // tag-0 != "tag-0_value-00000"
+        let output = apply(vec![in_list_expr("tag-0", "tag-0_value-00000", true)]).await;
+        assert_eq!(output, bitvec![u8, Lsb0; 1, 1, 1, 1, 1, 1, 1, 1]);
+
+        // tag-0 IS NOT NULL
+        let output = apply(vec![is_null_expr("tag-0", true)]).await;
+        assert_eq!(output, bitvec![u8, Lsb0; 1, 1, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_index_applier_apply_exprs_with_predicates() {
+        let applier = PredicatesIndexApplier::try_new(
+            vec![(
+                s("tag-1"),
+                vec![Predicate::InList(InListPredicate {
+                    list: HashSet::from([b"tag-1_value-a".to_vec()]),
+                })],
+            )],
+            vec![PredicateExpr::Or(vec![
+                is_null_expr("tag-0", false),
+                in_list_expr("tag-0", "tag-0_value-0", false),
+            ])],
+        )
+        .unwrap();
+
+        let mut mock_reader = mock_reader_with_nulls();
+        let output = applier
+            .apply(SearchContext::default(), &mut mock_reader)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.matched_segment_ids,
+            bitvec![u8, Lsb0; 0, 0, 0, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_applier_apply_exprs_with_nonexistent_index() {
+        let applier = PredicatesIndexApplier::try_new(
+            vec![],
+            vec![PredicateExpr::Or(vec![
+                in_list_expr("tag-2", "tag-2_value-0", true),
+                is_null_expr("tag-2", false),
+            ])],
+        )
+        .unwrap();
+
+        let mut mock_reader = MockInvertedIndexReader::new();
+        mock_reader
+            .expect_metadata()
+            .returning(|| Ok(mock_metas(["tag-0"])));
+
+        // The nonexistent index is regarded as full of nulls.
+        let output = applier
+            .apply(
+                SearchContext {
+                    index_not_found_strategy: IndexNotFoundStrategy::ReturnEmpty,
+                },
+                &mut mock_reader,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            output.matched_segment_ids,
+            bitvec![u8, Lsb0; 1, 1, 1, 1, 1, 1, 1, 1]
+        );
+
+        let applier = PredicatesIndexApplier::try_new(
+            vec![],
+            vec![PredicateExpr::Or(vec![
+                in_list_expr("tag-2", "tag-2_value-0", true),
+                is_null_expr("tag-2", true),
+            ])],
+        )
+        .unwrap();
+        let output = applier
+            .apply(
+                SearchContext {
+                    index_not_found_strategy: IndexNotFoundStrategy::ReturnEmpty,
+                },
+                &mut mock_reader,
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.matched_segment_ids.count_ones(), 0);
+
+        let output = applier
+            .apply(
+                SearchContext {
+                    index_not_found_strategy: IndexNotFoundStrategy::Ignore,
+                },
+                &mut mock_reader,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            output.matched_segment_ids,
+            bitvec![u8, Lsb0; 1, 1, 1, 1, 1, 1, 1, 1]
+        );
+
+        let result = applier
+            .apply(
+                SearchContext {
+                    index_not_found_strategy: IndexNotFoundStrategy::ThrowError,
+                },
+                &mut mock_reader,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::IndexNotFound { .. })));
+    }
 }
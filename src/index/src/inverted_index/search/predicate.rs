@@ -71,3 +71,38 @@ pub struct RegexMatchPredicate {
     /// The regex pattern.
     pub pattern: String,
 }
+
+/// `PredicateExpr` is a boolean expression tree over predicates of different indexes.
+/// Each leaf is evaluated to a bitmap of segments by its index, and inner nodes combine
+/// bitmaps of their children by intersection or union.
+///
+/// Negations are only allowed on leaves, where they are evaluated against the values of
+/// the index instead of the segments. Callers push negations down to leaves by De Morgan's laws.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PredicateExpr {
+    /// Matches segments containing a value of the index that satisfies all the predicates
+    /// (logical AND semantic), or a non-null value that doesn't if `negated` is true.
+    Predicates {
+        /// The name of the index.
+        index_name: String,
+        /// The predicates on the values of the index.
+        predicates: Vec<Predicate>,
+        /// Whether the predicates are negated.
+        negated: bool,
+    },
+
+    /// Matches segments containing null values of the index, or non-null values
+    /// if `negated` is true.
+    IsNull {
+        /// The name of the index.
+        index_name: String,
+        /// Whether the predicate is negated.
+        negated: bool,
+    },
+
+    /// Matches segments matched by all the sub-expressions.
+    And(Vec<PredicateExpr>),
+
+    /// Matches segments matched by any of the sub-expressions.
+    Or(Vec<PredicateExpr>),
+}
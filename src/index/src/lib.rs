@@ -14,4 +14,6 @@
 
 #![feature(iter_partition_in_place)]
 
+pub mod bloom_filter;
+pub mod full_text_index;
 pub mod inverted_index;
@@ -175,6 +175,47 @@ pub enum Error {
         error: rskafka::client::error::Error,
     },
 
+    #[snafu(display("Failed to get the earliest offset, ns: {}", ns))]
+    GetEarliestOffset {
+        ns: KafkaNamespace,
+        location: Location,
+        #[snafu(source)]
+        error: rskafka::client::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to delete records before offset {} from topic: {}",
+        offset,
+        topic
+    ))]
+    DeleteRecords {
+        topic: String,
+        offset: i64,
+        location: Location,
+        #[snafu(source)]
+        error: rskafka::client::error::Error,
+    },
+
+    #[snafu(display(
+        "Entries of ns {} starting from offset {} have been pruned, the earliest offset is {}",
+        ns,
+        start_offset,
+        earliest_offset
+    ))]
+    EntriesPruned {
+        ns: KafkaNamespace,
+        start_offset: i64,
+        earliest_offset: i64,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to access the WAL checkpoints of topic: {}", topic))]
+    Checkpoint {
+        topic: String,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Failed to do a cast"))]
     Cast { location: Location },
 
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod checkpoint;
 pub(crate) mod client_manager;
 pub mod log_store;
 pub(crate) mod util;
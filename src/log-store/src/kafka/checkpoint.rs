@@ -0,0 +1,410 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Mutex;
+
+use common_meta::key::datanode_table::DatanodeTableManager;
+use common_meta::kv_backend::KvBackendRef;
+use common_meta::rpc::store::{BatchPutRequest, RangeRequest};
+use common_telemetry::warn;
+use common_wal::options::WalOptions;
+use futures::TryStreamExt;
+use snafu::ResultExt;
+use store_api::logstore::entry::Id as EntryId;
+use store_api::storage::RegionId;
+
+use crate::error::{CheckpointSnafu, DecodeJsonSnafu, EncodeJsonSnafu, Result};
+use crate::kafka::NamespaceImpl;
+
+/// Key prefix of checkpoints of regions in Kafka topics.
+const CHECKPOINT_KEY_PREFIX: &str = "__kafka_wal_checkpoint";
+
+/// Persists the checkpoint of each region in a Kafka topic, i.e. the id of the last
+/// entry that is obsolete for the region, to the metadata store.
+///
+/// Records of a topic are only safe to be deleted if they are obsolete for all regions
+/// in the topic, including regions of other datanodes and regions that are not opened
+/// now. So checkpoints are shared by datanodes through the metadata store, and regions
+/// in the topic are listed from the table metadata.
+///
+/// Checkpoints advanced by flushes are buffered in memory and persisted in batches by
+/// [CheckpointManager::persist], so flushes don't access the metadata store.
+pub(crate) struct CheckpointManager {
+    kv_backend: KvBackendRef,
+    /// Checkpoints not persisted yet.
+    pending: Mutex<HashMap<NamespaceImpl, EntryId>>,
+}
+
+impl fmt::Debug for CheckpointManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckpointManager")
+            .field("kv_backend", &self.kv_backend.name())
+            .finish()
+    }
+}
+
+impl CheckpointManager {
+    pub(crate) fn new(kv_backend: KvBackendRef) -> Self {
+        Self {
+            kv_backend,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn topic_prefix(topic: &str) -> String {
+        format!("{CHECKPOINT_KEY_PREFIX}/{topic}/")
+    }
+
+    fn key(ns: &NamespaceImpl) -> String {
+        format!("{}{}", Self::topic_prefix(&ns.topic), ns.region_id)
+    }
+
+    /// Returns the checkpoint of the region. Entries with ids `<=` the checkpoint are
+    /// obsolete for the region.
+    pub(crate) async fn get(&self, ns: &NamespaceImpl) -> Result<Option<EntryId>> {
+        let Some(kv) = self
+            .kv_backend
+            .get(Self::key(ns).as_bytes())
+            .await
+            .context(CheckpointSnafu { topic: &ns.topic })?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_slice(&kv.value)
+            .context(DecodeJsonSnafu)
+            .map(Some)
+    }
+
+    /// Sets the checkpoint of the region if the region doesn't have one.
+    pub(crate) async fn init(&self, ns: &NamespaceImpl, entry_id: EntryId) -> Result<()> {
+        let value = serde_json::to_vec(&entry_id).context(EncodeJsonSnafu)?;
+        let _ = self
+            .kv_backend
+            .put_conditionally(Self::key(ns).into_bytes(), value, true)
+            .await
+            .context(CheckpointSnafu { topic: &ns.topic })?;
+        Ok(())
+    }
+
+    /// Advances the checkpoint of the region to `entry_id` in memory. It's persisted by
+    /// the next [CheckpointManager::persist].
+    pub(crate) fn advance(&self, ns: &NamespaceImpl, entry_id: EntryId) {
+        let mut pending = self.pending.lock().unwrap();
+        let checkpoint = pending.entry(ns.clone()).or_insert(entry_id);
+        *checkpoint = (*checkpoint).max(entry_id);
+    }
+
+    /// Persists checkpoints advanced since the last call. Checkpoints never move backward.
+    ///
+    /// Returns topics whose checkpoints are persisted. Checkpoints failed to persist are
+    /// kept to be persisted next time.
+    pub(crate) async fn persist(&self) -> HashSet<String> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        let mut topics: HashMap<String, Vec<(u64, EntryId)>> = HashMap::new();
+        for (ns, entry_id) in pending {
+            topics
+                .entry(ns.topic)
+                .or_default()
+                .push((ns.region_id, entry_id));
+        }
+
+        let mut persisted = HashSet::with_capacity(topics.len());
+        for (topic, checkpoints) in topics {
+            if let Err(e) = self.persist_topic(&topic, &checkpoints).await {
+                warn!(e; "Failed to persist checkpoints of topic {}", topic);
+                for (region_id, entry_id) in checkpoints {
+                    let ns = NamespaceImpl {
+                        region_id,
+                        topic: topic.clone(),
+                    };
+                    self.advance(&ns, entry_id);
+                }
+                continue;
+            }
+            let _ = persisted.insert(topic);
+        }
+        persisted
+    }
+
+    async fn persist_topic(&self, topic: &str, checkpoints: &[(u64, EntryId)]) -> Result<()> {
+        let current = self.checkpoints(topic).await?;
+        let mut req = BatchPutRequest::new();
+        for (region_id, entry_id) in checkpoints {
+            if current
+                .get(region_id)
+                .is_some_and(|current| current >= entry_id)
+            {
+                continue;
+            }
+            let ns = NamespaceImpl {
+                region_id: *region_id,
+                topic: topic.to_string(),
+            };
+            let value = serde_json::to_vec(entry_id).context(EncodeJsonSnafu)?;
+            req = req.add_kv(Self::key(&ns), value);
+        }
+        if req.kvs.is_empty() {
+            return Ok(());
+        }
+        // Only the leader of a region advances its checkpoint, so it's fine to put without
+        // comparing.
+        let _ = self
+            .kv_backend
+            .batch_put(req)
+            .await
+            .context(CheckpointSnafu { topic })?;
+        Ok(())
+    }
+
+    /// Removes the checkpoint of the region.
+    pub(crate) async fn remove(&self, ns: &NamespaceImpl) -> Result<()> {
+        let _ = self.pending.lock().unwrap().remove(ns);
+        let _ = self
+            .kv_backend
+            .delete(Self::key(ns).as_bytes(), false)
+            .await
+            .context(CheckpointSnafu { topic: &ns.topic })?;
+        Ok(())
+    }
+
+    /// Lists namespaces of all regions with checkpoints, i.e. regions created in topics.
+    pub(crate) async fn namespaces(&self) -> Result<Vec<NamespaceImpl>> {
+        let prefix = format!("{CHECKPOINT_KEY_PREFIX}/");
+        let req = RangeRequest::new().with_prefix(prefix.as_bytes());
+        let resp = self
+            .kv_backend
+            .range(req)
+            .await
+            .context(CheckpointSnafu { topic: "" })?;
+
+        let namespaces = resp
+            .kvs
+            .iter()
+            .filter_map(|kv| {
+                let key = String::from_utf8_lossy(&kv.key);
+                // Topic names don't contain '/'.
+                let (topic, region_id) = key.strip_prefix(&prefix)?.split_once('/')?;
+                Some(NamespaceImpl {
+                    region_id: region_id.parse().ok()?,
+                    topic: topic.to_string(),
+                })
+            })
+            .collect();
+        Ok(namespaces)
+    }
+
+    /// Returns the id of the last entry that is obsolete for all `regions` in the topic.
+    ///
+    /// Returns `None` if there is a region in the topic without checkpoint.
+    pub(crate) async fn prunable_entry_id(
+        &self,
+        topic: &str,
+        regions: &HashSet<u64>,
+    ) -> Result<Option<EntryId>> {
+        if regions.is_empty() {
+            return Ok(None);
+        }
+
+        let checkpoints = self.checkpoints(topic).await?;
+        let mut prunable: Option<EntryId> = None;
+        for region_id in regions {
+            let Some(checkpoint) = checkpoints.get(region_id) else {
+                return Ok(None);
+            };
+            prunable = Some(prunable.map_or(*checkpoint, |id| id.min(*checkpoint)));
+        }
+        Ok(prunable)
+    }
+
+    /// Returns checkpoints of regions in the topic. Key: region id.
+    async fn checkpoints(&self, topic: &str) -> Result<HashMap<u64, EntryId>> {
+        let prefix = Self::topic_prefix(topic);
+        let req = RangeRequest::new().with_prefix(prefix.as_bytes());
+        let resp = self
+            .kv_backend
+            .range(req)
+            .await
+            .context(CheckpointSnafu { topic })?;
+
+        let mut checkpoints = HashMap::with_capacity(resp.kvs.len());
+        for kv in resp.kvs {
+            // Ignores keys not written by the manager.
+            let Some(region_id) = String::from_utf8_lossy(&kv.key)
+                .strip_prefix(&prefix)
+                .and_then(|id| id.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            let checkpoint = serde_json::from_slice(&kv.value).context(DecodeJsonSnafu)?;
+            let _ = checkpoints.insert(region_id, checkpoint);
+        }
+        Ok(checkpoints)
+    }
+
+    /// Lists ids of regions writing to each topic from the table metadata. Key: topic.
+    pub(crate) async fn topic_regions(&self) -> Result<HashMap<String, HashSet<u64>>> {
+        let tables = DatanodeTableManager::new(self.kv_backend.clone())
+            .all_tables()
+            .try_collect::<Vec<_>>()
+            .await
+            .context(CheckpointSnafu { topic: "" })?;
+
+        let mut topic_regions: HashMap<String, HashSet<u64>> = HashMap::new();
+        for table in tables {
+            for region_number in &table.regions {
+                let Some(wal_options) = table.region_info.region_wal_options.get(region_number)
+                else {
+                    continue;
+                };
+                let wal_options: WalOptions =
+                    serde_json::from_str(wal_options).context(DecodeJsonSnafu)?;
+                if let WalOptions::Kafka(kafka_options) = wal_options {
+                    let region_id = RegionId::new(table.table_id, *region_number);
+                    let _ = topic_regions
+                        .entry(kafka_options.topic)
+                        .or_default()
+                        .insert(region_id.as_u64());
+                }
+            }
+        }
+        Ok(topic_regions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_meta::key::datanode_table::{DatanodeTableKey, DatanodeTableValue, RegionInfo};
+    use common_meta::key::{TableMetaKey, TableMetaValue};
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use common_meta::rpc::store::PutRequest;
+    use common_wal::options::KafkaWalOptions;
+
+    use super::*;
+
+    fn ns(topic: &str, region_id: RegionId) -> NamespaceImpl {
+        NamespaceImpl {
+            region_id: region_id.as_u64(),
+            topic: topic.to_string(),
+        }
+    }
+
+    async fn put_table(kv_backend: &KvBackendRef, datanode_id: u64, table_id: u32, topic: &str) {
+        let wal_options = serde_json::to_string(&WalOptions::Kafka(KafkaWalOptions {
+            topic: topic.to_string(),
+        }))
+        .unwrap();
+        let value = DatanodeTableValue::new(
+            table_id,
+            vec![0, 1],
+            RegionInfo {
+                region_wal_options: [(0, wal_options.clone()), (1, wal_options)].into(),
+                ..Default::default()
+            },
+        );
+        let req = PutRequest::new()
+            .with_key(DatanodeTableKey::new(datanode_id, table_id).as_raw_key())
+            .with_value(value.try_as_raw_value().unwrap());
+        let _ = kv_backend.put(req).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prunable_entry_id() {
+        let kv_backend: KvBackendRef = Arc::new(MemoryKvBackend::new());
+        // Regions of table 1024 on datanode 1 and table 1025 on datanode 2 share topic "a".
+        put_table(&kv_backend, 1, 1024, "a").await;
+        put_table(&kv_backend, 2, 1025, "a").await;
+        put_table(&kv_backend, 1, 1026, "b").await;
+        let manager = CheckpointManager::new(kv_backend);
+
+        let topic_regions = manager.topic_regions().await.unwrap();
+        assert_eq!(2, topic_regions.len());
+        let regions_a = &topic_regions["a"];
+        let regions_b = &topic_regions["b"];
+        assert_eq!(4, regions_a.len());
+
+        let regions = [
+            RegionId::new(1024, 0),
+            RegionId::new(1024, 1),
+            RegionId::new(1025, 0),
+        ];
+        for (i, region_id) in regions.iter().enumerate() {
+            manager.advance(&ns("a", *region_id), 10 + i as u64);
+        }
+        // Checkpoints are not persisted yet.
+        assert_eq!(None, manager.get(&ns("a", regions[0])).await.unwrap());
+        let persisted = manager.persist().await;
+        assert_eq!(HashSet::from(["a".to_string()]), persisted);
+        assert_eq!(Some(10), manager.get(&ns("a", regions[0])).await.unwrap());
+        assert!(manager.persist().await.is_empty());
+
+        // Region 1025/1 has no checkpoint.
+        assert_eq!(
+            None,
+            manager.prunable_entry_id("a", regions_a).await.unwrap()
+        );
+
+        manager
+            .init(&ns("a", RegionId::new(1025, 1)), 20)
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(10),
+            manager.prunable_entry_id("a", regions_a).await.unwrap()
+        );
+        // Regions of other topics are not affected.
+        assert_eq!(
+            None,
+            manager.prunable_entry_id("b", regions_b).await.unwrap()
+        );
+
+        // Checkpoints never move backward.
+        manager.advance(&ns("a", regions[0]), 5);
+        let _ = manager.persist().await;
+        manager.init(&ns("a", regions[0]), 5).await.unwrap();
+        assert_eq!(Some(10), manager.get(&ns("a", regions[0])).await.unwrap());
+
+        manager.advance(&ns("a", regions[0]), 30);
+        manager.advance(&ns("a", regions[0]), 25);
+        let _ = manager.persist().await;
+        assert_eq!(Some(30), manager.get(&ns("a", regions[0])).await.unwrap());
+        assert_eq!(
+            Some(11),
+            manager.prunable_entry_id("a", regions_a).await.unwrap()
+        );
+
+        let mut namespaces = manager.namespaces().await.unwrap();
+        namespaces.sort_by_key(|ns| ns.region_id);
+        let mut expected = regions
+            .iter()
+            .chain([&RegionId::new(1025, 1)])
+            .map(|region_id| ns("a", *region_id))
+            .collect::<Vec<_>>();
+        expected.sort_by_key(|ns| ns.region_id);
+        assert_eq!(expected, namespaces);
+
+        // Removing a region drops its pending checkpoint.
+        manager.advance(&ns("a", regions[1]), 40);
+        manager.remove(&ns("a", regions[1])).await.unwrap();
+        assert!(manager.persist().await.is_empty());
+        assert_eq!(None, manager.get(&ns("a", regions[1])).await.unwrap());
+        assert_eq!(
+            None,
+            manager.prunable_entry_id("a", regions_a).await.unwrap()
+        );
+    }
+}
@@ -12,41 +12,148 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use common_telemetry::{debug, warn};
+use common_meta::kv_backend::KvBackendRef;
+use common_runtime::{RepeatedTask, TaskFunction};
+use common_telemetry::{debug, info, warn};
 use common_wal::config::kafka::DatanodeKafkaConfig;
 use common_wal::options::WalOptions;
 use futures_util::StreamExt;
 use rskafka::client::consumer::{StartOffset, StreamConsumerBuilder};
 use rskafka::client::partition::OffsetAt;
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 use store_api::logstore::entry::Id as EntryId;
 use store_api::logstore::entry_stream::SendableEntryStream;
 use store_api::logstore::namespace::Id as NamespaceId;
 use store_api::logstore::{AppendBatchResponse, AppendResponse, LogStore};
 
-use crate::error::{ConsumeRecordSnafu, Error, GetOffsetSnafu, IllegalSequenceSnafu, Result};
+use crate::error::{
+    ConsumeRecordSnafu, DeleteRecordsSnafu, EntriesPrunedSnafu, Error, GetEarliestOffsetSnafu,
+    GetOffsetSnafu, IllegalSequenceSnafu, Result, StartGcTaskSnafu, StopGcTaskSnafu,
+};
+use crate::kafka::checkpoint::CheckpointManager;
 use crate::kafka::client_manager::{ClientManager, ClientManagerRef};
 use crate::kafka::util::offset::Offset;
 use crate::kafka::util::record::{maybe_emit_entry, Record, RecordProducer};
 use crate::kafka::{EntryImpl, NamespaceImpl};
 
+/// The timeout of deleting obsolete records from a topic.
+const DELETE_RECORDS_TIMEOUT_MS: i32 = 10_000;
+
 /// A log store backed by Kafka.
 #[derive(Debug)]
 pub struct KafkaLogStore {
     config: DatanodeKafkaConfig,
     /// Manages kafka clients through which the log store contact the Kafka cluster.
     client_manager: ClientManagerRef,
+    /// Persists checkpoints of regions in topics.
+    checkpoint_manager: Arc<CheckpointManager>,
+    /// Periodically persists checkpoints and prunes obsolete records.
+    checkpoint_task: RepeatedTask<Error>,
+}
+
+/// Persists checkpoints advanced since the last run and, if `prune_obsolete_records` is
+/// enabled, deletes records obsolete for all regions from topics whose checkpoints advanced.
+struct PruneObsoleteRecordsFunction {
+    client_manager: ClientManagerRef,
+    checkpoint_manager: Arc<CheckpointManager>,
+    prune_obsolete_records: bool,
+    /// Key: topic. Value: the id of the last entry pruned from the topic by the log store.
+    pruned_entry_ids: HashMap<String, EntryId>,
+}
+
+#[async_trait::async_trait]
+impl TaskFunction<Error> for PruneObsoleteRecordsFunction {
+    fn name(&self) -> &str {
+        "KafkaLogStore-checkpoint-task"
+    }
+
+    async fn call(&mut self) -> Result<()> {
+        let topics = self.checkpoint_manager.persist().await;
+        if !self.prune_obsolete_records || topics.is_empty() {
+            return Ok(());
+        }
+
+        // Pruning is best-effort, records will be pruned on the next run if it fails.
+        let topic_regions = match self.checkpoint_manager.topic_regions().await {
+            Ok(topic_regions) => topic_regions,
+            Err(e) => {
+                warn!(e; "Failed to list regions of topics");
+                return Ok(());
+            }
+        };
+        for topic in topics {
+            let Some(regions) = topic_regions.get(&topic) else {
+                continue;
+            };
+            if let Err(e) = self.prune(&topic, regions).await {
+                warn!(e; "Failed to prune obsolete records of topic {}", topic);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PruneObsoleteRecordsFunction {
+    /// Deletes records obsolete for all `regions` in the topic.
+    async fn prune(&mut self, topic: &String, regions: &HashSet<u64>) -> Result<()> {
+        let Some(entry_id) = self
+            .checkpoint_manager
+            .prunable_entry_id(topic, regions)
+            .await?
+        else {
+            return Ok(());
+        };
+        if self
+            .pruned_entry_ids
+            .get(topic)
+            .is_some_and(|pruned| *pruned >= entry_id)
+        {
+            return Ok(());
+        }
+
+        let client = self.client_manager.get_or_insert(topic).await?.raw_client;
+        // Records with offsets smaller than the given offset are deleted.
+        let offset = Offset::try_from(entry_id)?.0 + 1;
+        client
+            .delete_records(offset, DELETE_RECORDS_TIMEOUT_MS)
+            .await
+            .context(DeleteRecordsSnafu {
+                topic: topic.clone(),
+                offset,
+            })?;
+        let _ = self.pruned_entry_ids.insert(topic.clone(), entry_id);
+        info!("Pruned records before offset {} of topic {}", offset, topic);
+        Ok(())
+    }
 }
 
 impl KafkaLogStore {
-    /// Tries to create a Kafka log store.
-    pub async fn try_new(config: &DatanodeKafkaConfig) -> Result<Self> {
+    /// Tries to create a Kafka log store. Checkpoints of regions are persisted to the `kv_backend`,
+    /// which must be shared by all datanodes.
+    pub async fn try_new(config: &DatanodeKafkaConfig, kv_backend: KvBackendRef) -> Result<Self> {
+        let client_manager = Arc::new(ClientManager::try_new(config).await?);
+        let checkpoint_manager = Arc::new(CheckpointManager::new(kv_backend));
+        let checkpoint_task = RepeatedTask::new(
+            config.checkpoint_interval,
+            Box::new(PruneObsoleteRecordsFunction {
+                client_manager: client_manager.clone(),
+                checkpoint_manager: checkpoint_manager.clone(),
+                prune_obsolete_records: config.prune_obsolete_records,
+                pruned_entry_ids: HashMap::new(),
+            }),
+        );
+        checkpoint_task
+            .start(common_runtime::bg_runtime())
+            .context(StartGcTaskSnafu)?;
+
         Ok(Self {
-            client_manager: Arc::new(ClientManager::try_new(config).await?),
             config: config.clone(),
+            client_manager,
+            checkpoint_manager,
+            checkpoint_task,
         })
     }
 }
@@ -141,8 +248,32 @@ impl LogStore for KafkaLogStore {
             .await
             .context(GetOffsetSnafu { ns: ns.clone() })?
             - 1;
+        let earliest_offset = client
+            .get_offset(OffsetAt::Earliest)
+            .await
+            .context(GetEarliestOffsetSnafu { ns: ns.clone() })?;
         // Reads entries with offsets in the range [start_offset, end_offset].
-        let start_offset = Offset::try_from(entry_id)?.0;
+        let mut start_offset = Offset::try_from(entry_id)?.0;
+        if start_offset < earliest_offset {
+            // Records before the earliest offset have been deleted. Skipping them is only
+            // safe if they are obsolete for the region, otherwise entries are lost.
+            let checkpoint = self.checkpoint_manager.get(ns).await?;
+            ensure!(
+                checkpoint.is_some_and(|id| id as i64 + 1 >= earliest_offset),
+                EntriesPrunedSnafu {
+                    ns: ns.clone(),
+                    start_offset,
+                    earliest_offset,
+                }
+            );
+            start_offset = earliest_offset;
+        }
+        // Entries before `entry_id` are obsolete for the region. Regions created before
+        // checkpoints were introduced have no checkpoint, which prevents the topic from
+        // being pruned, so their checkpoints are initialized when they are opened.
+        if entry_id > 0 {
+            self.checkpoint_manager.init(ns, entry_id - 1).await?;
+        }
 
         debug!(
             "Start reading entries in range [{}, {}] for ns {}",
@@ -237,29 +368,57 @@ impl LogStore for KafkaLogStore {
     }
 
     /// Creates a new `Namespace` from the given ref.
-    async fn create_namespace(&self, _ns: &Self::Namespace) -> Result<()> {
+    ///
+    /// Records written before the namespace is created don't belong to it, so they are
+    /// obsolete for the namespace.
+    async fn create_namespace(&self, ns: &Self::Namespace) -> Result<()> {
+        let client = self
+            .client_manager
+            .get_or_insert(&ns.topic)
+            .await?
+            .raw_client;
+        let latest_offset = client
+            .get_offset(OffsetAt::Latest)
+            .await
+            .context(GetOffsetSnafu { ns: ns.clone() })?;
+        if latest_offset > 0 {
+            self.checkpoint_manager
+                .init(ns, latest_offset as u64 - 1)
+                .await?;
+        }
         Ok(())
     }
 
     /// Deletes an existing `Namespace` specified by the given ref.
-    async fn delete_namespace(&self, _ns: &Self::Namespace) -> Result<()> {
-        Ok(())
+    ///
+    /// Entries of the namespace are kept in the topic, but the namespace no longer prevents
+    /// records of the topic from being pruned.
+    async fn delete_namespace(&self, ns: &Self::Namespace) -> Result<()> {
+        self.checkpoint_manager.remove(ns).await
     }
 
-    /// Lists all existing namespaces.
+    /// Lists all existing namespaces, i.e. namespaces with checkpoints.
     async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>> {
-        Ok(vec![])
+        self.checkpoint_manager.namespaces().await
     }
 
     /// Marks all entries with ids `<=entry_id` of the given `namespace` as obsolete,
     /// so that the log store can safely delete those entries. This method does not guarantee
     /// that the obsolete entries are deleted immediately.
-    async fn obsolete(&self, _ns: Self::Namespace, _entry_id: EntryId) -> Result<()> {
+    ///
+    /// The entry id is persisted as the checkpoint of the region every `checkpoint_interval`.
+    /// If `prune_obsolete_records` is enabled, records of the topic are then deleted up to the
+    /// minimum checkpoint of all regions in the topic.
+    async fn obsolete(&self, ns: Self::Namespace, entry_id: EntryId) -> Result<()> {
+        self.checkpoint_manager.advance(&ns, entry_id);
         Ok(())
     }
 
     /// Stops components of the logstore.
     async fn stop(&self) -> Result<()> {
+        self.checkpoint_task.stop().await.context(StopGcTaskSnafu)?;
+        // Persists checkpoints advanced after the last run of the task.
+        let _ = self.checkpoint_manager.persist().await;
         Ok(())
     }
 }
@@ -288,6 +447,7 @@ fn check_termination(
 #[cfg(test)]
 mod tests {
     use common_base::readable_size::ReadableSize;
+    use common_meta::kv_backend::memory::MemoryKvBackend;
     use rand::seq::IteratorRandom;
 
     use super::*;
@@ -321,7 +481,9 @@ mod tests {
             max_batch_size: ReadableSize::kb(32),
             ..Default::default()
         };
-        let logstore = KafkaLogStore::try_new(&config).await.unwrap();
+        let logstore = KafkaLogStore::try_new(&config, Arc::new(MemoryKvBackend::new()))
+            .await
+            .unwrap();
 
         // Appends a no-op record to each topic.
         for topic in topics.iter() {
@@ -28,7 +28,7 @@ use common_meta::error::{self as meta_error, Result as MetaResult};
 use common_meta::rpc::ddl::{SubmitDdlTaskRequest, SubmitDdlTaskResponse};
 use common_meta::rpc::lock::{LockRequest, LockResponse, UnlockRequest};
 use common_meta::rpc::procedure::{
-    MigrateRegionRequest, MigrateRegionResponse, ProcedureStateResponse,
+    MigrateRegionRequest, MigrateRegionResponse, ProcedureDetail, ProcedureStateResponse,
 };
 use common_meta::rpc::store::{
     BatchDeleteRequest, BatchDeleteResponse, BatchGetRequest, BatchGetResponse, BatchPutRequest,
@@ -216,6 +216,20 @@ impl ProcedureExecutor for MetaClient {
             .map_err(BoxedError::new)
             .context(meta_error::ExternalSnafu)
     }
+
+    async fn cancel_procedure(&self, _ctx: &ExecutorContext, pid: &str) -> MetaResult<()> {
+        self.cancel_procedure(pid)
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
+
+    async fn list_procedures(&self, _ctx: &ExecutorContext) -> MetaResult<Vec<ProcedureDetail>> {
+        self.list_procedures()
+            .await
+            .map_err(BoxedError::new)
+            .context(meta_error::ExternalSnafu)
+    }
 }
 
 impl MetaClient {
@@ -358,6 +372,16 @@ impl MetaClient {
         self.procedure_client()?.query_procedure_state(pid).await
     }
 
+    /// Cancel the procedure by its id.
+    pub async fn cancel_procedure(&self, pid: &str) -> Result<()> {
+        self.procedure_client()?.cancel_procedure(pid).await
+    }
+
+    /// List procedures in the procedure manager of the metasrv leader.
+    pub async fn list_procedures(&self) -> Result<Vec<ProcedureDetail>> {
+        self.procedure_client()?.list_procedures().await
+    }
+
     /// Submit a region migration task.
     pub async fn migrate_region(
         &self,
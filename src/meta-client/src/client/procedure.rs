@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::future::Future;
+use std::future::{poll_fn, Future};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -21,11 +21,15 @@ use api::v1::meta::{
     DdlTaskRequest, DdlTaskResponse, ErrorCode, MigrateRegionRequest, MigrateRegionResponse,
     ProcedureId, ProcedureStateResponse, QueryProcedureRequest, ResponseHeader, Role,
 };
+use common_error::status_code::StatusCode;
 use common_grpc::channel_manager::ChannelManager;
+use common_meta::rpc::procedure::ProcedureDetail;
 use common_telemetry::tracing_context::TracingContext;
 use common_telemetry::{info, warn};
 use snafu::{ensure, ResultExt};
 use tokio::sync::RwLock;
+use tonic::body::empty_body;
+use tonic::codegen::{http, Service};
 use tonic::transport::Channel;
 use tonic::{Code, Status};
 
@@ -77,6 +81,26 @@ impl Client {
         inner.query_procedure_state(pid).await
     }
 
+    /// Cancel the procedure by its id
+    pub async fn cancel_procedure(&self, pid: &str) -> Result<()> {
+        // The id is a uuid, so it's safe to be put into the query string once it's parsed.
+        let pid = common_procedure::ProcedureId::parse_str(pid)
+            .context(error::ParseProcedureIdSnafu { pid })?;
+        let inner = self.inner.read().await;
+        let path = format!("{CANCEL_PROCEDURE_PATH}?id={pid}");
+        let _ = inner.admin_request("cancel procedure", &path).await?;
+        Ok(())
+    }
+
+    /// List procedures in the procedure manager of the metasrv leader
+    pub async fn list_procedures(&self) -> Result<Vec<ProcedureDetail>> {
+        let inner = self.inner.read().await;
+        let body = inner
+            .admin_request("list procedures", LIST_PROCEDURES_PATH)
+            .await?;
+        serde_json::from_str(&body).context(error::DeserializeFromJsonSnafu { input: body })
+    }
+
     /// Migrate the region from one datanode to the other datanode:
     /// - `region_id`:  the migrated region id
     /// - `from_peer`:  the source datanode id
@@ -207,6 +231,44 @@ impl Inner {
         .fail()
     }
 
+    /// Sends a request to the admin service of the metasrv leader, returns the body
+    /// of the response.
+    ///
+    /// The admin service shares the gRPC server of the metasrv, so the request is sent
+    /// through the same channel.
+    async fn admin_request(&self, task: &str, path: &str) -> Result<String> {
+        let ask_leader = self.ask_leader()?;
+        let mut times = 0;
+
+        while times < self.max_retry {
+            if let Some(leader) = &ask_leader.get_leader() {
+                let channel = self
+                    .channel_manager
+                    .get(leader)
+                    .context(error::CreateChannelSnafu)?;
+                match send_admin_request(channel, leader, path).await {
+                    Ok(body) => return Ok(body),
+                    Err(err @ error::Error::SendAdminRequest { .. }) => {
+                        // The leader may be unreachable.
+                        warn!("Failed to {task} to {leader}, source: {err}");
+                        let leader = ask_leader.ask_leader().await?;
+                        info!("Procedure client updated to new leader addr: {leader}");
+                        times += 1;
+                    }
+                    Err(err) => return Err(err),
+                }
+            } else if let Err(err) = ask_leader.ask_leader().await {
+                return Err(err);
+            }
+        }
+
+        error::RetryTimesExceededSnafu {
+            msg: format!("Failed to {task}"),
+            times: self.max_retry,
+        }
+        .fail()
+    }
+
     async fn migrate_region(
         &self,
         region_id: u64,
@@ -283,6 +345,42 @@ impl Inner {
     }
 }
 
+/// The path to list procedures in the admin service of the metasrv.
+const LIST_PROCEDURES_PATH: &str = "/admin/procedures";
+/// The path to cancel a procedure in the admin service of the metasrv.
+const CANCEL_PROCEDURE_PATH: &str = "/admin/procedures/cancel";
+
+/// Sends a request to the admin service of the metasrv. The admin service is served by the
+/// gRPC server of the metasrv (see `meta_srv::service::admin`), so the request is sent through
+/// the gRPC channel to the leader.
+async fn send_admin_request(mut channel: Channel, addr: &str, path: &str) -> Result<String> {
+    let request = http::Request::post(format!("http://{addr}{path}"))
+        .body(empty_body())
+        .context(error::BuildAdminRequestSnafu { path })?;
+    poll_fn(|cx| channel.poll_ready(cx))
+        .await
+        .context(error::SendAdminRequestSnafu { path })?;
+    let response = channel
+        .call(request)
+        .await
+        .context(error::SendAdminRequestSnafu { path })?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .context(error::ReadAdminResponseSnafu { path })?;
+    let body = String::from_utf8_lossy(&body).to_string();
+    if !status.is_success() {
+        return error::MetaServerSnafu {
+            code: StatusCode::Internal,
+            msg: body,
+        }
+        .fail();
+    }
+
+    Ok(body)
+}
+
 fn is_unreachable(status: &Status) -> bool {
     status.code() == Code::Unavailable || status.code() == Code::DeadlineExceeded
 }
@@ -296,3 +394,20 @@ fn is_not_leader(header: &Option<ResponseHeader>) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_procedure_with_invalid_id() {
+        let mut client = Client::new((0, 0), Role::Frontend, ChannelManager::default(), 3);
+        client.start(&["127.0.0.1:1000"]).await.unwrap();
+
+        let res = client.cancel_procedure("1&id=2").await;
+        assert!(matches!(
+            res.err(),
+            Some(error::Error::ParseProcedureId { .. })
+        ));
+    }
+}
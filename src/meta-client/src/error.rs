@@ -77,6 +77,45 @@ pub enum Error {
 
     #[snafu(display("Retry exceeded max times({}), message: {}", times, msg))]
     RetryTimesExceeded { times: usize, msg: String },
+
+    #[snafu(display("Failed to build admin request, path: {}", path))]
+    BuildAdminRequest {
+        path: String,
+        #[snafu(source)]
+        error: tonic::codegen::http::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to send admin request, path: {}", path))]
+    SendAdminRequest {
+        path: String,
+        #[snafu(source)]
+        error: tonic::transport::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to read admin response, path: {}", path))]
+    ReadAdminResponse {
+        path: String,
+        #[snafu(source)]
+        error: hyper::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to deserialize from json: {}", input))]
+    DeserializeFromJson {
+        input: String,
+        #[snafu(source)]
+        error: serde_json::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid procedure id: {}", pid))]
+    ParseProcedureId {
+        pid: String,
+        source: common_procedure::ParseIdError,
+        location: Location,
+    },
 }
 
 #[allow(dead_code)]
@@ -97,10 +136,16 @@ impl ErrorExt for Error {
             | Error::SendHeartbeat { .. }
             | Error::CreateHeartbeatStream { .. }
             | Error::CreateChannel { .. }
-            | Error::RetryTimesExceeded { .. } => StatusCode::Internal,
+            | Error::RetryTimesExceeded { .. }
+            | Error::BuildAdminRequest { .. }
+            | Error::SendAdminRequest { .. }
+            | Error::ReadAdminResponse { .. }
+            | Error::DeserializeFromJson { .. } => StatusCode::Internal,
 
             Error::MetaServer { code, .. } => *code,
 
+            Error::ParseProcedureId { .. } => StatusCode::InvalidArguments,
+
             Error::InvalidResponseHeader { source, .. }
             | Error::ConvertMetaRequest { source, .. }
             | Error::ConvertMetaResponse { source, .. } => source.status_code(),
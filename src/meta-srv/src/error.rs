@@ -85,6 +85,19 @@ pub enum Error {
         source: common_meta::error::Error,
     },
 
+    #[snafu(display("Failed to cancel procedure: {pid}"))]
+    CancelProcedure {
+        pid: String,
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
+    #[snafu(display("Failed to list procedures"))]
+    ListProcedures {
+        location: Location,
+        source: common_meta::error::Error,
+    },
+
     #[snafu(display("Failed to invalidate table cache"))]
     InvalidateTableCache {
         location: Location,
@@ -760,7 +773,9 @@ impl ErrorExt for Error {
 
             Error::RegisterProcedureLoader { source, .. } => source.status_code(),
             Error::OperateRegion { source, .. } => source.status_code(),
-            Error::SubmitDdlTask { source, .. } => source.status_code(),
+            Error::SubmitDdlTask { source, .. }
+            | Error::CancelProcedure { source, .. }
+            | Error::ListProcedures { source, .. } => source.status_code(),
             Error::TableRouteConversion { source, .. }
             | Error::ConvertProtoData { source, .. }
             | Error::TableMetadataManager { source, .. }
@@ -44,6 +44,7 @@ use common_procedure::error::{
     Error as ProcedureError, FromJsonSnafu, Result as ProcedureResult, ToJsonSnafu,
 };
 use common_procedure::{Context as ProcedureContext, LockKey, Procedure, Status, StringKey};
+use common_telemetry::{info, warn};
 pub use manager::RegionMigrationProcedureTask;
 use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt};
@@ -51,6 +52,7 @@ use store_api::storage::RegionId;
 use tokio::time::Instant;
 
 use self::migration_start::RegionMigrationStart;
+use self::update_metadata::UpdateMetadata;
 use crate::error::{self, Result};
 use crate::service::mailbox::{BroadcastChannel, MailboxRef};
 
@@ -402,6 +404,49 @@ impl RegionMigrationProcedure {
 
         Ok(Self { state, context })
     }
+
+    /// Rolls back the downgraded leader region if the candidate region isn't upgraded yet,
+    /// so that the leader region can serve writes again.
+    ///
+    /// Retry:
+    /// - Failed to retrieve or update the metadata of table.
+    async fn rollback_inner(&mut self) -> Result<()> {
+        // Deregisters the opening candidate region.
+        let _ = self.context.volatile_ctx.opening_region_guard.take();
+        // The cached table route may be outdated.
+        self.context.remove_table_route_value();
+
+        let region_id = self.context.region_id();
+        let from_peer_id = self.context.persistent_ctx.from_peer.id;
+        let table_route_value = self.context.get_table_route_value().await?;
+        let region_routes =
+            table_route_value
+                .region_routes()
+                .context(error::UnexpectedLogicalRouteTableSnafu {
+                    err_msg: format!("{region_id} belongs to a non-physical TableRouteValue."),
+                })?;
+        let downgraded = region_routes.iter().any(|route| {
+            route.region.id == region_id
+                && route.is_leader_downgraded()
+                && route
+                    .leader_peer
+                    .as_ref()
+                    .is_some_and(|peer| peer.id == from_peer_id)
+        });
+        if !downgraded {
+            return Ok(());
+        }
+
+        UpdateMetadata::Rollback
+            .rollback_downgraded_region(&mut self.context)
+            .await?;
+        if let Err(err) = self.context.invalidate_table_cache().await {
+            warn!("Failed to broadcast the invalidate table cache message during the rollback, error: {err:?}");
+        };
+        info!("Rolled back the downgraded leader region: {region_id}");
+
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -425,6 +470,20 @@ impl Procedure for RegionMigrationProcedure {
         Ok(status)
     }
 
+    async fn rollback(&mut self, _ctx: &ProcedureContext) -> ProcedureResult<()> {
+        self.rollback_inner().await.map_err(|e| {
+            if e.is_retryable() {
+                ProcedureError::retry_later(e)
+            } else {
+                ProcedureError::external(e)
+            }
+        })
+    }
+
+    fn rollback_supported(&self) -> bool {
+        true
+    }
+
     fn dump(&self) -> ProcedureResult<String> {
         let data = RegionMigrationData {
             state: self.state.as_ref(),
@@ -445,10 +504,9 @@ mod tests {
 
     use common_meta::distributed_time_constants::REGION_LEASE_SECS;
     use common_meta::key::test_utils::new_test_table_info;
-    use common_meta::rpc::router::{Region, RegionRoute};
+    use common_meta::rpc::router::{Region, RegionRoute, RegionStatus};
 
     use super::migration_end::RegionMigrationEnd;
-    use super::update_metadata::UpdateMetadata;
     use super::*;
     use crate::handler::HeartbeatMailbox;
     use crate::procedure::region_migration::open_candidate_region::OpenCandidateRegion;
@@ -586,6 +644,55 @@ mod tests {
         assert_matches!(instruction, Instruction::InvalidateTableIdCache(1024));
     }
 
+    #[tokio::test]
+    async fn test_rollback_downgraded_leader_region() {
+        let env = TestingEnv::new();
+        let persistent_context = new_persistent_context();
+        let from_peer = persistent_context.from_peer.clone();
+        let to_peer = persistent_context.to_peer.clone();
+        let table_id = persistent_context.region_id.table_id();
+
+        let table_info = new_test_table_info(1024, vec![1, 2]).into();
+        let region_routes = vec![
+            RegionRoute {
+                region: Region::new_test(RegionId::new(1024, 1)),
+                leader_peer: Some(from_peer.clone()),
+                leader_status: Some(RegionStatus::Downgraded),
+                ..Default::default()
+            },
+            RegionRoute {
+                region: Region::new_test(RegionId::new(1024, 2)),
+                leader_peer: Some(to_peer),
+                leader_status: Some(RegionStatus::Downgraded),
+                ..Default::default()
+            },
+        ];
+        env.create_physical_table_metadata(table_info, region_routes)
+            .await;
+
+        let ctx = TestingEnv::procedure_context();
+        let mut procedure =
+            RegionMigrationProcedure::new(persistent_context, env.context_factory());
+        assert!(procedure.rollback_supported());
+        procedure.rollback(&ctx).await.unwrap();
+
+        let table_route = env
+            .table_metadata_manager()
+            .table_route_manager()
+            .table_route_storage()
+            .get(table_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let region_routes = table_route.region_routes().unwrap();
+        // Only the migrating region is rolled back.
+        assert!(!region_routes[0].is_leader_downgraded());
+        assert!(region_routes[1].is_leader_downgraded());
+
+        // It's idempotent.
+        procedure.rollback(&ctx).await.unwrap();
+    }
+
     fn procedure_flow_steps(from_peer_id: u64, to_peer_id: u64) -> Vec<Step> {
         vec![
             // MigrationStart
@@ -18,6 +18,7 @@ mod leader;
 mod meta;
 // TODO(weny): removes it.
 mod node_lease;
+mod procedure;
 #[allow(dead_code)]
 mod region_migration;
 mod route;
@@ -99,6 +100,20 @@ pub fn make_admin_service(meta_srv: MetaSrv) -> Admin {
     };
     let router = router.route("/region-migration", handler);
 
+    let router = router
+        .route(
+            procedure::LIST_PROCEDURES_PATH,
+            procedure::ListProceduresHandler {
+                procedure_executor: meta_srv.procedure_executor().clone(),
+            },
+        )
+        .route(
+            procedure::CANCEL_PROCEDURE_PATH,
+            procedure::CancelProcedureHandler {
+                procedure_executor: meta_srv.procedure_executor().clone(),
+            },
+        );
+
     let router = Router::nest("/admin", router);
 
     Admin::new(router)
@@ -0,0 +1,75 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_meta::ddl::{ExecutorContext, ProcedureExecutorRef};
+use snafu::ResultExt;
+use tonic::codegen::http;
+
+use crate::error::{self, Result};
+use crate::service::admin::{util, HttpHandler};
+
+/// The path to list procedures.
+pub const LIST_PROCEDURES_PATH: &str = "/procedures";
+/// The path to cancel a procedure, the procedure id is passed by the `id` parameter.
+pub const CANCEL_PROCEDURE_PATH: &str = "/procedures/cancel";
+
+/// The handler of listing procedures in the metasrv, procedures are returned as
+/// a JSON array of [ProcedureDetail](common_meta::rpc::procedure::ProcedureDetail).
+pub struct ListProceduresHandler {
+    pub procedure_executor: ProcedureExecutorRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for ListProceduresHandler {
+    async fn handle(&self, _: &str, _: &HashMap<String, String>) -> Result<http::Response<String>> {
+        let procedures = self
+            .procedure_executor
+            .list_procedures(&ExecutorContext::default())
+            .await
+            .context(error::ListProceduresSnafu)?;
+        let body =
+            serde_json::to_string(&procedures).with_context(|_| error::SerializeToJsonSnafu {
+                input: format!("{procedures:?}"),
+            })?;
+
+        http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(body)
+            .context(error::InvalidHttpBodySnafu)
+    }
+}
+
+/// The handler of cancelling a procedure in the metasrv.
+pub struct CancelProcedureHandler {
+    pub procedure_executor: ProcedureExecutorRef,
+}
+
+#[async_trait::async_trait]
+impl HttpHandler for CancelProcedureHandler {
+    async fn handle(
+        &self,
+        _: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<http::Response<String>> {
+        let pid = util::get_value(params, "id")?;
+        self.procedure_executor
+            .cancel_procedure(&ExecutorContext::default(), pid)
+            .await
+            .context(error::CancelProcedureSnafu { pid })?;
+
+        util::to_text_response("Ok")
+    }
+}
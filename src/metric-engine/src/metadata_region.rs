@@ -348,6 +348,7 @@ impl MetadataRegion {
             filters: vec![],
             output_ordering: None,
             limit: None,
+            time_travel: None,
         };
         let record_batch_stream = self
             .mito
@@ -405,6 +406,7 @@ impl MetadataRegion {
             filters: vec![filter_expr.into()],
             output_ordering: None,
             limit: None,
+            time_travel: None,
         }
     }
 
@@ -565,6 +567,7 @@ mod test {
             filters: vec![expected_filter_expr.into()],
             output_ordering: None,
             limit: None,
+            time_travel: None,
         };
         let actual_scan_request = MetadataRegion::build_read_request(key);
         assert_eq!(actual_scan_request, expected_scan_request);
@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 use object_store::services::Fs;
 use object_store::util::{join_dir, with_instrument_layers};
 use object_store::ObjectStore;
+use puffin::blob_metadata::CompressionCodec;
 use snafu::ResultExt;
 use store_api::metadata::RegionMetadataRef;
 
@@ -35,6 +37,30 @@ use crate::sst::parquet::{SstInfo, WriteOptions};
 
 pub type AccessLayerRef = Arc<AccessLayer>;
 
+/// Unregisters files being written from the [AccessLayer] on drop.
+#[derive(Debug)]
+pub(crate) struct WritingFilesGuard {
+    writing_files: Arc<Mutex<HashSet<FileId>>>,
+    file_ids: Vec<FileId>,
+}
+
+impl WritingFilesGuard {
+    /// Marks another file as being written.
+    pub(crate) fn add(&mut self, file_id: FileId) {
+        self.writing_files.lock().unwrap().insert(file_id);
+        self.file_ids.push(file_id);
+    }
+}
+
+impl Drop for WritingFilesGuard {
+    fn drop(&mut self) {
+        let mut writing_files = self.writing_files.lock().unwrap();
+        for file_id in &self.file_ids {
+            writing_files.remove(file_id);
+        }
+    }
+}
+
 /// A layer to access SST files under the same directory.
 pub struct AccessLayer {
     region_dir: String,
@@ -42,6 +68,8 @@ pub struct AccessLayer {
     object_store: ObjectStore,
     /// Intermediate manager for inverted index.
     intermediate_manager: IntermediateManager,
+    /// Ids of files being written whose edits are not committed to the manifest yet.
+    writing_files: Arc<Mutex<HashSet<FileId>>>,
 }
 
 impl std::fmt::Debug for AccessLayer {
@@ -63,6 +91,7 @@ impl AccessLayer {
             region_dir: region_dir.into(),
             object_store,
             intermediate_manager,
+            writing_files: Arc::default(),
         }
     }
 
@@ -76,6 +105,34 @@ impl AccessLayer {
         &self.object_store
     }
 
+    /// Returns the intermediate manager of the layer.
+    pub(crate) fn intermediate_manager(&self) -> &IntermediateManager {
+        &self.intermediate_manager
+    }
+
+    /// Marks files with `file_ids` as being written until the returned guard is dropped.
+    ///
+    /// Flush, compaction and index build jobs hold the guard until their outputs are
+    /// committed so orphan file gc never deletes them.
+    pub(crate) fn register_writing_files(
+        &self,
+        file_ids: impl IntoIterator<Item = FileId>,
+    ) -> WritingFilesGuard {
+        let mut guard = WritingFilesGuard {
+            writing_files: self.writing_files.clone(),
+            file_ids: Vec::new(),
+        };
+        for file_id in file_ids {
+            guard.add(file_id);
+        }
+        guard
+    }
+
+    /// Returns ids of files being written.
+    pub(crate) fn writing_files(&self) -> HashSet<FileId> {
+        self.writing_files.lock().unwrap().clone()
+    }
+
     /// Deletes a SST file (and its index file if it has one) with given file id.
     pub(crate) async fn delete_sst(&self, file_meta: &FileMeta) -> Result<()> {
         let path = location::sst_file_path(&self.region_dir, file_meta.file_id);
@@ -86,19 +143,24 @@ impl AccessLayer {
                 file_id: file_meta.file_id,
             })?;
 
-        if file_meta.inverted_index_available() {
-            let path = location::index_file_path(&self.region_dir, file_meta.file_id);
-            self.object_store
-                .delete(&path)
-                .await
-                .context(DeleteIndexSnafu {
-                    file_id: file_meta.file_id,
-                })?;
+        if file_meta.index_file_available() {
+            self.delete_index(file_meta).await?;
         }
 
         Ok(())
     }
 
+    /// Deletes the index file of the SST.
+    pub(crate) async fn delete_index(&self, file_meta: &FileMeta) -> Result<()> {
+        let path = location::index_file_path(&self.region_dir, file_meta.index_id());
+        self.object_store
+            .delete(&path)
+            .await
+            .context(DeleteIndexSnafu {
+                file_id: file_meta.file_id,
+            })
+    }
+
     /// Returns a reader builder for specific `file`.
     pub(crate) fn read_sst(&self, file: FileHandle) -> ParquetReaderBuilder {
         ParquetReaderBuilder::new(self.region_dir.clone(), file, self.object_store.clone())
@@ -137,6 +199,7 @@ impl AccessLayer {
                 create_inverted_index: request.create_inverted_index,
                 mem_threshold_index_create: request.mem_threshold_index_create,
                 write_buffer_size: request.index_write_buffer_size,
+                compression: request.index_compression,
                 file_id,
                 file_path: index_file_path,
                 metadata: &request.metadata,
@@ -188,6 +251,8 @@ pub(crate) struct SstWriteRequest {
     pub(crate) mem_threshold_index_create: Option<usize>,
     /// The size of write buffer for index.
     pub(crate) index_write_buffer_size: Option<usize>,
+    /// The codec to compress index blobs.
+    pub(crate) index_compression: Option<CompressionCodec>,
     /// The options of the index for the region.
     pub(crate) index_options: IndexOptions,
 }
@@ -19,6 +19,7 @@ mod cache_size;
 pub(crate) mod file_cache;
 #[cfg(test)]
 pub(crate) mod test_util;
+pub(crate) mod warm_up;
 pub(crate) mod write_cache;
 
 use std::mem;
@@ -27,6 +28,7 @@ use std::sync::Arc;
 use datatypes::value::Value;
 use datatypes::vectors::VectorRef;
 use moka::sync::Cache;
+use object_store::ObjectStore;
 use parquet::column::page::Page;
 use parquet::file::metadata::ParquetMetaData;
 use store_api::storage::RegionId;
@@ -59,6 +61,8 @@ pub struct CacheManager {
     page_cache: Option<PageCache>,
     /// A Cache for writing files to object stores.
     write_cache: Option<WriteCacheRef>,
+    /// Whether to cache SSTs read from object stores in the write cache.
+    read_cache: bool,
 }
 
 pub type CacheManagerRef = Arc<CacheManager>;
@@ -162,6 +166,21 @@ impl CacheManager {
     pub(crate) fn write_cache(&self) -> Option<&WriteCacheRef> {
         self.write_cache.as_ref()
     }
+
+    /// Caches the file read from the `remote_store` in background if the read cache is enabled.
+    pub(crate) fn maybe_cache_remote_file(
+        &self,
+        key: IndexKey,
+        remote_path: &str,
+        remote_store: &ObjectStore,
+    ) {
+        if !self.read_cache {
+            return;
+        }
+        if let Some(write_cache) = &self.write_cache {
+            write_cache.download_in_background(key, remote_path, remote_store);
+        }
+    }
 }
 
 /// Builder to construct a [CacheManager].
@@ -171,6 +190,7 @@ pub struct CacheManagerBuilder {
     vector_cache_size: u64,
     page_cache_size: u64,
     write_cache: Option<WriteCacheRef>,
+    read_cache: bool,
 }
 
 impl CacheManagerBuilder {
@@ -198,6 +218,12 @@ impl CacheManagerBuilder {
         self
     }
 
+    /// Sets whether to cache SSTs read from object stores in the write cache.
+    pub fn read_cache(mut self, enable: bool) -> Self {
+        self.read_cache = enable;
+        self
+    }
+
     /// Builds the [CacheManager].
     pub fn build(self) -> CacheManager {
         let sst_meta_cache = (self.sst_meta_cache_size != 0).then(|| {
@@ -240,6 +266,7 @@ impl CacheManagerBuilder {
             vector_cache,
             page_cache,
             write_cache: self.write_cache,
+            read_cache: self.read_cache,
         }
     }
 }
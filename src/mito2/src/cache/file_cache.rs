@@ -167,7 +167,6 @@ impl FileCache {
         }
     }
 
-    #[allow(unused)]
     /// Removes a file from the cache explicitly.
     pub(crate) async fn remove(&self, key: IndexKey) {
         let file_path = self.cache_file_path(key);
@@ -272,7 +271,6 @@ impl FileCache {
     }
 
     /// Checks if the key is in the file cache.
-    #[cfg(test)]
     pub(crate) fn contains_key(&self, key: &IndexKey) -> bool {
         self.memory_index.contains_key(key)
     }
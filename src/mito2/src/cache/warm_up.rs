@@ -0,0 +1,371 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Warms up caches for a newly opened region.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{info, warn};
+
+use crate::access_layer::AccessLayerRef;
+use crate::cache::file_cache::{FileType, IndexKey};
+use crate::cache::CacheManagerRef;
+use crate::config::MitoConfig;
+use crate::error::Result;
+use crate::region::version::VersionRef;
+use crate::region::MitoRegionRef;
+use crate::schedule::scheduler::{LocalScheduler, Scheduler};
+use crate::sst::file::FileId;
+use crate::sst::location;
+use crate::sst::parquet::metadata::MetadataLoader;
+
+/// Bytes that warm-ups of all regions in the engine can still download.
+#[derive(Debug)]
+pub(crate) struct WarmUpBudget {
+    remaining: AtomicU64,
+}
+
+impl WarmUpBudget {
+    pub(crate) fn new(budget: ReadableSize) -> WarmUpBudget {
+        WarmUpBudget {
+            remaining: AtomicU64::new(budget.as_bytes()),
+        }
+    }
+
+    /// Takes `bytes` from the budget. Returns false if the budget is not enough.
+    fn try_acquire(&self, bytes: u64) -> bool {
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                remaining.checked_sub(bytes)
+            })
+            .is_ok()
+    }
+
+    /// Returns `bytes` to the budget.
+    fn release(&self, bytes: u64) {
+        self.remaining.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Runs warm-ups of regions in the background.
+///
+/// Warm-ups have their own concurrency limit so they don't delay flush and
+/// compaction jobs, and they share a global download budget so opening many
+/// regions doesn't flood the write cache.
+pub(crate) struct WarmUpScheduler {
+    scheduler: LocalScheduler,
+    global_budget: Arc<WarmUpBudget>,
+    /// Download budget of each region.
+    region_budget: ReadableSize,
+}
+
+pub(crate) type WarmUpSchedulerRef = Arc<WarmUpScheduler>;
+
+impl WarmUpScheduler {
+    pub(crate) fn new(config: &MitoConfig) -> WarmUpScheduler {
+        WarmUpScheduler {
+            scheduler: LocalScheduler::new(config.max_background_warm_ups),
+            global_budget: Arc::new(WarmUpBudget::new(config.global_cache_warm_up_size)),
+            region_budget: config.cache_warm_up_size,
+        }
+    }
+
+    /// Schedules a job to warm up caches of the `region`.
+    pub(crate) fn schedule(
+        &self,
+        region: &MitoRegionRef,
+        cache_manager: CacheManagerRef,
+    ) -> Result<()> {
+        let access_layer = region.access_layer.clone();
+        let version = region.version();
+        let global_budget = self.global_budget.clone();
+        let region_budget = self.region_budget;
+        self.scheduler.schedule(Box::pin(async move {
+            warm_up_region(
+                &access_layer,
+                &version,
+                &cache_manager,
+                region_budget,
+                &global_budget,
+            )
+            .await;
+        }))
+    }
+
+    /// Stops the scheduler without waiting for pending warm-ups.
+    pub(crate) async fn stop(&self) -> Result<()> {
+        self.scheduler.stop(false).await
+    }
+}
+
+/// Statistics of a warm-up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WarmUpStats {
+    /// Number of files whose metadata are loaded from the remote store.
+    pub(crate) num_metadata: usize,
+    /// Number of files downloaded to the write cache.
+    pub(crate) num_downloaded: usize,
+    /// Bytes of files downloaded to the write cache.
+    pub(crate) downloaded_bytes: u64,
+}
+
+/// Warms up caches for SSTs in the `version`.
+///
+/// It loads parquet metadata of all SSTs into the SST meta cache. If the write
+/// cache is enabled, it also downloads index files and then SSTs from the most
+/// recent time window until `budget` bytes are downloaded or the `global_budget`
+/// runs out.
+pub(crate) async fn warm_up_region(
+    access_layer: &AccessLayerRef,
+    version: &VersionRef,
+    cache_manager: &CacheManagerRef,
+    budget: ReadableSize,
+    global_budget: &WarmUpBudget,
+) -> WarmUpStats {
+    let region_id = version.metadata.region_id;
+    let start = Instant::now();
+    let mut stats = WarmUpStats::default();
+
+    let mut files: Vec<_> = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files().map(|file| file.meta()))
+        .collect();
+    // Queries usually read recent data.
+    files.sort_unstable_by(|a, b| b.time_range.1.cmp(&a.time_range.1));
+
+    if let Some(write_cache) = cache_manager.write_cache() {
+        let mut remaining = budget.as_bytes();
+        // Index files are small and help to prune all SSTs so we download them first.
+        let index_keys = files
+            .iter()
+            .filter(|file| file.index_file_available())
+            .map(|file| (file.index_id(), FileType::Puffin, file.index_file_size));
+        let sst_keys = files
+            .iter()
+            .map(|file| (file.file_id, FileType::Parquet, file.file_size));
+        for (file_id, file_type, file_size) in index_keys.chain(sst_keys) {
+            if file_size > remaining {
+                continue;
+            }
+            let key = IndexKey::new(region_id, file_id, file_type);
+            if write_cache.file_cache().contains_key(&key) {
+                continue;
+            }
+            if !global_budget.try_acquire(file_size) {
+                continue;
+            }
+
+            let remote_path = remote_file_path(access_layer, file_id, file_type);
+            match write_cache
+                .download(key, &remote_path, access_layer.object_store())
+                .await
+            {
+                Ok(Some(bytes)) => {
+                    remaining = remaining.saturating_sub(bytes);
+                    stats.num_downloaded += 1;
+                    stats.downloaded_bytes += bytes;
+                }
+                Ok(None) => {
+                    // Another task is downloading the file.
+                    global_budget.release(file_size);
+                }
+                Err(e) => {
+                    global_budget.release(file_size);
+                    warn!(e; "Failed to download file {} to cache during warm-up", remote_path);
+                }
+            }
+        }
+    }
+
+    for file in &files {
+        if cache_manager
+            .get_parquet_meta_data(region_id, file.file_id)
+            .await
+            .is_some()
+        {
+            continue;
+        }
+
+        let file_path = location::sst_file_path(access_layer.region_dir(), file.file_id);
+        let loader = MetadataLoader::new(
+            access_layer.object_store().clone(),
+            &file_path,
+            file.file_size,
+        );
+        match loader.load().await {
+            Ok(metadata) => {
+                cache_manager.put_parquet_meta_data(region_id, file.file_id, Arc::new(metadata));
+                stats.num_metadata += 1;
+            }
+            Err(e) => {
+                warn!(e; "Failed to load metadata of file {} during warm-up", file_path);
+            }
+        }
+    }
+
+    info!(
+        "Region {} warm up finished, stats: {:?}, cost: {:?}",
+        region_id,
+        stats,
+        start.elapsed()
+    );
+
+    stats
+}
+
+fn remote_file_path(access_layer: &AccessLayerRef, file_id: FileId, file_type: FileType) -> String {
+    match file_type {
+        FileType::Parquet => location::sst_file_path(access_layer.region_dir(), file_id),
+        FileType::Puffin => location::index_file_path(access_layer.region_dir(), file_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::Rows;
+    use common_test_util::temp_dir::create_temp_dir;
+    use store_api::region_engine::RegionEngine;
+    use store_api::region_request::RegionRequest;
+    use store_api::storage::RegionId;
+
+    use super::*;
+    use crate::cache::test_util::new_fs_store;
+    use crate::cache::CacheManager;
+    use crate::config::MitoConfig;
+    use crate::test_util::{
+        build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+    };
+
+    #[tokio::test]
+    async fn test_warm_up_region() {
+        let mut env = TestEnv::new();
+        let engine = env.create_engine(MitoConfig::default()).await;
+
+        let region_id = RegionId::new(1, 1);
+        let request = CreateRequestBuilder::new().build();
+        let column_schemas = rows_schema(&request);
+        engine
+            .handle_request(region_id, RegionRequest::Create(request))
+            .await
+            .unwrap();
+        let rows = Rows {
+            schema: column_schemas,
+            rows: build_rows(0, 3),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+
+        let region = engine.get_region(region_id).unwrap();
+        let version = region.version();
+        let file = version.ssts.levels()[0].files().next().unwrap().meta();
+
+        // Nothing is downloaded without budget.
+        let global_budget = WarmUpBudget::new(ReadableSize::mb(10));
+        let cache_manager = Arc::new(
+            CacheManager::builder()
+                .sst_meta_cache_size(ReadableSize::mb(1).as_bytes())
+                .build(),
+        );
+        let stats = warm_up_region(
+            &region.access_layer,
+            &version,
+            &cache_manager,
+            ReadableSize(0),
+            &global_budget,
+        )
+        .await;
+        assert_eq!(
+            WarmUpStats {
+                num_metadata: 1,
+                num_downloaded: 0,
+                downloaded_bytes: 0,
+            },
+            stats
+        );
+        assert!(cache_manager
+            .get_parquet_meta_data(region_id, file.file_id)
+            .await
+            .is_some());
+
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let write_cache = env
+            .create_write_cache(local_store, ReadableSize::mb(10))
+            .await;
+        let cache_manager = Arc::new(
+            CacheManager::builder()
+                .sst_meta_cache_size(ReadableSize::mb(1).as_bytes())
+                .write_cache(Some(write_cache.clone()))
+                .build(),
+        );
+        let stats = warm_up_region(
+            &region.access_layer,
+            &version,
+            &cache_manager,
+            ReadableSize::mb(1),
+            &global_budget,
+        )
+        .await;
+        let mut expect_bytes = file.file_size;
+        let puffin_key = IndexKey::new(region_id, file.index_id(), FileType::Puffin);
+        if file.index_file_available() {
+            expect_bytes += file.index_file_size;
+            assert!(write_cache.file_cache().contains_key(&puffin_key));
+        }
+        let parquet_key = IndexKey::new(region_id, file.file_id, FileType::Parquet);
+        assert!(write_cache.file_cache().contains_key(&parquet_key));
+        // Metadata is loaded from the write cache.
+        assert_eq!(0, stats.num_metadata);
+        assert_eq!(expect_bytes, stats.downloaded_bytes);
+
+        // Files already in the cache are skipped.
+        let stats = warm_up_region(
+            &region.access_layer,
+            &version,
+            &cache_manager,
+            ReadableSize::mb(1),
+            &global_budget,
+        )
+        .await;
+        assert_eq!(WarmUpStats::default(), stats);
+
+        // Nothing is downloaded if the global budget runs out.
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let write_cache = env
+            .create_write_cache(local_store, ReadableSize::mb(10))
+            .await;
+        let cache_manager = Arc::new(
+            CacheManager::builder()
+                .write_cache(Some(write_cache.clone()))
+                .build(),
+        );
+        let global_budget = WarmUpBudget::new(ReadableSize(file.file_size - 1));
+        let stats = warm_up_region(
+            &region.access_layer,
+            &version,
+            &cache_manager,
+            ReadableSize::mb(1),
+            &global_budget,
+        )
+        .await;
+        assert!(!write_cache.file_cache().contains_key(&parquet_key));
+        assert!(stats.downloaded_bytes < file.file_size);
+    }
+}
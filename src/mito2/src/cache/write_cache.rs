@@ -14,24 +14,29 @@
 
 //! A write-through cache for remote object stores.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 use common_base::readable_size::ReadableSize;
-use common_telemetry::{debug, info};
+use common_telemetry::{debug, info, warn};
 use object_store::manager::ObjectStoreManagerRef;
 use object_store::ObjectStore;
 use snafu::ResultExt;
+use tokio::sync::Semaphore;
 
 use crate::access_layer::{new_fs_object_store, SstWriteRequest};
 use crate::cache::file_cache::{FileCache, FileCacheRef, FileType, IndexKey, IndexValue};
 use crate::error::{self, Result};
-use crate::metrics::{FLUSH_ELAPSED, UPLOAD_BYTES_TOTAL};
+use crate::metrics::{DOWNLOAD_BYTES_TOTAL, FLUSH_ELAPSED, UPLOAD_BYTES_TOTAL};
 use crate::sst::index::intermediate::IntermediateManager;
 use crate::sst::index::IndexerBuilder;
 use crate::sst::parquet::writer::ParquetWriter;
 use crate::sst::parquet::{SstInfo, WriteOptions};
 use crate::sst::{DEFAULT_WRITE_BUFFER_SIZE, DEFAULT_WRITE_CONCURRENCY};
 
+/// Max number of files the read path downloads to the cache concurrently.
+const MAX_BACKGROUND_DOWNLOADS: usize = 4;
+
 /// A cache for uploading files to remote object stores.
 ///
 /// It keeps files in local disk and then sends files to object stores.
@@ -44,6 +49,10 @@ pub struct WriteCache {
     object_store_manager: ObjectStoreManagerRef,
     /// Intermediate manager for inverted index.
     intermediate_manager: IntermediateManager,
+    /// Files being downloaded.
+    downloading: Arc<Mutex<HashSet<IndexKey>>>,
+    /// Limits the number of background downloads.
+    download_semaphore: Arc<Semaphore>,
 }
 
 pub type WriteCacheRef = Arc<WriteCache>;
@@ -64,6 +73,8 @@ impl WriteCache {
             file_cache: Arc::new(file_cache),
             object_store_manager,
             intermediate_manager,
+            downloading: Arc::new(Mutex::new(HashSet::new())),
+            download_semaphore: Arc::new(Semaphore::new(MAX_BACKGROUND_DOWNLOADS)),
         })
     }
 
@@ -111,6 +122,7 @@ impl WriteCache {
             create_inverted_index: write_request.create_inverted_index,
             mem_threshold_index_create: write_request.mem_threshold_index_create,
             write_buffer_size: write_request.index_write_buffer_size,
+            compression: write_request.index_compression,
             file_id,
             file_path: self.file_cache.cache_file_path(puffin_key),
             metadata: &write_request.metadata,
@@ -143,7 +155,7 @@ impl WriteCache {
         let remote_store = &upload_request.remote_store;
         self.upload(parquet_key, parquet_path, remote_store).await?;
 
-        if sst_info.inverted_index_available {
+        if !sst_info.available_indexes.is_empty() {
             let puffin_key = IndexKey::new(region_id, file_id, FileType::Puffin);
             let puffin_path = &upload_request.index_upload_path;
             self.upload(puffin_key, puffin_path, remote_store).await?;
@@ -215,6 +227,141 @@ impl WriteCache {
 
         Ok(())
     }
+
+    /// Downloads a Parquet file or a Puffin file from the remote object store to the cache.
+    ///
+    /// Returns the size of the downloaded file, or `None` if the file is being
+    /// downloaded by another task.
+    pub(crate) async fn download(
+        &self,
+        index_key: IndexKey,
+        remote_path: &str,
+        remote_store: &ObjectStore,
+    ) -> Result<Option<u64>> {
+        let Some(_guard) = self.start_download(index_key) else {
+            return Ok(None);
+        };
+
+        self.download_file(index_key, remote_path, remote_store)
+            .await
+            .map(Some)
+    }
+
+    /// Downloads the file to the cache. The caller must hold the [DownloadingGuard] of the file.
+    async fn download_file(
+        &self,
+        index_key: IndexKey,
+        remote_path: &str,
+        remote_store: &ObjectStore,
+    ) -> Result<u64> {
+        let region_id = index_key.region_id;
+        let file_id = index_key.file_id;
+        let file_type = index_key.file_type;
+        let cache_path = self.file_cache.cache_file_path(index_key);
+
+        let reader = remote_store
+            .reader(remote_path)
+            .await
+            .context(error::OpenDalSnafu)?;
+
+        let mut writer = self
+            .file_cache
+            .local_store()
+            .writer(&cache_path)
+            .await
+            .context(error::OpenDalSnafu)?;
+
+        let bytes_written =
+            futures::io::copy(reader, &mut writer)
+                .await
+                .context(error::DownloadSnafu {
+                    region_id,
+                    file_id,
+                    file_type,
+                })?;
+
+        // Must close to flush all data.
+        writer.close().await.context(error::OpenDalSnafu)?;
+
+        DOWNLOAD_BYTES_TOTAL.inc_by(bytes_written);
+
+        debug!(
+            "Successfully download file to cache, region: {}, file: {}, remote_path: {}",
+            region_id, file_id, remote_path
+        );
+
+        let index_value = IndexValue {
+            file_size: bytes_written as _,
+        };
+        // Register to file cache
+        self.file_cache.put(index_key, index_value).await;
+
+        Ok(bytes_written)
+    }
+
+    /// Downloads the file to the cache in background if it is neither cached
+    /// nor being downloaded.
+    ///
+    /// Skips the file if there are already too many background downloads, a later
+    /// read of the file tries again.
+    pub(crate) fn download_in_background(
+        self: &Arc<Self>,
+        index_key: IndexKey,
+        remote_path: &str,
+        remote_store: &ObjectStore,
+    ) {
+        if self.file_cache.contains_key(&index_key) {
+            return;
+        }
+        let Ok(permit) = self.download_semaphore.clone().try_acquire_owned() else {
+            debug!(
+                "Too many background downloads, skip downloading file {} to cache",
+                remote_path
+            );
+            return;
+        };
+        let Some(guard) = self.start_download(index_key) else {
+            return;
+        };
+
+        let cache = self.clone();
+        let remote_path = remote_path.to_string();
+        let remote_store = remote_store.clone();
+        common_runtime::spawn_bg(async move {
+            if let Err(e) = cache
+                .download_file(index_key, &remote_path, &remote_store)
+                .await
+            {
+                warn!(e; "Failed to download file {} to cache", remote_path);
+            }
+            drop(guard);
+            drop(permit);
+        });
+    }
+
+    /// Marks the file as being downloaded. Returns `None` if it is already being downloaded.
+    fn start_download(&self, index_key: IndexKey) -> Option<DownloadingGuard> {
+        if !self.downloading.lock().unwrap().insert(index_key) {
+            return None;
+        }
+
+        Some(DownloadingGuard {
+            downloading: self.downloading.clone(),
+            index_key,
+        })
+    }
+}
+
+/// Unmarks the file as being downloaded on drop.
+struct DownloadingGuard {
+    downloading: Arc<Mutex<HashSet<IndexKey>>>,
+    index_key: IndexKey,
+}
+
+impl Drop for DownloadingGuard {
+    fn drop(&mut self) {
+        self.downloading.lock().unwrap().remove(&self.index_key);
+    }
 }
 
 /// Request to write and upload a SST.
@@ -231,6 +378,7 @@ pub struct SstUploadRequest {
 mod tests {
 
     use common_test_util::temp_dir::create_temp_dir;
+    use store_api::storage::RegionId;
 
     use super::*;
     use crate::cache::test_util::new_fs_store;
@@ -279,6 +427,7 @@ mod tests {
             create_inverted_index: true,
             mem_threshold_index_create: None,
             index_write_buffer_size: None,
+            index_compression: None,
             cache_manager: Default::default(),
             index_options: IndexOptions::default(),
         };
@@ -364,6 +513,7 @@ mod tests {
             create_inverted_index: false,
             mem_threshold_index_create: None,
             index_write_buffer_size: None,
+            index_compression: None,
             cache_manager: cache_manager.clone(),
             index_options: IndexOptions::default(),
         };
@@ -394,4 +544,36 @@ mod tests {
         // Check parquet metadata
         assert_parquet_metadata_eq(write_parquet_metadata, reader.parquet_metadata());
     }
+
+    #[tokio::test]
+    async fn test_download_in_background() {
+        let mut env = TestEnv::new();
+        let mock_store = env.init_object_store_manager();
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let write_cache = env
+            .create_write_cache(local_store.clone(), ReadableSize::mb(10))
+            .await;
+
+        let region_id = RegionId::new(1, 1);
+        let file_id = FileId::random();
+        let remote_path = sst_file_path("test", file_id);
+        mock_store.write(&remote_path, vec![1; 128]).await.unwrap();
+
+        let key = IndexKey::new(region_id, file_id, FileType::Parquet);
+        write_cache.download_in_background(key, &remote_path, &mock_store);
+        for _ in 0..100 {
+            if write_cache.file_cache.contains_key(&key) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(write_cache.file_cache.contains_key(&key));
+
+        let cache_data = local_store
+            .read(&write_cache.file_cache.cache_file_path(key))
+            .await
+            .unwrap();
+        assert_eq!(vec![1; 128], cache_data);
+    }
 }
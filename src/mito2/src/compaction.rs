@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod downsample;
 mod picker;
+mod stcs;
 #[cfg(test)]
 mod test_util;
 mod twcs;
@@ -29,6 +31,7 @@ use tokio::sync::mpsc::{self, Sender};
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::CacheManagerRef;
+use crate::compaction::stcs::StcsPicker;
 use crate::compaction::twcs::TwcsPicker;
 use crate::config::MitoConfig;
 use crate::error::{
@@ -77,6 +80,11 @@ pub fn compaction_options_to_picker(strategy: &CompactionOptions) -> CompactionP
             twcs_opts.max_inactive_window_files,
             twcs_opts.time_window_seconds(),
         )) as Arc<_>,
+        CompactionOptions::Stcs(stcs_opts) => Arc::new(StcsPicker::new(
+            stcs_opts.min_threshold,
+            stcs_opts.max_threshold,
+            stcs_opts.time_window_seconds(),
+        )) as Arc<_>,
     }
 }
 
@@ -0,0 +1,971 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Downsampling of aged time windows during compaction.
+//!
+//! Once a time window is older than `downsample.after`, compaction rewrites all
+//! files in the window into a single file that keeps one row per series for
+//! every `downsample.interval`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::v1::OpType;
+use async_trait::async_trait;
+use common_telemetry::{error, info};
+use common_time::timestamp::TimeUnit;
+use common_time::timestamp_millis::BucketAligned;
+use common_time::Timestamp;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::value::{OrderedFloat, Value};
+use datatypes::vectors::{UInt64Vector, UInt8Vector};
+use snafu::{ensure, ResultExt};
+use store_api::metadata::RegionMetadata;
+use store_api::storage::{ColumnId, SequenceNumber};
+
+use crate::compaction::twcs::CompactionOutput;
+use crate::error::{ComputeVectorSnafu, InvalidBatchSnafu, Result};
+use crate::read::{Batch, BatchColumn, BatchReader, BoxedBatchReader, Source};
+use crate::region::options::{DownsampleFunction, DownsampleOptions};
+use crate::sst::file::{FileHandle, FileId};
+
+/// Max number of rows in a batch returned by the [DownsampleReader].
+const MAX_BATCH_ROWS: usize = 1024;
+
+/// How to downsample rows of a compaction output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Downsample {
+    /// Interval of output rows.
+    pub(crate) interval: Duration,
+    /// Function to aggregate field values.
+    pub(crate) function: DownsampleFunction,
+    /// Functions of specific fields, keyed by lowercase field names.
+    pub(crate) field_functions: HashMap<String, DownsampleFunction>,
+}
+
+/// Picks windows whose upper bound is older than `downsample.after` and removes
+/// them from `time_windows`, so other strategies never merge them.
+///
+/// Returns an output for each aged window that still has files not downsampled
+/// at the current interval.
+pub(crate) fn pick_downsample_outputs(
+    time_windows: &mut BTreeMap<i64, Vec<FileHandle>>,
+    options: &DownsampleOptions,
+    now: Timestamp,
+) -> Vec<CompactionOutput> {
+    let Some((after, interval)) = options.enabled() else {
+        return vec![];
+    };
+    let expire_time = match now.sub_duration(after) {
+        Ok(expire_time) => expire_time,
+        Err(e) => {
+            error!(e; "Failed to calculate downsample expire time");
+            return vec![];
+        }
+    };
+    // Safety: Converting a timestamp into seconds never overflows.
+    let expire_sec = expire_time.convert_to(TimeUnit::Second).unwrap().value();
+
+    let aged_windows: Vec<_> = time_windows.range(..=expire_sec).map(|(w, _)| *w).collect();
+    let mut outputs = Vec::new();
+    for window in aged_windows {
+        // Safety: the window is from the map.
+        let files: Vec<_> = time_windows
+            .remove(&window)
+            .unwrap()
+            .into_iter()
+            .filter(|file| !file.compacting())
+            .collect();
+        let done = match files.as_slice() {
+            [] => true,
+            [file] => file.downsample_interval() == Some(interval),
+            _ => false,
+        };
+        if done {
+            continue;
+        }
+
+        info!(
+            "Downsample window {} with {} files to interval {:?}",
+            window,
+            files.len(),
+            interval
+        );
+        outputs.push(CompactionOutput {
+            output_file_id: FileId::random(),
+            output_level: 1,
+            inputs: files,
+            downsample: Some(Downsample {
+                interval,
+                function: options.function,
+                field_functions: options.field_functions.clone(),
+            }),
+            filter_deleted: true,
+        });
+    }
+    outputs
+}
+
+/// Returns functions of fields whose function is not the default one.
+pub(crate) fn field_functions(
+    downsample: &Downsample,
+    metadata: &RegionMetadata,
+) -> HashMap<ColumnId, DownsampleFunction> {
+    metadata
+        .field_columns()
+        .filter_map(|column| {
+            downsample
+                .field_functions
+                .get(&column.column_schema.name.to_lowercase())
+                .map(|function| (column.column_id, *function))
+        })
+        .collect()
+}
+
+/// Reader that converts rows not downsampled into aggregated states of one row, so
+/// they can be merged with rows of downsampled SSTs.
+///
+/// Sum, avg, min, max, first and last of one row are the row itself, while count of
+/// one row is 1 if the value is not null.
+pub(crate) struct RawRowReader {
+    source: Source,
+    default_function: DownsampleFunction,
+    /// Functions of fields whose function is not the default one.
+    field_functions: HashMap<ColumnId, DownsampleFunction>,
+}
+
+impl RawRowReader {
+    pub(crate) fn new(
+        source: Source,
+        default_function: DownsampleFunction,
+        field_functions: HashMap<ColumnId, DownsampleFunction>,
+    ) -> Self {
+        Self {
+            source,
+            default_function,
+            field_functions,
+        }
+    }
+
+    fn to_state(&self, batch: Batch) -> Result<Batch> {
+        let mut fields = Vec::with_capacity(batch.fields().len());
+        for field in batch.fields() {
+            let function = self
+                .field_functions
+                .get(&field.column_id)
+                .copied()
+                .unwrap_or(self.default_function);
+            let data_type = field.data.data_type();
+            if function != DownsampleFunction::Count || !data_type.is_numeric() {
+                fields.push(field.clone());
+                continue;
+            }
+
+            let mut vector = data_type.create_mutable_vector(field.data.len());
+            for i in 0..field.data.len() {
+                let value = if field.data.is_null(i) {
+                    Value::Null
+                } else {
+                    i128_to_value(1, &data_type)
+                };
+                vector
+                    .try_push_value_ref(value.as_value_ref())
+                    .context(ComputeVectorSnafu)?;
+            }
+            fields.push(BatchColumn {
+                column_id: field.column_id,
+                data: vector.to_vector(),
+            });
+        }
+        batch.with_fields(fields)
+    }
+}
+
+#[async_trait]
+impl BatchReader for RawRowReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        match self.source.next_batch().await? {
+            Some(batch) => self.to_state(batch).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reader that aggregates rows of each series into rows at a fixed interval.
+///
+/// Rows of the source hold aggregated states, e.g. a count field holds the number
+/// of rows in the interval. Rows of SSTs that are not downsampled should be converted
+/// by the [RawRowReader] first. Rows of all SSTs should be read by one merge reader,
+/// so duplicate and deleted rows are removed across them.
+///
+/// The source must return batches sorted by primary key and timestamp. An output
+/// row's timestamp is the start of its interval and its sequence is the max
+/// sequence of rows in the interval.
+pub(crate) struct DownsampleReader {
+    source: BoxedBatchReader,
+    interval: Duration,
+    default_function: DownsampleFunction,
+    /// Functions of fields whose function is not the default one.
+    field_functions: HashMap<ColumnId, DownsampleFunction>,
+    /// Series being aggregated.
+    series: Option<SeriesAggregator>,
+    /// Batches ready to return.
+    output: VecDeque<Batch>,
+}
+
+impl DownsampleReader {
+    pub(crate) fn new(
+        source: BoxedBatchReader,
+        downsample: &Downsample,
+        field_functions: HashMap<ColumnId, DownsampleFunction>,
+    ) -> Self {
+        Self {
+            source,
+            interval: downsample.interval,
+            default_function: downsample.function,
+            field_functions,
+            series: None,
+            output: VecDeque::new(),
+        }
+    }
+
+    fn push_batch(&mut self, mut batch: Batch) -> Result<()> {
+        batch.filter_deleted()?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(series) = &mut self.series {
+            if series.primary_key != batch.primary_key() {
+                self.output.extend(series.finish()?);
+                self.series = None;
+            }
+        }
+        if self.series.is_none() {
+            self.series = Some(SeriesAggregator::new(
+                &batch,
+                self.interval,
+                self.default_function,
+                &self.field_functions,
+            )?);
+        }
+        // Safety: the series is set above.
+        self.series.as_mut().unwrap().update(&batch);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BatchReader for DownsampleReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        loop {
+            if let Some(batch) = self.output.pop_front() {
+                return Ok(Some(batch));
+            }
+
+            match self.source.next_batch().await? {
+                Some(batch) => self.push_batch(batch)?,
+                None => {
+                    if let Some(mut series) = self.series.take() {
+                        self.output.extend(series.finish()?);
+                    }
+                    return Ok(self.output.pop_front());
+                }
+            }
+        }
+    }
+}
+
+/// Aggregates rows of a series.
+///
+/// Rows of a series may come from both sources, so it keeps states of all intervals
+/// in the time window until the series is finished.
+struct SeriesAggregator {
+    primary_key: Vec<u8>,
+    /// Interval in the unit of the time index.
+    interval: i64,
+    time_unit: TimeUnit,
+    timestamp_type: ConcreteDataType,
+    fields: Vec<FieldAggregator>,
+    /// Index of the first count field, whose value is the weight of average values
+    /// in states.
+    count_field: Option<usize>,
+    /// States of intervals, keyed by the start of intervals.
+    intervals: BTreeMap<i64, IntervalState>,
+}
+
+/// Metadata of a field to aggregate.
+struct FieldAggregator {
+    column_id: ColumnId,
+    data_type: ConcreteDataType,
+    function: DownsampleFunction,
+}
+
+impl SeriesAggregator {
+    fn new(
+        batch: &Batch,
+        interval: Duration,
+        default_function: DownsampleFunction,
+        field_functions: &HashMap<ColumnId, DownsampleFunction>,
+    ) -> Result<Self> {
+        let timestamp_type = batch.timestamps().data_type();
+        let ConcreteDataType::Timestamp(ts_type) = &timestamp_type else {
+            return InvalidBatchSnafu {
+                reason: format!("time index type {timestamp_type:?} is not a timestamp"),
+            }
+            .fail();
+        };
+        let time_unit = ts_type.unit();
+        let interval =
+            (interval.as_nanos() / time_unit.factor() as u128).clamp(1, i64::MAX as u128) as i64;
+
+        let fields: Vec<_> = batch
+            .fields()
+            .iter()
+            .map(|field| FieldAggregator {
+                column_id: field.column_id,
+                data_type: field.data.data_type(),
+                function: field_functions
+                    .get(&field.column_id)
+                    .copied()
+                    .unwrap_or(default_function),
+            })
+            .collect();
+        let count_field = fields.iter().position(|field| {
+            field.function == DownsampleFunction::Count && field.data_type.is_numeric()
+        });
+        // Averages of states can't be merged without the number of rows.
+        ensure!(
+            count_field.is_some()
+                || !fields.iter().any(|field| {
+                    field.function == DownsampleFunction::Avg && field.data_type.is_numeric()
+                }),
+            InvalidBatchSnafu {
+                reason: "downsampling by avg requires a count field",
+            }
+        );
+
+        Ok(Self {
+            primary_key: batch.primary_key().to_vec(),
+            interval,
+            time_unit,
+            timestamp_type,
+            fields,
+            count_field,
+            intervals: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the state of the interval `ts` belongs to.
+    fn interval_state(&mut self, ts: i64) -> &mut IntervalState {
+        let start = ts.align_by_bucket(self.interval).unwrap_or(i64::MIN);
+        let fields = &self.fields;
+        self.intervals
+            .entry(start)
+            .or_insert_with(|| IntervalState::new(fields))
+    }
+
+    /// Merges states in rows of the batch.
+    fn update(&mut self, batch: &Batch) {
+        // Safety: the batch is not empty.
+        let timestamps = batch.timestamps_native().unwrap();
+        for (i, ts) in timestamps.iter().enumerate() {
+            // A row without count is a single row.
+            let weight = self
+                .count_field
+                .and_then(|idx| value_to_f64(&batch.fields()[idx].data.get(i)))
+                .map(|count| count.max(0.0) as u64)
+                .unwrap_or(1);
+            let state = self.interval_state(*ts);
+            state.max_sequence = state.max_sequence.max(batch.get_sequence(i));
+            for (acc, field) in state.accumulators.iter_mut().zip(batch.fields()) {
+                acc.merge(field.data.get(i), *ts, weight);
+            }
+        }
+    }
+
+    /// Builds batches from all intervals.
+    fn finish(&mut self) -> Result<Vec<Batch>> {
+        let intervals = std::mem::take(&mut self.intervals);
+        let intervals: Vec<_> = intervals.into_iter().collect();
+        intervals
+            .chunks(MAX_BATCH_ROWS)
+            .map(|chunk| self.build_batch(chunk))
+            .collect()
+    }
+
+    fn build_batch(&self, intervals: &[(i64, IntervalState)]) -> Result<Batch> {
+        let num_rows = intervals.len();
+        let mut timestamps = self.timestamp_type.create_mutable_vector(num_rows);
+        for (start, _) in intervals {
+            timestamps
+                .try_push_value_ref(
+                    Value::Timestamp(Timestamp::new(*start, self.time_unit)).as_value_ref(),
+                )
+                .context(ComputeVectorSnafu)?;
+        }
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for (idx, field) in self.fields.iter().enumerate() {
+            let mut vector = field.data_type.create_mutable_vector(num_rows);
+            for (_, state) in intervals {
+                let value = state.accumulators[idx].finish(&field.data_type);
+                vector
+                    .try_push_value_ref(value.as_value_ref())
+                    .context(ComputeVectorSnafu)?;
+            }
+            fields.push(BatchColumn {
+                column_id: field.column_id,
+                data: vector.to_vector(),
+            });
+        }
+        let sequences = intervals
+            .iter()
+            .map(|(_, state)| state.max_sequence)
+            .collect();
+
+        Batch::new(
+            self.primary_key.clone(),
+            timestamps.to_vector(),
+            Arc::new(UInt64Vector::from_vec(sequences)),
+            Arc::new(UInt8Vector::from_vec(vec![OpType::Put as u8; num_rows])),
+            fields,
+        )
+    }
+}
+
+/// State of an interval being aggregated.
+struct IntervalState {
+    max_sequence: SequenceNumber,
+    accumulators: Vec<Accumulator>,
+}
+
+impl IntervalState {
+    fn new(fields: &[FieldAggregator]) -> Self {
+        Self {
+            max_sequence: 0,
+            accumulators: fields
+                .iter()
+                .map(|field| Accumulator::new(field.function, &field.data_type))
+                .collect(),
+        }
+    }
+}
+
+/// Accumulates non-null values of a field.
+///
+/// Sum, avg and count fall back to the last value for non-numeric fields. Sums
+/// of integers are exact and saturated at the bounds of the type.
+struct Accumulator {
+    function: DownsampleFunction,
+    /// Whether to aggregate values as numbers.
+    numeric: bool,
+    /// Whether values are integers.
+    integer: bool,
+    /// Value for min, max, first and last.
+    value: Value,
+    /// Timestamp of `value`.
+    value_ts: i64,
+    int_sum: i128,
+    float_sum: f64,
+    /// Number of values in the sum, or the value of count.
+    count: u64,
+}
+
+impl Accumulator {
+    fn new(function: DownsampleFunction, data_type: &ConcreteDataType) -> Self {
+        let numeric = matches!(
+            function,
+            DownsampleFunction::Sum | DownsampleFunction::Avg | DownsampleFunction::Count
+        ) && data_type.is_numeric();
+        Self {
+            function,
+            numeric,
+            integer: !data_type.is_float(),
+            value: Value::Null,
+            value_ts: 0,
+            int_sum: 0,
+            float_sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Merges a downsampled value of `weight` rows into the accumulator.
+    fn merge(&mut self, value: Value, ts: i64, weight: u64) {
+        if value.is_null() {
+            return;
+        }
+        if !self.numeric {
+            self.update_value(value, ts);
+            return;
+        }
+
+        match self.function {
+            DownsampleFunction::Sum => {
+                self.add(&value, 1);
+                self.count += 1;
+            }
+            DownsampleFunction::Avg => {
+                self.add(&value, weight);
+                self.count += weight;
+            }
+            DownsampleFunction::Count => {
+                // The value is a count of rows.
+                self.count += value_to_f64(&value).unwrap_or_default().max(0.0) as u64;
+            }
+            DownsampleFunction::Min
+            | DownsampleFunction::Max
+            | DownsampleFunction::First
+            | DownsampleFunction::Last => self.update_value(value, ts),
+        }
+    }
+
+    fn update_value(&mut self, value: Value, ts: i64) {
+        let replace = self.value.is_null()
+            || match self.function {
+                DownsampleFunction::Min => value < self.value,
+                DownsampleFunction::Max => value > self.value,
+                DownsampleFunction::First => ts < self.value_ts,
+                // Last value of other functions.
+                _ => ts >= self.value_ts,
+            };
+        if replace {
+            self.value = value;
+            self.value_ts = ts;
+        }
+    }
+
+    /// Adds `value * weight` to the sum.
+    fn add(&mut self, value: &Value, weight: u64) {
+        if self.integer {
+            if let Some(v) = value_to_i128(value) {
+                self.int_sum = self
+                    .int_sum
+                    .saturating_add(v.saturating_mul(weight as i128));
+            }
+        } else if let Some(v) = value_to_f64(value) {
+            self.float_sum += v * weight as f64;
+        }
+    }
+
+    fn finish(&self, data_type: &ConcreteDataType) -> Value {
+        if !self.numeric {
+            return self.value.clone();
+        }
+
+        match self.function {
+            DownsampleFunction::Sum | DownsampleFunction::Avg if self.count == 0 => Value::Null,
+            DownsampleFunction::Sum if self.integer => i128_to_value(self.int_sum, data_type),
+            DownsampleFunction::Sum => f64_to_value(self.float_sum, data_type),
+            // Division of integers rounds towards zero.
+            DownsampleFunction::Avg if self.integer => {
+                i128_to_value(self.int_sum / self.count as i128, data_type)
+            }
+            DownsampleFunction::Avg => f64_to_value(self.float_sum / self.count as f64, data_type),
+            DownsampleFunction::Count if self.integer => {
+                i128_to_value(self.count as i128, data_type)
+            }
+            DownsampleFunction::Count => f64_to_value(self.count as f64, data_type),
+            DownsampleFunction::Min
+            | DownsampleFunction::Max
+            | DownsampleFunction::First
+            | DownsampleFunction::Last => self.value.clone(),
+        }
+    }
+}
+
+fn value_to_i128(value: &Value) -> Option<i128> {
+    let v = match value {
+        Value::UInt8(v) => *v as i128,
+        Value::UInt16(v) => *v as i128,
+        Value::UInt32(v) => *v as i128,
+        Value::UInt64(v) => *v as i128,
+        Value::Int8(v) => *v as i128,
+        Value::Int16(v) => *v as i128,
+        Value::Int32(v) => *v as i128,
+        Value::Int64(v) => *v as i128,
+        _ => return None,
+    };
+    Some(v)
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    let v = match value {
+        Value::UInt8(v) => *v as f64,
+        Value::UInt16(v) => *v as f64,
+        Value::UInt32(v) => *v as f64,
+        Value::UInt64(v) => *v as f64,
+        Value::Int8(v) => *v as f64,
+        Value::Int16(v) => *v as f64,
+        Value::Int32(v) => *v as f64,
+        Value::Int64(v) => *v as f64,
+        Value::Float32(v) => v.0 as f64,
+        Value::Float64(v) => v.0,
+        _ => return None,
+    };
+    Some(v)
+}
+
+/// Casts the integer `v` to a value of `data_type`, saturated at the bounds of the type.
+fn i128_to_value(v: i128, data_type: &ConcreteDataType) -> Value {
+    match data_type {
+        ConcreteDataType::UInt8(_) => Value::UInt8(v.clamp(0, u8::MAX as i128) as u8),
+        ConcreteDataType::UInt16(_) => Value::UInt16(v.clamp(0, u16::MAX as i128) as u16),
+        ConcreteDataType::UInt32(_) => Value::UInt32(v.clamp(0, u32::MAX as i128) as u32),
+        ConcreteDataType::UInt64(_) => Value::UInt64(v.clamp(0, u64::MAX as i128) as u64),
+        ConcreteDataType::Int8(_) => Value::Int8(v.clamp(i8::MIN as i128, i8::MAX as i128) as i8),
+        ConcreteDataType::Int16(_) => {
+            Value::Int16(v.clamp(i16::MIN as i128, i16::MAX as i128) as i16)
+        }
+        ConcreteDataType::Int32(_) => {
+            Value::Int32(v.clamp(i32::MIN as i128, i32::MAX as i128) as i32)
+        }
+        ConcreteDataType::Int64(_) => {
+            Value::Int64(v.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+        }
+        _ => f64_to_value(v as f64, data_type),
+    }
+}
+
+/// Casts the float `v` to a value of `data_type`.
+fn f64_to_value(v: f64, data_type: &ConcreteDataType) -> Value {
+    match data_type {
+        ConcreteDataType::Float32(_) => Value::Float32(OrderedFloat(v as f32)),
+        _ => Value::Float64(OrderedFloat(v)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::arrow::array::UInt64Array;
+
+    use super::*;
+    use crate::compaction::test_util::new_file_handle;
+    use crate::read::merge::MergeReaderBuilder;
+    use crate::test_util::{
+        check_reader_result, new_batch, new_batch_builder, new_noop_file_purger, VecBatchReader,
+    };
+
+    /// Returns a reader to downsample `raw` rows and `state` rows.
+    async fn new_downsample_reader_with_states(
+        raw: &[Batch],
+        state: &[Batch],
+        interval_ms: u64,
+        function: DownsampleFunction,
+        field_functions: HashMap<ColumnId, DownsampleFunction>,
+    ) -> DownsampleReader {
+        let raw = RawRowReader::new(
+            Source::Reader(Box::new(VecBatchReader::new(raw))),
+            function,
+            field_functions.clone(),
+        );
+        let source = MergeReaderBuilder::new()
+            .push_batch_reader(Box::new(raw))
+            .push_batch_reader(Box::new(VecBatchReader::new(state)))
+            .build()
+            .await
+            .unwrap();
+        DownsampleReader::new(
+            Box::new(source),
+            &Downsample {
+                interval: Duration::from_millis(interval_ms),
+                function,
+                field_functions: HashMap::new(),
+            },
+            field_functions,
+        )
+    }
+
+    async fn new_downsample_reader(
+        batches: &[Batch],
+        interval_ms: u64,
+        function: DownsampleFunction,
+    ) -> DownsampleReader {
+        new_downsample_reader_with_states(batches, &[], interval_ms, function, HashMap::new()).await
+    }
+
+    /// Returns a batch of `k1` with an average field `1` and a count field `2`.
+    fn new_avg_count_batch(
+        timestamps: &[i64],
+        sequences: &[u64],
+        avg: &[u64],
+        count: &[u64],
+    ) -> Batch {
+        new_batch_builder(
+            b"k1",
+            timestamps,
+            sequences,
+            &vec![OpType::Put; timestamps.len()],
+            1,
+            avg,
+        )
+        .push_field_array(2, Arc::new(UInt64Array::from_iter_values(count.to_vec())))
+        .unwrap()
+        .build()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_downsample_reader() {
+        let input = [
+            new_batch(
+                b"k1",
+                &[1, 2, 10],
+                &[11, 12, 13],
+                &[OpType::Put, OpType::Put, OpType::Put],
+                &[5, 3, 8],
+            ),
+            // The interval continues in the next batch.
+            new_batch(
+                b"k1",
+                &[15, 21, 22],
+                &[10, 14, 11],
+                &[OpType::Put, OpType::Delete, OpType::Put],
+                &[2, 100, 4],
+            ),
+            new_batch(b"k2", &[3], &[20], &[OpType::Put], &[7]),
+        ];
+
+        let cases = [
+            (DownsampleFunction::Min, [3, 2, 4, 7]),
+            (DownsampleFunction::Max, [5, 8, 4, 7]),
+            (DownsampleFunction::Sum, [8, 10, 4, 7]),
+            (DownsampleFunction::First, [5, 8, 4, 7]),
+            (DownsampleFunction::Last, [3, 2, 4, 7]),
+            (DownsampleFunction::Count, [2, 2, 1, 1]),
+        ];
+        for (function, expect) in cases {
+            let mut reader = new_downsample_reader(&input, 10, function).await;
+            check_reader_result(
+                &mut reader,
+                &[
+                    new_batch(
+                        b"k1",
+                        &[0, 10, 20],
+                        &[12, 13, 11],
+                        &[OpType::Put, OpType::Put, OpType::Put],
+                        &expect[..3],
+                    ),
+                    new_batch(b"k2", &[0], &[20], &[OpType::Put], &expect[3..]),
+                ],
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_downsample_reader_avg() {
+        let input = [
+            new_avg_count_batch(&[1, 2, 10], &[11, 12, 13], &[5, 3, 8], &[0, 0, 0]),
+            new_avg_count_batch(&[15], &[10], &[2], &[0]),
+        ];
+        let mut reader = new_downsample_reader_with_states(
+            &input,
+            &[],
+            10,
+            DownsampleFunction::Avg,
+            HashMap::from([(2, DownsampleFunction::Count)]),
+        )
+        .await;
+        check_reader_result(
+            &mut reader,
+            &[new_avg_count_batch(&[0, 10], &[12, 13], &[4, 5], &[2, 2])],
+        )
+        .await;
+
+        // Averages of downsampled rows can't be merged without a count field.
+        let input = [new_batch(b"k1", &[1], &[1], &[OpType::Put], &[1])];
+        let mut reader = new_downsample_reader(&input, 10, DownsampleFunction::Avg).await;
+        assert!(reader.next_batch().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_downsample_reader_merge_states() {
+        // 3 rows with sum 12 and 1 row with value 6.
+        let state = [new_avg_count_batch(&[0, 10], &[5, 5], &[4, 6], &[3, 1])];
+        let raw = [
+            new_avg_count_batch(&[3, 12], &[9, 4], &[8, 2], &[100, 100]),
+            new_batch(b"k2", &[3], &[20], &[OpType::Put], &[7]),
+        ];
+        let mut reader = new_downsample_reader_with_states(
+            &raw,
+            &state,
+            10,
+            DownsampleFunction::Avg,
+            HashMap::from([(2, DownsampleFunction::Count)]),
+        )
+        .await;
+        check_reader_result(
+            &mut reader,
+            &[
+                // (12 + 8) / 4 and (6 + 2) / 2.
+                new_avg_count_batch(&[0, 10], &[9, 5], &[5, 4], &[4, 2]),
+                new_batch(b"k2", &[0], &[20], &[OpType::Put], &[7]),
+            ],
+        )
+        .await;
+
+        // Merges sums of states.
+        let state = [new_batch(b"k1", &[0], &[5], &[OpType::Put], &[10])];
+        let raw = [new_batch(b"k1", &[5], &[6], &[OpType::Put], &[1])];
+        let mut reader = new_downsample_reader_with_states(
+            &raw,
+            &state,
+            10,
+            DownsampleFunction::Sum,
+            HashMap::new(),
+        )
+        .await;
+        check_reader_result(
+            &mut reader,
+            &[new_batch(b"k1", &[0], &[6], &[OpType::Put], &[11])],
+        )
+        .await;
+
+        // A newer raw row deletes the downsampled row with the same timestamp.
+        let state = [new_batch(
+            b"k1",
+            &[0, 10],
+            &[5, 5],
+            &[OpType::Put; 2],
+            &[10, 20],
+        )];
+        let raw = [new_batch(
+            b"k1",
+            &[0, 15],
+            &[6, 6],
+            &[OpType::Delete, OpType::Put],
+            &[0, 1],
+        )];
+        let mut reader = new_downsample_reader_with_states(
+            &raw,
+            &state,
+            10,
+            DownsampleFunction::Sum,
+            HashMap::new(),
+        )
+        .await;
+        check_reader_result(
+            &mut reader,
+            &[new_batch(b"k1", &[10], &[6], &[OpType::Put], &[21])],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_downsample_reader_integer_sum() {
+        // Not representable by f64.
+        let big = (1 << 53) + 1;
+        let input = [new_batch(
+            b"k1",
+            &[1, 2, 11, 12],
+            &[1, 1, 1, 1],
+            &[OpType::Put; 4],
+            &[big, 0, u64::MAX, 1],
+        )];
+        let mut reader = new_downsample_reader(&input, 10, DownsampleFunction::Sum).await;
+        check_reader_result(
+            &mut reader,
+            &[new_batch(
+                b"k1",
+                &[0, 10],
+                &[1, 1],
+                &[OpType::Put, OpType::Put],
+                // The sum saturates at the bound.
+                &[big, u64::MAX],
+            )],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_downsample_reader_empty() {
+        let mut reader = new_downsample_reader(&[], 10, DownsampleFunction::Avg).await;
+        check_reader_result(&mut reader, &[]).await;
+
+        // All rows are deleted.
+        let input = [new_batch(b"k1", &[1], &[1], &[OpType::Delete], &[1])];
+        let mut reader = new_downsample_reader(&input, 10, DownsampleFunction::Avg).await;
+        check_reader_result(&mut reader, &[]).await;
+    }
+
+    #[test]
+    fn test_pick_downsample_outputs() {
+        let options = DownsampleOptions {
+            after: Some(Duration::from_secs(3600)),
+            interval: Some(Duration::from_secs(60)),
+            function: DownsampleFunction::Avg,
+            field_functions: HashMap::new(),
+        };
+        let downsampled = {
+            let mut meta = new_file_handle(FileId::random(), 0, 999, 1).meta();
+            meta.downsample_interval = Some(Duration::from_secs(60));
+            FileHandle::new(meta, new_noop_file_purger())
+        };
+        let compacting = new_file_handle(FileId::random(), 3_600_000, 3_600_999, 0);
+        compacting.set_compacting(true);
+        let aged_files = vec![
+            new_file_handle(FileId::random(), 3_600_000, 3_600_999, 0),
+            new_file_handle(FileId::random(), 3_601_000, 3_601_999, 0),
+            compacting.clone(),
+        ];
+        let mut windows = BTreeMap::from([
+            // Already downsampled.
+            (1, vec![downsampled]),
+            (3601, aged_files.clone()),
+            // Only has a compacting file.
+            (7200, vec![compacting]),
+            // Not aged.
+            (
+                10800,
+                vec![new_file_handle(FileId::random(), 10_800_000, 10_800_000, 0)],
+            ),
+        ]);
+
+        let outputs = pick_downsample_outputs(&mut windows, &options, Timestamp::new_second(10800));
+        assert_eq!(1, outputs.len());
+        assert_eq!(
+            aged_files[..2]
+                .iter()
+                .map(|f| f.file_id())
+                .collect::<Vec<_>>(),
+            outputs[0]
+                .inputs
+                .iter()
+                .map(|f| f.file_id())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(Downsample {
+                interval: Duration::from_secs(60),
+                function: DownsampleFunction::Avg,
+                field_functions: HashMap::new(),
+            }),
+            outputs[0].downsample
+        );
+        // Aged windows are removed.
+        assert_eq!(vec![10800], windows.keys().copied().collect::<Vec<_>>());
+
+        // Disabled.
+        let mut windows = BTreeMap::from([(3601, aged_files)]);
+        let outputs = pick_downsample_outputs(
+            &mut windows,
+            &DownsampleOptions::default(),
+            Timestamp::new_second(10800),
+        );
+        assert!(outputs.is_empty());
+        assert_eq!(1, windows.len());
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Debug, Formatter};
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{debug, info};
+use common_time::Timestamp;
+
+use crate::compaction::downsample::pick_downsample_outputs;
+use crate::compaction::picker::{CompactionTask, Picker};
+use crate::compaction::twcs::{
+    assign_to_windows, compaction_time_window_size, get_expired_ssts, CompactionOutput,
+    TwcsCompactionTask,
+};
+use crate::compaction::CompactionRequest;
+use crate::sst::file::{FileHandle, FileId};
+use crate::sst::version::LevelMeta;
+
+/// Files smaller than this size are always put into the same tier.
+const SMALL_FILE_SIZE: ReadableSize = ReadableSize::mb(32);
+/// A file belongs to a tier if its size is less than `BUCKET_HIGH` times the average
+/// size of files in the tier.
+const BUCKET_HIGH: f64 = 1.5;
+
+/// `StcsPicker` groups files of which the max timestamp are in the same time window
+/// into tiers of similar sizes, and picks files in the same tier as compaction candidates.
+///
+/// Unlike [TwcsPicker](crate::compaction::twcs::TwcsPicker), it doesn't merge all files
+/// in a window at once so large files are not rewritten by every compaction.
+pub struct StcsPicker {
+    min_threshold: usize,
+    max_threshold: usize,
+    time_window_seconds: Option<i64>,
+}
+
+impl Debug for StcsPicker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StcsPicker")
+            .field("min_threshold", &self.min_threshold)
+            .field("max_threshold", &self.max_threshold)
+            .finish()
+    }
+}
+
+impl StcsPicker {
+    pub fn new(
+        min_threshold: usize,
+        max_threshold: usize,
+        time_window_seconds: Option<i64>,
+    ) -> Self {
+        // We need at least 2 files to compact.
+        let min_threshold = min_threshold.max(2);
+        Self {
+            min_threshold,
+            max_threshold: max_threshold.max(min_threshold),
+            time_window_seconds,
+        }
+    }
+
+    /// Builds compaction output from files.
+    /// Each tier with at least `min_threshold` files in a window outputs a file
+    /// by merging at most `max_threshold` smallest files in the tier.
+    ///
+    /// The output keeps deleted rows unless the inputs contain all files in the
+    /// window overlapping with them, as other files may have rows they delete.
+    fn build_output(&self, time_windows: &BTreeMap<i64, Vec<FileHandle>>) -> Vec<CompactionOutput> {
+        let mut output = vec![];
+        for (window, files) in time_windows {
+            for tier in split_into_tiers(files) {
+                if tier.len() < self.min_threshold {
+                    debug!(
+                        "No enough files in tier, window: {}, current: {}, min_threshold: {}",
+                        window,
+                        tier.len(),
+                        self.min_threshold
+                    );
+                    continue;
+                }
+
+                let inputs: Vec<_> = tier.into_iter().take(self.max_threshold).collect();
+                let filter_deleted = contains_overlapping_files(&inputs, files);
+                output.push(CompactionOutput {
+                    output_file_id: FileId::random(),
+                    output_level: 1,
+                    inputs,
+                    downsample: None,
+                    filter_deleted,
+                });
+            }
+        }
+        output
+    }
+}
+
+impl Picker for StcsPicker {
+    fn pick(&self, req: CompactionRequest) -> Option<Box<dyn CompactionTask>> {
+        let CompactionRequest {
+            engine_config,
+            current_version,
+            access_layer,
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            cache_manager,
+        } = req;
+
+        let region_metadata = current_version.metadata.clone();
+        let region_id = region_metadata.region_id;
+
+        let levels = current_version.ssts.levels();
+        let ttl = current_version.options.ttl;
+        let now = Timestamp::current_millis();
+        let expired_ssts = get_expired_ssts(levels, ttl, now);
+        if !expired_ssts.is_empty() {
+            info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+            // here we mark expired SSTs as compacting to avoid them being picked.
+            expired_ssts.iter().for_each(|f| f.set_compacting(true));
+        }
+
+        let time_window_size =
+            compaction_time_window_size(&current_version, self.time_window_seconds);
+        let mut windows = assign_to_windows(
+            levels
+                .iter()
+                .flat_map(LevelMeta::files)
+                .filter(|file| !file.compacting()),
+            time_window_size,
+        );
+        let mut outputs =
+            pick_downsample_outputs(&mut windows, &current_version.options.downsample, now);
+        outputs.extend(self.build_output(&windows));
+
+        if outputs.is_empty() && expired_ssts.is_empty() {
+            // Nothing to compact, we are done. Notifies all waiters as we consume the compaction request.
+            for waiter in waiters {
+                waiter.send(Ok(0));
+            }
+            return None;
+        }
+        let task = TwcsCompactionTask {
+            engine_config,
+            region_id,
+            metadata: region_metadata,
+            sst_layer: access_layer,
+            outputs,
+            expired_ssts,
+            compaction_time_window: Some(time_window_size),
+            request_sender,
+            waiters,
+            file_purger,
+            start_time,
+            cache_manager,
+            storage: current_version.options.storage.clone(),
+            index_options: current_version.options.index_options.clone(),
+            append_mode: current_version.options.append_mode,
+        };
+        Some(Box::new(task))
+    }
+}
+
+/// Splits files into tiers of similar sizes.
+/// Files in each tier are sorted by their sizes.
+fn split_into_tiers(files: &[FileHandle]) -> Vec<Vec<FileHandle>> {
+    let mut files = files.to_vec();
+    files.sort_unstable_by_key(|file| file.size());
+
+    let mut tiers: Vec<Vec<FileHandle>> = Vec::new();
+    let mut tier_size = 0;
+    for file in files {
+        let size = file.size();
+        if let Some(tier) = tiers.last_mut() {
+            let avg_size = tier_size as f64 / tier.len() as f64;
+            // Files are sorted so the size is never less than the average.
+            if size < SMALL_FILE_SIZE.as_bytes() || (size as f64) < avg_size * BUCKET_HIGH {
+                tier.push(file);
+                tier_size += size;
+                continue;
+            }
+        }
+
+        tiers.push(vec![file]);
+        tier_size = size;
+    }
+    tiers
+}
+
+/// Returns true if `inputs` contain all `files` overlapping with the time range of `inputs`.
+fn contains_overlapping_files(inputs: &[FileHandle], files: &[FileHandle]) -> bool {
+    // Safety: a tier is never empty.
+    let start = inputs.iter().map(|file| file.time_range().0).min().unwrap();
+    let end = inputs.iter().map(|file| file.time_range().1).max().unwrap();
+    let input_ids: HashSet<_> = inputs.iter().map(|file| file.file_id()).collect();
+    files.iter().all(|file| {
+        let (file_start, file_end) = file.time_range();
+        input_ids.contains(&file.file_id()) || file_end < start || file_start > end
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compaction::test_util::new_file_handle_with_size;
+
+    fn new_files(sizes: &[u64]) -> Vec<FileHandle> {
+        sizes
+            .iter()
+            .map(|size| new_file_handle_with_size(FileId::random(), 0, 999, 0, *size))
+            .collect()
+    }
+
+    fn tier_sizes(tiers: &[Vec<FileHandle>]) -> Vec<Vec<u64>> {
+        tiers
+            .iter()
+            .map(|tier| tier.iter().map(|file| file.size()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_into_tiers() {
+        let mb = ReadableSize::mb(1).as_bytes();
+        assert!(split_into_tiers(&[]).is_empty());
+
+        // Small files are in the same tier.
+        let files = new_files(&[mb, 20 * mb, 5 * mb]);
+        assert_eq!(
+            vec![vec![mb, 5 * mb, 20 * mb]],
+            tier_sizes(&split_into_tiers(&files))
+        );
+
+        let files = new_files(&[
+            100 * mb,
+            mb,
+            1000 * mb,
+            120 * mb,
+            2 * mb,
+            110 * mb,
+            1100 * mb,
+        ]);
+        assert_eq!(
+            vec![
+                vec![mb, 2 * mb],
+                vec![100 * mb, 110 * mb, 120 * mb],
+                vec![1000 * mb, 1100 * mb],
+            ],
+            tier_sizes(&split_into_tiers(&files))
+        );
+    }
+
+    #[test]
+    fn test_build_output() {
+        let mb = ReadableSize::mb(1).as_bytes();
+        let picker = StcsPicker::new(3, 4, None);
+        let windows = BTreeMap::from([
+            // Not enough files in a tier.
+            (0, new_files(&[mb, 2 * mb, 100 * mb, 110 * mb])),
+            (
+                3600,
+                new_files(&[mb, 2 * mb, 3 * mb, 4 * mb, 5 * mb, 100 * mb]),
+            ),
+        ]);
+        let output = picker.build_output(&windows);
+        assert_eq!(1, output.len());
+        assert_eq!(
+            vec![mb, 2 * mb, 3 * mb, 4 * mb],
+            output[0]
+                .inputs
+                .iter()
+                .map(|file| file.size())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_build_output_filter_deleted() {
+        let mb = ReadableSize::mb(1).as_bytes();
+        let picker = StcsPicker::new(2, 4, None);
+        let new_file = |start, end, size| {
+            new_file_handle_with_size(FileId::random(), start, end, 0, size * mb)
+        };
+        let windows = BTreeMap::from([
+            // All files in the window are compacted.
+            (0, vec![new_file(0, 999, 1), new_file(0, 999, 2)]),
+            // The small tier may delete rows in the large file.
+            (
+                3600,
+                vec![
+                    new_file(3_600_000, 3_600_999, 1),
+                    new_file(3_600_000, 3_600_999, 2),
+                    new_file(3_600_000, 3_600_999, 100),
+                ],
+            ),
+            // The large file doesn't overlap with the small tier.
+            (
+                7200,
+                vec![
+                    new_file(7_200_500, 7_200_999, 1),
+                    new_file(7_200_500, 7_200_999, 2),
+                    new_file(7_200_000, 7_200_100, 100),
+                ],
+            ),
+        ]);
+        let output = picker.build_output(&windows);
+        assert_eq!(
+            vec![true, false, true],
+            output
+                .iter()
+                .map(|output| output.filter_deleted)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sanitize_thresholds() {
+        let picker = StcsPicker::new(0, 1, None);
+        assert_eq!(2, picker.min_threshold);
+        assert_eq!(2, picker.max_threshold);
+    }
+}
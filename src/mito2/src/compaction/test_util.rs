@@ -23,6 +23,17 @@ pub fn new_file_handle(
     start_ts_millis: i64,
     end_ts_millis: i64,
     level: Level,
+) -> FileHandle {
+    new_file_handle_with_size(file_id, start_ts_millis, end_ts_millis, level, 0)
+}
+
+/// Test util to create file handles with specific file size.
+pub fn new_file_handle_with_size(
+    file_id: FileId,
+    start_ts_millis: i64,
+    end_ts_millis: i64,
+    level: Level,
+    file_size: u64,
 ) -> FileHandle {
     let file_purger = new_noop_file_purger();
     FileHandle::new(
@@ -34,9 +45,11 @@ pub fn new_file_handle(
                 Timestamp::new_millisecond(end_ts_millis),
             ),
             level,
-            file_size: 0,
+            file_size,
             available_indexes: Default::default(),
             index_file_size: 0,
+            index_file_id: None,
+            downsample_interval: None,
         },
         file_purger,
     )
@@ -21,7 +21,6 @@ use common_telemetry::{debug, error, info};
 use common_time::timestamp::TimeUnit;
 use common_time::timestamp_millis::BucketAligned;
 use common_time::Timestamp;
-use smallvec::SmallVec;
 use snafu::ResultExt;
 use store_api::metadata::RegionMetadataRef;
 use store_api::storage::RegionId;
@@ -29,19 +28,24 @@ use tokio::sync::mpsc;
 
 use crate::access_layer::{AccessLayerRef, SstWriteRequest};
 use crate::cache::CacheManagerRef;
+use crate::compaction::downsample::{
+    self, pick_downsample_outputs, Downsample, DownsampleReader, RawRowReader,
+};
 use crate::compaction::picker::{CompactionTask, Picker};
 use crate::compaction::CompactionRequest;
 use crate::config::MitoConfig;
 use crate::error::{self, CompactRegionSnafu};
 use crate::metrics::{COMPACTION_FAILURE_COUNT, COMPACTION_STAGE_ELAPSED};
+use crate::read::merge::MergeReaderBuilder;
 use crate::read::projection::ProjectionMapper;
 use crate::read::seq_scan::SeqScan;
 use crate::read::{BoxedBatchReader, Source};
 use crate::region::options::IndexOptions;
+use crate::region::version::VersionRef;
 use crate::request::{
     BackgroundNotify, CompactionFailed, CompactionFinished, OutputTx, WorkerRequest,
 };
-use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType, Level};
+use crate::sst::file::{FileHandle, FileId, FileMeta, Level};
 use crate::sst::file_purger::FilePurgerRef;
 use crate::sst::parquet::WriteOptions;
 use crate::sst::version::LevelMeta;
@@ -96,6 +100,8 @@ impl TwcsPicker {
                         output_file_id: FileId::random(),
                         output_level: 1, // we only have two levels and always compact to l1
                         inputs: files.clone(),
+                        downsample: None,
+                        filter_deleted: true,
                     });
                 } else {
                     debug!("Active window not present or no enough files in active window {:?}, window: {}", active_window, *window);
@@ -107,6 +113,8 @@ impl TwcsPicker {
                         output_file_id: FileId::random(),
                         output_level: 1,
                         inputs: files.clone(),
+                        downsample: None,
+                        filter_deleted: true,
                     });
                 } else {
                     debug!(
@@ -139,32 +147,25 @@ impl Picker for TwcsPicker {
 
         let levels = current_version.ssts.levels();
         let ttl = current_version.options.ttl;
-        let expired_ssts = get_expired_ssts(levels, ttl, Timestamp::current_millis());
+        let now = Timestamp::current_millis();
+        let expired_ssts = get_expired_ssts(levels, ttl, now);
         if !expired_ssts.is_empty() {
             info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
             // here we mark expired SSTs as compacting to avoid them being picked.
             expired_ssts.iter().for_each(|f| f.set_compacting(true));
         }
 
-        let compaction_time_window = current_version
-            .compaction_time_window
-            .map(|window| window.as_secs() as i64);
-        let time_window_size = compaction_time_window
-            .or(self.time_window_seconds)
-            .unwrap_or_else(|| {
-                let inferred = infer_time_bucket(levels[0].files());
-                info!(
-                    "Compaction window for region {} is not present, inferring from files: {:?}",
-                    region_id, inferred
-                );
-                inferred
-            });
+        let time_window_size =
+            compaction_time_window_size(&current_version, self.time_window_seconds);
 
         // Find active window from files in level 0.
         let active_window = find_latest_window_in_seconds(levels[0].files(), time_window_size);
         // Assign files to windows
-        let windows = assign_to_windows(levels.iter().flat_map(LevelMeta::files), time_window_size);
-        let outputs = self.build_output(&windows, active_window);
+        let mut windows =
+            assign_to_windows(levels.iter().flat_map(LevelMeta::files), time_window_size);
+        let mut outputs =
+            pick_downsample_outputs(&mut windows, &current_version.options.downsample, now);
+        outputs.extend(self.build_output(&windows, active_window));
 
         if outputs.is_empty() && expired_ssts.is_empty() {
             // Nothing to compact, we are done. Notifies all waiters as we consume the compaction request.
@@ -188,13 +189,36 @@ impl Picker for TwcsPicker {
             cache_manager,
             storage: current_version.options.storage.clone(),
             index_options: current_version.options.index_options.clone(),
+            append_mode: current_version.options.append_mode,
         };
         Some(Box::new(task))
     }
 }
 
+/// Returns the compaction time window size (in seconds) of the region.
+///
+/// Uses the window of the last compaction, or `time_window_seconds` from the options
+/// if there is no compaction yet. Infers the window from files if both are absent.
+pub(crate) fn compaction_time_window_size(
+    current_version: &VersionRef,
+    time_window_seconds: Option<i64>,
+) -> i64 {
+    current_version
+        .compaction_time_window
+        .map(|window| window.as_secs() as i64)
+        .or(time_window_seconds)
+        .unwrap_or_else(|| {
+            let inferred = infer_time_bucket(current_version.ssts.levels()[0].files());
+            info!(
+                "Compaction window for region {} is not present, inferring from files: {:?}",
+                current_version.metadata.region_id, inferred
+            );
+            inferred
+        })
+}
+
 /// Assigns files to windows with predefined window size (in seconds) by their max timestamps.
-fn assign_to_windows<'a>(
+pub(crate) fn assign_to_windows<'a>(
     files: impl Iterator<Item = &'a FileHandle>,
     time_window_size: i64,
 ) -> BTreeMap<i64, Vec<FileHandle>> {
@@ -255,6 +279,8 @@ pub(crate) struct TwcsCompactionTask {
     pub(crate) storage: Option<String>,
     /// Index options of the region.
     pub(crate) index_options: IndexOptions,
+    /// The region is in append mode.
+    pub(crate) append_mode: bool,
 }
 
 impl Debug for TwcsCompactionTask {
@@ -324,6 +350,7 @@ impl TwcsCompactionTask {
                     .write_buffer_size
                     .as_bytes() as usize,
             );
+            let index_compression = self.engine_config.inverted_index.compression.codec();
 
             let metadata = self.metadata.clone();
             let sst_layer = self.sst_layer.clone();
@@ -332,9 +359,31 @@ impl TwcsCompactionTask {
             let cache_manager = self.cache_manager.clone();
             let storage = self.storage.clone();
             let index_options = self.index_options.clone();
+            let append_mode = self.append_mode;
             futs.push(async move {
-                let reader =
-                    build_sst_reader(metadata.clone(), sst_layer.clone(), &output.inputs).await?;
+                let reader: BoxedBatchReader = match &output.downsample {
+                    Some(downsample) => {
+                        build_downsample_reader(
+                            metadata.clone(),
+                            sst_layer.clone(),
+                            &output.inputs,
+                            downsample,
+                            append_mode,
+                            output.filter_deleted,
+                        )
+                        .await?
+                    }
+                    None => {
+                        build_sst_reader(
+                            metadata.clone(),
+                            sst_layer.clone(),
+                            &output.inputs,
+                            append_mode,
+                            output.filter_deleted,
+                        )
+                        .await?
+                    }
+                };
                 let file_meta_opt = sst_layer
                     .write_sst(
                         SstWriteRequest {
@@ -346,6 +395,7 @@ impl TwcsCompactionTask {
                             create_inverted_index,
                             mem_threshold_index_create,
                             index_write_buffer_size,
+                            index_compression,
                             index_options,
                         },
                         &write_opts,
@@ -357,11 +407,10 @@ impl TwcsCompactionTask {
                         time_range: sst_info.time_range,
                         level: output.output_level,
                         file_size: sst_info.file_size,
-                        available_indexes: sst_info
-                            .inverted_index_available
-                            .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
-                            .unwrap_or_default(),
+                        available_indexes: sst_info.available_indexes,
                         index_file_size: sst_info.index_file_size,
+                        index_file_id: None,
+                        downsample_interval: output.downsample.as_ref().map(|d| d.interval),
                     });
                 Ok(file_meta_opt)
             });
@@ -425,6 +474,10 @@ impl TwcsCompactionTask {
 #[async_trait::async_trait]
 impl CompactionTask for TwcsCompactionTask {
     async fn run(&mut self) {
+        // Keeps outputs from being collected by orphan file gc until they are committed.
+        let writing_files = self
+            .sst_layer
+            .register_writing_files(self.outputs.iter().map(|output| output.output_file_id));
         let notify = match self.handle_compaction().await {
             Ok((added, deleted)) => {
                 info!(
@@ -442,6 +495,7 @@ impl CompactionTask for TwcsCompactionTask {
                         .compaction_time_window
                         .map(|seconds| Duration::from_secs(seconds as u64)),
                     start_time: self.start_time,
+                    _writing_files: writing_files,
                 })
             }
             Err(e) => {
@@ -528,7 +582,7 @@ pub(crate) const TIME_BUCKETS: TimeBuckets = TimeBuckets([
 ]);
 
 /// Finds all expired SSTs across levels.
-fn get_expired_ssts(
+pub(crate) fn get_expired_ssts(
     levels: &[LevelMeta],
     ttl: Option<Duration>,
     now: Timestamp,
@@ -558,6 +612,11 @@ pub(crate) struct CompactionOutput {
     pub output_level: Level,
     /// Compaction input files.
     pub inputs: Vec<FileHandle>,
+    /// Downsamples rows of inputs if present.
+    pub downsample: Option<Downsample>,
+    /// Whether to remove deleted rows. Deleted rows must be kept if files not in
+    /// inputs may contain rows they delete.
+    pub filter_deleted: bool,
 }
 
 /// Builds [BoxedBatchReader] that reads all SST files and yields batches in primary key order.
@@ -565,15 +624,65 @@ async fn build_sst_reader(
     metadata: RegionMetadataRef,
     sst_layer: AccessLayerRef,
     inputs: &[FileHandle],
+    append_mode: bool,
+    filter_deleted: bool,
 ) -> error::Result<BoxedBatchReader> {
     SeqScan::new(sst_layer, ProjectionMapper::all(&metadata)?)
         .with_files(inputs.to_vec())
+        .with_append_mode(append_mode)
+        .with_filter_deleted(filter_deleted)
         // We ignore file not found error during compaction.
         .with_ignore_file_not_found(true)
         .build_reader()
         .await
 }
 
+/// Builds a [DownsampleReader] to downsample rows of SSTs.
+///
+/// Rows of downsampled SSTs hold aggregated states, so rows of other SSTs are converted
+/// to states before being merged with them.
+async fn build_downsample_reader(
+    metadata: RegionMetadataRef,
+    sst_layer: AccessLayerRef,
+    inputs: &[FileHandle],
+    downsample: &Downsample,
+    append_mode: bool,
+    filter_deleted: bool,
+) -> error::Result<BoxedBatchReader> {
+    let field_functions = downsample::field_functions(downsample, &metadata);
+    let (state_files, raw_files): (Vec<_>, Vec<_>) = inputs
+        .iter()
+        .cloned()
+        .partition(|file| file.downsample_interval().is_some());
+    let raw_sources = SeqScan::new(sst_layer.clone(), ProjectionMapper::all(&metadata)?)
+        .with_files(raw_files)
+        .with_ignore_file_not_found(true)
+        .build_sources()
+        .await?;
+    let state_sources = SeqScan::new(sst_layer, ProjectionMapper::all(&metadata)?)
+        .with_files(state_files)
+        .with_ignore_file_not_found(true)
+        .build_sources()
+        .await?;
+
+    // Merges all rows by one reader so duplicate and deleted rows are removed across SSTs.
+    let mut builder = MergeReaderBuilder::from_sources(state_sources);
+    for source in raw_sources {
+        let raw = RawRowReader::new(source, downsample.function, field_functions.clone());
+        builder.push_batch_reader(Box::new(raw));
+    }
+    let reader = builder
+        .dedup(!append_mode)
+        .filter_deleted(filter_deleted)
+        .build()
+        .await?;
+    Ok(Box::new(DownsampleReader::new(
+        Box::new(reader),
+        downsample,
+        field_functions,
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -20,6 +20,7 @@ use std::time::Duration;
 use common_base::readable_size::ReadableSize;
 use common_telemetry::warn;
 use object_store::util::join_dir;
+use puffin::blob_metadata::CompressionCodec;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, NoneAsEmptyString};
 
@@ -29,6 +30,10 @@ use crate::sst::DEFAULT_WRITE_BUFFER_SIZE;
 
 /// Default max running background job.
 const DEFAULT_MAX_BG_JOB: usize = 4;
+/// Default max running cache warm-ups.
+const DEFAULT_MAX_WARM_UP: usize = 1;
+/// Default max running index build jobs.
+const DEFAULT_MAX_INDEX_BUILD: usize = 1;
 
 const MULTIPART_UPLOAD_MINIMUM_SIZE: ReadableSize = ReadableSize::mb(5);
 /// Default channel size for parallel scan task.
@@ -87,6 +92,33 @@ pub struct MitoConfig {
     pub experimental_write_cache_path: String,
     /// Capacity for write cache.
     pub experimental_write_cache_size: ReadableSize,
+    /// Whether to also cache SSTs read from the object store in the write cache.
+    /// Only takes effect if the write cache is enabled.
+    pub enable_experimental_read_cache: bool,
+    /// Whether to warm up caches after opening or catching up a region.
+    pub enable_cache_warm_up: bool,
+    /// Max bytes of SST and index files to download into the write cache
+    /// while warming up a region.
+    pub cache_warm_up_size: ReadableSize,
+    /// Max bytes of SST and index files to download into the write cache
+    /// while warming up all regions of the engine.
+    pub global_cache_warm_up_size: ReadableSize,
+    /// Max number of regions to warm up at the same time (default 1).
+    pub max_background_warm_ups: usize,
+
+    // Orphan file GC configs:
+    /// Interval to collect SST and index files that are not referenced by the
+    /// manifest. Disabled if not set (default).
+    #[serde(with = "humantime_serde")]
+    pub orphan_file_gc_interval: Option<Duration>,
+    /// Orphan files modified within this period are never collected as they
+    /// may belong to an ongoing flush or compaction (default 1 hour).
+    #[serde(with = "humantime_serde")]
+    pub orphan_file_grace_period: Duration,
+
+    // Index build configs:
+    /// Max number of regions to build indexes for existing SSTs at the same time (default 1).
+    pub max_background_index_builds: usize,
 
     // Other configs:
     /// Buffer size for SST writing.
@@ -126,6 +158,14 @@ impl Default for MitoConfig {
             enable_experimental_write_cache: false,
             experimental_write_cache_path: String::new(),
             experimental_write_cache_size: ReadableSize::mb(512),
+            enable_experimental_read_cache: false,
+            enable_cache_warm_up: false,
+            cache_warm_up_size: ReadableSize::mb(256),
+            global_cache_warm_up_size: ReadableSize::gb(1),
+            max_background_warm_ups: DEFAULT_MAX_WARM_UP,
+            orphan_file_gc_interval: None,
+            orphan_file_grace_period: Duration::from_secs(60 * 60),
+            max_background_index_builds: DEFAULT_MAX_INDEX_BUILD,
             sst_write_buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
             scan_parallelism: divide_num_cpus(4),
             parallel_scan_channel_size: DEFAULT_SCAN_CHANNEL_SIZE,
@@ -164,6 +204,22 @@ impl MitoConfig {
             self.max_background_jobs = DEFAULT_MAX_BG_JOB;
         }
 
+        if self.max_background_warm_ups == 0 {
+            warn!(
+                "Sanitize max background warm ups 0 to {}",
+                DEFAULT_MAX_WARM_UP
+            );
+            self.max_background_warm_ups = DEFAULT_MAX_WARM_UP;
+        }
+
+        if self.max_background_index_builds == 0 {
+            warn!(
+                "Sanitize max background index builds 0 to {}",
+                DEFAULT_MAX_INDEX_BUILD
+            );
+            self.max_background_index_builds = DEFAULT_MAX_INDEX_BUILD;
+        }
+
         if self.global_write_buffer_reject_size <= self.global_write_buffer_size {
             self.global_write_buffer_reject_size = self.global_write_buffer_size * 2;
             warn!(
@@ -193,6 +249,16 @@ impl MitoConfig {
             );
         }
 
+        if self.orphan_file_gc_interval == Some(Duration::ZERO) {
+            warn!("Sanitize orphan file gc interval 0 to disable the gc");
+            self.orphan_file_gc_interval = None;
+        }
+
+        if self.enable_experimental_read_cache && !self.enable_experimental_write_cache {
+            warn!("Read cache is disabled as it requires the write cache");
+            self.enable_experimental_read_cache = false;
+        }
+
         // Sets write cache path if it is empty.
         if self.experimental_write_cache_path.is_empty() {
             self.experimental_write_cache_path = join_dir(data_home, "write_cache");
@@ -259,6 +325,30 @@ impl Mode {
     }
 }
 
+/// Compression codec for index blobs.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexCompression {
+    /// Index blobs are not compressed.
+    #[default]
+    None,
+    /// Index blobs are compressed with LZ4.
+    Lz4,
+    /// Index blobs are compressed with Zstandard.
+    Zstd,
+}
+
+impl IndexCompression {
+    /// Returns the codec to compress index blobs with, `None` if uncompressed.
+    pub fn codec(&self) -> Option<CompressionCodec> {
+        match self {
+            IndexCompression::None => None,
+            IndexCompression::Lz4 => Some(CompressionCodec::Lz4),
+            IndexCompression::Zstd => Some(CompressionCodec::Zstd),
+        }
+    }
+}
+
 /// Configuration options for the inverted index.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -278,6 +368,9 @@ pub struct InvertedIndexConfig {
     pub mem_threshold_on_create: Option<ReadableSize>,
     /// File system path to store intermediate files for external sort, defaults to `{data_home}/index_intermediate`.
     pub intermediate_path: String,
+    /// Compression codec of index blobs. The footer of the index file is
+    /// compressed with LZ4 if blobs are compressed.
+    pub compression: IndexCompression,
 }
 
 impl Default for InvertedIndexConfig {
@@ -289,6 +382,7 @@ impl Default for InvertedIndexConfig {
             write_buffer_size: ReadableSize::mb(8),
             mem_threshold_on_create: Some(ReadableSize::mb(64)),
             intermediate_path: String::new(),
+            compression: IndexCompression::None,
         }
     }
 }
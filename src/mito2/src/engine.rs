@@ -17,6 +17,8 @@
 #[cfg(test)]
 mod alter_test;
 #[cfg(test)]
+mod append_mode_test;
+#[cfg(test)]
 mod basic_test;
 #[cfg(test)]
 mod catchup_test;
@@ -30,6 +32,10 @@ mod create_test;
 mod drop_test;
 #[cfg(test)]
 mod flush_test;
+#[cfg(test)]
+mod gc_test;
+#[cfg(test)]
+mod index_build_test;
 #[cfg(any(test, feature = "test"))]
 pub mod listener;
 #[cfg(test)]
@@ -41,8 +47,12 @@ mod projection_test;
 #[cfg(test)]
 mod prune_test;
 #[cfg(test)]
+mod scan_test;
+#[cfg(test)]
 mod set_readonly_test;
 #[cfg(test)]
+mod snapshot_test;
+#[cfg(test)]
 mod truncate_test;
 
 use std::any::Any;
@@ -57,17 +67,25 @@ use object_store::manager::ObjectStoreManagerRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::metadata::RegionMetadataRef;
-use store_api::region_engine::{RegionEngine, RegionRole, SetReadonlyResponse};
+use store_api::region_engine::{
+    IndexBuildProgressInfo, OrphanFileInfo, OrphanFilesInfo, RegionEngine, RegionRole,
+    SetReadonlyResponse, SnapshotInfo,
+};
 use store_api::region_request::{AffectedRows, RegionRequest};
 use store_api::storage::{RegionId, ScanRequest};
 use tokio::sync::oneshot;
 
 use crate::config::MitoConfig;
-use crate::error::{InvalidRequestSnafu, RecvSnafu, RegionNotFoundSnafu, Result};
-use crate::manifest::action::RegionEdit;
+use crate::error::{
+    InvalidRequestSnafu, RecvSnafu, RegionNotFoundSnafu, RegionReadonlySnafu, Result,
+};
+use crate::gc::{collect_orphan_files, OrphanFileReport};
+use crate::index_build::IndexBuildProgress;
+use crate::manifest::action::{RegionEdit, RegionSnapshot};
 use crate::metrics::HANDLE_REQUEST_ELAPSED;
 use crate::read::scan_region::{ScanParallism, ScanRegion, Scanner};
-use crate::region::RegionUsage;
+use crate::region::version::VersionRef;
+use crate::region::{MitoRegionRef, RegionUsage};
 use crate::request::WorkerRequest;
 use crate::worker::WorkerGroup;
 
@@ -115,6 +133,17 @@ impl MitoEngine {
         self.inner.handle_query(region_id, request)
     }
 
+    /// Returns a scanner to scan for `request`, resolving the version to read
+    /// if the request travels in time.
+    async fn scanner_as_of(&self, region_id: RegionId, request: ScanRequest) -> Result<Scanner> {
+        if request.time_travel.is_none() {
+            return self.scanner(region_id, request);
+        }
+        self.inner
+            .handle_time_travel_query(region_id, request)
+            .await
+    }
+
     /// Edit region's metadata by [RegionEdit] directly. Use with care.
     /// Now we only allow adding files to region (the [RegionEdit] struct can only contain a non-empty "files_to_add" field).
     /// Other region editing intention will result in an "invalid request" error.
@@ -141,6 +170,59 @@ impl MitoEngine {
         rx.await.context(RecvSnafu)?
     }
 
+    /// Collects files under the region directory that are not referenced by the region.
+    ///
+    /// Only reports these files if `dry_run` is true, otherwise deletes them. Files
+    /// modified within the configured grace period are ignored.
+    pub async fn gc_orphan_files(
+        &self,
+        region_id: RegionId,
+        dry_run: bool,
+    ) -> Result<OrphanFileReport> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        ensure!(
+            dry_run || region.is_writable(),
+            RegionReadonlySnafu { region_id }
+        );
+
+        collect_orphan_files(&region, self.inner.config.orphan_file_grace_period, dry_run).await
+    }
+
+    /// Schedules a background job to build indexes for existing SSTs of the region.
+    ///
+    /// The job builds indexes for SSTs whose index types don't match the index options
+    /// of the region. If `rebuild_all` is true, it rebuilds indexes for all SSTs, e.g.
+    /// after changing the columns or the segment size of an index. This method returns
+    /// once the job is scheduled.
+    pub async fn build_index(&self, region_id: RegionId, rebuild_all: bool) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        let request = WorkerRequest::BuildIndex {
+            region_id,
+            rebuild_all,
+            tx,
+        };
+        self.inner
+            .workers
+            .submit_to_worker(region_id, request)
+            .await?;
+        rx.await.context(RecvSnafu)?
+    }
+
+    /// Returns the progress of the latest index build job of the region.
+    pub fn index_build_progress(&self, region_id: RegionId) -> Result<Option<IndexBuildProgress>> {
+        let region = self
+            .inner
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+
+        Ok(region.index_build.progress())
+    }
+
     #[cfg(test)]
     pub(crate) fn get_region(&self, id: RegionId) -> Option<crate::region::MitoRegionRef> {
         self.inner.workers.get_region(id)
@@ -163,6 +245,39 @@ fn is_valid_region_edit(edit: &RegionEdit) -> bool {
         )
 }
 
+fn orphan_files_info(report: OrphanFileReport) -> OrphanFilesInfo {
+    OrphanFilesInfo {
+        files: report
+            .orphan_files
+            .into_iter()
+            .map(|file| OrphanFileInfo {
+                path: file.path,
+                file_size: file.file_size,
+            })
+            .collect(),
+        dry_run: report.dry_run,
+    }
+}
+
+fn index_build_progress_info(progress: IndexBuildProgress) -> IndexBuildProgressInfo {
+    IndexBuildProgressInfo {
+        total_files: progress.total_files as u64,
+        processed_files: progress.processed_files as u64,
+        built_files: progress.built_files as u64,
+        failed_files: progress.failed_files as u64,
+        start_time_millis: progress.start_time_millis,
+        finished: progress.finished,
+    }
+}
+
+fn snapshot_info(snapshot: RegionSnapshot) -> SnapshotInfo {
+    SnapshotInfo {
+        name: snapshot.name,
+        manifest_version: snapshot.manifest_version,
+        created_at: snapshot.created_at,
+    }
+}
+
 /// Inner struct of [MitoEngine].
 struct EngineInner {
     /// Region workers group.
@@ -226,7 +341,45 @@ impl EngineInner {
             .workers
             .get_region(region_id)
             .context(RegionNotFoundSnafu { region_id })?;
+        ensure!(
+            request.time_travel.is_none(),
+            InvalidRequestSnafu {
+                region_id,
+                reason: "time travel query must be handled asynchronously",
+            }
+        );
         let version = region.version();
+
+        self.scan_version(&region, version, request, query_start)
+    }
+
+    /// Handles the time travel scan `request` and returns a [Scanner] for the `request`.
+    async fn handle_time_travel_query(
+        &self,
+        region_id: RegionId,
+        mut request: ScanRequest,
+    ) -> Result<Scanner> {
+        let query_start = Instant::now();
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+        let version = match request.time_travel.take() {
+            Some(time_travel) => region.version_as_of(&time_travel).await?,
+            None => region.version(),
+        };
+
+        self.scan_version(&region, version, request, query_start)
+    }
+
+    /// Returns a [Scanner] to scan the `version` of the region.
+    fn scan_version(
+        &self,
+        region: &MitoRegionRef,
+        version: VersionRef,
+        request: ScanRequest,
+        query_start: Instant,
+    ) -> Result<Scanner> {
         // Get cache.
         let cache_manager = self.workers.cache_manager();
         let scan_parallelism = ScanParallism {
@@ -268,6 +421,40 @@ impl EngineInner {
         receiver.await.context(RecvSnafu)
     }
 
+    /// Creates a snapshot named `name` of the region.
+    async fn create_snapshot(&self, region_id: RegionId, name: &str) -> Result<RegionSnapshot> {
+        let (tx, rx) = oneshot::channel();
+        let request = WorkerRequest::CreateSnapshot {
+            region_id,
+            name: name.to_string(),
+            tx,
+        };
+        self.workers.submit_to_worker(region_id, request).await?;
+        rx.await.context(RecvSnafu)?
+    }
+
+    /// Drops the snapshot named `name` of the region.
+    async fn drop_snapshot(&self, region_id: RegionId, name: &str) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        let request = WorkerRequest::DropSnapshot {
+            region_id,
+            name: name.to_string(),
+            tx,
+        };
+        self.workers.submit_to_worker(region_id, request).await?;
+        rx.await.context(RecvSnafu)?
+    }
+
+    /// Lists snapshots of the region.
+    fn list_snapshots(&self, region_id: RegionId) -> Result<Vec<RegionSnapshot>> {
+        let region = self
+            .workers
+            .get_region(region_id)
+            .context(RegionNotFoundSnafu { region_id })?;
+
+        Ok(region.snapshots.list())
+    }
+
     fn role(&self, region_id: RegionId) -> Option<RegionRole> {
         self.workers.get_region(region_id).map(|region| {
             if region.is_writable() {
@@ -304,7 +491,8 @@ impl RegionEngine for MitoEngine {
         region_id: RegionId,
         request: ScanRequest,
     ) -> std::result::Result<SendableRecordBatchStream, BoxedError> {
-        self.scanner(region_id, request)
+        self.scanner_as_of(region_id, request)
+            .await
             .map_err(BoxedError::new)?
             .scan()
             .await
@@ -357,6 +545,63 @@ impl RegionEngine for MitoEngine {
         self.inner.role(region_id)
     }
 
+    /// Creates a snapshot named `name` of the region.
+    ///
+    /// The snapshot captures SST files in the latest manifest and prevents them from being
+    /// purged until the snapshot is dropped. Data in memtables is not included, so flush
+    /// the region before creating the snapshot if necessary.
+    async fn create_snapshot(
+        &self,
+        region_id: RegionId,
+        name: &str,
+    ) -> Result<SnapshotInfo, BoxedError> {
+        self.inner
+            .create_snapshot(region_id, name)
+            .await
+            .map(snapshot_info)
+            .map_err(BoxedError::new)
+    }
+
+    async fn drop_snapshot(&self, region_id: RegionId, name: &str) -> Result<(), BoxedError> {
+        self.inner
+            .drop_snapshot(region_id, name)
+            .await
+            .map_err(BoxedError::new)
+    }
+
+    fn list_snapshots(&self, region_id: RegionId) -> Result<Vec<SnapshotInfo>, BoxedError> {
+        self.inner
+            .list_snapshots(region_id)
+            .map(|snapshots| snapshots.into_iter().map(snapshot_info).collect())
+            .map_err(BoxedError::new)
+    }
+
+    async fn gc_orphan_files(
+        &self,
+        region_id: RegionId,
+        dry_run: bool,
+    ) -> Result<OrphanFilesInfo, BoxedError> {
+        MitoEngine::gc_orphan_files(self, region_id, dry_run)
+            .await
+            .map(orphan_files_info)
+            .map_err(BoxedError::new)
+    }
+
+    async fn build_index(&self, region_id: RegionId, rebuild_all: bool) -> Result<(), BoxedError> {
+        MitoEngine::build_index(self, region_id, rebuild_all)
+            .await
+            .map_err(BoxedError::new)
+    }
+
+    fn index_build_progress(
+        &self,
+        region_id: RegionId,
+    ) -> Result<Option<IndexBuildProgressInfo>, BoxedError> {
+        MitoEngine::index_build_progress(self, region_id)
+            .map(|progress| progress.map(index_build_progress_info))
+            .map_err(BoxedError::new)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -145,3 +145,182 @@ async fn test_compaction_region() {
     let vec = collect_stream_ts(stream).await;
     assert_eq!((0..25).map(|v| v * 1000).collect::<Vec<_>>(), vec);
 }
+
+#[tokio::test]
+async fn test_compaction_region_with_stcs() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("compaction.type", "stcs")
+        .insert_option("compaction.stcs.min_threshold", "3")
+        .insert_option("compaction.stcs.max_threshold", "3")
+        .build();
+
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Flush 5 SSTs of similar sizes.
+    for i in 0..5 {
+        put_and_flush(&engine, region_id, &column_schemas, i * 10..(i + 1) * 10).await;
+    }
+
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    // Merges at most 3 files at a time.
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        3,
+        scanner.num_files(),
+        "unexpected files: {:?}",
+        scanner.file_ids()
+    );
+    let stream = scanner.scan().await.unwrap();
+
+    let vec = collect_stream_ts(stream).await;
+    assert_eq!((0..50).map(|v| v * 1000).collect::<Vec<_>>(), vec);
+}
+
+#[tokio::test]
+async fn test_compaction_region_with_stcs_keep_deletes() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("compaction.type", "stcs")
+        .insert_option("compaction.stcs.min_threshold", "3")
+        .insert_option("compaction.stcs.max_threshold", "3")
+        .build();
+
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // A large file and 3 small files, one of them deletes rows in the large file.
+    put_and_flush(&engine, region_id, &column_schemas, 0..100).await;
+    delete_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 200..201).await;
+    put_and_flush(&engine, region_id, &column_schemas, 300..301).await;
+
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    // Only merges the 3 small files.
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(
+        2,
+        scanner.num_files(),
+        "unexpected files: {:?}",
+        scanner.file_ids()
+    );
+    let stream = scanner.scan().await.unwrap();
+
+    // Deleted rows are still invisible.
+    let vec = collect_stream_ts(stream).await;
+    let expect: Vec<_> = (10..100).chain([200, 300]).map(|v| v * 1000).collect();
+    assert_eq!(expect, vec);
+}
+
+#[tokio::test]
+async fn test_compaction_region_with_downsample() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    // Averages can't be merged without a count field.
+    let request = CreateRequestBuilder::new()
+        .insert_option("downsample.after", "1d")
+        .insert_option("downsample.interval", "10s")
+        .build();
+    assert!(engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .is_err());
+
+    let request = CreateRequestBuilder::new()
+        .insert_option("downsample.after", "1d")
+        .insert_option("downsample.interval", "10s")
+        .insert_option("downsample.function", "max")
+        .build();
+
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Rows in 1970 are older than `downsample.after`.
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 10..20).await;
+    put_and_flush(&engine, region_id, &column_schemas, 20..25).await;
+
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let files: Vec<_> = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .collect();
+    assert_eq!(1, files.len(), "unexpected files: {:?}", files);
+    assert_eq!(
+        Some(std::time::Duration::from_secs(10)),
+        files[0].downsample_interval()
+    );
+
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 9.0     | 1970-01-01T00:00:00 |
+| a     | 9.0     | 1970-01-01T00:00:10 |
+| a     | 4.0     | 1970-01-01T00:00:20 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+
+    // The downsampled window is not compacted again.
+    let output = engine
+        .handle_request(region_id, RegionRequest::Compact(RegionCompactRequest {}))
+        .await
+        .unwrap();
+    assert_eq!(output, 0);
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(vec![files[0].file_id()], scanner.file_ids());
+}
@@ -0,0 +1,160 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use api::v1::Rows;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::RegionId;
+
+use crate::config::MitoConfig;
+use crate::sst::file::FileId;
+use crate::sst::location;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+#[tokio::test]
+async fn test_engine_gc_orphan_files() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::with_prefix("gc-orphan-files");
+    let engine = env
+        .create_engine(MitoConfig {
+            orphan_file_grace_period: Duration::ZERO,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    // Files that are not referenced by the manifest.
+    let object_store = env.get_object_store().unwrap();
+    let orphan_id = FileId::random();
+    let sst_path = location::sst_file_path(&region_dir, orphan_id);
+    let index_path = location::index_file_path(&region_dir, orphan_id);
+    object_store.write(&sst_path, vec![0; 16]).await.unwrap();
+    object_store.write(&index_path, vec![0; 8]).await.unwrap();
+    // Wait for the grace period.
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let report = engine.gc_orphan_files(region_id, true).await.unwrap();
+    assert!(report.dry_run);
+    let mut paths: Vec<_> = report
+        .orphan_files
+        .iter()
+        .map(|file| file.path.clone())
+        .collect();
+    paths.sort_unstable();
+    let mut expect = vec![index_path.clone(), sst_path.clone()];
+    expect.sort_unstable();
+    assert_eq!(expect, paths);
+    assert_eq!(24, report.total_size());
+    assert!(object_store.is_exist(&sst_path).await.unwrap());
+
+    let report = engine.gc_orphan_files(region_id, false).await.unwrap();
+    assert_eq!(2, report.orphan_files.len());
+    assert!(!object_store.is_exist(&sst_path).await.unwrap());
+    assert!(!object_store.is_exist(&index_path).await.unwrap());
+
+    // Files of the region are kept.
+    let report = engine.gc_orphan_files(region_id, false).await.unwrap();
+    assert!(report.orphan_files.is_empty());
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    let files: Vec<_> = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .collect();
+    assert_eq!(1, files.len());
+    let path = location::sst_file_path(&region_dir, files[0].file_id());
+    assert!(object_store.is_exist(&path).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_engine_gc_orphan_files_grace_period() {
+    let mut env = TestEnv::with_prefix("gc-orphan-files-grace");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let object_store = env.get_object_store().unwrap();
+    let sst_path = location::sst_file_path(&region_dir, FileId::random());
+    object_store.write(&sst_path, vec![0; 16]).await.unwrap();
+
+    // The file is within the grace period.
+    let report = engine.gc_orphan_files(region_id, false).await.unwrap();
+    assert!(report.orphan_files.is_empty());
+    assert!(object_store.is_exist(&sst_path).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_engine_gc_skip_writing_files() {
+    let mut env = TestEnv::with_prefix("gc-writing-files");
+    let engine = env
+        .create_engine(MitoConfig {
+            orphan_file_grace_period: Duration::ZERO,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // An output of a running job, e.g. a compaction.
+    let region = engine.get_region(region_id).unwrap();
+    let file_id = FileId::random();
+    let guard = region.access_layer.register_writing_files([file_id]);
+    let object_store = env.get_object_store().unwrap();
+    let sst_path = location::sst_file_path(&region_dir, file_id);
+    object_store.write(&sst_path, vec![0; 16]).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let report = engine.gc_orphan_files(region_id, false).await.unwrap();
+    assert!(report.orphan_files.is_empty());
+    assert!(object_store.is_exist(&sst_path).await.unwrap());
+
+    // The file becomes orphan if the job fails.
+    drop(guard);
+    let report = engine.gc_orphan_files(region_id, false).await.unwrap();
+    assert_eq!(1, report.orphan_files.len());
+    assert!(!object_store.is_exist(&sst_path).await.unwrap());
+}
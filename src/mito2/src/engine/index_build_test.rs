@@ -0,0 +1,181 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use api::v1::Rows;
+use common_query::prelude::Expr;
+use common_recordbatch::RecordBatches;
+use datafusion_expr::{col, lit};
+use object_store::ObjectStore;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::{InvertedIndexConfig, MitoConfig, Mode};
+use crate::engine::MitoEngine;
+use crate::index_build::IndexBuildProgress;
+use crate::sst::file::{FileMeta, IndexType};
+use crate::sst::location;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, reopen_region, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+/// Waits until the index build job of the region is finished.
+async fn wait_index_build(engine: &MitoEngine, region_id: RegionId) -> IndexBuildProgress {
+    for _ in 0..100 {
+        let progress = engine.index_build_progress(region_id).unwrap().unwrap();
+        if progress.finished {
+            return progress;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Index build of region {region_id} is not finished");
+}
+
+/// Waits until the file at `path` is purged.
+async fn wait_file_purged(object_store: &ObjectStore, path: &str) {
+    for _ in 0..100 {
+        if !object_store.is_exist(path).await.unwrap() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("File {path} is not purged");
+}
+
+fn region_files(engine: &MitoEngine, region_id: RegionId) -> Vec<FileMeta> {
+    let region = engine.get_region(region_id).unwrap();
+    let version = region.version();
+    version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files().map(|file| file.meta()))
+        .collect()
+}
+
+async fn scan_tag(engine: &MitoEngine, region_id: RegionId, tag: &str) -> String {
+    let request = ScanRequest {
+        filters: vec![Expr::from(col("tag_0").eq(lit(tag)))],
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_engine_build_index() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::with_prefix("build-index");
+    let engine = env
+        .create_engine(MitoConfig {
+            inverted_index: InvertedIndexConfig {
+                create_on_flush: Mode::Disable,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    assert!(engine.index_build_progress(region_id).unwrap().is_none());
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(0, 15),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, Some(5)).await;
+    let files = region_files(&engine, region_id);
+    assert_eq!(1, files.len());
+    assert!(!files[0].index_file_available());
+
+    engine.build_index(region_id, false).await.unwrap();
+    let progress = wait_index_build(&engine, region_id).await;
+    assert_eq!(
+        (1, 1, 1, 0),
+        (
+            progress.total_files,
+            progress.processed_files,
+            progress.built_files,
+            progress.failed_files
+        )
+    );
+    let files = region_files(&engine, region_id);
+    let file = &files[0];
+    assert_eq!(&[IndexType::InvertedIndex], &file.available_indexes[..]);
+    assert_ne!(file.file_id, file.index_id());
+    assert!(file.index_file_size > 0);
+    let object_store = env.get_object_store().unwrap();
+    let index_path = location::index_file_path(&region_dir, file.index_id());
+    assert!(object_store.is_exist(&index_path).await.unwrap());
+
+    // The index is applied to the query.
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 10    | 10.0    | 1970-01-01T00:00:10 |
++-------+---------+---------------------+";
+    assert_eq!(expected, scan_tag(&engine, region_id, "10").await);
+
+    // The index is up to date.
+    engine.build_index(region_id, false).await.unwrap();
+    let progress = wait_index_build(&engine, region_id).await;
+    assert_eq!((1, 0), (progress.processed_files, progress.built_files));
+    assert_eq!(file, &region_files(&engine, region_id)[0]);
+
+    // Rebuilds the index and purges the old index file once the old handle is released.
+    engine.build_index(region_id, true).await.unwrap();
+    let progress = wait_index_build(&engine, region_id).await;
+    assert_eq!((1, 1), (progress.processed_files, progress.built_files));
+    let rebuilt = region_files(&engine, region_id).remove(0);
+    assert_ne!(file.index_id(), rebuilt.index_id());
+    wait_file_purged(&object_store, &index_path).await;
+    let rebuilt_path = location::index_file_path(&region_dir, rebuilt.index_id());
+    assert!(object_store.is_exist(&rebuilt_path).await.unwrap());
+    assert_eq!(expected, scan_tag(&engine, region_id, "10").await);
+
+    // The new index is persisted in the manifest.
+    reopen_region(&engine, region_id, region_dir, true).await;
+    assert_eq!(rebuilt, region_files(&engine, region_id)[0]);
+    assert_eq!(expected, scan_tag(&engine, region_id, "10").await);
+}
+
+#[tokio::test]
+async fn test_engine_build_index_readonly() {
+    let mut env = TestEnv::with_prefix("build-index-readonly");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    reopen_region(&engine, region_id, region_dir, false).await;
+
+    assert!(engine.build_index(region_id, false).await.is_err());
+    assert!(engine.index_build_progress(region_id).unwrap().is_none());
+}
@@ -22,14 +22,18 @@ use store_api::region_engine::RegionEngine;
 use store_api::region_request::RegionRequest;
 use store_api::storage::{RegionId, ScanRequest};
 
-use crate::config::MitoConfig;
+use crate::config::{IndexCompression, InvertedIndexConfig, MitoConfig};
 use crate::test_util::{
     build_rows, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
 };
 
 async fn check_prune_row_groups(expr: DfExpr, expected: &str) {
+    check_prune_row_groups_with_config(MitoConfig::default(), expr, expected).await;
+}
+
+async fn check_prune_row_groups_with_config(config: MitoConfig, expr: DfExpr, expected: &str) {
     let mut env = TestEnv::new();
-    let engine = env.create_engine(MitoConfig::default()).await;
+    let engine = env.create_engine(config).await;
 
     let region_id = RegionId::new(1, 1);
     let request = CreateRequestBuilder::new().build();
@@ -132,6 +136,34 @@ async fn test_prune_tag_and_field() {
     .await;
 }
 
+#[tokio::test]
+async fn test_prune_tag_compressed_index() {
+    for compression in [IndexCompression::Lz4, IndexCompression::Zstd] {
+        let config = MitoConfig {
+            inverted_index: InvertedIndexConfig {
+                compression,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        check_prune_row_groups_with_config(
+            config,
+            datafusion_expr::col("tag_0").gt(lit(ScalarValue::Utf8(Some("4".to_string())))),
+            "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 5     | 5.0     | 1970-01-01T00:00:05 |
+| 6     | 6.0     | 1970-01-01T00:00:06 |
+| 7     | 7.0     | 1970-01-01T00:00:07 |
+| 8     | 8.0     | 1970-01-01T00:00:08 |
+| 9     | 9.0     | 1970-01-01T00:00:09 |
++-------+---------+---------------------+",
+        )
+        .await;
+    }
+}
+
 /// Creates a time range `[start_sec, end_sec)`
 fn time_range_expr(start_sec: i64, end_sec: i64) -> Expr {
     Expr::from(
@@ -0,0 +1,160 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for scanners.
+
+use api::v1::Rows;
+use common_recordbatch::{OrderOption, RecordBatches, SendableRecordBatchStream};
+use datatypes::arrow::compute::SortOptions;
+use datatypes::prelude::ScalarVector;
+use datatypes::vectors::TimestampMillisecondVector;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::RegionRequest;
+use store_api::storage::{RegionId, ScanRequest};
+
+use crate::config::MitoConfig;
+use crate::test_util::{
+    build_rows_for_key, flush_region, put_rows, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+async fn collect_stream_ts(stream: SendableRecordBatchStream) -> Vec<i64> {
+    let mut res = Vec::new();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    for batch in batches {
+        let ts_col = batch
+            .column_by_name("ts")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondVector>()
+            .unwrap();
+        res.extend(ts_col.iter_data().map(|t| t.unwrap().0.value() / 1000));
+    }
+    res
+}
+
+fn order_by_ts(descending: bool) -> ScanRequest {
+    ScanRequest {
+        output_ordering: Some(vec![OrderOption {
+            name: "ts".to_string(),
+            options: SortOptions {
+                descending,
+                nulls_first: false,
+            },
+        }]),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_windowed_scan() {
+    let mut env = TestEnv::new();
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // SST with time range [0, 2].
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_for_key("a", 0, 3, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    // SST with time range [3, 5].
+    let mut rows = build_rows_for_key("a", 3, 6, 0);
+    rows.extend(build_rows_for_key("b", 3, 6, 0));
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows,
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    // Memtable with time range [5, 7] overlaps with the second SST.
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("b", 5, 8, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let scanner = engine.scanner(region_id, order_by_ts(true)).unwrap();
+    assert_eq!(Some(2), scanner.num_windows());
+    assert_eq!(1, scanner.num_memtables());
+    assert_eq!(2, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    assert_eq!(
+        vec![7, 6, 5, 5, 4, 4, 3, 3, 2, 1, 0],
+        collect_stream_ts(stream).await
+    );
+
+    let scanner = engine.scanner(region_id, order_by_ts(false)).unwrap();
+    let stream = scanner.scan().await.unwrap();
+    assert_eq!(
+        vec![0, 1, 2, 3, 3, 4, 4, 5, 5, 6, 7],
+        collect_stream_ts(stream).await
+    );
+
+    // Falls back to the sequential scan if the request doesn't order by the time index.
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(None, scanner.num_windows());
+}
+
+#[tokio::test]
+async fn test_unordered_scan_in_parallel() {
+    let mut env = TestEnv::new();
+    let engine = env
+        .create_engine(MitoConfig {
+            scan_parallelism: 2,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .insert_option("append_mode", "true")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    for key in ["a", "b", "c"] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows_for_key(key, 0, 3, 0),
+        };
+        put_rows(&engine, region_id, rows).await;
+        flush_region(&engine, region_id, None).await;
+    }
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_for_key("a", 0, 3, 0),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let scanner = engine.scanner(region_id, ScanRequest::default()).unwrap();
+    assert_eq!(None, scanner.num_windows());
+    assert_eq!(1, scanner.num_memtables());
+    assert_eq!(3, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    // Rows are returned in arbitrary order and duplicate rows are kept.
+    let mut timestamps = collect_stream_ts(stream).await;
+    timestamps.sort_unstable();
+    assert_eq!(vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2], timestamps);
+}
@@ -0,0 +1,161 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::Rows;
+use common_recordbatch::RecordBatches;
+use common_time::util::current_time_millis;
+use store_api::region_engine::RegionEngine;
+use store_api::region_request::{RegionRequest, RegionTruncateRequest};
+use store_api::storage::{RegionId, ScanRequest, TimeTravel};
+
+use crate::config::MitoConfig;
+use crate::engine::MitoEngine;
+use crate::test_util::{
+    build_rows, flush_region, put_rows, reopen_region, rows_schema, CreateRequestBuilder, TestEnv,
+};
+
+async fn scan_as_of(engine: &MitoEngine, region_id: RegionId, time_travel: TimeTravel) -> String {
+    let request = ScanRequest {
+        time_travel: Some(time_travel),
+        ..Default::default()
+    };
+    let stream = engine.handle_query(region_id, request).await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+async fn scan_latest(engine: &MitoEngine, region_id: RegionId) -> String {
+    let stream = engine
+        .handle_query(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    batches.pretty_print().unwrap()
+}
+
+#[tokio::test]
+async fn test_engine_snapshot_and_time_travel() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::with_prefix("snapshot-time-travel");
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new().build();
+    let region_dir = request.region_dir.clone();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    let snapshot = engine.create_snapshot(region_id, "s1").await.unwrap();
+    assert_eq!("s1", snapshot.name);
+    let region = engine.get_region(region_id).unwrap();
+    assert_eq!(1, region.snapshots.get("s1").unwrap().files.len());
+    // Snapshot names are unique.
+    assert!(engine.create_snapshot(region_id, "s1").await.is_err());
+    assert!(engine.create_snapshot(region_id, "../s2").await.is_err());
+    assert!(engine.create_snapshot(region_id, "").await.is_err());
+
+    // Removes all files from the latest version.
+    engine
+        .handle_request(region_id, RegionRequest::Truncate(RegionTruncateRequest {}))
+        .await
+        .unwrap();
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(5, 7),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+
+    let latest = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 5     | 5.0     | 1970-01-01T00:00:05 |
+| 6     | 6.0     | 1970-01-01T00:00:06 |
++-------+---------+---------------------+";
+    let old = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
++-------+---------+---------------------+";
+    assert_eq!(latest, scan_latest(&engine, region_id).await);
+    assert_eq!(
+        old,
+        scan_as_of(&engine, region_id, TimeTravel::Snapshot("s1".to_string())).await
+    );
+    assert_eq!(
+        old,
+        scan_as_of(
+            &engine,
+            region_id,
+            TimeTravel::Version(snapshot.manifest_version)
+        )
+        .await
+    );
+    assert_eq!(
+        latest,
+        scan_as_of(
+            &engine,
+            region_id,
+            TimeTravel::Timestamp(current_time_millis())
+        )
+        .await
+    );
+
+    // Files of the snapshot are still pinned after reopening the region.
+    reopen_region(&engine, region_id, region_dir, true).await;
+    let snapshots = engine.list_snapshots(region_id).unwrap();
+    assert_eq!(vec![snapshot], snapshots);
+    assert_eq!(latest, scan_latest(&engine, region_id).await);
+    assert_eq!(
+        old,
+        scan_as_of(&engine, region_id, TimeTravel::Snapshot("s1".to_string())).await
+    );
+
+    let region = engine.get_region(region_id).unwrap();
+    assert!(region
+        .manifest_manager
+        .load_snapshot_files("s1")
+        .await
+        .unwrap()
+        .is_some());
+
+    engine.drop_snapshot(region_id, "s1").await.unwrap();
+    assert!(region
+        .manifest_manager
+        .load_snapshot_files("s1")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(engine.list_snapshots(region_id).unwrap().is_empty());
+    assert!(engine.drop_snapshot(region_id, "s1").await.is_err());
+    let request = ScanRequest {
+        time_travel: Some(TimeTravel::Snapshot("s1".to_string())),
+        ..Default::default()
+    };
+    assert!(engine.handle_query(region_id, request).await.is_err());
+}
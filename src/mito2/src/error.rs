@@ -167,6 +167,24 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Manifest history of region {} is unavailable, reason: {}",
+        region_id,
+        reason
+    ))]
+    ManifestHistoryUnavailable {
+        region_id: RegionId,
+        reason: String,
+        location: Location,
+    },
+
+    #[snafu(display("Snapshot {} not found in region {}", name, region_id))]
+    SnapshotNotFound {
+        region_id: RegionId,
+        name: String,
+        location: Location,
+    },
+
     #[snafu(display(
         "Failed to convert ConcreteDataType to ColumnDataType, reason: {}",
         reason
@@ -225,6 +243,13 @@ pub enum Error {
         error: DecodeError,
     },
 
+    #[snafu(display("Failed to create WAL, region_id: {}", region_id))]
+    CreateWal {
+        region_id: RegionId,
+        location: Location,
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to delete WAL, region_id: {}", region_id))]
     DeleteWal {
         region_id: RegionId,
@@ -473,6 +498,30 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to write full-text index completely"))]
+    FullTextIndexFinish {
+        source: index::full_text_index::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to apply full-text index"))]
+    ApplyFullTextIndex {
+        source: index::full_text_index::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to write bloom filter index completely"))]
+    BloomFilterIndexFinish {
+        source: index::bloom_filter::error::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to apply bloom filter index"))]
+    ApplyBloomFilterIndex {
+        source: index::bloom_filter::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Operate on aborted index"))]
     OperateAbortedIndex { location: Location },
 
@@ -544,6 +593,21 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to download file, region_id: {}, file_id: {}, file_type: {:?}",
+        region_id,
+        file_id,
+        file_type,
+    ))]
+    Download {
+        region_id: RegionId,
+        file_id: FileId,
+        file_type: FileType,
+        #[snafu(source)]
+        error: std::io::Error,
+        location: Location,
+    },
+
     #[snafu(display("Failed to filter record batch"))]
     FilterRecordBatch {
         source: common_recordbatch::error::Error,
@@ -600,6 +664,7 @@ impl ErrorExt for Error {
             | ReadParquet { .. }
             | WriteWal { .. }
             | ReadWal { .. }
+            | CreateWal { .. }
             | DeleteWal { .. } => StatusCode::StorageUnavailable,
             CompressObject { .. }
             | DecompressObject { .. }
@@ -618,6 +683,8 @@ impl ErrorExt for Error {
             | InvalidScanIndex { .. }
             | InvalidMeta { .. }
             | InvalidRequest { .. }
+            | ManifestHistoryUnavailable { .. }
+            | SnapshotNotFound { .. }
             | FillDefault { .. }
             | ConvertColumnDataType { .. }
             | ColumnNotFound { .. }
@@ -665,6 +732,12 @@ impl ErrorExt for Error {
             | PushIndexValue { source, .. }
             | ApplyIndex { source, .. }
             | IndexFinish { source, .. } => source.status_code(),
+            FullTextIndexFinish { source, .. } | ApplyFullTextIndex { source, .. } => {
+                source.status_code()
+            }
+            BloomFilterIndexFinish { source, .. } | ApplyBloomFilterIndex { source, .. } => {
+                source.status_code()
+            }
             PuffinReadMetadata { source, .. }
             | PuffinReadBlob { source, .. }
             | PuffinFinish { source, .. }
@@ -673,7 +746,7 @@ impl ErrorExt for Error {
             InvalidConfig { .. } => StatusCode::InvalidArguments,
             StaleLogEntry { .. } => StatusCode::Unexpected,
             FilterRecordBatch { source, .. } => source.status_code(),
-            Upload { .. } => StatusCode::StorageUnavailable,
+            Upload { .. } | Download { .. } => StatusCode::StorageUnavailable,
             BiError { .. } => StatusCode::Internal,
             EncodeMemtable { .. } | ReadDataPart { .. } => StatusCode::Internal,
         }
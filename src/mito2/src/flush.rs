@@ -19,13 +19,12 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use common_telemetry::{error, info};
-use smallvec::SmallVec;
 use snafu::ResultExt;
 use store_api::storage::RegionId;
 use strum::IntoStaticStr;
 use tokio::sync::mpsc;
 
-use crate::access_layer::{AccessLayerRef, SstWriteRequest};
+use crate::access_layer::{AccessLayerRef, SstWriteRequest, WritingFilesGuard};
 use crate::cache::CacheManagerRef;
 use crate::config::MitoConfig;
 use crate::error::{
@@ -40,7 +39,7 @@ use crate::request::{
     SenderWriteRequest, WorkerRequest,
 };
 use crate::schedule::scheduler::{Job, SchedulerRef};
-use crate::sst::file::{FileId, FileMeta, IndexType};
+use crate::sst::file::{FileId, FileMeta};
 use crate::sst::file_purger::FilePurgerRef;
 use crate::sst::parquet::WriteOptions;
 use crate::worker::WorkerListener;
@@ -249,7 +248,12 @@ impl RegionFlushTask {
         let timer = FLUSH_ELAPSED.with_label_values(&["total"]).start_timer();
         self.listener.on_flush_begin(self.region_id).await;
 
-        let worker_request = match self.flush_memtables(&version_data.version).await {
+        // Keeps outputs from being collected by orphan file gc until they are committed.
+        let mut writing_files = self.access_layer.register_writing_files([]);
+        let worker_request = match self
+            .flush_memtables(&version_data.version, &mut writing_files)
+            .await
+        {
             Ok(file_metas) => {
                 let memtables_to_remove = version_data
                     .version
@@ -268,6 +272,7 @@ impl RegionFlushTask {
                     memtables_to_remove,
                     senders: std::mem::take(&mut self.senders),
                     file_purger: self.file_purger.clone(),
+                    _writing_files: writing_files,
                     _timer: timer,
                 };
                 WorkerRequest::Background {
@@ -292,7 +297,11 @@ impl RegionFlushTask {
     }
 
     /// Flushes memtables to level 0 SSTs.
-    async fn flush_memtables(&self, version: &VersionRef) -> Result<Vec<FileMeta>> {
+    async fn flush_memtables(
+        &self,
+        version: &VersionRef,
+        writing_files: &mut WritingFilesGuard,
+    ) -> Result<Vec<FileMeta>> {
         let timer = FLUSH_ELAPSED
             .with_label_values(&["flush_memtables"])
             .start_timer();
@@ -315,6 +324,7 @@ impl RegionFlushTask {
             }
 
             let file_id = FileId::random();
+            writing_files.add(file_id);
             let iter = mem.iter(None, None)?;
             let source = Source::Iter(iter);
             let create_inverted_index = self.engine_config.inverted_index.create_on_flush.auto();
@@ -329,6 +339,7 @@ impl RegionFlushTask {
                     .write_buffer_size
                     .as_bytes() as usize,
             );
+            let index_compression = self.engine_config.inverted_index.compression.codec();
 
             // Flush to level 0.
             let write_request = SstWriteRequest {
@@ -340,6 +351,7 @@ impl RegionFlushTask {
                 create_inverted_index,
                 mem_threshold_index_create,
                 index_write_buffer_size,
+                index_compression,
                 index_options: self.index_options.clone(),
             };
             let Some(sst_info) = self
@@ -358,11 +370,10 @@ impl RegionFlushTask {
                 time_range: sst_info.time_range,
                 level: 0,
                 file_size: sst_info.file_size,
-                available_indexes: sst_info
-                    .inverted_index_available
-                    .then(|| SmallVec::from_iter([IndexType::InvertedIndex]))
-                    .unwrap_or_default(),
+                available_indexes: sst_info.available_indexes,
                 index_file_size: sst_info.index_file_size,
+                index_file_id: None,
+                downsample_interval: None,
             };
             file_metas.push(file_meta);
         }
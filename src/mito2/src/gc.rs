@@ -0,0 +1,201 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Garbage collection of orphan files.
+//!
+//! A file is orphan if it is under the region directory but no longer referenced
+//! by the region. They are left behind if the datanode crashes between writing a
+//! file and committing the edit to the manifest, or if a compaction fails.
+
+use std::time::Duration;
+
+use common_telemetry::{info, warn};
+use common_time::util::current_time_millis;
+use futures::TryStreamExt;
+use object_store::util::join_dir;
+use object_store::{Metakey, ObjectStore};
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+
+use crate::error::{OpenDalSnafu, Result};
+use crate::region::MitoRegionRef;
+use crate::sst::file::FileId;
+
+/// Suffix of SST files.
+const SST_FILE_SUFFIX: &str = ".parquet";
+/// Suffix of index files.
+const INDEX_FILE_SUFFIX: &str = ".puffin";
+/// Directory of index files under the region directory.
+const INDEX_DIR: &str = "index";
+
+/// A file under the region directory that is not referenced by the region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanFile {
+    /// Path of the file in the object store.
+    pub path: String,
+    /// Id in the name of the file.
+    pub file_id: FileId,
+    /// Size of the file in bytes.
+    pub file_size: u64,
+}
+
+/// Orphan files found in a region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanFileReport {
+    pub region_id: RegionId,
+    pub orphan_files: Vec<OrphanFile>,
+    /// Whether the orphan files are only reported but not deleted.
+    pub dry_run: bool,
+}
+
+impl OrphanFileReport {
+    /// Returns the total size of orphan files.
+    pub fn total_size(&self) -> u64 {
+        self.orphan_files.iter().map(|file| file.file_size).sum()
+    }
+}
+
+/// Collects orphan files of the `region`.
+///
+/// Outputs of ongoing flush, compaction and index build jobs are never collected.
+/// Files modified within the `grace_period` are also skipped in case they are
+/// written by other writers. Deletes orphan files unless `dry_run` is true.
+pub(crate) async fn collect_orphan_files(
+    region: &MitoRegionRef,
+    grace_period: Duration,
+    dry_run: bool,
+) -> Result<OrphanFileReport> {
+    let object_store = region.access_layer.object_store();
+    let region_dir = region.access_layer.region_dir();
+    let expire_time = current_time_millis() - grace_period.as_millis() as i64;
+
+    // Lists files before collecting referenced files so files added to the
+    // region after listing are not treated as orphan.
+    let sst_files = list_files(object_store, region_dir, SST_FILE_SUFFIX, expire_time).await?;
+    let index_files = list_files(
+        object_store,
+        &join_dir(region_dir, INDEX_DIR),
+        INDEX_FILE_SUFFIX,
+        expire_time,
+    )
+    .await?;
+
+    // Outputs of running flush, compaction and index build jobs. We must collect
+    // them before the version as they are unregistered after being added to the
+    // version.
+    let writing_files = region.access_layer.writing_files();
+    // Files removed from the version are either pinned by snapshots or pending
+    // for purge, so we must collect the version before others.
+    let version = region.version();
+    let mut referenced_ssts = writing_files.clone();
+    let mut referenced_indexes = writing_files;
+    let files = version
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files().map(|file| file.meta()))
+        .chain(region.snapshots.file_metas());
+    for file in files {
+        referenced_ssts.insert(file.file_id);
+        if file.index_file_available() {
+            referenced_indexes.insert(file.index_id());
+        }
+    }
+    // Pending files also contain replaced indexes that are still held by old
+    // handles. Index files of pending SSTs may have been rebuilt, but these files
+    // are usually purged before the grace period expires.
+    let pending_files = region.file_purger.pending_files();
+    referenced_ssts.extend(pending_files.iter().copied());
+    referenced_indexes.extend(pending_files);
+
+    let orphan_files: Vec<_> = sst_files
+        .into_iter()
+        .filter(|file| !referenced_ssts.contains(&file.file_id))
+        .chain(
+            index_files
+                .into_iter()
+                .filter(|file| !referenced_indexes.contains(&file.file_id)),
+        )
+        .collect();
+
+    if !dry_run {
+        for file in &orphan_files {
+            object_store
+                .delete(&file.path)
+                .await
+                .context(OpenDalSnafu)?;
+        }
+    }
+
+    if !orphan_files.is_empty() {
+        info!(
+            "Collected {} orphan files of region {}, dry_run: {}, files: {:?}",
+            orphan_files.len(),
+            region.region_id,
+            dry_run,
+            orphan_files
+        );
+    }
+
+    Ok(OrphanFileReport {
+        region_id: region.region_id,
+        orphan_files,
+        dry_run,
+    })
+}
+
+/// Lists files with `suffix` under `dir` that are last modified before `expire_time`.
+async fn list_files(
+    object_store: &ObjectStore,
+    dir: &str,
+    suffix: &str,
+    expire_time: i64,
+) -> Result<Vec<OrphanFile>> {
+    let mut lister = object_store
+        .lister_with(dir)
+        .metakey(Metakey::ContentLength | Metakey::LastModified)
+        .await
+        .context(OpenDalSnafu)?;
+
+    let mut files = Vec::new();
+    while let Some(entry) = lister.try_next().await.context(OpenDalSnafu)? {
+        let Some(file_id) = entry
+            .name()
+            .strip_suffix(suffix)
+            .and_then(|name| FileId::parse_str(name).ok())
+        else {
+            continue;
+        };
+        let meta = entry.metadata();
+        let Some(last_modified) = meta.last_modified() else {
+            // We can't tell whether the file is still being written.
+            warn!(
+                "Skip file {} without last modified time during orphan file gc",
+                entry.path()
+            );
+            continue;
+        };
+        if last_modified.timestamp_millis() > expire_time {
+            continue;
+        }
+
+        files.push(OrphanFile {
+            path: entry.path().to_string(),
+            file_id,
+            file_size: meta.content_length(),
+        });
+    }
+
+    Ok(files)
+}
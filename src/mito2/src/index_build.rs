@@ -0,0 +1,298 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Building indexes for existing SSTs.
+//!
+//! Index options only take effect on SSTs written by flush and compaction, so SSTs
+//! written before the options change keep their indexes until they are compacted.
+//! The index build job reads these SSTs, writes their indexes to new index files
+//! and commits the new index files to the region one SST at a time.
+
+use std::sync::{Arc, Mutex};
+
+use common_telemetry::{error, info, warn};
+use common_time::util::current_time_millis;
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::cache::file_cache::{FileType, IndexKey};
+use crate::cache::CacheManagerRef;
+use crate::config::MitoConfig;
+use crate::error::{RecvSnafu, Result};
+use crate::read::BatchReader;
+use crate::region::MitoRegionRef;
+use crate::request::{BackgroundNotify, IndexBuilt, WorkerRequest};
+use crate::sst::file::{FileHandle, FileId, FileMeta, IndexType};
+use crate::sst::index::IndexerBuilder;
+use crate::sst::location;
+
+/// Progress of the index build job of a region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexBuildProgress {
+    pub region_id: RegionId,
+    /// Number of SSTs in the region when the job started.
+    pub total_files: usize,
+    /// Number of SSTs checked by the job.
+    pub processed_files: usize,
+    /// Number of SSTs whose new indexes are committed.
+    pub built_files: usize,
+    /// Number of SSTs that failed to build indexes.
+    pub failed_files: usize,
+    /// Start time of the job in millis.
+    pub start_time_millis: i64,
+    /// Whether the job is finished.
+    pub finished: bool,
+}
+
+/// Tracks the latest index build job of a region.
+#[derive(Debug, Default)]
+pub(crate) struct IndexBuildTracker {
+    progress: Mutex<Option<IndexBuildProgress>>,
+}
+
+impl IndexBuildTracker {
+    /// Returns the progress of the latest job.
+    pub(crate) fn progress(&self) -> Option<IndexBuildProgress> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Returns true if a job is running.
+    pub(crate) fn is_running(&self) -> bool {
+        self.progress
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|progress| !progress.finished)
+    }
+
+    /// Starts tracking a new job that checks `total_files` SSTs.
+    pub(crate) fn start(&self, region_id: RegionId, total_files: usize) {
+        *self.progress.lock().unwrap() = Some(IndexBuildProgress {
+            region_id,
+            total_files,
+            processed_files: 0,
+            built_files: 0,
+            failed_files: 0,
+            start_time_millis: current_time_millis(),
+            finished: false,
+        });
+    }
+
+    /// Updates the progress of the running job.
+    fn update(&self, f: impl FnOnce(&mut IndexBuildProgress)) {
+        if let Some(progress) = self.progress.lock().unwrap().as_mut() {
+            f(progress);
+        }
+    }
+
+    /// Marks the running job as finished.
+    pub(crate) fn finish(&self) {
+        self.update(|progress| progress.finished = true);
+    }
+}
+
+/// Result of building indexes for a SST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildOutcome {
+    /// The new index is committed.
+    Built,
+    /// The SST doesn't need a new index or is removed from the region.
+    Skipped,
+    /// Failed to create the index.
+    Failed,
+}
+
+/// Background job to build indexes for SSTs of a region.
+pub(crate) struct IndexBuildJob {
+    pub(crate) region: MitoRegionRef,
+    /// SSTs to check.
+    pub(crate) files: Vec<FileHandle>,
+    /// Whether to build indexes for SSTs whose index types already match the options.
+    pub(crate) rebuild_all: bool,
+    /// Sender to commit new indexes in the region worker.
+    pub(crate) request_sender: mpsc::Sender<WorkerRequest>,
+    pub(crate) cache_manager: CacheManagerRef,
+    pub(crate) engine_config: Arc<MitoConfig>,
+}
+
+impl IndexBuildJob {
+    /// Builds indexes for SSTs one by one and updates the progress.
+    pub(crate) async fn run(self) {
+        let region_id = self.region.region_id;
+        for file in &self.files {
+            let outcome = match self.build_file(file).await {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    error!(e; "Failed to build index for file {}, region: {}", file.file_id(), region_id);
+                    BuildOutcome::Failed
+                }
+            };
+            self.region.index_build.update(|progress| {
+                progress.processed_files += 1;
+                match outcome {
+                    BuildOutcome::Built => progress.built_files += 1,
+                    BuildOutcome::Skipped => {}
+                    BuildOutcome::Failed => progress.failed_files += 1,
+                }
+            });
+        }
+        self.region.index_build.finish();
+
+        info!(
+            "Index build of region {} finished, progress: {:?}",
+            region_id,
+            self.region.index_build.progress()
+        );
+    }
+
+    async fn build_file(&self, file: &FileHandle) -> Result<BuildOutcome> {
+        let file_meta = file.meta();
+        let version = self.region.version();
+        // Compaction rewrites the file with the latest options.
+        if file.compacting() || version.ssts.get_file(&file_meta).is_none() {
+            return Ok(BuildOutcome::Skipped);
+        }
+
+        let mut reader = self
+            .region
+            .access_layer
+            .read_sst(file.clone())
+            .cache(Some(self.cache_manager.clone()))
+            .build()
+            .await?;
+        let parquet_meta = reader.parquet_metadata();
+        if parquet_meta.num_row_groups() == 0 {
+            return Ok(BuildOutcome::Skipped);
+        }
+        // Segments of the index must align with row groups of the file.
+        let row_group_size = parquet_meta.row_group(0).num_rows() as usize;
+
+        let index_file_id = FileId::random();
+        // Keeps the index from orphan file gc until it is committed.
+        let _writing_files = self
+            .region
+            .access_layer
+            .register_writing_files([index_file_id]);
+        let metadata = reader.metadata().clone();
+        let mut indexer = IndexerBuilder {
+            create_inverted_index: true,
+            mem_threshold_index_create: self
+                .engine_config
+                .inverted_index
+                .mem_threshold_on_create
+                .map(|m| m.as_bytes() as _),
+            write_buffer_size: Some(
+                self.engine_config
+                    .inverted_index
+                    .write_buffer_size
+                    .as_bytes() as usize,
+            ),
+            compression: self.engine_config.inverted_index.compression.codec(),
+            file_id: index_file_id,
+            file_path: location::index_file_path(self.region.region_dir(), index_file_id),
+            metadata: &metadata,
+            row_group_size,
+            object_store: self.region.access_layer.object_store().clone(),
+            intermediate_manager: self.region.access_layer.intermediate_manager().clone(),
+            index_options: version.options.index_options.clone(),
+        }
+        .build();
+
+        let index_types = indexer.index_types();
+        // Files without any index to build are up to date even if we rebuild all.
+        let up_to_date = same_index_types(&index_types, &file_meta.available_indexes);
+        if up_to_date && (!self.rebuild_all || index_types.is_empty()) {
+            indexer.abort().await;
+            return Ok(BuildOutcome::Skipped);
+        }
+
+        let mut new_meta = FileMeta {
+            available_indexes: Default::default(),
+            index_file_size: 0,
+            index_file_id: None,
+            ..file_meta.clone()
+        };
+        // The file no longer needs an index if there are no index types.
+        if !index_types.is_empty() {
+            while let Some(batch) = reader.next_batch().await? {
+                indexer.update(&batch).await;
+            }
+            let output = indexer.finish().await;
+            if output.available_indexes.is_empty() {
+                // The indexer logs the error.
+                self.delete_index(index_file_id).await;
+                return Ok(BuildOutcome::Failed);
+            }
+            new_meta.available_indexes = output.available_indexes;
+            new_meta.index_file_size = output.file_size as u64;
+            new_meta.index_file_id = Some(index_file_id);
+        }
+
+        let committed = self.commit(new_meta.clone()).await;
+        if !matches!(committed, Ok(true)) {
+            if new_meta.index_file_available() {
+                self.delete_index(index_file_id).await;
+            }
+            return committed.map(|_| BuildOutcome::Skipped);
+        }
+
+        info!(
+            "Built index for file {}, region: {}, index file: {:?}, indexes: {:?}",
+            file_meta.file_id,
+            file_meta.region_id,
+            new_meta.index_file_id,
+            new_meta.available_indexes
+        );
+
+        Ok(BuildOutcome::Built)
+    }
+
+    /// Commits the new index in the region worker.
+    ///
+    /// Returns false if the file is removed or being compacted.
+    async fn commit(&self, file_meta: FileMeta) -> Result<bool> {
+        let (sender, receiver) = oneshot::channel();
+        let request = WorkerRequest::Background {
+            region_id: self.region.region_id,
+            notify: BackgroundNotify::IndexBuilt(IndexBuilt { file_meta, sender }),
+        };
+        // The receiver returns an error if the worker is stopped.
+        let _ = self.request_sender.send(request).await;
+        receiver.await.context(RecvSnafu)?
+    }
+
+    /// Deletes the index file with `index_file_id` and removes it from the file cache.
+    async fn delete_index(&self, index_file_id: FileId) {
+        let path = location::index_file_path(self.region.region_dir(), index_file_id);
+        if let Err(e) = self.region.access_layer.object_store().delete(&path).await {
+            warn!(e; "Failed to delete index file {}", path);
+        }
+        if let Some(write_cache) = self.cache_manager.write_cache() {
+            write_cache
+                .file_cache()
+                .remove(IndexKey::new(
+                    self.region.region_id,
+                    index_file_id,
+                    FileType::Puffin,
+                ))
+                .await;
+        }
+    }
+}
+
+/// Returns true if both slices contain the same index types.
+fn same_index_types(a: &[IndexType], b: &[IndexType]) -> bool {
+    a.len() == b.len() && a.iter().all(|index_type| b.contains(index_type))
+}
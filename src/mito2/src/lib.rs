@@ -30,6 +30,8 @@ pub mod config;
 pub mod engine;
 pub mod error;
 pub mod flush;
+pub mod gc;
+pub mod index_build;
 pub mod manifest;
 pub mod memtable;
 mod metrics;
@@ -81,7 +83,7 @@ mod worker;
 ///     -Receiver receiver
 ///     -Wal~LogStore~ wal
 ///     -ObjectStore object_store
-///     -MemtableBuilderRef memtable_builder
+///     -MemtableBuilderProvider memtable_builder_provider
 ///     -FlushSchedulerRef~LogStore~ flush_scheduler
 ///     -FlushStrategy flush_strategy
 ///     -CompactionSchedulerRef~LogStore~ compaction_scheduler
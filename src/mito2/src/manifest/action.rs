@@ -17,6 +17,7 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
+use common_time::util::current_time_millis;
 use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt};
 use store_api::manifest::ManifestVersion;
@@ -38,6 +39,10 @@ pub enum RegionMetaAction {
     Remove(RegionRemove),
     /// Truncate the region.
     Truncate(RegionTruncate),
+    /// Create a named snapshot of the region.
+    CreateSnapshot(RegionSnapshot),
+    /// Drop a named snapshot of the region.
+    DropSnapshot(RegionDropSnapshot),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -71,6 +76,52 @@ pub struct RegionTruncate {
     pub truncated_sequence: SequenceNumber,
 }
 
+/// A named snapshot of the region.
+///
+/// A snapshot records the SST files of the region at a manifest version. Files
+/// referenced by snapshots are not purged until all these snapshots are dropped.
+///
+/// The manifest only keeps this entry, the files are stored in a separate
+/// [RegionSnapshotFiles] so checkpoints don't copy them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionSnapshot {
+    /// Name of the snapshot.
+    pub name: String,
+    /// Manifest version the snapshot is taken at.
+    pub manifest_version: ManifestVersion,
+    /// Time in millis when the snapshot is created.
+    pub created_at: i64,
+}
+
+/// SST files of a [RegionSnapshot].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionSnapshotFiles {
+    /// SST files at the manifest version.
+    pub files: Vec<FileMeta>,
+    /// Last sequence of flushed data at the manifest version.
+    pub flushed_sequence: SequenceNumber,
+}
+
+impl RegionSnapshotFiles {
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let json = serde_json::to_string(&self).context(SerdeJsonSnafu)?;
+
+        Ok(json.into_bytes())
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let data = std::str::from_utf8(bytes).context(Utf8Snafu)?;
+
+        serde_json::from_str(data).context(SerdeJsonSnafu)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegionDropSnapshot {
+    /// Name of the snapshot to drop.
+    pub name: String,
+}
+
 /// The region manifest data.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionManifest {
@@ -89,6 +140,28 @@ pub struct RegionManifest {
     /// Inferred compaction time window.
     #[serde(with = "humantime_serde")]
     pub compaction_time_window: Option<Duration>,
+    /// Named snapshots of the region.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub snapshots: HashMap<String, RegionSnapshot>,
+}
+
+impl RegionManifest {
+    /// Returns a [RegionSnapshot] named `name` of this manifest.
+    pub fn to_snapshot(&self, name: String) -> RegionSnapshot {
+        RegionSnapshot {
+            name,
+            manifest_version: self.manifest_version,
+            created_at: current_time_millis(),
+        }
+    }
+
+    /// Returns the [RegionSnapshotFiles] of this manifest.
+    pub fn snapshot_files(&self) -> RegionSnapshotFiles {
+        RegionSnapshotFiles {
+            files: self.files.values().cloned().collect(),
+            flushed_sequence: self.flushed_sequence,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -100,6 +173,7 @@ pub struct RegionManifestBuilder {
     manifest_version: ManifestVersion,
     truncated_entry_id: Option<EntryId>,
     compaction_time_window: Option<Duration>,
+    snapshots: HashMap<String, RegionSnapshot>,
 }
 
 impl RegionManifestBuilder {
@@ -114,6 +188,7 @@ impl RegionManifestBuilder {
                 flushed_sequence: s.flushed_sequence,
                 truncated_entry_id: s.truncated_entry_id,
                 compaction_time_window: s.compaction_time_window,
+                snapshots: s.snapshots,
             }
         } else {
             Default::default()
@@ -152,6 +227,24 @@ impl RegionManifestBuilder {
         self.files.clear();
     }
 
+    pub fn apply_create_snapshot(
+        &mut self,
+        manifest_version: ManifestVersion,
+        snapshot: RegionSnapshot,
+    ) {
+        self.manifest_version = manifest_version;
+        self.snapshots.insert(snapshot.name.clone(), snapshot);
+    }
+
+    pub fn apply_drop_snapshot(
+        &mut self,
+        manifest_version: ManifestVersion,
+        drop_snapshot: RegionDropSnapshot,
+    ) {
+        self.manifest_version = manifest_version;
+        self.snapshots.remove(&drop_snapshot.name);
+    }
+
     /// Check if the builder keeps a [RegionMetadata](store_api::metadata::RegionMetadata).
     pub fn contains_metadata(&self) -> bool {
         self.metadata.is_some()
@@ -167,6 +260,7 @@ impl RegionManifestBuilder {
             manifest_version: self.manifest_version,
             truncated_entry_id: self.truncated_entry_id,
             compaction_time_window: self.compaction_time_window,
+            snapshots: self.snapshots,
         })
     }
 }
@@ -203,17 +297,23 @@ impl RegionCheckpoint {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RegionMetaActionList {
     pub actions: Vec<RegionMetaAction>,
+    /// Time in millis when the action list is created.
+    ///
+    /// Manifests written by older versions don't have this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
 }
 
 impl RegionMetaActionList {
     pub fn with_action(action: RegionMetaAction) -> Self {
-        Self {
-            actions: vec![action],
-        }
+        Self::new(vec![action])
     }
 
     pub fn new(actions: Vec<RegionMetaAction>) -> Self {
-        Self { actions }
+        Self {
+            actions,
+            timestamp: Some(current_time_millis()),
+        }
     }
 }
 
@@ -234,8 +334,10 @@ impl RegionMetaActionList {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
 
     use super::*;
+    use crate::manifest::tests::utils::basic_region_metadata;
 
     #[test]
     fn test_encode_decode_action_list() {
@@ -286,6 +388,66 @@ mod tests {
 
         let region_remove = r#"{"region_id":42}"#;
         let _ = serde_json::from_str::<RegionRemove>(region_remove).unwrap();
+
+        let action_list = r#"{"actions":[{"Remove":{"region_id":42}}]}"#;
+        let action_list = RegionMetaActionList::decode(action_list.as_bytes()).unwrap();
+        assert_eq!(None, action_list.timestamp);
+    }
+
+    #[test]
+    fn test_region_manifest_builder_snapshot() {
+        let metadata = Arc::new(basic_region_metadata());
+        let mut builder = RegionManifestBuilder::default();
+        builder.apply_change(0, RegionChange { metadata });
+        let file = FileMeta {
+            file_id: FileId::random(),
+            ..Default::default()
+        };
+        builder.apply_edit(
+            1,
+            RegionEdit {
+                files_to_add: vec![file.clone()],
+                files_to_remove: vec![],
+                compaction_time_window: None,
+                flushed_entry_id: None,
+                flushed_sequence: Some(10),
+            },
+        );
+        let manifest = builder.try_build().unwrap();
+        let snapshot = manifest.to_snapshot("s1".to_string());
+        assert_eq!(1, snapshot.manifest_version);
+        let files = manifest.snapshot_files();
+        assert_eq!(10, files.flushed_sequence);
+        assert_eq!(vec![file], files.files);
+        assert_eq!(
+            files,
+            RegionSnapshotFiles::decode(&files.encode().unwrap()).unwrap()
+        );
+
+        let mut builder = RegionManifestBuilder::with_checkpoint(Some(manifest));
+        builder.apply_create_snapshot(2, snapshot.clone());
+        let manifest = builder.try_build().unwrap();
+        assert_eq!(2, manifest.manifest_version);
+        assert_eq!(Some(&snapshot), manifest.snapshots.get("s1"));
+
+        // Snapshots are persisted in checkpoints.
+        let checkpoint = RegionCheckpoint {
+            last_version: 2,
+            compacted_actions: 3,
+            checkpoint: Some(manifest.clone()),
+        };
+        let decoded = RegionCheckpoint::decode(&checkpoint.encode().unwrap()).unwrap();
+        assert_eq!(checkpoint, decoded);
+
+        let mut builder = RegionManifestBuilder::with_checkpoint(Some(manifest));
+        builder.apply_drop_snapshot(
+            3,
+            RegionDropSnapshot {
+                name: "s1".to_string(),
+            },
+        );
+        let manifest = builder.try_build().unwrap();
+        assert!(manifest.snapshots.is_empty());
     }
 
     #[test]
@@ -18,7 +18,7 @@ use common_datasource::compression::CompressionType;
 use common_telemetry::{debug, info};
 use futures::TryStreamExt;
 use object_store::ObjectStore;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::{ManifestVersion, MAX_VERSION, MIN_VERSION};
 use store_api::metadata::RegionMetadataRef;
 use tokio::sync::RwLock;
@@ -26,7 +26,7 @@ use tokio::sync::RwLock;
 use crate::error::{self, Result};
 use crate::manifest::action::{
     RegionChange, RegionCheckpoint, RegionManifest, RegionManifestBuilder, RegionMetaAction,
-    RegionMetaActionList,
+    RegionMetaActionList, RegionSnapshotFiles,
 };
 use crate::manifest::storage::{file_version, is_delta_file, ManifestObjectStore};
 
@@ -157,6 +157,46 @@ impl RegionManifestManager {
         inner.store.clone()
     }
 
+    /// Retrieves the [RegionManifest] at the specific `version`.
+    ///
+    /// Only versions that are not compacted by checkpoints are available.
+    pub async fn manifest_at(&self, version: ManifestVersion) -> Result<RegionManifest> {
+        let inner = self.inner.read().await;
+        inner.manifest_at(version).await
+    }
+
+    /// Returns the latest manifest version committed at or before `timestamp` in millis.
+    pub async fn version_at(&self, timestamp: i64) -> Result<ManifestVersion> {
+        let inner = self.inner.read().await;
+        inner.version_at(timestamp).await
+    }
+
+    /// Saves files of the snapshot named `name`.
+    ///
+    /// Files are stored outside the manifest so checkpoints don't copy them.
+    pub async fn save_snapshot_files(&self, name: &str, files: &RegionSnapshotFiles) -> Result<()> {
+        let bytes = files.encode()?;
+        let inner = self.inner.read().await;
+        inner.store.save_snapshot(name, &bytes).await
+    }
+
+    /// Loads files of the snapshot named `name`.
+    pub async fn load_snapshot_files(&self, name: &str) -> Result<Option<RegionSnapshotFiles>> {
+        let inner = self.inner.read().await;
+        inner
+            .store
+            .load_snapshot(name)
+            .await?
+            .map(|bytes| RegionSnapshotFiles::decode(&bytes))
+            .transpose()
+    }
+
+    /// Deletes files of the snapshot named `name`.
+    pub async fn delete_snapshot_files(&self, name: &str) -> Result<()> {
+        let inner = self.inner.read().await;
+        inner.store.delete_snapshot(name).await
+    }
+
     /// Returns total manifest size.
     pub async fn manifest_usage(&self) -> u64 {
         let inner = self.inner.read().await;
@@ -301,6 +341,12 @@ impl RegionManifestManagerInner {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(manifest_version, action);
                     }
+                    RegionMetaAction::CreateSnapshot(action) => {
+                        manifest_builder.apply_create_snapshot(manifest_version, action);
+                    }
+                    RegionMetaAction::DropSnapshot(action) => {
+                        manifest_builder.apply_drop_snapshot(manifest_version, action);
+                    }
                 }
             }
         }
@@ -355,6 +401,12 @@ impl RegionManifestManagerInner {
                 RegionMetaAction::Truncate(action) => {
                     manifest_builder.apply_truncate(version, action);
                 }
+                RegionMetaAction::CreateSnapshot(action) => {
+                    manifest_builder.apply_create_snapshot(version, action);
+                }
+                RegionMetaAction::DropSnapshot(action) => {
+                    manifest_builder.apply_drop_snapshot(version, action);
+                }
             }
         }
         let new_manifest = manifest_builder.try_build()?;
@@ -368,6 +420,120 @@ impl RegionManifestManagerInner {
     pub(crate) fn total_manifest_size(&self) -> u64 {
         self.store.total_manifest_size()
     }
+
+    /// Rebuilds the manifest at the specific `version` from the last checkpoint and delta files.
+    async fn manifest_at(&self, version: ManifestVersion) -> Result<RegionManifest> {
+        if version == self.last_version {
+            return Ok(self.manifest.as_ref().clone());
+        }
+
+        let region_id = self.manifest.metadata.region_id;
+        ensure!(
+            version < self.last_version,
+            error::ManifestHistoryUnavailableSnafu {
+                region_id,
+                reason: format!(
+                    "version {} is greater than the latest version {}",
+                    version, self.last_version
+                ),
+            }
+        );
+
+        // Loading checkpoint updates the file sizes of the store so we use a copy.
+        let mut store = self.store.clone();
+        let (start_version, mut manifest_builder) = match Self::last_checkpoint(&mut store).await? {
+            Some(checkpoint) => {
+                ensure!(
+                    checkpoint.last_version <= version,
+                    error::ManifestHistoryUnavailableSnafu {
+                        region_id,
+                        reason: format!(
+                            "version {} is compacted into the checkpoint of version {}",
+                            version, checkpoint.last_version
+                        ),
+                    }
+                );
+                (
+                    checkpoint.last_version + 1,
+                    RegionManifestBuilder::with_checkpoint(checkpoint.checkpoint),
+                )
+            }
+            None => (MIN_VERSION, RegionManifestBuilder::default()),
+        };
+
+        let manifests = store.scan(start_version, version + 1).await?;
+        let manifests = store.fetch_manifests(&manifests).await?;
+        for (manifest_version, raw_action_list) in manifests {
+            let action_list = RegionMetaActionList::decode(&raw_action_list)?;
+            for action in action_list.actions {
+                match action {
+                    RegionMetaAction::Change(action) => {
+                        manifest_builder.apply_change(manifest_version, action);
+                    }
+                    RegionMetaAction::Edit(action) => {
+                        manifest_builder.apply_edit(manifest_version, action);
+                    }
+                    RegionMetaAction::Remove(_) => {
+                        debug!(
+                            "Unhandled action for region {}, action: {:?}",
+                            region_id, action
+                        );
+                    }
+                    RegionMetaAction::Truncate(action) => {
+                        manifest_builder.apply_truncate(manifest_version, action);
+                    }
+                    RegionMetaAction::CreateSnapshot(action) => {
+                        manifest_builder.apply_create_snapshot(manifest_version, action);
+                    }
+                    RegionMetaAction::DropSnapshot(action) => {
+                        manifest_builder.apply_drop_snapshot(manifest_version, action);
+                    }
+                }
+            }
+        }
+
+        let manifest = manifest_builder.try_build()?;
+        ensure!(
+            manifest.manifest_version == version,
+            error::ManifestHistoryUnavailableSnafu {
+                region_id,
+                reason: format!("manifest of version {} not found", version),
+            }
+        );
+
+        Ok(manifest)
+    }
+
+    /// Finds the latest manifest version committed at or before `timestamp` in millis.
+    async fn version_at(&self, timestamp: i64) -> Result<ManifestVersion> {
+        let region_id = self.manifest.metadata.region_id;
+        let mut store = self.store.clone();
+        let start_version = Self::last_checkpoint(&mut store)
+            .await?
+            .map(|checkpoint| checkpoint.last_version)
+            .unwrap_or(MIN_VERSION);
+
+        let manifests = store.scan(start_version, MAX_VERSION).await?;
+        let manifests = store.fetch_manifests(&manifests).await?;
+        let mut found = None;
+        for (manifest_version, raw_action_list) in manifests {
+            let action_list = RegionMetaActionList::decode(&raw_action_list)?;
+            match action_list.timestamp {
+                Some(committed_at) if committed_at <= timestamp => found = Some(manifest_version),
+                Some(_) => break,
+                // Manifests written by older versions don't have a timestamp.
+                None => continue,
+            }
+        }
+
+        found.context(error::ManifestHistoryUnavailableSnafu {
+            region_id,
+            reason: format!(
+                "no manifest version committed before timestamp {}",
+                timestamp
+            ),
+        })
+    }
 }
 
 impl RegionManifestManagerInner {
@@ -437,6 +603,12 @@ impl RegionManifestManagerInner {
                     RegionMetaAction::Truncate(action) => {
                         manifest_builder.apply_truncate(version, action);
                     }
+                    RegionMetaAction::CreateSnapshot(action) => {
+                        manifest_builder.apply_create_snapshot(version, action);
+                    }
+                    RegionMetaAction::DropSnapshot(action) => {
+                        manifest_builder.apply_drop_snapshot(version, action);
+                    }
                 }
             }
             last_version = version;
@@ -526,6 +698,7 @@ mod test {
     use super::*;
     use crate::manifest::action::{RegionChange, RegionEdit};
     use crate::manifest::tests::utils::basic_region_metadata;
+    use crate::sst::file::{FileId, FileMeta};
     use crate::test_util::TestEnv;
 
     #[tokio::test]
@@ -608,6 +781,71 @@ mod test {
         manager.validate_manifest(&new_metadata, 1).await;
     }
 
+    #[tokio::test]
+    async fn test_manifest_at_version() {
+        let metadata = Arc::new(basic_region_metadata());
+        let env = TestEnv::new();
+        let manager = env
+            .create_manifest_manager(CompressionType::Uncompressed, 3, Some(metadata.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut files = Vec::new();
+        for _ in 0..2 {
+            let file = FileMeta {
+                region_id: metadata.region_id,
+                file_id: FileId::random(),
+                ..Default::default()
+            };
+            files.push(file.clone());
+            manager
+                .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                    RegionEdit {
+                        files_to_add: vec![file],
+                        files_to_remove: vec![],
+                        compaction_time_window: None,
+                        flushed_entry_id: None,
+                        flushed_sequence: None,
+                    },
+                )))
+                .await
+                .unwrap();
+        }
+
+        let manifest = manager.manifest_at(0).await.unwrap();
+        assert_eq!(0, manifest.manifest_version);
+        assert!(manifest.files.is_empty());
+        let manifest = manager.manifest_at(1).await.unwrap();
+        assert_eq!(1, manifest.manifest_version);
+        assert_eq!(1, manifest.files.len());
+        assert!(manifest.files.contains_key(&files[0].file_id));
+        let manifest = manager.manifest_at(2).await.unwrap();
+        assert_eq!(*manager.manifest().await, manifest);
+        assert!(manager.manifest_at(3).await.is_err());
+
+        assert_eq!(2, manager.version_at(i64::MAX).await.unwrap());
+        assert!(manager.version_at(0).await.is_err());
+
+        // Triggers a checkpoint and older versions become unavailable.
+        manager
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                RegionEdit {
+                    files_to_add: vec![],
+                    files_to_remove: files.clone(),
+                    compaction_time_window: None,
+                    flushed_entry_id: None,
+                    flushed_sequence: None,
+                },
+            )))
+            .await
+            .unwrap();
+        assert!(manager.manifest_at(1).await.is_err());
+        let manifest = manager.manifest_at(3).await.unwrap();
+        assert!(manifest.files.is_empty());
+        assert_eq!(3, manager.version_at(i64::MAX).await.unwrap());
+    }
+
     /// Just for test, refer to wal_dir_usage in src/store-api/src/logstore.rs.
     async fn manifest_dir_usage(path: &str) -> u64 {
         let mut size = 0;
@@ -696,6 +934,6 @@ mod test {
 
         // get manifest size again
         let manifest_size = manager.manifest_usage().await;
-        assert_eq!(manifest_size, 1312);
+        assert_eq!(manifest_size, 1364);
     }
 }
@@ -39,6 +39,8 @@ lazy_static! {
 }
 
 const LAST_CHECKPOINT_FILE: &str = "_last_checkpoint";
+/// Dir under the manifest dir to store files of snapshots.
+const SNAPSHOT_DIR: &str = "snapshot/";
 const DEFAULT_MANIFEST_COMPRESSION_TYPE: CompressionType = CompressionType::Gzip;
 /// Due to backward compatibility, it is possible that the user's manifest file has not been compressed.
 /// So when we encounter problems, we need to fall back to `FALL_BACK_COMPRESS_TYPE` for processing.
@@ -65,6 +67,11 @@ pub fn checkpoint_file(version: ManifestVersion) -> String {
     format!("{version:020}.checkpoint")
 }
 
+#[inline]
+pub fn snapshot_file(name: &str) -> String {
+    format!("{SNAPSHOT_DIR}{name}.files")
+}
+
 #[inline]
 pub fn gen_path(path: &str, file: &str, compress_type: CompressionType) -> String {
     if compress_type == CompressionType::Uncompressed {
@@ -482,6 +489,72 @@ impl ManifestObjectStore {
         self.load_checkpoint(checkpoint_metadata.version).await
     }
 
+    /// Save files of the snapshot named `name`.
+    pub async fn save_snapshot(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let path = gen_path(&self.path, &snapshot_file(name), self.compress_type);
+        let data = self
+            .compress_type
+            .encode(bytes)
+            .await
+            .context(CompressObjectSnafu {
+                compress_type: self.compress_type,
+                path: &path,
+            })?;
+        debug!("Save snapshot {} in path: {}", name, path);
+        self.object_store
+            .write(&path, data)
+            .await
+            .context(OpenDalSnafu)
+    }
+
+    /// Load files of the snapshot named `name`.
+    ///
+    /// The compression type may be changed after the snapshot is created, so it
+    /// also tries the file not compressed.
+    pub async fn load_snapshot(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let mut compress_types = vec![self.compress_type];
+        if self.compress_type != FALL_BACK_COMPRESS_TYPE {
+            compress_types.push(FALL_BACK_COMPRESS_TYPE);
+        }
+
+        for compress_type in compress_types {
+            let path = gen_path(&self.path, &snapshot_file(name), compress_type);
+            match self.object_store.read(&path).await {
+                Ok(data) => {
+                    let data = compress_type
+                        .decode(data)
+                        .await
+                        .context(DecompressObjectSnafu {
+                            compress_type,
+                            path,
+                        })?;
+                    return Ok(Some(data));
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e).context(OpenDalSnafu),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Delete files of the snapshot named `name`.
+    pub async fn delete_snapshot(&self, name: &str) -> Result<()> {
+        let mut paths = vec![gen_path(
+            &self.path,
+            &snapshot_file(name),
+            self.compress_type,
+        )];
+        if self.compress_type != FALL_BACK_COMPRESS_TYPE {
+            paths.push(gen_path(
+                &self.path,
+                &snapshot_file(name),
+                FALL_BACK_COMPRESS_TYPE,
+            ));
+        }
+        self.object_store.remove(paths).await.context(OpenDalSnafu)
+    }
+
     #[cfg(test)]
     pub async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
         self.object_store.read(path).await.context(OpenDalSnafu)
@@ -694,6 +767,39 @@ mod tests {
         assert!(it.next().is_none());
     }
 
+    #[tokio::test]
+    async fn test_save_load_snapshot() {
+        let mut log_store = new_test_manifest_store();
+        assert!(log_store.load_snapshot("s1").await.unwrap().is_none());
+
+        log_store
+            .save_snapshot("s1", b"files_uncompressed")
+            .await
+            .unwrap();
+        log_store.compress_type = CompressionType::Gzip;
+        log_store
+            .save_snapshot("s2", b"files_compressed")
+            .await
+            .unwrap();
+        log_store.save(0, b"hello, 0").await.unwrap();
+
+        assert_eq!(
+            b"files_uncompressed".to_vec(),
+            log_store.load_snapshot("s1").await.unwrap().unwrap()
+        );
+        assert_eq!(
+            b"files_compressed".to_vec(),
+            log_store.load_snapshot("s2").await.unwrap().unwrap()
+        );
+        // Snapshot files are not manifest files.
+        let manifests = log_store.scan(0, 10).await.unwrap();
+        assert_eq!(1, manifests.len());
+
+        log_store.delete_snapshot("s1").await.unwrap();
+        assert!(log_store.load_snapshot("s1").await.unwrap().is_none());
+        assert!(log_store.load_snapshot("s2").await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_file_version() {
         let version = file_version("00000000000000000007.checkpoint");
@@ -28,11 +28,15 @@ use store_api::metadata::RegionMetadataRef;
 use store_api::storage::ColumnId;
 use table::predicate::Predicate;
 
+use crate::config::MitoConfig;
 use crate::error::Result;
 use crate::flush::WriteBufferManagerRef;
 pub use crate::memtable::key_values::KeyValues;
+use crate::memtable::merge_tree::MergeTreeMemtableBuilder;
+use crate::memtable::time_series::TimeSeriesMemtableBuilder;
 use crate::metrics::WRITE_BUFFER_BYTES;
 use crate::read::Batch;
+use crate::region::options::RegionOptions;
 
 /// Id for memtables.
 ///
@@ -103,6 +107,47 @@ pub trait MemtableBuilder: Send + Sync + fmt::Debug {
 
 pub type MemtableBuilderRef = Arc<dyn MemtableBuilder>;
 
+/// Provides [MemtableBuilder]s for regions according to their options.
+#[derive(Clone)]
+pub(crate) struct MemtableBuilderProvider {
+    write_buffer_manager: Option<WriteBufferManagerRef>,
+    config: Arc<MitoConfig>,
+}
+
+impl MemtableBuilderProvider {
+    /// Returns a new provider.
+    pub(crate) fn new(
+        write_buffer_manager: Option<WriteBufferManagerRef>,
+        config: Arc<MitoConfig>,
+    ) -> Self {
+        Self {
+            write_buffer_manager,
+            config,
+        }
+    }
+
+    /// Returns a memtable builder for a region with specific `options`.
+    ///
+    /// Memtables built by the builder don't remove duplicate rows if the region
+    /// is in append mode.
+    pub(crate) fn builder_for_options(&self, options: &RegionOptions) -> MemtableBuilderRef {
+        let dedup = !options.append_mode;
+        if let Some(config) = &self.config.experimental_memtable {
+            let mut config = config.clone();
+            config.dedup &= dedup;
+            Arc::new(MergeTreeMemtableBuilder::new(
+                config,
+                self.write_buffer_manager.clone(),
+            ))
+        } else {
+            Arc::new(TimeSeriesMemtableBuilder::new(
+                self.write_buffer_manager.clone(),
+                dedup,
+            ))
+        }
+    }
+}
+
 /// Memtable memory allocation tracker.
 #[derive(Default)]
 pub struct AllocTracker {
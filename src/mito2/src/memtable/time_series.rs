@@ -50,16 +50,19 @@ use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
 const INITIAL_BUILDER_CAPACITY: usize = 0;
 
 /// Builder to build [TimeSeriesMemtable].
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TimeSeriesMemtableBuilder {
     write_buffer_manager: Option<WriteBufferManagerRef>,
+    /// Whether memtables remove duplicate rows.
+    dedup: bool,
 }
 
 impl TimeSeriesMemtableBuilder {
     /// Creates a new builder with specific `write_buffer_manager`.
-    pub fn new(write_buffer_manager: Option<WriteBufferManagerRef>) -> Self {
+    pub fn new(write_buffer_manager: Option<WriteBufferManagerRef>, dedup: bool) -> Self {
         Self {
             write_buffer_manager,
+            dedup,
         }
     }
 }
@@ -70,6 +73,7 @@ impl MemtableBuilder for TimeSeriesMemtableBuilder {
             metadata.clone(),
             id,
             self.write_buffer_manager.clone(),
+            self.dedup,
         ))
     }
 }
@@ -83,6 +87,8 @@ pub struct TimeSeriesMemtable {
     alloc_tracker: AllocTracker,
     max_timestamp: AtomicI64,
     min_timestamp: AtomicI64,
+    /// Whether to remove duplicate rows while reading.
+    dedup: bool,
 }
 
 impl TimeSeriesMemtable {
@@ -90,6 +96,7 @@ impl TimeSeriesMemtable {
         region_metadata: RegionMetadataRef,
         id: MemtableId,
         write_buffer_manager: Option<WriteBufferManagerRef>,
+        dedup: bool,
     ) -> Self {
         let row_codec = Arc::new(McmpRowCodec::new(
             region_metadata
@@ -106,6 +113,7 @@ impl TimeSeriesMemtable {
             alloc_tracker: AllocTracker::new(write_buffer_manager),
             max_timestamp: AtomicI64::new(i64::MIN),
             min_timestamp: AtomicI64::new(i64::MAX),
+            dedup,
         }
     }
 
@@ -218,7 +226,7 @@ impl Memtable for TimeSeriesMemtable {
                 .collect()
         };
 
-        let iter = self.series_set.iter_series(projection, filters);
+        let iter = self.series_set.iter_series(projection, filters, self.dedup);
         Ok(Box::new(iter))
     }
 
@@ -263,6 +271,7 @@ impl Memtable for TimeSeriesMemtable {
             metadata.clone(),
             id,
             self.alloc_tracker.write_buffer_manager(),
+            self.dedup,
         ))
     }
 }
@@ -306,7 +315,12 @@ impl SeriesSet {
     }
 
     /// Iterates all series in [SeriesSet].
-    fn iter_series(&self, projection: HashSet<ColumnId>, predicate: Option<Predicate>) -> Iter {
+    fn iter_series(
+        &self,
+        projection: HashSet<ColumnId>,
+        predicate: Option<Predicate>,
+        dedup: bool,
+    ) -> Iter {
         let primary_key_schema = primary_key_schema(&self.region_metadata);
         let primary_key_datatypes = self
             .region_metadata
@@ -322,6 +336,7 @@ impl SeriesSet {
             primary_key_schema,
             primary_key_datatypes,
             self.codec.clone(),
+            dedup,
         )
     }
 }
@@ -368,10 +383,12 @@ struct Iter {
     pk_schema: arrow::datatypes::SchemaRef,
     pk_datatypes: Vec<ConcreteDataType>,
     codec: Arc<McmpRowCodec>,
+    dedup: bool,
     metrics: Metrics,
 }
 
 impl Iter {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         metadata: RegionMetadataRef,
         series: Arc<SeriesRwLockMap>,
@@ -380,6 +397,7 @@ impl Iter {
         pk_schema: arrow::datatypes::SchemaRef,
         pk_datatypes: Vec<ConcreteDataType>,
         codec: Arc<McmpRowCodec>,
+        dedup: bool,
     ) -> Self {
         let simple_filters = predicate
             .map(|p| {
@@ -398,6 +416,7 @@ impl Iter {
             pk_schema,
             pk_datatypes,
             codec,
+            dedup,
             metrics: Metrics::default(),
         }
     }
@@ -454,8 +473,9 @@ impl Iterator for Iter {
             self.last_key = Some(primary_key.clone());
 
             let values = series.compact(&self.metadata);
-            let batch =
-                values.and_then(|v| v.to_batch(primary_key, &self.metadata, &self.projection));
+            let batch = values.and_then(|v| {
+                v.to_batch(primary_key, &self.metadata, &self.projection, self.dedup)
+            });
 
             // Update metrics.
             self.metrics.num_batches += 1;
@@ -667,12 +687,13 @@ struct Values {
 
 impl Values {
     /// Converts [Values] to `Batch`, sorts the batch according to `timestamp, sequence` desc and
-    /// keeps only the latest row for the same timestamp.
+    /// keeps only the latest row for the same timestamp if `dedup` is true.
     pub fn to_batch(
         &self,
         primary_key: &[u8],
         metadata: &RegionMetadataRef,
         projection: &HashSet<ColumnId>,
+        dedup: bool,
     ) -> Result<Batch> {
         let builder = BatchBuilder::with_required_columns(
             primary_key.to_vec(),
@@ -693,7 +714,7 @@ impl Values {
             .collect();
 
         let mut batch = builder.with_fields(fields).build()?;
-        batch.sort_and_dedup()?;
+        batch.sort(dedup)?;
         Ok(batch)
     }
 
@@ -953,7 +974,12 @@ mod tests {
         };
 
         let batch = values
-            .to_batch(b"test", &schema, &[0, 1, 2, 3, 4].into_iter().collect())
+            .to_batch(
+                b"test",
+                &schema,
+                &[0, 1, 2, 3, 4].into_iter().collect(),
+                true,
+            )
             .unwrap();
         check_value(
             &batch,
@@ -990,6 +1016,69 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_values_sort_without_dedup() {
+        let schema = schema_for_test();
+        let timestamp = Arc::new(TimestampMillisecondVector::from_vec(vec![1, 3, 2, 3]));
+        let sequence = Arc::new(UInt64Vector::from_vec(vec![1, 1, 1, 2]));
+        let op_type = Arc::new(UInt8Vector::from_vec(vec![1, 1, 1, 1]));
+
+        let fields = vec![
+            Arc::new(Int64Vector::from_vec(vec![4, 3, 2, 1])) as Arc<_>,
+            Arc::new(Float64Vector::from_vec(vec![1.1, 2.1, 4.2, 3.3])) as Arc<_>,
+        ];
+        let values = Values {
+            timestamp: timestamp as Arc<_>,
+            sequence,
+            op_type,
+            fields,
+        };
+
+        let batch = values
+            .to_batch(
+                b"test",
+                &schema,
+                &[0, 1, 2, 3, 4].into_iter().collect(),
+                false,
+            )
+            .unwrap();
+        // Duplicate rows are kept and ordered by sequence desc.
+        let rows = (0..batch.num_rows())
+            .map(|idx| {
+                (
+                    batch.timestamps().get(idx),
+                    batch.sequences().get(idx),
+                    batch.fields()[0].data.get(idx),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    Value::Timestamp(Timestamp::new_millisecond(1)),
+                    Value::UInt64(1),
+                    Value::Int64(4)
+                ),
+                (
+                    Value::Timestamp(Timestamp::new_millisecond(2)),
+                    Value::UInt64(1),
+                    Value::Int64(2)
+                ),
+                (
+                    Value::Timestamp(Timestamp::new_millisecond(3)),
+                    Value::UInt64(2),
+                    Value::Int64(1)
+                ),
+                (
+                    Value::Timestamp(Timestamp::new_millisecond(3)),
+                    Value::UInt64(1),
+                    Value::Int64(3)
+                ),
+            ],
+            rows
+        );
+    }
+
     fn build_key_values(schema: &RegionMetadataRef, k0: String, k1: i64, len: usize) -> KeyValues {
         let column_schema = schema
             .column_metadatas
@@ -1119,7 +1208,7 @@ mod tests {
         common_telemetry::init_default_ut_logging();
         let schema = schema_for_test();
         let kvs = build_key_values(&schema, "hello".to_string(), 42, 100);
-        let memtable = TimeSeriesMemtable::new(schema, 42, None);
+        let memtable = TimeSeriesMemtable::new(schema, 42, None, true);
         memtable.write(&kvs).unwrap();
 
         let expected_ts = kvs
@@ -1160,7 +1249,7 @@ mod tests {
         common_telemetry::init_default_ut_logging();
         let schema = schema_for_test();
         let kvs = build_key_values(&schema, "hello".to_string(), 42, 100);
-        let memtable = TimeSeriesMemtable::new(schema, 42, None);
+        let memtable = TimeSeriesMemtable::new(schema, 42, None, true);
         memtable.write(&kvs).unwrap();
 
         let iter = memtable.iter(Some(&[3]), None).unwrap();
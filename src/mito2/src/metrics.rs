@@ -185,6 +185,12 @@ lazy_static! {
         "mito upload bytes total",
     )
     .unwrap();
+    /// Download bytes counter.
+    pub static ref DOWNLOAD_BYTES_TOTAL: IntCounter = register_int_counter!(
+        "mito_download_bytes_total",
+        "mito download bytes total",
+    )
+    .unwrap();
     // ------- End of cache metrics.
 
     // Index metrics.
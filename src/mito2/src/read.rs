@@ -19,6 +19,8 @@ pub mod merge;
 pub mod projection;
 pub(crate) mod scan_region;
 pub(crate) mod seq_scan;
+pub(crate) mod unordered_scan;
+pub(crate) mod windowed_scan;
 
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -340,6 +342,13 @@ impl Batch {
     /// row for the same timestamp. It doesn't consider op type as sequence
     /// should already provide uniqueness for a row.
     pub fn sort_and_dedup(&mut self) -> Result<()> {
+        self.sort(true)
+    }
+
+    /// Sorts rows in the batch by timestamp, sequence desc.
+    ///
+    /// If `dedup` is true, it only keeps the latest row for the same timestamp.
+    pub fn sort(&mut self, dedup: bool) -> Result<()> {
         // If building a converter each time is costly, we may allow passing a
         // converter.
         let converter = RowConverter::new(vec![
@@ -362,14 +371,16 @@ impl Batch {
         let mut to_sort: Vec<_> = rows.iter().enumerate().collect();
         to_sort.sort_unstable_by(|left, right| left.1.cmp(&right.1));
 
-        // Dedup by timestamps.
-        to_sort.dedup_by(|left, right| {
-            debug_assert_eq!(18, left.1.as_ref().len());
-            debug_assert_eq!(18, right.1.as_ref().len());
-            let (left_key, right_key) = (left.1.as_ref(), right.1.as_ref());
-            // We only compare the timestamp part and ignore sequence.
-            left_key[..TIMESTAMP_KEY_LEN] == right_key[..TIMESTAMP_KEY_LEN]
-        });
+        if dedup {
+            // Dedup by timestamps.
+            to_sort.dedup_by(|left, right| {
+                debug_assert_eq!(18, left.1.as_ref().len());
+                debug_assert_eq!(18, right.1.as_ref().len());
+                let (left_key, right_key) = (left.1.as_ref(), right.1.as_ref());
+                // We only compare the timestamp part and ignore sequence.
+                left_key[..TIMESTAMP_KEY_LEN] == right_key[..TIMESTAMP_KEY_LEN]
+            });
+        }
 
         let indices = UInt32Vector::from_iter_values(to_sort.iter().map(|v| v.0 as u32));
         self.take_in_place(&indices)
@@ -33,8 +33,12 @@ use crate::read::{Batch, BatchReader, BoxedBatchReader, Source};
 /// The merge reader merges [Batch]es from multiple sources that yield sorted batches.
 /// 1. Batch is ordered by primary key, time index, sequence desc, op type desc (we can
 /// ignore op type as sequence is already unique).
-/// 2. Batch doesn't have duplicate elements (elements with the same primary key and time index).
+/// 2. Batch doesn't have duplicate elements (elements with the same primary key and time index)
+/// if the reader removes duplicate rows.
 /// 3. Batches from sources **must** not be empty.
+///
+/// The reader keeps all rows with the same primary key and time index if `dedup` is false.
+/// It keeps deleted rows if `filter_deleted` is false.
 pub struct MergeReader {
     /// Holds [Node]s whose key range of current batch **is** overlapped with the merge window.
     /// Each node yields batches from a `source`.
@@ -48,6 +52,10 @@ pub struct MergeReader {
     cold: BinaryHeap<Node>,
     /// Batch to output.
     output_batch: Option<Batch>,
+    /// Whether to remove duplicate rows.
+    dedup: bool,
+    /// Whether to remove deleted rows.
+    filter_deleted: bool,
     /// Local metrics.
     metrics: Metrics,
 }
@@ -98,7 +106,11 @@ impl Drop for MergeReader {
 
 impl MergeReader {
     /// Creates and initializes a new [MergeReader].
-    pub async fn new(sources: Vec<Source>) -> Result<MergeReader> {
+    pub async fn new(
+        sources: Vec<Source>,
+        dedup: bool,
+        filter_deleted: bool,
+    ) -> Result<MergeReader> {
         let start = Instant::now();
         let mut metrics = Metrics::default();
 
@@ -116,6 +128,8 @@ impl MergeReader {
             hot,
             cold,
             output_batch: None,
+            dedup,
+            filter_deleted,
             metrics,
         };
         // Initializes the reader.
@@ -150,7 +164,12 @@ impl MergeReader {
 
         let mut hottest = self.hot.pop().unwrap();
         let batch = hottest.fetch_batch(&mut self.metrics).await?;
-        Self::maybe_output_batch(batch, &mut self.output_batch, &mut self.metrics)?;
+        Self::maybe_output_batch(
+            batch,
+            self.filter_deleted,
+            &mut self.output_batch,
+            &mut self.metrics,
+        )?;
         self.reheap(hottest)
     }
 
@@ -173,6 +192,20 @@ impl MergeReader {
 
         // Safety: Batches in the heap is not empty, so we can use unwrap here.
         let timestamps = top.timestamps_native().unwrap();
+        if !self.dedup {
+            // Outputs all timestamps not greater than `next_min_ts`. The top node is the
+            // hottest one so it outputs at least one row.
+            let pos = timestamps.partition_point(|ts| *ts <= next_min_ts.value());
+            Self::maybe_output_batch(
+                top.slice(0, pos),
+                self.filter_deleted,
+                &mut self.output_batch,
+                &mut self.metrics,
+            )?;
+            top_node.skip_rows(pos, &mut self.metrics).await?;
+            return self.reheap(top_node);
+        }
+
         // Binary searches the timestamp in the top batch.
         // Safety: Batches should have the same timestamp resolution so we can compare the native
         // value directly.
@@ -183,6 +216,7 @@ impl MergeReader {
                 // must be less than `next_min_ts`.
                 Self::maybe_output_batch(
                     top.slice(0, pos),
+                    self.filter_deleted,
                     &mut self.output_batch,
                     &mut self.metrics,
                 )?;
@@ -197,6 +231,7 @@ impl MergeReader {
                 // No duplicate timestamp. Outputs timestamp before `pos`.
                 Self::maybe_output_batch(
                     top.slice(0, pos),
+                    self.filter_deleted,
                     &mut self.output_batch,
                     &mut self.metrics,
                 )?;
@@ -291,20 +326,23 @@ impl MergeReader {
         Ok(())
     }
 
-    /// Removeds deleted entries and sets the `batch` to the `output_batch`.
+    /// Removeds deleted entries if `filter_deleted` is true and sets the `batch` to the `output_batch`.
     ///
     /// Ignores the `batch` if it is empty.
     fn maybe_output_batch(
         mut batch: Batch,
+        filter_deleted: bool,
         output_batch: &mut Option<Batch>,
         metrics: &mut Metrics,
     ) -> Result<()> {
         debug_assert!(output_batch.is_none());
 
-        let num_rows = batch.num_rows();
-        batch.filter_deleted()?;
-        // Update deleted rows metrics.
-        metrics.num_deleted_rows += num_rows - batch.num_rows();
+        if filter_deleted {
+            let num_rows = batch.num_rows();
+            batch.filter_deleted()?;
+            // Update deleted rows metrics.
+            metrics.num_deleted_rows += num_rows - batch.num_rows();
+        }
         if batch.is_empty() {
             return Ok(());
         }
@@ -315,12 +353,25 @@ impl MergeReader {
 }
 
 /// Builder to build and initialize a [MergeReader].
-#[derive(Default)]
 pub struct MergeReaderBuilder {
     /// Input sources.
     ///
     /// All source must yield batches with the same schema.
     sources: Vec<Source>,
+    /// Whether to remove duplicate rows.
+    dedup: bool,
+    /// Whether to remove deleted rows.
+    filter_deleted: bool,
+}
+
+impl Default for MergeReaderBuilder {
+    fn default() -> Self {
+        MergeReaderBuilder {
+            sources: Vec::new(),
+            dedup: true,
+            filter_deleted: true,
+        }
+    }
 }
 
 impl MergeReaderBuilder {
@@ -331,7 +382,23 @@ impl MergeReaderBuilder {
 
     /// Creates a builder from sources.
     pub fn from_sources(sources: Vec<Source>) -> MergeReaderBuilder {
-        MergeReaderBuilder { sources }
+        MergeReaderBuilder {
+            sources,
+            dedup: true,
+            filter_deleted: true,
+        }
+    }
+
+    /// Sets whether to remove duplicate rows.
+    pub fn dedup(&mut self, dedup: bool) -> &mut Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Sets whether to remove deleted rows.
+    pub fn filter_deleted(&mut self, filter_deleted: bool) -> &mut Self {
+        self.filter_deleted = filter_deleted;
+        self
     }
 
     /// Pushes a batch reader to sources.
@@ -349,7 +416,7 @@ impl MergeReaderBuilder {
     /// Builds and initializes the reader, then resets the builder.
     pub async fn build(&mut self) -> Result<MergeReader> {
         let sources = mem::take(&mut self.sources);
-        MergeReader::new(sources).await
+        MergeReader::new(sources, self.dedup, self.filter_deleted).await
     }
 }
 
@@ -901,4 +968,46 @@ mod tests {
             .collect();
         check_reader_result(&mut reader, &expect).await;
     }
+
+    #[tokio::test]
+    async fn test_merge_keep_duplicates() {
+        let reader1 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 2],
+            &[10, 10],
+            &[OpType::Put, OpType::Put],
+            &[21, 22],
+        )]);
+        let reader2 = VecBatchReader::new(&[new_batch(
+            b"k1",
+            &[1, 3],
+            &[11, 11],
+            &[OpType::Put, OpType::Put],
+            &[31, 33],
+        )]);
+        let mut reader = MergeReaderBuilder::new()
+            .push_batch_reader(Box::new(reader1))
+            .push_batch_iter(Box::new(reader2))
+            .dedup(false)
+            .build()
+            .await
+            .unwrap();
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch(b"k1", &[1], &[11], &[OpType::Put], &[31]),
+                new_batch(
+                    b"k1",
+                    &[1, 2],
+                    &[10, 10],
+                    &[OpType::Put, OpType::Put],
+                    &[21, 22],
+                ),
+                new_batch(b"k1", &[3], &[11], &[OpType::Put], &[33]),
+            ],
+        )
+        .await;
+
+        assert_eq!(0, reader.metrics.num_duplicate_rows);
+    }
 }
@@ -17,7 +17,7 @@
 use std::sync::Arc;
 use std::time::Instant;
 
-use common_recordbatch::SendableRecordBatchStream;
+use common_recordbatch::{OrderOption, SendableRecordBatchStream};
 use common_telemetry::{debug, warn};
 use common_time::range::TimestampRange;
 use store_api::storage::ScanRequest;
@@ -29,6 +29,8 @@ use crate::cache::CacheManagerRef;
 use crate::error::Result;
 use crate::read::projection::ProjectionMapper;
 use crate::read::seq_scan::SeqScan;
+use crate::read::unordered_scan::UnorderedScan;
+use crate::read::windowed_scan::WindowedScan;
 use crate::region::version::VersionRef;
 use crate::sst::file::FileHandle;
 use crate::sst::index::applier::builder::SstIndexApplierBuilder;
@@ -38,7 +40,10 @@ use crate::sst::index::applier::SstIndexApplierRef;
 pub(crate) enum Scanner {
     /// Sequential scan.
     Seq(SeqScan),
-    // TODO(yingwen): Support windowed scan and chained scan.
+    /// Unordered scan for append only regions.
+    Unordered(UnorderedScan),
+    /// Scan by time windows to return rows ordered by the time index.
+    Windowed(WindowedScan),
 }
 
 impl Scanner {
@@ -46,6 +51,8 @@ impl Scanner {
     pub(crate) async fn scan(&self) -> Result<SendableRecordBatchStream> {
         match self {
             Scanner::Seq(seq_scan) => seq_scan.build_stream().await,
+            Scanner::Unordered(unordered_scan) => unordered_scan.build_stream().await,
+            Scanner::Windowed(windowed_scan) => windowed_scan.build_stream().await,
         }
     }
 }
@@ -56,6 +63,8 @@ impl Scanner {
     pub(crate) fn num_files(&self) -> usize {
         match self {
             Scanner::Seq(seq_scan) => seq_scan.num_files(),
+            Scanner::Unordered(unordered_scan) => unordered_scan.input().num_files(),
+            Scanner::Windowed(windowed_scan) => windowed_scan.num_files(),
         }
     }
 
@@ -63,6 +72,8 @@ impl Scanner {
     pub(crate) fn num_memtables(&self) -> usize {
         match self {
             Scanner::Seq(seq_scan) => seq_scan.num_memtables(),
+            Scanner::Unordered(unordered_scan) => unordered_scan.input().num_memtables(),
+            Scanner::Windowed(windowed_scan) => windowed_scan.num_memtables(),
         }
     }
 
@@ -70,6 +81,16 @@ impl Scanner {
     pub(crate) fn file_ids(&self) -> Vec<crate::sst::file::FileId> {
         match self {
             Scanner::Seq(seq_scan) => seq_scan.file_ids(),
+            Scanner::Unordered(unordered_scan) => unordered_scan.input().file_ids(),
+            Scanner::Windowed(windowed_scan) => windowed_scan.file_ids(),
+        }
+    }
+
+    /// Returns number of time windows to scan, `None` if the scanner doesn't scan by windows.
+    pub(crate) fn num_windows(&self) -> Option<usize> {
+        match self {
+            Scanner::Seq(_) | Scanner::Unordered(_) => None,
+            Scanner::Windowed(windowed_scan) => Some(windowed_scan.num_windows()),
         }
     }
 }
@@ -87,10 +108,14 @@ impl Scanner {
 ///     -ScanRequest request
 ///     ~scanner() Scanner
 ///     ~seq_scan() SeqScan
+///     ~unordered_scan() UnorderedScan
+///     ~windowed_scan() WindowedScan
 /// }
 /// class Scanner {
 ///     <<enumeration>>
 ///     SeqScan
+///     UnorderedScan
+///     WindowedScan
 ///     +scan() SendableRecordBatchStream
 /// }
 /// class SeqScan {
@@ -108,6 +133,10 @@ impl Scanner {
 /// ScanRegion -- Scanner
 /// ScanRegion o-- ScanRequest
 /// Scanner o-- SeqScan
+/// Scanner o-- UnorderedScan
+/// UnorderedScan o-- SeqScan
+/// Scanner o-- WindowedScan
+/// WindowedScan o-- SeqScan
 /// Scanner -- SendableRecordBatchStream
 /// SeqScan o-- ProjectionMapper
 /// SeqScan -- SendableRecordBatchStream
@@ -169,9 +198,31 @@ impl ScanRegion {
 
     /// Returns a [Scanner] to scan the region.
     pub(crate) fn scanner(self) -> Result<Scanner> {
+        if let Some(order) = self.time_index_ordering() {
+            // The query only requires rows ordered by the time index.
+            return self.windowed_scan(order).map(Scanner::Windowed);
+        }
+
+        if self.version.options.append_mode {
+            // Rows of an append only region don't need to be merged so we can
+            // scan them in any order.
+            return self.unordered_scan().map(Scanner::Unordered);
+        }
+
         self.seq_scan().map(Scanner::Seq)
     }
 
+    /// Scan without ordering guarantee.
+    pub(crate) fn unordered_scan(self) -> Result<UnorderedScan> {
+        self.seq_scan().map(UnorderedScan::new)
+    }
+
+    /// Scan by time windows and return rows in `order` of the time index.
+    pub(crate) fn windowed_scan(self, order: OrderOption) -> Result<WindowedScan> {
+        self.seq_scan()
+            .and_then(|seq_scan| WindowedScan::new(seq_scan, order))
+    }
+
     /// Scan sequentially.
     pub(crate) fn seq_scan(self) -> Result<SeqScan> {
         let time_range = self.build_time_range_predicate();
@@ -234,11 +285,34 @@ impl ScanRegion {
             .with_cache(self.cache_manager)
             .with_index_applier(index_applier)
             .with_parallelism(self.parallelism)
-            .with_start_time(self.start_time);
+            .with_start_time(self.start_time)
+            .with_append_mode(self.version.options.append_mode);
 
         Ok(seq_scan)
     }
 
+    /// Returns the ordering hint of the request if it only orders by the time index
+    /// and the time index is in the projection.
+    fn time_index_ordering(&self) -> Option<OrderOption> {
+        let ordering = self.request.output_ordering.as_ref()?;
+        let [order] = ordering.as_slice() else {
+            return None;
+        };
+        let metadata = &self.version.metadata;
+        let time_index = metadata.time_index_column();
+        if order.name != time_index.column_schema.name {
+            return None;
+        }
+        if let Some(projection) = &self.request.projection {
+            let index = metadata.column_index_by_id(time_index.column_id)?;
+            if !projection.contains(&index) {
+                return None;
+            }
+        }
+
+        Some(order.clone())
+    }
+
     /// Build time range predicate from filters.
     fn build_time_range_predicate(&self) -> TimestampRange {
         let time_index = self.version.metadata.time_index_column();
@@ -279,6 +353,26 @@ impl ScanRegion {
                 .copied()
                 .collect(),
         )
+        .with_full_text_column_ids(
+            self.version
+                .options
+                .index_options
+                .full_text_index
+                .column_ids
+                .iter()
+                .copied()
+                .collect(),
+        )
+        .with_bloom_filter_column_ids(
+            self.version
+                .options
+                .index_options
+                .bloom_filter_index
+                .column_ids
+                .iter()
+                .copied()
+                .collect(),
+        )
         .build(&self.request.filters)
         .inspect_err(|err| warn!(err; "Failed to build index applier"))
         .ok()
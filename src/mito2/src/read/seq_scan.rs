@@ -67,6 +67,10 @@ pub struct SeqScan {
     index_applier: Option<SstIndexApplierRef>,
     /// Start time of the query.
     query_start: Option<Instant>,
+    /// The region is in append mode, so duplicate rows are kept.
+    append_mode: bool,
+    /// Whether to remove deleted rows.
+    filter_deleted: bool,
 }
 
 impl SeqScan {
@@ -85,6 +89,8 @@ impl SeqScan {
             parallelism: ScanParallism::default(),
             index_applier: None,
             query_start: None,
+            append_mode: false,
+            filter_deleted: true,
         }
     }
 
@@ -151,6 +157,20 @@ impl SeqScan {
         self
     }
 
+    /// Sets whether the region is in append mode.
+    #[must_use]
+    pub(crate) fn with_append_mode(mut self, append_mode: bool) -> Self {
+        self.append_mode = append_mode;
+        self
+    }
+
+    /// Sets whether to remove deleted rows.
+    #[must_use]
+    pub(crate) fn with_filter_deleted(mut self, filter_deleted: bool) -> Self {
+        self.filter_deleted = filter_deleted;
+        self
+    }
+
     /// Builds a stream for the query.
     pub async fn build_stream(&self) -> Result<SendableRecordBatchStream> {
         let mut metrics = Metrics::default();
@@ -211,6 +231,9 @@ impl SeqScan {
         // Scans all memtables and SSTs. Builds a merge reader to merge results.
         let sources = self.build_sources().await?;
         let mut builder = MergeReaderBuilder::from_sources(sources);
+        builder
+            .dedup(!self.append_mode)
+            .filter_deleted(self.filter_deleted);
         Ok(Box::new(builder.build().await?))
     }
 
@@ -229,11 +252,14 @@ impl SeqScan {
             })
             .collect();
         let mut builder = MergeReaderBuilder::from_sources(sources);
+        builder
+            .dedup(!self.append_mode)
+            .filter_deleted(self.filter_deleted);
         Ok(Box::new(builder.build().await?))
     }
 
     /// Builds and returns sources to read.
-    async fn build_sources(&self) -> Result<Vec<Source>> {
+    pub(crate) async fn build_sources(&self) -> Result<Vec<Source>> {
         let mut sources = Vec::with_capacity(self.memtables.len() + self.files.len());
         for mem in &self.memtables {
             let iter = mem.iter(Some(self.mapper.column_ids()), self.predicate.clone())?;
@@ -277,13 +303,72 @@ impl SeqScan {
         Ok(sources)
     }
 
+    /// Returns a new [SeqScan] that reads `memtables` and `files` with the same
+    /// settings as this scan.
+    #[must_use]
+    pub(crate) fn with_targets(
+        &self,
+        memtables: Vec<MemtableRef>,
+        files: Vec<FileHandle>,
+    ) -> SeqScan {
+        SeqScan {
+            access_layer: self.access_layer.clone(),
+            mapper: self.mapper.clone(),
+            time_range: self.time_range,
+            predicate: self.predicate.clone(),
+            memtables,
+            files,
+            cache_manager: self.cache_manager.clone(),
+            ignore_file_not_found: self.ignore_file_not_found,
+            parallelism: self.parallelism,
+            index_applier: self.index_applier.clone(),
+            query_start: self.query_start,
+            append_mode: self.append_mode,
+            filter_deleted: self.filter_deleted,
+        }
+    }
+
+    /// Returns memtables to scan.
+    pub(crate) fn memtables(&self) -> &[MemtableRef] {
+        &self.memtables
+    }
+
+    /// Returns SST files to scan.
+    pub(crate) fn files(&self) -> &[FileHandle] {
+        &self.files
+    }
+
+    /// Returns the scan parallelism.
+    pub(crate) fn parallelism(&self) -> ScanParallism {
+        self.parallelism
+    }
+
+    /// Returns the mapper to convert batches.
+    pub(crate) fn mapper(&self) -> &Arc<ProjectionMapper> {
+        &self.mapper
+    }
+
+    /// Returns the cache manager of the scan.
+    pub(crate) fn cache_manager(&self) -> Option<&CacheManagerRef> {
+        self.cache_manager.as_ref()
+    }
+
+    /// Returns start time of the query.
+    pub(crate) fn query_start(&self) -> Option<Instant> {
+        self.query_start
+    }
+
     /// Returns whether to use a parallel reader.
     fn use_parallel_reader(&self) -> bool {
         self.parallelism.allow_parallel_scan() && (self.files.len() + self.memtables.len()) > 1
     }
 
     /// Scan the input source in another task.
-    fn spawn_scan_task(&self, mut input: Source, semaphore: Arc<Semaphore>) -> BoxedBatchStream {
+    pub(crate) fn spawn_scan_task(
+        &self,
+        mut input: Source,
+        semaphore: Arc<Semaphore>,
+    ) -> BoxedBatchStream {
         let (sender, receiver) = mpsc::channel(self.parallelism.channel_size);
         tokio::spawn(async move {
             loop {
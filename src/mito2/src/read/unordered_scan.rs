@@ -0,0 +1,159 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unordered scan.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_stream::try_stream;
+use common_error::ext::BoxedError;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{RecordBatchStreamWrapper, SendableRecordBatchStream};
+use common_telemetry::debug;
+use futures::stream;
+use snafu::ResultExt;
+use tokio::sync::Semaphore;
+
+use crate::error::Result;
+use crate::metrics::{READ_BATCHES_RETURN, READ_ROWS_RETURN, READ_STAGE_ELAPSED};
+use crate::read::seq_scan::SeqScan;
+use crate::read::Source;
+
+/// Scans a region without providing any output ordering guarantee.
+///
+/// It doesn't merge memtables and SSTs, so it neither sorts nor deduplicates
+/// rows. Only append only regions should use this scanner. Memtables and SSTs
+/// are read one by one, or in parallel if the parallelism allows, and batches
+/// are returned as soon as they are ready.
+pub struct UnorderedScan {
+    /// Scan that holds memtables and SSTs to read.
+    input: SeqScan,
+}
+
+impl UnorderedScan {
+    /// Creates a new [UnorderedScan] that reads targets of the `input`.
+    pub(crate) fn new(input: SeqScan) -> UnorderedScan {
+        UnorderedScan { input }
+    }
+
+    /// Builds a stream for the query.
+    pub async fn build_stream(&self) -> Result<SendableRecordBatchStream> {
+        let mut metrics = Metrics::default();
+        let build_start = Instant::now();
+        let query_start = self.input.query_start().unwrap_or(build_start);
+        metrics.prepare_scan_cost = query_start.elapsed();
+        let mut sources = self.input.build_sources().await?;
+        let parallelism = self.input.parallelism();
+        let use_parallel = parallelism.allow_parallel_scan() && sources.len() > 1;
+        if use_parallel {
+            // Polls all sources in parallel and yields batches in the order they arrive.
+            let semaphore = Arc::new(Semaphore::new(parallelism.parallelism));
+            let streams: Vec<_> = sources
+                .into_iter()
+                .map(|source| self.input.spawn_scan_task(source, semaphore.clone()))
+                .collect();
+            sources = vec![Source::Stream(Box::pin(stream::select_all(streams)))];
+        }
+        metrics.build_reader_cost = build_start.elapsed();
+        READ_STAGE_ELAPSED
+            .with_label_values(&["prepare_scan"])
+            .observe(metrics.prepare_scan_cost.as_secs_f64());
+        READ_STAGE_ELAPSED
+            .with_label_values(&["build_reader"])
+            .observe(metrics.build_reader_cost.as_secs_f64());
+
+        let mapper = self.input.mapper().clone();
+        let cache_manager = self.input.cache_manager().cloned();
+        let stream = try_stream! {
+            let cache = cache_manager.as_ref().map(|cache| cache.as_ref());
+            for mut source in sources {
+                loop {
+                    let start = Instant::now();
+                    let Some(mut batch) = source
+                        .next_batch()
+                        .await
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?
+                    else {
+                        metrics.scan_cost += start.elapsed();
+                        break;
+                    };
+                    // Sources may still contain deleted rows.
+                    batch
+                        .filter_deleted()
+                        .map_err(BoxedError::new)
+                        .context(ExternalSnafu)?;
+                    if batch.is_empty() {
+                        metrics.scan_cost += start.elapsed();
+                        continue;
+                    }
+
+                    let convert_start = Instant::now();
+                    let record_batch = mapper.convert(&batch, cache)?;
+                    metrics.convert_cost += convert_start.elapsed();
+                    metrics.scan_cost += start.elapsed();
+
+                    metrics.num_batches += 1;
+                    metrics.num_rows += record_batch.num_rows();
+                    yield record_batch;
+                }
+            }
+
+            metrics.total_cost = query_start.elapsed();
+            READ_STAGE_ELAPSED.with_label_values(&["convert_rb"]).observe(metrics.convert_cost.as_secs_f64());
+            READ_STAGE_ELAPSED.with_label_values(&["scan"]).observe(metrics.scan_cost.as_secs_f64());
+            READ_STAGE_ELAPSED.with_label_values(&["total"]).observe(metrics.total_cost.as_secs_f64());
+            READ_ROWS_RETURN.observe(metrics.num_rows as f64);
+            READ_BATCHES_RETURN.observe(metrics.num_batches as f64);
+            debug!(
+                "Unordered scan finished, region_id: {:?}, metrics: {:?}, use_parallel: {}, parallelism: {}",
+                mapper.metadata().region_id, metrics, use_parallel, parallelism.parallelism,
+            );
+        };
+        let stream = Box::pin(RecordBatchStreamWrapper::new(
+            self.input.mapper().output_schema(),
+            Box::pin(stream),
+        ));
+
+        Ok(stream)
+    }
+}
+
+#[cfg(test)]
+impl UnorderedScan {
+    /// Returns the input scan.
+    pub(crate) fn input(&self) -> &SeqScan {
+        &self.input
+    }
+}
+
+/// Metrics for [UnorderedScan].
+#[derive(Debug, Default)]
+struct Metrics {
+    /// Duration to prepare the scan task.
+    prepare_scan_cost: Duration,
+    /// Duration to build sources.
+    build_reader_cost: Duration,
+    /// Duration to scan data.
+    scan_cost: Duration,
+    /// Duration to convert batches.
+    convert_cost: Duration,
+    /// Duration of the scan.
+    total_cost: Duration,
+    /// Number of batches returned.
+    num_batches: usize,
+    /// Number of rows returned.
+    num_rows: usize,
+}
@@ -0,0 +1,540 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windowed scan.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_stream::try_stream;
+use common_error::ext::BoxedError;
+use common_recordbatch::error::{ArrowComputeSnafu, ExternalSnafu, NewDfRecordBatchSnafu};
+use common_recordbatch::{
+    DfRecordBatch, OrderOption, RecordBatch, RecordBatchStreamWrapper, SendableRecordBatchStream,
+};
+use common_telemetry::debug;
+use common_time::Timestamp;
+use datatypes::arrow::array::Array;
+use datatypes::arrow::compute;
+use datatypes::schema::SchemaRef;
+use snafu::{OptionExt, ResultExt};
+
+use crate::cache::CacheManagerRef;
+use crate::error::{InvalidRequestSnafu, Result};
+use crate::memtable::MemtableRef;
+use crate::metrics::{READ_BATCHES_RETURN, READ_ROWS_RETURN, READ_STAGE_ELAPSED};
+use crate::read::projection::ProjectionMapper;
+use crate::read::seq_scan::SeqScan;
+use crate::read::BatchReader;
+use crate::sst::file::FileHandle;
+use crate::sst::parquet::DEFAULT_READ_BATCH_SIZE;
+
+/// Scans a region window by window and returns rows ordered by the time index.
+///
+/// It groups memtables and SSTs whose time ranges overlap into the same time window.
+/// Windows don't overlap with each other so the scanner can merge each window
+/// independently and return rows of the first window early. This helps queries
+/// like `ORDER BY ts DESC LIMIT 10` that only need rows in the latest windows.
+///
+/// Each batch read from a window contains rows of one series sorted by the time
+/// index, so the scanner merges these sorted runs by a heap and returns small
+/// batches instead of sorting all rows in the window.
+pub struct WindowedScan {
+    /// Scans of each window, in output order.
+    windows: Arc<[SeqScan]>,
+    /// Maps projected Batches to RecordBatches.
+    mapper: Arc<ProjectionMapper>,
+    /// Cache.
+    cache_manager: Option<CacheManagerRef>,
+    /// Start time of the query.
+    query_start: Option<Instant>,
+    /// Ordering of the time index in output.
+    order: OrderOption,
+    /// Index of the time index column in output.
+    time_index: usize,
+}
+
+impl WindowedScan {
+    /// Creates a new [WindowedScan] that reads targets of the `input` in `order`.
+    ///
+    /// Returns an error if the time index isn't in the output of the `input`.
+    pub(crate) fn new(input: SeqScan, order: OrderOption) -> Result<WindowedScan> {
+        let time_index = input
+            .mapper()
+            .output_schema()
+            .column_index_by_name(&order.name)
+            .with_context(|| InvalidRequestSnafu {
+                region_id: input.mapper().metadata().region_id,
+                reason: format!("time index {} is not in the projection", order.name),
+            })?;
+        let mut windows: Vec<_> = group_by_windows(input.memtables(), input.files())
+            .into_iter()
+            .map(|window| input.with_targets(window.memtables, window.files))
+            .collect();
+        if order.options.descending {
+            windows.reverse();
+        }
+
+        Ok(WindowedScan {
+            windows: windows.into(),
+            mapper: input.mapper().clone(),
+            cache_manager: input.cache_manager().cloned(),
+            query_start: input.query_start(),
+            order,
+            time_index,
+        })
+    }
+
+    /// Builds a stream for the query.
+    pub async fn build_stream(&self) -> Result<SendableRecordBatchStream> {
+        let mut metrics = Metrics {
+            num_windows: self.windows.len(),
+            ..Default::default()
+        };
+        let query_start = self.query_start.unwrap_or_else(Instant::now);
+        let windows = self.windows.clone();
+        let mapper = self.mapper.clone();
+        let cache_manager = self.cache_manager.clone();
+        let time_index = self.time_index;
+        let descending = self.order.options.descending;
+        let stream = try_stream! {
+            let cache = cache_manager.as_ref().map(|cache| cache.as_ref());
+            let output_schema = mapper.output_schema();
+            for window in windows.iter() {
+                let start = Instant::now();
+                // Merges rows in the window.
+                let mut reader = window
+                    .build_reader()
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu)?;
+                let mut merger = SortedRunsMerger::new(output_schema.clone(), time_index, descending);
+                while let Some(batch) = reader
+                    .next_batch()
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(ExternalSnafu)?
+                {
+                    // Safety: The reader doesn't return empty batches.
+                    let timestamps = batch.timestamps_native().unwrap().to_vec();
+                    merger.push(mapper.convert(&batch, cache)?, timestamps);
+                }
+                metrics.scan_cost += start.elapsed();
+
+                loop {
+                    let merge_start = Instant::now();
+                    let merged = merger.next_batch(DEFAULT_READ_BATCH_SIZE)?;
+                    metrics.merge_cost += merge_start.elapsed();
+                    let Some(record_batch) = merged else {
+                        break;
+                    };
+                    metrics.num_batches += 1;
+                    metrics.num_rows += record_batch.num_rows();
+                    yield record_batch;
+                }
+            }
+
+            metrics.total_cost = query_start.elapsed();
+            READ_STAGE_ELAPSED.with_label_values(&["merge_window"]).observe(metrics.merge_cost.as_secs_f64());
+            READ_STAGE_ELAPSED.with_label_values(&["scan"]).observe(metrics.scan_cost.as_secs_f64());
+            READ_STAGE_ELAPSED.with_label_values(&["total"]).observe(metrics.total_cost.as_secs_f64());
+            READ_ROWS_RETURN.observe(metrics.num_rows as f64);
+            READ_BATCHES_RETURN.observe(metrics.num_batches as f64);
+            debug!(
+                "Windowed scan finished, region_id: {:?}, metrics: {:?}",
+                mapper.metadata().region_id, metrics,
+            );
+        };
+
+        Ok(Box::pin(RecordBatchStreamWrapper {
+            schema: self.mapper.output_schema(),
+            stream: Box::pin(stream),
+            output_ordering: Some(vec![self.order.clone()]),
+            metrics: Default::default(),
+        }))
+    }
+}
+
+#[cfg(test)]
+impl WindowedScan {
+    /// Returns number of windows to scan.
+    pub(crate) fn num_windows(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Returns number of memtables to scan.
+    pub(crate) fn num_memtables(&self) -> usize {
+        self.windows.iter().map(|w| w.num_memtables()).sum()
+    }
+
+    /// Returns number of SST files to scan.
+    pub(crate) fn num_files(&self) -> usize {
+        self.windows.iter().map(|w| w.num_files()).sum()
+    }
+
+    /// Returns SST file ids to scan.
+    pub(crate) fn file_ids(&self) -> Vec<crate::sst::file::FileId> {
+        self.windows.iter().flat_map(|w| w.file_ids()).collect()
+    }
+}
+
+/// Memtables and SSTs in a time window.
+#[derive(Default)]
+struct Window {
+    /// Inclusive time range of the window, `None` if the window is unbounded.
+    time_range: Option<(Timestamp, Timestamp)>,
+    memtables: Vec<MemtableRef>,
+    files: Vec<FileHandle>,
+}
+
+/// A memtable or a SST to scan.
+enum Target {
+    Memtable(MemtableRef),
+    File(FileHandle),
+}
+
+/// Groups `memtables` and `files` whose time ranges overlap into the same window.
+///
+/// Returns windows in ascending order of their time ranges.
+fn group_by_windows(memtables: &[MemtableRef], files: &[FileHandle]) -> Vec<Window> {
+    let mut targets = Vec::with_capacity(memtables.len() + files.len());
+    let mut unbounded = false;
+    for mem in memtables {
+        match mem.stats().time_range() {
+            Some(range) => targets.push((range, Target::Memtable(mem.clone()))),
+            None => unbounded = true,
+        }
+    }
+    for file in files {
+        targets.push((file.time_range(), Target::File(file.clone())));
+    }
+    if unbounded {
+        // We don't know the time range of some memtables, so we put everything
+        // into one window.
+        return vec![Window {
+            time_range: None,
+            memtables: memtables.to_vec(),
+            files: files.to_vec(),
+        }];
+    }
+
+    targets.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    let mut windows: Vec<Window> = Vec::new();
+    for ((start, end), target) in targets {
+        let overlapped = windows
+            .last()
+            .and_then(|window| window.time_range)
+            .map(|(_, window_end)| start <= window_end)
+            .unwrap_or(false);
+        if !overlapped {
+            windows.push(Window {
+                time_range: Some((start, end)),
+                ..Default::default()
+            });
+        }
+        // Safety: We ensure the last window exists and it is bounded.
+        let window = windows.last_mut().unwrap();
+        let (window_start, window_end) = window.time_range.unwrap();
+        window.time_range = Some((window_start, window_end.max(end)));
+        match target {
+            Target::Memtable(mem) => window.memtables.push(mem),
+            Target::File(file) => window.files.push(file),
+        }
+    }
+
+    windows
+}
+
+/// Position of the next row to merge in a sorted run.
+#[derive(Debug, PartialEq, Eq)]
+struct RunCursor {
+    /// Timestamp of the row.
+    ts: i64,
+    /// Index of the run.
+    run: usize,
+    /// Index of the row in the run.
+    row: usize,
+    /// Whether to pop larger timestamps first.
+    descending: bool,
+}
+
+impl Ord for RunCursor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // [BinaryHeap] pops the greatest cursor first. Rows of former runs are returned
+        // first if timestamps are equal.
+        let by_ts = if self.descending {
+            self.ts.cmp(&other.ts)
+        } else {
+            other.ts.cmp(&self.ts)
+        };
+        by_ts.then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for RunCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Merges record batches whose rows are sorted by the time index in ascending
+/// order into batches sorted in the output order.
+struct SortedRunsMerger {
+    schema: SchemaRef,
+    /// Index of the time index column.
+    time_index: usize,
+    descending: bool,
+    /// Record batches to merge.
+    runs: Vec<RecordBatch>,
+    /// Timestamps of each run.
+    timestamps: Vec<Vec<i64>>,
+    heap: BinaryHeap<RunCursor>,
+}
+
+impl SortedRunsMerger {
+    fn new(schema: SchemaRef, time_index: usize, descending: bool) -> SortedRunsMerger {
+        SortedRunsMerger {
+            schema,
+            time_index,
+            descending,
+            runs: Vec::new(),
+            timestamps: Vec::new(),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Adds a run with `timestamps` of its rows.
+    fn push(&mut self, run: RecordBatch, timestamps: Vec<i64>) {
+        debug_assert_eq!(run.num_rows(), timestamps.len());
+        debug_assert_eq!(
+            timestamps.len(),
+            run.df_record_batch().column(self.time_index).len()
+        );
+        if timestamps.is_empty() {
+            return;
+        }
+
+        let row = if self.descending {
+            timestamps.len() - 1
+        } else {
+            0
+        };
+        self.heap.push(RunCursor {
+            ts: timestamps[row],
+            run: self.runs.len(),
+            row,
+            descending: self.descending,
+        });
+        self.runs.push(run);
+        self.timestamps.push(timestamps);
+    }
+
+    /// Returns the next batch with at most `batch_size` rows, or `None` if all
+    /// rows are returned.
+    fn next_batch(
+        &mut self,
+        batch_size: usize,
+    ) -> common_recordbatch::error::Result<Option<RecordBatch>> {
+        let mut indices = Vec::with_capacity(batch_size.min(self.heap.len()));
+        while indices.len() < batch_size {
+            let Some(cursor) = self.heap.pop() else {
+                break;
+            };
+            indices.push((cursor.run, cursor.row));
+
+            let timestamps = &self.timestamps[cursor.run];
+            let next_row = if self.descending {
+                cursor.row.checked_sub(1)
+            } else {
+                Some(cursor.row + 1).filter(|row| *row < timestamps.len())
+            };
+            if let Some(row) = next_row {
+                self.heap.push(RunCursor {
+                    ts: timestamps[row],
+                    row,
+                    ..cursor
+                });
+            }
+        }
+        if indices.is_empty() {
+            return Ok(None);
+        }
+
+        let num_columns = self.schema.num_columns();
+        let mut columns = Vec::with_capacity(num_columns);
+        for i in 0..num_columns {
+            let arrays: Vec<&dyn Array> = self
+                .runs
+                .iter()
+                .map(|run| run.df_record_batch().column(i).as_ref())
+                .collect();
+            columns.push(compute::interleave(&arrays, &indices).context(ArrowComputeSnafu)?);
+        }
+        let merged = DfRecordBatch::try_new(self.schema.arrow_schema().clone(), columns)
+            .context(NewDfRecordBatchSnafu)?;
+
+        RecordBatch::try_from_df_record_batch(self.schema.clone(), merged).map(Some)
+    }
+}
+
+/// Metrics for [WindowedScan].
+#[derive(Debug, Default)]
+struct Metrics {
+    /// Number of windows to scan.
+    num_windows: usize,
+    /// Duration to scan data.
+    scan_cost: Duration,
+    /// Duration to merge rows in windows.
+    merge_cost: Duration,
+    /// Duration of the scan.
+    total_cost: Duration,
+    /// Number of batches returned.
+    num_batches: usize,
+    /// Number of rows returned.
+    num_rows: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use datatypes::prelude::{ConcreteDataType, ScalarVector};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Int64Vector, TimestampMillisecondVector};
+
+    use super::*;
+    use crate::sst::file::FileId;
+    use crate::test_util::memtable_util::EmptyMemtable;
+    use crate::test_util::sst_util::sst_file_handle;
+
+    fn window_file_ids(windows: &[Window]) -> Vec<Vec<FileId>> {
+        windows
+            .iter()
+            .map(|window| window.files.iter().map(|file| file.file_id()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_windows() {
+        let files = [
+            sst_file_handle(10, 20),
+            sst_file_handle(0, 5),
+            sst_file_handle(15, 30),
+            sst_file_handle(6, 9),
+            sst_file_handle(30, 40),
+            sst_file_handle(50, 60),
+        ];
+        let windows = group_by_windows(&[], &files);
+        let ids: Vec<_> = files.iter().map(|file| file.file_id()).collect();
+        assert_eq!(
+            vec![
+                vec![ids[1]],
+                vec![ids[3]],
+                vec![ids[0], ids[2], ids[4]],
+                vec![ids[5]],
+            ],
+            window_file_ids(&windows)
+        );
+        assert_eq!(
+            vec![
+                Some((
+                    Timestamp::new_millisecond(10),
+                    Timestamp::new_millisecond(40)
+                )),
+                Some((
+                    Timestamp::new_millisecond(50),
+                    Timestamp::new_millisecond(60)
+                )),
+            ],
+            windows[2..]
+                .iter()
+                .map(|window| window.time_range)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_group_by_windows_unbounded() {
+        let files = [sst_file_handle(0, 5), sst_file_handle(10, 20)];
+        // The time range of an empty memtable is unknown.
+        let memtables = [Arc::new(EmptyMemtable::new(0)) as MemtableRef];
+        let windows = group_by_windows(&memtables, &files);
+        assert_eq!(1, windows.len());
+        assert!(windows[0].time_range.is_none());
+        assert_eq!(1, windows[0].memtables.len());
+        assert_eq!(2, windows[0].files.len());
+    }
+
+    fn new_run(schema: &SchemaRef, timestamps: &[i64], value: i64) -> (RecordBatch, Vec<i64>) {
+        let batch = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Vector::from_vec(vec![value; timestamps.len()])) as _,
+                Arc::new(TimestampMillisecondVector::from_vec(timestamps.to_vec())) as _,
+            ],
+        )
+        .unwrap();
+        (batch, timestamps.to_vec())
+    }
+
+    fn merge_runs(descending: bool, batch_size: usize) -> Vec<Vec<(i64, i64)>> {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("v", ConcreteDataType::int64_datatype(), false),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+        ]));
+        let mut merger = SortedRunsMerger::new(schema.clone(), 1, descending);
+        for (timestamps, value) in [(&[1, 3, 5][..], 0), (&[][..], 1), (&[2, 3, 6][..], 2)] {
+            let (batch, timestamps) = new_run(&schema, timestamps, value);
+            merger.push(batch, timestamps);
+        }
+
+        let mut outputs = Vec::new();
+        while let Some(batch) = merger.next_batch(batch_size).unwrap() {
+            let values = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Vector>()
+                .unwrap();
+            let timestamps = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<TimestampMillisecondVector>()
+                .unwrap();
+            outputs.push(
+                timestamps
+                    .iter_data()
+                    .zip(values.iter_data())
+                    .map(|(ts, v)| (ts.unwrap().0.value(), v.unwrap()))
+                    .collect(),
+            );
+        }
+        outputs
+    }
+
+    #[test]
+    fn test_sorted_runs_merger() {
+        assert_eq!(
+            vec![vec![(1, 0), (2, 2), (3, 0), (3, 2)], vec![(5, 0), (6, 2)]],
+            merge_runs(false, 4)
+        );
+        assert_eq!(
+            vec![vec![(6, 2), (5, 0), (3, 0)], vec![(3, 2), (2, 2), (1, 0)]],
+            merge_runs(true, 3)
+        );
+    }
+}
@@ -16,6 +16,7 @@
 
 pub(crate) mod opener;
 pub mod options;
+pub(crate) mod snapshot;
 pub(crate) mod version;
 
 use std::collections::HashMap;
@@ -31,9 +32,11 @@ use store_api::storage::RegionId;
 
 use crate::access_layer::AccessLayerRef;
 use crate::error::{RegionNotFoundSnafu, RegionReadonlySnafu, Result};
+use crate::index_build::IndexBuildTracker;
 use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
 use crate::manifest::manager::RegionManifestManager;
-use crate::memtable::MemtableId;
+use crate::memtable::{MemtableBuilderRef, MemtableId};
+use crate::region::snapshot::RegionSnapshots;
 use crate::region::version::{VersionControlRef, VersionRef};
 use crate::request::OnFailure;
 use crate::sst::file_purger::FilePurgerRef;
@@ -79,6 +82,12 @@ pub(crate) struct MitoRegion {
     pub(crate) file_purger: FilePurgerRef,
     /// Wal options of this region.
     pub(crate) wal_options: WalOptions,
+    /// Builder to build memtables of this region.
+    pub(crate) memtable_builder: MemtableBuilderRef,
+    /// Named snapshots of this region.
+    pub(crate) snapshots: RegionSnapshots,
+    /// Tracker of the index build job of this region.
+    pub(crate) index_build: IndexBuildTracker,
     /// Last flush time in millis.
     last_flush_millis: AtomicI64,
     /// Whether the region is writable.
@@ -35,16 +35,18 @@ use crate::config::MitoConfig;
 use crate::error::{
     EmptyRegionDirSnafu, ObjectStoreNotFoundSnafu, RegionCorruptedSnafu, Result, StaleLogEntrySnafu,
 };
+use crate::index_build::IndexBuildTracker;
 use crate::manifest::manager::{RegionManifestManager, RegionManifestOptions};
 use crate::manifest::storage::manifest_compress_type;
-use crate::memtable::MemtableBuilderRef;
+use crate::memtable::MemtableBuilderProvider;
 use crate::region::options::RegionOptions;
+use crate::region::snapshot::RegionSnapshots;
 use crate::region::version::{VersionBuilder, VersionControl, VersionControlRef};
 use crate::region::MitoRegion;
 use crate::region_write_ctx::RegionWriteCtx;
 use crate::request::OptionOutputTx;
 use crate::schedule::scheduler::SchedulerRef;
-use crate::sst::file_purger::LocalFilePurger;
+use crate::sst::file_purger::{FilePurgerRef, LocalFilePurger};
 use crate::sst::index::intermediate::IntermediateManager;
 use crate::wal::{EntryId, Wal};
 
@@ -52,7 +54,7 @@ use crate::wal::{EntryId, Wal};
 pub(crate) struct RegionOpener {
     region_id: RegionId,
     metadata: Option<RegionMetadata>,
-    memtable_builder: MemtableBuilderRef,
+    memtable_builder_provider: MemtableBuilderProvider,
     object_store_manager: ObjectStoreManagerRef,
     region_dir: String,
     scheduler: SchedulerRef,
@@ -67,7 +69,7 @@ impl RegionOpener {
     pub(crate) fn new(
         region_id: RegionId,
         region_dir: &str,
-        memtable_builder: MemtableBuilderRef,
+        memtable_builder_provider: MemtableBuilderProvider,
         object_store_manager: ObjectStoreManagerRef,
         scheduler: SchedulerRef,
         intermediate_manager: IntermediateManager,
@@ -75,7 +77,7 @@ impl RegionOpener {
         RegionOpener {
             region_id,
             metadata: None,
-            memtable_builder,
+            memtable_builder_provider,
             object_store_manager,
             region_dir: normalize_dir(region_dir),
             scheduler,
@@ -167,9 +169,11 @@ impl RegionOpener {
         let metadata = Arc::new(self.metadata.unwrap());
         let manifest_manager =
             RegionManifestManager::new(metadata.clone(), region_manifest_options).await?;
+        wal.create_region(region_id, &wal_options).await?;
 
+        let memtable_builder = self.memtable_builder_provider.builder_for_options(&options);
         // Initial memtable id is 0.
-        let mutable = self.memtable_builder.build(0, &metadata);
+        let mutable = memtable_builder.build(0, &metadata);
 
         let version = VersionBuilder::new(metadata, mutable)
             .options(options)
@@ -192,6 +196,9 @@ impl RegionOpener {
                 self.cache_manager,
             )),
             wal_options,
+            memtable_builder,
+            snapshots: RegionSnapshots::default(),
+            index_build: IndexBuildTracker::default(),
             last_flush_millis: AtomicI64::new(current_time_millis()),
             // Region is writable after it is created.
             writable: AtomicBool::new(true),
@@ -254,13 +261,16 @@ impl RegionOpener {
             object_store,
             self.intermediate_manager.clone(),
         ));
-        let file_purger = Arc::new(LocalFilePurger::new(
+        let file_purger: FilePurgerRef = Arc::new(LocalFilePurger::new(
             self.scheduler.clone(),
             access_layer.clone(),
             self.cache_manager.clone(),
         ));
+        let memtable_builder = self
+            .memtable_builder_provider
+            .builder_for_options(&region_options);
         // Initial memtable id is 0.
-        let mutable = self.memtable_builder.build(0, &metadata);
+        let mutable = memtable_builder.build(0, &metadata);
         let version = VersionBuilder::new(metadata, mutable)
             .add_files(file_purger.clone(), manifest.files.values().cloned())
             .flushed_entry_id(manifest.flushed_entry_id)
@@ -270,6 +280,18 @@ impl RegionOpener {
             .options(region_options)
             .build();
         let flushed_entry_id = version.flushed_entry_id;
+        // Pins files referenced by snapshots.
+        let snapshots = RegionSnapshots::default();
+        for snapshot in manifest.snapshots.values() {
+            let files = manifest_manager
+                .load_snapshot_files(&snapshot.name)
+                .await?
+                .with_context(|| RegionCorruptedSnafu {
+                    region_id,
+                    reason: format!("files of snapshot {} not found", snapshot.name),
+                })?;
+            snapshots.pin(snapshot.clone(), files, &version.ssts, &file_purger);
+        }
         let version_control = Arc::new(VersionControl::new(version));
         if !self.skip_wal_replay {
             info!(
@@ -297,6 +319,9 @@ impl RegionOpener {
             manifest_manager,
             file_purger,
             wal_options,
+            memtable_builder,
+            snapshots,
+            index_build: IndexBuildTracker::default(),
             last_flush_millis: AtomicI64::new(current_time_millis()),
             // Region is always opened in read only mode.
             writable: AtomicBool::new(false),
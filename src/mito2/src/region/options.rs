@@ -22,12 +22,15 @@ use serde::de::Error as _;
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 use serde_with::{serde_as, with_prefix, DisplayFromStr};
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
+use store_api::metadata::RegionMetadata;
 use store_api::storage::ColumnId;
 
-use crate::error::{Error, JsonOptionsSnafu, Result};
+use crate::error::{Error, InvalidRequestSnafu, JsonOptionsSnafu, Result};
 
 const DEFAULT_INDEX_SEGMENT_ROW_COUNT: usize = 1024;
+/// Prefix of options to set the downsample function of a field.
+const DOWNSAMPLE_FIELD_FUNCTION_PREFIX: &str = "downsample.function.";
 
 /// Options that affect the entire region.
 ///
@@ -46,6 +49,11 @@ pub struct RegionOptions {
     pub wal_options: WalOptions,
     /// Index options.
     pub index_options: IndexOptions,
+    /// Whether the region is in append only mode. Rows with the same primary key
+    /// and timestamp are not deduplicated in this mode.
+    pub append_mode: bool,
+    /// Downsample options.
+    pub downsample: DownsampleOptions,
 }
 
 impl TryFrom<&HashMap<String, String>> for RegionOptions {
@@ -71,6 +79,9 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
         )?;
 
         let index_options: IndexOptions = serde_json::from_str(&json).context(JsonOptionsSnafu)?;
+        let mut downsample: DownsampleOptions =
+            serde_json::from_str(&json).context(JsonOptionsSnafu)?;
+        downsample.field_functions = downsample_field_functions(&value)?;
 
         Ok(RegionOptions {
             ttl: options.ttl,
@@ -78,6 +89,8 @@ impl TryFrom<&HashMap<String, String>> for RegionOptions {
             storage: options.storage,
             wal_options,
             index_options,
+            append_mode: options.append_mode,
+            downsample,
         })
     }
 }
@@ -90,6 +103,9 @@ pub enum CompactionOptions {
     /// Time window compaction strategy.
     #[serde(with = "prefix_twcs")]
     Twcs(TwcsOptions),
+    /// Size-tiered compaction strategy within time windows.
+    #[serde(with = "prefix_stcs")]
+    Stcs(StcsOptions),
 }
 
 impl Default for CompactionOptions {
@@ -140,8 +156,129 @@ impl Default for TwcsOptions {
     }
 }
 
+/// Size-tiered compaction options.
+///
+/// Files in the same time window are grouped into tiers of similar sizes and
+/// only files in the same tier are merged.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct StcsOptions {
+    /// Min num of files in a tier to trigger a compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub min_threshold: usize,
+    /// Max num of files to merge in a compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub max_threshold: usize,
+    /// Compaction time window defined when creating tables.
+    #[serde(with = "humantime_serde")]
+    pub time_window: Option<Duration>,
+}
+
+with_prefix!(prefix_stcs "compaction.stcs.");
+
+impl StcsOptions {
+    /// Returns time window in second resolution.
+    pub fn time_window_seconds(&self) -> Option<i64> {
+        self.time_window.and_then(|window| {
+            let window_secs = window.as_secs();
+            if window_secs == 0 {
+                None
+            } else {
+                window_secs.try_into().ok()
+            }
+        })
+    }
+}
+
+impl Default for StcsOptions {
+    fn default() -> Self {
+        Self {
+            min_threshold: 4,
+            max_threshold: 32,
+            time_window: None,
+        }
+    }
+}
+
+/// Options to roll up old data into coarser resolution during compaction.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default)]
+pub struct DownsampleOptions {
+    /// Downsamples time windows older than this duration. Disabled if not set.
+    #[serde(rename = "downsample.after", with = "humantime_serde")]
+    pub after: Option<Duration>,
+    /// Resolution of the downsampled data.
+    #[serde(rename = "downsample.interval", with = "humantime_serde")]
+    pub interval: Option<Duration>,
+    /// Function to aggregate field values in the same interval.
+    #[serde(rename = "downsample.function")]
+    pub function: DownsampleFunction,
+    /// Functions of specific fields, keyed by lowercase field names. They are set by
+    /// `downsample.function.<field>` options.
+    #[serde(skip)]
+    pub field_functions: HashMap<String, DownsampleFunction>,
+}
+
+impl DownsampleOptions {
+    /// Returns `(after, interval)` if downsampling is enabled.
+    pub fn enabled(&self) -> Option<(Duration, Duration)> {
+        match (self.after, self.interval) {
+            (Some(after), Some(interval)) if interval.as_millis() > 0 => Some((after, interval)),
+            _ => None,
+        }
+    }
+
+    /// Validates the options against fields of the region.
+    ///
+    /// Averages of downsampled rows are merged by the number of rows, so a numeric
+    /// field downsampled by avg requires a numeric field downsampled by count.
+    pub(crate) fn validate(&self, metadata: &RegionMetadata) -> Result<()> {
+        if self.enabled().is_none() {
+            return Ok(());
+        }
+
+        let has_function = |function| {
+            metadata.field_columns().any(|column| {
+                column.column_schema.data_type.is_numeric()
+                    && self
+                        .field_functions
+                        .get(&column.column_schema.name.to_lowercase())
+                        .copied()
+                        .unwrap_or(self.function)
+                        == function
+            })
+        };
+        ensure!(
+            !has_function(DownsampleFunction::Avg) || has_function(DownsampleFunction::Count),
+            InvalidRequestSnafu {
+                region_id: metadata.region_id,
+                reason: "downsampling by avg requires a numeric field downsampled by count",
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Function to aggregate field values while downsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownsampleFunction {
+    /// Average of values. Integers are rounded towards zero.
+    #[default]
+    Avg,
+    Min,
+    Max,
+    Sum,
+    First,
+    Last,
+    /// Number of non-null values.
+    Count,
+}
+
 /// We need to define a new struct without enum fields as `#[serde(default)]` does not
 /// support external tagging.
+#[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(default)]
 struct RegionOptionsWithoutEnum {
@@ -149,6 +286,8 @@ struct RegionOptionsWithoutEnum {
     #[serde(with = "humantime_serde")]
     ttl: Option<Duration>,
     storage: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    append_mode: bool,
 }
 
 impl Default for RegionOptionsWithoutEnum {
@@ -157,11 +296,14 @@ impl Default for RegionOptionsWithoutEnum {
         RegionOptionsWithoutEnum {
             ttl: options.ttl,
             storage: options.storage,
+            append_mode: options.append_mode,
         }
     }
 }
 
 with_prefix!(prefix_inverted_index "index.inverted_index.");
+with_prefix!(prefix_full_text_index "index.full_text_index.");
+with_prefix!(prefix_bloom_filter_index "index.bloom_filter_index.");
 
 /// Options for index.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
@@ -170,6 +312,12 @@ pub struct IndexOptions {
     /// Options for the inverted index.
     #[serde(flatten, with = "prefix_inverted_index")]
     pub inverted_index: InvertedIndexOptions,
+    /// Options for the full-text index.
+    #[serde(flatten, with = "prefix_full_text_index")]
+    pub full_text_index: FullTextIndexOptions,
+    /// Options for the bloom filter index.
+    #[serde(flatten, with = "prefix_bloom_filter_index")]
+    pub bloom_filter_index: BloomFilterIndexOptions,
 }
 
 /// Options for the inverted index.
@@ -179,7 +327,7 @@ pub struct IndexOptions {
 pub struct InvertedIndexOptions {
     /// The column ids that should be ignored when building the inverted index.
     /// The column ids are separated by commas. For example, "1,2,3".
-    #[serde(deserialize_with = "deserialize_ignore_column_ids")]
+    #[serde(deserialize_with = "deserialize_column_ids")]
     pub ignore_column_ids: Vec<ColumnId>,
 
     /// The number of rows in a segment.
@@ -196,7 +344,27 @@ impl Default for InvertedIndexOptions {
     }
 }
 
-fn deserialize_ignore_column_ids<'de, D>(deserializer: D) -> Result<Vec<ColumnId>, D::Error>
+/// Options for the full-text index.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default)]
+pub struct FullTextIndexOptions {
+    /// The ids of string field columns to build the full-text index.
+    /// The column ids are separated by commas. For example, "4,5".
+    #[serde(deserialize_with = "deserialize_column_ids")]
+    pub column_ids: Vec<ColumnId>,
+}
+
+/// Options for the bloom filter index.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default)]
+pub struct BloomFilterIndexOptions {
+    /// The ids of field columns to build the bloom filter index.
+    /// The column ids are separated by commas. For example, "4,5".
+    #[serde(deserialize_with = "deserialize_column_ids")]
+    pub column_ids: Vec<ColumnId>,
+}
+
+fn deserialize_column_ids<'de, D>(deserializer: D) -> Result<Vec<ColumnId>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -212,6 +380,23 @@ where
 /// Converts the `options` map to a json object.
 ///
 /// Converts all key-values to lowercase and replaces "null" strings by `null` json values.
+/// Parses `downsample.function.<field>` options.
+fn downsample_field_functions(options: &Value) -> Result<HashMap<String, DownsampleFunction>> {
+    let Value::Object(map) = options else {
+        return Ok(HashMap::new());
+    };
+    map.iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(DOWNSAMPLE_FIELD_FUNCTION_PREFIX)
+                .map(|field| (field, value))
+        })
+        .map(|(field, value)| {
+            let function = serde_json::from_value(value.clone()).context(JsonOptionsSnafu)?;
+            Ok((field.to_string(), function))
+        })
+        .collect()
+}
+
 fn options_map_to_value(options: &HashMap<String, String>) -> Value {
     let map = options
         .iter()
@@ -303,6 +488,77 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_stcs_compaction_type() {
+        let map = make_map(&[
+            ("compaction.stcs.min_threshold", "2"),
+            ("compaction.stcs.max_threshold", "8"),
+            ("compaction.stcs.time_window", "1d"),
+            ("compaction.type", "stcs"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            compaction: CompactionOptions::Stcs(StcsOptions {
+                min_threshold: 2,
+                max_threshold: 8,
+                time_window: Some(Duration::from_secs(3600 * 24)),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+    }
+
+    #[test]
+    fn test_with_downsample() {
+        let map = make_map(&[("downsample.after", "7d"), ("downsample.interval", "5m")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            downsample: DownsampleOptions {
+                after: Some(Duration::from_secs(3600 * 24 * 7)),
+                interval: Some(Duration::from_secs(300)),
+                function: DownsampleFunction::Avg,
+                field_functions: HashMap::new(),
+            },
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+        assert!(options.downsample.enabled().is_some());
+
+        let map = make_map(&[("downsample.function", "unknown")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+
+        let map = make_map(&[
+            ("downsample.function", "sum"),
+            ("downsample.function.Requests", "count"),
+            ("downsample.function.latency", "max"),
+        ]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        assert_eq!(DownsampleFunction::Sum, options.downsample.function);
+        assert_eq!(
+            HashMap::from([
+                ("requests".to_string(), DownsampleFunction::Count),
+                ("latency".to_string(), DownsampleFunction::Max),
+            ]),
+            options.downsample.field_functions
+        );
+        let map = make_map(&[("downsample.function.latency", "unknown")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+    }
+
+    #[test]
+    fn test_with_append_mode() {
+        let map = make_map(&[("append_mode", "true")]);
+        let options = RegionOptions::try_from(&map).unwrap();
+        let expect = RegionOptions {
+            append_mode: true,
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+
+        let map = make_map(&[("append_mode", "invalid")]);
+        assert!(RegionOptions::try_from(&map).is_err());
+    }
+
     fn test_with_wal_options(wal_options: &WalOptions) -> bool {
         let encoded_wal_options = serde_json::to_string(&wal_options).unwrap();
         let map = make_map(&[(WAL_OPTIONS_KEY, &encoded_wal_options)]);
@@ -319,6 +575,8 @@ mod tests {
         let map = make_map(&[
             ("index.inverted_index.ignore_column_ids", "1,2,3"),
             ("index.inverted_index.segment_row_count", "512"),
+            ("index.full_text_index.column_ids", "4,5"),
+            ("index.bloom_filter_index.column_ids", "6"),
         ]);
         let options = RegionOptions::try_from(&map).unwrap();
         let expect = RegionOptions {
@@ -327,6 +585,12 @@ mod tests {
                     ignore_column_ids: vec![1, 2, 3],
                     segment_row_count: 512,
                 },
+                full_text_index: FullTextIndexOptions {
+                    column_ids: vec![4, 5],
+                },
+                bloom_filter_index: BloomFilterIndexOptions {
+                    column_ids: vec![6],
+                },
             },
             ..Default::default()
         };
@@ -358,6 +622,10 @@ mod tests {
             ("compaction.type", "twcs"),
             ("storage", "S3"),
             ("index.inverted_index.ignore_column_ids", "1,2,3"),
+            ("append_mode", "true"),
+            ("downsample.after", "7d"),
+            ("downsample.interval", "5m"),
+            ("downsample.function", "max"),
             (
                 WAL_OPTIONS_KEY,
                 &serde_json::to_string(&wal_options).unwrap(),
@@ -378,6 +646,15 @@ mod tests {
                     ignore_column_ids: vec![1, 2, 3],
                     segment_row_count: 1024,
                 },
+                full_text_index: FullTextIndexOptions::default(),
+                bloom_filter_index: BloomFilterIndexOptions::default(),
+            },
+            append_mode: true,
+            downsample: DownsampleOptions {
+                after: Some(Duration::from_secs(3600 * 24 * 7)),
+                interval: Some(Duration::from_secs(300)),
+                function: DownsampleFunction::Max,
+                field_functions: HashMap::new(),
             },
         };
         assert_eq!(expect, options);
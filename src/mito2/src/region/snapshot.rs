@@ -0,0 +1,237 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Named snapshots and time travel of a region.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use snafu::{ensure, OptionExt};
+use store_api::manifest::ManifestVersion;
+use store_api::storage::{SequenceNumber, TimeTravel};
+
+use crate::error::{ManifestHistoryUnavailableSnafu, Result, SnapshotNotFoundSnafu};
+use crate::manifest::action::{RegionSnapshot, RegionSnapshotFiles};
+use crate::region::version::{VersionBuilder, VersionRef};
+use crate::region::MitoRegion;
+use crate::sst::file::{FileHandle, FileId, FileMeta};
+use crate::sst::file_purger::FilePurgerRef;
+use crate::sst::version::SstVersion;
+
+/// A [RegionSnapshot] with handles to its SST files.
+///
+/// Holding the handles prevents the purger from deleting these files
+/// even if they are removed from the latest version.
+#[derive(Debug)]
+pub(crate) struct PinnedSnapshot {
+    pub(crate) snapshot: RegionSnapshot,
+    pub(crate) files: Vec<FileHandle>,
+    /// Last sequence of flushed data at the snapshot.
+    pub(crate) flushed_sequence: SequenceNumber,
+}
+
+pub(crate) type PinnedSnapshotRef = Arc<PinnedSnapshot>;
+
+/// Named snapshots of a region.
+#[derive(Debug, Default)]
+pub(crate) struct RegionSnapshots {
+    snapshots: RwLock<HashMap<String, PinnedSnapshotRef>>,
+}
+
+impl RegionSnapshots {
+    /// Returns the snapshot named `name`.
+    pub(crate) fn get(&self, name: &str) -> Option<PinnedSnapshotRef> {
+        let snapshots = self.snapshots.read().unwrap();
+        snapshots.get(name).cloned()
+    }
+
+    /// Returns true if the snapshot named `name` exists.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        let snapshots = self.snapshots.read().unwrap();
+        snapshots.contains_key(name)
+    }
+
+    /// Lists all snapshots ordered by their manifest versions.
+    pub(crate) fn list(&self) -> Vec<RegionSnapshot> {
+        let snapshots = self.snapshots.read().unwrap();
+        let mut snapshots = snapshots
+            .values()
+            .map(|pinned| pinned.snapshot.clone())
+            .collect::<Vec<_>>();
+        snapshots.sort_unstable_by(|a, b| {
+            (a.manifest_version, &a.name).cmp(&(b.manifest_version, &b.name))
+        });
+        snapshots
+    }
+
+    /// Pins `files` of the `snapshot` and adds it to the snapshot set.
+    ///
+    /// It reuses handles from `ssts` and other snapshots so a file is only purged
+    /// after all of them release it. Files not referenced by any of them are no
+    /// longer in use so their handles are marked as deleted.
+    pub(crate) fn pin(
+        &self,
+        snapshot: RegionSnapshot,
+        files: RegionSnapshotFiles,
+        ssts: &SstVersion,
+        file_purger: &FilePurgerRef,
+    ) {
+        let mut snapshots = self.snapshots.write().unwrap();
+        let handles = files
+            .files
+            .into_iter()
+            .map(|file| {
+                if let Some(handle) = ssts.get_file(&file) {
+                    return handle.clone();
+                }
+                if let Some(handle) = find_file(&snapshots, file.file_id) {
+                    return handle;
+                }
+                let handle = FileHandle::new(file, file_purger.clone());
+                handle.mark_deleted();
+                handle
+            })
+            .collect();
+
+        snapshots.insert(
+            snapshot.name.clone(),
+            Arc::new(PinnedSnapshot {
+                snapshot,
+                files: handles,
+                flushed_sequence: files.flushed_sequence,
+            }),
+        );
+    }
+
+    /// Removes the snapshot named `name`.
+    ///
+    /// Files only referenced by this snapshot are purged once the returned snapshot is dropped.
+    pub(crate) fn remove(&self, name: &str) -> Option<PinnedSnapshotRef> {
+        let mut snapshots = self.snapshots.write().unwrap();
+        snapshots.remove(name)
+    }
+
+    /// Returns metas of all files pinned by snapshots.
+    pub(crate) fn file_metas(&self) -> Vec<FileMeta> {
+        let snapshots = self.snapshots.read().unwrap();
+        snapshots
+            .values()
+            .flat_map(|pinned| pinned.files.iter().map(|handle| handle.meta()))
+            .collect()
+    }
+
+    /// Returns the handle of the file pinned by snapshots.
+    pub(crate) fn get_file(&self, file_id: FileId) -> Option<FileHandle> {
+        let snapshots = self.snapshots.read().unwrap();
+        find_file(&snapshots, file_id)
+    }
+}
+
+fn find_file(
+    snapshots: &HashMap<String, PinnedSnapshotRef>,
+    file_id: FileId,
+) -> Option<FileHandle> {
+    snapshots
+        .values()
+        .flat_map(|pinned| pinned.files.iter())
+        .find(|handle| handle.file_id() == file_id)
+        .cloned()
+}
+
+impl MitoRegion {
+    /// Returns a [Version](crate::region::version::Version) that reads the region
+    /// as of `time_travel`.
+    ///
+    /// The version only contains SST files so data in memtables are invisible.
+    pub(crate) async fn version_as_of(&self, time_travel: &TimeTravel) -> Result<VersionRef> {
+        let region_id = self.region_id;
+        let current = self.version();
+
+        let (files, flushed_sequence) = match time_travel {
+            TimeTravel::Snapshot(name) => {
+                let pinned = self
+                    .snapshots
+                    .get(name)
+                    .context(SnapshotNotFoundSnafu { region_id, name })?;
+                (pinned.files.clone(), pinned.flushed_sequence)
+            }
+            TimeTravel::Version(version) => {
+                let manifest = self.manifest_manager.manifest_at(*version).await?;
+                let files = self
+                    .resolve_files(&current, manifest.files.into_values(), *version)
+                    .await?;
+                (files, manifest.flushed_sequence)
+            }
+            TimeTravel::Timestamp(timestamp) => {
+                let version = self.manifest_manager.version_at(*timestamp).await?;
+                let manifest = self.manifest_manager.manifest_at(version).await?;
+                let files = self
+                    .resolve_files(&current, manifest.files.into_values(), version)
+                    .await?;
+                (files, manifest.flushed_sequence)
+            }
+        };
+
+        let mut ssts = SstVersion::new();
+        ssts.add_file_handles(files.into_iter());
+        // Reads with the latest metadata, SSTs written in older schemas are
+        // adapted by the reader.
+        let mutable = self
+            .memtable_builder
+            .build(current.memtables.next_memtable_id(), &current.metadata);
+        let version = VersionBuilder::new(current.metadata.clone(), mutable)
+            .ssts(ssts)
+            .flushed_sequence(flushed_sequence)
+            .options(current.options.clone())
+            .build();
+
+        Ok(Arc::new(version))
+    }
+
+    /// Returns handles of `files` at manifest `version`.
+    async fn resolve_files(
+        &self,
+        current: &VersionRef,
+        files: impl Iterator<Item = FileMeta>,
+        version: ManifestVersion,
+    ) -> Result<Vec<FileHandle>> {
+        let mut handles = Vec::new();
+        for file in files {
+            if let Some(handle) = current.ssts.get_file(&file) {
+                handles.push(handle.clone());
+                continue;
+            }
+            if let Some(handle) = self.snapshots.get_file(file.file_id) {
+                handles.push(handle);
+                continue;
+            }
+
+            // The file is removed from the latest version and isn't pinned by any
+            // snapshot, so it might be purged.
+            ensure!(
+                self.access_layer.is_exist(&file).await?,
+                ManifestHistoryUnavailableSnafu {
+                    region_id: self.region_id,
+                    reason: format!(
+                        "file {} of version {} has been purged",
+                        file.file_id, version
+                    ),
+                }
+            );
+            handles.push(FileHandle::new(file, self.file_purger.clone()));
+        }
+
+        Ok(handles)
+    }
+}
@@ -275,6 +275,12 @@ impl VersionBuilder {
         self
     }
 
+    /// Sets SSTs.
+    pub(crate) fn ssts(mut self, ssts: SstVersion) -> Self {
+        self.ssts = Arc::new(ssts);
+        self
+    }
+
     /// Sets metadata.
     pub(crate) fn metadata(mut self, metadata: RegionMetadataRef) -> Self {
         self.metadata = metadata;
@@ -22,9 +22,11 @@ use api::helper::{
     is_column_type_value_eq, is_semantic_type_eq, proto_value_type, to_proto_value,
     ColumnDataTypeWrapper,
 };
+use api::v1::value::ValueData;
 use api::v1::{ColumnDataType, ColumnSchema, OpType, Rows, SemanticType, Value};
 use common_telemetry::{info, warn};
 use datatypes::prelude::DataType;
+use datatypes::types::validate_jsonb;
 use prometheus::HistogramTimer;
 use prost::Message;
 use smallvec::SmallVec;
@@ -39,11 +41,12 @@ use store_api::region_request::{
 use store_api::storage::{RegionId, SequenceNumber};
 use tokio::sync::oneshot::{self, Receiver, Sender};
 
+use crate::access_layer::WritingFilesGuard;
 use crate::error::{
     CompactRegionSnafu, ConvertColumnDataTypeSnafu, CreateDefaultSnafu, Error, FillDefaultSnafu,
     FlushRegionSnafu, InvalidRequestSnafu, Result,
 };
-use crate::manifest::action::RegionEdit;
+use crate::manifest::action::{RegionEdit, RegionSnapshot};
 use crate::memtable::MemtableId;
 use crate::metrics::COMPACTION_ELAPSED_TOTAL;
 use crate::sst::file::FileMeta;
@@ -172,6 +175,10 @@ impl WriteRequest {
                     }
                 );
 
+                if column.column_schema.data_type.is_json() {
+                    self.check_json_values(column)?;
+                }
+
                 // Check semantic type.
                 ensure!(
                     is_semantic_type_eq(input_col.semantic_type, column.semantic_type),
@@ -226,6 +233,27 @@ impl WriteRequest {
         Ok(())
     }
 
+    /// Checks values of the JSON column are in the JSONB format.
+    fn check_json_values(&self, column: &ColumnMetadata) -> Result<()> {
+        // Safety: the caller ensures this column exists.
+        let index = self.name_to_index[&column.column_schema.name];
+        for row in &self.rows.rows {
+            if let Some(ValueData::BinaryValue(bytes)) = &row.values[index].value_data {
+                validate_jsonb(bytes).map_err(|e| {
+                    InvalidRequestSnafu {
+                        region_id: self.region_id,
+                        reason: format!(
+                            "column {} has invalid JSON value: {}",
+                            column.column_schema.name, e
+                        ),
+                    }
+                    .build()
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     /// Tries to fill missing columns.
     ///
     /// Currently, our protobuf format might be inefficient when we need to fill lots of null
@@ -498,6 +526,27 @@ pub(crate) enum WorkerRequest {
         edit: RegionEdit,
         tx: Sender<Result<()>>,
     },
+
+    /// Creates a named snapshot of a region.
+    CreateSnapshot {
+        region_id: RegionId,
+        name: String,
+        tx: Sender<Result<RegionSnapshot>>,
+    },
+
+    /// Drops a named snapshot of a region.
+    DropSnapshot {
+        region_id: RegionId,
+        name: String,
+        tx: Sender<Result<()>>,
+    },
+
+    /// Schedules a job to build indexes for SSTs of a region.
+    BuildIndex {
+        region_id: RegionId,
+        rebuild_all: bool,
+        tx: Sender<Result<()>>,
+    },
 }
 
 impl WorkerRequest {
@@ -620,6 +669,8 @@ pub(crate) enum BackgroundNotify {
     CompactionFinished(CompactionFinished),
     /// Compaction has failed.
     CompactionFailed(CompactionFailed),
+    /// Index of a SST is built.
+    IndexBuilt(IndexBuilt),
 }
 
 /// Notifies a flush job is finished.
@@ -639,6 +690,8 @@ pub(crate) struct FlushFinished {
     pub(crate) senders: Vec<OutputTx>,
     /// File purger for cleaning files on failure.
     pub(crate) file_purger: FilePurgerRef,
+    /// Keeps flushed SSTs from orphan file gc until the edit is committed.
+    pub(crate) _writing_files: WritingFilesGuard,
     /// Flush timer.
     pub(crate) _timer: HistogramTimer,
 }
@@ -676,6 +729,15 @@ pub(crate) struct FlushFailed {
     pub(crate) err: Arc<Error>,
 }
 
+/// Notifies the index build job has built a new index for a SST.
+#[derive(Debug)]
+pub(crate) struct IndexBuilt {
+    /// Meta of the SST with the new index.
+    pub(crate) file_meta: FileMeta,
+    /// Sender of whether the new index is committed.
+    pub(crate) sender: Sender<Result<bool>>,
+}
+
 /// Notifies a compaction job has finished.
 #[derive(Debug)]
 pub(crate) struct CompactionFinished {
@@ -693,6 +755,8 @@ pub(crate) struct CompactionFinished {
     pub(crate) compaction_time_window: Option<Duration>,
     /// Start time of compaction task.
     pub(crate) start_time: Instant,
+    /// Keeps compaction outputs from orphan file gc until the edit is committed.
+    pub(crate) _writing_files: WritingFilesGuard,
 }
 
 impl CompactionFinished {
@@ -739,7 +803,6 @@ pub(crate) struct CompactionFailed {
 
 #[cfg(test)]
 mod tests {
-    use api::v1::value::ValueData;
     use api::v1::{Row, SemanticType};
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::ColumnDefaultConstraint;
@@ -887,6 +950,66 @@ mod tests {
         check_invalid_request(&err, "column ts expect type Timestamp(Millisecond(TimestampMillisecondType)), given: INT64(4)");
     }
 
+    #[test]
+    fn test_json_column() {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1, 1));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: datatypes::schema::ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: datatypes::schema::ColumnSchema::new(
+                    "j",
+                    ConcreteDataType::json_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 2,
+            });
+        let metadata = builder.build().unwrap();
+
+        let new_rows = |value: Vec<u8>| Rows {
+            schema: vec![
+                new_column_schema(
+                    "ts",
+                    ColumnDataType::TimestampMillisecond,
+                    SemanticType::Timestamp,
+                ),
+                new_column_schema("j", ColumnDataType::Binary, SemanticType::Field),
+            ],
+            rows: vec![Row {
+                values: vec![
+                    ts_ms_value(1),
+                    Value {
+                        value_data: Some(ValueData::BinaryValue(value)),
+                    },
+                ],
+            }],
+        };
+
+        let jsonb = datatypes::types::parse_string_to_jsonb(r#"{"a":1}"#).unwrap();
+        let request = WriteRequest::new(RegionId::new(1, 1), OpType::Put, new_rows(jsonb)).unwrap();
+        request.check_schema(&metadata).unwrap();
+
+        let request = WriteRequest::new(
+            RegionId::new(1, 1),
+            OpType::Put,
+            new_rows(b"not jsonb".to_vec()),
+        )
+        .unwrap();
+        let err = request.check_schema(&metadata).unwrap_err();
+        assert!(
+            err.to_string().contains("column j has invalid JSON value"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_semantic_type() {
         let rows = Rows {
@@ -78,7 +78,8 @@ impl SortField {
             ConcreteDataType::Decimal128(_) => 19,
             ConcreteDataType::Null(_)
             | ConcreteDataType::List(_)
-            | ConcreteDataType::Dictionary(_) => 0,
+            | ConcreteDataType::Dictionary(_)
+            | ConcreteDataType::Json(_) => 0,
         }
     }
 }
@@ -118,6 +119,7 @@ impl SortField {
                     }
                     ConcreteDataType::List(_) |
                     ConcreteDataType::Dictionary(_) |
+                    ConcreteDataType::Json(_) |
                     ConcreteDataType::Null(_) => {
                         return error::NotSupportedFieldSnafu {
                             data_type: $self.data_type.clone()
@@ -191,6 +193,10 @@ impl SortField {
                         data_type: ConcreteDataType::Null(n.clone()),
                     }
                     .fail(),
+                    ConcreteDataType::Json(j) => NotSupportedFieldSnafu {
+                        data_type: ConcreteDataType::Json(j.clone()),
+                    }
+                    .fail(),
                 }
             };
         }
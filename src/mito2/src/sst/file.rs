@@ -17,7 +17,8 @@
 use std::fmt;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use common_time::Timestamp;
 use serde::{Deserialize, Serialize};
@@ -100,6 +101,14 @@ pub struct FileMeta {
     pub available_indexes: SmallVec<[IndexType; 4]>,
     /// Size of the index file.
     pub index_file_size: u64,
+    /// Id of the index file if it differs from the file id.
+    ///
+    /// Indexes rebuilt for an existing file are written to a new index file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_file_id: Option<FileId>,
+    /// Interval of rows in the file if it is downsampled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downsample_interval: Option<Duration>,
 }
 
 /// Type of index.
@@ -107,12 +116,35 @@ pub struct FileMeta {
 pub enum IndexType {
     /// Inverted index.
     InvertedIndex,
+    /// Full-text index.
+    FullTextIndex,
+    /// Bloom filter index.
+    BloomFilterIndex,
 }
 
 impl FileMeta {
     pub fn inverted_index_available(&self) -> bool {
         self.available_indexes.contains(&IndexType::InvertedIndex)
     }
+
+    pub fn full_text_index_available(&self) -> bool {
+        self.available_indexes.contains(&IndexType::FullTextIndex)
+    }
+
+    pub fn bloom_filter_index_available(&self) -> bool {
+        self.available_indexes
+            .contains(&IndexType::BloomFilterIndex)
+    }
+
+    /// Returns true if the file has an index file.
+    pub fn index_file_available(&self) -> bool {
+        !self.available_indexes.is_empty()
+    }
+
+    /// Returns the id of the index file.
+    pub fn index_id(&self) -> FileId {
+        self.index_file_id.unwrap_or(self.file_id)
+    }
 }
 
 /// Handle to a SST file.
@@ -129,8 +161,8 @@ impl fmt::Debug for FileHandle {
             .field("time_range", &self.inner.meta.time_range)
             .field("size", &self.inner.meta.file_size)
             .field("level", &self.inner.meta.level)
-            .field("compacting", &self.inner.compacting)
-            .field("deleted", &self.inner.deleted)
+            .field("compacting", &self.inner.state.compacting)
+            .field("deleted", &self.inner.state.deleted)
             .finish()
     }
 }
@@ -162,38 +194,91 @@ impl FileHandle {
         self.inner.meta.time_range
     }
 
+    /// Returns the level of the file.
+    pub fn level(&self) -> Level {
+        self.inner.meta.level
+    }
+
+    /// Returns the size of the file.
+    pub fn size(&self) -> u64 {
+        self.inner.meta.file_size
+    }
+
+    /// Returns the interval of rows if the file is downsampled.
+    pub fn downsample_interval(&self) -> Option<Duration> {
+        self.inner.meta.downsample_interval
+    }
+
     /// Mark the file as deleted and will delete it on drop asynchronously
     pub fn mark_deleted(&self) {
-        self.inner.deleted.store(true, Ordering::Relaxed);
+        let state = &self.inner.state;
+        if !state.deleted.swap(true, Ordering::Relaxed) {
+            state.file_purger.add_pending_file(self.file_id());
+        }
     }
 
     pub fn compacting(&self) -> bool {
-        self.inner.compacting.load(Ordering::Relaxed)
+        self.inner.state.compacting.load(Ordering::Relaxed)
     }
 
     pub fn set_compacting(&self, compacting: bool) {
-        self.inner.compacting.store(compacting, Ordering::Relaxed);
+        self.inner
+            .state
+            .compacting
+            .store(compacting, Ordering::Relaxed);
     }
 
     pub fn meta(&self) -> FileMeta {
         self.inner.meta.clone()
     }
+
+    /// Returns the id of the index file.
+    pub fn index_id(&self) -> FileId {
+        self.inner.meta.index_id()
+    }
+
+    /// Returns a new handle of the file with the index described by `meta`.
+    ///
+    /// The new handle shares the state of the file with this handle so the file
+    /// is only purged after all of them are dropped. The old index is purged after
+    /// this handle and all its clones are dropped, which allows readers holding this
+    /// handle (e.g. snapshots) to keep reading the old index.
+    ///
+    /// The caller should commit `meta` to the manifest before creating the handle.
+    pub(crate) fn with_index(&self, meta: FileMeta) -> FileHandle {
+        debug_assert_eq!(self.file_id(), meta.file_id);
+        let state = &self.inner.state;
+        if self.inner.meta.index_file_available()
+            && !self.inner.index_replaced.swap(true, Ordering::Relaxed)
+        {
+            state.file_purger.add_pending_file(self.index_id());
+        }
+        *state.latest_meta.lock().unwrap() = meta.clone();
+
+        FileHandle {
+            inner: Arc::new(FileHandleInner {
+                meta,
+                state: state.clone(),
+                index_replaced: AtomicBool::new(false),
+            }),
+        }
+    }
 }
 
 /// Inner data of [FileHandle].
 ///
-/// Contains meta of the file, and other mutable info like whether the file is compacting.
+/// Contains meta of the file, and the state shared by all handles of the file.
 struct FileHandleInner {
     meta: FileMeta,
-    compacting: AtomicBool,
-    deleted: AtomicBool,
-    file_purger: FilePurgerRef,
+    state: Arc<FileState>,
+    /// Whether the index in the meta is replaced by another handle.
+    index_replaced: AtomicBool,
 }
 
 impl Drop for FileHandleInner {
     fn drop(&mut self) {
-        if self.deleted.load(Ordering::Relaxed) {
-            self.file_purger.send_request(PurgeRequest {
+        if self.index_replaced.load(Ordering::Relaxed) {
+            self.state.file_purger.purge_index(PurgeRequest {
                 file_meta: self.meta.clone(),
             });
         }
@@ -203,10 +288,33 @@ impl Drop for FileHandleInner {
 impl FileHandleInner {
     fn new(meta: FileMeta, file_purger: FilePurgerRef) -> FileHandleInner {
         FileHandleInner {
+            state: Arc::new(FileState {
+                latest_meta: Mutex::new(meta.clone()),
+                compacting: AtomicBool::new(false),
+                deleted: AtomicBool::new(false),
+                file_purger,
+            }),
             meta,
-            compacting: AtomicBool::new(false),
-            deleted: AtomicBool::new(false),
-            file_purger,
+            index_replaced: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Mutable info of a file shared by its handles, like whether the file is compacting.
+struct FileState {
+    /// Meta with the latest index of the file.
+    latest_meta: Mutex<FileMeta>,
+    compacting: AtomicBool,
+    deleted: AtomicBool,
+    file_purger: FilePurgerRef,
+}
+
+impl Drop for FileState {
+    fn drop(&mut self) {
+        if self.deleted.load(Ordering::Relaxed) {
+            self.file_purger.send_request(PurgeRequest {
+                file_meta: self.latest_meta.lock().unwrap().clone(),
+            });
         }
     }
 }
@@ -255,6 +363,8 @@ mod tests {
             file_size: 0,
             available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
             index_file_size: 0,
+            index_file_id: None,
+            downsample_interval: None,
         }
     }
 
@@ -278,4 +388,31 @@ mod tests {
         let deserialized_file_meta: FileMeta = serde_json::from_str(json_file_meta).unwrap();
         assert_eq!(file_meta, deserialized_file_meta);
     }
+
+    #[test]
+    fn test_file_handle_with_index() {
+        let file_id = FileId::random();
+        let handle = FileHandle::new(
+            create_file_meta(file_id, 0),
+            crate::test_util::new_noop_file_purger(),
+        );
+        assert_eq!(file_id, handle.index_id());
+
+        let mut meta = handle.meta();
+        meta.index_file_id = Some(FileId::random());
+        meta.available_indexes.push(IndexType::BloomFilterIndex);
+        meta.index_file_size = 1024;
+        let new_handle = handle.with_index(meta.clone());
+        assert_eq!(meta, new_handle.meta());
+        assert_eq!(meta.index_file_id.unwrap(), new_handle.index_id());
+        // The old handle keeps its own index.
+        assert_eq!(file_id, handle.index_id());
+        // Handles share the state of the file.
+        handle.set_compacting(true);
+        assert!(new_handle.compacting());
+
+        let serialized = serde_json::to_string(&meta).unwrap();
+        let deserialized: FileMeta = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(meta, deserialized);
+    }
 }
@@ -12,15 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use common_telemetry::{error, info};
 
 use crate::access_layer::AccessLayerRef;
+use crate::cache::file_cache::{FileType, IndexKey};
 use crate::cache::CacheManagerRef;
 use crate::schedule::scheduler::SchedulerRef;
-use crate::sst::file::FileMeta;
+use crate::sst::file::{FileId, FileMeta};
 
 /// Request to remove a file.
 #[derive(Debug)]
@@ -33,6 +35,24 @@ pub struct PurgeRequest {
 pub trait FilePurger: Send + Sync + fmt::Debug {
     /// Send a purge request to the background worker.
     fn send_request(&self, request: PurgeRequest);
+
+    /// Send a request to purge the index file of the request's file meta.
+    ///
+    /// The index is replaced by a new index of the file and is no longer in use.
+    fn purge_index(&self, _request: PurgeRequest) {}
+
+    /// Notifies the purger that the file is marked as deleted and will be purged
+    /// once all its handles are dropped.
+    ///
+    /// The file can also be an index file replaced by a new index.
+    fn add_pending_file(&self, _file_id: FileId) {}
+
+    /// Returns ids of files and index files that are marked as deleted but not purged yet.
+    ///
+    /// These files may still be read by ongoing queries.
+    fn pending_files(&self) -> Vec<FileId> {
+        Vec::new()
+    }
 }
 
 pub type FilePurgerRef = Arc<dyn FilePurger>;
@@ -42,6 +62,8 @@ pub struct LocalFilePurger {
     scheduler: SchedulerRef,
     sst_layer: AccessLayerRef,
     cache_manager: Option<CacheManagerRef>,
+    /// Files marked as deleted but not purged yet.
+    pending_files: Arc<Mutex<HashSet<FileId>>>,
 }
 
 impl fmt::Debug for LocalFilePurger {
@@ -63,6 +85,7 @@ impl LocalFilePurger {
             scheduler,
             sst_layer,
             cache_manager,
+            pending_files: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 }
@@ -70,12 +93,19 @@ impl LocalFilePurger {
 impl FilePurger for LocalFilePurger {
     fn send_request(&self, request: PurgeRequest) {
         let file_meta = request.file_meta;
+        let file_id = file_meta.file_id;
         let sst_layer = self.sst_layer.clone();
+        let pending_files = self.pending_files.clone();
 
         // Remove meta of the file from cache.
         if let Some(cache) = &self.cache_manager {
             cache.remove_parquet_meta_data(file_meta.region_id, file_meta.file_id);
         }
+        let write_cache = self
+            .cache_manager
+            .as_ref()
+            .and_then(|cache| cache.write_cache())
+            .cloned();
 
         if let Err(e) = self.scheduler.schedule(Box::pin(async move {
             if let Err(e) = sst_layer.delete_sst(&file_meta).await {
@@ -87,10 +117,82 @@ impl FilePurger for LocalFilePurger {
                     file_meta.file_id, file_meta.region_id
                 );
             }
+            pending_files.lock().unwrap().remove(&file_meta.file_id);
+
+            // Remove the file from the local cache.
+            if let Some(write_cache) = write_cache {
+                let file_cache = write_cache.file_cache();
+                file_cache
+                    .remove(IndexKey::new(
+                        file_meta.region_id,
+                        file_id,
+                        FileType::Parquet,
+                    ))
+                    .await;
+                if file_meta.index_file_available() {
+                    file_cache
+                        .remove(IndexKey::new(
+                            file_meta.region_id,
+                            file_meta.index_id(),
+                            FileType::Puffin,
+                        ))
+                        .await;
+                }
+            }
         })) {
             error!(e; "Failed to schedule the file purge request");
+            // Leaves the file to the orphan file gc.
+            self.pending_files.lock().unwrap().remove(&file_id);
+        }
+    }
+
+    fn purge_index(&self, request: PurgeRequest) {
+        let file_meta = request.file_meta;
+        let index_id = file_meta.index_id();
+        let sst_layer = self.sst_layer.clone();
+        let pending_files = self.pending_files.clone();
+        let write_cache = self
+            .cache_manager
+            .as_ref()
+            .and_then(|cache| cache.write_cache())
+            .cloned();
+
+        if let Err(e) = self.scheduler.schedule(Box::pin(async move {
+            if let Err(e) = sst_layer.delete_index(&file_meta).await {
+                error!(e; "Failed to delete index file, file_id: {}, index_id: {}, region: {}",
+                    file_meta.file_id, index_id, file_meta.region_id);
+            } else {
+                info!(
+                    "Successfully deleted index file, file_id: {}, index_id: {}, region: {}",
+                    file_meta.file_id, index_id, file_meta.region_id
+                );
+            }
+            pending_files.lock().unwrap().remove(&index_id);
+
+            if let Some(write_cache) = write_cache {
+                write_cache
+                    .file_cache()
+                    .remove(IndexKey::new(
+                        file_meta.region_id,
+                        index_id,
+                        FileType::Puffin,
+                    ))
+                    .await;
+            }
+        })) {
+            error!(e; "Failed to schedule the index purge request");
+            // Leaves the index to the orphan file gc.
+            self.pending_files.lock().unwrap().remove(&index_id);
         }
     }
+
+    fn add_pending_file(&self, file_id: FileId) {
+        self.pending_files.lock().unwrap().insert(file_id);
+    }
+
+    fn pending_files(&self) -> Vec<FileId> {
+        self.pending_files.lock().unwrap().iter().copied().collect()
+    }
 }
 
 #[cfg(test)]
@@ -141,6 +243,8 @@ mod tests {
                     file_size: 4096,
                     available_indexes: Default::default(),
                     index_file_size: 0,
+                    index_file_id: None,
+                    downsample_interval: None,
                 },
                 file_purger,
             );
@@ -192,6 +296,8 @@ mod tests {
                     file_size: 4096,
                     available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
                     index_file_size: 4096,
+                    index_file_id: None,
+                    downsample_interval: None,
                 },
                 file_purger,
             );
@@ -204,4 +310,67 @@ mod tests {
         assert!(!object_store.is_exist(&path).await.unwrap());
         assert!(!object_store.is_exist(&index_path).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_purge_replaced_index() {
+        common_telemetry::init_default_ut_logging();
+
+        let dir = create_temp_dir("file-purge");
+        let dir_path = dir.path().display().to_string();
+        let mut builder = Fs::default();
+        builder.root(&dir_path);
+        let sst_file_id = FileId::random();
+        let sst_dir = "table1";
+        let intm_mgr = IntermediateManager::init_fs(join_dir(&dir_path, "intm"))
+            .await
+            .unwrap();
+        let path = location::sst_file_path(sst_dir, sst_file_id);
+
+        let object_store = ObjectStore::new(builder).unwrap().finish();
+        object_store.write(&path, vec![0; 4096]).await.unwrap();
+        let index_path = location::index_file_path(sst_dir, sst_file_id);
+        object_store
+            .write(&index_path, vec![0; 4096])
+            .await
+            .unwrap();
+        let new_index_id = FileId::random();
+        let new_index_path = location::index_file_path(sst_dir, new_index_id);
+        object_store
+            .write(&new_index_path, vec![0; 4096])
+            .await
+            .unwrap();
+
+        let scheduler = Arc::new(LocalScheduler::new(3));
+        let layer = Arc::new(AccessLayer::new(sst_dir, object_store.clone(), intm_mgr));
+
+        let file_purger = Arc::new(LocalFilePurger::new(scheduler.clone(), layer, None));
+
+        let meta = FileMeta {
+            region_id: 0.into(),
+            file_id: sst_file_id,
+            time_range: FileTimeRange::default(),
+            level: 0,
+            file_size: 4096,
+            available_indexes: SmallVec::from_iter([IndexType::InvertedIndex]),
+            index_file_size: 4096,
+            index_file_id: None,
+            downsample_interval: None,
+        };
+        let handle = FileHandle::new(meta.clone(), file_purger.clone());
+        let new_handle = handle.with_index(FileMeta {
+            index_file_id: Some(new_index_id),
+            ..meta
+        });
+        assert_eq!(vec![sst_file_id], file_purger.pending_files());
+        // Drops the old handle, we expect only the old index file is deleted.
+        drop(handle);
+        drop(new_handle);
+
+        scheduler.stop(true).await.unwrap();
+
+        assert!(object_store.is_exist(&path).await.unwrap());
+        assert!(!object_store.is_exist(&index_path).await.unwrap());
+        assert!(object_store.is_exist(&new_index_path).await.unwrap());
+        assert!(file_purger.pending_files().is_empty());
+    }
 }
@@ -20,19 +20,36 @@ mod store;
 
 use std::num::NonZeroUsize;
 
+use api::v1::SemanticType;
 use common_telemetry::{debug, warn};
 use creator::SstIndexCreator;
+use datatypes::data_type::ConcreteDataType;
 use object_store::ObjectStore;
+use puffin::blob_metadata::CompressionCodec;
+use smallvec::SmallVec;
 use store_api::metadata::RegionMetadataRef;
-use store_api::storage::RegionId;
+use store_api::storage::{ColumnId, RegionId};
 
 use crate::metrics::INDEX_CREATE_MEMORY_USAGE;
 use crate::read::Batch;
 use crate::region::options::IndexOptions;
-use crate::sst::file::FileId;
+use crate::sst::file::{FileId, IndexType};
 use crate::sst::index::intermediate::IntermediateManager;
 
 const INDEX_BLOB_TYPE: &str = "greptime-inverted-index-v1";
+const FULL_TEXT_INDEX_BLOB_TYPE: &str = "greptime-full-text-index-v1";
+const BLOOM_FILTER_INDEX_BLOB_TYPE: &str = "greptime-bloom-filter-index-v1";
+/// Blob property of the column id a full-text or bloom filter index blob is built for.
+const INDEX_COLUMN_ID_PROPERTY: &str = "column_id";
+
+/// Output of the index creation.
+#[derive(Debug, Default)]
+pub struct IndexOutput {
+    /// Size of the index file in bytes.
+    pub file_size: usize,
+    /// Indexes written to the index file.
+    pub available_indexes: SmallVec<[IndexType; 4]>,
+}
 
 /// The index creator that hides the error handling details.
 #[derive(Default)]
@@ -44,6 +61,14 @@ pub struct Indexer {
 }
 
 impl Indexer {
+    /// Returns the indexes to create, or empty if no index will be created.
+    pub fn index_types(&self) -> SmallVec<[IndexType; 4]> {
+        self.inner
+            .as_ref()
+            .map(|creator| creator.index_types())
+            .unwrap_or_default()
+    }
+
     /// Update the index with the given batch.
     pub async fn update(&mut self, batch: &Batch) {
         if let Some(creator) = self.inner.as_mut() {
@@ -76,8 +101,8 @@ impl Indexer {
     }
 
     /// Finish the index creation.
-    /// Returns the number of bytes written if success or None if failed.
-    pub async fn finish(&mut self) -> Option<usize> {
+    /// Returns the size of the index file and the indexes written, or an empty output if failed.
+    pub async fn finish(&mut self) -> IndexOutput {
         if let Some(mut creator) = self.inner.take() {
             match creator.finish().await {
                 Ok((row_count, byte_count)) => {
@@ -88,7 +113,10 @@ impl Indexer {
 
                     INDEX_CREATE_MEMORY_USAGE.sub(self.last_memory_usage as i64);
                     self.last_memory_usage = 0;
-                    return Some(byte_count);
+                    return IndexOutput {
+                        file_size: byte_count,
+                        available_indexes: creator.index_types(),
+                    };
                 }
                 Err(err) => {
                     if cfg!(any(test, feature = "test")) {
@@ -108,7 +136,7 @@ impl Indexer {
 
         INDEX_CREATE_MEMORY_USAGE.sub(self.last_memory_usage as i64);
         self.last_memory_usage = 0;
-        None
+        IndexOutput::default()
     }
 
     /// Abort the index creation.
@@ -137,6 +165,7 @@ pub(crate) struct IndexerBuilder<'a> {
     pub(crate) create_inverted_index: bool,
     pub(crate) mem_threshold_index_create: Option<usize>,
     pub(crate) write_buffer_size: Option<usize>,
+    pub(crate) compression: Option<CompressionCodec>,
     pub(crate) file_id: FileId,
     pub(crate) file_path: String,
     pub(crate) metadata: &'a RegionMetadataRef,
@@ -158,9 +187,14 @@ impl<'a> IndexerBuilder<'a> {
             return Indexer::default();
         }
 
-        if self.metadata.primary_key.is_empty() {
+        let full_text_column_ids = self.full_text_column_ids();
+        let bloom_filter_columns = self.bloom_filter_columns();
+        if self.metadata.primary_key.is_empty()
+            && full_text_column_ids.is_empty()
+            && bloom_filter_columns.is_empty()
+        {
             debug!(
-                "No tag columns, skip creating index, region_id: {}, file_id: {}",
+                "No columns to index, skip creating index, region_id: {}, file_id: {}",
                 self.metadata.region_id, self.file_id,
             );
             return Indexer::default();
@@ -199,6 +233,7 @@ impl<'a> IndexerBuilder<'a> {
             segment_row_count,
         )
         .with_buffer_size(self.write_buffer_size)
+        .with_compression(self.compression)
         .with_ignore_column_ids(
             self.index_options
                 .inverted_index
@@ -206,7 +241,9 @@ impl<'a> IndexerBuilder<'a> {
                 .iter()
                 .map(|i| i.to_string())
                 .collect(),
-        );
+        )
+        .with_full_text_column_ids(&full_text_column_ids)
+        .with_bloom_filter_columns(bloom_filter_columns);
 
         Indexer {
             file_id: self.file_id,
@@ -215,6 +252,47 @@ impl<'a> IndexerBuilder<'a> {
             last_memory_usage: 0,
         }
     }
+
+    /// Returns ids of string field columns to create the full-text index.
+    fn full_text_column_ids(&self) -> Vec<ColumnId> {
+        self.index_options
+            .full_text_index
+            .column_ids
+            .iter()
+            .copied()
+            .filter(|column_id| {
+                self.metadata
+                    .column_by_id(*column_id)
+                    .is_some_and(|column| {
+                        column.semantic_type == SemanticType::Field
+                            && column.column_schema.data_type.is_string()
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns ids and data types of field columns to create the bloom filter index.
+    fn bloom_filter_columns(&self) -> Vec<(ColumnId, ConcreteDataType)> {
+        self.index_options
+            .bloom_filter_index
+            .column_ids
+            .iter()
+            .filter_map(|column_id| {
+                let column = self.metadata.column_by_id(*column_id)?;
+                let data_type = &column.column_schema.data_type;
+                // Values are encoded in the same way as the inverted index.
+                let supported = !matches!(
+                    data_type,
+                    ConcreteDataType::List(_)
+                        | ConcreteDataType::Dictionary(_)
+                        | ConcreteDataType::Json(_)
+                        | ConcreteDataType::Null(_)
+                );
+                (column.semantic_type == SemanticType::Field && supported)
+                    .then(|| (*column_id, data_type.clone()))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -297,6 +375,7 @@ mod tests {
             create_inverted_index: true,
             mem_threshold_index_create: Some(1024),
             write_buffer_size: None,
+            compression: None,
             file_id: FileId::random(),
             file_path: "test".to_string(),
             metadata: &metadata,
@@ -317,6 +396,7 @@ mod tests {
             create_inverted_index: false,
             mem_threshold_index_create: Some(1024),
             write_buffer_size: None,
+            compression: None,
             file_id: FileId::random(),
             file_path: "test".to_string(),
             metadata: &metadata,
@@ -337,6 +417,7 @@ mod tests {
             create_inverted_index: true,
             mem_threshold_index_create: Some(1024),
             write_buffer_size: None,
+            compression: None,
             file_id: FileId::random(),
             file_path: "test".to_string(),
             metadata: &metadata,
@@ -350,6 +431,93 @@ mod tests {
         assert!(indexer.inner.is_none());
     }
 
+    #[test]
+    fn test_build_indexer_full_text_no_tag() {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1, 2));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new("a", ConcreteDataType::string_datatype(), true),
+                semantic_type: SemanticType::Field,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new("b", ConcreteDataType::float64_datatype(), false),
+                semantic_type: SemanticType::Field,
+                column_id: 2,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "c",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 3,
+            });
+        let metadata = Arc::new(builder.build().unwrap());
+        let build_indexer = |column_ids: Vec<ColumnId>| {
+            let mut index_options = IndexOptions::default();
+            index_options.full_text_index.column_ids = column_ids;
+            IndexerBuilder {
+                create_inverted_index: true,
+                mem_threshold_index_create: Some(1024),
+                write_buffer_size: None,
+                compression: None,
+                file_id: FileId::random(),
+                file_path: "test".to_string(),
+                metadata: &metadata,
+                row_group_size: 1024,
+                object_store: mock_object_store(),
+                intermediate_manager: mock_intm_mgr(),
+                index_options,
+            }
+            .build()
+        };
+
+        let indexer = build_indexer(vec![1]);
+        let creator = indexer.inner.unwrap();
+        assert_eq!(
+            &[IndexType::FullTextIndex],
+            creator.index_types().as_slice()
+        );
+
+        // Only string field columns are indexed.
+        assert!(build_indexer(vec![2, 3]).inner.is_none());
+    }
+
+    #[test]
+    fn test_build_indexer_bloom_filter_no_tag() {
+        let metadata = no_tag_region_metadata();
+        let build_indexer = |column_ids: Vec<ColumnId>| {
+            let mut index_options = IndexOptions::default();
+            index_options.bloom_filter_index.column_ids = column_ids;
+            IndexerBuilder {
+                create_inverted_index: true,
+                mem_threshold_index_create: Some(1024),
+                write_buffer_size: None,
+                compression: None,
+                file_id: FileId::random(),
+                file_path: "test".to_string(),
+                metadata: &metadata,
+                row_group_size: 1024,
+                object_store: mock_object_store(),
+                intermediate_manager: mock_intm_mgr(),
+                index_options,
+            }
+            .build()
+        };
+
+        let indexer = build_indexer(vec![1, 2]);
+        let creator = indexer.inner.unwrap();
+        assert_eq!(
+            &[IndexType::BloomFilterIndex],
+            creator.index_types().as_slice()
+        );
+
+        // Only field columns are indexed.
+        assert!(build_indexer(vec![3]).inner.is_none());
+    }
+
     #[test]
     fn test_build_indexer_zero_row_group() {
         let metadata = mock_region_metadata();
@@ -357,6 +525,7 @@ mod tests {
             create_inverted_index: true,
             mem_threshold_index_create: Some(1024),
             write_buffer_size: None,
+            compression: None,
             file_id: FileId::random(),
             file_path: "test".to_string(),
             metadata: &metadata,
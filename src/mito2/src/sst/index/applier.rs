@@ -16,7 +16,11 @@ pub mod builder;
 
 use std::sync::Arc;
 
+use common_base::BitVec;
 use futures::{AsyncRead, AsyncSeek};
+use index::bloom_filter::format::BloomFilterIndexBlobReader;
+use index::full_text_index::format::FullTextIndexBlobReader;
+use index::full_text_index::tokenizer::Term;
 use index::inverted_index::format::reader::InvertedIndexBlobReader;
 use index::inverted_index::search::index_apply::{
     ApplyOutput, IndexApplier, IndexNotFoundStrategy, SearchContext,
@@ -24,20 +28,23 @@ use index::inverted_index::search::index_apply::{
 use object_store::ObjectStore;
 use puffin::file_format::reader::{PuffinAsyncReader, PuffinFileReader};
 use snafu::{OptionExt, ResultExt};
-use store_api::storage::RegionId;
+use store_api::storage::{ColumnId, RegionId};
 
 use crate::cache::file_cache::{FileCacheRef, FileType, IndexKey};
 use crate::error::{
-    ApplyIndexSnafu, PuffinBlobTypeNotFoundSnafu, PuffinReadBlobSnafu, PuffinReadMetadataSnafu,
-    Result,
+    ApplyBloomFilterIndexSnafu, ApplyFullTextIndexSnafu, ApplyIndexSnafu,
+    PuffinBlobTypeNotFoundSnafu, PuffinReadBlobSnafu, PuffinReadMetadataSnafu, Result,
 };
 use crate::metrics::{
     INDEX_APPLY_ELAPSED, INDEX_APPLY_MEMORY_USAGE, INDEX_PUFFIN_READ_BYTES_TOTAL,
     INDEX_PUFFIN_READ_OP_TOTAL, INDEX_PUFFIN_SEEK_OP_TOTAL,
 };
-use crate::sst::file::FileId;
+use crate::sst::file::{FileId, IndexType};
 use crate::sst::index::store::InstrumentedStore;
-use crate::sst::index::INDEX_BLOB_TYPE;
+use crate::sst::index::{
+    BLOOM_FILTER_INDEX_BLOB_TYPE, FULL_TEXT_INDEX_BLOB_TYPE, INDEX_BLOB_TYPE,
+    INDEX_COLUMN_ID_PROPERTY,
+};
 use crate::sst::location;
 
 /// The [`SstIndexApplier`] is responsible for applying predicates to the provided SST files
@@ -57,7 +64,14 @@ pub(crate) struct SstIndexApplier {
 
     /// Predefined index applier used to apply predicates to index files
     /// and return the relevant row group ids for further scan.
-    index_applier: Box<dyn IndexApplier>,
+    /// `None` if no predicate can be applied to the inverted index.
+    index_applier: Option<Box<dyn IndexApplier>>,
+
+    /// Terms each full-text indexed column must contain.
+    full_text_queries: Vec<(ColumnId, Vec<Term>)>,
+
+    /// Encoded values of `IN` predicates on each bloom filter indexed column.
+    bloom_filter_queries: Vec<(ColumnId, Vec<Vec<Vec<u8>>>)>,
 }
 
 pub(crate) type SstIndexApplierRef = Arc<SstIndexApplier>;
@@ -69,9 +83,11 @@ impl SstIndexApplier {
         region_id: RegionId,
         object_store: ObjectStore,
         file_cache: Option<FileCacheRef>,
-        index_applier: Box<dyn IndexApplier>,
+        index_applier: Option<Box<dyn IndexApplier>>,
     ) -> Self {
-        INDEX_APPLY_MEMORY_USAGE.add(index_applier.memory_usage() as i64);
+        if let Some(index_applier) = &index_applier {
+            INDEX_APPLY_MEMORY_USAGE.add(index_applier.memory_usage() as i64);
+        }
 
         Self {
             region_dir,
@@ -79,36 +95,155 @@ impl SstIndexApplier {
             store: InstrumentedStore::new(object_store),
             file_cache,
             index_applier,
+            full_text_queries: Vec::new(),
+            bloom_filter_queries: Vec::new(),
         }
     }
 
-    /// Applies predicates to the provided SST file id and returns the relevant row group ids
-    pub async fn apply(&self, file_id: FileId) -> Result<ApplyOutput> {
-        let _timer = INDEX_APPLY_ELAPSED.start_timer();
+    /// Sets the terms each full-text indexed column must contain.
+    pub fn with_full_text_queries(mut self, full_text_queries: Vec<(ColumnId, Vec<Term>)>) -> Self {
+        self.full_text_queries = full_text_queries;
+        self
+    }
 
-        let context = SearchContext {
-            // Encountering a non-existing column indicates that it doesn't match predicates.
-            index_not_found_strategy: IndexNotFoundStrategy::ReturnEmpty,
+    /// Sets the encoded values of `IN` predicates on each bloom filter indexed column.
+    pub fn with_bloom_filter_queries(
+        mut self,
+        bloom_filter_queries: Vec<(ColumnId, Vec<Vec<Vec<u8>>>)>,
+    ) -> Self {
+        self.bloom_filter_queries = bloom_filter_queries;
+        self
+    }
+
+    /// Applies predicates to the index file of a SST and returns the relevant row group ids.
+    ///
+    /// `file_id` is the id of the index file, which is the SST file id unless the index is
+    /// rebuilt. Returns `None` if none of the `available_indexes` of the file can be applied.
+    pub async fn apply(
+        &self,
+        file_id: FileId,
+        available_indexes: &[IndexType],
+    ) -> Result<Option<ApplyOutput>> {
+        let applicable = ApplicableIndexes {
+            inverted_index: self.index_applier.is_some()
+                && available_indexes.contains(&IndexType::InvertedIndex),
+            full_text_index: !self.full_text_queries.is_empty()
+                && available_indexes.contains(&IndexType::FullTextIndex),
+            bloom_filter_index: !self.bloom_filter_queries.is_empty()
+                && available_indexes.contains(&IndexType::BloomFilterIndex),
         };
+        if !applicable.any() {
+            return Ok(None);
+        }
+
+        let _timer = INDEX_APPLY_ELAPSED.start_timer();
 
         match self.cached_puffin_reader(file_id).await? {
-            Some(mut puffin_reader) => {
-                let blob_reader = Self::index_blob_reader(&mut puffin_reader).await?;
-                let mut index_reader = InvertedIndexBlobReader::new(blob_reader);
-                self.index_applier
-                    .apply(context, &mut index_reader)
-                    .await
-                    .context(ApplyIndexSnafu)
-            }
+            Some(mut puffin_reader) => self.apply_puffin(&mut puffin_reader, applicable).await,
             None => {
                 let mut puffin_reader = self.remote_puffin_reader(file_id).await?;
-                let blob_reader = Self::index_blob_reader(&mut puffin_reader).await?;
-                let mut index_reader = InvertedIndexBlobReader::new(blob_reader);
-                self.index_applier
-                    .apply(context, &mut index_reader)
-                    .await
-                    .context(ApplyIndexSnafu)
+                self.apply_puffin(&mut puffin_reader, applicable).await
+            }
+        }
+    }
+
+    /// Applies the applicable indexes in the index file and intersects the outputs.
+    async fn apply_puffin(
+        &self,
+        puffin_reader: &mut PuffinFileReader<impl AsyncRead + AsyncSeek + Unpin + Send>,
+        applicable: ApplicableIndexes,
+    ) -> Result<Option<ApplyOutput>> {
+        let mut output = None;
+
+        if let Some(index_applier) = self
+            .index_applier
+            .as_ref()
+            .filter(|_| applicable.inverted_index)
+        {
+            let context = SearchContext {
+                // Encountering a non-existing column indicates that it doesn't match predicates.
+                index_not_found_strategy: IndexNotFoundStrategy::ReturnEmpty,
+            };
+            let blob_reader = Self::index_blob_reader(puffin_reader).await?;
+            let mut index_reader = InvertedIndexBlobReader::new(blob_reader);
+            let inverted_output = index_applier
+                .apply(context, &mut index_reader)
+                .await
+                .context(ApplyIndexSnafu)?;
+            output = Some(inverted_output);
+        }
+
+        if applicable.full_text_index {
+            for (column_id, terms) in &self.full_text_queries {
+                // The column may be indexed after the file is written.
+                let Some(blob_reader) =
+                    Self::column_blob_reader(puffin_reader, FULL_TEXT_INDEX_BLOB_TYPE, *column_id)
+                        .await?
+                else {
+                    continue;
+                };
+                let mut index_reader = FullTextIndexBlobReader::new(blob_reader);
+                let search_output =
+                    index::full_text_index::search::search(&mut index_reader, terms)
+                        .await
+                        .context(ApplyFullTextIndexSnafu)?;
+                output = Some(Self::intersect(
+                    output,
+                    search_output.matched_segment_ids,
+                    search_output.total_row_count,
+                    search_output.segment_row_count,
+                ));
+            }
+        }
+
+        if applicable.bloom_filter_index {
+            for (column_id, predicates) in &self.bloom_filter_queries {
+                // The column may be indexed after the file is written.
+                let Some(blob_reader) = Self::column_blob_reader(
+                    puffin_reader,
+                    BLOOM_FILTER_INDEX_BLOB_TYPE,
+                    *column_id,
+                )
+                .await?
+                else {
+                    continue;
+                };
+                let mut index_reader = BloomFilterIndexBlobReader::new(blob_reader);
+                let search_output =
+                    index::bloom_filter::search::search(&mut index_reader, predicates)
+                        .await
+                        .context(ApplyBloomFilterIndexSnafu)?;
+                output = Some(Self::intersect(
+                    output,
+                    search_output.matched_segment_ids,
+                    search_output.total_row_count,
+                    search_output.segment_row_count,
+                ));
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Helper function to intersect the matched segments of an index with the output of
+    /// indexes applied before.
+    fn intersect(
+        output: Option<ApplyOutput>,
+        mut matched_segment_ids: BitVec,
+        total_row_count: usize,
+        segment_row_count: usize,
+    ) -> ApplyOutput {
+        match output {
+            Some(mut output) => {
+                matched_segment_ids.resize(output.matched_segment_ids.len(), false);
+                output.matched_segment_ids &= matched_segment_ids;
+                output
             }
+            None => ApplyOutput {
+                matched_segment_ids,
+                total_row_count,
+                segment_row_count,
+            },
         }
     }
 
@@ -164,20 +299,65 @@ impl SstIndexApplier {
             .blob_reader(blob_meta)
             .context(PuffinReadBlobSnafu)
     }
+
+    /// Helper function to create a [`PuffinBlobReader`] for the index blob of the column.
+    /// Returns `None` if the column has no index blob of `blob_type`.
+    async fn column_blob_reader<'b>(
+        puffin_reader: &'b mut PuffinFileReader<impl AsyncRead + AsyncSeek + Unpin + Send>,
+        blob_type: &'static str,
+        column_id: ColumnId,
+    ) -> Result<Option<impl AsyncRead + AsyncSeek + 'b>> {
+        let file_meta = puffin_reader
+            .metadata()
+            .await
+            .context(PuffinReadMetadataSnafu)?;
+        let column_id = column_id.to_string();
+        let Some(blob_meta) = file_meta.blobs.iter().find(|blob| {
+            blob.blob_type == blob_type
+                && blob.properties.get(INDEX_COLUMN_ID_PROPERTY) == Some(&column_id)
+        }) else {
+            return Ok(None);
+        };
+        puffin_reader
+            .blob_reader(blob_meta)
+            .context(PuffinReadBlobSnafu)
+            .map(Some)
+    }
+}
+
+/// Indexes of a file that can be applied to the predicates.
+#[derive(Debug, Clone, Copy)]
+struct ApplicableIndexes {
+    inverted_index: bool,
+    full_text_index: bool,
+    bloom_filter_index: bool,
+}
+
+impl ApplicableIndexes {
+    fn any(&self) -> bool {
+        self.inverted_index || self.full_text_index || self.bloom_filter_index
+    }
 }
 
 impl Drop for SstIndexApplier {
     fn drop(&mut self) {
-        INDEX_APPLY_MEMORY_USAGE.sub(self.index_applier.memory_usage() as i64);
+        if let Some(index_applier) = &self.index_applier {
+            INDEX_APPLY_MEMORY_USAGE.sub(index_applier.memory_usage() as i64);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::num::NonZeroUsize;
+
     use common_base::BitVec;
     use futures::io::Cursor;
+    use index::full_text_index::create::FullTextIndexCreator;
     use index::inverted_index::search::index_apply::MockIndexApplier;
     use object_store::services::Memory;
+    use puffin::blob_metadata::CompressionCodec;
     use puffin::file_format::writer::{Blob, PuffinAsyncWriter, PuffinFileWriter};
 
     use super::*;
@@ -196,6 +376,7 @@ mod tests {
                 blob_type: INDEX_BLOB_TYPE.to_string(),
                 data: Cursor::new(vec![]),
                 properties: Default::default(),
+                compression_codec: None,
             })
             .await
             .unwrap();
@@ -216,9 +397,13 @@ mod tests {
             RegionId::new(0, 0),
             object_store,
             None,
-            Box::new(mock_index_applier),
+            Some(Box::new(mock_index_applier)),
         );
-        let output = sst_index_applier.apply(file_id).await.unwrap();
+        let output = sst_index_applier
+            .apply(file_id, &[IndexType::InvertedIndex])
+            .await
+            .unwrap()
+            .unwrap();
         assert_eq!(
             output,
             ApplyOutput {
@@ -242,6 +427,7 @@ mod tests {
                 blob_type: "invalid_blob_type".to_string(),
                 data: Cursor::new(vec![]),
                 properties: Default::default(),
+                compression_codec: None,
             })
             .await
             .unwrap();
@@ -256,9 +442,89 @@ mod tests {
             RegionId::new(0, 0),
             object_store,
             None,
-            Box::new(mock_index_applier),
+            Some(Box::new(mock_index_applier)),
         );
-        let res = sst_index_applier.apply(file_id).await;
+        let res = sst_index_applier
+            .apply(file_id, &[IndexType::InvertedIndex])
+            .await;
         assert!(matches!(res, Err(Error::PuffinBlobTypeNotFound { .. })));
     }
+
+    #[tokio::test]
+    async fn test_index_applier_apply_full_text() {
+        let object_store = ObjectStore::new(Memory::default()).unwrap().finish();
+        let file_id = FileId::random();
+        let region_dir = "region_dir".to_string();
+        let path = location::index_file_path(&region_dir, file_id);
+
+        let mut creator = FullTextIndexCreator::new(NonZeroUsize::new(2).unwrap());
+        for text in ["connection timeout", "ok", "disk full", "request timeout"] {
+            creator.push_text(Some(text));
+        }
+        let mut blob = Cursor::new(vec![]);
+        creator.finish(&mut blob).await.unwrap();
+
+        let mut puffin_writer = PuffinFileWriter::new(object_store.writer(&path).await.unwrap())
+            .with_footer_lz4_compressed(true);
+        puffin_writer
+            .add_blob(Blob {
+                blob_type: FULL_TEXT_INDEX_BLOB_TYPE.to_string(),
+                data: Cursor::new(blob.into_inner()),
+                properties: HashMap::from([(
+                    INDEX_COLUMN_ID_PROPERTY.to_string(),
+                    "3".to_string(),
+                )]),
+                compression_codec: Some(CompressionCodec::Zstd),
+            })
+            .await
+            .unwrap();
+        puffin_writer.finish().await.unwrap();
+
+        let sst_index_applier = |column_id, token: &str| {
+            SstIndexApplier::new(
+                region_dir.clone(),
+                RegionId::new(0, 0),
+                object_store.clone(),
+                None,
+                None,
+            )
+            .with_full_text_queries(vec![(column_id, vec![Term::Token(token.to_string())])])
+        };
+
+        let output = sst_index_applier(3, "timeout")
+            .apply(file_id, &[IndexType::FullTextIndex])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![0, 1],
+            output.matched_segment_ids.iter_ones().collect::<Vec<_>>()
+        );
+        assert_eq!(4, output.total_row_count);
+        assert_eq!(2, output.segment_row_count);
+
+        let output = sst_index_applier(3, "disk")
+            .apply(file_id, &[IndexType::FullTextIndex])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vec![1],
+            output.matched_segment_ids.iter_ones().collect::<Vec<_>>()
+        );
+
+        // No index for the column.
+        let output = sst_index_applier(4, "disk")
+            .apply(file_id, &[IndexType::FullTextIndex])
+            .await
+            .unwrap();
+        assert!(output.is_none());
+
+        // The file has no full-text index.
+        let output = sst_index_applier(3, "disk")
+            .apply(file_id, &[IndexType::InvertedIndex])
+            .await
+            .unwrap();
+        assert!(output.is_none());
+    }
 }
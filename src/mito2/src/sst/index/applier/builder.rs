@@ -13,9 +13,12 @@
 // limitations under the License.
 
 mod between;
+mod bloom_filter;
 mod comparison;
 mod eq_list;
+mod full_text;
 mod in_list;
+mod predicate_expr;
 mod regex_match;
 
 use std::collections::{HashMap, HashSet};
@@ -24,11 +27,13 @@ use api::v1::SemanticType;
 use common_query::logical_plan::Expr;
 use common_telemetry::warn;
 use datafusion_common::ScalarValue;
+use datafusion_expr::expr::ScalarUDF;
 use datafusion_expr::{BinaryExpr, Expr as DfExpr, Operator};
 use datatypes::data_type::ConcreteDataType;
 use datatypes::value::Value;
-use index::inverted_index::search::index_apply::PredicatesIndexApplier;
-use index::inverted_index::search::predicate::Predicate;
+use index::full_text_index::tokenizer::{Term, MATCHES_FUNCTION_NAME};
+use index::inverted_index::search::index_apply::{IndexApplier, PredicatesIndexApplier};
+use index::inverted_index::search::predicate::{Predicate, PredicateExpr};
 use object_store::ObjectStore;
 use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadata;
@@ -59,6 +64,23 @@ pub(crate) struct SstIndexApplierBuilder<'a> {
 
     /// Stores predicates during traversal on the Expr tree.
     output: HashMap<ColumnId, Vec<Predicate>>,
+
+    /// Stores predicate expressions which can't be represented by predicates of
+    /// a single column during traversal on the Expr tree, e.g. `OR` across columns.
+    expr_output: Vec<PredicateExpr>,
+
+    /// Ids of columns with the full-text index.
+    full_text_column_ids: HashSet<ColumnId>,
+
+    /// Stores terms of full-text indexed columns during traversal on the Expr tree.
+    full_text_output: HashMap<ColumnId, Vec<Term>>,
+
+    /// Ids of columns with the bloom filter index.
+    bloom_filter_column_ids: HashSet<ColumnId>,
+
+    /// Stores encoded values of `IN` predicates on bloom filter indexed columns
+    /// during traversal on the Expr tree.
+    bloom_filter_output: HashMap<ColumnId, Vec<Vec<Vec<u8>>>>,
 }
 
 impl<'a> SstIndexApplierBuilder<'a> {
@@ -77,9 +99,29 @@ impl<'a> SstIndexApplierBuilder<'a> {
             metadata,
             ignore_column_ids,
             output: HashMap::default(),
+            expr_output: Vec::default(),
+            full_text_column_ids: HashSet::default(),
+            full_text_output: HashMap::default(),
+            bloom_filter_column_ids: HashSet::default(),
+            bloom_filter_output: HashMap::default(),
         }
     }
 
+    /// Sets ids of columns with the full-text index.
+    pub fn with_full_text_column_ids(mut self, full_text_column_ids: HashSet<ColumnId>) -> Self {
+        self.full_text_column_ids = full_text_column_ids;
+        self
+    }
+
+    /// Sets ids of columns with the bloom filter index.
+    pub fn with_bloom_filter_column_ids(
+        mut self,
+        bloom_filter_column_ids: HashSet<ColumnId>,
+    ) -> Self {
+        self.bloom_filter_column_ids = bloom_filter_column_ids;
+        self
+    }
+
     /// Consumes the builder to construct an [`SstIndexApplier`], optionally returned based on
     /// the expressions provided. If no predicates match, returns `None`.
     pub fn build(mut self, exprs: &[Expr]) -> Result<Option<SstIndexApplier>> {
@@ -87,48 +129,82 @@ impl<'a> SstIndexApplierBuilder<'a> {
             self.traverse_and_collect(expr.df_expr());
         }
 
-        if self.output.is_empty() {
+        if self.output.is_empty()
+            && self.expr_output.is_empty()
+            && self.full_text_output.is_empty()
+            && self.bloom_filter_output.is_empty()
+        {
             return Ok(None);
         }
 
-        let predicates = self
-            .output
-            .into_iter()
-            .map(|(column_id, predicates)| (column_id.to_string(), predicates))
-            .collect();
-        let applier = PredicatesIndexApplier::try_from(predicates);
-        Ok(Some(SstIndexApplier::new(
-            self.region_dir,
-            self.metadata.region_id,
-            self.object_store,
-            self.file_cache,
-            Box::new(applier.context(BuildIndexApplierSnafu)?),
-        )))
+        let index_applier: Option<Box<dyn IndexApplier>> =
+            if self.output.is_empty() && self.expr_output.is_empty() {
+                None
+            } else {
+                let predicates = self
+                    .output
+                    .into_iter()
+                    .map(|(column_id, predicates)| (column_id.to_string(), predicates))
+                    .collect();
+                let applier = PredicatesIndexApplier::try_new(predicates, self.expr_output)
+                    .context(BuildIndexApplierSnafu)?;
+                Some(Box::new(applier) as _)
+            };
+        Ok(Some(
+            SstIndexApplier::new(
+                self.region_dir,
+                self.metadata.region_id,
+                self.object_store,
+                self.file_cache,
+                index_applier,
+            )
+            .with_full_text_queries(self.full_text_output.into_iter().collect())
+            .with_bloom_filter_queries(self.bloom_filter_output.into_iter().collect()),
+        ))
     }
 
     /// Recursively traverses expressions to collect predicates.
-    /// Results are stored in `self.output`.
+    /// Results are stored in `self.output` and `self.expr_output`.
     fn traverse_and_collect(&mut self, expr: &DfExpr) {
         let res = match expr {
+            DfExpr::Between(between) if between.negated => self.collect_predicate_expr(expr),
             DfExpr::Between(between) => self.collect_between(between),
 
-            DfExpr::InList(in_list) => self.collect_inlist(in_list),
+            DfExpr::InList(in_list) if in_list.negated => self.collect_predicate_expr(expr),
+            DfExpr::InList(in_list) => self
+                .collect_inlist(in_list)
+                .and_then(|_| self.collect_bloom_filter_inlist(in_list)),
             DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
                 Operator::And => {
                     self.traverse_and_collect(left);
                     self.traverse_and_collect(right);
                     Ok(())
                 }
-                Operator::Or => self.collect_or_eq_list(left, right),
-                Operator::Eq => self.collect_eq(left, right),
+                Operator::Or => self.collect_or_eq_list(left, right).and_then(|collected| {
+                    if collected {
+                        Ok(())
+                    } else {
+                        self.collect_predicate_expr(expr)
+                    }
+                }),
+                Operator::Eq => self
+                    .collect_eq(left, right)
+                    .and_then(|_| self.collect_bloom_filter_eq(left, right)),
                 Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => {
                     self.collect_comparison_expr(left, op, right)
                 }
                 Operator::RegexMatch => self.collect_regex_match(left, right),
+                Operator::NotEq | Operator::RegexNotMatch => self.collect_predicate_expr(expr),
                 _ => Ok(()),
             },
+            DfExpr::Not(_) | DfExpr::IsNull(_) | DfExpr::IsNotNull(_) => {
+                self.collect_predicate_expr(expr)
+            }
+            DfExpr::Like(like) => self.collect_like(like),
+            DfExpr::ScalarUDF(ScalarUDF { fun, args }) if fun.name == MATCHES_FUNCTION_NAME => {
+                self.collect_matches(args)
+            }
 
-            // TODO(zhongzc): support more expressions, e.g. IsNull, IsNotNull, ...
             _ => Ok(()),
         };
 
@@ -203,7 +279,7 @@ mod tests {
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::ColumnSchema;
     use index::inverted_index::search::predicate::{
-        Bound, Range, RangePredicate, RegexMatchPredicate,
+        Bound, InListPredicate, Range, RangePredicate, RegexMatchPredicate,
     };
     use object_store::services::Memory;
     use object_store::ObjectStore;
@@ -358,4 +434,41 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_collect_or() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        // Eq list of the same column is collected as an InList predicate.
+        builder.traverse_and_collect(
+            &tag_column()
+                .eq(string_lit("foo"))
+                .or(tag_column().eq(string_lit("bar"))),
+        );
+        // OR across columns is collected as a predicate expression.
+        builder.traverse_and_collect(
+            &tag_column()
+                .eq(string_lit("foo"))
+                .or(tag_column2().eq(int64_lit(1))),
+        );
+
+        assert_eq!(
+            builder.output.get(&1).unwrap(),
+            &vec![Predicate::InList(InListPredicate {
+                list: HashSet::from_iter([encoded_string("foo"), encoded_string("bar")]),
+            })]
+        );
+        assert_eq!(builder.output.len(), 1);
+        assert!(matches!(
+            builder.expr_output.as_slice(),
+            [PredicateExpr::Or(exprs)] if exprs.len() == 2
+        ));
+    }
 }
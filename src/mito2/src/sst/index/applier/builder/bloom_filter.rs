@@ -0,0 +1,212 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::SemanticType;
+use datafusion_expr::expr::InList;
+use datafusion_expr::Expr as DfExpr;
+use datatypes::data_type::ConcreteDataType;
+use snafu::OptionExt;
+use store_api::storage::ColumnId;
+
+use crate::error::{ColumnNotFoundSnafu, Result};
+use crate::sst::index::applier::builder::SstIndexApplierBuilder;
+
+impl<'a> SstIndexApplierBuilder<'a> {
+    /// Collects an eq expression in the form of `column = lit` on a bloom filter indexed column.
+    pub(crate) fn collect_bloom_filter_eq(&mut self, left: &DfExpr, right: &DfExpr) -> Result<()> {
+        let Some(column_name) = Self::column_name(left).or_else(|| Self::column_name(right)) else {
+            return Ok(());
+        };
+        let Some(lit) = Self::nonnull_lit(right).or_else(|| Self::nonnull_lit(left)) else {
+            return Ok(());
+        };
+        let Some((column_id, data_type)) = self.bloom_filter_column_id_and_type(column_name)?
+        else {
+            return Ok(());
+        };
+
+        let values = vec![Self::encode_lit(lit, data_type)?];
+        self.add_bloom_filter_predicate(column_id, values);
+        Ok(())
+    }
+
+    /// Collects an in list expression in the form of `column IN (lit, lit, ...)`
+    /// on a bloom filter indexed column.
+    pub(crate) fn collect_bloom_filter_inlist(&mut self, inlist: &InList) -> Result<()> {
+        if inlist.negated {
+            return Ok(());
+        }
+        let Some(column_name) = Self::column_name(&inlist.expr) else {
+            return Ok(());
+        };
+        let Some((column_id, data_type)) = self.bloom_filter_column_id_and_type(column_name)?
+        else {
+            return Ok(());
+        };
+
+        let mut values = Vec::with_capacity(inlist.list.len());
+        for lit in &inlist.list {
+            let Some(lit) = Self::nonnull_lit(lit) else {
+                return Ok(());
+            };
+            values.push(Self::encode_lit(lit, data_type.clone())?);
+        }
+
+        self.add_bloom_filter_predicate(column_id, values);
+        Ok(())
+    }
+
+    /// Helper function to add an `IN` predicate of a bloom filter indexed column to the output.
+    fn add_bloom_filter_predicate(&mut self, column_id: ColumnId, values: Vec<Vec<u8>>) {
+        self.bloom_filter_output
+            .entry(column_id)
+            .or_default()
+            .push(values);
+    }
+
+    /// Helper function to get the column id and the column type of a field column
+    /// with the bloom filter index.
+    /// Returns `None` if the column has no bloom filter index.
+    fn bloom_filter_column_id_and_type(
+        &self,
+        column_name: &str,
+    ) -> Result<Option<(ColumnId, ConcreteDataType)>> {
+        let column = self
+            .metadata
+            .column_by_name(column_name)
+            .context(ColumnNotFoundSnafu {
+                column: column_name,
+            })?;
+
+        if !self.bloom_filter_column_ids.contains(&column.column_id)
+            || column.semantic_type != SemanticType::Field
+        {
+            return Ok(None);
+        }
+
+        Ok(Some((
+            column.column_id,
+            column.column_schema.data_type.clone(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::sst::index::applier::builder::tests::{
+        encoded_string, field_column, nonexistent_column, string_lit, tag_column,
+        test_object_store, test_region_metadata,
+    };
+
+    #[test]
+    fn test_collect_bloom_filter_eq_basic() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_bloom_filter_column_ids(HashSet::from([3]));
+
+        builder
+            .collect_bloom_filter_eq(&field_column(), &string_lit("abc"))
+            .unwrap();
+        builder
+            .collect_bloom_filter_eq(&string_lit("def"), &field_column())
+            .unwrap();
+
+        let predicates = builder.bloom_filter_output.get(&3).unwrap();
+        assert_eq!(
+            predicates,
+            &vec![vec![encoded_string("abc")], vec![encoded_string("def")]]
+        );
+    }
+
+    #[test]
+    fn test_collect_bloom_filter_inlist_basic() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_bloom_filter_column_ids(HashSet::from([3]));
+
+        let in_list = InList {
+            expr: Box::new(field_column()),
+            list: vec![string_lit("foo"), string_lit("bar")],
+            negated: false,
+        };
+        builder.collect_bloom_filter_inlist(&in_list).unwrap();
+
+        let predicates = builder.bloom_filter_output.get(&3).unwrap();
+        assert_eq!(
+            predicates,
+            &vec![vec![encoded_string("foo"), encoded_string("bar")]]
+        );
+    }
+
+    #[test]
+    fn test_collect_bloom_filter_not_indexed() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_bloom_filter_column_ids(HashSet::from([1]));
+
+        builder
+            .collect_bloom_filter_eq(&field_column(), &string_lit("abc"))
+            .unwrap();
+        builder
+            .collect_bloom_filter_eq(&tag_column(), &string_lit("abc"))
+            .unwrap();
+        let in_list = InList {
+            expr: Box::new(field_column()),
+            list: vec![string_lit("foo")],
+            negated: true,
+        };
+        builder.collect_bloom_filter_inlist(&in_list).unwrap();
+
+        assert!(builder.bloom_filter_output.is_empty());
+    }
+
+    #[test]
+    fn test_collect_bloom_filter_nonexistent_column() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_bloom_filter_column_ids(HashSet::from([3]));
+
+        let res = builder.collect_bloom_filter_eq(&nonexistent_column(), &string_lit("abc"));
+        assert!(matches!(res, Err(Error::ColumnNotFound { .. })));
+        assert!(builder.bloom_filter_output.is_empty());
+    }
+}
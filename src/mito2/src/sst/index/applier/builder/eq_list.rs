@@ -43,24 +43,30 @@ impl<'a> SstIndexApplierBuilder<'a> {
     }
 
     /// Collects eq list in the form of `column = lit OR column = lit OR ...`.
-    pub(crate) fn collect_or_eq_list(&mut self, eq_expr: &DfExpr, or_list: &DfExpr) -> Result<()> {
+    ///
+    /// Returns false if the expression doesn't match the form.
+    pub(crate) fn collect_or_eq_list(
+        &mut self,
+        eq_expr: &DfExpr,
+        or_list: &DfExpr,
+    ) -> Result<bool> {
         let DfExpr::BinaryExpr(BinaryExpr {
             left,
             op: Operator::Eq,
             right,
         }) = eq_expr
         else {
-            return Ok(());
+            return Ok(false);
         };
 
         let Some(column_name) = Self::column_name(left).or_else(|| Self::column_name(right)) else {
-            return Ok(());
+            return Ok(false);
         };
         let Some(lit) = Self::nonnull_lit(right).or_else(|| Self::nonnull_lit(left)) else {
-            return Ok(());
+            return Ok(false);
         };
         let Some((column_id, data_type)) = self.tag_column_id_and_type(column_name)? else {
-            return Ok(());
+            return Ok(false);
         };
 
         let bytes = Self::encode_lit(lit, data_type.clone())?;
@@ -69,9 +75,10 @@ impl<'a> SstIndexApplierBuilder<'a> {
         if Self::collect_eq_list_inner(column_name, &data_type, or_list, &mut inlist)? {
             let predicate = Predicate::InList(InListPredicate { list: inlist });
             self.add_predicate(column_id, predicate);
+            return Ok(true);
         }
 
-        Ok(())
+        Ok(false)
     }
 
     /// Recursively collects eq list.
@@ -0,0 +1,225 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::SemanticType;
+use datafusion_common::ScalarValue;
+use datafusion_expr::expr::Like;
+use datafusion_expr::Expr as DfExpr;
+use index::full_text_index::tokenizer::{like_terms, tokenize, Term};
+use snafu::OptionExt;
+use store_api::storage::ColumnId;
+
+use crate::error::{ColumnNotFoundSnafu, Result};
+use crate::sst::index::applier::builder::SstIndexApplierBuilder;
+
+impl<'a> SstIndexApplierBuilder<'a> {
+    /// Collects a full-text query in the form of `matches(column, query)`.
+    pub(crate) fn collect_matches(&mut self, args: &[DfExpr]) -> Result<()> {
+        let [column, query] = args else {
+            return Ok(());
+        };
+        let Some(column_name) = Self::column_name(column) else {
+            return Ok(());
+        };
+        let Some(column_id) = self.full_text_column_id(column_name)? else {
+            return Ok(());
+        };
+        let DfExpr::Literal(ScalarValue::Utf8(Some(query))) = query else {
+            return Ok(());
+        };
+
+        let terms = tokenize(query).map(Term::Token).collect();
+        self.add_full_text_terms(column_id, terms);
+        Ok(())
+    }
+
+    /// Collects a like expression in the form of `column LIKE pattern`.
+    pub(crate) fn collect_like(&mut self, like: &Like) -> Result<()> {
+        if like.negated || like.escape_char.is_some_and(|c| c != '\\') {
+            return Ok(());
+        }
+        let Some(column_name) = Self::column_name(&like.expr) else {
+            return Ok(());
+        };
+        let Some(column_id) = self.full_text_column_id(column_name)? else {
+            return Ok(());
+        };
+        let DfExpr::Literal(ScalarValue::Utf8(Some(pattern))) = like.pattern.as_ref() else {
+            return Ok(());
+        };
+
+        self.add_full_text_terms(column_id, like_terms(pattern));
+        Ok(())
+    }
+
+    /// Helper function to add terms of a full-text indexed column to the output.
+    fn add_full_text_terms(&mut self, column_id: ColumnId, terms: Vec<Term>) {
+        if terms.is_empty() {
+            return;
+        }
+        self.full_text_output
+            .entry(column_id)
+            .or_default()
+            .extend(terms);
+    }
+
+    /// Helper function to get the column id of a string field column with the full-text index.
+    /// Returns `None` if the column has no full-text index.
+    fn full_text_column_id(&self, column_name: &str) -> Result<Option<ColumnId>> {
+        let column = self
+            .metadata
+            .column_by_name(column_name)
+            .context(ColumnNotFoundSnafu {
+                column: column_name,
+            })?;
+
+        if !self.full_text_column_ids.contains(&column.column_id)
+            || column.semantic_type != SemanticType::Field
+            || !column.column_schema.data_type.is_string()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(column.column_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::sst::index::applier::builder::tests::{
+        field_column, int64_lit, nonexistent_column, string_lit, tag_column, test_object_store,
+        test_region_metadata,
+    };
+
+    fn like(column: DfExpr, pattern: DfExpr, negated: bool) -> Like {
+        Like {
+            negated,
+            expr: Box::new(column),
+            pattern: Box::new(pattern),
+            escape_char: None,
+            case_insensitive: false,
+        }
+    }
+
+    #[test]
+    fn test_collect_matches_basic() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_full_text_column_ids(HashSet::from([3]));
+
+        builder
+            .collect_matches(&[field_column(), string_lit("Connection TIMEOUT")])
+            .unwrap();
+
+        let terms = builder.full_text_output.get(&3).unwrap();
+        assert_eq!(
+            terms,
+            &vec![
+                Term::Token("connection".to_string()),
+                Term::Token("timeout".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_matches_not_indexed() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_full_text_column_ids(HashSet::from([1]));
+
+        builder
+            .collect_matches(&[field_column(), string_lit("timeout")])
+            .unwrap();
+        builder
+            .collect_matches(&[tag_column(), string_lit("timeout")])
+            .unwrap();
+        builder
+            .collect_matches(&[field_column(), int64_lit(1)])
+            .unwrap();
+
+        assert!(builder.full_text_output.is_empty());
+    }
+
+    #[test]
+    fn test_collect_like() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_full_text_column_ids(HashSet::from([3]));
+
+        builder
+            .collect_like(&like(field_column(), string_lit("%timeout%"), true))
+            .unwrap();
+        assert!(builder.full_text_output.is_empty());
+
+        builder
+            .collect_like(&like(field_column(), string_lit("%"), false))
+            .unwrap();
+        assert!(builder.full_text_output.is_empty());
+
+        builder
+            .collect_like(&like(
+                field_column(),
+                string_lit("%connection timeout%"),
+                false,
+            ))
+            .unwrap();
+        let terms = builder.full_text_output.get(&3).unwrap();
+        assert_eq!(
+            terms,
+            &vec![
+                Term::Fragment("connection".to_string()),
+                Term::Fragment("timeout".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_like_nonexistent_column() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        )
+        .with_full_text_column_ids(HashSet::from([3]));
+
+        let res = builder.collect_like(&like(nonexistent_column(), string_lit("%abc%"), false));
+        assert!(matches!(res, Err(Error::ColumnNotFound { .. })));
+        assert!(builder.full_text_output.is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::mem;
+
+use datafusion_expr::expr::InList;
+use datafusion_expr::{Between, BinaryExpr, Expr as DfExpr, Operator};
+use index::inverted_index::search::predicate::PredicateExpr;
+
+use crate::error::Result;
+use crate::sst::index::applier::builder::SstIndexApplierBuilder;
+
+impl<'a> SstIndexApplierBuilder<'a> {
+    /// Collects an expression which can't be represented by predicates of a single column,
+    /// e.g. `NOT`, `IS NULL`, `!=` and `OR` across columns, as a predicate expression.
+    pub(crate) fn collect_predicate_expr(&mut self, expr: &DfExpr) -> Result<()> {
+        if let Some(predicate_expr) = self.predicate_expr(expr, false)? {
+            self.expr_output.push(predicate_expr);
+        }
+        Ok(())
+    }
+
+    /// Converts the expression, negated if `negated` is true, to a predicate expression.
+    /// Negations are pushed down to leaves by De Morgan's laws.
+    ///
+    /// Returns `None` if the expression can't be converted, that is, it may match any segment.
+    fn predicate_expr(&mut self, expr: &DfExpr, negated: bool) -> Result<Option<PredicateExpr>> {
+        match expr {
+            DfExpr::Not(expr) => self.predicate_expr(expr, !negated),
+            DfExpr::IsNull(expr) => self.is_null_expr(expr, negated),
+            DfExpr::IsNotNull(expr) => self.is_null_expr(expr, !negated),
+
+            DfExpr::InList(in_list) if in_list.negated => {
+                let in_list = InList {
+                    negated: false,
+                    ..in_list.clone()
+                };
+                self.leaf_expr(!negated, |this| this.collect_inlist(&in_list))
+            }
+            DfExpr::InList(in_list) => self.leaf_expr(negated, |this| this.collect_inlist(in_list)),
+
+            DfExpr::Between(between) if between.negated => {
+                let between = Between {
+                    negated: false,
+                    ..between.clone()
+                };
+                self.leaf_expr(!negated, |this| this.collect_between(&between))
+            }
+            DfExpr::Between(between) => {
+                self.leaf_expr(negated, |this| this.collect_between(between))
+            }
+
+            DfExpr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And | Operator::Or => {
+                    let left = self.predicate_expr(left, negated)?;
+                    let right = self.predicate_expr(right, negated)?;
+
+                    // `NOT (a AND b)` is `NOT a OR NOT b`, and `NOT (a OR b)` is `NOT a AND NOT b`.
+                    if (op == &Operator::And) != negated {
+                        // An unconvertible operand of AND filters out no segment.
+                        let exprs = left.into_iter().chain(right).collect::<Vec<_>>();
+                        Ok((!exprs.is_empty()).then_some(PredicateExpr::And(exprs)))
+                    } else {
+                        // An unconvertible operand of OR makes the whole OR match any segment.
+                        Ok(left
+                            .zip(right)
+                            .map(|(left, right)| PredicateExpr::Or(vec![left, right])))
+                    }
+                }
+                Operator::Eq => self.leaf_expr(negated, |this| this.collect_eq(left, right)),
+                Operator::NotEq => self.leaf_expr(!negated, |this| this.collect_eq(left, right)),
+                Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq => self
+                    .leaf_expr(negated, |this| {
+                        this.collect_comparison_expr(left, op, right)
+                    }),
+                Operator::RegexMatch => {
+                    self.leaf_expr(negated, |this| this.collect_regex_match(left, right))
+                }
+                Operator::RegexNotMatch => {
+                    self.leaf_expr(!negated, |this| this.collect_regex_match(left, right))
+                }
+                _ => Ok(None),
+            },
+
+            _ => Ok(None),
+        }
+    }
+
+    /// Converts `column IS NULL`, negated if `negated` is true, to a predicate expression.
+    fn is_null_expr(&self, column: &DfExpr, negated: bool) -> Result<Option<PredicateExpr>> {
+        let Some(column_name) = Self::column_name(column) else {
+            return Ok(None);
+        };
+        let Some((column_id, _)) = self.tag_column_id_and_type(column_name)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(PredicateExpr::IsNull {
+            index_name: column_id.to_string(),
+            negated,
+        }))
+    }
+
+    /// Collects predicates of a single column by `collect` aside from `self.output`,
+    /// and converts them to a predicate expression, negated if `negated` is true.
+    fn leaf_expr(
+        &mut self,
+        negated: bool,
+        collect: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<Option<PredicateExpr>> {
+        let output = mem::take(&mut self.output);
+        let res = collect(self);
+        let leaf_output = mem::replace(&mut self.output, output);
+        res?;
+
+        // Leaf expressions refer to at most one column.
+        Ok(leaf_output
+            .into_iter()
+            .next()
+            .map(|(column_id, predicates)| PredicateExpr::Predicates {
+                index_name: column_id.to_string(),
+                predicates,
+                negated,
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use index::inverted_index::search::predicate::{
+        Bound, InListPredicate, Predicate, Range, RangePredicate, RegexMatchPredicate,
+    };
+
+    use super::*;
+    use crate::error::Error;
+    use crate::sst::index::applier::builder::tests::{
+        encoded_int64, encoded_string, field_column, int64_lit, nonexistent_column, string_lit,
+        tag_column, tag_column2, test_object_store, test_region_metadata,
+    };
+
+    fn in_list_expr(index_name: &str, values: &[Vec<u8>], negated: bool) -> PredicateExpr {
+        PredicateExpr::Predicates {
+            index_name: index_name.to_string(),
+            predicates: vec![Predicate::InList(InListPredicate {
+                list: HashSet::from_iter(values.iter().cloned()),
+            })],
+            negated,
+        }
+    }
+
+    #[test]
+    fn test_collect_predicate_expr_negation() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        builder
+            .collect_predicate_expr(&tag_column().not_eq(string_lit("foo")))
+            .unwrap();
+        builder
+            .collect_predicate_expr(&tag_column().in_list(vec![string_lit("bar")], true))
+            .unwrap();
+        builder
+            .collect_predicate_expr(&DfExpr::Not(Box::new(
+                tag_column().not_eq(string_lit("baz")),
+            )))
+            .unwrap();
+        builder
+            .collect_predicate_expr(&DfExpr::BinaryExpr(BinaryExpr {
+                left: Box::new(tag_column()),
+                op: Operator::RegexNotMatch,
+                right: Box::new(string_lit("qux")),
+            }))
+            .unwrap();
+
+        assert!(builder.output.is_empty());
+        assert_eq!(
+            builder.expr_output,
+            vec![
+                in_list_expr("1", &[encoded_string("foo")], true),
+                in_list_expr("1", &[encoded_string("bar")], true),
+                in_list_expr("1", &[encoded_string("baz")], false),
+                PredicateExpr::Predicates {
+                    index_name: "1".to_string(),
+                    predicates: vec![Predicate::RegexMatch(RegexMatchPredicate {
+                        pattern: "qux".to_string(),
+                    })],
+                    negated: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_predicate_expr_is_null() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        builder
+            .collect_predicate_expr(&tag_column().is_null())
+            .unwrap();
+        builder
+            .collect_predicate_expr(&tag_column2().is_not_null())
+            .unwrap();
+        builder
+            .collect_predicate_expr(&DfExpr::Not(Box::new(tag_column().is_not_null())))
+            .unwrap();
+        // Field columns are not indexed.
+        builder
+            .collect_predicate_expr(&field_column().is_null())
+            .unwrap();
+
+        assert_eq!(
+            builder.expr_output,
+            vec![
+                PredicateExpr::IsNull {
+                    index_name: "1".to_string(),
+                    negated: false,
+                },
+                PredicateExpr::IsNull {
+                    index_name: "2".to_string(),
+                    negated: true,
+                },
+                PredicateExpr::IsNull {
+                    index_name: "1".to_string(),
+                    negated: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_predicate_expr_or() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        // a = "foo" OR b > 1
+        builder
+            .collect_predicate_expr(
+                &tag_column()
+                    .eq(string_lit("foo"))
+                    .or(tag_column2().gt(int64_lit(1))),
+            )
+            .unwrap();
+        // NOT (a = "foo" OR b > 1)
+        builder
+            .collect_predicate_expr(&DfExpr::Not(Box::new(
+                tag_column()
+                    .eq(string_lit("foo"))
+                    .or(tag_column2().gt(int64_lit(1))),
+            )))
+            .unwrap();
+
+        let greater_than_one = Predicate::Range(RangePredicate {
+            range: Range {
+                lower: Some(Bound {
+                    inclusive: false,
+                    value: encoded_int64(1),
+                }),
+                upper: None,
+            },
+        });
+        assert_eq!(
+            builder.expr_output,
+            vec![
+                PredicateExpr::Or(vec![
+                    in_list_expr("1", &[encoded_string("foo")], false),
+                    PredicateExpr::Predicates {
+                        index_name: "2".to_string(),
+                        predicates: vec![greater_than_one.clone()],
+                        negated: false,
+                    },
+                ]),
+                PredicateExpr::And(vec![
+                    in_list_expr("1", &[encoded_string("foo")], true),
+                    PredicateExpr::Predicates {
+                        index_name: "2".to_string(),
+                        predicates: vec![greater_than_one],
+                        negated: true,
+                    },
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_predicate_expr_unconvertible() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        // a = "foo" OR c = "bar"
+        builder
+            .collect_predicate_expr(
+                &tag_column()
+                    .eq(string_lit("foo"))
+                    .or(field_column().eq(string_lit("bar"))),
+            )
+            .unwrap();
+        assert!(builder.expr_output.is_empty());
+
+        // NOT (a = "foo" AND c = "bar")
+        builder
+            .collect_predicate_expr(&DfExpr::Not(Box::new(
+                tag_column()
+                    .eq(string_lit("foo"))
+                    .and(field_column().eq(string_lit("bar"))),
+            )))
+            .unwrap();
+        assert!(builder.expr_output.is_empty());
+
+        // NOT (a = "foo" OR c = "bar")
+        builder
+            .collect_predicate_expr(&DfExpr::Not(Box::new(
+                tag_column()
+                    .eq(string_lit("foo"))
+                    .or(field_column().eq(string_lit("bar"))),
+            )))
+            .unwrap();
+        assert_eq!(
+            builder.expr_output,
+            vec![PredicateExpr::And(vec![in_list_expr(
+                "1",
+                &[encoded_string("foo")],
+                true
+            )])]
+        );
+    }
+
+    #[test]
+    fn test_collect_predicate_expr_nonexistent_column() {
+        let metadata = test_region_metadata();
+        let mut builder = SstIndexApplierBuilder::new(
+            "test".to_string(),
+            test_object_store(),
+            None,
+            &metadata,
+            HashSet::default(),
+        );
+
+        let res = builder.collect_predicate_expr(&nonexistent_column().not_eq(string_lit("foo")));
+        assert!(matches!(res, Err(Error::ColumnNotFound { .. })));
+        let res = builder.collect_predicate_expr(&nonexistent_column().is_null());
+        assert!(matches!(res, Err(Error::ColumnNotFound { .. })));
+        assert!(builder.expr_output.is_empty());
+        assert!(builder.output.is_empty());
+    }
+}
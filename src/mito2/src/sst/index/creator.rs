@@ -21,32 +21,44 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
 use common_telemetry::warn;
+use datatypes::data_type::ConcreteDataType;
+use index::bloom_filter::create::BloomFilterCreator;
+use index::bloom_filter::DEFAULT_FALSE_POSITIVE_RATE;
+use index::full_text_index::create::FullTextIndexCreator;
 use index::inverted_index::create::sort::external_sort::ExternalSorter;
 use index::inverted_index::create::sort_create::SortIndexCreator;
 use index::inverted_index::create::InvertedIndexCreator;
 use index::inverted_index::format::writer::InvertedIndexBlobWriter;
 use object_store::ObjectStore;
+use puffin::blob_metadata::CompressionCodec;
 use puffin::file_format::writer::{Blob, PuffinAsyncWriter, PuffinFileWriter};
+use smallvec::SmallVec;
 use snafu::{ensure, ResultExt};
 use store_api::metadata::RegionMetadataRef;
+use store_api::storage::ColumnId as RegionColumnId;
 use tokio::io::duplex;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
 use crate::error::{
-    BiSnafu, IndexFinishSnafu, OperateAbortedIndexSnafu, PuffinAddBlobSnafu, PuffinFinishSnafu,
+    BiSnafu, BloomFilterIndexFinishSnafu, ConvertValueSnafu, FullTextIndexFinishSnafu,
+    IndexFinishSnafu, OperateAbortedIndexSnafu, PuffinAddBlobSnafu, PuffinFinishSnafu,
     PushIndexValueSnafu, Result,
 };
 use crate::metrics::{
     INDEX_PUFFIN_FLUSH_OP_TOTAL, INDEX_PUFFIN_WRITE_BYTES_TOTAL, INDEX_PUFFIN_WRITE_OP_TOTAL,
 };
 use crate::read::Batch;
-use crate::sst::file::FileId;
+use crate::row_converter::SortField;
+use crate::sst::file::{FileId, IndexType};
 use crate::sst::index::codec::{ColumnId, IndexValueCodec, IndexValuesCodec};
 use crate::sst::index::creator::statistics::Statistics;
 use crate::sst::index::creator::temp_provider::TempFileProvider;
 use crate::sst::index::intermediate::{IntermediateLocation, IntermediateManager};
 use crate::sst::index::store::InstrumentedStore;
-use crate::sst::index::INDEX_BLOB_TYPE;
+use crate::sst::index::{
+    BLOOM_FILTER_INDEX_BLOB_TYPE, FULL_TEXT_INDEX_BLOB_TYPE, INDEX_BLOB_TYPE,
+    INDEX_COLUMN_ID_PROPERTY,
+};
 
 /// The minimum memory usage threshold for one column.
 const MIN_MEMORY_USAGE_THRESHOLD_PER_COLUMN: usize = 1024 * 1024; // 1MB
@@ -63,10 +75,18 @@ pub struct SstIndexCreator {
     file_path: String,
     /// The store to write index files.
     store: InstrumentedStore,
-    /// The index creator.
-    index_creator: Box<dyn InvertedIndexCreator>,
+    /// The inverted index creator, `None` if the region has no tag column.
+    index_creator: Option<Box<dyn InvertedIndexCreator>>,
+    /// The full-text index creators of field columns.
+    full_text_creators: Vec<(RegionColumnId, FullTextIndexCreator)>,
+    /// The bloom filter index creators of field columns.
+    bloom_filter_creators: Vec<(RegionColumnId, SortField, BloomFilterCreator)>,
+    /// The number of rows in a segment.
+    segment_row_count: NonZeroUsize,
     /// The provider of intermediate files.
     temp_file_provider: Arc<TempFileProvider>,
+    /// The codec to compress index blobs, `None` if uncompressed.
+    compression: Option<CompressionCodec>,
 
     /// Codec for decoding primary keys.
     codec: IndexValuesCodec,
@@ -83,11 +103,13 @@ pub struct SstIndexCreator {
 
     /// The memory usage of the index creator.
     memory_usage: Arc<AtomicUsize>,
+    /// Stops creating full-text indexes of columns if the memory usage exceeds it.
+    memory_usage_threshold: Option<usize>,
 }
 
 impl SstIndexCreator {
     /// Creates a new `SstIndexCreator`.
-    /// Should ensure that the number of tag columns is greater than 0.
+    /// Only creates the inverted index if the region has tag columns.
     pub fn new(
         file_path: String,
         sst_file_id: FileId,
@@ -110,7 +132,9 @@ impl SstIndexCreator {
             memory_usage.clone(),
             memory_usage_threshold,
         );
-        let index_creator = Box::new(SortIndexCreator::new(sorter, segment_row_count));
+        let index_creator: Option<Box<dyn InvertedIndexCreator>> =
+            (!metadata.primary_key.is_empty())
+                .then(|| Box::new(SortIndexCreator::new(sorter, segment_row_count)) as _);
 
         let codec = IndexValuesCodec::from_tag_columns(metadata.primary_key_columns());
         Self {
@@ -118,7 +142,11 @@ impl SstIndexCreator {
             store: InstrumentedStore::new(index_store),
             codec,
             index_creator,
+            full_text_creators: Vec::new(),
+            bloom_filter_creators: Vec::new(),
+            segment_row_count,
             temp_file_provider,
+            compression: None,
 
             value_buf: vec![],
 
@@ -127,6 +155,7 @@ impl SstIndexCreator {
 
             ignore_column_ids: HashSet::default(),
             memory_usage,
+            memory_usage_threshold,
         }
     }
 
@@ -136,12 +165,65 @@ impl SstIndexCreator {
         self
     }
 
+    /// Sets the codec to compress index blobs.
+    pub fn with_compression(mut self, compression: Option<CompressionCodec>) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Sets the ignore column IDs for index creation.
     pub fn with_ignore_column_ids(mut self, ignore_column_ids: HashSet<ColumnId>) -> Self {
         self.ignore_column_ids = ignore_column_ids;
         self
     }
 
+    /// Sets the field columns to create the full-text index.
+    pub fn with_full_text_column_ids(mut self, column_ids: &[RegionColumnId]) -> Self {
+        self.full_text_creators = column_ids
+            .iter()
+            .map(|column_id| {
+                (
+                    *column_id,
+                    FullTextIndexCreator::new(self.segment_row_count),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// Sets the field columns and their data types to create the bloom filter index.
+    pub fn with_bloom_filter_columns(
+        mut self,
+        columns: Vec<(RegionColumnId, ConcreteDataType)>,
+    ) -> Self {
+        self.bloom_filter_creators = columns
+            .into_iter()
+            .map(|(column_id, data_type)| {
+                (
+                    column_id,
+                    SortField::new(data_type),
+                    BloomFilterCreator::new(self.segment_row_count, DEFAULT_FALSE_POSITIVE_RATE),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// Returns the indexes this creator writes to the index file.
+    pub fn index_types(&self) -> SmallVec<[IndexType; 4]> {
+        let mut index_types = SmallVec::new();
+        if self.index_creator.is_some() {
+            index_types.push(IndexType::InvertedIndex);
+        }
+        if !self.full_text_creators.is_empty() {
+            index_types.push(IndexType::FullTextIndex);
+        }
+        if !self.bloom_filter_creators.is_empty() {
+            index_types.push(IndexType::BloomFilterIndex);
+        }
+        index_types
+    }
+
     /// Updates index with a batch of rows.
     /// Garbage will be cleaned up if failed to update.
     pub async fn update(&mut self, batch: &Batch) -> Result<()> {
@@ -211,26 +293,61 @@ impl SstIndexCreator {
         let n = batch.num_rows();
         guard.inc_row_count(n);
 
-        for (column_id, field, value) in self.codec.decode(batch.primary_key())? {
-            if self.ignore_column_ids.contains(column_id) {
+        if let Some(index_creator) = self.index_creator.as_mut() {
+            for (column_id, field, value) in self.codec.decode(batch.primary_key())? {
+                if self.ignore_column_ids.contains(column_id) {
+                    continue;
+                }
+
+                if let Some(value) = value.as_ref() {
+                    self.value_buf.clear();
+                    IndexValueCodec::encode_nonnull_value(
+                        value.as_value_ref(),
+                        field,
+                        &mut self.value_buf,
+                    )?;
+                }
+
+                // non-null value -> Some(encoded_bytes), null value -> None
+                let value = value.is_some().then_some(self.value_buf.as_slice());
+                index_creator
+                    .push_with_name_n(column_id, value, n)
+                    .await
+                    .context(PushIndexValueSnafu)?;
+            }
+        }
+
+        for (column_id, creator) in &mut self.full_text_creators {
+            // Pushes nulls if the batch doesn't contain the column.
+            let Some(column) = batch.fields().iter().find(|c| c.column_id == *column_id) else {
+                (0..n).for_each(|_| creator.push_text(None));
                 continue;
+            };
+            for i in 0..n {
+                let text = column.data.get_ref(i);
+                creator.push_text(text.as_string().context(ConvertValueSnafu)?);
             }
+        }
+        if let Some(threshold) = self.memory_usage_threshold {
+            self.abort_full_text_creators(threshold);
+        }
 
-            if let Some(value) = value.as_ref() {
+        for (column_id, field, creator) in &mut self.bloom_filter_creators {
+            // Pushes nulls if the batch doesn't contain the column.
+            let Some(column) = batch.fields().iter().find(|c| c.column_id == *column_id) else {
+                (0..n).for_each(|_| creator.push_value(None));
+                continue;
+            };
+            for i in 0..n {
+                let value = column.data.get_ref(i);
+                if value.is_null() {
+                    creator.push_value(None);
+                    continue;
+                }
                 self.value_buf.clear();
-                IndexValueCodec::encode_nonnull_value(
-                    value.as_value_ref(),
-                    field,
-                    &mut self.value_buf,
-                )?;
+                IndexValueCodec::encode_nonnull_value(value, field, &mut self.value_buf)?;
+                creator.push_value(Some(&self.value_buf));
             }
-
-            // non-null value -> Some(encoded_bytes), null value -> None
-            let value = value.is_some().then_some(self.value_buf.as_slice());
-            self.index_creator
-                .push_with_name_n(column_id, value, n)
-                .await
-                .context(PushIndexValueSnafu)?;
         }
 
         Ok(())
@@ -266,39 +383,123 @@ impl SstIndexCreator {
                 &INDEX_PUFFIN_FLUSH_OP_TOTAL,
             )
             .await?;
-        let mut puffin_writer = PuffinFileWriter::new(file_writer);
+        // Compresses the footer as well if blobs are compressed.
+        let mut puffin_writer = PuffinFileWriter::new(file_writer)
+            .with_footer_lz4_compressed(self.compression.is_some());
+
+        if let Some(index_creator) = self.index_creator.as_mut() {
+            let (tx, rx) = duplex(PIPE_BUFFER_SIZE_FOR_SENDING_BLOB);
+            let blob = Blob {
+                blob_type: INDEX_BLOB_TYPE.to_string(),
+                data: rx.compat(),
+                properties: HashMap::default(),
+                compression_codec: self.compression,
+            };
+            let mut index_writer = InvertedIndexBlobWriter::new(tx.compat_write());
+
+            let (index_finish, puffin_add_blob) = futures::join!(
+                index_creator.finish(&mut index_writer),
+                puffin_writer.add_blob(blob)
+            );
+            Self::check_blob_written(
+                puffin_add_blob.context(PuffinAddBlobSnafu),
+                index_finish.context(IndexFinishSnafu),
+            )?;
+        }
 
-        let (tx, rx) = duplex(PIPE_BUFFER_SIZE_FOR_SENDING_BLOB);
-        let blob = Blob {
-            blob_type: INDEX_BLOB_TYPE.to_string(),
-            data: rx.compat(),
-            properties: HashMap::default(),
-        };
-        let mut index_writer = InvertedIndexBlobWriter::new(tx.compat_write());
+        for (column_id, creator) in &mut self.full_text_creators {
+            if creator.is_aborted() {
+                continue;
+            }
+            let (tx, rx) = duplex(PIPE_BUFFER_SIZE_FOR_SENDING_BLOB);
+            let blob = Blob {
+                blob_type: FULL_TEXT_INDEX_BLOB_TYPE.to_string(),
+                data: rx.compat(),
+                properties: HashMap::from([(
+                    INDEX_COLUMN_ID_PROPERTY.to_string(),
+                    column_id.to_string(),
+                )]),
+                compression_codec: self.compression,
+            };
+            let mut index_writer = tx.compat_write();
+
+            let (index_finish, puffin_add_blob) = futures::join!(
+                creator.finish(&mut index_writer),
+                puffin_writer.add_blob(blob)
+            );
+            Self::check_blob_written(
+                puffin_add_blob.context(PuffinAddBlobSnafu),
+                index_finish.context(FullTextIndexFinishSnafu),
+            )?;
+        }
 
-        let (index_finish, puffin_add_blob) = futures::join!(
-            self.index_creator.finish(&mut index_writer),
-            puffin_writer.add_blob(blob)
-        );
+        for (column_id, _, creator) in &mut self.bloom_filter_creators {
+            let (tx, rx) = duplex(PIPE_BUFFER_SIZE_FOR_SENDING_BLOB);
+            let blob = Blob {
+                blob_type: BLOOM_FILTER_INDEX_BLOB_TYPE.to_string(),
+                data: rx.compat(),
+                properties: HashMap::from([(
+                    INDEX_COLUMN_ID_PROPERTY.to_string(),
+                    column_id.to_string(),
+                )]),
+                compression_codec: self.compression,
+            };
+            let mut index_writer = tx.compat_write();
+
+            let (index_finish, puffin_add_blob) = futures::join!(
+                creator.finish(&mut index_writer),
+                puffin_writer.add_blob(blob)
+            );
+            Self::check_blob_written(
+                puffin_add_blob.context(PuffinAddBlobSnafu),
+                index_finish.context(BloomFilterIndexFinishSnafu),
+            )?;
+        }
+
+        let byte_count = puffin_writer.finish().await.context(PuffinFinishSnafu)?;
+        guard.inc_byte_count(byte_count);
+        Ok(())
+    }
 
-        match (
-            puffin_add_blob.context(PuffinAddBlobSnafu),
-            index_finish.context(IndexFinishSnafu),
-        ) {
+    /// Stops creating full-text indexes of columns, from the one using the most memory,
+    /// until the memory usage doesn't exceed the `threshold`.
+    ///
+    /// Tokens can't be spilled like the inverted index, and the applier doesn't prune
+    /// the file by a column without the index.
+    fn abort_full_text_creators(&mut self, threshold: usize) {
+        while self.memory_usage() > threshold {
+            let Some((column_id, creator)) = self
+                .full_text_creators
+                .iter_mut()
+                .filter(|(_, creator)| creator.memory_usage() > 0)
+                .max_by_key(|(_, creator)| creator.memory_usage())
+            else {
+                return;
+            };
+            warn!(
+                "Stop creating full-text index for column {}, memory usage: {}, threshold: {}, file_path: {}",
+                column_id,
+                creator.memory_usage(),
+                threshold,
+                self.file_path
+            );
+            creator.abort();
+        }
+    }
+
+    /// Merges results of writing an index blob and adding it to the puffin file.
+    fn check_blob_written(puffin_add_blob: Result<()>, index_finish: Result<()>) -> Result<()> {
+        match (puffin_add_blob, index_finish) {
             (Err(e1), Err(e2)) => BiSnafu {
                 first: Box::new(e1),
                 second: Box::new(e2),
             }
-            .fail()?,
+            .fail(),
 
-            (Ok(_), e @ Err(_)) => e?,
-            (e @ Err(_), Ok(_)) => e?,
-            _ => {}
+            (Ok(_), e @ Err(_)) => e,
+            (e @ Err(_), Ok(_)) => e,
+            _ => Ok(()),
         }
-
-        let byte_count = puffin_writer.finish().await.context(PuffinFinishSnafu)?;
-        guard.inc_byte_count(byte_count);
-        Ok(())
     }
 
     async fn do_cleanup(&mut self) -> Result<()> {
@@ -308,7 +509,19 @@ impl SstIndexCreator {
     }
 
     pub fn memory_usage(&self) -> usize {
+        let full_text_memory_usage: usize = self
+            .full_text_creators
+            .iter()
+            .map(|(_, creator)| creator.memory_usage())
+            .sum();
+        let bloom_filter_memory_usage: usize = self
+            .bloom_filter_creators
+            .iter()
+            .map(|(_, _, creator)| creator.memory_usage())
+            .sum();
         self.memory_usage.load(std::sync::atomic::Ordering::Relaxed)
+            + full_text_memory_usage
+            + bloom_filter_memory_usage
     }
 }
 
@@ -322,13 +535,14 @@ mod tests {
     use datatypes::data_type::ConcreteDataType;
     use datatypes::schema::ColumnSchema;
     use datatypes::value::ValueRef;
-    use datatypes::vectors::{UInt64Vector, UInt8Vector};
+    use datatypes::vectors::{StringVector, UInt64Vector, UInt8Vector};
     use futures::future::BoxFuture;
     use object_store::services::Memory;
     use store_api::metadata::{ColumnMetadata, RegionMetadataBuilder};
     use store_api::storage::RegionId;
 
     use super::*;
+    use crate::read::BatchColumn;
     use crate::row_converter::{McmpRowCodec, RowCodec, SortField};
     use crate::sst::index::applier::builder::SstIndexApplierBuilder;
     use crate::sst::location;
@@ -444,9 +658,10 @@ mod tests {
             .unwrap();
             Box::pin(async move {
                 applier
-                    .apply(sst_file_id)
+                    .apply(sst_file_id, &[IndexType::InvertedIndex])
                     .await
                     .unwrap()
+                    .unwrap()
                     .matched_segment_ids
                     .iter_ones()
                     .collect()
@@ -625,4 +840,359 @@ mod tests {
         let res = applier_factory(expr).await;
         assert_eq!(res, vec![0, 1, 2]);
     }
+
+    #[tokio::test]
+    async fn test_create_and_query_negation_and_or() {
+        let tags = BTreeSet::from_iter([
+            ("aaa", 1),
+            ("aaa", 2),
+            ("aaa", 3),
+            ("aab", 1),
+            ("aab", 2),
+            ("aab", 3),
+            ("abc", 1),
+            ("abc", 2),
+            ("abc", 3),
+        ]);
+
+        let applier_factory = build_applier_factory(tags).await;
+
+        let expr = col("tag_str").not_eq(lit("aaa"));
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![3, 4, 5, 6, 7, 8]);
+
+        let expr = col("tag_str").in_list(vec![lit("aaa"), lit("abc")], true);
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![3, 4, 5]);
+
+        let expr = col("tag_i32").not_between(lit(2), lit(3));
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![0, 3, 6]);
+
+        let expr = binary_expr(col("tag_str"), Operator::RegexNotMatch, lit("^aa"));
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![6, 7, 8]);
+
+        let expr = col("tag_str").eq(lit("aaa")).or(col("tag_i32").eq(lit(2)));
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![0, 1, 2, 4, 7]);
+
+        let expr = !(col("tag_str").eq(lit("aaa")).and(col("tag_i32").eq(lit(1))));
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let expr = col("tag_str").is_null();
+        let res = applier_factory(expr).await;
+        assert!(res.is_empty());
+
+        let expr = col("tag_str").is_not_null();
+        let res = applier_factory(expr).await;
+        assert_eq!(res, vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    fn full_text_region_metadata() -> RegionMetadataRef {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1, 2));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "tag_str",
+                    ConcreteDataType::string_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Tag,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "tag_i32",
+                    ConcreteDataType::int32_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Tag,
+                column_id: 2,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 3,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "message",
+                    ConcreteDataType::string_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 4,
+            })
+            .primary_key(vec![1, 2]);
+        Arc::new(builder.build().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_create_and_query_full_text() {
+        let region_metadata = full_text_region_metadata();
+
+        let region_dir = "region0".to_string();
+        let sst_file_id = FileId::random();
+        let file_path = location::index_file_path(&region_dir, sst_file_id);
+        let object_store = mock_object_store();
+        let mut creator = SstIndexCreator::new(
+            file_path,
+            sst_file_id,
+            &region_metadata,
+            object_store.clone(),
+            mock_intm_mgr(),
+            None,
+            NonZeroUsize::new(2).unwrap(),
+        )
+        .with_full_text_column_ids(&[4]);
+
+        let rows = [
+            ("aaa", 1, [Some("connection timeout"), Some("ok")]),
+            ("aaa", 2, [None, Some("Disk full")]),
+            ("abc", 1, [Some("request timeout"), Some("ok")]),
+        ];
+        for (str_tag, i32_tag, messages) in rows {
+            let batch = new_batch(messages.len(), str_tag, i32_tag)
+                .with_fields(vec![BatchColumn {
+                    column_id: 4,
+                    data: Arc::new(StringVector::from(messages.to_vec())),
+                }])
+                .unwrap();
+            creator.update(&batch).await.unwrap();
+        }
+        assert_eq!(
+            &[IndexType::InvertedIndex, IndexType::FullTextIndex],
+            creator.index_types().as_slice()
+        );
+        creator.finish().await.unwrap();
+
+        let apply = |expr: DfExpr| {
+            let applier = SstIndexApplierBuilder::new(
+                region_dir.clone(),
+                object_store.clone(),
+                None,
+                &region_metadata,
+                Default::default(),
+            )
+            .with_full_text_column_ids(HashSet::from([4]))
+            .build(&[expr.into()])
+            .unwrap()
+            .unwrap();
+            async move {
+                applier
+                    .apply(
+                        sst_file_id,
+                        &[IndexType::InvertedIndex, IndexType::FullTextIndex],
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .matched_segment_ids
+                    .iter_ones()
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let res = apply(col("message").like(lit("%timeout%"))).await;
+        assert_eq!(res, vec![0, 2]);
+
+        let res = apply(col("message").like(lit("%disk%"))).await;
+        assert_eq!(res, vec![1]);
+
+        let res = apply(col("message").like(lit("%Timeout"))).await;
+        assert_eq!(res, vec![0, 2]);
+
+        let res = apply(
+            col("message")
+                .like(lit("%timeout%"))
+                .and(col("tag_str").eq(lit("abc"))),
+        )
+        .await;
+        assert_eq!(res, vec![2]);
+
+        let res = apply(col("message").like(lit("%error%"))).await;
+        assert!(res.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_full_text_exceed_memory_threshold() {
+        let region_metadata = full_text_region_metadata();
+        let region_dir = "region0".to_string();
+        let sst_file_id = FileId::random();
+        let file_path = location::index_file_path(&region_dir, sst_file_id);
+        let object_store = mock_object_store();
+        let mut creator = SstIndexCreator::new(
+            file_path,
+            sst_file_id,
+            &region_metadata,
+            object_store.clone(),
+            mock_intm_mgr(),
+            Some(1),
+            NonZeroUsize::new(2).unwrap(),
+        )
+        .with_full_text_column_ids(&[4]);
+
+        let batch = new_batch(2, "aaa", 1)
+            .with_fields(vec![BatchColumn {
+                column_id: 4,
+                data: Arc::new(StringVector::from(vec![
+                    Some("connection timeout"),
+                    Some("ok"),
+                ])),
+            }])
+            .unwrap();
+        creator.update(&batch).await.unwrap();
+        assert!(creator.full_text_creators[0].1.is_aborted());
+        creator.finish().await.unwrap();
+
+        // The file has no full-text index of the column so it isn't pruned.
+        let applier = SstIndexApplierBuilder::new(
+            region_dir,
+            object_store,
+            None,
+            &region_metadata,
+            Default::default(),
+        )
+        .with_full_text_column_ids(HashSet::from([4]))
+        .build(&[col("message").like(lit("%error%")).into()])
+        .unwrap()
+        .unwrap();
+        let output = applier
+            .apply(
+                sst_file_id,
+                &[IndexType::InvertedIndex, IndexType::FullTextIndex],
+            )
+            .await
+            .unwrap();
+        assert!(output.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_query_bloom_filter() {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1, 2));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "tag_str",
+                    ConcreteDataType::string_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Tag,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "tag_i32",
+                    ConcreteDataType::int32_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Tag,
+                column_id: 2,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 3,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "trace_id",
+                    ConcreteDataType::string_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 4,
+            })
+            .primary_key(vec![1, 2]);
+        let region_metadata = Arc::new(builder.build().unwrap());
+
+        let region_dir = "region0".to_string();
+        let sst_file_id = FileId::random();
+        let file_path = location::index_file_path(&region_dir, sst_file_id);
+        let object_store = mock_object_store();
+        let mut creator = SstIndexCreator::new(
+            file_path,
+            sst_file_id,
+            &region_metadata,
+            object_store.clone(),
+            mock_intm_mgr(),
+            None,
+            NonZeroUsize::new(2).unwrap(),
+        )
+        .with_bloom_filter_columns(vec![(4, ConcreteDataType::string_datatype())]);
+
+        let rows = [
+            ("aaa", 1, [Some("trace-1"), Some("trace-2")]),
+            ("aaa", 2, [None, Some("trace-3")]),
+            ("abc", 1, [Some("trace-1"), Some("trace-4")]),
+        ];
+        for (str_tag, i32_tag, trace_ids) in rows {
+            let batch = new_batch(trace_ids.len(), str_tag, i32_tag)
+                .with_fields(vec![BatchColumn {
+                    column_id: 4,
+                    data: Arc::new(StringVector::from(trace_ids.to_vec())),
+                }])
+                .unwrap();
+            creator.update(&batch).await.unwrap();
+        }
+        assert_eq!(
+            &[IndexType::InvertedIndex, IndexType::BloomFilterIndex],
+            creator.index_types().as_slice()
+        );
+        creator.finish().await.unwrap();
+
+        let apply = |expr: DfExpr| {
+            let applier = SstIndexApplierBuilder::new(
+                region_dir.clone(),
+                object_store.clone(),
+                None,
+                &region_metadata,
+                Default::default(),
+            )
+            .with_bloom_filter_column_ids(HashSet::from([4]))
+            .build(&[expr.into()])
+            .unwrap()
+            .unwrap();
+            async move {
+                applier
+                    .apply(
+                        sst_file_id,
+                        &[IndexType::InvertedIndex, IndexType::BloomFilterIndex],
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .matched_segment_ids
+                    .iter_ones()
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let res = apply(col("trace_id").eq(lit("trace-1"))).await;
+        assert_eq!(res, vec![0, 2]);
+
+        let res = apply(col("trace_id").in_list(vec![lit("trace-3"), lit("trace-4")], false)).await;
+        assert_eq!(res, vec![1, 2]);
+
+        let res = apply(
+            col("trace_id")
+                .eq(lit("trace-1"))
+                .and(col("tag_str").eq(lit("abc"))),
+        )
+        .await;
+        assert_eq!(res, vec![2]);
+
+        let res = apply(col("trace_id").eq(lit("trace-5"))).await;
+        assert!(res.is_empty());
+    }
 }
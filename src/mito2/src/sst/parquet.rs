@@ -28,8 +28,9 @@ use std::sync::Arc;
 
 use common_base::readable_size::ReadableSize;
 use parquet::file::metadata::ParquetMetaData;
+use smallvec::SmallVec;
 
-use crate::sst::file::FileTimeRange;
+use crate::sst::file::{FileTimeRange, IndexType};
 use crate::sst::DEFAULT_WRITE_BUFFER_SIZE;
 
 /// Key of metadata in parquet SST.
@@ -68,8 +69,8 @@ pub struct SstInfo {
     pub num_rows: usize,
     /// File Meta Data
     pub file_metadata: Option<Arc<ParquetMetaData>>,
-    /// Whether inverted index is available.
-    pub inverted_index_available: bool,
+    /// Indexes available in the index file.
+    pub available_indexes: SmallVec<[IndexType; 4]>,
     /// Index file size in bytes.
     pub index_file_size: u64,
 }
@@ -77,13 +78,17 @@ pub struct SstInfo {
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
+    use common_test_util::temp_dir::create_temp_dir;
     use common_time::Timestamp;
     use datafusion_common::{Column, ScalarValue};
     use datafusion_expr::{BinaryExpr, Expr, Operator};
     use table::predicate::Predicate;
 
     use super::*;
+    use crate::cache::file_cache::{FileType, IndexKey};
+    use crate::cache::test_util::new_fs_store;
     use crate::cache::{CacheManager, PageKey};
     use crate::sst::index::Indexer;
     use crate::sst::parquet::reader::ParquetReaderBuilder;
@@ -222,6 +227,82 @@ mod tests {
         assert!(cache.as_ref().unwrap().get_pages(&page_key).is_none());
     }
 
+    #[tokio::test]
+    async fn test_read_populates_file_cache() {
+        let mut env = TestEnv::new();
+        let object_store = env.init_object_store_manager();
+        let handle = sst_file_handle(0, 1000);
+        let file_path = handle.file_path(FILE_DIR);
+        let metadata = Arc::new(sst_region_metadata());
+        let source = new_source(&[
+            new_batch_by_range(&["a", "d"], 0, 60),
+            new_batch_by_range(&["b", "f"], 0, 40),
+        ]);
+        let mut writer = ParquetWriter::new(
+            file_path,
+            metadata.clone(),
+            object_store.clone(),
+            Indexer::default(),
+        );
+        let info = writer
+            .write_all(source, &WriteOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Enable the write cache and the read cache.
+        let local_dir = create_temp_dir("");
+        let local_store = new_fs_store(local_dir.path().to_str().unwrap());
+        let write_cache = env
+            .create_write_cache(local_store, ReadableSize::mb(10))
+            .await;
+        let cache = Some(Arc::new(
+            CacheManager::builder()
+                .write_cache(Some(write_cache.clone()))
+                .read_cache(true)
+                .build(),
+        ));
+        let builder = ParquetReaderBuilder::new(FILE_DIR.to_string(), handle.clone(), object_store)
+            .cache(cache);
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch_by_range(&["a", "d"], 0, 60),
+                new_batch_by_range(&["b", "f"], 0, 40),
+            ],
+        )
+        .await;
+
+        // The read downloads the file to the file cache in background.
+        let key = IndexKey::new(metadata.region_id, handle.file_id(), FileType::Parquet);
+        let file_cache = write_cache.file_cache();
+        for _ in 0..100 {
+            if file_cache.contains_key(&key) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(file_cache.contains_key(&key));
+        let cached = file_cache
+            .local_store()
+            .read(&file_cache.cache_file_path(key))
+            .await
+            .unwrap();
+        assert_eq!(info.file_size, cached.len() as u64);
+
+        // Reads from the file cache later.
+        let mut reader = builder.build().await.unwrap();
+        check_reader_result(
+            &mut reader,
+            &[
+                new_batch_by_range(&["a", "d"], 0, 60),
+                new_batch_by_range(&["b", "f"], 0, 40),
+            ],
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn test_parquet_metadata_eq() {
         // create test env
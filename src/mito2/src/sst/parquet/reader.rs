@@ -300,12 +300,13 @@ impl ParquetReaderBuilder {
             return None;
         };
 
-        if !self.file_handle.meta().inverted_index_available() {
-            return None;
-        }
-
-        let output = match index_applier.apply(self.file_handle.file_id()).await {
-            Ok(output) => output,
+        let file_meta = self.file_handle.meta();
+        let output = match index_applier
+            .apply(file_meta.index_id(), &file_meta.available_indexes)
+            .await
+        {
+            Ok(Some(output)) => output,
+            Ok(None) => return None,
             Err(err) => {
                 if cfg!(any(test, feature = "test")) {
                     panic!(
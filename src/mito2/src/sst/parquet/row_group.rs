@@ -239,6 +239,10 @@ impl<'a> InMemoryRowGroup<'a> {
                 let data = fetch_byte_ranges(self.file_path, self.object_store.clone(), ranges)
                     .await
                     .map_err(|e| ParquetError::External(Box::new(e)))?;
+                // Caches the whole file so later reads are served locally.
+                if let Some(cache) = &self.cache_manager {
+                    cache.maybe_cache_remote_file(key, self.file_path, &self.object_store);
+                }
                 Ok(data)
             }
         }
@@ -109,9 +109,7 @@ impl ParquetWriter {
             self.indexer.update(&batch).await;
         }
 
-        let index_size = self.indexer.finish().await;
-        let inverted_index_available = index_size.is_some();
-        let index_file_size = index_size.unwrap_or(0) as u64;
+        let index_output = self.indexer.finish().await;
 
         if stats.num_rows == 0 {
             debug!(
@@ -137,8 +135,8 @@ impl ParquetWriter {
             file_size,
             num_rows: stats.num_rows,
             file_metadata: Some(Arc::new(parquet_metadata)),
-            inverted_index_available,
-            index_file_size,
+            available_indexes: index_output.available_indexes,
+            index_file_size: index_output.file_size as u64,
         }))
     }
 
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 //! SST version.
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
@@ -46,6 +47,9 @@ impl SstVersion {
 
     /// Add files to the version.
     ///
+    /// If the version already contains a file with a different index, replaces
+    /// its handle with a handle of the new index.
+    ///
     /// # Panics
     /// Panics if level of [FileMeta] is greater than [MAX_LEVEL].
     pub(crate) fn add_files(
@@ -55,13 +59,41 @@ impl SstVersion {
     ) {
         for file in files_to_add {
             let level = file.level;
+            match self.levels[level as usize].files.entry(file.file_id) {
+                Entry::Occupied(mut entry) => {
+                    if !same_index(&entry.get().meta(), &file) {
+                        let handle = entry.get().with_index(file);
+                        entry.insert(handle);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(FileHandle::new(file, file_purger.clone()));
+                }
+            }
+        }
+    }
+
+    /// Add handles of files to the version.
+    ///
+    /// # Panics
+    /// Panics if level of the file is greater than [MAX_LEVEL].
+    pub(crate) fn add_file_handles(&mut self, handles: impl Iterator<Item = FileHandle>) {
+        for handle in handles {
+            let level = handle.level();
             self.levels[level as usize]
                 .files
-                .entry(file.file_id)
-                .or_insert_with(|| FileHandle::new(file, file_purger.clone()));
+                .entry(handle.file_id())
+                .or_insert(handle);
         }
     }
 
+    /// Returns the handle of the file if the version contains it.
+    pub(crate) fn get_file(&self, file: &FileMeta) -> Option<&FileHandle> {
+        self.levels
+            .get(file.level as usize)
+            .and_then(|level_meta| level_meta.files.get(&file.file_id))
+    }
+
     /// Remove files from the version.
     ///
     /// # Panics
@@ -158,9 +190,17 @@ fn new_level_meta_vec() -> LevelMetaArray {
         .unwrap() // safety: LevelMetaArray is a fixed length array with length MAX_LEVEL
 }
 
+/// Returns true if both metas have the same index.
+fn same_index(a: &FileMeta, b: &FileMeta) -> bool {
+    a.index_file_id == b.index_file_id
+        && a.index_file_size == b.index_file_size
+        && a.available_indexes == b.available_indexes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sst::file::IndexType;
     use crate::test_util::new_noop_file_purger;
 
     #[test]
@@ -185,4 +225,28 @@ mod tests {
             assert!(added_files.contains_key(&f.file_id));
         });
     }
+
+    #[test]
+    fn test_add_files_with_new_index() {
+        let purger = new_noop_file_purger();
+        let file = FileMeta {
+            file_id: FileId::random(),
+            ..Default::default()
+        };
+
+        let mut version = SstVersion::new();
+        version.add_files(purger.clone(), std::iter::once(file.clone()));
+        let old_handle = version.get_file(&file).unwrap().clone();
+
+        let new_file = FileMeta {
+            available_indexes: [IndexType::InvertedIndex].into_iter().collect(),
+            index_file_size: 1024,
+            index_file_id: Some(FileId::random()),
+            ..file.clone()
+        };
+        version.add_files(purger, std::iter::once(new_file.clone()));
+        assert_eq!(new_file, version.get_file(&file).unwrap().meta());
+        // The old handle still has the old index.
+        assert_eq!(file, old_handle.meta());
+    }
 }
@@ -111,6 +111,8 @@ pub fn sst_file_handle(start_ms: i64, end_ms: i64) -> FileHandle {
             file_size: 0,
             available_indexes: Default::default(),
             index_file_size: 0,
+            index_file_id: None,
+            downsample_interval: None,
         },
         file_purger,
     )
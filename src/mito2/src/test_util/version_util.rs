@@ -94,6 +94,8 @@ impl VersionControlBuilder {
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                index_file_id: None,
+                downsample_interval: None,
             },
         );
         self
@@ -136,6 +138,8 @@ pub(crate) fn apply_edit(
                 file_size: 0, // We don't care file size.
                 available_indexes: Default::default(),
                 index_file_size: 0,
+                index_file_id: None,
+                downsample_interval: None,
             }
         })
         .collect();
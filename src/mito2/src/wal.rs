@@ -110,6 +110,29 @@ impl<S: LogStore> Wal<S> {
             .map_err(BoxedError::new)
             .context(DeleteWalSnafu { region_id })
     }
+
+    /// Registers a newly created region to the WAL.
+    pub async fn create_region(&self, region_id: RegionId, wal_options: &WalOptions) -> Result<()> {
+        let namespace = self.store.namespace(region_id.into(), wal_options);
+        self.store
+            .create_namespace(&namespace)
+            .await
+            .map_err(BoxedError::new)
+            .context(CreateWalSnafu { region_id })
+    }
+
+    /// Removes the region from the WAL when the region is dropped.
+    ///
+    /// Entries of the region are not deleted, but the region no longer prevents
+    /// the log store from deleting entries obsoleted by other regions.
+    pub async fn remove_region(&self, region_id: RegionId, wal_options: &WalOptions) -> Result<()> {
+        let namespace = self.store.namespace(region_id.into(), wal_options);
+        self.store
+            .delete_namespace(&namespace)
+            .await
+            .map_err(BoxedError::new)
+            .context(DeleteWalSnafu { region_id })
+    }
 }
 
 /// Decode Wal entry from log store.
@@ -21,7 +21,10 @@ mod handle_compaction;
 mod handle_create;
 mod handle_drop;
 mod handle_flush;
+mod handle_gc;
+mod handle_index_build;
 mod handle_open;
+mod handle_snapshot;
 mod handle_truncate;
 mod handle_write;
 
@@ -40,6 +43,7 @@ use store_api::storage::RegionId;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
+use crate::cache::warm_up::{WarmUpScheduler, WarmUpSchedulerRef};
 use crate::cache::write_cache::{WriteCache, WriteCacheRef};
 use crate::cache::{CacheManager, CacheManagerRef};
 use crate::compaction::CompactionScheduler;
@@ -47,9 +51,7 @@ use crate::config::MitoConfig;
 use crate::error::{InvalidRequestSnafu, JoinSnafu, Result, WorkerStoppedSnafu};
 use crate::flush::{FlushScheduler, WriteBufferManagerImpl, WriteBufferManagerRef};
 use crate::manifest::action::RegionEdit;
-use crate::memtable::merge_tree::MergeTreeMemtableBuilder;
-use crate::memtable::time_series::TimeSeriesMemtableBuilder;
-use crate::memtable::MemtableBuilderRef;
+use crate::memtable::MemtableBuilderProvider;
 use crate::region::{MitoRegionRef, RegionMap, RegionMapRef};
 use crate::request::{
     BackgroundNotify, DdlRequest, SenderDdlRequest, SenderWriteRequest, WorkerRequest,
@@ -105,6 +107,10 @@ pub(crate) struct WorkerGroup {
     workers: Vec<RegionWorker>,
     /// Global background job scheduelr.
     scheduler: SchedulerRef,
+    /// Scheduler for cache warm-ups.
+    warm_up_scheduler: WarmUpSchedulerRef,
+    /// Scheduler for index build jobs.
+    index_build_scheduler: SchedulerRef,
     /// Cache.
     cache_manager: CacheManagerRef,
 }
@@ -126,6 +132,9 @@ impl WorkerGroup {
                 .await?
                 .with_buffer_size(Some(config.inverted_index.write_buffer_size.as_bytes() as _));
         let scheduler = Arc::new(LocalScheduler::new(config.max_background_jobs));
+        let warm_up_scheduler = Arc::new(WarmUpScheduler::new(&config));
+        let index_build_scheduler =
+            Arc::new(LocalScheduler::new(config.max_background_index_builds));
         let write_cache = write_cache_from_config(
             &config,
             object_store_manager.clone(),
@@ -138,6 +147,7 @@ impl WorkerGroup {
                 .vector_cache_size(config.vector_cache_size.as_bytes())
                 .page_cache_size(config.page_cache_size.as_bytes())
                 .write_cache(write_cache)
+                .read_cache(config.enable_experimental_read_cache)
                 .build(),
         );
 
@@ -150,6 +160,8 @@ impl WorkerGroup {
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
                     scheduler: scheduler.clone(),
+                    warm_up_scheduler: warm_up_scheduler.clone(),
+                    index_build_scheduler: index_build_scheduler.clone(),
                     listener: WorkerListener::default(),
                     cache_manager: cache_manager.clone(),
                     intermediate_manager: intermediate_manager.clone(),
@@ -161,6 +173,8 @@ impl WorkerGroup {
         Ok(WorkerGroup {
             workers,
             scheduler,
+            warm_up_scheduler,
+            index_build_scheduler,
             cache_manager,
         })
     }
@@ -171,6 +185,9 @@ impl WorkerGroup {
 
         // Stops the scheduler gracefully.
         self.scheduler.stop(true).await?;
+        self.warm_up_scheduler.stop().await?;
+        // Index build jobs can be restarted so we don't wait for them.
+        self.index_build_scheduler.stop(false).await?;
 
         try_join_all(self.workers.iter().map(|worker| worker.stop())).await?;
 
@@ -230,6 +247,9 @@ impl WorkerGroup {
             ))
         });
         let scheduler = Arc::new(LocalScheduler::new(config.max_background_jobs));
+        let warm_up_scheduler = Arc::new(WarmUpScheduler::new(&config));
+        let index_build_scheduler =
+            Arc::new(LocalScheduler::new(config.max_background_index_builds));
         let intermediate_manager =
             IntermediateManager::init_fs(&config.inverted_index.intermediate_path)
                 .await?
@@ -246,6 +266,7 @@ impl WorkerGroup {
                 .vector_cache_size(config.vector_cache_size.as_bytes())
                 .page_cache_size(config.page_cache_size.as_bytes())
                 .write_cache(write_cache)
+                .read_cache(config.enable_experimental_read_cache)
                 .build(),
         );
         let workers = (0..config.num_workers)
@@ -257,6 +278,8 @@ impl WorkerGroup {
                     object_store_manager: object_store_manager.clone(),
                     write_buffer_manager: write_buffer_manager.clone(),
                     scheduler: scheduler.clone(),
+                    warm_up_scheduler: warm_up_scheduler.clone(),
+                    index_build_scheduler: index_build_scheduler.clone(),
                     listener: WorkerListener::new(listener.clone()),
                     cache_manager: cache_manager.clone(),
                     intermediate_manager: intermediate_manager.clone(),
@@ -268,6 +291,8 @@ impl WorkerGroup {
         Ok(WorkerGroup {
             workers,
             scheduler,
+            warm_up_scheduler,
+            index_build_scheduler,
             cache_manager,
         })
     }
@@ -308,6 +333,8 @@ struct WorkerStarter<S> {
     object_store_manager: ObjectStoreManagerRef,
     write_buffer_manager: WriteBufferManagerRef,
     scheduler: SchedulerRef,
+    warm_up_scheduler: WarmUpSchedulerRef,
+    index_build_scheduler: SchedulerRef,
     listener: WorkerListener,
     cache_manager: CacheManagerRef,
     intermediate_manager: IntermediateManager,
@@ -320,16 +347,10 @@ impl<S: LogStore> WorkerStarter<S> {
         let (sender, receiver) = mpsc::channel(self.config.worker_channel_size);
 
         let running = Arc::new(AtomicBool::new(true));
-        let memtable_builder = if let Some(config) = &self.config.experimental_memtable {
-            Arc::new(MergeTreeMemtableBuilder::new(
-                config.clone(),
-                Some(self.write_buffer_manager.clone()),
-            )) as _
-        } else {
-            Arc::new(TimeSeriesMemtableBuilder::new(Some(
-                self.write_buffer_manager.clone(),
-            ))) as _
-        };
+        let memtable_builder_provider = MemtableBuilderProvider::new(
+            Some(self.write_buffer_manager.clone()),
+            self.config.clone(),
+        );
         let mut worker_thread = RegionWorkerLoop {
             id: self.id,
             config: self.config,
@@ -340,8 +361,10 @@ impl<S: LogStore> WorkerStarter<S> {
             wal: Wal::new(self.log_store),
             object_store_manager: self.object_store_manager.clone(),
             running: running.clone(),
-            memtable_builder,
+            memtable_builder_provider,
             scheduler: self.scheduler.clone(),
+            warm_up_scheduler: self.warm_up_scheduler,
+            index_build_scheduler: self.index_build_scheduler,
             write_buffer_manager: self.write_buffer_manager,
             flush_scheduler: FlushScheduler::new(self.scheduler.clone()),
             compaction_scheduler: CompactionScheduler::new(
@@ -493,10 +516,14 @@ struct RegionWorkerLoop<S> {
     object_store_manager: ObjectStoreManagerRef,
     /// Whether the worker thread is still running.
     running: Arc<AtomicBool>,
-    /// Memtable builder for each region.
-    memtable_builder: MemtableBuilderRef,
+    /// Provides memtable builders for regions.
+    memtable_builder_provider: MemtableBuilderProvider,
     /// Background job scheduler.
     scheduler: SchedulerRef,
+    /// Scheduler for cache warm-ups.
+    warm_up_scheduler: WarmUpSchedulerRef,
+    /// Scheduler for index build jobs.
+    index_build_scheduler: SchedulerRef,
     /// Engine write buffer manager.
     write_buffer_manager: WriteBufferManagerRef,
     /// Schedules background flush requests.
@@ -521,11 +548,27 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         // Buffer to retrieve requests from receiver.
         let mut buffer = RequestBuffer::with_capacity(self.config.worker_request_batch_size);
 
+        // Deadline to collect orphan files of regions in this worker.
+        let mut next_gc_time = self.next_orphan_file_gc_time();
+
         while self.running.load(Ordering::Relaxed) {
             // Clear the buffer before handling next batch of requests.
             buffer.clear();
 
-            match self.receiver.recv().await {
+            let request = match next_gc_time {
+                Some(gc_time) => {
+                    tokio::select! {
+                        request = self.receiver.recv() => request,
+                        _ = tokio::time::sleep_until(gc_time) => {
+                            self.schedule_orphan_file_gc();
+                            next_gc_time = self.next_orphan_file_gc_time();
+                            continue;
+                        }
+                    }
+                }
+                None => self.receiver.recv().await,
+            };
+            match request {
                 Some(request) => buffer.push(request),
                 None => break,
             }
@@ -579,6 +622,36 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                         warn!("Failed to send edit region error to caller, error: {e:?}");
                     }
                 }
+                WorkerRequest::CreateSnapshot {
+                    region_id,
+                    name,
+                    tx,
+                } => {
+                    let result = self.handle_create_snapshot(region_id, name).await;
+                    if let Err(Err(e)) = tx.send(result) {
+                        warn!("Failed to send create snapshot error to caller, error: {e:?}");
+                    }
+                }
+                WorkerRequest::DropSnapshot {
+                    region_id,
+                    name,
+                    tx,
+                } => {
+                    let result = self.handle_drop_snapshot(region_id, name).await;
+                    if let Err(Err(e)) = tx.send(result) {
+                        warn!("Failed to send drop snapshot error to caller, error: {e:?}");
+                    }
+                }
+                WorkerRequest::BuildIndex {
+                    region_id,
+                    rebuild_all,
+                    tx,
+                } => {
+                    let result = self.handle_build_index_request(region_id, rebuild_all);
+                    if let Err(Err(e)) = tx.send(result) {
+                        warn!("Failed to send build index error to caller, error: {e:?}");
+                    }
+                }
                 // We receive a stop signal, but we still want to process remaining
                 // requests. The worker thread will then check the running flag and
                 // then exit.
@@ -640,6 +713,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 self.handle_compaction_finished(region_id, req).await
             }
             BackgroundNotify::CompactionFailed(req) => self.handle_compaction_failure(req).await,
+            BackgroundNotify::IndexBuilt(req) => self.handle_index_built(region_id, req).await,
         }
     }
 
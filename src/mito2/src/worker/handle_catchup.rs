@@ -51,7 +51,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                 RegionOpener::new(
                     region_id,
                     region.region_dir(),
-                    self.memtable_builder.clone(),
+                    self.memtable_builder_provider.clone(),
                     self.object_store_manager.clone(),
                     self.scheduler.clone(),
                     self.intermediate_manager.clone(),
@@ -100,6 +100,8 @@ impl<S: LogStore> RegionWorkerLoop<S> {
 
         if request.set_writable {
             region.set_writable(true);
+            // The region becomes the leader, e.g. upgraded by region migration.
+            self.maybe_warm_up_region(&region);
         }
 
         Ok(0)
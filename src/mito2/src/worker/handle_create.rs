@@ -26,6 +26,7 @@ use store_api::storage::RegionId;
 use crate::error::{InvalidMetadataSnafu, Result};
 use crate::metrics::REGION_COUNT;
 use crate::region::opener::{check_recovered_region, RegionOpener};
+use crate::region::options::RegionOptions;
 use crate::worker::RegionWorkerLoop;
 
 impl<S: LogStore> RegionWorkerLoop<S> {
@@ -54,17 +55,19 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         }
         builder.primary_key(request.primary_key);
         let metadata = builder.build().context(InvalidMetadataSnafu)?;
+        let options = RegionOptions::try_from(&request.options)?;
+        options.downsample.validate(&metadata)?;
         // Create a MitoRegion from the RegionMetadata.
         let region = RegionOpener::new(
             region_id,
             &request.region_dir,
-            self.memtable_builder.clone(),
+            self.memtable_builder_provider.clone(),
             self.object_store_manager.clone(),
             self.scheduler.clone(),
             self.intermediate_manager.clone(),
         )
         .metadata(metadata)
-        .parse_options(request.options)?
+        .options(options)
         .cache(Some(self.cache_manager.clone()))
         .create_or_open(&self.config, &self.wal)
         .await?;
@@ -21,6 +21,7 @@ use futures::TryStreamExt;
 use object_store::util::join_path;
 use object_store::{EntryMode, ObjectStore};
 use snafu::ResultExt;
+use store_api::logstore::LogStore;
 use store_api::region_request::AffectedRows;
 use store_api::storage::RegionId;
 use tokio::time::sleep;
@@ -33,7 +34,7 @@ use crate::worker::{RegionWorkerLoop, DROPPING_MARKER_FILE};
 const GC_TASK_INTERVAL_SEC: u64 = 5 * 60; // 5 minutes
 const MAX_RETRY_TIMES: u64 = 288; // 24 hours (5m * 288)
 
-impl<S> RegionWorkerLoop<S> {
+impl<S: LogStore> RegionWorkerLoop<S> {
     pub(crate) async fn handle_drop_request(
         &mut self,
         region_id: RegionId,
@@ -59,9 +60,14 @@ impl<S> RegionWorkerLoop<S> {
         self.flush_scheduler.on_region_dropped(region_id);
         // Notifies compaction scheduler.
         self.compaction_scheduler.on_region_dropped(region_id);
+        if let Err(e) = self.wal.remove_region(region_id, &region.wal_options).await {
+            warn!(e; "Failed to remove region {} from wal", region_id);
+        }
 
         // mark region version as dropped
-        region.version_control.mark_dropped(&self.memtable_builder);
+        region
+            .version_control
+            .mark_dropped(&region.memtable_builder);
         info!(
             "Region {} is dropped logically, but some files are not deleted yet",
             region_id
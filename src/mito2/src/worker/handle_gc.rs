@@ -0,0 +1,53 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling orphan file gc.
+
+use common_telemetry::error;
+use store_api::logstore::LogStore;
+use tokio::time::Instant;
+
+use crate::gc::collect_orphan_files;
+use crate::worker::RegionWorkerLoop;
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Returns the time to collect orphan files next time, or None if the gc is disabled.
+    pub(crate) fn next_orphan_file_gc_time(&self) -> Option<Instant> {
+        self.config
+            .orphan_file_gc_interval
+            .map(|interval| Instant::now() + interval)
+    }
+
+    /// Schedules background jobs to collect orphan files of regions in this worker.
+    ///
+    /// Only the leader region deletes files as followers don't own the region directory.
+    pub(crate) fn schedule_orphan_file_gc(&mut self) {
+        let grace_period = self.config.orphan_file_grace_period;
+        for region in self.regions.list_regions() {
+            if !region.is_writable() {
+                continue;
+            }
+
+            let region_id = region.region_id;
+            if let Err(e) = self.scheduler.schedule(Box::pin(async move {
+                if let Err(e) = collect_orphan_files(&region, grace_period, false).await {
+                    error!(e; "Failed to collect orphan files of region {}", region.region_id);
+                }
+            })) {
+                error!(e; "Failed to schedule orphan file gc for region {}", region_id);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling index build requests.
+
+use common_telemetry::{info, warn};
+use snafu::ensure;
+use store_api::logstore::LogStore;
+use store_api::storage::RegionId;
+
+use crate::error::{InvalidRequestSnafu, Result};
+use crate::index_build::IndexBuildJob;
+use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
+use crate::request::IndexBuilt;
+use crate::sst::file::FileMeta;
+use crate::worker::RegionWorkerLoop;
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Schedules a background job to build indexes for SSTs in the region.
+    pub(crate) fn handle_build_index_request(
+        &mut self,
+        region_id: RegionId,
+        rebuild_all: bool,
+    ) -> Result<()> {
+        let region = self.regions.writable_region(region_id)?;
+        ensure!(
+            !region.index_build.is_running(),
+            InvalidRequestSnafu {
+                region_id,
+                reason: "index build is already running",
+            }
+        );
+
+        let version = region.version();
+        let files: Vec<_> = version
+            .ssts
+            .levels()
+            .iter()
+            .flat_map(|level| level.files().cloned())
+            .collect();
+        region.index_build.start(region_id, files.len());
+        info!(
+            "Schedule index build for region {}, files: {}, rebuild_all: {}",
+            region_id,
+            files.len(),
+            rebuild_all
+        );
+
+        let job = IndexBuildJob {
+            region: region.clone(),
+            files,
+            rebuild_all,
+            request_sender: self.sender.clone(),
+            cache_manager: self.cache_manager.clone(),
+            engine_config: self.config.clone(),
+        };
+        if let Err(e) = self.index_build_scheduler.schedule(Box::pin(job.run())) {
+            region.index_build.finish();
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Commits the new index of a SST to the region.
+    pub(crate) async fn handle_index_built(&mut self, region_id: RegionId, request: IndexBuilt) {
+        let result = self.commit_index(region_id, &request.file_meta).await;
+        if let Err(Err(e)) = request.sender.send(result) {
+            warn!(e; "Failed to send index commit result for region {}", region_id);
+        }
+    }
+
+    /// Returns false if the file is no longer in the region or is being compacted.
+    async fn commit_index(&self, region_id: RegionId, file_meta: &FileMeta) -> Result<bool> {
+        let region = self.regions.writable_region(region_id)?;
+        let version = region.version();
+        let Some(file) = version.ssts.get_file(file_meta) else {
+            return Ok(false);
+        };
+        // The compaction replaces the file so the index is useless.
+        if file.compacting() {
+            return Ok(false);
+        }
+
+        let edit = RegionEdit {
+            files_to_add: vec![file_meta.clone()],
+            files_to_remove: vec![],
+            compaction_time_window: None,
+            flushed_entry_id: None,
+            flushed_sequence: None,
+        };
+        let action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit.clone()));
+        region.manifest_manager.update(action_list).await?;
+        // Replaces the handle in the version with a handle of the new index. The
+        // old index is purged after snapshots and queries release the old handle.
+        region
+            .version_control
+            .apply_edit(edit, &[], region.file_purger.clone());
+
+        Ok(true)
+    }
+}
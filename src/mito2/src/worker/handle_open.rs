@@ -16,7 +16,7 @@
 
 use std::sync::Arc;
 
-use common_telemetry::info;
+use common_telemetry::{error, info};
 use object_store::util::join_path;
 use snafu::{OptionExt, ResultExt};
 use store_api::logstore::LogStore;
@@ -26,6 +26,7 @@ use store_api::storage::RegionId;
 use crate::error::{ObjectStoreNotFoundSnafu, OpenDalSnafu, RegionNotFoundSnafu, Result};
 use crate::metrics::REGION_COUNT;
 use crate::region::opener::RegionOpener;
+use crate::region::MitoRegionRef;
 use crate::worker::handle_drop::remove_region_dir_once;
 use crate::worker::{RegionWorkerLoop, DROPPING_MARKER_FILE};
 
@@ -65,7 +66,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         let region = RegionOpener::new(
             region_id,
             &request.region_dir,
-            self.memtable_builder.clone(),
+            self.memtable_builder_provider.clone(),
             self.object_store_manager.clone(),
             self.scheduler.clone(),
             self.intermediate_manager.clone(),
@@ -81,8 +82,24 @@ impl<S: LogStore> RegionWorkerLoop<S> {
         REGION_COUNT.inc();
 
         // Insert the MitoRegion into the RegionMap.
-        self.regions.insert_region(Arc::new(region));
+        let region = Arc::new(region);
+        self.regions.insert_region(region.clone());
+        self.maybe_warm_up_region(&region);
 
         Ok(0)
     }
+
+    /// Schedules a background job to warm up caches of the `region` if enabled.
+    pub(crate) fn maybe_warm_up_region(&self, region: &MitoRegionRef) {
+        if !self.config.enable_cache_warm_up {
+            return;
+        }
+
+        if let Err(e) = self
+            .warm_up_scheduler
+            .schedule(region, self.cache_manager.clone())
+        {
+            error!(e; "Failed to schedule cache warm-up for region {}", region.region_id);
+        }
+    }
 }
@@ -0,0 +1,137 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handling snapshot related requests.
+
+use common_telemetry::{info, warn};
+use snafu::{ensure, OptionExt};
+use store_api::logstore::LogStore;
+use store_api::storage::RegionId;
+
+use crate::error::{InvalidRequestSnafu, Result, SnapshotNotFoundSnafu};
+use crate::manifest::action::{
+    RegionDropSnapshot, RegionMetaAction, RegionMetaActionList, RegionSnapshot,
+};
+use crate::worker::RegionWorkerLoop;
+
+impl<S: LogStore> RegionWorkerLoop<S> {
+    /// Creates a snapshot named `name` of the region's latest manifest.
+    pub(crate) async fn handle_create_snapshot(
+        &mut self,
+        region_id: RegionId,
+        name: String,
+    ) -> Result<RegionSnapshot> {
+        let region = self.regions.writable_region(region_id)?;
+        ensure!(
+            is_valid_snapshot_name(&name),
+            InvalidRequestSnafu {
+                region_id,
+                reason: format!("invalid snapshot name {name}"),
+            }
+        );
+        ensure!(
+            !region.snapshots.contains(&name),
+            InvalidRequestSnafu {
+                region_id,
+                reason: format!("snapshot {name} already exists"),
+            }
+        );
+
+        let manifest = region.manifest_manager.manifest().await;
+        let snapshot = manifest.to_snapshot(name);
+        let files = manifest.snapshot_files();
+        // Saves files before the snapshot is visible in the manifest.
+        region
+            .manifest_manager
+            .save_snapshot_files(&snapshot.name, &files)
+            .await?;
+        let action_list =
+            RegionMetaActionList::with_action(RegionMetaAction::CreateSnapshot(snapshot.clone()));
+        if let Err(e) = region.manifest_manager.update(action_list).await {
+            if let Err(delete_err) = region
+                .manifest_manager
+                .delete_snapshot_files(&snapshot.name)
+                .await
+            {
+                warn!(delete_err; "Failed to delete files of snapshot {} in region {}", snapshot.name, region_id);
+            }
+            return Err(e);
+        }
+
+        let num_files = files.files.len();
+        let version = region.version();
+        region
+            .snapshots
+            .pin(snapshot.clone(), files, &version.ssts, &region.file_purger);
+
+        info!(
+            "Created snapshot {} for region {} at manifest version {}, files: {}",
+            snapshot.name, region_id, snapshot.manifest_version, num_files
+        );
+
+        Ok(snapshot)
+    }
+
+    /// Drops the snapshot named `name` and releases files only referenced by it.
+    pub(crate) async fn handle_drop_snapshot(
+        &mut self,
+        region_id: RegionId,
+        name: String,
+    ) -> Result<()> {
+        let region = self.regions.writable_region(region_id)?;
+        ensure!(
+            region.snapshots.contains(&name),
+            SnapshotNotFoundSnafu {
+                region_id,
+                name: &name,
+            }
+        );
+
+        let action_list =
+            RegionMetaActionList::with_action(RegionMetaAction::DropSnapshot(RegionDropSnapshot {
+                name: name.clone(),
+            }));
+        region.manifest_manager.update(action_list).await?;
+
+        let pinned = region
+            .snapshots
+            .remove(&name)
+            .context(SnapshotNotFoundSnafu {
+                region_id,
+                name: &name,
+            })?;
+        // The snapshot is already dropped from the manifest, a leftover file is harmless.
+        if let Err(e) = region.manifest_manager.delete_snapshot_files(&name).await {
+            warn!(e; "Failed to delete files of snapshot {} in region {}", name, region_id);
+        }
+        info!(
+            "Dropped snapshot {} of region {}, files: {}",
+            pinned.snapshot.name,
+            region_id,
+            pinned.files.len()
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns true if `name` can be used as the name of a snapshot.
+///
+/// The name is a part of the path of the snapshot file.
+fn is_valid_snapshot_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
@@ -17,11 +17,13 @@
 use std::collections::{hash_map, HashMap};
 use std::sync::Arc;
 
+use api::v1::OpType;
+use snafu::ensure;
 use store_api::logstore::LogStore;
 use store_api::metadata::RegionMetadata;
 use store_api::storage::RegionId;
 
-use crate::error::{RejectWriteSnafu, Result};
+use crate::error::{InvalidRequestSnafu, RejectWriteSnafu, Result};
 use crate::metrics::{
     WRITE_REJECT_TOTAL, WRITE_ROWS_TOTAL, WRITE_STAGE_ELAPSED, WRITE_STALL_TOTAL,
 };
@@ -171,6 +173,16 @@ impl<S> RegionWorkerLoop<S> {
                 continue;
             }
 
+            // Append mode regions never remove rows, so they can't handle deletes.
+            if let Err(e) = check_op_type(
+                region_ctx.version().options.append_mode,
+                &sender_req.request,
+            ) {
+                sender_req.sender.send(Err(e));
+
+                continue;
+            }
+
             // Collect requests by region.
             region_ctx.push_mutation(
                 sender_req.request.op_type as i32,
@@ -204,6 +216,21 @@ fn reject_write_requests(write_requests: Vec<SenderWriteRequest>) {
     }
 }
 
+/// Rejects delete requests if the region is in append mode.
+fn check_op_type(append_mode: bool, request: &WriteRequest) -> Result<()> {
+    if append_mode {
+        ensure!(
+            request.op_type == OpType::Put,
+            InvalidRequestSnafu {
+                region_id: request.region_id,
+                reason: "DELETE is not allowed under append mode",
+            }
+        );
+    }
+
+    Ok(())
+}
+
 /// Checks the schema and fill missing columns.
 fn maybe_fill_missing_columns(request: &mut WriteRequest, metadata: &RegionMetadata) -> Result<()> {
     if let Err(e) = request.check_schema(metadata) {
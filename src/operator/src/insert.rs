@@ -26,8 +26,10 @@ use common_catalog::consts::default_engine;
 use common_grpc_expr::util::{extract_new_columns, ColumnExpr};
 use common_meta::datanode_manager::{AffectedRows, DatanodeManagerRef};
 use common_meta::peer::Peer;
+use common_meta::table_name::TableName;
 use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use common_query::Output;
+use common_recordbatch::RecordBatch;
 use common_telemetry::tracing_context::TracingContext;
 use common_telemetry::{error, info};
 use datatypes::schema::Schema;
@@ -40,7 +42,7 @@ use sql::statements::insert::Insert;
 use store_api::metric_engine_consts::{
     LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME, PHYSICAL_TABLE_METADATA_KEY,
 };
-use table::requests::InsertRequest as TableInsertRequest;
+use table::requests::{InsertRequest as TableInsertRequest, APPEND_MODE_KEY};
 use table::table_reference::TableReference;
 use table::TableRef;
 
@@ -50,9 +52,21 @@ use crate::error::{
 };
 use crate::expr_factory::CreateExprFactory;
 use crate::region_req_factory::RegionRequestFactory;
-use crate::req_convert::insert::{ColumnToRow, RowToRegion, StatementToRegion, TableToRegion};
+use crate::req_convert::insert::{
+    ColumnToRow, RecordBatchToRegion, RowToRegion, StatementToRegion, TableToRegion,
+};
 use crate::statement::StatementExecutor;
 
+/// Type of the table to create when the table of an insert request doesn't exist.
+enum AutoCreateTableType {
+    /// A logical table of the metric engine on the given physical table.
+    Logical(String),
+    /// A regular table.
+    Physical,
+    /// A table in append mode to store logs.
+    Log,
+}
+
 pub struct Inserter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
@@ -86,10 +100,43 @@ impl Inserter {
     }
 
     pub async fn handle_row_inserts(
+        &self,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+        statement_executor: &StatementExecutor,
+    ) -> Result<Output> {
+        self.handle_row_inserts_with_create_type(
+            requests,
+            ctx,
+            statement_executor,
+            AutoCreateTableType::Physical,
+        )
+        .await
+    }
+
+    /// Handle row inserts request of logs. Tables that don't exist are created
+    /// in append mode, so log entries with the same timestamp are all kept.
+    pub async fn handle_log_inserts(
+        &self,
+        requests: RowInsertRequests,
+        ctx: QueryContextRef,
+        statement_executor: &StatementExecutor,
+    ) -> Result<Output> {
+        self.handle_row_inserts_with_create_type(
+            requests,
+            ctx,
+            statement_executor,
+            AutoCreateTableType::Log,
+        )
+        .await
+    }
+
+    async fn handle_row_inserts_with_create_type(
         &self,
         mut requests: RowInsertRequests,
         ctx: QueryContextRef,
         statement_executor: &StatementExecutor,
+        create_type: AutoCreateTableType,
     ) -> Result<Output> {
         // remove empty requests
         requests.inserts.retain(|req| {
@@ -100,7 +147,7 @@ impl Inserter {
         });
         validate_column_count_match(&requests)?;
 
-        self.create_or_alter_tables_on_demand(&requests, &ctx, None, statement_executor)
+        self.create_or_alter_tables_on_demand(&requests, &ctx, create_type, statement_executor)
             .await?;
         let inserts = RowToRegion::new(
             self.catalog_manager.as_ref(),
@@ -139,7 +186,7 @@ impl Inserter {
         self.create_or_alter_tables_on_demand(
             &requests,
             &ctx,
-            Some(physical_table.to_string()),
+            AutoCreateTableType::Logical(physical_table.to_string()),
             statement_executor,
         )
         .await?;
@@ -174,6 +221,34 @@ impl Inserter {
         Ok(affected_rows as _)
     }
 
+    /// Inserts the record batch into the table. Columns of the record batch must
+    /// exist in the table with the same data types.
+    pub async fn handle_record_batch_insert(
+        &self,
+        table_name: &TableName,
+        record_batch: &RecordBatch,
+        ctx: QueryContextRef,
+    ) -> Result<AffectedRows> {
+        let table = self
+            .get_table(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            )
+            .await?;
+        let table = table.with_context(|| TableNotFoundSnafu {
+            table_name: table_name.to_string(),
+        })?;
+        let table_info = table.table_info();
+
+        let inserts = RecordBatchToRegion::new(&table_info, &self.partition_manager)
+            .convert(record_batch)
+            .await?;
+
+        let affected_rows = self.do_request(inserts, &ctx).await?;
+        Ok(affected_rows as _)
+    }
+
     pub async fn handle_statement_insert(
         &self,
         insert: &Insert,
@@ -249,7 +324,7 @@ impl Inserter {
         &self,
         requests: &RowInsertRequests,
         ctx: &QueryContextRef,
-        on_physical_table: Option<String>,
+        create_type: AutoCreateTableType,
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
         let mut create_tables = vec![];
@@ -270,18 +345,28 @@ impl Inserter {
             }
         }
         if !create_tables.is_empty() {
-            if let Some(on_physical_table) = on_physical_table {
-                // Creates logical tables in batch.
-                self.create_logical_tables(
-                    create_tables,
-                    ctx,
-                    &on_physical_table,
-                    statement_executor,
-                )
-                .await?;
-            } else {
-                for req in create_tables {
-                    self.create_table(req, ctx, statement_executor).await?;
+            match create_type {
+                AutoCreateTableType::Logical(on_physical_table) => {
+                    // Creates logical tables in batch.
+                    self.create_logical_tables(
+                        create_tables,
+                        ctx,
+                        &on_physical_table,
+                        statement_executor,
+                    )
+                    .await?;
+                }
+                AutoCreateTableType::Physical => {
+                    for req in create_tables {
+                        self.create_table(req, ctx, false, statement_executor)
+                            .await?;
+                    }
+                }
+                AutoCreateTableType::Log => {
+                    for req in create_tables {
+                        self.create_table(req, ctx, true, statement_executor)
+                            .await?;
+                    }
                 }
             }
         }
@@ -414,11 +499,12 @@ impl Inserter {
 
     /// Create a table with schema from insert request.
     ///
-    /// To create a metric engine logical table, specify the `on_physical_table` parameter.
+    /// The table is created in append mode if `append_mode` is true.
     async fn create_table(
         &self,
         req: &RowInsertRequest,
         ctx: &QueryContextRef,
+        append_mode: bool,
         statement_executor: &StatementExecutor,
     ) -> Result<()> {
         let table_ref =
@@ -426,6 +512,11 @@ impl Inserter {
 
         let request_schema = req.rows.as_ref().unwrap().schema.as_slice();
         let create_table_expr = &mut build_create_table_expr(&table_ref, request_schema)?;
+        if append_mode {
+            create_table_expr
+                .table_options
+                .insert(APPEND_MODE_KEY.to_string(), "true".to_string());
+        }
 
         info!("Table `{table_ref}` does not exist, try creating table");
 
@@ -16,7 +16,7 @@ use async_trait::async_trait;
 use common_error::ext::BoxedError;
 use common_function::handlers::ProcedureServiceHandler;
 use common_meta::ddl::{ExecutorContext, ProcedureExecutorRef};
-use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureStateResponse};
+use common_meta::rpc::procedure::{MigrateRegionRequest, ProcedureDetail, ProcedureStateResponse};
 use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
 use snafu::ResultExt;
@@ -53,4 +53,20 @@ impl ProcedureServiceHandler for ProcedureServiceOperator {
             .map_err(BoxedError::new)
             .context(query_error::ProcedureServiceSnafu)
     }
+
+    async fn cancel_procedure(&self, pid: &str) -> QueryResult<()> {
+        self.procedure_executor
+            .cancel_procedure(&ExecutorContext::default(), pid)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::ProcedureServiceSnafu)
+    }
+
+    async fn list_procedures(&self) -> QueryResult<Vec<ProcedureDetail>> {
+        self.procedure_executor
+            .list_procedures(&ExecutorContext::default())
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::ProcedureServiceSnafu)
+    }
 }
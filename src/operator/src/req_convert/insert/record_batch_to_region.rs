@@ -0,0 +1,182 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::helper::ColumnDataTypeWrapper;
+use api::v1::region::InsertRequests as RegionInsertRequests;
+use api::v1::{ColumnSchema, Rows};
+use common_recordbatch::RecordBatch;
+use partition::manager::PartitionRuleManager;
+use snafu::{ensure, OptionExt, ResultExt};
+use table::metadata::TableInfo;
+
+use super::semantic_type;
+use crate::error::{ColumnDataTypeSnafu, ColumnNotFoundSnafu, InvalidInsertRequestSnafu, Result};
+use crate::req_convert::common::partitioner::Partitioner;
+
+/// Converts a [RecordBatch] into region insert requests of the table.
+///
+/// Unlike [TableToRegion](super::TableToRegion), columns are taken from the record
+/// batch in order and are checked against the table schema up front, so a mismatched
+/// batch is rejected before any region is touched.
+pub struct RecordBatchToRegion<'a> {
+    table_info: &'a TableInfo,
+    partition_manager: &'a PartitionRuleManager,
+}
+
+impl<'a> RecordBatchToRegion<'a> {
+    pub fn new(table_info: &'a TableInfo, partition_manager: &'a PartitionRuleManager) -> Self {
+        Self {
+            table_info,
+            partition_manager,
+        }
+    }
+
+    pub async fn convert(&self, record_batch: &RecordBatch) -> Result<RegionInsertRequests> {
+        let schema = self.column_schema(record_batch)?;
+        let rows =
+            api::helper::vectors_to_rows(record_batch.columns().iter(), record_batch.num_rows());
+
+        let rows = Rows { schema, rows };
+        let requests = Partitioner::new(self.partition_manager)
+            .partition_insert_requests(self.table_info.table_id(), rows)
+            .await?;
+        Ok(RegionInsertRequests { requests })
+    }
+
+    fn column_schema(&self, record_batch: &RecordBatch) -> Result<Vec<ColumnSchema>> {
+        let table_schema = &self.table_info.meta.schema;
+        record_batch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| {
+                let table_column = table_schema
+                    .column_schema_by_name(&column.name)
+                    .with_context(|| ColumnNotFoundSnafu {
+                        msg: format!("unable to find column {} in table schema", column.name),
+                    })?;
+                ensure!(
+                    table_column.data_type == column.data_type,
+                    InvalidInsertRequestSnafu {
+                        reason: format!(
+                            "Data type of column '{}' mismatch, expected: {:?}, actual: {:?}",
+                            column.name, table_column.data_type, column.data_type
+                        ),
+                    }
+                );
+
+                let (datatype, datatype_extension) =
+                    ColumnDataTypeWrapper::try_from(column.data_type.clone())
+                        .context(ColumnDataTypeSnafu)?
+                        .to_parts();
+                Ok(ColumnSchema {
+                    column_name: column.name.clone(),
+                    datatype: datatype as i32,
+                    semantic_type: semantic_type(self.table_info, &column.name)?.into(),
+                    datatype_extension,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use api::v1::value::ValueData;
+    use api::v1::{ColumnDataType, SemanticType};
+    use common_meta::kv_backend::memory::MemoryKvBackend;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema as DtColumnSchema, Schema};
+    use datatypes::vectors::{Int32Vector, Int64Vector, TimestampMillisecondVector};
+    use store_api::storage::RegionId;
+
+    use super::*;
+    use crate::tests::{create_partition_rule_manager, new_test_table_info};
+
+    #[tokio::test]
+    async fn test_record_batch_to_region() {
+        let backend = Arc::new(MemoryKvBackend::default());
+        let partition_manager = create_partition_rule_manager(backend).await;
+        let table_info = new_test_table_info(1, "table_1", vec![0u32, 1, 2].into_iter());
+        let converter = RecordBatchToRegion::new(&table_info, &partition_manager);
+
+        let schema = Arc::new(Schema::new(vec![
+            DtColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                false,
+            ),
+            DtColumnSchema::new("a", ConcreteDataType::int32_datatype(), true),
+        ]));
+        let record_batch = RecordBatch::new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondVector::from_vec(vec![1, 2, 3])) as _,
+                Arc::new(Int32Vector::from(vec![Some(1), Some(11), Some(101)])) as _,
+            ],
+        )
+        .unwrap();
+
+        let requests = converter.convert(&record_batch).await.unwrap().requests;
+        assert_eq!(requests.len(), 3);
+        for request in requests {
+            let rows = request.rows.unwrap();
+            // columns keep the order of the record batch
+            assert_eq!(rows.schema[0].column_name, "ts");
+            assert_eq!(rows.schema[0].semantic_type, SemanticType::Timestamp as i32);
+            assert_eq!(rows.schema[1].column_name, "a");
+            assert_eq!(rows.schema[1].datatype, ColumnDataType::Int32 as i32);
+            assert_eq!(rows.rows.len(), 1);
+            let expected = match RegionId::from_u64(request.region_id).region_number() {
+                1 => 101,
+                2 => 11,
+                _ => 1,
+            };
+            assert_eq!(
+                rows.rows[0].values[1].value_data,
+                Some(ValueData::I32Value(expected))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_batch_to_region_mismatch() {
+        let backend = Arc::new(MemoryKvBackend::default());
+        let partition_manager = create_partition_rule_manager(backend).await;
+        let table_info = new_test_table_info(1, "table_1", vec![0u32, 1, 2].into_iter());
+        let converter = RecordBatchToRegion::new(&table_info, &partition_manager);
+
+        // column `a` is Int32 in the table
+        let schema = Arc::new(Schema::new(vec![DtColumnSchema::new(
+            "a",
+            ConcreteDataType::int64_datatype(),
+            true,
+        )]));
+        let record_batch =
+            RecordBatch::new(schema, vec![Arc::new(Int64Vector::from_vec(vec![1])) as _]).unwrap();
+        let err = converter.convert(&record_batch).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid InsertRequest"), "{err}");
+
+        let schema = Arc::new(Schema::new(vec![DtColumnSchema::new(
+            "c",
+            ConcreteDataType::int32_datatype(),
+            true,
+        )]));
+        let record_batch =
+            RecordBatch::new(schema, vec![Arc::new(Int32Vector::from_vec(vec![1])) as _]).unwrap();
+        assert!(converter.convert(&record_batch).await.is_err());
+    }
+}
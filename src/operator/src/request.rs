@@ -26,12 +26,14 @@ use futures_util::future;
 use partition::manager::{PartitionInfo, PartitionRuleManagerRef};
 use session::context::QueryContextRef;
 use snafu::prelude::*;
+use store_api::region_engine::{OrphanFilesInfo, SnapshotInfo};
+use store_api::region_request::{RegionAction, RegionActionOutput};
 use store_api::storage::RegionId;
 use table::requests::{CompactTableRequest, FlushTableRequest};
 
 use crate::error::{
     CatalogSnafu, FindRegionLeaderSnafu, FindTablePartitionRuleSnafu, JoinTaskSnafu,
-    RequestRegionSnafu, Result, TableNotFoundSnafu, UnsupportedRegionRequestSnafu,
+    RequestRegionSnafu, Result, TableNotFoundSnafu, UnexpectedSnafu, UnsupportedRegionRequestSnafu,
 };
 use crate::region_req_factory::RegionRequestFactory;
 
@@ -150,6 +152,90 @@ impl Requester {
         info!("Handle region manual compaction request: {region_id}");
         self.do_request(vec![request], None, &ctx).await
     }
+
+    /// Handle the request to create a snapshot of the region.
+    pub async fn handle_region_create_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+    ) -> Result<SnapshotInfo> {
+        info!("Handle region create snapshot request: {region_id}, name: {name}");
+        match self
+            .do_action(RegionAction::CreateSnapshot { region_id, name })
+            .await?
+        {
+            RegionActionOutput::Snapshot(snapshot) => Ok(snapshot),
+            output => UnexpectedSnafu {
+                violated: format!("creating snapshot outputs {output:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Handle the request to drop a snapshot of the region.
+    pub async fn handle_region_drop_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+    ) -> Result<()> {
+        info!("Handle region drop snapshot request: {region_id}, name: {name}");
+        let _ = self
+            .do_action(RegionAction::DropSnapshot { region_id, name })
+            .await?;
+        Ok(())
+    }
+
+    /// Handle the request to list snapshots of the region.
+    pub async fn handle_region_list_snapshots(
+        &self,
+        region_id: RegionId,
+    ) -> Result<Vec<SnapshotInfo>> {
+        match self
+            .do_action(RegionAction::ListSnapshots { region_id })
+            .await?
+        {
+            RegionActionOutput::Snapshots(snapshots) => Ok(snapshots),
+            output => UnexpectedSnafu {
+                violated: format!("listing snapshots outputs {output:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Handle the request to collect orphan files of the region.
+    pub async fn handle_region_gc_orphan_files(
+        &self,
+        region_id: RegionId,
+        dry_run: bool,
+    ) -> Result<OrphanFilesInfo> {
+        info!("Handle region gc orphan files request: {region_id}, dry_run: {dry_run}");
+        match self
+            .do_action(RegionAction::GcOrphanFiles { region_id, dry_run })
+            .await?
+        {
+            RegionActionOutput::OrphanFiles(orphan_files) => Ok(orphan_files),
+            output => UnexpectedSnafu {
+                violated: format!("collecting orphan files outputs {output:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    /// Handle the request to build indexes for existing SSTs of the region.
+    pub async fn handle_region_build_index(
+        &self,
+        region_id: RegionId,
+        rebuild_all: bool,
+    ) -> Result<()> {
+        info!("Handle region build index request: {region_id}, rebuild_all: {rebuild_all}");
+        let _ = self
+            .do_action(RegionAction::BuildIndex {
+                region_id,
+                rebuild_all,
+            })
+            .await?;
+        Ok(())
+    }
 }
 
 impl Requester {
@@ -186,6 +272,22 @@ impl Requester {
         Ok(affected_rows)
     }
 
+    /// Sends the `action` to the leader of the region.
+    async fn do_action(&self, action: RegionAction) -> Result<RegionActionOutput> {
+        let region_id = action.region_id().context(UnsupportedRegionRequestSnafu)?;
+        let peer = self
+            .partition_manager
+            .find_region_leader(region_id)
+            .await
+            .context(FindRegionLeaderSnafu)?;
+        self.datanode_manager
+            .datanode(&peer)
+            .await
+            .handle_action(action)
+            .await
+            .context(RequestRegionSnafu)
+    }
+
     async fn find_region_leader_by_request(
         partition_manager: PartitionRuleManagerRef,
         req: &RegionRequestBody,
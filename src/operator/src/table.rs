@@ -20,6 +20,7 @@ use common_query::error as query_error;
 use common_query::error::Result as QueryResult;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
+use store_api::region_engine::{OrphanFilesInfo, SnapshotInfo};
 use store_api::storage::RegionId;
 use table::requests::{
     CompactTableRequest, DeleteRequest as TableDeleteRequest, FlushTableRequest,
@@ -119,4 +120,68 @@ impl TableMutationHandler for TableMutationOperator {
             .map_err(BoxedError::new)
             .context(query_error::TableMutationSnafu)
     }
+
+    async fn create_region_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<SnapshotInfo> {
+        self.requester
+            .handle_region_create_snapshot(region_id, name)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
+    async fn drop_region_snapshot(
+        &self,
+        region_id: RegionId,
+        name: String,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<()> {
+        self.requester
+            .handle_region_drop_snapshot(region_id, name)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
+    async fn list_region_snapshots(
+        &self,
+        region_id: RegionId,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<Vec<SnapshotInfo>> {
+        self.requester
+            .handle_region_list_snapshots(region_id)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
+    async fn gc_region_orphan_files(
+        &self,
+        region_id: RegionId,
+        dry_run: bool,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<OrphanFilesInfo> {
+        self.requester
+            .handle_region_gc_orphan_files(region_id, dry_run)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
+    async fn build_region_index(
+        &self,
+        region_id: RegionId,
+        rebuild_all: bool,
+        _ctx: QueryContextRef,
+    ) -> QueryResult<()> {
+        self.requester
+            .handle_region_build_index(region_id, rebuild_all)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
 }
@@ -135,6 +135,22 @@ pub enum Error {
         operator: String,
         location: Location,
     },
+
+    #[snafu(display("Invalid regular expression in {}: {}", fn_name, regex))]
+    InvalidRegex {
+        fn_name: String,
+        regex: String,
+        #[snafu(source)]
+        error: regex::Error,
+        location: Location,
+    },
+
+    #[snafu(display("Invalid label name in {}: {}", fn_name, label))]
+    InvalidLabelName {
+        fn_name: String,
+        label: String,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -156,6 +172,8 @@ impl ErrorExt for Error {
             | CombineTableColumnMismatch { .. }
             | DataFusionPlanning { .. }
             | MultiFieldsNotSupported { .. }
+            | InvalidRegex { .. }
+            | InvalidLabelName { .. }
             | UnexpectedPlanExpr { .. }
             | IllegalRange { .. } => StatusCode::InvalidArguments,
 
@@ -19,6 +19,7 @@ mod normalize;
 mod planner;
 mod range_manipulate;
 mod series_divide;
+mod series_unique_check;
 #[cfg(test)]
 mod test_util;
 mod union_distinct_on;
@@ -31,6 +32,9 @@ pub use normalize::{SeriesNormalize, SeriesNormalizeExec, SeriesNormalizeStream}
 pub use planner::PromExtensionPlanner;
 pub use range_manipulate::{RangeManipulate, RangeManipulateExec, RangeManipulateStream};
 pub use series_divide::{SeriesDivide, SeriesDivideExec, SeriesDivideStream};
+pub use series_unique_check::{
+    CheckedSide, SeriesUniqueCheck, SeriesUniqueCheckExec, SeriesUniqueCheckStream,
+};
 pub use union_distinct_on::{UnionDistinctOn, UnionDistinctOnExec, UnionDistinctOnStream};
 
 pub(crate) type Millisecond = <TimestampMillisecondType as ArrowPrimitiveType>::Native;
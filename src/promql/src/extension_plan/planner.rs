@@ -24,6 +24,7 @@ use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
 use super::{HistogramFold, UnionDistinctOn};
 use crate::extension_plan::{
     EmptyMetric, InstantManipulate, RangeManipulate, SeriesDivide, SeriesNormalize,
+    SeriesUniqueCheck,
 };
 
 pub struct PromExtensionPlanner;
@@ -50,6 +51,8 @@ impl ExtensionPlanner for PromExtensionPlanner {
             Ok(Some(node.to_execution_plan(session_state, planner)?))
         } else if let Some(node) = node.as_any().downcast_ref::<HistogramFold>() {
             Ok(Some(node.to_execution_plan(physical_inputs[0].clone())))
+        } else if let Some(node) = node.as_any().downcast_ref::<SeriesUniqueCheck>() {
+            Ok(Some(node.to_execution_plan(physical_inputs[0].clone())))
         } else if let Some(node) = node.as_any().downcast_ref::<UnionDistinctOn>() {
             Ok(Some(node.to_execution_plan(
                 physical_inputs[0].clone(),
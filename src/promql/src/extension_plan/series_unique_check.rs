@@ -0,0 +1,407 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use ahash::HashSet;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{OwnedRow, RowConverter, SortField};
+use datafusion::arrow::util::display::array_value_to_string;
+use datafusion::common::DFSchemaRef;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::{Expr, LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use futures::{ready, Stream, StreamExt};
+
+/// Check that the input doesn't contain multiple rows with the same value on
+/// `match_columns` and the time index at the same time. The input is passed through
+/// as is if the check succeeds.
+///
+/// This is used on the "one" side of PromQL's one-to-many or many-to-one vector
+/// matching (`group_left` and `group_right`), where series are required to be unique
+/// on the matching labels. It's also used on the result of the matching, where series
+/// are required to be unique on all labels after labels are copied from the "one" side.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SeriesUniqueCheck {
+    /// Matching labels. The time index is not included.
+    match_columns: Vec<String>,
+    time_index: String,
+    /// Which part of the binary operation the input is, for error message.
+    side: CheckedSide,
+    input: LogicalPlan,
+}
+
+/// The part of a one-to-many or many-to-one binary operation checked by [SeriesUniqueCheck].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckedSide {
+    /// The left hand-side is the "one" side.
+    Left,
+    /// The right hand-side is the "one" side.
+    Right,
+    /// The result of the operation.
+    Result,
+}
+
+impl Display for CheckedSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckedSide::Left => write!(f, "left"),
+            CheckedSide::Right => write!(f, "right"),
+            CheckedSide::Result => write!(f, "result"),
+        }
+    }
+}
+
+impl SeriesUniqueCheck {
+    pub const fn name() -> &'static str {
+        "SeriesUniqueCheck"
+    }
+
+    pub fn new(
+        match_columns: Vec<String>,
+        time_index: String,
+        side: CheckedSide,
+        input: LogicalPlan,
+    ) -> Self {
+        Self {
+            match_columns,
+            time_index,
+            side,
+            input,
+        }
+    }
+
+    pub fn to_execution_plan(&self, exec_input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        Arc::new(SeriesUniqueCheckExec {
+            match_columns: self.match_columns.clone(),
+            time_index: self.time_index.clone(),
+            side: self.side,
+            input: exec_input,
+            metric: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl UserDefinedLogicalNodeCore for SeriesUniqueCheck {
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![]
+    }
+
+    fn fmt_for_explain(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PromSeriesUniqueCheck: on col=[{:?}], time index=[{}], side=[{}]",
+            self.match_columns, self.time_index, self.side
+        )
+    }
+
+    fn from_template(&self, _exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert!(!inputs.is_empty());
+
+        Self {
+            match_columns: self.match_columns.clone(),
+            time_index: self.time_index.clone(),
+            side: self.side,
+            input: inputs[0].clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SeriesUniqueCheckExec {
+    match_columns: Vec<String>,
+    time_index: String,
+    side: CheckedSide,
+    input: Arc<dyn ExecutionPlan>,
+    metric: ExecutionPlanMetricsSet,
+}
+
+impl ExecutionPlan for SeriesUniqueCheckExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true; self.children().len()]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        assert!(!children.is_empty());
+        Ok(Arc::new(Self {
+            match_columns: self.match_columns.clone(),
+            time_index: self.time_index.clone(),
+            side: self.side,
+            input: children[0].clone(),
+            metric: self.metric.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let baseline_metric = BaselineMetrics::new(&self.metric, partition);
+
+        let input = self.input.execute(partition, context)?;
+        let schema = input.schema();
+
+        // Convert column name to column index. The time index is the last one.
+        let mut key_indices = Vec::with_capacity(self.match_columns.len() + 1);
+        let mut sort_fields = Vec::with_capacity(self.match_columns.len() + 1);
+        for key in self.match_columns.iter().chain(Some(&self.time_index)) {
+            let (index, field) = schema
+                .column_with_name(key)
+                .ok_or_else(|| DataFusionError::Internal(format!("Column {} not found", key)))?;
+            key_indices.push(index);
+            sort_fields.push(SortField::new(field.data_type().clone()));
+        }
+        let row_converter = RowConverter::new(sort_fields)?;
+
+        Ok(Box::pin(SeriesUniqueCheckStream {
+            key_indices,
+            match_columns: self.match_columns.clone(),
+            side: self.side,
+            observed: HashSet::default(),
+            row_converter,
+            input,
+            metric: baseline_metric,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metric.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+impl DisplayAs for SeriesUniqueCheckExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "PromSeriesUniqueCheckExec: on col=[{:?}], time index=[{}], side=[{}]",
+                    self.match_columns, self.time_index, self.side
+                )
+            }
+        }
+    }
+}
+
+pub struct SeriesUniqueCheckStream {
+    /// Include time index as the last element
+    key_indices: Vec<usize>,
+    match_columns: Vec<String>,
+    side: CheckedSide,
+    /// All observed keys. Keys are stored as is rather than their hashes, as
+    /// different keys may have the same hash.
+    observed: HashSet<OwnedRow>,
+    /// Converter for the key columns
+    row_converter: RowConverter,
+
+    input: SendableRecordBatchStream,
+    metric: BaselineMetrics,
+}
+
+impl RecordBatchStream for SeriesUniqueCheckStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+impl Stream for SeriesUniqueCheckStream {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = match ready!(self.input.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                let elapsed_compute = self.metric.elapsed_compute().clone();
+                let _timer = elapsed_compute.timer();
+                Poll::Ready(Some(self.check(&batch).map(|_| batch)))
+            }
+            other => Poll::Ready(other),
+        };
+        self.metric.record_poll(poll)
+    }
+}
+
+impl SeriesUniqueCheckStream {
+    pub fn check(&mut self, batch: &RecordBatch) -> DataFusionResult<()> {
+        let arrays = self
+            .key_indices
+            .iter()
+            .map(|i| batch.column(*i).clone())
+            .collect::<Vec<_>>();
+        let rows = self.row_converter.convert_columns(&arrays)?;
+
+        for (row, key) in rows.iter().enumerate() {
+            if !self.observed.insert(key.owned()) {
+                // skip the time index column
+                let match_group = self
+                    .match_columns
+                    .iter()
+                    .zip(arrays.iter())
+                    .map(|(name, array)| {
+                        array_value_to_string(array, row).map(|value| format!("{name}=\"{value}\""))
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?
+                    .join(", ");
+                let msg = match self.side {
+                    CheckedSide::Left | CheckedSide::Right => format!(
+                        "found duplicate series for the match group {{{match_group}}} on the {} hand-side of the operation; \
+                        many-to-many matching not allowed: matching labels must be unique on one side",
+                        self.side
+                    ),
+                    CheckedSide::Result => format!(
+                        "found duplicate series {{{match_group}}} in the result of the operation; \
+                        multiple matches for labels: grouping labels must ensure unique matches"
+                    ),
+                };
+                return Err(DataFusionError::Execution(msg));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampMillisecondArray};
+    use datafusion::arrow::datatypes::{
+        ArrowPrimitiveType, DataType, Field, Schema, TimestampMillisecondType,
+    };
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    fn prepare_test_data(paths: Vec<&'static str>) -> MemoryExec {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("path", DataType::Utf8, true),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("timestamp", TimestampMillisecondType::DATA_TYPE, true),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let num_rows = paths.len();
+        let path_column = Arc::new(StringArray::from(paths)) as _;
+        let host_column = Arc::new(StringArray::from(
+            (0..num_rows)
+                .map(|i| format!("host_{i}"))
+                .collect::<Vec<_>>(),
+        )) as _;
+        let timestamp_column = Arc::new(TimestampMillisecondArray::from(vec![0; num_rows])) as _;
+        let field_column = Arc::new(Float64Array::from(vec![1.0; num_rows])) as _;
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![path_column, host_column, timestamp_column, field_column],
+        )
+        .unwrap();
+
+        MemoryExec::try_new(&[vec![data]], schema, None).unwrap()
+    }
+
+    async fn do_check(paths: Vec<&'static str>) -> DataFusionResult<usize> {
+        do_check_on(paths, CheckedSide::Right).await
+    }
+
+    async fn do_check_on(paths: Vec<&'static str>, side: CheckedSide) -> DataFusionResult<usize> {
+        let memory_exec = Arc::new(prepare_test_data(paths));
+        let check_exec = Arc::new(SeriesUniqueCheckExec {
+            match_columns: vec!["path".to_string()],
+            time_index: "timestamp".to_string(),
+            side,
+            input: memory_exec,
+            metric: ExecutionPlanMetricsSet::new(),
+        });
+        let session_context = SessionContext::default();
+        let result =
+            datafusion::physical_plan::collect(check_exec, session_context.task_ctx()).await?;
+        Ok(result.iter().map(|batch| batch.num_rows()).sum())
+    }
+
+    #[tokio::test]
+    async fn unique_series() {
+        let num_rows = do_check(vec!["foo", "bar", "baz"]).await.unwrap();
+        assert_eq!(num_rows, 3);
+    }
+
+    #[tokio::test]
+    async fn duplicate_series() {
+        let err = do_check(vec!["foo", "bar", "foo"]).await.unwrap_err();
+        let expected =
+            "found duplicate series for the match group {path=\"foo\"} on the right hand-side";
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+
+    #[tokio::test]
+    async fn duplicate_result_series() {
+        let err = do_check_on(vec!["foo", "foo"], CheckedSide::Result)
+            .await
+            .unwrap_err();
+        let expected = "found duplicate series {path=\"foo\"} in the result of the operation; \
+            multiple matches for labels";
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+}
@@ -16,6 +16,7 @@ mod aggr_over_time;
 mod changes;
 mod deriv;
 mod extrapolate_rate;
+mod format_float;
 mod holt_winters;
 mod idelta;
 mod predict_linear;
@@ -34,10 +35,11 @@ use datafusion::error::DataFusionError;
 use datafusion::physical_plan::ColumnarValue;
 pub use deriv::Deriv;
 pub use extrapolate_rate::{Delta, Increase, Rate};
+pub use format_float::FormatFloat;
 pub use holt_winters::HoltWinters;
 pub use idelta::IDelta;
 pub use predict_linear::PredictLinear;
-pub use quantile::QuantileOverTime;
+pub use quantile::{QuantileAggr, QuantileOverTime};
 pub use resets::Resets;
 
 pub(crate) fn extract_array(columnar_value: &ColumnarValue) -> Result<ArrayRef, DataFusionError> {
@@ -0,0 +1,117 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::StringArray;
+use datafusion::common::cast::as_float64_array;
+use datafusion::common::DataFusionError;
+use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datatypes::arrow::datatypes::DataType;
+
+use crate::error;
+use crate::functions::extract_array;
+
+/// Formats sample values into strings in the same way as Prometheus'
+/// `strconv.FormatFloat(v, 'f', -1, 64)`. It's used to build the label of `count_values`.
+pub struct FormatFloat;
+
+impl FormatFloat {
+    pub const fn name() -> &'static str {
+        "prom_format_float"
+    }
+
+    pub fn scalar_udf() -> ScalarUDF {
+        ScalarUDF {
+            name: Self::name().to_string(),
+            signature: Signature::new(
+                TypeSignature::Exact(vec![DataType::Float64]),
+                Volatility::Immutable,
+            ),
+            return_type: Arc::new(|_| Ok(Arc::new(DataType::Utf8))),
+            fun: Arc::new(Self::calc),
+        }
+    }
+
+    fn calc(input: &[ColumnarValue]) -> Result<ColumnarValue, DataFusionError> {
+        error::ensure(
+            input.len() == 1,
+            DataFusionError::Plan(format!(
+                "{}: expect 1 argument, found {}",
+                Self::name(),
+                input.len()
+            )),
+        )?;
+        let array = extract_array(&input[0])?;
+        let array = as_float64_array(&array)?;
+
+        let result = array
+            .iter()
+            .map(|value| value.map(format_float))
+            .collect::<StringArray>();
+        Ok(ColumnarValue::Array(Arc::new(result)))
+    }
+}
+
+/// Rust's [Display](std::fmt::Display) of `f64` already prints the shortest
+/// representation without exponent, only infinities are different.
+fn format_float(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::Float64Array;
+
+    use super::*;
+
+    #[test]
+    fn test_format_float() {
+        let cases = [
+            (1.0, "1"),
+            (-0.5, "-0.5"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (1e21, "1000000000000000000000"),
+            (1e-7, "0.0000001"),
+            (f64::NAN, "NaN"),
+            (f64::INFINITY, "+Inf"),
+            (f64::NEG_INFINITY, "-Inf"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_float(value), expected);
+        }
+    }
+
+    #[test]
+    fn test_format_float_calc() {
+        let input = Arc::new(Float64Array::from(vec![Some(2.0), None, Some(0.25)]));
+        let ColumnarValue::Array(output) =
+            FormatFloat::calc(&[ColumnarValue::Array(input)]).unwrap()
+        else {
+            unreachable!()
+        };
+        let output = output.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            output.iter().collect::<Vec<_>>(),
+            vec![Some("2"), None, Some("0.25")]
+        );
+    }
+}
@@ -14,11 +14,16 @@
 
 use std::sync::Arc;
 
-use datafusion::arrow::array::Float64Array;
+use datafusion::arrow::array::{ArrayRef, Float64Array};
 use datafusion::arrow::datatypes::TimeUnit;
+use datafusion::common::cast::{as_float64_array, as_list_array};
 use datafusion::common::DataFusionError;
-use datafusion::logical_expr::{ScalarUDF, Signature, TypeSignature, Volatility};
+use datafusion::logical_expr::{
+    Accumulator, AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, ScalarUDF,
+    Signature, StateTypeFunction, TypeSignature, Volatility,
+};
 use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
 use datatypes::arrow::array::Array;
 use datatypes::arrow::datatypes::DataType;
 
@@ -128,6 +133,107 @@ impl QuantileOverTime {
     }
 }
 
+/// The `quantile` aggregation operator. It calculates the φ-quantile of all values
+/// in the same group, like `quantile(0.99, some_metric) by (job)`.
+///
+/// The φ is passed as the second argument, so it can be evaluated at each step like
+/// `quantile(time() / 1e10, some_metric)`. It's expected to be the same inside a group.
+pub struct QuantileAggr;
+
+impl QuantileAggr {
+    pub const fn name() -> &'static str {
+        "prom_quantile"
+    }
+
+    pub fn aggregate_udf() -> AggregateUDF {
+        let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+        let accumulator: AccumulatorFactoryFunction =
+            Arc::new(|_| Ok(Box::new(QuantileAccumulator::new()) as _));
+        let state_type: StateTypeFunction = Arc::new(|_| Ok(Arc::new(Self::state_type())));
+
+        AggregateUDF::new(
+            Self::name(),
+            &Signature::new(
+                TypeSignature::Exact(vec![DataType::Float64, DataType::Float64]),
+                Volatility::Immutable,
+            ),
+            &return_type,
+            &accumulator,
+            &state_type,
+        )
+    }
+
+    /// All values are kept in a list as the intermediate state, along with the φ.
+    fn state_type() -> Vec<DataType> {
+        vec![
+            DataType::new_list(DataType::Float64, true),
+            DataType::Float64,
+        ]
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct QuantileAccumulator {
+    quantile: Option<f64>,
+    values: Vec<f64>,
+}
+
+impl QuantileAccumulator {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn update_quantile(&mut self, quantiles: &ArrayRef) -> Result<(), DataFusionError> {
+        if self.quantile.is_none() {
+            self.quantile = as_float64_array(quantiles)?.iter().flatten().next();
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for QuantileAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>, DataFusionError> {
+        let values = self
+            .values
+            .iter()
+            .map(|value| ScalarValue::Float64(Some(*value)))
+            .collect();
+        Ok(vec![
+            ScalarValue::new_list(Some(values), DataType::Float64),
+            ScalarValue::Float64(self.quantile),
+        ])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<(), DataFusionError> {
+        let array = as_float64_array(&values[0])?;
+        self.values.extend(array.iter().flatten());
+        self.update_quantile(&values[1])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<(), DataFusionError> {
+        let list_array = as_list_array(&states[0])?;
+        for values in list_array.iter().flatten() {
+            let array = as_float64_array(&values)?;
+            self.values.extend(array.iter().flatten());
+        }
+        self.update_quantile(&states[1])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue, DataFusionError> {
+        if self.values.is_empty() {
+            return Ok(ScalarValue::Float64(None));
+        }
+        Ok(ScalarValue::Float64(quantile_impl(
+            &self.values,
+            self.quantile.unwrap_or(f64::NAN),
+        )))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.values.capacity() * std::mem::size_of::<f64>()
+    }
+}
+
 /// Refer to <https://github.com/prometheus/prometheus/blob/6e2905a4d4ff9b47b1f6d201333f5bd53633f921/promql/quantile.go#L357-L386>
 fn quantile_impl(values: &[f64], quantile: f64) -> Option<f64> {
     if quantile.is_nan() || values.is_empty() {
@@ -200,6 +306,59 @@ mod tests {
         assert_eq!(quantile_impl(values, q).unwrap(), 2.5);
     }
 
+    #[test]
+    fn test_quantile_accumulator() {
+        let mut accumulator = QuantileAccumulator::new();
+        assert_eq!(accumulator.evaluate().unwrap(), ScalarValue::Float64(None));
+
+        let input = Arc::new(Float64Array::from(vec![Some(3.0), None, Some(1.0)])) as ArrayRef;
+        let quantile = Arc::new(Float64Array::from(vec![0.5; 3])) as ArrayRef;
+        accumulator.update_batch(&[input, quantile]).unwrap();
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::Float64(Some(2.0))
+        );
+
+        // merge the state of another accumulator
+        let mut other = QuantileAccumulator::new();
+        let input = Arc::new(Float64Array::from(vec![5.0, 2.0])) as ArrayRef;
+        let quantile = Arc::new(Float64Array::from(vec![0.5; 2])) as ArrayRef;
+        other.update_batch(&[input, quantile]).unwrap();
+        let state = other
+            .state()
+            .unwrap()
+            .iter()
+            .map(|value| value.to_array())
+            .collect::<Vec<_>>();
+        accumulator.merge_batch(&state).unwrap();
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::Float64(Some(2.5))
+        );
+    }
+
+    #[test]
+    fn test_quantile_accumulator_merge_into_empty() {
+        let mut other = QuantileAccumulator::new();
+        let input = Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 4.0])) as ArrayRef;
+        let quantile = Arc::new(Float64Array::from(vec![1.0; 4])) as ArrayRef;
+        other.update_batch(&[input, quantile]).unwrap();
+        let state = other
+            .state()
+            .unwrap()
+            .iter()
+            .map(|value| value.to_array())
+            .collect::<Vec<_>>();
+
+        // the quantile is carried by the state
+        let mut accumulator = QuantileAccumulator::new();
+        accumulator.merge_batch(&state).unwrap();
+        assert_eq!(
+            accumulator.evaluate().unwrap(),
+            ScalarValue::Float64(Some(4.0))
+        );
+    }
+
     #[test]
     fn test_quantile_impl_odd_length() {
         let values = &[4.0, 1.0, 3.0, 2.0, 5.0];
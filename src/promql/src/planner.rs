@@ -22,11 +22,14 @@ use catalog::table_source::DfTableSourceProvider;
 use common_query::prelude::GREPTIME_VALUE;
 use datafusion::common::{DFSchemaRef, OwnedTableReference, Result as DfResult};
 use datafusion::datasource::DefaultTableSource;
-use datafusion::logical_expr::expr::{AggregateFunction, Alias, ScalarFunction, ScalarUDF};
-use datafusion::logical_expr::expr_rewriter::normalize_cols;
+use datafusion::logical_expr::expr::{
+    AggregateFunction, AggregateUDF, Alias, ScalarFunction, ScalarUDF, WindowFunction,
+};
+use datafusion::logical_expr::expr_rewriter::{normalize_col, normalize_cols};
 use datafusion::logical_expr::{
-    AggregateFunction as AggregateFunctionEnum, BinaryExpr, BuiltinScalarFunction, Cast, Extension,
-    LogicalPlan, LogicalPlanBuilder, Operator, ScalarUDF as ScalarUdfDef,
+    AggregateFunction as AggregateFunctionEnum, BinaryExpr, BuiltInWindowFunction,
+    BuiltinScalarFunction, Cast, Extension, LogicalPlan, LogicalPlanBuilder, Operator,
+    ScalarUDF as ScalarUdfDef, WindowFrame, WindowFunction as WindowFunctionEnum,
 };
 use datafusion::optimizer::utils::{self, conjunction};
 use datafusion::prelude as df_prelude;
@@ -41,25 +44,27 @@ use promql_parser::parser::{
     NumberLiteral, Offset, ParenExpr, StringLiteral, SubqueryExpr, TokenType, UnaryExpr,
     VectorMatchCardinality, VectorSelector,
 };
+use regex::Regex;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::error::{
     CatalogSnafu, ColumnNotFoundSnafu, CombineTableColumnMismatchSnafu, DataFusionPlanningSnafu,
-    ExpectRangeSelectorSnafu, FunctionInvalidArgumentSnafu, MultiFieldsNotSupportedSnafu,
-    MultipleMetricMatchersSnafu, MultipleVectorSnafu, NoMetricMatcherSnafu, Result,
-    TableNameNotFoundSnafu, TimeIndexNotFoundSnafu, UnexpectedPlanExprSnafu, UnexpectedTokenSnafu,
-    UnknownTableSnafu, UnsupportedExprSnafu, UnsupportedVectorMatchSnafu, ValueNotFoundSnafu,
-    ZeroRangeSelectorSnafu,
+    ExpectRangeSelectorSnafu, FunctionInvalidArgumentSnafu, InvalidLabelNameSnafu,
+    InvalidRegexSnafu, MultiFieldsNotSupportedSnafu, MultipleMetricMatchersSnafu,
+    MultipleVectorSnafu, NoMetricMatcherSnafu, Result, TableNameNotFoundSnafu,
+    TimeIndexNotFoundSnafu, UnexpectedPlanExprSnafu, UnexpectedTokenSnafu, UnknownTableSnafu,
+    UnsupportedExprSnafu, UnsupportedVectorMatchSnafu, ValueNotFoundSnafu, ZeroRangeSelectorSnafu,
 };
 use crate::extension_plan::{
-    build_special_time_expr, EmptyMetric, HistogramFold, InstantManipulate, Millisecond,
-    RangeManipulate, SeriesDivide, SeriesNormalize, UnionDistinctOn,
+    build_special_time_expr, CheckedSide, EmptyMetric, HistogramFold, InstantManipulate,
+    Millisecond, RangeManipulate, SeriesDivide, SeriesNormalize, SeriesUniqueCheck,
+    UnionDistinctOn,
 };
 use crate::functions::{
-    AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, HoltWinters, IDelta,
-    Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
-    QuantileOverTime, Rate, Resets, StddevOverTime, StdvarOverTime, SumOverTime,
+    AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, FormatFloat, HoltWinters,
+    IDelta, Increase, LastOverTime, MaxOverTime, MinOverTime, PredictLinear, PresentOverTime,
+    QuantileAggr, QuantileOverTime, Rate, Resets, StddevOverTime, StdvarOverTime, SumOverTime,
 };
 
 /// `time()` function in PromQL.
@@ -68,6 +73,10 @@ const SPECIAL_TIME_FUNCTION: &str = "time";
 const SPECIAL_HISTOGRAM_QUANTILE: &str = "histogram_quantile";
 /// `vector` function in PromQL
 const SPECIAL_VECTOR_FUNCTION: &str = "vector";
+/// `label_replace` function in PromQL
+const SPECIAL_LABEL_REPLACE: &str = "label_replace";
+/// `label_join` function in PromQL
+const SPECIAL_LABEL_JOIN: &str = "label_join";
 /// `le` column for conventional histogram.
 const LE_COLUMN_NAME: &str = "le";
 
@@ -79,6 +88,9 @@ const DEFAULT_FIELD_COLUMN: &str = "value";
 /// Special modifier to project field columns under multi-field mode
 const FIELD_COLUMN_MATCHER: &str = "__field__";
 
+/// Temporary column to rank series in `topk` and `bottomk`
+const TOPK_ROW_NUMBER_COLUMN: &str = "__topk_row_number";
+
 #[derive(Default, Debug, Clone)]
 struct PromPlannerContext {
     // query parameters
@@ -148,18 +160,28 @@ impl PromPlanner {
             PromExpr::Aggregate(AggregateExpr {
                 op,
                 expr,
-                // TODO(ruihang): support param
-                param: _param,
+                param,
                 modifier,
             }) => {
                 let input = self.prom_expr_to_plan(*expr.clone()).await?;
 
+                // some aggregations keep or generate series instead of folding them
+                match op.id() {
+                    token::T_TOPK | token::T_BOTTOMK => {
+                        return self.create_topk_plan(*op, param, modifier, input)
+                    }
+                    token::T_COUNT_VALUES => {
+                        return self.create_count_values_plan(param, modifier, input)
+                    }
+                    _ => {}
+                }
+
                 // calculate columns to group by
                 // Need to append time index column into group by columns
                 let group_exprs = self.agg_modifier_to_col(input.schema(), modifier)?;
 
                 // convert op and value columns to aggregate exprs
-                let aggr_exprs = self.create_aggregate_exprs(*op, &input)?;
+                let aggr_exprs = self.create_aggregate_exprs(*op, param, &input)?;
 
                 // create plan
                 let group_sort_expr = group_exprs
@@ -318,6 +340,24 @@ impl PromPlanner {
                             );
                         }
 
+                        // one-to-many and many-to-one matching (`group_left` and `group_right`)
+                        if let Some(modifier) = modifier
+                            && matches!(
+                                modifier.card,
+                                VectorMatchCardinality::ManyToOne(_)
+                                    | VectorMatchCardinality::OneToMany(_)
+                            )
+                        {
+                            return self.group_join_on_non_field_columns(
+                                left_input,
+                                right_input,
+                                left_context,
+                                right_context,
+                                *op,
+                                modifier,
+                            );
+                        }
+
                         // normal join
                         if left_table_ref == right_table_ref {
                             // rename table references to avoid ambiguity
@@ -368,10 +408,7 @@ impl PromPlanner {
                 }
             }
             PromExpr::Paren(ParenExpr { expr }) => self.prom_expr_to_plan(*expr.clone()).await?,
-            PromExpr::Subquery(SubqueryExpr { .. }) => UnsupportedExprSnafu {
-                name: "Prom Subquery",
-            }
-            .fail()?,
+            PromExpr::Subquery(subquery) => self.create_subquery_plan(subquery).await?,
             PromExpr::NumberLiteral(NumberLiteral { val }) => {
                 self.ctx.time_index_column = Some(DEFAULT_TIME_INDEX_COLUMN.to_string());
                 self.ctx.field_columns = vec![DEFAULT_FIELD_COLUMN.to_string()];
@@ -480,6 +517,8 @@ impl PromPlanner {
                 match func.name {
                     SPECIAL_HISTOGRAM_QUANTILE => return self.create_histogram_plan(args).await,
                     SPECIAL_VECTOR_FUNCTION => return self.create_vector_plan(args).await,
+                    SPECIAL_LABEL_REPLACE => return self.create_label_replace_plan(args).await,
+                    SPECIAL_LABEL_JOIN => return self.create_label_join_plan(args).await,
                     _ => {}
                 }
 
@@ -1237,6 +1276,7 @@ impl PromPlanner {
     fn create_aggregate_exprs(
         &mut self,
         op: TokenType,
+        param: &Option<Box<PromExpr>>,
         input_plan: &LogicalPlan,
     ) -> Result<Vec<DfExpr>> {
         let aggr = match op.id() {
@@ -1248,11 +1288,26 @@ impl PromPlanner {
             token::T_GROUP => AggregateFunctionEnum::Grouping,
             token::T_STDDEV => AggregateFunctionEnum::StddevPop,
             token::T_STDVAR => AggregateFunctionEnum::VariancePop,
-            token::T_TOPK | token::T_BOTTOMK | token::T_COUNT_VALUES | token::T_QUANTILE => {
-                UnsupportedExprSnafu {
-                    name: format!("{op:?}"),
-                }
-                .fail()?
+            token::T_QUANTILE => {
+                let quantile_expr = self.create_param_expr("quantile", param)?;
+                let udaf = Arc::new(QuantileAggr::aggregate_udf());
+                let exprs = self
+                    .ctx
+                    .field_columns
+                    .iter()
+                    .map(|col| {
+                        DfExpr::AggregateUDF(AggregateUDF {
+                            fun: udaf.clone(),
+                            args: vec![
+                                DfExpr::Column(Column::from_name(col)),
+                                quantile_expr.clone(),
+                            ],
+                            filter: None,
+                            order_by: None,
+                        })
+                    })
+                    .collect();
+                return self.update_field_columns_by_aggregate_exprs(exprs, input_plan);
             }
             _ => UnexpectedTokenSnafu { token: op }.fail()?,
         };
@@ -1273,6 +1328,15 @@ impl PromPlanner {
             })
             .collect();
 
+        self.update_field_columns_by_aggregate_exprs(exprs, input_plan)
+    }
+
+    /// Update value column names in context to the output names of given aggregate exprs.
+    fn update_field_columns_by_aggregate_exprs(
+        &mut self,
+        exprs: Vec<DfExpr>,
+        input_plan: &LogicalPlan,
+    ) -> Result<Vec<DfExpr>> {
         // update value column name according to the aggregators
         let mut new_field_columns = Vec::with_capacity(self.ctx.field_columns.len());
         let normalized_exprs =
@@ -1285,6 +1349,184 @@ impl PromPlanner {
         Ok(exprs)
     }
 
+    /// Create a plan for `topk` or `bottomk` aggregation.
+    ///
+    /// Series are ranked inside each group (including the time index) by a
+    /// `ROW_NUMBER()` window, and only the first `k` of them are kept. Unlike other
+    /// aggregations, the output preserves all labels of the input series.
+    fn create_topk_plan(
+        &mut self,
+        op: TokenType,
+        param: &Option<Box<PromExpr>>,
+        modifier: &Option<LabelModifier>,
+        input: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let fn_name = if op.id() == token::T_TOPK {
+            "topk"
+        } else {
+            "bottomk"
+        };
+        let row_number_column = DfExpr::Column(Column::from_name(TOPK_ROW_NUMBER_COLUMN));
+        let filter_expr = match param.as_deref().and_then(Self::try_build_float_literal) {
+            Some(k) => {
+                // the parameter is truncated to integer like Prometheus does
+                let k = if k < 1.0 { 0 } else { k as u64 };
+                row_number_column.lt_eq(DfExpr::Literal(ScalarValue::UInt64(Some(k))))
+            }
+            // `k` is evaluated at each step, comparing in float is the same as truncating it
+            None => DfExpr::Cast(Cast {
+                expr: Box::new(row_number_column),
+                data_type: ArrowDataType::Float64,
+            })
+            .lt_eq(self.create_param_expr(fn_name, param)?),
+        };
+        ensure!(
+            self.ctx.field_columns.len() == 1,
+            MultiFieldsNotSupportedSnafu { operator: fn_name }
+        );
+        let field_column = self.ctx.field_columns[0].clone();
+
+        // `agg_modifier_to_col` changes tag columns to the grouping labels, but
+        // topk and bottomk keep the original series.
+        let tag_columns = self.ctx.tag_columns.clone();
+        let partition_exprs = self.agg_modifier_to_col(input.schema(), modifier)?;
+        self.ctx.tag_columns = tag_columns;
+
+        let value_sort_expr = DfExpr::Column(Column::from_name(&field_column))
+            .sort(op.id() == token::T_BOTTOMK, false);
+        let output_sort_exprs = partition_exprs
+            .iter()
+            .map(|expr| expr.clone().sort(true, false))
+            .chain(Some(value_sort_expr.clone()))
+            .collect::<Vec<_>>();
+        let row_number_expr = DfExpr::WindowFunction(WindowFunction::new(
+            WindowFunctionEnum::BuiltInWindowFunction(BuiltInWindowFunction::RowNumber),
+            vec![],
+            partition_exprs,
+            vec![value_sort_expr],
+            WindowFrame::new(true),
+        ))
+        .alias(TOPK_ROW_NUMBER_COLUMN);
+
+        let output_exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| DfExpr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        LogicalPlanBuilder::from(input)
+            .window(vec![row_number_expr])
+            .context(DataFusionPlanningSnafu)?
+            .filter(filter_expr)
+            .context(DataFusionPlanningSnafu)?
+            .project(output_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .sort(output_sort_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Create a plan for `count_values` aggregation.
+    ///
+    /// The value of each sample is formatted into the label given by the parameter,
+    /// then the number of samples with the same value is counted in each group.
+    fn create_count_values_plan(
+        &mut self,
+        param: &Option<Box<PromExpr>>,
+        modifier: &Option<LabelModifier>,
+        input: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let label = param
+            .as_deref()
+            .and_then(Self::try_build_string_literal)
+            .with_context(|| FunctionInvalidArgumentSnafu {
+                fn_name: "count_values",
+            })?;
+        Self::ensure_valid_label_name("count_values", &label)?;
+        ensure!(
+            self.ctx.field_columns.len() == 1,
+            MultiFieldsNotSupportedSnafu {
+                operator: "count_values"
+            }
+        );
+        let field_column = self.ctx.field_columns[0].clone();
+
+        // step 1: write values into the label. It overrides the label if it already exists.
+        let label_expr = DfExpr::ScalarUDF(ScalarUDF {
+            fun: Arc::new(FormatFloat::scalar_udf()),
+            args: vec![DfExpr::Column(Column::from_name(&field_column))],
+        })
+        .alias(&label);
+        let project_exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .filter(|field| field.name() != &label)
+            .map(|field| DfExpr::Column(field.qualified_column()))
+            .chain(Some(label_expr))
+            .collect::<Vec<_>>();
+        let projected = LogicalPlanBuilder::from(input)
+            .project(project_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // step 2: count samples grouped by labels and the new label
+        let mut group_exprs = self.agg_modifier_to_col(projected.schema(), modifier)?;
+        if !self.ctx.tag_columns.contains(&label) {
+            group_exprs.insert(0, DfExpr::Column(Column::from_name(&label)));
+            self.ctx.tag_columns.push(label);
+        }
+        let count_expr = DfExpr::AggregateFunction(AggregateFunction {
+            fun: AggregateFunctionEnum::Count,
+            args: vec![DfExpr::Column(Column::from_name(&field_column))],
+            distinct: false,
+            filter: None,
+            order_by: None,
+        });
+        let count_column = normalize_col(count_expr.clone(), &projected)
+            .context(DataFusionPlanningSnafu)?
+            .display_name()
+            .context(DataFusionPlanningSnafu)?;
+        let group_sort_expr = group_exprs
+            .clone()
+            .into_iter()
+            .map(|expr| expr.sort(true, false));
+        let aggregated = LogicalPlanBuilder::from(projected)
+            .aggregate(group_exprs, vec![count_expr])
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // step 3: PromQL values are always float
+        let output_exprs = aggregated
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| {
+                if field.name() == &count_column {
+                    DfExpr::Cast(Cast {
+                        expr: Box::new(DfExpr::Column(field.qualified_column())),
+                        data_type: ArrowDataType::Float64,
+                    })
+                    .alias(&count_column)
+                } else {
+                    DfExpr::Column(field.qualified_column())
+                }
+            })
+            .collect::<Vec<_>>();
+        self.ctx.field_columns = vec![count_column];
+
+        LogicalPlanBuilder::from(aggregated)
+            .project(output_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .sort(group_sort_expr)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
     /// Create a [SPECIAL_HISTOGRAM_QUANTILE] plan.
     async fn create_histogram_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
         if args.args.len() != 2 {
@@ -1338,6 +1580,96 @@ impl PromPlanner {
         }))
     }
 
+    /// Create a plan for subquery like `<expr>[<range>:<step>]`.
+    ///
+    /// The inner expression is evaluated on timestamps aligned to the subquery step,
+    /// covering `[start - offset - range, end - offset]`. Its output is then divided
+    /// into series again and folded by [RangeManipulate] with the outer evaluation
+    /// parameters, so it can be consumed by range functions like a matrix selector.
+    async fn create_subquery_plan(&mut self, subquery: &SubqueryExpr) -> Result<LogicalPlan> {
+        let SubqueryExpr {
+            expr,
+            offset,
+            range,
+            step,
+            ..
+        } = subquery;
+
+        ensure!(!range.is_zero(), ZeroRangeSelectorSnafu);
+        let range_ms = range.as_millis() as Millisecond;
+        let step_ms = step
+            .map(|step| step.as_millis() as Millisecond)
+            .filter(|step| *step > 0)
+            .unwrap_or(self.ctx.interval);
+        let offset_duration = match offset {
+            Some(Offset::Pos(duration)) => duration.as_millis() as Millisecond,
+            Some(Offset::Neg(duration)) => -(duration.as_millis() as Millisecond),
+            None => 0,
+        };
+
+        // evaluate the inner expression on the subquery step. The start timestamp
+        // is aligned to the step like Prometheus does.
+        let (outer_start, outer_end, outer_interval) =
+            (self.ctx.start, self.ctx.end, self.ctx.interval);
+        let inner_start = outer_start - offset_duration - range_ms;
+        let inner_start = if inner_start.rem_euclid(step_ms) == 0 {
+            inner_start
+        } else {
+            inner_start - inner_start.rem_euclid(step_ms) + step_ms
+        };
+        self.ctx.start = inner_start;
+        self.ctx.end = outer_end - offset_duration;
+        self.ctx.interval = step_ms;
+        let inner_plan = self.prom_expr_to_plan(*expr.clone()).await;
+        self.ctx.start = outer_start;
+        self.ctx.end = outer_end;
+        self.ctx.interval = outer_interval;
+        let inner_plan = inner_plan?;
+
+        // the subquery behaves like a range selector to its parent
+        self.ctx.range = Some(range_ms);
+        let time_index_column =
+            self.ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: self.ctx.table_name.clone().unwrap_or_default(),
+                })?;
+
+        // re-divide the evaluated result into series
+        let sort_plan = LogicalPlanBuilder::from(inner_plan)
+            .sort(self.create_tag_and_time_index_column_sort_exprs()?)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+        let divide_plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesDivide::new(self.ctx.tag_columns.clone(), sort_plan)),
+        });
+        let normalize_plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesNormalize::new(
+                offset_duration,
+                &time_index_column,
+                true,
+                divide_plan,
+            )),
+        });
+
+        let manipulate = RangeManipulate::new(
+            self.ctx.start,
+            self.ctx.end,
+            self.ctx.interval,
+            range_ms,
+            time_index_column,
+            self.ctx.field_columns.clone(),
+            normalize_plan,
+        )
+        .context(DataFusionPlanningSnafu)?;
+
+        Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(manipulate),
+        }))
+    }
+
     /// Create a [SPECIAL_VECTOR_FUNCTION] plan
     async fn create_vector_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
         if args.args.len() != 1 {
@@ -1372,6 +1704,175 @@ impl PromPlanner {
         }))
     }
 
+    /// Create a [SPECIAL_LABEL_REPLACE] plan.
+    ///
+    /// `label_replace(v, dst, replacement, src, regex)` matches the anchored `regex`
+    /// against the value of label `src`. If it matches, label `dst` is set to
+    /// `replacement` with capture groups (`$1`, `${name}`) expanded. Otherwise
+    /// the series is left unchanged. An empty result removes label `dst`.
+    async fn create_label_replace_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
+        ensure!(
+            args.args.len() == 5,
+            FunctionInvalidArgumentSnafu {
+                fn_name: SPECIAL_LABEL_REPLACE,
+            }
+        );
+        let mut string_args = Vec::with_capacity(4);
+        for arg in &args.args[1..] {
+            string_args.push(Self::try_build_string_literal(arg).with_context(|| {
+                FunctionInvalidArgumentSnafu {
+                    fn_name: SPECIAL_LABEL_REPLACE,
+                }
+            })?);
+        }
+        let [dst_label, replacement, src_label, regex]: [String; 4] =
+            string_args.try_into().unwrap();
+        Self::ensure_valid_label_name(SPECIAL_LABEL_REPLACE, &dst_label)?;
+        // Prometheus anchors the regex on both ends
+        let regex = format!("^(?:{regex})$");
+        let _ = Regex::new(&regex).context(InvalidRegexSnafu {
+            fn_name: SPECIAL_LABEL_REPLACE,
+            regex: regex.clone(),
+        })?;
+
+        let input = self.prom_expr_to_plan(*args.args[0].clone()).await?;
+        let src_expr = self.label_value_expr(&src_label);
+        let regex_expr = df_prelude::lit(regex);
+        let is_matched =
+            df_prelude::regexp_match(vec![src_expr.clone(), regex_expr.clone()]).is_not_null();
+        let replaced =
+            df_prelude::regexp_replace(vec![src_expr, regex_expr, df_prelude::lit(replacement)]);
+        let dst_expr = df_prelude::when(
+            is_matched,
+            df_prelude::nullif(replaced, df_prelude::lit("")),
+        )
+        .otherwise(self.label_value_expr_or_null(&dst_label))
+        .context(DataFusionPlanningSnafu)?;
+
+        self.project_with_label(input, dst_label, dst_expr)
+    }
+
+    /// Create a [SPECIAL_LABEL_JOIN] plan.
+    ///
+    /// `label_join(v, dst, separator, src_1, src_2, ...)` sets label `dst` to the values
+    /// of all `src` labels joined by `separator`. An empty result removes label `dst`.
+    async fn create_label_join_plan(&mut self, args: &PromFunctionArgs) -> Result<LogicalPlan> {
+        ensure!(
+            args.args.len() >= 3,
+            FunctionInvalidArgumentSnafu {
+                fn_name: SPECIAL_LABEL_JOIN,
+            }
+        );
+        let mut string_args = Vec::with_capacity(args.args.len() - 1);
+        for arg in &args.args[1..] {
+            string_args.push(Self::try_build_string_literal(arg).with_context(|| {
+                FunctionInvalidArgumentSnafu {
+                    fn_name: SPECIAL_LABEL_JOIN,
+                }
+            })?);
+        }
+        let dst_label = string_args[0].clone();
+        let separator = string_args[1].clone();
+        Self::ensure_valid_label_name(SPECIAL_LABEL_JOIN, &dst_label)?;
+
+        let input = self.prom_expr_to_plan(*args.args[0].clone()).await?;
+        let src_exprs = string_args[2..]
+            .iter()
+            .map(|label| self.label_value_expr(label))
+            .collect::<Vec<_>>();
+        let dst_expr = if src_exprs.is_empty() {
+            DfExpr::Literal(ScalarValue::Utf8(None))
+        } else {
+            df_prelude::nullif(
+                df_prelude::concat_ws(df_prelude::lit(separator), src_exprs),
+                df_prelude::lit(""),
+            )
+        };
+
+        self.project_with_label(input, dst_label, dst_expr)
+    }
+
+    /// Build an expr to read the value of the given label. Missing label is
+    /// treated as empty string, like Prometheus does.
+    fn label_value_expr(&self, label: &str) -> DfExpr {
+        if label == METRIC_NAME {
+            return df_prelude::lit(self.ctx.table_name.clone().unwrap_or_default());
+        }
+        if self.ctx.tag_columns.iter().any(|tag| tag == label) {
+            df_prelude::coalesce(vec![
+                DfExpr::Column(Column::from_name(label)),
+                df_prelude::lit(""),
+            ])
+        } else {
+            df_prelude::lit("")
+        }
+    }
+
+    /// Build an expr to read the value of the given label as is. `NULL` is
+    /// returned if the label doesn't exist.
+    fn label_value_expr_or_null(&self, label: &str) -> DfExpr {
+        if self.ctx.tag_columns.iter().any(|tag| tag == label) {
+            DfExpr::Column(Column::from_name(label))
+        } else {
+            DfExpr::Literal(ScalarValue::Utf8(None))
+        }
+    }
+
+    /// Project `dst_expr` as tag column `dst_label`. The existing tag column is replaced,
+    /// or a new tag column is appended if it doesn't exist.
+    ///
+    /// # Side effect
+    ///
+    /// This method will update [PromPlannerContext]'s tag columns.
+    fn project_with_label(
+        &mut self,
+        input: LogicalPlan,
+        dst_label: String,
+        dst_expr: DfExpr,
+    ) -> Result<LogicalPlan> {
+        let mut exprs = vec![self.create_time_index_column_expr()?];
+        let mut dst_expr = Some(dst_expr);
+        for tag in &self.ctx.tag_columns {
+            if *tag == dst_label
+                && let Some(expr) = dst_expr.take()
+            {
+                exprs.push(expr.alias(tag));
+            } else {
+                exprs.push(DfExpr::Column(Column::from_name(tag)));
+            }
+        }
+        if let Some(expr) = dst_expr {
+            exprs.push(expr.alias(&dst_label));
+            self.ctx.tag_columns.push(dst_label);
+        }
+        exprs.extend(
+            self.ctx
+                .field_columns
+                .iter()
+                .map(|col| DfExpr::Column(Column::from_name(col))),
+        );
+
+        LogicalPlanBuilder::from(input)
+            .project(exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Check if the given label name is a valid Prometheus label name, i.e.,
+    /// matches `[a-zA-Z_][a-zA-Z0-9_]*`. The metric name is not allowed to be
+    /// overwritten.
+    fn ensure_valid_label_name(fn_name: &str, label: &str) -> Result<()> {
+        let mut chars = label.chars();
+        let is_valid = label != METRIC_NAME
+            && chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        ensure!(is_valid, InvalidLabelNameSnafu { fn_name, label });
+        Ok(())
+    }
+
     /// Try to build a DataFusion Literal Expression from PromQL Expr, return
     /// `None` if the input is not a literal expression.
     fn try_build_literal_expr(expr: &PromExpr) -> Option<DfExpr> {
@@ -1441,6 +1942,57 @@ impl PromPlanner {
         }
     }
 
+    /// Create the expression of an aggregation parameter like `k` of `topk`. Besides
+    /// number literals, it can be a scalar expression of `time()` that is evaluated
+    /// at each step.
+    fn create_param_expr(&self, fn_name: &str, param: &Option<Box<PromExpr>>) -> Result<DfExpr> {
+        let time_index_column = self
+            .ctx
+            .time_index_column
+            .as_deref()
+            .with_context(|| TimeIndexNotFoundSnafu { table: "unknown" })?;
+        param
+            .as_deref()
+            .and_then(|param| Self::try_build_param_expr(param, time_index_column))
+            .with_context(|| FunctionInvalidArgumentSnafu { fn_name })
+    }
+
+    /// Try to build a float expression from a scalar [PromExpr] that only consists
+    /// of number literals and `time()`. Return `None` for other expressions.
+    fn try_build_param_expr(expr: &PromExpr, time_index_col: &str) -> Option<DfExpr> {
+        match expr {
+            PromExpr::NumberLiteral(NumberLiteral { val }) => {
+                Some(DfExpr::Literal(ScalarValue::Float64(Some(*val))))
+            }
+            PromExpr::Call(_) => Self::try_build_special_time_expr(expr, time_index_col),
+            PromExpr::Paren(ParenExpr { expr }) => Self::try_build_param_expr(expr, time_index_col),
+            PromExpr::Unary(UnaryExpr { expr, .. }) => Some(DfExpr::Negative(Box::new(
+                Self::try_build_param_expr(expr, time_index_col)?,
+            ))),
+            PromExpr::Binary(PromBinaryExpr { lhs, rhs, op, .. }) => {
+                let lhs = Self::try_build_param_expr(lhs, time_index_col)?;
+                let rhs = Self::try_build_param_expr(rhs, time_index_col)?;
+                let expr_builder = Self::prom_token_to_binary_expr_builder(*op).ok()?;
+                let expr = expr_builder(lhs, rhs).ok()?;
+                // comparisons between scalars always come with `bool`
+                if Self::is_token_a_comparison_op(*op) {
+                    Some(DfExpr::Cast(Cast {
+                        expr: Box::new(expr),
+                        data_type: ArrowDataType::Float64,
+                    }))
+                } else {
+                    Some(expr)
+                }
+            }
+            PromExpr::StringLiteral(_)
+            | PromExpr::VectorSelector(_)
+            | PromExpr::MatrixSelector(_)
+            | PromExpr::Extension(_)
+            | PromExpr::Aggregate(_)
+            | PromExpr::Subquery(_) => None,
+        }
+    }
+
     /// Try to build a [f64] from [PromExpr].
     fn try_build_float_literal(expr: &PromExpr) -> Option<f64> {
         match expr {
@@ -1460,6 +2012,24 @@ impl PromPlanner {
         }
     }
 
+    /// Try to get the string value of a PromQL string literal, return `None` if the
+    /// input is not a string literal.
+    fn try_build_string_literal(expr: &PromExpr) -> Option<String> {
+        match expr {
+            PromExpr::StringLiteral(StringLiteral { val }) => Some(val.clone()),
+            PromExpr::Paren(ParenExpr { expr }) => Self::try_build_string_literal(expr),
+            PromExpr::NumberLiteral(_)
+            | PromExpr::Unary(_)
+            | PromExpr::Binary(_)
+            | PromExpr::VectorSelector(_)
+            | PromExpr::MatrixSelector(_)
+            | PromExpr::Call(_)
+            | PromExpr::Extension(_)
+            | PromExpr::Aggregate(_)
+            | PromExpr::Subquery(_) => None,
+        }
+    }
+
     /// Return a lambda to build binary expression from token.
     /// Because some binary operator are function in DataFusion like `atan2` or `^`.
     #[allow(clippy::type_complexity)]
@@ -1558,6 +2128,225 @@ impl PromPlanner {
             .context(DataFusionPlanningSnafu)
     }
 
+    /// Build a one-to-many or many-to-one join (`group_left` / `group_right`).
+    ///
+    /// Series on the "one" side are required to be unique on the matching labels,
+    /// which is checked by [SeriesUniqueCheck] at execution time. The result keeps
+    /// labels from the "many" side, while labels listed in the grouping modifier are
+    /// copied from the "one" side (or dropped if the "one" side doesn't have them).
+    /// Series in the result are also checked to be unique after the relabelling.
+    fn group_join_on_non_field_columns(
+        &mut self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        left_context: PromPlannerContext,
+        right_context: PromPlannerContext,
+        op: TokenType,
+        modifier: &BinModifier,
+    ) -> Result<LogicalPlan> {
+        let (include_labels, many_is_left) = match &modifier.card {
+            VectorMatchCardinality::ManyToOne(labels) => (&labels.labels, true),
+            VectorMatchCardinality::OneToMany(labels) => (&labels.labels, false),
+            _ => {
+                return UnsupportedVectorMatchSnafu {
+                    name: modifier.card.clone(),
+                }
+                .fail()
+            }
+        };
+        ensure!(
+            left_context.field_columns.len() == 1 && right_context.field_columns.len() == 1,
+            MultiFieldsNotSupportedSnafu {
+                operator: "group_left/group_right"
+            }
+        );
+        let left_table_ref = OwnedTableReference::bare("lhs");
+        let right_table_ref = OwnedTableReference::bare("rhs");
+        let (many_context, one_context, many_table_ref, one_table_ref, one_side) = if many_is_left {
+            (
+                &left_context,
+                &right_context,
+                &left_table_ref,
+                &right_table_ref,
+                CheckedSide::Right,
+            )
+        } else {
+            (
+                &right_context,
+                &left_context,
+                &right_table_ref,
+                &left_table_ref,
+                CheckedSide::Left,
+            )
+        };
+        let many_tags = many_context
+            .tag_columns
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let one_tags = one_context
+            .tag_columns
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let many_time_index = many_context.time_index_column.clone().unwrap();
+        let one_time_index = one_context.time_index_column.clone().unwrap();
+
+        // step 1: compute match columns. Labels that don't exist on both sides are
+        // not comparable and are skipped.
+        let match_columns: BTreeSet<String> = if let Some(matching) = &modifier.matching {
+            match matching {
+                // keeps columns mentioned in `on`
+                LabelModifier::Include(on) => on
+                    .labels
+                    .iter()
+                    .filter(|label| many_tags.contains(*label) && one_tags.contains(*label))
+                    .cloned()
+                    .collect(),
+                // removes columns memtioned in `ignoring`
+                LabelModifier::Exclude(ignoring) => many_tags
+                    .intersection(&one_tags)
+                    .filter(|label| !ignoring.labels.contains(*label))
+                    .cloned()
+                    .collect(),
+            }
+        } else {
+            many_tags.intersection(&one_tags).cloned().collect()
+        };
+        let match_columns = match_columns.into_iter().collect::<Vec<_>>();
+
+        // step 2: check uniqueness of the "one" side
+        let (many_input, one_input) = if many_is_left {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        let one_input = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesUniqueCheck::new(
+                match_columns.clone(),
+                one_time_index.clone(),
+                one_side,
+                one_input,
+            )),
+        });
+
+        // step 3: join two sides on match columns and time index
+        let many_keys = match_columns
+            .iter()
+            .chain([&many_time_index])
+            .map(|col| Column::new(Some(many_table_ref.clone()), col))
+            .collect::<Vec<_>>();
+        let one_keys = match_columns
+            .iter()
+            .chain([&one_time_index])
+            .map(|col| Column::new(Some(one_table_ref.clone()), col))
+            .collect::<Vec<_>>();
+        let one_input = LogicalPlanBuilder::from(one_input)
+            .alias(one_table_ref.clone())
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+        let join_plan = LogicalPlanBuilder::from(many_input)
+            .alias(many_table_ref.clone())
+            .context(DataFusionPlanningSnafu)?
+            .join(one_input, JoinType::Inner, (many_keys, one_keys), None)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // step 4: compute the value column. The operands keep their original order.
+        let left_field_col =
+            Column::new(Some(left_table_ref.clone()), &left_context.field_columns[0]);
+        let right_field_col = Column::new(
+            Some(right_table_ref.clone()),
+            &right_context.field_columns[0],
+        );
+        let binary_expr_builder = Self::prom_token_to_binary_expr_builder(op)?;
+        let mut binary_expr = binary_expr_builder(
+            DfExpr::Column(left_field_col.clone()),
+            DfExpr::Column(right_field_col),
+        )?;
+        let is_comparison_op = Self::is_token_a_comparison_op(op);
+        let (join_plan, field_expr, field_name) = if is_comparison_op && !modifier.return_bool {
+            // comparison without `bool` filters the result and keeps the left value
+            let filtered = LogicalPlanBuilder::from(join_plan)
+                .filter(binary_expr)
+                .context(DataFusionPlanningSnafu)?
+                .build()
+                .context(DataFusionPlanningSnafu)?;
+            (
+                filtered,
+                DfExpr::Column(left_field_col),
+                left_context.field_columns[0].clone(),
+            )
+        } else {
+            if is_comparison_op {
+                binary_expr = DfExpr::Cast(Cast {
+                    expr: Box::new(binary_expr),
+                    data_type: ArrowDataType::Float64,
+                });
+            }
+            let field_name = binary_expr
+                .display_name()
+                .context(DataFusionPlanningSnafu)?;
+            (join_plan, binary_expr, field_name)
+        };
+
+        // step 5: project the result labels. Labels in `group_x(...)` are taken from
+        // the "one" side and override the "many" side.
+        let mut result_tags = many_tags
+            .iter()
+            .filter(|tag| !include_labels.contains(*tag))
+            .map(|tag| (tag.clone(), many_table_ref))
+            .collect::<Vec<_>>();
+        for label in include_labels {
+            if one_tags.contains(label) && !result_tags.iter().any(|(tag, _)| tag == label) {
+                result_tags.push((label.clone(), one_table_ref));
+            }
+        }
+        let project_exprs = result_tags
+            .iter()
+            .map(|(tag, table_ref)| {
+                DfExpr::Column(Column::new(Some((*table_ref).clone()), tag)).alias(tag)
+            })
+            .chain([
+                DfExpr::Column(Column::new(Some(many_table_ref.clone()), &many_time_index))
+                    .alias(&many_time_index),
+                field_expr.alias(&field_name),
+            ])
+            .collect::<Vec<_>>();
+        let table_name = many_context.table_name.clone().unwrap_or_default();
+        let projected = LogicalPlanBuilder::from(join_plan)
+            .project(project_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // step 6: check uniqueness of the result, as different series on the "many"
+        // side may become the same after copying labels from the "one" side
+        let result_check = LogicalPlan::Extension(Extension {
+            node: Arc::new(SeriesUniqueCheck::new(
+                result_tags.iter().map(|(tag, _)| tag.clone()).collect(),
+                many_time_index.clone(),
+                CheckedSide::Result,
+                projected,
+            )),
+        });
+        let result = LogicalPlanBuilder::from(result_check)
+            .alias(OwnedTableReference::bare(table_name.clone()))
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        // step 7: update context
+        self.ctx.table_name = Some(table_name);
+        self.ctx.time_index_column = Some(many_time_index);
+        self.ctx.tag_columns = result_tags.into_iter().map(|(tag, _)| tag).collect();
+        self.ctx.field_columns = vec![field_name];
+
+        Ok(result)
+    }
+
     /// Build a set operator (AND/OR/UNLESS)
     fn set_op_on_non_field_columns(
         &mut self,
@@ -2068,6 +2857,26 @@ mod test {
         DfTableSourceProvider::new(catalog_list, false, QueryContext::arc().as_ref())
     }
 
+    /// Build an [EvalStmt] evaluating the query in `[0, 100000s]` with a 5s step.
+    fn build_test_eval_stmt(query: &str) -> EvalStmt {
+        EvalStmt {
+            expr: parser::parse(query).unwrap(),
+            start: UNIX_EPOCH,
+            end: UNIX_EPOCH
+                .checked_add(Duration::from_secs(100_000))
+                .unwrap(),
+            interval: Duration::from_secs(5),
+            lookback_delta: Duration::from_secs(1),
+        }
+    }
+
+    /// Plan the query over `some_metric` with the given number of tag and field columns.
+    async fn plan_test_query(query: &str, num_tag: usize, num_field: usize) -> Result<LogicalPlan> {
+        let table_provider =
+            build_test_table_provider("some_metric".to_string(), num_tag, num_field).await;
+        PromPlanner::stmt_to_plan(table_provider, build_test_eval_stmt(query)).await
+    }
+
     // {
     //     input: `abs(some_metric{foo!="bar"})`,
     //     expected: &Call{
@@ -2385,28 +3194,118 @@ mod test {
         do_aggregate_expr_plan("stdvar", "VARIANCE_POP").await;
     }
 
+    async fn aggregate_plan_string(query: &str) -> String {
+        plan_test_query(query, 2, 1)
+            .await
+            .unwrap()
+            .display_indent_schema()
+            .to_string()
+    }
+
     #[tokio::test]
-    #[should_panic]
     async fn aggregate_top_k() {
-        do_aggregate_expr_plan("topk", "").await;
+        let plan = aggregate_plan_string(r#"topk by (tag_1)(10, some_metric{tag_0!="bar"})"#).await;
+
+        // all labels are preserved
+        let expected_sort = "Sort: some_metric.tag_1 ASC NULLS LAST, some_metric.timestamp ASC NULLS LAST, some_metric.field_0 DESC NULLS LAST \
+            [tag_0:Utf8, tag_1:Utf8, timestamp:Timestamp(Millisecond, None), field_0:Float64;N]";
+        let expected_window =
+            "ROW_NUMBER() PARTITION BY [some_metric.tag_1, some_metric.timestamp] \
+            ORDER BY [some_metric.field_0 DESC NULLS LAST]";
+        assert!(plan.starts_with(expected_sort), "{plan}");
+        assert!(plan.contains(expected_window), "{plan}");
+        assert!(
+            plan.contains("Filter: __topk_row_number <= UInt64(10)"),
+            "{plan}"
+        );
     }
 
     #[tokio::test]
-    #[should_panic]
     async fn aggregate_bottom_k() {
-        do_aggregate_expr_plan("bottomk", "").await;
+        let plan = aggregate_plan_string(r#"bottomk without (tag_1)(3, some_metric)"#).await;
+
+        let expected_window =
+            "ROW_NUMBER() PARTITION BY [some_metric.tag_0, some_metric.timestamp] \
+            ORDER BY [some_metric.field_0 ASC NULLS LAST]";
+        assert!(plan.contains(expected_window), "{plan}");
+        assert!(
+            plan.contains("Filter: __topk_row_number <= UInt64(3)"),
+            "{plan}"
+        );
     }
 
     #[tokio::test]
-    #[should_panic]
     async fn aggregate_count_values() {
-        do_aggregate_expr_plan("count_values", "").await;
+        let plan = aggregate_plan_string(r#"count_values by (tag_1)("value", some_metric)"#).await;
+
+        let expected_projection =
+            "Projection: some_metric.tag_0, some_metric.tag_1, some_metric.timestamp, \
+            some_metric.field_0, prom_format_float(some_metric.field_0) AS value";
+        let expected_aggregate =
+            "Aggregate: groupBy=[[value, some_metric.tag_1, some_metric.timestamp]], \
+            aggr=[[COUNT(some_metric.field_0)]]";
+        let expected_cast =
+            "CAST(COUNT(some_metric.field_0) AS Float64) AS COUNT(some_metric.field_0)";
+        assert!(plan.contains(expected_projection), "{plan}");
+        assert!(plan.contains(expected_aggregate), "{plan}");
+        assert!(plan.contains(expected_cast), "{plan}");
     }
 
     #[tokio::test]
-    #[should_panic]
     async fn aggregate_quantile() {
-        do_aggregate_expr_plan("quantile", "").await;
+        let plan = aggregate_plan_string(r#"quantile by (tag_1)(0.99, some_metric)"#).await;
+
+        let expected_aggregate =
+            "Aggregate: groupBy=[[some_metric.tag_1, some_metric.timestamp]], \
+            aggr=[[prom_quantile(some_metric.field_0, Float64(0.99))]]";
+        assert!(plan.contains(expected_aggregate), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn aggregate_with_per_step_param() {
+        let plan = aggregate_plan_string(r#"topk(time() / 10000, some_metric)"#).await;
+        assert!(
+            plan.contains("Filter: CAST(__topk_row_number AS Float64) <= "),
+            "{plan}"
+        );
+
+        let plan = aggregate_plan_string(r#"quantile by (tag_1)(-time() + 1, some_metric)"#).await;
+        assert!(
+            plan.contains("aggr=[[prom_quantile(some_metric.field_0, (- "),
+            "{plan}"
+        );
+        assert!(plan.contains("some_metric.timestamp"), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn aggregate_with_invalid_param() {
+        let cases = [
+            (
+                "topk(scalar(some_metric), some_metric)",
+                "Invalid function argument for topk",
+            ),
+            (
+                "quantile by (tag_1)(scalar(some_metric), some_metric)",
+                "Invalid function argument for quantile",
+            ),
+            (
+                r#"count_values("1value", some_metric)"#,
+                "Invalid label name in count_values: 1value",
+            ),
+        ];
+        for (query, expected) in cases {
+            let err = plan_test_query(query, 2, 1).await.unwrap_err();
+            assert_eq!(err.to_string(), expected, "{query}");
+        }
+
+        // multiple field columns are not supported
+        let err = plan_test_query("topk(1, some_metric)", 2, 2)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Multi fields calculation is not supported in topk"
+        );
     }
 
     // TODO(ruihang): add range fn tests once exprs are ready.
@@ -2473,6 +3372,92 @@ mod test {
         assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    #[tokio::test]
+    async fn group_left_join() {
+        let plan =
+            aggregate_plan_string(r#"some_metric * on(tag_0) group_left(tag_1) some_metric"#).await;
+
+        // the "one" side is checked to be unique on the matching labels
+        assert!(plan.contains(
+            "PromSeriesUniqueCheck: on col=[[\"tag_0\"]], time index=[timestamp], side=[right]"
+        ));
+        assert!(plan.contains("Inner Join: lhs.tag_0 = rhs.tag_0, lhs.timestamp = rhs.timestamp"));
+        // `tag_1` is copied from the "one" side
+        assert!(plan.contains(
+            "Projection: lhs.tag_0 AS tag_0, rhs.tag_1 AS tag_1, lhs.timestamp AS timestamp, lhs.field_0 * rhs.field_0 AS lhs.field_0 * rhs.field_0"
+        ));
+        // the result is checked to be unique after `tag_1` is copied
+        assert!(plan.contains(
+            "PromSeriesUniqueCheck: on col=[[\"tag_0\", \"tag_1\"]], time index=[timestamp], side=[result]"
+        ));
+    }
+
+    #[tokio::test]
+    async fn group_right_comparison() {
+        let plan =
+            aggregate_plan_string(r#"some_metric > ignoring(tag_1) group_right some_metric"#).await;
+
+        assert!(plan.contains(
+            "PromSeriesUniqueCheck: on col=[[\"tag_0\"]], time index=[timestamp], side=[left]"
+        ));
+        assert!(plan.contains("Inner Join: rhs.tag_0 = lhs.tag_0, rhs.timestamp = lhs.timestamp"));
+        // comparison without `bool` filters and keeps the left hand-side value
+        assert!(plan.contains("Filter: lhs.field_0 > rhs.field_0"));
+        assert!(plan.contains(
+            "Projection: rhs.tag_0 AS tag_0, rhs.tag_1 AS tag_1, rhs.timestamp AS timestamp, lhs.field_0 AS field_0"
+        ));
+    }
+
+    #[tokio::test]
+    async fn label_replace() {
+        let plan = aggregate_plan_string(
+            r#"label_replace(some_metric, "foo", "$1-x", "tag_0", "(.*):.*")"#,
+        )
+        .await;
+
+        let expected = "Projection: some_metric.timestamp, some_metric.tag_0, some_metric.tag_1, \
+            CASE WHEN regexp_match(coalesce(some_metric.tag_0, Utf8(\"\")), Utf8(\"^(?:(.*):.*)$\")) IS NOT NULL \
+            THEN nullif(regexp_replace(coalesce(some_metric.tag_0, Utf8(\"\")), Utf8(\"^(?:(.*):.*)$\"), Utf8(\"$1-x\")), Utf8(\"\")) \
+            ELSE Utf8(NULL) END AS foo, some_metric.field_0";
+        assert!(plan.contains(expected), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn label_join() {
+        let plan = aggregate_plan_string(
+            r#"label_join(some_metric, "tag_1", "-", "tag_0", "tag_1", "__name__")"#,
+        )
+        .await;
+
+        // the existing label is overwritten
+        let expected = "Projection: some_metric.timestamp, some_metric.tag_0, \
+            nullif(concat_ws(Utf8(\"-\"), coalesce(some_metric.tag_0, Utf8(\"\")), coalesce(some_metric.tag_1, Utf8(\"\")), Utf8(\"some_metric\")), Utf8(\"\")) AS tag_1, \
+            some_metric.field_0";
+        assert!(plan.contains(expected), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn label_replace_with_invalid_args() {
+        let cases = [
+            (
+                r#"label_replace(some_metric, "foo", "$1", "tag_0", "(.*")"#,
+                "Invalid regular expression in label_replace: ^(?:(.*)$",
+            ),
+            (
+                r#"label_replace(some_metric, "1foo", "$1", "tag_0", "(.*)")"#,
+                "Invalid label name in label_replace: 1foo",
+            ),
+            (
+                r#"label_join(some_metric, "__name__", "-", "tag_0")"#,
+                "Invalid label name in label_join: __name__",
+            ),
+        ];
+        for (query, expected) in cases {
+            let err = plan_test_query(query, 2, 1).await.unwrap_err();
+            assert_eq!(err.to_string(), expected, "{query}");
+        }
+    }
+
     async fn indie_query_plan_compare(query: &str, expected: String) {
         let prom_expr = parser::parse(query).unwrap();
         let eval_stmt = EvalStmt {
@@ -2615,6 +3600,47 @@ mod test {
         indie_query_plan_compare(query, expected).await;
     }
 
+    #[tokio::test]
+    async fn subquery_in_range_function() {
+        let plan = plan_test_query("max_over_time(rate(some_metric[5m])[1h:1m])", 1, 1)
+            .await
+            .unwrap()
+            .display_indent_schema()
+            .to_string();
+
+        // outer range function is evaluated on the query step with subquery range
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[3600000], time index=[timestamp], values=[\"prom_rate(timestamp_range,field_0,timestamp)\"]"
+        ));
+        // inner expression is evaluated on the subquery step
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[-3600000..100000000], interval=[60000], eval range=[300000], time index=[timestamp], values=[\"field_0\"]"
+        ));
+        assert!(plan.contains(
+            "PromSeriesNormalize: offset=[0], time index=[timestamp], filter NaN: [true]"
+        ));
+    }
+
+    #[tokio::test]
+    async fn subquery_with_offset_and_unaligned_start() {
+        let plan = plan_test_query("min_over_time(some_metric[10m:7m] offset 1m)", 1, 1)
+            .await
+            .unwrap()
+            .display_indent_schema()
+            .to_string();
+
+        // start = 0 - 60_000 - 600_000 = -660_000, aligned up to the 420_000 step
+        assert!(plan.contains(
+            "PromInstantManipulate: range=[-420000..99940000], lookback=[1000], interval=[420000], time index=[timestamp]"
+        ));
+        assert!(plan.contains(
+            "PromRangeManipulate: req range=[0..100000000], interval=[5000], eval range=[600000]"
+        ));
+        assert!(plan.contains(
+            "PromSeriesNormalize: offset=[60000], time index=[timestamp], filter NaN: [true]"
+        ));
+    }
+
     #[tokio::test]
     async fn value_matcher() {
         // template
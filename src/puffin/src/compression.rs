@@ -0,0 +1,329 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compression of puffin blobs.
+//!
+//! A compressed blob is split into chunks of [CHUNK_SIZE] bytes, each compressed as an
+//! independent frame, so a reader only decompresses the chunks it reads. A seek table
+//! of the chunks is stored in a skippable frame at the end of the blob:
+//!
+//! ```text
+//! Blob layout:   Frame₁ Frame₂ ... Frameₙ SeekTableFrame
+//!
+//! SeekTableFrame: SkippableMagic FrameSize FrameSize₁ ... FrameSizeₙ Trailer
+//!                 [4]            [4]       [4]            [4]        [20]
+//!
+//! Trailer:        ContentSize ChunkSize NumChunks SeekTableMagic
+//!                 [8]         [4]       [4]       [4]
+//! ```
+//!
+//! Both LZ4 and Zstd decoders ignore skippable frames, so the blob is still a valid
+//! stream of frames for readers that decompress the whole blob.
+//!
+//! The Puffin spec requires a blob with `compression-codec` to be a single frame, so a
+//! blob in the chunked layout leaves the field unset and stores its codec in the
+//! [CHUNKED_COMPRESSION_PROPERTY] property. Blobs with `compression-codec` written by
+//! other writers are decompressed as a whole.
+
+use std::io::{self, Read, Write};
+use std::ops::Range;
+
+use lz4_flex::frame::{FrameDecoder, FrameEncoder, FrameInfo};
+
+use crate::blob_metadata::CompressionCodec;
+
+/// Property of a blob compressed in chunks, the value is the name of the codec.
+pub(crate) const CHUNKED_COMPRESSION_PROPERTY: &str = "greptime-chunked-compression-codec";
+
+/// Size of uncompressed chunks of a compressed blob.
+pub(crate) const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Magic of the skippable frame, both LZ4 and Zstd skip frames with magic in
+/// `0x184D2A50..=0x184D2A5F`.
+const SKIPPABLE_FRAME_MAGIC: u32 = 0x184D2A5E;
+
+/// Magic at the end of the seek table.
+const SEEK_TABLE_MAGIC: u32 = 0x50465354;
+
+/// Size of the fields after the frame sizes in the seek table frame.
+pub(crate) const SEEK_TABLE_TRAILER_SIZE: u64 = 20;
+
+/// Seek table of the chunks of a compressed blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SeekTable {
+    /// Size of the decompressed blob.
+    content_size: u64,
+    /// Size of each uncompressed chunk except the last one.
+    chunk_size: u64,
+    /// Offsets of frames in the blob, with the end offset of the last frame.
+    frame_offsets: Vec<u64>,
+}
+
+impl SeekTable {
+    /// Creates an empty seek table of chunks with `chunk_size` bytes.
+    pub(crate) fn new(chunk_size: usize) -> Self {
+        Self {
+            content_size: 0,
+            chunk_size: chunk_size as u64,
+            frame_offsets: vec![0],
+        }
+    }
+
+    /// Adds a chunk of `content_size` bytes compressed into `frame_size` bytes.
+    pub(crate) fn push_chunk(&mut self, content_size: usize, frame_size: usize) {
+        let end = self.frames_size();
+        self.frame_offsets.push(end + frame_size as u64);
+        self.content_size += content_size as u64;
+    }
+
+    /// Returns the size of the decompressed blob.
+    pub(crate) fn content_size(&self) -> u64 {
+        self.content_size
+    }
+
+    /// Returns the index of the chunk containing the `position` of the decompressed blob.
+    pub(crate) fn chunk_index(&self, position: u64) -> usize {
+        (position / self.chunk_size) as usize
+    }
+
+    /// Returns the range of the decompressed blob in the chunk at `index`.
+    pub(crate) fn chunk_range(&self, index: usize) -> Range<u64> {
+        let start = index as u64 * self.chunk_size;
+        start..(start + self.chunk_size).min(self.content_size)
+    }
+
+    /// Returns the range of the blob storing the frame of the chunk at `index`.
+    pub(crate) fn frame_range(&self, index: usize) -> Range<u64> {
+        self.frame_offsets[index]..self.frame_offsets[index + 1]
+    }
+
+    /// Returns the size of all frames before the seek table frame.
+    pub(crate) fn frames_size(&self) -> u64 {
+        self.frame_offsets.last().copied().unwrap_or_default()
+    }
+
+    /// Encodes the seek table into a skippable frame.
+    pub(crate) fn to_frame(&self) -> Vec<u8> {
+        let num_chunks = self.frame_offsets.len() - 1;
+        let frame_size = num_chunks * 4 + SEEK_TABLE_TRAILER_SIZE as usize;
+        let mut frame = Vec::with_capacity(frame_size + 8);
+        frame.extend_from_slice(&SKIPPABLE_FRAME_MAGIC.to_le_bytes());
+        frame.extend_from_slice(&(frame_size as u32).to_le_bytes());
+        for range in self.frame_offsets.windows(2) {
+            frame.extend_from_slice(&((range[1] - range[0]) as u32).to_le_bytes());
+        }
+        frame.extend_from_slice(&self.content_size.to_le_bytes());
+        frame.extend_from_slice(&(self.chunk_size as u32).to_le_bytes());
+        frame.extend_from_slice(&(num_chunks as u32).to_le_bytes());
+        frame.extend_from_slice(&SEEK_TABLE_MAGIC.to_le_bytes());
+        frame
+    }
+
+    /// Parses the trailer at the end of the blob.
+    pub(crate) fn parse_trailer(trailer: &[u8]) -> io::Result<SeekTableTrailer> {
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(trailer[offset..offset + 4].try_into().unwrap()) as u64
+        };
+        if trailer.len() != SEEK_TABLE_TRAILER_SIZE as usize
+            || read_u32(16) as u32 != SEEK_TABLE_MAGIC
+        {
+            return Err(invalid_data("seek table of the compressed blob not found"));
+        }
+        let trailer = SeekTableTrailer {
+            content_size: u64::from_le_bytes(trailer[..8].try_into().unwrap()),
+            chunk_size: read_u32(8),
+            num_chunks: read_u32(12),
+        };
+        if trailer.chunk_size == 0 {
+            return Err(invalid_data("invalid chunk size 0 of the compressed blob"));
+        }
+        Ok(trailer)
+    }
+
+    /// Builds the seek table from the `trailer` and the `frame_sizes` before it.
+    pub(crate) fn from_frame_sizes(
+        trailer: SeekTableTrailer,
+        frame_sizes: &[u8],
+    ) -> io::Result<SeekTable> {
+        let mut frame_offsets = Vec::with_capacity(frame_sizes.len() / 4 + 1);
+        frame_offsets.push(0);
+        for frame_size in frame_sizes.chunks_exact(4) {
+            let frame_size = u32::from_le_bytes(frame_size.try_into().unwrap()) as u64;
+            frame_offsets.push(frame_offsets.last().unwrap() + frame_size);
+        }
+        let expected_chunks = trailer.content_size.div_ceil(trailer.chunk_size);
+        if frame_offsets.len() as u64 != trailer.num_chunks + 1
+            || trailer.num_chunks != expected_chunks
+        {
+            return Err(invalid_data("corrupted seek table of the compressed blob"));
+        }
+        Ok(SeekTable {
+            content_size: trailer.content_size,
+            chunk_size: trailer.chunk_size,
+            frame_offsets,
+        })
+    }
+}
+
+/// Fixed size fields at the end of the seek table frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SeekTableTrailer {
+    content_size: u64,
+    chunk_size: u64,
+    num_chunks: u64,
+}
+
+impl SeekTableTrailer {
+    /// Returns the size of the frame sizes before the trailer.
+    pub(crate) fn frame_sizes_len(&self) -> u64 {
+        self.num_chunks * 4
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Returns the codec named `name`, `None` if the codec is unknown.
+pub(crate) fn codec_from_name(name: &str) -> Option<CompressionCodec> {
+    match name {
+        "lz4" => Some(CompressionCodec::Lz4),
+        "zstd" => Some(CompressionCodec::Zstd),
+        _ => None,
+    }
+}
+
+/// Compresses `data` as a single frame of `codec` with content size present.
+pub(crate) fn compress(codec: CompressionCodec, data: &[u8]) -> io::Result<Vec<u8>> {
+    match codec {
+        CompressionCodec::Lz4 => {
+            let mut frame_info = FrameInfo::new();
+            frame_info.content_size = Some(data.len() as u64);
+            let mut encoder = FrameEncoder::with_frame_info(frame_info, Vec::new());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionCodec::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+    }
+}
+
+/// Decompresses `data` compressed by `codec`.
+pub(crate) fn decompress(codec: CompressionCodec, data: &[u8]) -> io::Result<Vec<u8>> {
+    match codec {
+        CompressionCodec::Lz4 => {
+            let mut buf = Vec::new();
+            FrameDecoder::new(data).read_to_end(&mut buf)?;
+            Ok(buf)
+        }
+        CompressionCodec::Zstd => zstd::stream::decode_all(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_table_frame() {
+        let mut table = SeekTable::new(CHUNK_SIZE);
+        table.push_chunk(CHUNK_SIZE, 100);
+        table.push_chunk(CHUNK_SIZE, 200);
+        table.push_chunk(10, 5);
+        assert_eq!(2 * CHUNK_SIZE as u64 + 10, table.content_size());
+        assert_eq!(1, table.chunk_index(CHUNK_SIZE as u64));
+        assert_eq!(
+            2 * CHUNK_SIZE as u64..2 * CHUNK_SIZE as u64 + 10,
+            table.chunk_range(2)
+        );
+        assert_eq!(100..300, table.frame_range(1));
+
+        let frame = table.to_frame();
+        let trailer =
+            SeekTable::parse_trailer(&frame[frame.len() - SEEK_TABLE_TRAILER_SIZE as usize..])
+                .unwrap();
+        assert_eq!(12, trailer.frame_sizes_len());
+        let frame_sizes = &frame[8..20];
+        assert_eq!(
+            table,
+            SeekTable::from_frame_sizes(trailer, frame_sizes).unwrap()
+        );
+
+        let trailer =
+            SeekTable::parse_trailer(&SeekTable::new(CHUNK_SIZE).to_frame()[8..]).unwrap();
+        assert_eq!(
+            SeekTable::new(CHUNK_SIZE),
+            SeekTable::from_frame_sizes(trailer, &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_seek_table() {
+        assert!(SeekTable::parse_trailer(&[0; 20]).is_err());
+        assert!(SeekTable::parse_trailer(&[0; 10]).is_err());
+
+        let mut table = SeekTable::new(CHUNK_SIZE);
+        table.push_chunk(CHUNK_SIZE, 100);
+        let frame = table.to_frame();
+        let trailer =
+            SeekTable::parse_trailer(&frame[frame.len() - SEEK_TABLE_TRAILER_SIZE as usize..])
+                .unwrap();
+        assert!(SeekTable::from_frame_sizes(trailer, &[]).is_err());
+    }
+
+    #[test]
+    fn test_decompress_frames_with_seek_table() {
+        let data = b"Hello, Puffin!".repeat(100);
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let mut blob = Vec::new();
+            let mut table = SeekTable::new(500);
+            for chunk in data.chunks(500) {
+                let frame = compress(codec, chunk).unwrap();
+                table.push_chunk(chunk.len(), frame.len());
+                blob.extend_from_slice(&frame);
+            }
+            blob.extend_from_slice(&table.to_frame());
+
+            // Decoders skip the seek table.
+            assert_eq!(data, decompress(codec, &blob).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_compress_decompress() {
+        let data = b"Hello, Puffin! Hello, Puffin! Hello, Puffin!".repeat(100);
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let compressed = compress(codec, &data).unwrap();
+            assert!(compressed.len() < data.len());
+            assert_eq!(data, decompress(codec, &compressed).unwrap());
+
+            let compressed = compress(codec, &[]).unwrap();
+            assert!(decompress(codec, &compressed).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_codec_from_name() {
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            assert_eq!(Some(codec), codec_from_name(&codec.to_string()));
+        }
+        assert_eq!(None, codec_from_name("gzip"));
+    }
+
+    #[test]
+    fn test_decompress_corrupted() {
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            assert!(decompress(codec, b"not compressed").is_err());
+        }
+    }
+}
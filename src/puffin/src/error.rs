@@ -69,6 +69,29 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to compress with {}", codec))]
+    Compress {
+        codec: String,
+        #[snafu(source)]
+        error: IoError,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to join compression task"))]
+    JoinCompressTask {
+        #[snafu(source)]
+        error: common_runtime::JoinError,
+        location: Location,
+    },
+
+    #[snafu(display("Failed to decompress with {}", codec))]
+    Decompress {
+        codec: String,
+        #[snafu(source)]
+        error: IoError,
+        location: Location,
+    },
+
     #[snafu(display("Unsupported decompression: {}", decompression))]
     UnsupportedDecompression {
         decompression: String,
@@ -129,6 +152,9 @@ impl ErrorExt for Error {
             | Flush { .. }
             | Close { .. }
             | SerializeJson { .. }
+            | Compress { .. }
+            | JoinCompressTask { .. }
+            | Decompress { .. }
             | BytesToInteger { .. }
             | ParseStageNotMatch { .. }
             | UnexpectedFooterPayloadSize { .. }
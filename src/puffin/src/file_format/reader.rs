@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod blob;
 mod file;
 mod footer;
 
@@ -19,6 +20,7 @@ use async_trait::async_trait;
 
 use crate::blob_metadata::BlobMetadata;
 use crate::error::Result;
+pub use crate::file_format::reader::blob::BlobReader;
 pub use crate::file_format::reader::file::PuffinFileReader;
 use crate::file_metadata::FileMetadata;
 
@@ -0,0 +1,499 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, Read, Seek};
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{ready, AsyncRead, AsyncSeek};
+use snafu::OptionExt;
+
+use crate::blob_metadata::{BlobMetadata, CompressionCodec};
+use crate::compression::{
+    codec_from_name, decompress, SeekTable, SeekTableTrailer, CHUNKED_COMPRESSION_PROPERTY,
+    SEEK_TABLE_TRAILER_SIZE,
+};
+use crate::error::{Result, UnsupportedDecompressionSnafu};
+use crate::partial_reader::{position_after_seek, PartialReader};
+
+/// Compression of a blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlobCompression {
+    /// A single frame of the codec, as the Puffin spec defines for `compression-codec`.
+    Frame(CompressionCodec),
+    /// Chunks compressed into frames of the codec with a seek table, see [crate::compression].
+    Chunked(CompressionCodec),
+}
+
+impl BlobCompression {
+    /// Returns the compression of the blob, `None` if the blob is uncompressed.
+    pub(crate) fn from_metadata(metadata: &BlobMetadata) -> Result<Option<BlobCompression>> {
+        if let Some(codec) = metadata.compression_codec {
+            return Ok(Some(BlobCompression::Frame(codec)));
+        }
+        let Some(name) = metadata.properties.get(CHUNKED_COMPRESSION_PROPERTY) else {
+            return Ok(None);
+        };
+        let codec = codec_from_name(name).context(UnsupportedDecompressionSnafu {
+            decompression: name,
+        })?;
+        Ok(Some(BlobCompression::Chunked(codec)))
+    }
+
+    fn codec(&self) -> CompressionCodec {
+        match self {
+            BlobCompression::Frame(codec) | BlobCompression::Chunked(codec) => *codec,
+        }
+    }
+}
+
+/// Reader for the data of a blob, implements both sync and async reads and seeks.
+///
+/// An uncompressed blob is read from the source directly. A blob of a single frame
+/// is decompressed as a whole on the first read or seek. A blob compressed in chunks
+/// is read chunk by chunk: the seek table at the end of the blob is loaded on the
+/// first read or seek, and a read only fetches and decompresses the chunk at the
+/// current position.
+pub struct BlobReader<R> {
+    /// The portion of the source where the blob is stored.
+    source: PartialReader<R>,
+
+    /// The compression of the blob, `None` if the blob is uncompressed.
+    compression: Option<BlobCompression>,
+
+    /// The trailer of the seek table, set before the frame sizes are read.
+    trailer: Option<SeekTableTrailer>,
+
+    /// The seek table of the compressed blob, a single frame blob has one chunk.
+    seek_table: Option<SeekTable>,
+
+    /// The current position in the decompressed blob.
+    position: u64,
+
+    /// The index and data of the last decompressed chunk.
+    chunk: Option<(usize, Vec<u8>)>,
+
+    /// The range of the source being read by async reads, kept across pending polls.
+    pending: Option<PendingRead>,
+}
+
+/// A read of a range of the source that may be resumed after `Poll::Pending`.
+struct PendingRead {
+    range: Range<u64>,
+    buf: Vec<u8>,
+    filled: usize,
+    seeked: bool,
+}
+
+impl<R> BlobReader<R> {
+    pub(crate) fn new(
+        source: R,
+        offset: u64,
+        size: u64,
+        compression: Option<BlobCompression>,
+    ) -> Self {
+        Self {
+            source: PartialReader::new(source, offset, size),
+            compression,
+            trailer: None,
+            seek_table: None,
+            position: 0,
+            chunk: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the range of the source to read before the compressed blob can be
+    /// read at the current position, or `None` if nothing is left to read.
+    ///
+    /// Only the seek table is loaded if `load_chunk` is false.
+    fn next_range(
+        &self,
+        compression: BlobCompression,
+        load_chunk: bool,
+    ) -> io::Result<Option<Range<u64>>> {
+        let size = self.source.size();
+        let Some(table) = &self.seek_table else {
+            if let BlobCompression::Frame(_) = compression {
+                return Ok(Some(0..size));
+            }
+            let Some(trailer) = &self.trailer else {
+                if size < SEEK_TABLE_TRAILER_SIZE {
+                    return Err(invalid_data("seek table of the compressed blob not found"));
+                }
+                return Ok(Some(size - SEEK_TABLE_TRAILER_SIZE..size));
+            };
+            // The frame sizes follow the skippable frame magic and frame size.
+            let end = size - SEEK_TABLE_TRAILER_SIZE;
+            let frame_sizes_len = trailer.frame_sizes_len();
+            if frame_sizes_len + 8 > end {
+                return Err(invalid_data("corrupted seek table of the compressed blob"));
+            }
+            return Ok(Some(end - frame_sizes_len..end));
+        };
+
+        if !load_chunk || self.position >= table.content_size() {
+            return Ok(None);
+        }
+        let index = table.chunk_index(self.position);
+        if matches!(&self.chunk, Some((i, _)) if *i == index) {
+            return Ok(None);
+        }
+        Ok(Some(table.frame_range(index)))
+    }
+
+    /// Handles the `data` read from the range returned by [BlobReader::next_range].
+    fn on_range_read(&mut self, compression: BlobCompression, data: Vec<u8>) -> io::Result<()> {
+        let codec = compression.codec();
+        let Some(table) = &self.seek_table else {
+            if let BlobCompression::Frame(_) = compression {
+                let content = decompress_frame(codec, &data)?;
+                let mut table = SeekTable::new(content.len().max(1));
+                table.push_chunk(content.len(), data.len());
+                self.seek_table = Some(table);
+                self.chunk = Some((0, content));
+                return Ok(());
+            }
+            match self.trailer.take() {
+                None => self.trailer = Some(SeekTable::parse_trailer(&data)?),
+                Some(trailer) => {
+                    let table = SeekTable::from_frame_sizes(trailer, &data)?;
+                    let table_frame_size = data.len() as u64 + 8 + SEEK_TABLE_TRAILER_SIZE;
+                    if table.frames_size() + table_frame_size != self.source.size() {
+                        return Err(invalid_data("corrupted seek table of the compressed blob"));
+                    }
+                    self.seek_table = Some(table);
+                }
+            }
+            return Ok(());
+        };
+
+        let index = table.chunk_index(self.position);
+        let chunk = decompress_frame(codec, &data)?;
+        let range = table.chunk_range(index);
+        if chunk.len() as u64 != range.end - range.start {
+            return Err(invalid_data(&format!(
+                "unexpected size {} of decompressed chunk {index}, expected {}",
+                chunk.len(),
+                range.end - range.start
+            )));
+        }
+        self.chunk = Some((index, chunk));
+        Ok(())
+    }
+
+    /// Reads the decompressed chunk at the current position into `buf`.
+    fn read_chunk(&mut self, buf: &mut [u8]) -> usize {
+        let (Some(table), Some((index, chunk))) = (&self.seek_table, &self.chunk) else {
+            return 0;
+        };
+        if self.position >= table.content_size() {
+            return 0;
+        }
+        let start = (self.position - table.chunk_range(*index).start) as usize;
+        let len = buf.len().min(chunk.len() - start);
+        buf[..len].copy_from_slice(&chunk[start..start + len]);
+        self.position += len as u64;
+        len
+    }
+
+    /// Seeks in the decompressed blob, the seek table must be loaded.
+    fn seek_chunk(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let content_size = self.seek_table.as_ref().map_or(0, |t| t.content_size());
+        self.position = position_after_seek(pos, self.position, content_size)?;
+        Ok(self.position)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn decompress_frame(codec: CompressionCodec, data: &[u8]) -> io::Result<Vec<u8>> {
+    decompress(codec, data)
+        .map_err(|e| invalid_data(&format!("failed to decompress blob with {codec}: {e}")))
+}
+
+impl<R: io::Read + io::Seek> BlobReader<R> {
+    fn load_sync(&mut self, compression: BlobCompression, load_chunk: bool) -> io::Result<()> {
+        while let Some(range) = self.next_range(compression, load_chunk)? {
+            let mut data = vec![0; (range.end - range.start) as usize];
+            self.source.seek(io::SeekFrom::Start(range.start))?;
+            self.source.read_exact(&mut data)?;
+            self.on_range_read(compression, data)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: io::Read + io::Seek> io::Read for BlobReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.compression {
+            None => self.source.read(buf),
+            Some(compression) => {
+                self.load_sync(compression, true)?;
+                Ok(self.read_chunk(buf))
+            }
+        }
+    }
+}
+
+impl<R: io::Read + io::Seek> io::Seek for BlobReader<R> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        match self.compression {
+            None => self.source.seek(pos),
+            Some(compression) => {
+                self.load_sync(compression, false)?;
+                self.seek_chunk(pos)
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> BlobReader<R> {
+    fn poll_load(
+        &mut self,
+        cx: &mut Context<'_>,
+        compression: BlobCompression,
+        load_chunk: bool,
+    ) -> Poll<io::Result<()>> {
+        while let Some(range) = self.next_range(compression, load_chunk)? {
+            let data = ready!(self.poll_read_range(cx, range))?;
+            self.on_range_read(compression, data)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_read_range(
+        &mut self,
+        cx: &mut Context<'_>,
+        range: Range<u64>,
+    ) -> Poll<io::Result<Vec<u8>>> {
+        if self.pending.as_ref().map_or(true, |p| p.range != range) {
+            self.pending = Some(PendingRead {
+                buf: vec![0; (range.end - range.start) as usize],
+                range,
+                filled: 0,
+                seeked: false,
+            });
+        }
+
+        let pending = self.pending.as_mut().unwrap();
+        if !pending.seeked {
+            let start = io::SeekFrom::Start(pending.range.start);
+            ready!(Pin::new(&mut self.source).poll_seek(cx, start))?;
+            pending.seeked = true;
+        }
+        while pending.filled < pending.buf.len() {
+            let buf = &mut pending.buf[pending.filled..];
+            let read_bytes = ready!(Pin::new(&mut self.source).poll_read(cx, buf))?;
+            if read_bytes == 0 {
+                self.pending = None;
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+            pending.filled += read_bytes;
+        }
+        Poll::Ready(Ok(self.pending.take().unwrap().buf))
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for BlobReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match this.compression {
+            None => Pin::new(&mut this.source).poll_read(cx, buf),
+            Some(compression) => {
+                ready!(this.poll_load(cx, compression, true))?;
+                Poll::Ready(Ok(this.read_chunk(buf)))
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncSeek for BlobReader<R> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: io::SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        match this.compression {
+            None => Pin::new(&mut this.source).poll_seek(cx, pos),
+            Some(compression) => {
+                ready!(this.poll_load(cx, compression, false))?;
+                Poll::Ready(this.seek_chunk(pos))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::Cursor as AsyncCursor;
+    use futures::{AsyncReadExt, AsyncSeekExt};
+
+    use super::*;
+    use crate::blob_metadata::BlobMetadataBuilder;
+    use crate::compression::compress;
+
+    /// Builds a file with a blob of `data`, a chunked blob is compressed in chunks of 16 bytes.
+    fn file_with_blob(data: &[u8], compression: Option<BlobCompression>) -> (Vec<u8>, u64) {
+        let blob = match compression {
+            Some(BlobCompression::Frame(codec)) => compress(codec, data).unwrap(),
+            Some(BlobCompression::Chunked(codec)) => {
+                let mut blob = Vec::new();
+                let mut table = SeekTable::new(16);
+                for chunk in data.chunks(16) {
+                    let frame = compress(codec, chunk).unwrap();
+                    table.push_chunk(chunk.len(), frame.len());
+                    blob.extend_from_slice(&frame);
+                }
+                blob.extend_from_slice(&table.to_frame());
+                blob
+            }
+            None => data.to_vec(),
+        };
+        let mut file = vec![0; 10];
+        file.extend_from_slice(&blob);
+        file.extend_from_slice(&[0; 10]);
+        (file, blob.len() as u64)
+    }
+
+    fn all_compressions() -> [Option<BlobCompression>; 5] {
+        [
+            None,
+            Some(BlobCompression::Frame(CompressionCodec::Lz4)),
+            Some(BlobCompression::Frame(CompressionCodec::Zstd)),
+            Some(BlobCompression::Chunked(CompressionCodec::Lz4)),
+            Some(BlobCompression::Chunked(CompressionCodec::Zstd)),
+        ]
+    }
+
+    #[test]
+    fn test_read_seek_blob_sync() {
+        let data: Vec<u8> = (0..100).collect();
+        for compression in all_compressions() {
+            let (file, size) = file_with_blob(&data, compression);
+            let mut reader = BlobReader::new(io::Cursor::new(file), 10, size, compression);
+
+            let mut buf = vec![0; 10];
+            reader.seek(io::SeekFrom::Start(20)).unwrap();
+            reader.read_exact(&mut buf).unwrap();
+            assert_eq!(&data[20..30], &buf[..]);
+
+            // Reads across chunks.
+            let mut buf = vec![0; 40];
+            reader.seek(io::SeekFrom::Current(-5)).unwrap();
+            reader.read_exact(&mut buf).unwrap();
+            assert_eq!(&data[25..65], &buf[..]);
+
+            let mut buf = vec![];
+            reader.seek(io::SeekFrom::End(-10)).unwrap();
+            reader.read_to_end(&mut buf).unwrap();
+            assert_eq!(&data[90..], &buf[..]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_seek_blob_async() {
+        let data: Vec<u8> = (0..100).collect();
+        for compression in all_compressions() {
+            let (file, size) = file_with_blob(&data, compression);
+            let mut reader = BlobReader::new(AsyncCursor::new(file), 10, size, compression);
+
+            let mut buf = vec![0; 10];
+            reader.seek(io::SeekFrom::Start(20)).await.unwrap();
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&data[20..30], &buf[..]);
+
+            // Reads across chunks.
+            let mut buf = vec![0; 40];
+            reader.seek(io::SeekFrom::Current(-5)).await.unwrap();
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&data[25..65], &buf[..]);
+
+            let mut buf = vec![];
+            reader.seek(io::SeekFrom::End(-10)).await.unwrap();
+            reader.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(&data[90..], &buf[..]);
+        }
+    }
+
+    #[test]
+    fn test_seek_beyond_blob() {
+        let data: Vec<u8> = (0..100).collect();
+        for compression in all_compressions() {
+            let (file, size) = file_with_blob(&data, compression);
+            let mut reader = BlobReader::new(io::Cursor::new(file), 10, size, compression);
+            assert_eq!(100, reader.seek(io::SeekFrom::End(0)).unwrap());
+            assert_eq!(0, reader.read(&mut [0; 10]).unwrap());
+            let err = reader.seek(io::SeekFrom::Start(101)).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+        }
+    }
+
+    #[test]
+    fn test_read_corrupted_blob() {
+        let data: Vec<u8> = (0..100).collect();
+        for compression in all_compressions().into_iter().flatten() {
+            let mut reader =
+                BlobReader::new(io::Cursor::new(data.clone()), 10, 20, Some(compression));
+            let err = reader.read(&mut [0; 10]).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        }
+
+        // The blob is truncated.
+        let compression = Some(BlobCompression::Chunked(CompressionCodec::Lz4));
+        let (file, size) = file_with_blob(&data, compression);
+        let mut reader = BlobReader::new(io::Cursor::new(file), 10, size - 10, compression);
+        let err = reader.read(&mut [0; 10]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn test_blob_compression_from_metadata() {
+        let mut metadata = BlobMetadataBuilder::default()
+            .blob_type("some-blob".to_string())
+            .offset(0)
+            .length(0)
+            .build()
+            .unwrap();
+        assert_eq!(None, BlobCompression::from_metadata(&metadata).unwrap());
+
+        metadata
+            .properties
+            .insert(CHUNKED_COMPRESSION_PROPERTY.to_string(), "zstd".to_string());
+        assert_eq!(
+            Some(BlobCompression::Chunked(CompressionCodec::Zstd)),
+            BlobCompression::from_metadata(&metadata).unwrap()
+        );
+
+        metadata.compression_codec = Some(CompressionCodec::Lz4);
+        assert_eq!(
+            Some(BlobCompression::Frame(CompressionCodec::Lz4)),
+            BlobCompression::from_metadata(&metadata).unwrap()
+        );
+
+        metadata.compression_codec = None;
+        metadata
+            .properties
+            .insert(CHUNKED_COMPRESSION_PROPERTY.to_string(), "gzip".to_string());
+        assert!(BlobCompression::from_metadata(&metadata).is_err());
+    }
+}
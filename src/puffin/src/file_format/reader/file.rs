@@ -21,13 +21,12 @@ use snafu::{ensure, ResultExt};
 use crate::blob_metadata::BlobMetadata;
 use crate::error::{
     MagicNotMatchedSnafu, ReadSnafu, Result, SeekSnafu, UnexpectedPuffinFileSizeSnafu,
-    UnsupportedDecompressionSnafu,
 };
+use crate::file_format::reader::blob::BlobCompression;
 use crate::file_format::reader::footer::FooterParser;
-use crate::file_format::reader::{PuffinAsyncReader, PuffinSyncReader};
+use crate::file_format::reader::{BlobReader, PuffinAsyncReader, PuffinSyncReader};
 use crate::file_format::{MAGIC, MAGIC_SIZE, MIN_FILE_SIZE};
 use crate::file_metadata::FileMetadata;
-use crate::partial_reader::PartialReader;
 
 /// Puffin file reader, implemented [`PuffinSyncReader`] and [`PuffinAsyncReader`]
 ///
@@ -64,7 +63,7 @@ impl<R> PuffinFileReader<R> {
 }
 
 impl<'a, R: io::Read + io::Seek + 'a> PuffinSyncReader<'a> for PuffinFileReader<R> {
-    type Reader = PartialReader<&'a mut R>;
+    type Reader = BlobReader<&'a mut R>;
 
     fn metadata(&mut self) -> Result<FileMetadata> {
         if let Some(metadata) = &self.metadata {
@@ -85,19 +84,11 @@ impl<'a, R: io::Read + io::Seek + 'a> PuffinSyncReader<'a> for PuffinFileReader<
     }
 
     fn blob_reader(&'a mut self, blob_metadata: &BlobMetadata) -> Result<Self::Reader> {
-        // TODO(zhongzc): support decompression
-        let compression = blob_metadata.compression_codec.as_ref();
-        ensure!(
-            compression.is_none(),
-            UnsupportedDecompressionSnafu {
-                decompression: compression.unwrap().to_string()
-            }
-        );
-
-        Ok(PartialReader::new(
+        Ok(BlobReader::new(
             &mut self.source,
             blob_metadata.offset as _,
             blob_metadata.length as _,
+            BlobCompression::from_metadata(blob_metadata)?,
         ))
     }
 }
@@ -106,7 +97,7 @@ impl<'a, R: io::Read + io::Seek + 'a> PuffinSyncReader<'a> for PuffinFileReader<
 impl<'a, R: AsyncRead + AsyncSeek + Unpin + Send + 'a> PuffinAsyncReader<'a>
     for PuffinFileReader<R>
 {
-    type Reader = PartialReader<&'a mut R>;
+    type Reader = BlobReader<&'a mut R>;
 
     async fn metadata(&'a mut self) -> Result<FileMetadata> {
         if let Some(metadata) = &self.metadata {
@@ -132,19 +123,11 @@ impl<'a, R: AsyncRead + AsyncSeek + Unpin + Send + 'a> PuffinAsyncReader<'a>
     }
 
     fn blob_reader(&'a mut self, blob_metadata: &BlobMetadata) -> Result<Self::Reader> {
-        // TODO(zhongzc): support decompression
-        let compression = blob_metadata.compression_codec.as_ref();
-        ensure!(
-            compression.is_none(),
-            UnsupportedDecompressionSnafu {
-                decompression: compression.unwrap().to_string()
-            }
-        );
-
-        Ok(PartialReader::new(
+        Ok(BlobReader::new(
             &mut self.source,
             blob_metadata.offset as _,
             blob_metadata.length as _,
+            BlobCompression::from_metadata(blob_metadata)?,
         ))
     }
 }
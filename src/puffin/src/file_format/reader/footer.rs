@@ -17,10 +17,12 @@ use std::io::{self, SeekFrom};
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use snafu::{ensure, ResultExt};
 
+use crate::blob_metadata::CompressionCodec;
+use crate::compression::decompress;
 use crate::error::{
-    BytesToIntegerSnafu, DeserializeJsonSnafu, InvalidBlobAreaEndSnafu, InvalidBlobOffsetSnafu,
-    MagicNotMatchedSnafu, ParseStageNotMatchSnafu, ReadSnafu, Result, SeekSnafu,
-    UnexpectedFooterPayloadSizeSnafu, UnsupportedDecompressionSnafu,
+    BytesToIntegerSnafu, DecompressSnafu, DeserializeJsonSnafu, InvalidBlobAreaEndSnafu,
+    InvalidBlobOffsetSnafu, MagicNotMatchedSnafu, ParseStageNotMatchSnafu, ReadSnafu, Result,
+    SeekSnafu, UnexpectedFooterPayloadSizeSnafu,
 };
 use crate::file_format::{Flags, FLAGS_SIZE, MAGIC, MAGIC_SIZE, MIN_FILE_SIZE, PAYLOAD_SIZE_SIZE};
 use crate::file_metadata::FileMetadata;
@@ -249,13 +251,13 @@ impl StageParser {
     }
 
     fn parse_payload(&self, bytes: &[u8]) -> Result<FileMetadata> {
-        // TODO(zhongzc): support lz4
-        ensure!(
-            !self.flags.contains(Flags::FOOTER_PAYLOAD_COMPRESSED_LZ4),
-            UnsupportedDecompressionSnafu {
-                decompression: "lz4"
-            }
-        );
+        if self.flags.contains(Flags::FOOTER_PAYLOAD_COMPRESSED_LZ4) {
+            let codec = CompressionCodec::Lz4;
+            let decompressed = decompress(codec, bytes).context(DecompressSnafu {
+                codec: codec.to_string(),
+            })?;
+            return serde_json::from_slice(&decompressed).context(DeserializeJsonSnafu);
+        }
 
         serde_json::from_slice(bytes).context(DeserializeJsonSnafu)
     }
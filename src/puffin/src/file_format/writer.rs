@@ -19,13 +19,14 @@ use std::collections::HashMap;
 
 use async_trait::async_trait;
 
+use crate::blob_metadata::CompressionCodec;
 use crate::error::Result;
 pub use crate::file_format::writer::file::PuffinFileWriter;
 
 /// Blob ready to be written
 pub struct Blob<R> {
     // TODO(zhongzc): ignore `input_fields`, `snapshot_id`, `sequence_number`
-    // and `compression_codec` for now to keep thing simple
+    // for now to keep thing simple
     /// The type of the blob
     pub blob_type: String,
 
@@ -34,6 +35,12 @@ pub struct Blob<R> {
 
     /// The properties of the blob
     pub properties: HashMap<String, String>,
+
+    /// The codec to compress the blob with, `None` to write the blob uncompressed
+    ///
+    /// The blob is compressed in chunks so readers can seek in it, the codec is
+    /// recorded in a blob property instead of the `compression-codec` field
+    pub compression_codec: Option<CompressionCodec>,
 }
 
 /// The trait for writing Puffin files synchronously
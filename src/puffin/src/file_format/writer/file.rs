@@ -16,11 +16,14 @@ use std::collections::HashMap;
 use std::{io, mem};
 
 use async_trait::async_trait;
-use futures::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use snafu::ResultExt;
 
-use crate::blob_metadata::{BlobMetadata, BlobMetadataBuilder};
-use crate::error::{CloseSnafu, FlushSnafu, Result, WriteSnafu};
+use crate::blob_metadata::{BlobMetadata, BlobMetadataBuilder, CompressionCodec};
+use crate::compression::{compress, SeekTable, CHUNKED_COMPRESSION_PROPERTY, CHUNK_SIZE};
+use crate::error::{
+    CloseSnafu, CompressSnafu, FlushSnafu, JoinCompressTaskSnafu, ReadSnafu, Result, WriteSnafu,
+};
 use crate::file_format::writer::footer::FooterWriter;
 use crate::file_format::writer::{Blob, PuffinAsyncWriter, PuffinSyncWriter};
 use crate::file_format::MAGIC;
@@ -38,6 +41,9 @@ pub struct PuffinFileWriter<W> {
 
     /// The number of bytes written
     written_bytes: u64,
+
+    /// Whether to compress the footer payload with LZ4
+    footer_lz4_compressed: bool,
 }
 
 impl<W> PuffinFileWriter<W> {
@@ -47,9 +53,17 @@ impl<W> PuffinFileWriter<W> {
             properties: HashMap::new(),
             blob_metadata: Vec::new(),
             written_bytes: 0,
+            footer_lz4_compressed: false,
         }
     }
 
+    /// Sets whether to compress the footer payload with LZ4, the only codec
+    /// the Puffin spec allows for the footer.
+    pub fn with_footer_lz4_compressed(mut self, footer_lz4_compressed: bool) -> Self {
+        self.footer_lz4_compressed = footer_lz4_compressed;
+        self
+    }
+
     fn create_blob_metadata(
         &self,
         typ: String,
@@ -64,6 +78,26 @@ impl<W> PuffinFileWriter<W> {
             .build()
             .expect("Required fields are not set")
     }
+
+    /// Returns properties of the blob, recording the codec of a blob compressed in chunks.
+    ///
+    /// The chunked layout isn't the single frame the Puffin spec requires for the
+    /// `compression-codec` field, so the codec is stored as a property instead.
+    fn blob_properties(
+        mut properties: HashMap<String, String>,
+        compression_codec: Option<CompressionCodec>,
+    ) -> HashMap<String, String> {
+        if let Some(codec) = compression_codec {
+            properties.insert(CHUNKED_COMPRESSION_PROPERTY.to_string(), codec.to_string());
+        }
+        properties
+    }
+
+    fn compress_blob(codec: CompressionCodec, data: &[u8]) -> Result<Vec<u8>> {
+        compress(codec, data).context(CompressSnafu {
+            codec: codec.to_string(),
+        })
+    }
 }
 
 impl<W: io::Write> PuffinSyncWriter for PuffinFileWriter<W> {
@@ -74,9 +108,29 @@ impl<W: io::Write> PuffinSyncWriter for PuffinFileWriter<W> {
     fn add_blob<R: io::Read>(&mut self, mut blob: Blob<R>) -> Result<()> {
         self.write_header_if_needed_sync()?;
 
-        let size = io::copy(&mut blob.data, &mut self.writer).context(WriteSnafu)?;
+        let size = match blob.compression_codec {
+            None => io::copy(&mut blob.data, &mut self.writer).context(WriteSnafu)?,
+            Some(codec) => {
+                // Each chunk is compressed into its own frame so readers can seek to it
+                let mut table = SeekTable::new(CHUNK_SIZE);
+                let mut chunk = vec![0; CHUNK_SIZE];
+                loop {
+                    let len = read_chunk_sync(&mut blob.data, &mut chunk).context(ReadSnafu)?;
+                    if len == 0 {
+                        break;
+                    }
+                    let frame = Self::compress_blob(codec, &chunk[..len])?;
+                    self.writer.write_all(&frame).context(WriteSnafu)?;
+                    table.push_chunk(len, frame.len());
+                }
+                let table_frame = table.to_frame();
+                self.writer.write_all(&table_frame).context(WriteSnafu)?;
+                table.frames_size() + table_frame.len() as u64
+            }
+        };
 
-        let blob_metadata = self.create_blob_metadata(blob.blob_type, blob.properties, size);
+        let properties = Self::blob_properties(blob.properties, blob.compression_codec);
+        let blob_metadata = self.create_blob_metadata(blob.blob_type, properties, size);
         self.blob_metadata.push(blob_metadata);
 
         self.written_bytes += size;
@@ -101,11 +155,42 @@ impl<W: AsyncWrite + Unpin + Send> PuffinAsyncWriter for PuffinFileWriter<W> {
     async fn add_blob<R: AsyncRead + Send>(&mut self, blob: Blob<R>) -> Result<()> {
         self.write_header_if_needed_async().await?;
 
-        let size = futures::io::copy(blob.data, &mut self.writer)
-            .await
-            .context(WriteSnafu)?;
+        let size = match blob.compression_codec {
+            None => futures::io::copy(blob.data, &mut self.writer)
+                .await
+                .context(WriteSnafu)?,
+            Some(codec) => {
+                // Each chunk is compressed into its own frame so readers can seek to it,
+                // compression runs on the blocking pool to not stall the async runtime
+                let data = blob.data;
+                futures::pin_mut!(data);
+                let mut table = SeekTable::new(CHUNK_SIZE);
+                let mut chunk = vec![0; CHUNK_SIZE];
+                loop {
+                    let len = read_chunk_async(&mut data, &mut chunk)
+                        .await
+                        .context(ReadSnafu)?;
+                    if len == 0 {
+                        break;
+                    }
+                    chunk.truncate(len);
+                    let (frame, buf) = compress_chunk_in_background(codec, chunk).await?;
+                    self.writer.write_all(&frame).await.context(WriteSnafu)?;
+                    table.push_chunk(len, frame.len());
+                    chunk = buf;
+                    chunk.resize(CHUNK_SIZE, 0);
+                }
+                let table_frame = table.to_frame();
+                self.writer
+                    .write_all(&table_frame)
+                    .await
+                    .context(WriteSnafu)?;
+                table.frames_size() + table_frame.len() as u64
+            }
+        };
 
-        let blob_metadata = self.create_blob_metadata(blob.blob_type, blob.properties, size);
+        let properties = Self::blob_properties(blob.properties, blob.compression_codec);
+        let blob_metadata = self.create_blob_metadata(blob.blob_type, properties, size);
         self.blob_metadata.push(blob_metadata);
 
         self.written_bytes += size;
@@ -135,6 +220,7 @@ impl<W: io::Write> PuffinFileWriter<W> {
         let bytes = FooterWriter::new(
             mem::take(&mut self.blob_metadata),
             mem::take(&mut self.properties),
+            self.footer_lz4_compressed,
         )
         .into_footer_bytes()?;
 
@@ -157,6 +243,7 @@ impl<W: AsyncWrite + Unpin> PuffinFileWriter<W> {
         let bytes = FooterWriter::new(
             mem::take(&mut self.blob_metadata),
             mem::take(&mut self.properties),
+            self.footer_lz4_compressed,
         )
         .into_footer_bytes()?;
 
@@ -165,3 +252,53 @@ impl<W: AsyncWrite + Unpin> PuffinFileWriter<W> {
         Ok(())
     }
 }
+
+/// Compresses the `chunk` on the blocking pool, returns the frame and the chunk
+/// buffer for reuse.
+async fn compress_chunk_in_background(
+    codec: CompressionCodec,
+    chunk: Vec<u8>,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (frame, chunk) = common_runtime::spawn_blocking_bg(move || {
+        let frame = compress(codec, &chunk);
+        (frame, chunk)
+    })
+    .await
+    .context(JoinCompressTaskSnafu)?;
+    let frame = frame.context(CompressSnafu {
+        codec: codec.to_string(),
+    })?;
+    Ok((frame, chunk))
+}
+
+/// Reads from `reader` until `buf` is full or the reader reaches EOF, returns
+/// the number of bytes read.
+fn read_chunk_sync(reader: &mut impl io::Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Async version of [read_chunk_sync].
+async fn read_chunk_async(
+    reader: &mut (impl AsyncRead + Unpin),
+    buf: &mut [u8],
+) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]).await {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
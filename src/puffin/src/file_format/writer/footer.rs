@@ -17,8 +17,9 @@ use std::mem;
 
 use snafu::ResultExt;
 
-use crate::blob_metadata::BlobMetadata;
-use crate::error::{Result, SerializeJsonSnafu};
+use crate::blob_metadata::{BlobMetadata, CompressionCodec};
+use crate::compression::compress;
+use crate::error::{CompressSnafu, Result, SerializeJsonSnafu};
 use crate::file_format::{Flags, MAGIC, MIN_FOOTER_SIZE};
 use crate::file_metadata::FileMetadataBuilder;
 
@@ -31,13 +32,19 @@ use crate::file_metadata::FileMetadataBuilder;
 pub struct FooterWriter {
     blob_metadata: Vec<BlobMetadata>,
     file_properties: HashMap<String, String>,
+    lz4_compressed: bool,
 }
 
 impl FooterWriter {
-    pub fn new(blob_metadata: Vec<BlobMetadata>, file_properties: HashMap<String, String>) -> Self {
+    pub fn new(
+        blob_metadata: Vec<BlobMetadata>,
+        file_properties: HashMap<String, String>,
+        lz4_compressed: bool,
+    ) -> Self {
         Self {
             blob_metadata,
             file_properties,
+            lz4_compressed,
         }
     }
 
@@ -69,11 +76,14 @@ impl FooterWriter {
         buf.extend_from_slice(&payload_size.to_le_bytes());
     }
 
-    /// Appends reserved flags (currently zero-initialized) to the given buffer.
-    ///
-    /// TODO(zhongzc): support compression
+    /// Appends flags to the given buffer, marking whether the payload is compressed.
     fn write_flags(&self, buf: &mut Vec<u8>) {
-        buf.extend_from_slice(&Flags::DEFAULT.bits().to_le_bytes());
+        let flags = if self.lz4_compressed {
+            Flags::FOOTER_PAYLOAD_COMPRESSED_LZ4
+        } else {
+            Flags::DEFAULT
+        };
+        buf.extend_from_slice(&flags.bits().to_le_bytes());
     }
 
     fn footer_payload(&mut self) -> Result<Vec<u8>> {
@@ -83,6 +93,14 @@ impl FooterWriter {
             .build()
             .expect("Required fields are not set");
 
-        serde_json::to_vec(&file_metadata).context(SerializeJsonSnafu)
+        let payload = serde_json::to_vec(&file_metadata).context(SerializeJsonSnafu)?;
+        if !self.lz4_compressed {
+            return Ok(payload);
+        }
+
+        let codec = CompressionCodec::Lz4;
+        compress(codec, &payload).context(CompressSnafu {
+            codec: codec.to_string(),
+        })
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod blob_metadata;
+mod compression;
 pub mod error;
 pub mod file_format;
 pub mod file_metadata;
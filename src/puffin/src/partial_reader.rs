@@ -17,6 +17,7 @@ mod position;
 mod sync;
 
 use pin_project::pin_project;
+pub(crate) use position::position_after_seek;
 
 /// `PartialReader` to perform synchronous or asynchronous reads on a portion of a resource.
 #[pin_project]
@@ -14,12 +14,14 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 
 use futures::io::Cursor as AsyncCursor;
-use futures::AsyncReadExt;
+use futures::{AsyncReadExt, AsyncSeekExt};
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
+use crate::blob_metadata::CompressionCodec;
+use crate::compression::{CHUNKED_COMPRESSION_PROPERTY, CHUNK_SIZE};
 use crate::file_format::reader::{PuffinAsyncReader, PuffinFileReader, PuffinSyncReader};
 use crate::file_format::writer::{Blob, PuffinAsyncWriter, PuffinFileWriter, PuffinSyncWriter};
 
@@ -179,6 +181,7 @@ fn test_writer_reader_sync() {
             data: Cursor::new(&blob1),
             blob_type: "some-blob".to_string(),
             properties: Default::default(),
+            compression_codec: None,
         })
         .unwrap();
 
@@ -188,6 +191,7 @@ fn test_writer_reader_sync() {
             data: Cursor::new(&blob2),
             blob_type: "some-other-blob".to_string(),
             properties: Default::default(),
+            compression_codec: None,
         })
         .unwrap();
 
@@ -241,6 +245,7 @@ async fn test_writer_reader_async() {
             data: AsyncCursor::new(blob1),
             blob_type: "some-blob".to_string(),
             properties: Default::default(),
+            compression_codec: None,
         })
         .await
         .unwrap();
@@ -251,6 +256,7 @@ async fn test_writer_reader_async() {
             data: AsyncCursor::new(&blob2),
             blob_type: "some-other-blob".to_string(),
             properties: Default::default(),
+            compression_codec: None,
         })
         .await
         .unwrap();
@@ -292,3 +298,228 @@ async fn test_writer_reader_async() {
     some_other_blob.read_to_end(&mut buf).await.unwrap();
     assert_eq!(buf, blob2);
 }
+
+#[test]
+fn test_writer_reader_compressed_sync() {
+    let mut buf = Cursor::new(vec![]);
+
+    let mut writer = PuffinFileWriter::new(&mut buf).with_footer_lz4_compressed(true);
+
+    let blob1 = "abcdefghi".repeat(100);
+    writer
+        .add_blob(Blob {
+            data: Cursor::new(&blob1),
+            blob_type: "some-blob".to_string(),
+            properties: Default::default(),
+            compression_codec: Some(CompressionCodec::Lz4),
+        })
+        .unwrap();
+
+    let blob2 = include_bytes!("tests/resources/sample-metric-data.blob");
+    writer
+        .add_blob(Blob {
+            data: Cursor::new(&blob2),
+            blob_type: "some-other-blob".to_string(),
+            properties: Default::default(),
+            compression_codec: Some(CompressionCodec::Zstd),
+        })
+        .unwrap();
+
+    writer.set_properties(HashMap::from([(
+        "created-by".to_string(),
+        "Test 1234".to_string(),
+    )]));
+
+    let written_bytes = writer.finish().unwrap();
+    assert!(written_bytes > 0);
+
+    let mut buf = Cursor::new(buf.into_inner());
+    let mut reader = PuffinFileReader::new(&mut buf);
+    let metadata = reader.metadata().unwrap();
+
+    assert_eq!(metadata.properties.len(), 1);
+    assert_eq!(
+        metadata.properties.get("created-by"),
+        Some(&"Test 1234".to_string())
+    );
+
+    assert_eq!(metadata.blobs.len(), 2);
+    assert_eq!(metadata.blobs[0].blob_type, "some-blob");
+    assert_eq!(metadata.blobs[0].compression_codec, None);
+    assert_eq!(
+        metadata.blobs[0]
+            .properties
+            .get(CHUNKED_COMPRESSION_PROPERTY),
+        Some(&CompressionCodec::Lz4.to_string())
+    );
+    assert!((metadata.blobs[0].length as usize) < blob1.len());
+
+    assert_eq!(metadata.blobs[1].blob_type, "some-other-blob");
+    assert_eq!(metadata.blobs[1].compression_codec, None);
+    assert_eq!(
+        metadata.blobs[1]
+            .properties
+            .get(CHUNKED_COMPRESSION_PROPERTY),
+        Some(&CompressionCodec::Zstd.to_string())
+    );
+
+    let mut some_blob = reader.blob_reader(&metadata.blobs[0]).unwrap();
+    let mut buf = String::new();
+    some_blob.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, blob1);
+
+    let mut some_other_blob = reader.blob_reader(&metadata.blobs[1]).unwrap();
+    let mut buf = Vec::new();
+    some_other_blob.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, blob2);
+}
+
+#[tokio::test]
+async fn test_writer_reader_compressed_async() {
+    let mut buf = AsyncCursor::new(vec![]);
+
+    let mut writer = PuffinFileWriter::new(&mut buf).with_footer_lz4_compressed(true);
+
+    let blob1 = "abcdefghi".repeat(100);
+    writer
+        .add_blob(Blob {
+            data: AsyncCursor::new(blob1.as_bytes()),
+            blob_type: "some-blob".to_string(),
+            properties: Default::default(),
+            compression_codec: Some(CompressionCodec::Lz4),
+        })
+        .await
+        .unwrap();
+
+    let blob2 = include_bytes!("tests/resources/sample-metric-data.blob");
+    writer
+        .add_blob(Blob {
+            data: AsyncCursor::new(&blob2),
+            blob_type: "some-other-blob".to_string(),
+            properties: Default::default(),
+            compression_codec: Some(CompressionCodec::Zstd),
+        })
+        .await
+        .unwrap();
+
+    writer.set_properties(HashMap::from([(
+        "created-by".to_string(),
+        "Test 1234".to_string(),
+    )]));
+
+    let written_bytes = writer.finish().await.unwrap();
+    assert!(written_bytes > 0);
+
+    let mut buf = AsyncCursor::new(buf.into_inner());
+    let mut reader = PuffinFileReader::new(&mut buf);
+    let metadata = reader.metadata().await.unwrap();
+
+    assert_eq!(metadata.properties.len(), 1);
+    assert_eq!(
+        metadata.properties.get("created-by"),
+        Some(&"Test 1234".to_string())
+    );
+
+    assert_eq!(metadata.blobs.len(), 2);
+    assert_eq!(metadata.blobs[0].compression_codec, None);
+    assert_eq!(
+        metadata.blobs[0]
+            .properties
+            .get(CHUNKED_COMPRESSION_PROPERTY),
+        Some(&CompressionCodec::Lz4.to_string())
+    );
+    assert!((metadata.blobs[0].length as usize) < blob1.len());
+    assert_eq!(metadata.blobs[1].compression_codec, None);
+    assert_eq!(
+        metadata.blobs[1]
+            .properties
+            .get(CHUNKED_COMPRESSION_PROPERTY),
+        Some(&CompressionCodec::Zstd.to_string())
+    );
+
+    let mut some_blob = reader.blob_reader(&metadata.blobs[0]).unwrap();
+    let mut buf = String::new();
+    some_blob.read_to_string(&mut buf).await.unwrap();
+    assert_eq!(buf, blob1);
+
+    let mut some_other_blob = reader.blob_reader(&metadata.blobs[1]).unwrap();
+    let mut buf = Vec::new();
+    some_other_blob.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, blob2);
+}
+
+/// Data of 2.5 chunks that doesn't compress to nothing.
+fn multi_chunk_data() -> Vec<u8> {
+    (0..CHUNK_SIZE * 5 / 2).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn test_writer_reader_compressed_multi_chunks_sync() {
+    let data = multi_chunk_data();
+    for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+        let mut buf = Cursor::new(vec![]);
+        let mut writer = PuffinFileWriter::new(&mut buf);
+        writer
+            .add_blob(Blob {
+                data: Cursor::new(&data),
+                blob_type: "some-blob".to_string(),
+                properties: Default::default(),
+                compression_codec: Some(codec),
+            })
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut buf = Cursor::new(buf.into_inner());
+        let mut reader = PuffinFileReader::new(&mut buf);
+        let metadata = reader.metadata().unwrap();
+        let mut blob = reader.blob_reader(&metadata.blobs[0]).unwrap();
+
+        // Reads across the first and the second chunk.
+        let start = CHUNK_SIZE - 10;
+        let mut buf = vec![0; 20];
+        blob.seek(SeekFrom::Start(start as u64)).unwrap();
+        blob.read_exact(&mut buf).unwrap();
+        assert_eq!(&data[start..start + 20], &buf[..]);
+
+        let mut buf = Vec::new();
+        blob.rewind().unwrap();
+        blob.read_to_end(&mut buf).unwrap();
+        assert_eq!(data, buf);
+    }
+}
+
+#[tokio::test]
+async fn test_writer_reader_compressed_multi_chunks_async() {
+    let data = multi_chunk_data();
+    for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+        let mut buf = AsyncCursor::new(vec![]);
+        let mut writer = PuffinFileWriter::new(&mut buf);
+        writer
+            .add_blob(Blob {
+                data: AsyncCursor::new(&data),
+                blob_type: "some-blob".to_string(),
+                properties: Default::default(),
+                compression_codec: Some(codec),
+            })
+            .await
+            .unwrap();
+        writer.finish().await.unwrap();
+
+        let mut buf = AsyncCursor::new(buf.into_inner());
+        let mut reader = PuffinFileReader::new(&mut buf);
+        let metadata = reader.metadata().await.unwrap();
+        let mut blob = reader.blob_reader(&metadata.blobs[0]).unwrap();
+
+        // Reads across the second and the last chunk.
+        let start = CHUNK_SIZE * 2 - 10;
+        let mut buf = vec![0; 20];
+        blob.seek(SeekFrom::Start(start as u64)).await.unwrap();
+        blob.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&data[start..start + 20], &buf[..]);
+
+        let mut buf = Vec::new();
+        blob.seek(SeekFrom::Start(0)).await.unwrap();
+        blob.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(data, buf);
+    }
+}
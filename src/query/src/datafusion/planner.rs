@@ -21,6 +21,7 @@ use catalog::table_source::DfTableSourceProvider;
 use common_function::scalars::udf::create_udf;
 use common_query::logical_plan::create_aggregate_function;
 use datafusion::catalog::TableReference;
+use datafusion::datasource::DefaultTableSource;
 use datafusion::error::Result as DfResult;
 use datafusion::execution::context::SessionState;
 use datafusion::physical_plan::udaf::AggregateUDF;
@@ -32,9 +33,11 @@ use datafusion_expr::{TableSource, WindowUDF};
 use datafusion_physical_expr::var_provider::{is_system_variables, VarType};
 use datafusion_sql::parser::Statement as DfStatement;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use snafu::{ensure, OptionExt, ResultExt};
+use store_api::storage::TimeTravel;
+use table::table::adapter::DfTableProviderAdapter;
 
-use crate::error::{CatalogSnafu, DataFusionSnafu, Result};
+use crate::error::{CatalogSnafu, DataFusionSnafu, InvalidTimeTravelSnafu, Result};
 use crate::query_engine::QueryEngineState;
 
 pub struct DfContextProviderAdapter {
@@ -76,6 +79,44 @@ impl DfContextProviderAdapter {
             query_ctx,
         })
     }
+
+    /// Reads the resolved table `catalog.schema.table` at a historical state.
+    pub(crate) fn with_time_travel(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        time_travel: TimeTravel,
+    ) -> Result<()> {
+        let table_name = format!("{catalog}.{schema}.{table}");
+        let provider = self
+            .tables
+            .get(&table_name)
+            .and_then(|source| source.as_any().downcast_ref::<DefaultTableSource>())
+            .and_then(|source| {
+                source
+                    .table_provider
+                    .as_any()
+                    .downcast_ref::<DfTableProviderAdapter>()
+            })
+            .with_context(|| InvalidTimeTravelSnafu {
+                table: &table_name,
+                reason: "not a table referenced by the query",
+            })?;
+
+        if let Some(existing) = provider.time_travel() {
+            ensure!(
+                existing == time_travel,
+                InvalidTimeTravelSnafu {
+                    table: &table_name,
+                    reason: "conflicting AS OF clauses",
+                }
+            );
+        }
+        provider.with_time_travel(time_travel);
+
+        Ok(())
+    }
 }
 
 async fn resolve_tables(
@@ -30,7 +30,8 @@ use datafusion_common::TableReference;
 use datafusion_expr::{LogicalPlan, UserDefinedLogicalNode};
 use datafusion_optimizer::analyzer::Analyzer;
 use snafu::{OptionExt, ResultExt};
-use store_api::storage::RegionId;
+use store_api::storage::{RegionId, TimeTravel};
+use substrait::time_travel::encode_time_travel;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 pub use table::metadata::TableType;
 use table::table::adapter::DfTableProviderAdapter;
@@ -85,7 +86,7 @@ impl ExtensionPlanner for DistExtensionPlanner {
         }
 
         let optimized_plan = self.optimize_input_logical_plan(session_state, input_plan)?;
-        let Some(table_name) = Self::extract_full_table_name(input_plan)? else {
+        let Some((table_name, time_travel)) = Self::extract_full_table_name(input_plan)? else {
             // no relation found in input plan, going to execute them locally
             return fallback(&optimized_plan).await;
         };
@@ -99,10 +100,13 @@ impl ExtensionPlanner for DistExtensionPlanner {
         let schema = optimized_plan.schema().as_ref().into();
         // Pass down the original plan, allow execution nodes to do their optimization
         let amended_plan = Self::plan_with_full_table_name(input_plan.clone(), &table_name)?;
-        let substrait_plan = DFLogicalSubstraitConvertor
+        let mut substrait_plan = DFLogicalSubstraitConvertor
             .encode(&amended_plan)
-            .context(error::EncodeSubstraitLogicalPlanSnafu)?
-            .into();
+            .context(error::EncodeSubstraitLogicalPlanSnafu)?;
+        if let Some(time_travel) = &time_travel {
+            substrait_plan = encode_time_travel(&substrait_plan, time_travel)
+                .context(error::EncodeSubstraitLogicalPlanSnafu)?;
+        }
         let merge_scan_plan = MergeScanExec::new(
             table_name,
             regions,
@@ -115,11 +119,15 @@ impl ExtensionPlanner for DistExtensionPlanner {
 }
 
 impl DistExtensionPlanner {
-    /// Extract fully resolved table name from logical plan
-    fn extract_full_table_name(plan: &LogicalPlan) -> Result<Option<TableName>> {
+    /// Extract fully resolved table name and the time travel of the table from logical plan
+    fn extract_full_table_name(
+        plan: &LogicalPlan,
+    ) -> Result<Option<(TableName, Option<TimeTravel>)>> {
         let mut extractor = TableNameExtractor::default();
         let _ = plan.visit(&mut extractor)?;
-        Ok(extractor.table_name)
+        Ok(extractor
+            .table_name
+            .map(|table_name| (table_name, extractor.time_travel)))
     }
 
     /// Apply the fully resolved table name to the TableScan plan
@@ -160,6 +168,8 @@ impl DistExtensionPlanner {
 #[derive(Default)]
 struct TableNameExtractor {
     pub table_name: Option<TableName>,
+    /// Historical state of the table to read.
+    pub time_travel: Option<TimeTravel>,
 }
 
 impl TreeNodeVisitor for TableNameExtractor {
@@ -181,6 +191,7 @@ impl TreeNodeVisitor for TableNameExtractor {
                                 info.schema_name.clone(),
                                 info.name.clone(),
                             ));
+                            self.time_travel = provider.time_travel();
                         }
                         return Ok(VisitRecursion::Stop);
                     }
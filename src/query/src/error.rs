@@ -244,6 +244,13 @@ pub enum Error {
 
     #[snafu(display("Range Query: {}", msg))]
     RangeQuery { msg: String, location: Location },
+
+    #[snafu(display("Invalid time travel on table {}: {}", table, reason))]
+    InvalidTimeTravel {
+        table: String,
+        reason: String,
+        location: Location,
+    },
 }
 
 impl ErrorExt for Error {
@@ -270,7 +277,8 @@ impl ErrorExt for Error {
             | AddSystemTimeOverflow { .. }
             | ColumnSchemaIncompatible { .. }
             | UnsupportedVariable { .. }
-            | ColumnSchemaNoDefault { .. } => StatusCode::InvalidArguments,
+            | ColumnSchemaNoDefault { .. }
+            | InvalidTimeTravel { .. } => StatusCode::InvalidArguments,
 
             BuildBackend { .. } | ListObjects { .. } => StatusCode::StorageUnavailable,
             EncodeSubstraitLogicalPlan { source, .. } => source.status_code(),
@@ -19,6 +19,8 @@ use async_trait::async_trait;
 use catalog::table_source::DfTableSourceProvider;
 use common_error::ext::BoxedError;
 use common_telemetry::tracing;
+use common_time::timestamp::TimeUnit;
+use common_time::Timestamp;
 use datafusion::common::DFSchema;
 use datafusion::execution::context::SessionState;
 use datafusion::sql::planner::PlannerContext;
@@ -27,11 +29,16 @@ use datafusion_sql::planner::{ParserOptions, SqlToRel};
 use promql::planner::PromPlanner;
 use promql_parser::parser::EvalStmt;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
-use sql::ast::Expr as SqlExpr;
+use session::table_name::table_idents_to_full_name;
+use snafu::{OptionExt, ResultExt};
+use sql::ast::{Expr as SqlExpr, Value};
+use sql::statements::query::{AsOf, TableAsOf};
 use sql::statements::statement::Statement;
+use store_api::storage::TimeTravel;
 
-use crate::error::{DataFusionSnafu, PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu};
+use crate::error::{
+    DataFusionSnafu, InvalidTimeTravelSnafu, PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu,
+};
 use crate::parser::QueryStatement;
 use crate::plan::LogicalPlan;
 use crate::query_engine::QueryEngineState;
@@ -76,6 +83,9 @@ impl DfLogicalPlanner {
             query_ctx.clone(),
         )
         .await?;
+        if let Statement::Query(query) = &stmt {
+            apply_time_travels(&context_provider, &query.time_travels, &query_ctx)?;
+        }
 
         let config_options = self.session_state.config().options();
         let parser_options = ParserOptions {
@@ -147,6 +157,43 @@ impl DfLogicalPlanner {
     }
 }
 
+/// Sets the historical states of the tables in `AS OF` clauses.
+fn apply_time_travels(
+    context_provider: &DfContextProviderAdapter,
+    time_travels: &[TableAsOf],
+    query_ctx: &QueryContextRef,
+) -> Result<()> {
+    for TableAsOf { table, as_of } in time_travels {
+        let (catalog, schema, table_name) =
+            table_idents_to_full_name(table, query_ctx).context(SqlSnafu)?;
+        let time_travel = match as_of {
+            AsOf::Version(version) => TimeTravel::Version(*version),
+            AsOf::Snapshot(name) => TimeTravel::Snapshot(name.clone()),
+            AsOf::Timestamp(value) => {
+                let millis = match value {
+                    // Milliseconds since the epoch.
+                    Value::Number(n, _) => n.parse::<i64>().ok(),
+                    Value::SingleQuotedString(s) => {
+                        Timestamp::from_str(s, Some(&query_ctx.timezone()))
+                            .ok()
+                            .and_then(|ts| ts.convert_to(TimeUnit::Millisecond))
+                            .map(|ts| ts.value())
+                    }
+                    _ => None,
+                };
+                let millis = millis.with_context(|| InvalidTimeTravelSnafu {
+                    table: table.to_string(),
+                    reason: format!("invalid timestamp {value}"),
+                })?;
+                TimeTravel::Timestamp(millis)
+            }
+        };
+        context_provider.with_time_travel(&catalog, &schema, &table_name, time_travel)?;
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl LogicalPlanner for DfLogicalPlanner {
     #[tracing::instrument(skip_all)]
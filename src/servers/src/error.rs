@@ -256,6 +256,24 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid Flight action, reason: {}", reason))]
+    InvalidFlightAction { reason: String, location: Location },
+
+    #[snafu(display("Invalid Flight descriptor, reason: {}", reason))]
+    InvalidFlightDescriptor { reason: String, location: Location },
+
+    #[snafu(display("Failed to decode Flight data"))]
+    DecodeFlightData {
+        location: Location,
+        source: common_grpc::error::Error,
+    },
+
+    #[snafu(display("Table not found: {}", table_name))]
+    TableNotFound {
+        table_name: String,
+        location: Location,
+    },
+
     #[snafu(display("Tls is required for {}, plain connection is rejected", server))]
     TlsRequired { server: String },
 
@@ -395,6 +413,12 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Failed to convert jsonb to string"))]
+    ConvertJsonb {
+        source: datatypes::error::Error,
+        location: Location,
+    },
+
     #[snafu(display("Expected type: {:?}, actual: {:?}", expected, actual))]
     PreparedStmtTypeMismatch {
         expected: ConcreteDataType,
@@ -500,6 +524,8 @@ impl ErrorExt for Error {
             | InvalidPromRemoteRequest { .. }
             | InvalidExportMetricsConfig { .. }
             | InvalidFlightTicket { .. }
+            | InvalidFlightAction { .. }
+            | InvalidFlightDescriptor { .. }
             | InvalidPrepareStatement { .. }
             | DataFrame { .. }
             | PreparedStmtTypeMismatch { .. }
@@ -512,7 +538,8 @@ impl ErrorExt for Error {
 
             InfluxdbLinesWrite { source, .. }
             | PromSeriesWrite { source, .. }
-            | OtlpMetricsWrite { source, .. } => source.status_code(),
+            | OtlpMetricsWrite { source, .. }
+            | DecodeFlightData { source, .. } => source.status_code(),
 
             Hyper { .. } => StatusCode::Unknown,
             TlsRequired { .. } => StatusCode::Unknown,
@@ -527,6 +554,7 @@ impl ErrorExt for Error {
             | InvalidUtf8Value { .. } => StatusCode::InvalidAuthHeader,
 
             DatabaseNotFound { .. } => StatusCode::DatabaseNotFound,
+            TableNotFound { .. } => StatusCode::TableNotFound,
             #[cfg(feature = "mem-prof")]
             DumpProfileData { source, .. } => source.status_code(),
             InvalidFlushArgument { .. } => StatusCode::InvalidArguments,
@@ -551,7 +579,7 @@ impl ErrorExt for Error {
             #[cfg(feature = "pprof")]
             DumpPprof { source, .. } => source.status_code(),
 
-            ConvertScalarValue { source, .. } => source.status_code(),
+            ConvertScalarValue { source, .. } | ConvertJsonb { source, .. } => source.status_code(),
 
             ToJson { .. } => StatusCode::Internal,
         }
@@ -98,7 +98,7 @@ where
     }
 }
 
-async fn do_auth<T>(
+pub(crate) async fn do_auth<T>(
     req: &mut hyper::Request<T>,
     user_provider: Option<UserProviderRef>,
 ) -> Result<(), tonic::Status> {
@@ -20,6 +20,7 @@ use api::v1::region::region_server::RegionServer;
 use arrow_flight::flight_service_server::FlightServiceServer;
 use auth::UserProviderRef;
 use common_runtime::Runtime;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::LogsServiceServer;
 use opentelemetry_proto::tonic::collector::metrics::v1::metrics_service_server::MetricsServiceServer;
 use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::TraceServiceServer;
 use tokio::sync::Mutex;
@@ -131,10 +132,17 @@ impl GrpcServerBuilder {
         self.routes_builder.add_service(trace_server);
 
         let metrics_server = ServiceBuilder::new()
-            .layer(AuthMiddlewareLayer::with(user_provider))
-            .service(MetricsServiceServer::new(OtlpService::new(otlp_handler)));
+            .layer(AuthMiddlewareLayer::with(user_provider.clone()))
+            .service(MetricsServiceServer::new(OtlpService::new(
+                otlp_handler.clone(),
+            )));
         self.routes_builder.add_service(metrics_server);
 
+        let logs_server = ServiceBuilder::new()
+            .layer(AuthMiddlewareLayer::with(user_provider))
+            .service(LogsServiceServer::new(OtlpService::new(otlp_handler)));
+        self.routes_builder.add_service(logs_server);
+
         self
     }
 
@@ -17,19 +17,26 @@ mod stream;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use api::v1::GreptimeRequest;
+use api::v1::{AffectedRows, FlightMetadata, GreptimeRequest};
+use arrow_flight::flight_descriptor::DescriptorType;
 use arrow_flight::flight_service_server::FlightService;
 use arrow_flight::{
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
-    HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
+    HandshakeRequest, HandshakeResponse, PutResult, SchemaAsIpc, SchemaResult, Ticket,
 };
 use async_trait::async_trait;
-use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_grpc::flight::{FlightDecoder, FlightEncoder, FlightMessage};
+use common_meta::table_name::TableName;
 use common_query::Output;
 use common_telemetry::tracing_context::TracingContext;
-use futures::Stream;
+use datatypes::arrow::error::ArrowError;
+use datatypes::arrow::ipc::writer::IpcWriteOptions;
+use futures::{Stream, StreamExt};
 use prost::Message;
-use snafu::ResultExt;
+use session::context::QueryContextRef;
+use snafu::{ensure, OptionExt, ResultExt};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
 use crate::error;
@@ -37,6 +44,9 @@ pub use crate::grpc::flight::stream::FlightRecordBatchStream;
 use crate::grpc::greptime_handler::GreptimeRequestHandler;
 use crate::grpc::TonicResult;
 
+/// Size of the channel buffering [PutResult]s of `do_put` that are not consumed yet.
+const PUT_RESULT_CHANNEL_SIZE: usize = 16;
+
 pub type TonicStream<T> = Pin<Box<dyn Stream<Item = TonicResult<T>> + Send + Sync + 'static>>;
 
 /// A subset of [FlightService]
@@ -46,6 +56,20 @@ pub trait FlightCraft: Send + Sync + 'static {
         &self,
         request: Request<Ticket>,
     ) -> TonicResult<Response<TonicStream<FlightData>>>;
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<TonicStream<PutResult>>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<SchemaResult>> {
+        Err(Status::unimplemented("Not yet implemented"))
+    }
 }
 
 pub type FlightCraftRef = Arc<dyn FlightCraft>;
@@ -66,6 +90,20 @@ impl FlightCraft for FlightCraftRef {
     ) -> TonicResult<Response<TonicStream<FlightData>>> {
         (**self).do_get(request).await
     }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<TonicStream<PutResult>>> {
+        (**self).do_put(request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<SchemaResult>> {
+        (**self).get_schema(request).await
+    }
 }
 
 #[async_trait]
@@ -97,9 +135,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn get_schema(
         &self,
-        _: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> TonicResult<Response<SchemaResult>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.get_schema(request).await
     }
 
     type DoGetStream = TonicStream<FlightData>;
@@ -112,9 +150,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn do_put(
         &self,
-        _: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> TonicResult<Response<Self::DoPutStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.do_put(request).await
     }
 
     type DoExchangeStream = TonicStream<FlightData>;
@@ -158,6 +196,143 @@ impl FlightCraft for GreptimeRequestHandler {
             to_flight_data_stream(output, TracingContext::new());
         Ok(Response::new(stream))
     }
+
+    /// Writes the record batches in the stream into the table specified by the path of
+    /// the [FlightDescriptor] in the first message. A [PutResult] carrying the affected
+    /// rows is sent back as soon as each record batch is written.
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<TonicStream<PutResult>>> {
+        let query_ctx = self.flight_query_context(request.metadata()).await?;
+        let stream = request.into_inner();
+
+        let (tx, rx) = mpsc::channel(PUT_RESULT_CHANNEL_SIZE);
+        let handler = self.clone();
+        let _handle = tokio::spawn(async move {
+            if let Err(e) = put_record_batches(&handler, stream, query_ctx, &tx).await {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        let stream: TonicStream<PutResult> = Box::pin(ReceiverStream::new(rx));
+        Ok(Response::new(stream))
+    }
+
+    /// Gets the schema of the table specified by the path of the [FlightDescriptor].
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<SchemaResult>> {
+        let query_ctx = self.flight_query_context(request.metadata()).await?;
+        let table_name = table_name_from_descriptor(request.get_ref(), &query_ctx)?;
+        let schema = self.table_schema(&table_name, query_ctx).await?;
+
+        let options = IpcWriteOptions::default();
+        let schema_result = SchemaAsIpc::new(schema.arrow_schema(), &options)
+            .try_into()
+            .map_err(|e: ArrowError| Status::internal(e.to_string()))?;
+        Ok(Response::new(schema_result))
+    }
+}
+
+/// Decodes record batches from the stream and writes them one by one. Stops once the
+/// receiver of the results is dropped.
+async fn put_record_batches<S>(
+    handler: &GreptimeRequestHandler,
+    mut stream: S,
+    query_ctx: QueryContextRef,
+    tx: &mpsc::Sender<TonicResult<PutResult>>,
+) -> TonicResult<()>
+where
+    S: Stream<Item = TonicResult<FlightData>> + Unpin,
+{
+    let mut decoder = FlightDecoder::default();
+    let mut table_name = None;
+    while let Some(flight_data) = stream.next().await {
+        let flight_data = flight_data?;
+        if table_name.is_none()
+            && let Some(descriptor) = &flight_data.flight_descriptor
+        {
+            table_name = Some(table_name_from_descriptor(descriptor, &query_ctx)?);
+        }
+
+        match decoder
+            .try_decode(flight_data)
+            .context(error::DecodeFlightDataSnafu)?
+        {
+            FlightMessage::Schema(_) => {}
+            FlightMessage::Recordbatch(record_batch) => {
+                let table_name =
+                    table_name
+                        .clone()
+                        .context(error::InvalidFlightDescriptorSnafu {
+                            reason: "Expecting a FlightDescriptor in the first message",
+                        })?;
+                let affected_rows = handler
+                    .put_record_batch(table_name, record_batch, query_ctx.clone())
+                    .await?;
+                let metadata = FlightMetadata {
+                    affected_rows: Some(AffectedRows {
+                        value: affected_rows as _,
+                    }),
+                    metrics: None,
+                };
+                let result = PutResult {
+                    app_metadata: metadata.encode_to_vec().into(),
+                };
+                if tx.send(Ok(result)).await.is_err() {
+                    // the client has gone
+                    return Ok(());
+                }
+            }
+            FlightMessage::AffectedRows(_) | FlightMessage::Metrics(_) => {
+                return Err(Status::invalid_argument(
+                    "Expecting schema or record batch in FlightData",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the table name from the path of [FlightDescriptor]. The path can be
+/// `[table]`, `[schema, table]` or `[catalog, schema, table]`. The catalog and schema
+/// in the path must be the ones of the query context, which are authorized for the
+/// request, so the path can't be used to access tables of other databases.
+fn table_name_from_descriptor(
+    descriptor: &FlightDescriptor,
+    query_ctx: &QueryContextRef,
+) -> error::Result<TableName> {
+    ensure!(
+        descriptor.r#type == DescriptorType::Path as i32,
+        error::InvalidFlightDescriptorSnafu {
+            reason: "Expecting a path descriptor",
+        }
+    );
+
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    let (path_catalog, path_schema, table) = match descriptor.path.as_slice() {
+        [table] => (catalog, schema, table),
+        [path_schema, table] => (catalog, path_schema.as_str(), table),
+        [path_catalog, path_schema, table] => (path_catalog.as_str(), path_schema.as_str(), table),
+        path => {
+            return error::InvalidFlightDescriptorSnafu {
+                reason: format!("Invalid table path: {path:?}"),
+            }
+            .fail()
+        }
+    };
+    ensure!(
+        path_catalog == catalog && path_schema == schema,
+        error::InvalidFlightDescriptorSnafu {
+            reason: format!(
+                "Table {path_catalog}.{path_schema}.{table} is not in the database {catalog}.{schema} of the request"
+            ),
+        }
+    );
+    Ok(TableName::new(catalog, schema, table))
 }
 
 fn to_flight_data_stream(
@@ -181,3 +356,47 @@ fn to_flight_data_stream(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use session::context::QueryContext;
+
+    use super::*;
+
+    #[test]
+    fn test_table_name_from_descriptor() {
+        let query_ctx = QueryContext::with("greptime", "public");
+
+        let descriptor = FlightDescriptor::new_path(vec!["foo".to_string()]);
+        let table_name = table_name_from_descriptor(&descriptor, &query_ctx).unwrap();
+        assert_eq!(table_name, TableName::new("greptime", "public", "foo"));
+
+        let descriptor = FlightDescriptor::new_path(vec!["public".to_string(), "foo".to_string()]);
+        let table_name = table_name_from_descriptor(&descriptor, &query_ctx).unwrap();
+        assert_eq!(table_name, TableName::new("greptime", "public", "foo"));
+
+        let descriptor = FlightDescriptor::new_path(vec![
+            "greptime".to_string(),
+            "public".to_string(),
+            "foo".to_string(),
+        ]);
+        let table_name = table_name_from_descriptor(&descriptor, &query_ctx).unwrap();
+        assert_eq!(table_name, TableName::new("greptime", "public", "foo"));
+
+        // Tables out of the database of the request are rejected.
+        let descriptor = FlightDescriptor::new_path(vec!["db".to_string(), "foo".to_string()]);
+        assert!(table_name_from_descriptor(&descriptor, &query_ctx).is_err());
+        let descriptor = FlightDescriptor::new_path(vec![
+            "catalog".to_string(),
+            "public".to_string(),
+            "foo".to_string(),
+        ]);
+        assert!(table_name_from_descriptor(&descriptor, &query_ctx).is_err());
+
+        let descriptor = FlightDescriptor::new_path(vec![]);
+        assert!(table_name_from_descriptor(&descriptor, &query_ctx).is_err());
+
+        let descriptor = FlightDescriptor::new_cmd(b"foo".to_vec());
+        assert!(table_name_from_descriptor(&descriptor, &query_ctx).is_err());
+    }
+}
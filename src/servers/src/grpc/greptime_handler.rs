@@ -25,15 +25,24 @@ use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_catalog::parse_catalog_and_schema_from_db_string;
 use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
+use common_meta::table_name::TableName;
 use common_query::Output;
+use common_recordbatch::RecordBatch;
 use common_runtime::Runtime;
 use common_telemetry::logging;
 use common_time::timezone::parse_timezone;
+use datatypes::schema::SchemaRef;
 use session::context::{QueryContextBuilder, QueryContextRef};
 use snafu::{OptionExt, ResultExt};
+use tonic::metadata::MetadataMap;
 
 use crate::error::Error::UnsupportedAuthScheme;
-use crate::error::{AuthSnafu, InvalidQuerySnafu, JoinTaskSnafu, NotFoundAuthHeaderSnafu, Result};
+use crate::error::{
+    AuthSnafu, InvalidQuerySnafu, JoinTaskSnafu, MissingQueryContextSnafu, NotFoundAuthHeaderSnafu,
+    Result, TableNotFoundSnafu,
+};
+use crate::grpc::authorize::do_auth;
+use crate::grpc::TonicResult;
 use crate::metrics::{METRIC_AUTH_FAILURE, METRIC_SERVER_GRPC_DB_REQUEST_TIMER};
 use crate::query_handler::grpc::ServerGrpcQueryHandlerRef;
 
@@ -96,6 +105,67 @@ impl GreptimeRequestHandler {
             e
         })?
     }
+
+    /// Creates the query context of an Arrow Flight request. Unlike [GreptimeRequest],
+    /// Flight requests carry the database name and the authorization in the gRPC
+    /// metadata, in the same way as HTTP headers.
+    pub(crate) async fn flight_query_context(
+        &self,
+        metadata: &MetadataMap,
+    ) -> TonicResult<QueryContextRef> {
+        let mut request = hyper::Request::new(());
+        *request.headers_mut() = metadata.clone().into_headers();
+        do_auth(&mut request, self.user_provider.clone()).await?;
+        let query_ctx = request
+            .extensions()
+            .get::<QueryContextRef>()
+            .cloned()
+            .context(MissingQueryContextSnafu)?;
+        Ok(query_ctx)
+    }
+
+    /// Writes the record batch into the table. Like [Self::handle_request], the
+    /// insertion is executed in another runtime.
+    pub(crate) async fn put_record_batch(
+        &self,
+        table: TableName,
+        record_batch: RecordBatch,
+        query_ctx: QueryContextRef,
+    ) -> Result<usize> {
+        let handler = self.handler.clone();
+        let timer = RequestTimer::new(query_ctx.get_db_string(), "put_record_batch".to_string());
+
+        let handle = self.runtime.spawn(async move {
+            handler
+                .put_record_batch(&table, record_batch, query_ctx)
+                .await
+                .map_err(|e| {
+                    if e.status_code().should_log_error() {
+                        logging::error!(e; "Failed to put record batch");
+                    }
+                    e
+                })
+        });
+
+        handle.await.context(JoinTaskSnafu).map_err(|e| {
+            timer.record(e.status_code());
+            e
+        })?
+    }
+
+    /// Gets the schema of the table.
+    pub(crate) async fn table_schema(
+        &self,
+        table: &TableName,
+        query_ctx: QueryContextRef,
+    ) -> Result<SchemaRef> {
+        self.handler
+            .table_schema(table, query_ctx)
+            .await?
+            .with_context(|| TableNotFoundSnafu {
+                table_name: table.to_string(),
+            })
+    }
 }
 
 pub(crate) async fn auth(
@@ -35,6 +35,7 @@ pub mod constants {
     pub const GREPTIME_DB_HEADER_METRICS: &str = "x-greptime-metrics";
     pub const GREPTIME_DB_HEADER_NAME: &str = "x-greptime-db-name";
     pub const GREPTIME_TIMEZONE_HEADER_NAME: &str = "x-greptime-timezone";
+    pub const GREPTIME_LOG_TABLE_NAME_HEADER_NAME: &str = "x-greptime-log-table-name";
     pub const GREPTIME_DB_HEADER_ERROR_CODE: &str = common_error::GREPTIME_DB_HEADER_ERROR_CODE;
     pub const GREPTIME_DB_HEADER_ERROR_MSG: &str = common_error::GREPTIME_DB_HEADER_ERROR_MSG;
 }
@@ -54,6 +55,10 @@ pub static GREPTIME_DB_HEADER_NAME: HeaderName =
 pub static GREPTIME_TIMEZONE_HEADER_NAME: HeaderName =
     HeaderName::from_static(constants::GREPTIME_TIMEZONE_HEADER_NAME);
 
+/// Header key of the table to write OpenTelemetry logs into.
+pub static GREPTIME_LOG_TABLE_NAME_HEADER_NAME: HeaderName =
+    HeaderName::from_static(constants::GREPTIME_LOG_TABLE_NAME_HEADER_NAME);
+
 pub struct GreptimeDbName(Option<String>);
 
 impl Header for GreptimeDbName {
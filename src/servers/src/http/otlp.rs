@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use axum::extract::{RawBody, State};
-use axum::http::header;
+use axum::http::{header, HeaderMap};
 use axum::response::IntoResponse;
 use axum::Extension;
 use hyper::Body;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
 };
@@ -28,6 +31,8 @@ use session::context::QueryContextRef;
 use snafu::prelude::*;
 
 use crate::error::{self, Result};
+use crate::http::header::GREPTIME_LOG_TABLE_NAME_HEADER_NAME;
+use crate::otlp::logs::LOG_TABLE_NAME;
 use crate::query_handler::OpenTelemetryProtocolHandlerRef;
 
 #[axum_macros::debug_handler]
@@ -105,3 +110,57 @@ impl IntoResponse for OtlpTracesResponse {
             .into_response()
     }
 }
+
+#[axum_macros::debug_handler]
+pub async fn logs(
+    State(handler): State<OpenTelemetryProtocolHandlerRef>,
+    Extension(query_ctx): Extension<QueryContextRef>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<OtlpLogsResponse> {
+    let db = query_ctx.get_db_string();
+    let _timer = crate::metrics::METRIC_HTTP_OPENTELEMETRY_LOGS_ELAPSED
+        .with_label_values(&[db.as_str()])
+        .start_timer();
+    let table_name = log_table_name(&headers)?;
+    let request = parse_logs_body(body).await?;
+    handler
+        .logs(request, table_name, query_ctx)
+        .await
+        .map(OtlpLogsResponse)
+}
+
+/// Gets the table name to write logs into from the header, or the default one if
+/// the header is absent.
+pub(crate) fn log_table_name(headers: &HeaderMap) -> Result<String> {
+    match headers.get(&GREPTIME_LOG_TABLE_NAME_HEADER_NAME) {
+        Some(value) => value.to_str().map(|s| s.to_string()).map_err(|_| {
+            error::InvalidParameterSnafu {
+                reason: format!("invalid value of header {GREPTIME_LOG_TABLE_NAME_HEADER_NAME}"),
+            }
+            .build()
+        }),
+        None => Ok(LOG_TABLE_NAME.to_string()),
+    }
+}
+
+async fn parse_logs_body(body: Body) -> Result<ExportLogsServiceRequest> {
+    hyper::body::to_bytes(body)
+        .await
+        .context(error::HyperSnafu)
+        .and_then(|buf| {
+            ExportLogsServiceRequest::decode(&buf[..]).context(error::DecodeOtlpRequestSnafu)
+        })
+}
+
+pub struct OtlpLogsResponse(ExportLogsServiceResponse);
+
+impl IntoResponse for OtlpLogsResponse {
+    fn into_response(self) -> axum::response::Response {
+        (
+            [(header::CONTENT_TYPE, "application/x-protobuf")],
+            self.0.encode_to_vec(),
+        )
+            .into_response()
+    }
+}
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use api::prom_store::remote::{ReadRequest, WriteRequest};
+use axum::body::StreamBody;
 use axum::extract::{Query, RawBody, State};
 use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
@@ -30,7 +31,7 @@ use snafu::prelude::*;
 
 use crate::error::{self, Result, UnexpectedPhysicalTableSnafu};
 use crate::prom_store::snappy_decompress;
-use crate::query_handler::{PromStoreProtocolHandlerRef, PromStoreResponse};
+use crate::query_handler::{PromStoreProtocolHandlerRef, PromStoreResponse, PromStoreResponseBody};
 
 pub const PHYSICAL_TABLE_PARAM: &str = "physical_table";
 
@@ -99,14 +100,21 @@ pub async fn remote_write(
 
 impl IntoResponse for PromStoreResponse {
     fn into_response(self) -> axum::response::Response {
-        (
-            [
-                (header::CONTENT_TYPE, self.content_type),
-                (header::CONTENT_ENCODING, self.content_encoding),
-            ],
-            self.body,
-        )
-            .into_response()
+        let body = match self.body {
+            PromStoreResponseBody::Full(body) => body.into_response(),
+            PromStoreResponseBody::Stream(stream) => StreamBody::new(stream).into_response(),
+        };
+        match self.content_encoding {
+            Some(content_encoding) => (
+                [
+                    (header::CONTENT_TYPE, self.content_type),
+                    (header::CONTENT_ENCODING, content_encoding),
+                ],
+                body,
+            )
+                .into_response(),
+            None => ([(header::CONTENT_TYPE, self.content_type)], body).into_response(),
+        }
     }
 }
 
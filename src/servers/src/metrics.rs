@@ -129,6 +129,13 @@ lazy_static! {
             &[METRIC_DB_LABEL]
         )
         .unwrap();
+    pub static ref METRIC_HTTP_OPENTELEMETRY_LOGS_ELAPSED: HistogramVec =
+        register_histogram_vec!(
+            "greptime_servers_http_otlp_logs_elapsed",
+            "servers http otlp logs elapsed",
+            &[METRIC_DB_LABEL]
+        )
+        .unwrap();
     pub static ref METRIC_TCP_OPENTSDB_LINE_WRITE_ELAPSED: Histogram = register_histogram!(
         "greptime_servers_opentsdb_line_write_elapsed",
         "servers opentsdb line write elapsed"
@@ -20,6 +20,7 @@ use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::{debug, error};
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::SchemaRef;
+use datatypes::types::jsonb_to_string;
 use futures::StreamExt;
 use opensrv_mysql::{
     Column, ColumnFlags, ColumnType, ErrorKind, OkResponse, QueryResultWriter, RowWriter,
@@ -174,8 +175,9 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
         recordbatch: &RecordBatch,
         query_context: QueryContextRef,
     ) -> Result<()> {
+        let column_schemas = recordbatch.schema.column_schemas();
         for row in recordbatch.rows() {
-            for value in row.into_iter() {
+            for (value, column) in row.into_iter().zip(column_schemas) {
                 match value {
                     Value::Null => row_writer.write_col(None::<u8>)?,
                     Value::Boolean(v) => row_writer.write_col(v as i8)?,
@@ -190,6 +192,10 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Value::Float32(v) => row_writer.write_col(v.0)?,
                     Value::Float64(v) => row_writer.write_col(v.0)?,
                     Value::String(v) => row_writer.write_col(v.as_utf8())?,
+                    Value::Binary(v) if column.data_type.is_json() => {
+                        let json = jsonb_to_string(&v).context(error::ConvertJsonbSnafu)?;
+                        row_writer.write_col(json)?
+                    }
                     Value::Binary(v) => row_writer.write_col(v.deref())?,
                     Value::Date(v) => row_writer.write_col(v.to_chrono_date())?,
                     // convert datetime and timestamp to timezone of current connection
@@ -265,6 +271,7 @@ pub(crate) fn create_mysql_column(
         ConcreteDataType::Interval(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
         ConcreteDataType::Duration(_) => Ok(ColumnType::MYSQL_TYPE_TIME),
         ConcreteDataType::Decimal128(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
+        ConcreteDataType::Json(_) => Ok(ColumnType::MYSQL_TYPE_JSON),
         _ => error::UnsupportedDataTypeSnafu {
             data_type,
             reason: "not implemented",
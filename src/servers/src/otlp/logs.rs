@@ -0,0 +1,245 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use api::v1::value::ValueData;
+use api::v1::{ColumnDataType, RowInsertRequests};
+use common_grpc::writer::Precision;
+use common_query::prelude::GREPTIME_TIMESTAMP;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::common::v1::InstrumentationScope;
+use opentelemetry_proto::tonic::logs::v1::LogRecord;
+
+use super::trace::attributes::{Attributes, OtlpAnyValue};
+use super::trace::span::bytes_to_hex_string;
+use crate::error::Result;
+use crate::row_writer::{self, MultiTableData, TableData};
+
+const APPROXIMATE_COLUMN_COUNT: usize = 13;
+pub const LOG_TABLE_NAME: &str = "opentelemetry_logs";
+
+/// Convert OpenTelemetry logs to GreptimeDB row insert requests.
+/// Returns `InsertRequests` and total number of rows to ingest
+///
+/// See
+/// <https://github.com/open-telemetry/opentelemetry-proto/blob/main/opentelemetry/proto/logs/v1/logs.proto>
+/// for data structure of OTLP logs.
+pub fn to_grpc_insert_requests(
+    request: ExportLogsServiceRequest,
+    table_name: String,
+) -> Result<(RowInsertRequests, usize)> {
+    let num_records = request
+        .resource_logs
+        .iter()
+        .flat_map(|resource_logs| &resource_logs.scope_logs)
+        .map(|scope_logs| scope_logs.log_records.len())
+        .sum();
+    let mut multi_table_writer = MultiTableData::default();
+    let one_table_writer = multi_table_writer.get_or_default_table_data(
+        table_name,
+        APPROXIMATE_COLUMN_COUNT,
+        num_records,
+    );
+
+    for resource_logs in request.resource_logs {
+        let resource_attrs = Attributes::from(
+            resource_logs
+                .resource
+                .map(|r| r.attributes)
+                .unwrap_or_default(),
+        );
+        for scope_logs in resource_logs.scope_logs {
+            let scope = scope_logs.scope.unwrap_or_default();
+            for log in scope_logs.log_records {
+                write_log_to_row(one_table_writer, &resource_attrs, &scope, log)?;
+            }
+        }
+    }
+
+    Ok(multi_table_writer.into_row_insert_requests())
+}
+
+fn write_log_to_row(
+    writer: &mut TableData,
+    resource_attrs: &Attributes,
+    scope: &InstrumentationScope,
+    log: LogRecord,
+) -> Result<()> {
+    let mut row = writer.alloc_one_row();
+    {
+        // fields, trace and span ids are almost unique so they are not used as
+        // tags to avoid creating a series for each log record
+        let body = log
+            .body
+            .as_ref()
+            .map(|body| OtlpAnyValue::from(body).to_string())
+            .unwrap_or_default();
+        let str_fields_iter = vec![
+            ("trace_id", bytes_to_hex_string(&log.trace_id)),
+            ("span_id", bytes_to_hex_string(&log.span_id)),
+            ("body", body),
+            ("severity_text", log.severity_text),
+            (
+                "log_attributes",
+                Attributes::from(log.attributes).to_string(),
+            ),
+            ("scope_name", scope.name.clone()),
+            ("scope_version", scope.version.clone()),
+            (
+                "scope_attributes",
+                Attributes::from(scope.attributes.clone()).to_string(),
+            ),
+            ("resource_attributes", resource_attrs.to_string()),
+        ]
+        .into_iter()
+        .map(|(col, val)| {
+            (
+                col.into(),
+                ColumnDataType::String,
+                ValueData::StringValue(val),
+            )
+        });
+
+        let int_fields_iter = vec![
+            (
+                "severity_number".to_string(),
+                ColumnDataType::Int32,
+                ValueData::I32Value(log.severity_number),
+            ),
+            (
+                "trace_flags".to_string(),
+                ColumnDataType::Uint32,
+                ValueData::U32Value(log.flags),
+            ),
+            (
+                "observed_timestamp".to_string(),
+                ColumnDataType::TimestampNanosecond,
+                ValueData::TimestampNanosecondValue(log.observed_time_unix_nano as i64),
+            ),
+        ]
+        .into_iter();
+
+        row_writer::write_fields(writer, str_fields_iter, &mut row)?;
+        row_writer::write_fields(writer, int_fields_iter, &mut row)?;
+    }
+
+    // The time when the event occurred is optional, use the time when the
+    // event was observed by the collection system instead.
+    let timestamp = if log.time_unix_nano != 0 {
+        log.time_unix_nano
+    } else {
+        log.observed_time_unix_nano
+    };
+    row_writer::write_ts_precision(
+        writer,
+        GREPTIME_TIMESTAMP,
+        Some(timestamp as i64),
+        Precision::Nanosecond,
+        &mut row,
+    )?;
+
+    writer.add_row(row);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use api::v1::value::ValueData;
+    use api::v1::SemanticType;
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+    use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue};
+    use opentelemetry_proto::tonic::logs::v1::{LogRecord, ResourceLogs, ScopeLogs};
+
+    use super::*;
+
+    fn string_value(value: &str) -> AnyValue {
+        AnyValue {
+            value: Some(Value::StringValue(value.to_string())),
+        }
+    }
+
+    #[test]
+    fn test_to_grpc_insert_requests() {
+        let log = LogRecord {
+            time_unix_nano: 0,
+            observed_time_unix_nano: 1_000_000_000,
+            severity_number: 9,
+            severity_text: "INFO".to_string(),
+            body: Some(string_value("hello")),
+            attributes: vec![KeyValue {
+                key: "k".to_string(),
+                value: Some(string_value("v")),
+            }],
+            trace_id: vec![1, 2],
+            span_id: vec![3],
+            ..Default::default()
+        };
+        let request = ExportLogsServiceRequest {
+            resource_logs: vec![ResourceLogs {
+                scope_logs: vec![ScopeLogs {
+                    log_records: vec![log],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+
+        let (requests, rows) = to_grpc_insert_requests(request, "logs".to_string()).unwrap();
+        assert_eq!(1, rows);
+        assert_eq!(1, requests.inserts.len());
+        let insert = &requests.inserts[0];
+        assert_eq!("logs", insert.table_name);
+
+        let rows = insert.rows.as_ref().unwrap();
+        let value_of = |name: &str| {
+            let index = rows
+                .schema
+                .iter()
+                .position(|column| column.column_name == name)
+                .unwrap();
+            rows.rows[0].values[index].value_data.clone().unwrap()
+        };
+        let semantic_type_of = |name: &str| {
+            rows.schema
+                .iter()
+                .find(|column| column.column_name == name)
+                .unwrap()
+                .semantic_type
+        };
+        assert_eq!(SemanticType::Field as i32, semantic_type_of("trace_id"));
+        assert_eq!(SemanticType::Field as i32, semantic_type_of("span_id"));
+        assert_eq!(
+            ValueData::StringValue("0102".to_string()),
+            value_of("trace_id")
+        );
+        assert_eq!(
+            ValueData::StringValue("03".to_string()),
+            value_of("span_id")
+        );
+        assert_eq!(
+            ValueData::StringValue("hello".to_string()),
+            value_of("body")
+        );
+        assert_eq!(ValueData::I32Value(9), value_of("severity_number"));
+        assert_eq!(
+            ValueData::StringValue(r#"{"k":"v"}"#.to_string()),
+            value_of("log_attributes")
+        );
+        // fallback to observed time
+        assert_eq!(
+            ValueData::TimestampNanosecondValue(1_000_000_000),
+            value_of(GREPTIME_TIMESTAMP)
+        );
+    }
+}
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use api::v1::{RowInsertRequests, Value};
+use api::v1::value::ValueData;
+use api::v1::{ColumnDataType, RowInsertRequests, Value};
 use common_grpc::writer::Precision;
 use common_query::prelude::{GREPTIME_COUNT, GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
@@ -83,8 +84,15 @@ fn encode_metrics(
             metric::Data::Histogram(hist) => {
                 encode_histogram(table_writer, name, hist, resource_attrs, scope_attrs)?;
             }
-            // TODO(sunng87) leave ExponentialHistogram for next release
-            metric::Data::ExponentialHistogram(_hist) => {}
+            metric::Data::ExponentialHistogram(hist) => {
+                encode_exponential_histogram(
+                    table_writer,
+                    name,
+                    hist,
+                    resource_attrs,
+                    scope_attrs,
+                )?;
+            }
         }
     }
 
@@ -344,9 +352,229 @@ fn encode_histogram(
     Ok(())
 }
 
-#[allow(dead_code)]
-fn encode_exponential_histogram(_name: &str, _hist: &ExponentialHistogram) -> Result<()> {
-    // TODO(sunng87): implement this using a prometheus compatible way
+const EXP_HISTOGRAM_SCALE_COLUMN: &str = "greptime_scale";
+const EXP_HISTOGRAM_ZERO_COUNT_COLUMN: &str = "greptime_zero_count";
+const EXP_HISTOGRAM_ZERO_THRESHOLD_COLUMN: &str = "greptime_zero_threshold";
+const EXP_HISTOGRAM_POSITIVE_OFFSET_COLUMN: &str = "greptime_positive_offset";
+const EXP_HISTOGRAM_POSITIVE_COUNTS_COLUMN: &str = "greptime_positive_bucket_counts";
+const EXP_HISTOGRAM_NEGATIVE_OFFSET_COLUMN: &str = "greptime_negative_offset";
+const EXP_HISTOGRAM_NEGATIVE_COUNTS_COLUMN: &str = "greptime_negative_bucket_counts";
+
+/// Returns the upper bound of the exponential histogram bucket at `index`.
+///
+/// The bucket at `index` covers `(base^index, base^(index+1)]` where
+/// `base = 2^(2^-scale)`.
+fn exponential_bucket_upper_bound(scale: i32, index: i64) -> f64 {
+    ((index + 1) as f64 * 2f64.powi(-scale)).exp2()
+}
+
+/// Returns the lower bound of the exponential histogram bucket at `index`.
+fn exponential_bucket_lower_bound(scale: i32, index: i64) -> f64 {
+    (index as f64 * 2f64.powi(-scale)).exp2()
+}
+
+/// Returns the offset and bucket counts of the exponential histogram buckets.
+fn buckets_of(buckets: &Option<exponential_histogram_data_point::Buckets>) -> (i32, &[u64]) {
+    buckets
+        .as_ref()
+        .map(|b| (b.offset, b.bucket_counts.as_slice()))
+        .unwrap_or((0, &[]))
+}
+
+fn format_bucket_counts(counts: &[u64]) -> String {
+    let counts = counts
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("[{counts}]")
+}
+
+/// Encode exponential histogram data. This function returns 4 insert requests
+/// for 4 tables.
+///
+/// - A `%metric%` table storing the native representation of the data point:
+/// `greptime_scale`, `greptime_zero_count`, `greptime_zero_threshold`, the
+/// offsets of positive and negative buckets and their bucket counts (encoded as
+/// a JSON array)
+/// - A `%metric%_bucket` table including `le` tag that stores bucket upper
+/// limit, and `greptime_value` for accumulated bucket count. Negative buckets,
+/// the zero bucket and positive buckets are converted to explicit bounds in
+/// ascending order, followed by a `+Inf` bucket.
+/// - A `%metric%_sum` table storing sum of samples
+/// - A `%metric%_count` table storing count of samples.
+///
+/// The `_bucket`, `_sum` and `_count` tables follow the same layout as
+/// [encode_histogram], so prometheus quantile functions can be applied on them.
+fn encode_exponential_histogram(
+    table_writer: &mut MultiTableData,
+    name: &str,
+    hist: &ExponentialHistogram,
+    resource_attrs: Option<&Vec<KeyValue>>,
+    scope_attrs: Option<&Vec<KeyValue>>,
+) -> Result<()> {
+    let normalized_name = normalize_otlp_name(name);
+
+    let bucket_table_name = format!("{}_bucket", normalized_name);
+    let sum_table_name = format!("{}_sum", normalized_name);
+    let count_table_name = format!("{}_count", normalized_name);
+
+    let data_points_len = hist.data_points.len();
+    // Note that the row and columns number here is approximate
+    let mut native_table = TableData::new(APPROXIMATE_COLUMN_COUNT + 7, data_points_len);
+    let mut bucket_table = TableData::new(APPROXIMATE_COLUMN_COUNT, data_points_len * 3);
+    let mut sum_table = TableData::new(APPROXIMATE_COLUMN_COUNT, data_points_len);
+    let mut count_table = TableData::new(APPROXIMATE_COLUMN_COUNT, data_points_len);
+
+    for data_point in &hist.data_points {
+        let scale = data_point.scale;
+        let (positive_offset, positive_counts) = buckets_of(&data_point.positive);
+        let (negative_offset, negative_counts) = buckets_of(&data_point.negative);
+
+        // native table
+        let mut native_row = native_table.alloc_one_row();
+        write_tags_and_timestamp(
+            &mut native_table,
+            &mut native_row,
+            resource_attrs,
+            scope_attrs,
+            Some(data_point.attributes.as_ref()),
+            data_point.time_unix_nano as i64,
+        )?;
+        let native_fields = vec![
+            (
+                EXP_HISTOGRAM_SCALE_COLUMN.to_string(),
+                ColumnDataType::Int32,
+                ValueData::I32Value(scale),
+            ),
+            (
+                EXP_HISTOGRAM_ZERO_COUNT_COLUMN.to_string(),
+                ColumnDataType::Float64,
+                ValueData::F64Value(data_point.zero_count as f64),
+            ),
+            (
+                EXP_HISTOGRAM_ZERO_THRESHOLD_COLUMN.to_string(),
+                ColumnDataType::Float64,
+                ValueData::F64Value(data_point.zero_threshold),
+            ),
+            (
+                EXP_HISTOGRAM_POSITIVE_OFFSET_COLUMN.to_string(),
+                ColumnDataType::Int32,
+                ValueData::I32Value(positive_offset),
+            ),
+            (
+                EXP_HISTOGRAM_POSITIVE_COUNTS_COLUMN.to_string(),
+                ColumnDataType::String,
+                ValueData::StringValue(format_bucket_counts(positive_counts)),
+            ),
+            (
+                EXP_HISTOGRAM_NEGATIVE_OFFSET_COLUMN.to_string(),
+                ColumnDataType::Int32,
+                ValueData::I32Value(negative_offset),
+            ),
+            (
+                EXP_HISTOGRAM_NEGATIVE_COUNTS_COLUMN.to_string(),
+                ColumnDataType::String,
+                ValueData::StringValue(format_bucket_counts(negative_counts)),
+            ),
+        ];
+        row_writer::write_fields(
+            &mut native_table,
+            native_fields.into_iter(),
+            &mut native_row,
+        )?;
+        native_table.add_row(native_row);
+
+        // bucket table, with buckets converted to ascending explicit upper bounds:
+        // the most negative bucket comes first, and the zero bucket sits between
+        // negative and positive buckets.
+        let negative_buckets = negative_counts
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(idx, count)| {
+                let index = negative_offset as i64 + idx as i64;
+                (-exponential_bucket_lower_bound(scale, index), *count)
+            });
+        let zero_bucket = std::iter::once((data_point.zero_threshold, data_point.zero_count));
+        let positive_buckets = positive_counts.iter().enumerate().map(|(idx, count)| {
+            let index = positive_offset as i64 + idx as i64;
+            (exponential_bucket_upper_bound(scale, index), *count)
+        });
+
+        let mut buckets = Vec::with_capacity(negative_counts.len() + positive_counts.len() + 2);
+        let mut accumulated_count = 0;
+        for (upper_bound, count) in negative_buckets.chain(zero_bucket).chain(positive_buckets) {
+            accumulated_count += count;
+            buckets.push((upper_bound, accumulated_count));
+        }
+        // The last bucket
+        buckets.push((f64::INFINITY, accumulated_count));
+
+        for (upper_bound, accumulated_count) in buckets {
+            let mut bucket_row = bucket_table.alloc_one_row();
+            write_tags_and_timestamp(
+                &mut bucket_table,
+                &mut bucket_row,
+                resource_attrs,
+                scope_attrs,
+                Some(data_point.attributes.as_ref()),
+                data_point.time_unix_nano as i64,
+            )?;
+            row_writer::write_tag(
+                &mut bucket_table,
+                HISTOGRAM_LE_COLUMN,
+                upper_bound,
+                &mut bucket_row,
+            )?;
+            row_writer::write_f64(
+                &mut bucket_table,
+                GREPTIME_VALUE,
+                accumulated_count as f64,
+                &mut bucket_row,
+            )?;
+            bucket_table.add_row(bucket_row);
+        }
+
+        if let Some(sum) = data_point.sum {
+            let mut sum_row = sum_table.alloc_one_row();
+            write_tags_and_timestamp(
+                &mut sum_table,
+                &mut sum_row,
+                resource_attrs,
+                scope_attrs,
+                Some(data_point.attributes.as_ref()),
+                data_point.time_unix_nano as i64,
+            )?;
+
+            row_writer::write_f64(&mut sum_table, GREPTIME_VALUE, sum, &mut sum_row)?;
+            sum_table.add_row(sum_row);
+        }
+
+        let mut count_row = count_table.alloc_one_row();
+        write_tags_and_timestamp(
+            &mut count_table,
+            &mut count_row,
+            resource_attrs,
+            scope_attrs,
+            Some(data_point.attributes.as_ref()),
+            data_point.time_unix_nano as i64,
+        )?;
+
+        row_writer::write_f64(
+            &mut count_table,
+            GREPTIME_VALUE,
+            data_point.count as f64,
+            &mut count_row,
+        )?;
+        count_table.add_row(count_row);
+    }
+
+    table_writer.add_table_data(normalized_name, native_table);
+    table_writer.add_table_data(bucket_table_name, bucket_table);
+    table_writer.add_table_data(sum_table_name, sum_table);
+    table_writer.add_table_data(count_table_name, count_table);
+
     Ok(())
 }
 
@@ -394,9 +622,12 @@ fn encode_summary(
 mod tests {
     use opentelemetry_proto::tonic::common::v1::any_value::Value as Val;
     use opentelemetry_proto::tonic::common::v1::{AnyValue, KeyValue};
+    use opentelemetry_proto::tonic::metrics::v1::exponential_histogram_data_point::Buckets;
     use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
     use opentelemetry_proto::tonic::metrics::v1::summary_data_point::ValueAtQuantile;
-    use opentelemetry_proto::tonic::metrics::v1::{HistogramDataPoint, NumberDataPoint};
+    use opentelemetry_proto::tonic::metrics::v1::{
+        ExponentialHistogramDataPoint, HistogramDataPoint, NumberDataPoint,
+    };
 
     use super::*;
 
@@ -655,4 +886,130 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_exponential_bucket_bounds() {
+        // base = 2
+        assert_eq!(exponential_bucket_upper_bound(0, 0), 2.0);
+        assert_eq!(exponential_bucket_upper_bound(0, 2), 8.0);
+        assert_eq!(exponential_bucket_lower_bound(0, 2), 4.0);
+        // base = 4
+        assert_eq!(exponential_bucket_upper_bound(-1, 1), 16.0);
+        // base = sqrt(2)
+        assert_eq!(exponential_bucket_upper_bound(1, 1), 2.0);
+        assert_eq!(exponential_bucket_lower_bound(1, -2), 0.5);
+    }
+
+    #[test]
+    fn test_encode_exponential_histogram() {
+        let mut tables = MultiTableData::default();
+
+        let data_points = vec![ExponentialHistogramDataPoint {
+            attributes: vec![keyvalue("host", "testserver")],
+            time_unix_nano: 100,
+            start_time_unix_nano: 23,
+            count: 12,
+            sum: Some(100.),
+            scale: 0,
+            zero_count: 1,
+            positive: Some(Buckets {
+                offset: 1,
+                bucket_counts: vec![2, 4, 3],
+            }),
+            negative: Some(Buckets {
+                offset: 0,
+                bucket_counts: vec![2],
+            }),
+            ..Default::default()
+        }];
+
+        let histogram = ExponentialHistogram {
+            data_points,
+            aggregation_temporality: AggregationTemporality::Delta.into(),
+        };
+        encode_exponential_histogram(
+            &mut tables,
+            "histo",
+            &histogram,
+            Some(&vec![keyvalue("resource", "app")]),
+            Some(&vec![keyvalue("scope", "otel")]),
+        )
+        .unwrap();
+
+        assert_eq!(4, tables.num_tables());
+
+        // native table
+        let native_table = tables.get_or_default_table_data("histo", 0, 0);
+        assert_eq!(native_table.num_rows(), 1);
+        assert_eq!(
+            native_table
+                .columns()
+                .iter()
+                .map(|c| &c.column_name)
+                .collect::<Vec<&String>>(),
+            vec![
+                "resource",
+                "scope",
+                "host",
+                "greptime_timestamp",
+                "greptime_scale",
+                "greptime_zero_count",
+                "greptime_zero_threshold",
+                "greptime_positive_offset",
+                "greptime_positive_bucket_counts",
+                "greptime_negative_offset",
+                "greptime_negative_bucket_counts",
+            ]
+        );
+
+        // bucket table: 1 negative bucket, the zero bucket, 3 positive buckets
+        // and the `+Inf` bucket
+        let bucket_table = tables.get_or_default_table_data("histo_bucket", 0, 0);
+        assert_eq!(bucket_table.num_rows(), 6);
+        assert_eq!(bucket_table.num_columns(), 6);
+        let le_index = bucket_table
+            .columns()
+            .iter()
+            .position(|c| c.column_name == HISTOGRAM_LE_COLUMN)
+            .unwrap();
+        let value_index = bucket_table
+            .columns()
+            .iter()
+            .position(|c| c.column_name == GREPTIME_VALUE)
+            .unwrap();
+        let rows = tables
+            .into_row_insert_requests()
+            .0
+            .inserts
+            .into_iter()
+            .find(|r| r.table_name == "histo_bucket")
+            .and_then(|r| r.rows)
+            .unwrap()
+            .rows;
+        let buckets = rows
+            .iter()
+            .map(|row| {
+                let le = match row.values[le_index].value_data.as_ref().unwrap() {
+                    ValueData::StringValue(le) => le.clone(),
+                    _ => unreachable!(),
+                };
+                let value = match row.values[value_index].value_data.as_ref().unwrap() {
+                    ValueData::F64Value(v) => *v,
+                    _ => unreachable!(),
+                };
+                (le, value)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                ("-1".to_string(), 2.0),
+                ("0".to_string(), 3.0),
+                ("4".to_string(), 5.0),
+                ("8".to_string(), 9.0),
+                ("16".to_string(), 12.0),
+                ("inf".to_string(), 12.0),
+            ]
+        );
+    }
 }
@@ -22,7 +22,7 @@ use common_time::Interval;
 use datafusion_common::ScalarValue;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::Schema;
-use datatypes::types::TimestampType;
+use datatypes::types::{jsonb_to_string, TimestampType};
 use pgwire::api::portal::{Format, Portal};
 use pgwire::api::results::{DataRowEncoder, FieldInfo};
 use pgwire::api::Type;
@@ -50,7 +50,11 @@ pub(super) fn schema_to_pg(origin: &Schema, field_formats: &Format) -> Result<Ve
         .collect::<Result<Vec<FieldInfo>>>()
 }
 
-pub(super) fn encode_value(value: &Value, builder: &mut DataRowEncoder) -> PgWireResult<()> {
+pub(super) fn encode_value(
+    value: &Value,
+    datatype: &ConcreteDataType,
+    builder: &mut DataRowEncoder,
+) -> PgWireResult<()> {
     match value {
         Value::Null => builder.encode_field(&None::<&i8>),
         Value::Boolean(v) => builder.encode_field(v),
@@ -65,7 +69,17 @@ pub(super) fn encode_value(value: &Value, builder: &mut DataRowEncoder) -> PgWir
         Value::Float32(v) => builder.encode_field(&v.0),
         Value::Float64(v) => builder.encode_field(&v.0),
         Value::String(v) => builder.encode_field(&v.as_utf8()),
-        Value::Binary(v) => builder.encode_field(&v.deref()),
+        Value::Binary(v) => match datatype {
+            ConcreteDataType::Json(_) => {
+                let json = jsonb_to_string(v).map_err(|e| {
+                    PgWireError::ApiError(Box::new(Error::Internal {
+                        err_msg: format!("Failed to convert jsonb to postgres type: {e}"),
+                    }))
+                })?;
+                builder.encode_field(&json)
+            }
+            _ => builder.encode_field(&v.deref()),
+        },
         Value::Date(v) => {
             if let Some(date) = v.to_chrono_date() {
                 builder.encode_field(&date)
@@ -133,6 +147,7 @@ pub(super) fn type_gt_to_pg(origin: &ConcreteDataType) -> Result<Type> {
         &ConcreteDataType::Time(_) => Ok(Type::TIME),
         &ConcreteDataType::Interval(_) => Ok(Type::INTERVAL),
         &ConcreteDataType::Decimal128(_) => Ok(Type::NUMERIC),
+        &ConcreteDataType::Json(_) => Ok(Type::JSON),
         &ConcreteDataType::Duration(_)
         | &ConcreteDataType::List(_)
         | &ConcreteDataType::Dictionary(_) => error::UnsupportedDataTypeSnafu {
@@ -786,7 +801,7 @@ mod test {
         ];
         let mut builder = DataRowEncoder::new(Arc::new(schema));
         for i in values.iter() {
-            encode_value(i, &mut builder).unwrap();
+            encode_value(i, &i.data_type(), &mut builder).unwrap();
         }
 
         let err = encode_value(
@@ -794,6 +809,7 @@ mod test {
                 Some(Box::default()),
                 ConcreteDataType::int16_datatype(),
             )),
+            &ConcreteDataType::list_datatype(ConcreteDataType::int16_datatype()),
             &mut builder,
         )
         .unwrap_err();
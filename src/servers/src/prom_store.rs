@@ -14,30 +14,48 @@
 
 //! prometheus protocol supportings
 //! handles prometheus remote_write, remote_read logic
+
+mod xor_chunk;
+
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 
 use api::prom_store::remote::label_matcher::Type as MatcherType;
-use api::prom_store::remote::{Label, Query, Sample, TimeSeries, WriteRequest};
+use api::prom_store::remote::{
+    Chunk, ChunkedReadResponse, ChunkedSeries, Label, Query, Sample, TimeSeries, WriteRequest,
+};
 use api::v1::RowInsertRequests;
 use common_query::prelude::{GREPTIME_TIMESTAMP, GREPTIME_VALUE};
 use common_recordbatch::{RecordBatch, RecordBatches};
 use common_time::timestamp::TimeUnit;
 use datafusion::prelude::{col, lit, regexp_match, Expr};
-use datafusion_common::ScalarValue;
-use datatypes::prelude::{ConcreteDataType, Value};
+use datafusion_common::{Column, ScalarValue};
+use datatypes::prelude::{ConcreteDataType, Value, VectorRef};
 use openmetrics_parser::{MetricsExposition, PrometheusType, PrometheusValue};
+use prost::Message;
 use query::dataframe::DataFrame;
 use query::plan::LogicalPlan;
 use snafu::{ensure, OptionExt, ResultExt};
 use snap::raw::{Decoder, Encoder};
 
 use crate::error::{self, Result};
+use crate::prom_store::xor_chunk::XorChunkEncoder;
 use crate::row_writer::{self, MultiTableData};
 
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
+/// Content type of the `STREAMED_XOR_CHUNKS` remote read response.
+pub const STREAMED_XOR_CHUNKS_CONTENT_TYPE: &str =
+    "application/x-streamed-protobuf; proto=prometheus.ChunkedReadResponse";
+
+/// Max bytes of series in a frame of the `STREAMED_XOR_CHUNKS` response, same as
+/// the default value of Prometheus.
+pub const DEFAULT_MAX_BYTES_IN_FRAME: usize = 1024 * 1024;
+
+/// Max number of samples in a XOR chunk, same as Prometheus TSDB.
+const MAX_SAMPLES_PER_CHUNK: usize = 120;
+
 /// Metrics for push gateway protocol
 pub struct Metrics {
     pub exposition: MetricsExposition<PrometheusType, PrometheusValue>,
@@ -64,7 +82,36 @@ pub fn table_name(q: &Query) -> Result<String> {
 /// Create a DataFrame from a remote Query
 pub fn query_to_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
     let DataFrame::DataFusion(dataframe) = dataframe;
+    let dataframe = filter_by_query(dataframe, q)?;
 
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+/// Create a DataFrame from a remote Query, rows of the result are sorted by all
+/// label columns and then the timestamp, so rows of a series are adjacent and
+/// in time order. It's required by the `STREAMED_XOR_CHUNKS` response.
+pub fn query_to_sorted_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
+    let DataFrame::DataFusion(dataframe) = dataframe;
+    let dataframe = filter_by_query(dataframe, q)?;
+
+    let mut sort_exprs = dataframe
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name())
+        .filter(|name| *name != GREPTIME_VALUE && *name != GREPTIME_TIMESTAMP)
+        .map(|name| Expr::Column(Column::from_name(name)).sort(true, true))
+        .collect::<Vec<_>>();
+    sort_exprs.push(col(GREPTIME_TIMESTAMP).sort(true, true));
+    let dataframe = dataframe.sort(sort_exprs).context(error::DataFrameSnafu)?;
+
+    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+}
+
+fn filter_by_query(
+    dataframe: datafusion::dataframe::DataFrame,
+    q: &Query,
+) -> Result<datafusion::dataframe::DataFrame> {
     let start_timestamp_ms = q.start_timestamp_ms;
     let end_timestamp_ms = q.end_timestamp_ms;
 
@@ -111,11 +158,7 @@ pub fn query_to_plan(dataframe: DataFrame, q: &Query) -> Result<LogicalPlan> {
     // Safety: conditions MUST not be empty, reduce always return Some(expr).
     let conditions = conditions.into_iter().reduce(Expr::and).unwrap();
 
-    let dataframe = dataframe
-        .filter(conditions)
-        .context(error::DataFrameSnafu)?;
-
-    Ok(LogicalPlan::DfPlan(dataframe.into_parts().1))
+    dataframe.filter(conditions).context(error::DataFrameSnafu)
 }
 
 #[inline]
@@ -234,7 +277,8 @@ pub fn recordbatches_to_timeseries(
         .collect())
 }
 
-fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Vec<TimeSeries>> {
+/// Returns the timestamp and value columns of the `recordbatch`.
+fn timestamp_and_value_columns(recordbatch: &RecordBatch) -> Result<(&VectorRef, &VectorRef)> {
     let ts_column = recordbatch.column_by_name(GREPTIME_TIMESTAMP).context(
         error::InvalidPromRemoteReadQueryResultSnafu {
             msg: "missing greptime_timestamp column in query result",
@@ -265,6 +309,12 @@ fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Ve
         }
     );
 
+    Ok((ts_column, field_column))
+}
+
+fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Vec<TimeSeries>> {
+    let (ts_column, field_column) = timestamp_and_value_columns(&recordbatch)?;
+
     // First, collect each row's timeseries id
     let timeseries_ids = collect_timeseries_ids(table, &recordbatch);
     // Then, group timeseries by it's id.
@@ -278,24 +328,232 @@ fn recordbatch_to_timeseries(table: &str, recordbatch: RecordBatch) -> Result<Ve
                 ..Default::default()
             });
 
-        if ts_column.is_null(row) || field_column.is_null(row) {
-            continue;
+        if let Some(sample) = sample_at(ts_column, field_column, row) {
+            timeseries.samples.push(sample);
+        }
+    }
+
+    Ok(timeseries_map.into_values().collect())
+}
+
+/// Converts the `recordbatch` into timeseries in the order of rows, adjacent rows of
+/// the same series are put into one timeseries.
+fn recordbatch_to_adjacent_timeseries(
+    table: &str,
+    recordbatch: RecordBatch,
+) -> Result<Vec<TimeSeries>> {
+    let (ts_column, field_column) = timestamp_and_value_columns(&recordbatch)?;
+
+    let mut timeseries: Vec<TimeSeries> = Vec::new();
+    let mut last_id = None;
+    for (row, timeseries_id) in collect_timeseries_ids(table, &recordbatch)
+        .into_iter()
+        .enumerate()
+    {
+        if last_id.as_ref() != Some(&timeseries_id) {
+            timeseries.push(TimeSeries {
+                labels: timeseries_id.labels.clone(),
+                ..Default::default()
+            });
+            last_id = Some(timeseries_id);
+        }
+
+        if let Some(sample) = sample_at(ts_column, field_column, row) {
+            // Safety: a timeseries is pushed above.
+            timeseries.last_mut().unwrap().samples.push(sample);
+        }
+    }
+
+    Ok(timeseries)
+}
+
+/// Returns the sample at `row`, or `None` if the timestamp or value is null.
+///
+/// Data types of the columns are checked by [timestamp_and_value_columns].
+fn sample_at(ts_column: &VectorRef, field_column: &VectorRef, row: usize) -> Option<Sample> {
+    if ts_column.is_null(row) || field_column.is_null(row) {
+        return None;
+    }
+
+    let value: f64 = match field_column.get(row) {
+        Value::Float64(value) => value.into(),
+        _ => unreachable!("checked by the \"ensure\" in timestamp_and_value_columns"),
+    };
+    let timestamp = match ts_column.get(row) {
+        Value::Timestamp(t) if t.unit() == TimeUnit::Millisecond => t.value(),
+        _ => unreachable!("checked by the \"ensure\" in timestamp_and_value_columns"),
+    };
+    Some(Sample { value, timestamp })
+}
+
+/// Encodes results of a remote read query into frames of the `STREAMED_XOR_CHUNKS`
+/// response.
+///
+/// Samples of a series are encoded into XOR chunks, and series are put into a frame
+/// until the frame exceeds `max_bytes_in_frame`. Rows of a series are expected to be
+/// adjacent and sorted by time in the query result, see [query_to_sorted_plan].
+pub struct ChunkedReadResponseEncoder {
+    table_name: String,
+    query_index: i64,
+    max_bytes_in_frame: usize,
+    /// Series to send in the next frame.
+    chunked_series: Vec<ChunkedSeries>,
+    /// Encoded length of `chunked_series`.
+    frame_bytes: usize,
+    /// The series being encoded, its samples may span multiple record batches.
+    current: Option<SeriesChunksEncoder>,
+}
+
+impl ChunkedReadResponseEncoder {
+    pub fn new(
+        table_name: String,
+        query_index: i64,
+        max_bytes_in_frame: usize,
+    ) -> ChunkedReadResponseEncoder {
+        ChunkedReadResponseEncoder {
+            table_name,
+            query_index,
+            max_bytes_in_frame,
+            chunked_series: Vec::new(),
+            frame_bytes: 0,
+            current: None,
         }
+    }
+
+    /// Encodes rows in the `recordbatch`, returns frames that are ready to send.
+    pub fn encode(&mut self, recordbatch: RecordBatch) -> Result<Vec<Vec<u8>>> {
+        let mut frames = Vec::new();
+        for timeseries in recordbatch_to_adjacent_timeseries(&self.table_name, recordbatch)? {
+            let mut labels = timeseries.labels;
+            // Labels of a chunked series must be sorted by name.
+            labels.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+            if self.current.as_ref().map(|current| &current.labels) != Some(&labels) {
+                self.finish_series();
+                if self.frame_bytes >= self.max_bytes_in_frame {
+                    frames.push(self.flush());
+                }
+                self.current = Some(SeriesChunksEncoder::new(labels));
+            }
 
-        let value: f64 = match field_column.get(row) {
-            Value::Float64(value) => value.into(),
-            _ => unreachable!("checked by the \"ensure\" above"),
+            for sample in timeseries.samples {
+                // Safety: the current series is set above.
+                let current = self.current.as_mut().unwrap();
+                if !current.append(sample.timestamp, sample.value) {
+                    continue;
+                }
+                // A chunk is cut, sends chunks of the series if the frame is full.
+                if self.frame_bytes + current.encoded_len() >= self.max_bytes_in_frame {
+                    let series = current.take_chunks(false);
+                    self.push_series(series);
+                    frames.push(self.flush());
+                }
+            }
+        }
+
+        Ok(frames)
+    }
+
+    /// Finishes the query, returns the last frame if any.
+    pub fn finish(mut self) -> Option<Vec<u8>> {
+        self.finish_series();
+        (!self.chunked_series.is_empty()).then(|| self.flush())
+    }
+
+    /// Puts remaining chunks of the current series into the frame.
+    fn finish_series(&mut self) {
+        let Some(mut current) = self.current.take() else {
+            return;
         };
-        let timestamp = match ts_column.get(row) {
-            Value::Timestamp(t) if t.unit() == TimeUnit::Millisecond => t.value(),
-            _ => unreachable!("checked by the \"ensure\" above"),
+        let series = current.take_chunks(true);
+        if !series.chunks.is_empty() {
+            self.push_series(series);
+        }
+    }
+
+    fn push_series(&mut self, series: ChunkedSeries) {
+        self.frame_bytes += series.encoded_len();
+        self.chunked_series.push(series);
+    }
+
+    /// Encodes series in the frame.
+    fn flush(&mut self) -> Vec<u8> {
+        let response = ChunkedReadResponse {
+            chunked_series: std::mem::take(&mut self.chunked_series),
+            query_index: self.query_index,
         };
-        let sample = Sample { value, timestamp };
+        self.frame_bytes = 0;
+        encode_frame(&response)
+    }
+}
+
+/// Encodes samples of a series into XOR chunks.
+struct SeriesChunksEncoder {
+    labels: Vec<Label>,
+    /// Chunks that are full.
+    chunks: Vec<Chunk>,
+    /// The chunk to append samples.
+    chunk: Option<XorChunkEncoder>,
+}
 
-        timeseries.samples.push(sample);
+impl SeriesChunksEncoder {
+    fn new(labels: Vec<Label>) -> SeriesChunksEncoder {
+        SeriesChunksEncoder {
+            labels,
+            chunks: Vec::new(),
+            chunk: None,
+        }
     }
 
-    Ok(timeseries_map.into_values().collect())
+    /// Appends a sample, returns true if a chunk is full.
+    fn append(&mut self, timestamp: i64, value: f64) -> bool {
+        let chunk = self.chunk.get_or_insert_with(XorChunkEncoder::new);
+        chunk.append(timestamp, value);
+        if chunk.num_samples() < MAX_SAMPLES_PER_CHUNK {
+            return false;
+        }
+        // Safety: the chunk is set above.
+        self.chunks.push(self.chunk.take().unwrap().finish());
+        true
+    }
+
+    /// Returns the encoded length of full chunks and labels.
+    fn encoded_len(&self) -> usize {
+        self.labels
+            .iter()
+            .map(|label| label.encoded_len())
+            .sum::<usize>()
+            + self
+                .chunks
+                .iter()
+                .map(|chunk| chunk.encoded_len())
+                .sum::<usize>()
+    }
+
+    /// Takes full chunks as a [ChunkedSeries], also takes the chunk not full if
+    /// `finish` is true.
+    fn take_chunks(&mut self, finish: bool) -> ChunkedSeries {
+        if finish {
+            if let Some(chunk) = self.chunk.take() {
+                self.chunks.push(chunk.finish());
+            }
+        }
+        ChunkedSeries {
+            labels: self.labels.clone(),
+            chunks: std::mem::take(&mut self.chunks),
+        }
+    }
+}
+
+/// Encodes a frame of the streamed response, the frame consists of the uvarint
+/// length of the message, the CRC32 (Castagnoli) checksum of the message in big
+/// endian and the message itself.
+fn encode_frame(response: &ChunkedReadResponse) -> Vec<u8> {
+    let message = response.encode_to_vec();
+    let mut frame = Vec::with_capacity(message.len() + 14);
+    prost::encoding::encode_varint(message.len() as u64, &mut frame);
+    frame.extend_from_slice(&crc32c::crc32c(&message).to_be_bytes());
+    frame.extend_from_slice(&message);
+    frame
 }
 
 pub fn to_grpc_row_insert_requests(request: WriteRequest) -> Result<(RowInsertRequests, usize)> {
@@ -466,8 +724,8 @@ mod tests {
 
     use api::prom_store::remote::LabelMatcher;
     use api::v1::{ColumnDataType, Row, SemanticType};
-    use datafusion::prelude::SessionContext;
-    use datatypes::schema::{ColumnSchema, Schema};
+    use datafusion::prelude::{SessionConfig, SessionContext};
+    use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
     use datatypes::vectors::{Float64Vector, StringVector, TimestampMillisecondVector};
     use table::table::adapter::DfTableProviderAdapter;
     use table::test_util::MemTable;
@@ -799,4 +1057,199 @@ mod tests {
             }]
         );
     }
+
+    fn decode_frame(mut frame: &[u8]) -> (ChunkedReadResponse, &[u8]) {
+        let len = prost::encoding::decode_varint(&mut frame).unwrap() as usize;
+        let crc = u32::from_be_bytes(frame[..4].try_into().unwrap());
+        let message = &frame[4..4 + len];
+        assert_eq!(crc32c::crc32c(message), crc);
+        (
+            ChunkedReadResponse::decode(message).unwrap(),
+            &frame[4 + len..],
+        )
+    }
+
+    fn new_host_recordbatch(schema: &SchemaRef, host: &str, start: i64, end: i64) -> RecordBatch {
+        let timestamps: Vec<_> = (start..end).map(|i| i * 1000).collect();
+        let values: Vec<_> = (start..end).map(|i| i as f64).collect();
+        let hosts = vec![host; timestamps.len()];
+        RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(StringVector::from(hosts)) as _,
+                Arc::new(TimestampMillisecondVector::from_vec(timestamps)) as _,
+                Arc::new(Float64Vector::from_vec(values)) as _,
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_chunked_read_response_encoder() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("instance", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                GREPTIME_TIMESTAMP,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(GREPTIME_VALUE, ConcreteDataType::float64_datatype(), true),
+        ]));
+
+        let mut encoder =
+            ChunkedReadResponseEncoder::new("metric1".to_string(), 3, DEFAULT_MAX_BYTES_IN_FRAME);
+        // Samples of host1 span two record batches.
+        let frames = encoder
+            .encode(new_host_recordbatch(&schema, "host1", 0, 100))
+            .unwrap();
+        assert!(frames.is_empty());
+        let frames = encoder
+            .encode(new_host_recordbatch(&schema, "host1", 100, 150))
+            .unwrap();
+        assert!(frames.is_empty());
+        let frames = encoder
+            .encode(new_host_recordbatch(&schema, "host2", 0, 3))
+            .unwrap();
+        assert!(frames.is_empty());
+        let frame = encoder.finish().unwrap();
+
+        let (response, remaining) = decode_frame(&frame);
+        assert!(remaining.is_empty());
+        assert_eq!(3, response.query_index);
+        assert_eq!(2, response.chunked_series.len());
+
+        let series = &response.chunked_series[0];
+        assert_eq!(
+            vec![
+                new_label(METRIC_NAME_LABEL.to_string(), "metric1".to_string()),
+                new_label("instance".to_string(), "host1".to_string()),
+            ],
+            series.labels
+        );
+        assert_eq!(
+            vec![(0, 119_000), (120_000, 149_000)],
+            series
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.min_time_ms, chunk.max_time_ms))
+                .collect::<Vec<_>>()
+        );
+        let series = &response.chunked_series[1];
+        assert_eq!("host2", series.labels[1].value);
+        assert_eq!(1, series.chunks.len());
+        assert_eq!(
+            3,
+            u16::from_be_bytes([series.chunks[0].data[0], series.chunks[0].data[1]])
+        );
+    }
+
+    #[test]
+    fn test_chunked_read_response_encoder_split_frames() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("instance", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                GREPTIME_TIMESTAMP,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(GREPTIME_VALUE, ConcreteDataType::float64_datatype(), true),
+        ]));
+
+        // Each frame holds at most one chunk.
+        let mut encoder = ChunkedReadResponseEncoder::new("metric1".to_string(), 0, 1);
+        let mut frames = encoder
+            .encode(new_host_recordbatch(&schema, "host1", 0, 300))
+            .unwrap();
+        frames.extend(encoder.finish());
+        assert_eq!(3, frames.len());
+
+        let num_samples: Vec<_> = frames
+            .iter()
+            .map(|frame| {
+                let (response, _) = decode_frame(frame);
+                assert_eq!(1, response.chunked_series.len());
+                let series = &response.chunked_series[0];
+                assert_eq!("host1", series.labels[1].value);
+                assert_eq!(1, series.chunks.len());
+                u16::from_be_bytes([series.chunks[0].data[0], series.chunks[0].data[1]])
+            })
+            .collect();
+        assert_eq!(vec![120, 120, 60], num_samples);
+    }
+
+    #[tokio::test]
+    async fn test_sorted_plan_chunked_read_response() {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("instance", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                GREPTIME_TIMESTAMP,
+                ConcreteDataType::timestamp_millisecond_datatype(),
+                true,
+            ),
+            ColumnSchema::new(GREPTIME_VALUE, ConcreteDataType::float64_datatype(), true),
+        ]));
+        // Samples of both series are interleaved and out of order.
+        let recordbatch = RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(StringVector::from(vec![
+                    "host2", "host1", "host2", "host1", "host1", "host2",
+                ])) as _,
+                Arc::new(TimestampMillisecondVector::from_vec(vec![
+                    2000, 1000, 0, 0, 2000, 1000,
+                ])) as _,
+                Arc::new(Float64Vector::from_vec(vec![5.0, 1.0, 3.0, 0.0, 2.0, 4.0])) as _,
+            ],
+        )
+        .unwrap();
+        let q = Query {
+            start_timestamp_ms: 0,
+            end_timestamp_ms: 2000,
+            matchers: vec![LabelMatcher {
+                name: METRIC_NAME_LABEL.to_string(),
+                value: "metric1".to_string(),
+                r#type: EQ_TYPE,
+            }],
+            ..Default::default()
+        };
+
+        // Splits the output into batches of 2 rows, so a series spans multiple batches.
+        let ctx = SessionContext::new_with_config(SessionConfig::new().with_batch_size(2));
+        let table = MemTable::table("metric1", recordbatch);
+        let table_provider = Arc::new(DfTableProviderAdapter::new(table));
+        let dataframe = ctx.read_table(table_provider).unwrap();
+        let LogicalPlan::DfPlan(plan) =
+            query_to_sorted_plan(DataFrame::DataFusion(dataframe), &q).unwrap();
+        let batches = ctx
+            .execute_logical_plan(plan)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert!(batches.len() > 1);
+
+        let mut encoder =
+            ChunkedReadResponseEncoder::new("metric1".to_string(), 0, DEFAULT_MAX_BYTES_IN_FRAME);
+        for batch in batches {
+            let batch = RecordBatch::try_from_df_record_batch(schema.clone(), batch).unwrap();
+            assert!(encoder.encode(batch).unwrap().is_empty());
+        }
+        let frame = encoder.finish().unwrap();
+
+        let (response, remaining) = decode_frame(&frame);
+        assert!(remaining.is_empty());
+        // Each series is encoded once, with all its samples in time order.
+        assert_eq!(2, response.chunked_series.len());
+        for (series, host) in response.chunked_series.iter().zip(["host1", "host2"]) {
+            assert_eq!(host, series.labels[1].value);
+            assert_eq!(1, series.chunks.len());
+            assert_eq!(0, series.chunks[0].min_time_ms);
+            assert_eq!(2000, series.chunks[0].max_time_ms);
+            assert_eq!(
+                3,
+                u16::from_be_bytes([series.chunks[0].data[0], series.chunks[0].data[1]])
+            );
+        }
+    }
 }
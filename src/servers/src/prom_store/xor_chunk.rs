@@ -0,0 +1,333 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoder of the XOR chunk, the Gorilla-style compressed chunk format used by
+//! Prometheus TSDB. See `tsdb/chunkenc/xor.go` in Prometheus for the layout.
+
+use api::prom_store::remote::{chunk, Chunk};
+
+/// Initial value of the leading zeros, means there is no previous XOR window.
+const UNSET_LEADING: u8 = 0xff;
+
+/// Writes bits into a byte buffer, from the most significant bit of each byte.
+#[derive(Debug)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Number of bits available in the last byte.
+    available: u8,
+}
+
+impl BitWriter {
+    fn new(bytes: Vec<u8>) -> BitWriter {
+        BitWriter {
+            bytes,
+            available: 0,
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        if self.available == 0 {
+            self.bytes.push(0);
+            self.available = 8;
+        }
+        self.available -= 1;
+        if bit {
+            // Safety: we ensure the buffer isn't empty above.
+            *self.bytes.last_mut().unwrap() |= 1 << self.available;
+        }
+    }
+
+    /// Writes the lowest `num_bits` bits of `value`.
+    fn write_bits(&mut self, value: u64, num_bits: u8) {
+        for i in (0..num_bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        self.write_bits(byte as u64, 8);
+    }
+
+    fn write_uvarint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.write_byte(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.write_byte(value as u8);
+    }
+
+    fn write_varint(&mut self, value: i64) {
+        self.write_uvarint(((value << 1) ^ (value >> 63)) as u64);
+    }
+}
+
+/// Appends samples of a series into a XOR chunk.
+#[derive(Debug)]
+pub(crate) struct XorChunkEncoder {
+    writer: BitWriter,
+    num_samples: u16,
+    min_time: i64,
+    /// Timestamp of the last sample.
+    t: i64,
+    /// Value of the last sample.
+    v: f64,
+    /// Delta between timestamps of the last two samples.
+    t_delta: u64,
+    leading: u8,
+    trailing: u8,
+}
+
+impl XorChunkEncoder {
+    pub(crate) fn new() -> XorChunkEncoder {
+        XorChunkEncoder {
+            // The first 2 bytes hold the number of samples.
+            writer: BitWriter::new(vec![0, 0]),
+            num_samples: 0,
+            min_time: 0,
+            t: 0,
+            v: 0.0,
+            t_delta: 0,
+            leading: UNSET_LEADING,
+            trailing: 0,
+        }
+    }
+
+    /// Returns the number of samples in the chunk.
+    pub(crate) fn num_samples(&self) -> usize {
+        self.num_samples as usize
+    }
+
+    /// Appends a sample to the chunk, samples must be appended in ascending order of `t`.
+    pub(crate) fn append(&mut self, t: i64, v: f64) {
+        let mut t_delta = 0;
+        match self.num_samples {
+            0 => {
+                self.writer.write_varint(t);
+                self.writer.write_bits(v.to_bits(), 64);
+                self.min_time = t;
+            }
+            1 => {
+                t_delta = t.wrapping_sub(self.t) as u64;
+                self.writer.write_uvarint(t_delta);
+                self.write_value(v);
+            }
+            _ => {
+                t_delta = t.wrapping_sub(self.t) as u64;
+                let dod = t_delta.wrapping_sub(self.t_delta) as i64;
+                if dod == 0 {
+                    self.writer.write_bit(false);
+                } else if in_bit_range(dod, 14) {
+                    self.writer.write_bits(0b10, 2);
+                    self.writer.write_bits(dod as u64, 14);
+                } else if in_bit_range(dod, 17) {
+                    self.writer.write_bits(0b110, 3);
+                    self.writer.write_bits(dod as u64, 17);
+                } else if in_bit_range(dod, 20) {
+                    self.writer.write_bits(0b1110, 4);
+                    self.writer.write_bits(dod as u64, 20);
+                } else {
+                    self.writer.write_bits(0b1111, 4);
+                    self.writer.write_bits(dod as u64, 64);
+                }
+                self.write_value(v);
+            }
+        }
+
+        self.t = t;
+        self.v = v;
+        self.t_delta = t_delta;
+        self.num_samples += 1;
+    }
+
+    /// Writes the XOR of `v` and the previous value.
+    fn write_value(&mut self, v: f64) {
+        let delta = v.to_bits() ^ self.v.to_bits();
+        if delta == 0 {
+            self.writer.write_bit(false);
+            return;
+        }
+        self.writer.write_bit(true);
+
+        // Leading zeros are stored in 5 bits.
+        let leading = (delta.leading_zeros() as u8).min(31);
+        let trailing = delta.trailing_zeros() as u8;
+        if self.leading != UNSET_LEADING && leading >= self.leading && trailing >= self.trailing {
+            // Reuses the previous window.
+            self.writer.write_bit(false);
+            self.writer
+                .write_bits(delta >> self.trailing, 64 - self.leading - self.trailing);
+            return;
+        }
+
+        self.leading = leading;
+        self.trailing = trailing;
+        self.writer.write_bit(true);
+        self.writer.write_bits(leading as u64, 5);
+        // 64 significant bits overflows to 0 in 6 bits, readers treat 0 as 64.
+        let sig_bits = 64 - leading - trailing;
+        self.writer.write_bits(sig_bits as u64, 6);
+        self.writer.write_bits(delta >> trailing, sig_bits);
+    }
+
+    /// Finishes the chunk.
+    pub(crate) fn finish(self) -> Chunk {
+        let mut data = self.writer.bytes;
+        data[..2].copy_from_slice(&self.num_samples.to_be_bytes());
+        Chunk {
+            min_time_ms: self.min_time,
+            max_time_ms: self.t,
+            r#type: chunk::Encoding::Xor as i32,
+            data,
+        }
+    }
+}
+
+/// Returns whether `x` can be stored in `num_bits` bits.
+fn in_bit_range(x: i64, num_bits: u8) -> bool {
+    -((1 << (num_bits - 1)) - 1) <= x && x <= 1 << (num_bits - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal decoder of the XOR chunk to verify the encoder.
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read_bit(&mut self) -> bool {
+            let bit = (self.bytes[self.pos / 8] >> (7 - self.pos % 8)) & 1 == 1;
+            self.pos += 1;
+            bit
+        }
+
+        fn read_bits(&mut self, num_bits: u8) -> u64 {
+            (0..num_bits).fold(0, |acc, _| (acc << 1) | self.read_bit() as u64)
+        }
+
+        fn read_uvarint(&mut self) -> u64 {
+            let mut value = 0;
+            for shift in (0..).step_by(7) {
+                let byte = self.read_bits(8);
+                value |= (byte & 0x7f) << shift;
+                if byte < 0x80 {
+                    break;
+                }
+            }
+            value
+        }
+
+        fn read_varint(&mut self) -> i64 {
+            let value = self.read_uvarint();
+            (value >> 1) as i64 ^ -((value & 1) as i64)
+        }
+    }
+
+    fn decode(data: &[u8]) -> Vec<(i64, f64)> {
+        let num_samples = u16::from_be_bytes([data[0], data[1]]) as usize;
+        let mut reader = BitReader {
+            bytes: &data[2..],
+            pos: 0,
+        };
+        let mut samples = Vec::with_capacity(num_samples);
+        let (mut t, mut v, mut t_delta) = (0i64, 0u64, 0u64);
+        let (mut leading, mut trailing) = (0u8, 0u8);
+        for i in 0..num_samples {
+            match i {
+                0 => {
+                    t = reader.read_varint();
+                    v = reader.read_bits(64);
+                    samples.push((t, f64::from_bits(v)));
+                    continue;
+                }
+                1 => t_delta = reader.read_uvarint(),
+                _ => {
+                    let mut prefix = 0;
+                    while prefix < 4 && reader.read_bit() {
+                        prefix += 1;
+                    }
+                    let num_bits = [0, 14, 17, 20, 64][prefix];
+                    let mut dod = reader.read_bits(num_bits) as i64;
+                    if num_bits != 64 && dod > 1 << (num_bits - 1) {
+                        dod -= 1 << num_bits;
+                    }
+                    t_delta = (t_delta as i64 + dod) as u64;
+                }
+            }
+            t += t_delta as i64;
+            if reader.read_bit() {
+                if reader.read_bit() {
+                    leading = reader.read_bits(5) as u8;
+                    let sig_bits = match reader.read_bits(6) as u8 {
+                        0 => 64,
+                        n => n,
+                    };
+                    trailing = 64 - leading - sig_bits;
+                }
+                v ^= reader.read_bits(64 - leading - trailing) << trailing;
+            }
+            samples.push((t, f64::from_bits(v)));
+        }
+        samples
+    }
+
+    #[test]
+    fn test_in_bit_range() {
+        assert!(in_bit_range(8192, 14));
+        assert!(!in_bit_range(8193, 14));
+        assert!(in_bit_range(-8191, 14));
+        assert!(!in_bit_range(-8192, 14));
+    }
+
+    #[test]
+    fn test_encode_xor_chunk() {
+        let samples = vec![
+            (1000, 1.0),
+            (2000, 1.0),
+            (3000, 2.5),
+            (4000, 2.5),
+            (4001, -3.0),
+            (10_000, f64::MAX),
+            (20_000, 0.0),
+            (100_000, 0.1),
+            (10_000_000, 0.2),
+            (10_000_000_000, f64::MIN_POSITIVE),
+            (10_000_000_001, 1e100),
+        ];
+        let mut encoder = XorChunkEncoder::new();
+        for (t, v) in &samples {
+            encoder.append(*t, *v);
+        }
+        assert_eq!(samples.len(), encoder.num_samples());
+
+        let chunk = encoder.finish();
+        assert_eq!(1000, chunk.min_time_ms);
+        assert_eq!(10_000_000_001, chunk.max_time_ms);
+        assert_eq!(chunk::Encoding::Xor as i32, chunk.r#type);
+        assert_eq!(samples, decode(&chunk.data));
+    }
+
+    #[test]
+    fn test_encode_single_sample() {
+        let mut encoder = XorChunkEncoder::new();
+        encoder.append(-1, 1.0);
+        let chunk = encoder.finish();
+        // Header, zigzag varint of -1 and 8 bytes of the value.
+        assert_eq!(vec![0, 1, 1, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0], chunk.data);
+        assert_eq!(vec![(-1, 1.0)], decode(&chunk.data));
+    }
+}
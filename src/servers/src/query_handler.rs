@@ -31,6 +31,10 @@ use std::sync::Arc;
 use api::prom_store::remote::{ReadRequest, WriteRequest};
 use async_trait::async_trait;
 use common_query::Output;
+use futures::stream::BoxStream;
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
 };
@@ -82,8 +86,17 @@ pub trait OpentsdbProtocolHandler {
 
 pub struct PromStoreResponse {
     pub content_type: String,
-    pub content_encoding: String,
-    pub body: Vec<u8>,
+    /// Encoding of the body, `None` if the body isn't encoded.
+    pub content_encoding: Option<String>,
+    pub body: PromStoreResponseBody,
+}
+
+/// Body of the prometheus remote read response.
+pub enum PromStoreResponseBody {
+    /// The whole body.
+    Full(Vec<u8>),
+    /// The body is sent frame by frame.
+    Stream(BoxStream<'static, Result<Vec<u8>>>),
 }
 
 #[async_trait]
@@ -116,4 +129,12 @@ pub trait OpenTelemetryProtocolHandler {
         request: ExportTraceServiceRequest,
         ctx: QueryContextRef,
     ) -> Result<ExportTraceServiceResponse>;
+
+    /// Handling opentelemetry logs request, the logs are written into `table_name`
+    async fn logs(
+        &self,
+        request: ExportLogsServiceRequest,
+        table_name: String,
+        ctx: QueryContextRef,
+    ) -> Result<ExportLogsServiceResponse>;
 }
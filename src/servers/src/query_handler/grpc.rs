@@ -17,7 +17,10 @@ use std::sync::Arc;
 use api::v1::greptime_request::Request;
 use async_trait::async_trait;
 use common_error::ext::{BoxedError, ErrorExt};
+use common_meta::table_name::TableName;
 use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datatypes::schema::SchemaRef;
 use session::context::QueryContextRef;
 use snafu::ResultExt;
 
@@ -35,6 +38,31 @@ pub trait GrpcQueryHandler {
         query: Request,
         ctx: QueryContextRef,
     ) -> std::result::Result<Output, Self::Error>;
+
+    /// Writes the record batch into the given table directly. Returns the affected rows.
+    async fn put_record_batch(
+        &self,
+        _table: &TableName,
+        _record_batch: RecordBatch,
+        _ctx: QueryContextRef,
+    ) -> Result<usize> {
+        error::NotSupportedSnafu {
+            feat: "put_record_batch",
+        }
+        .fail()
+    }
+
+    /// Gets the schema of the given table, or `None` if the table doesn't exist.
+    async fn table_schema(
+        &self,
+        _table: &TableName,
+        _ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        error::NotSupportedSnafu {
+            feat: "table_schema",
+        }
+        .fail()
+    }
 }
 
 pub struct ServerGrpcQueryHandlerAdapter<E>(GrpcQueryHandlerRef<E>);
@@ -59,4 +87,21 @@ where
             .map_err(BoxedError::new)
             .context(error::ExecuteGrpcQuerySnafu)
     }
+
+    async fn put_record_batch(
+        &self,
+        table: &TableName,
+        record_batch: RecordBatch,
+        ctx: QueryContextRef,
+    ) -> Result<usize> {
+        self.0.put_record_batch(table, record_batch, ctx).await
+    }
+
+    async fn table_schema(
+        &self,
+        table: &TableName,
+        ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        self.0.table_schema(table, ctx).await
+    }
 }
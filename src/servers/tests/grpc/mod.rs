@@ -16,13 +16,21 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 use api::v1::auth_header::AuthScheme;
-use api::v1::Basic;
+use api::v1::{Basic, FlightMetadata};
+use arrow_flight::flight_service_client::FlightServiceClient;
 use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::FlightDescriptor;
 use async_trait::async_trait;
 use auth::tests::MockUserProvider;
 use auth::UserProviderRef;
 use client::{Client, Database, DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_grpc::flight::{FlightEncoder, FlightMessage};
+use common_recordbatch::RecordBatch;
 use common_runtime::{Builder as RuntimeBuilder, Runtime};
+use datatypes::arrow::datatypes::Schema as ArrowSchema;
+use datatypes::vectors::UInt32Vector;
+use futures::StreamExt;
+use prost::Message;
 use servers::error::{Result, StartGrpcSnafu, TcpBindSnafu};
 use servers::grpc::flight::FlightCraftWrapper;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
@@ -33,6 +41,7 @@ use table::test_util::MemTable;
 use table::TableRef;
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::TcpListenerStream;
+use tonic::Code;
 
 use crate::{create_testing_grpc_query_handler, LOCALHOST_WITH_0};
 
@@ -147,3 +156,94 @@ async fn test_grpc_query() {
     let re = db.sql("select * from numbers").await;
     let _ = re.unwrap();
 }
+
+/// Attaches the basic auth of the default user to the Flight request.
+fn with_auth<T>(message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    let _ = request.metadata_mut().insert(
+        "authorization",
+        "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=".parse().unwrap(),
+    );
+    request
+}
+
+#[tokio::test]
+async fn test_flight_do_put() {
+    let table = MemTable::default_numbers_table();
+    let schema = table.schema();
+    let server = create_grpc_server(table).unwrap();
+    let addr = server
+        .start(LOCALHOST_WITH_0.parse().unwrap())
+        .await
+        .unwrap();
+    let mut client = FlightServiceClient::connect(format!("http://{addr}"))
+        .await
+        .unwrap();
+
+    let mut encoder = FlightEncoder::default();
+    let mut messages = vec![encoder.encode(FlightMessage::Schema(schema.clone()))];
+    messages[0].flight_descriptor = Some(FlightDescriptor::new_path(vec!["numbers".to_string()]));
+    for num_rows in [3, 2] {
+        let record_batch = RecordBatch::new(
+            schema.clone(),
+            vec![Arc::new(UInt32Vector::from_vec(vec![1; num_rows])) as _],
+        )
+        .unwrap();
+        messages.push(encoder.encode(FlightMessage::Recordbatch(record_batch)));
+    }
+
+    // each record batch is acknowledged with its affected rows
+    let results = client
+        .do_put(with_auth(futures::stream::iter(messages.clone())))
+        .await
+        .unwrap()
+        .into_inner()
+        .map(|result| {
+            let metadata = FlightMetadata::decode(result.unwrap().app_metadata).unwrap();
+            metadata.affected_rows.unwrap().value
+        })
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(results, vec![3, 2]);
+
+    // the first message must carry the descriptor
+    messages[0].flight_descriptor = None;
+    let mut stream = client
+        .do_put(with_auth(futures::stream::iter(messages)))
+        .await
+        .unwrap()
+        .into_inner();
+    let status = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument, "{status}");
+}
+
+#[tokio::test]
+async fn test_flight_get_schema() {
+    let table = MemTable::default_numbers_table();
+    let expected = table.schema().arrow_schema().clone();
+    let server = create_grpc_server(table).unwrap();
+    let addr = server
+        .start(LOCALHOST_WITH_0.parse().unwrap())
+        .await
+        .unwrap();
+    let mut client = FlightServiceClient::connect(format!("http://{addr}"))
+        .await
+        .unwrap();
+
+    let descriptor = FlightDescriptor::new_path(vec!["numbers".to_string()]);
+    let schema_result = client
+        .get_schema(with_auth(descriptor))
+        .await
+        .unwrap()
+        .into_inner();
+    let schema = ArrowSchema::try_from(&schema_result).unwrap();
+    assert_eq!(schema.fields(), expected.fields());
+
+    let descriptor = FlightDescriptor::new_path(vec!["not_exist".to_string()]);
+    let status = client.get_schema(with_auth(descriptor)).await.unwrap_err();
+    assert_eq!(status.code(), Code::NotFound, "{status}");
+
+    // unauthorized
+    let descriptor = FlightDescriptor::new_path(vec!["numbers".to_string()]);
+    assert!(client.get_schema(descriptor).await.is_err());
+}
@@ -19,8 +19,12 @@ use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
 use async_trait::async_trait;
 use catalog::memory::MemoryCatalogManager;
+use catalog::CatalogManagerRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_meta::table_name::TableName;
 use common_query::Output;
+use common_recordbatch::RecordBatch;
+use datatypes::schema::SchemaRef;
 use query::parser::{PromQuery, QueryLanguageParser, QueryStatement};
 use query::plan::LogicalPlan;
 use query::query_engine::DescribeResult;
@@ -47,16 +51,18 @@ mod py_script;
 const LOCALHOST_WITH_0: &str = "127.0.0.1:0";
 
 pub struct DummyInstance {
+    catalog_manager: CatalogManagerRef,
     query_engine: QueryEngineRef,
     py_engine: Arc<PyEngine>,
     scripts: RwLock<HashMap<String, Arc<PyScript>>>,
 }
 
 impl DummyInstance {
-    fn new(query_engine: QueryEngineRef) -> Self {
+    fn new(catalog_manager: CatalogManagerRef, query_engine: QueryEngineRef) -> Self {
         Self {
             py_engine: Arc::new(PyEngine::new(query_engine.clone())),
             scripts: RwLock::new(HashMap::new()),
+            catalog_manager,
             query_engine,
         }
     }
@@ -210,13 +216,35 @@ impl GrpcQueryHandler for DummyInstance {
         };
         Ok(output)
     }
+
+    async fn put_record_batch(
+        &self,
+        _table: &TableName,
+        record_batch: RecordBatch,
+        _ctx: QueryContextRef,
+    ) -> Result<usize> {
+        Ok(record_batch.num_rows())
+    }
+
+    async fn table_schema(
+        &self,
+        table: &TableName,
+        _ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        let table = self
+            .catalog_manager
+            .table(&table.catalog_name, &table.schema_name, &table.table_name)
+            .await
+            .unwrap();
+        Ok(table.map(|table| table.schema()))
+    }
 }
 
 fn create_testing_instance(table: TableRef) -> DummyInstance {
     let catalog_manager = MemoryCatalogManager::new_with_table(table);
     let query_engine =
-        QueryEngineFactory::new(catalog_manager, None, None, None, false).query_engine();
-    DummyInstance::new(query_engine)
+        QueryEngineFactory::new(catalog_manager.clone(), None, None, None, false).query_engine();
+    DummyInstance::new(catalog_manager, query_engine)
 }
 
 fn create_testing_script_handler(table: TableRef) -> ScriptHandlerRef {
@@ -17,11 +17,11 @@ use sqlparser::ast::Ident;
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError, ParserOptions};
-use sqlparser::tokenizer::{Token, TokenWithLocation};
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 
 use crate::ast::{Expr, ObjectName};
 use crate::error::{self, Result, SyntaxSnafu};
-use crate::parsers::tql_parser;
+use crate::parsers::{time_travel_parser, tql_parser};
 use crate::statements::statement::Statement;
 use crate::statements::transform_statements;
 
@@ -44,10 +44,15 @@ impl<'a> ParserContext<'a> {
     ) -> Result<Vec<Statement>> {
         let mut stmts: Vec<Statement> = Vec::new();
 
+        let tokens = Tokenizer::new(dialect, sql)
+            .tokenize_with_location()
+            .map_err(ParserError::from)
+            .context(SyntaxSnafu)?;
+        let (tokens, time_travels) = time_travel_parser::strip_time_travels(tokens)?;
+
         let parser = Parser::new(dialect)
             .with_options(ParserOptions::new().with_trailing_commas(true))
-            .try_with_sql(sql)
-            .context(SyntaxSnafu)?;
+            .with_tokens_with_locations(tokens);
         let mut parser_ctx = ParserContext { sql, parser };
 
         let mut expecting_statement_delimiter = false;
@@ -69,6 +74,7 @@ impl<'a> ParserContext<'a> {
             expecting_statement_delimiter = true;
         }
 
+        time_travel_parser::attach_time_travels(&mut stmts, time_travels)?;
         transform_statements(&mut stmts)?;
 
         Ok(stmts)
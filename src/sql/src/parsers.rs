@@ -23,5 +23,6 @@ pub(crate) mod insert_parser;
 pub(crate) mod query_parser;
 pub(crate) mod set_var_parser;
 pub(crate) mod show_parser;
+pub(crate) mod time_travel_parser;
 pub(crate) mod tql_parser;
 pub(crate) mod truncate_parser;
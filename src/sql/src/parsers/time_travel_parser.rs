@@ -0,0 +1,286 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extracts `<table> AS OF VERSION | TIMESTAMP | SNAPSHOT <literal>` clauses.
+//!
+//! The sqlparser only parses table versions for a few dialects, so the clauses
+//! are removed from the token stream before parsing and attached to the parsed
+//! [Query](crate::statements::query::Query) afterwards.
+
+use snafu::ensure;
+use sqlparser::ast::{Ident, ObjectName, Value};
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, TokenWithLocation};
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::query::{AsOf, TableAsOf};
+use crate::statements::statement::Statement;
+
+/// A time travel clause and the index of the statement it belongs to.
+pub(crate) type StatementTimeTravel = (usize, TableAsOf);
+
+/// Removes the `AS OF` clauses from `tokens`.
+pub(crate) fn strip_time_travels(
+    tokens: Vec<TokenWithLocation>,
+) -> Result<(Vec<TokenWithLocation>, Vec<StatementTimeTravel>)> {
+    let mut output: Vec<TokenWithLocation> = Vec::with_capacity(tokens.len());
+    let mut time_travels = Vec::new();
+    // Index of the current non-empty statement.
+    let mut stmt_index = 0;
+    let mut stmt_has_content = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::SemiColon => {
+                if stmt_has_content {
+                    stmt_index += 1;
+                    stmt_has_content = false;
+                }
+            }
+            Token::Whitespace(_) | Token::EOF => {}
+            _ => stmt_has_content = true,
+        }
+
+        if is_word(&tokens[i].token, "AS") {
+            if let Some(table) = table_before(&output) {
+                if let Some((as_of, next)) = parse_as_of(&tokens, i)? {
+                    time_travels.push((stmt_index, TableAsOf { table, as_of }));
+                    i = next;
+                    continue;
+                }
+            }
+        }
+
+        output.push(tokens[i].clone());
+        i += 1;
+    }
+
+    Ok((output, time_travels))
+}
+
+/// Attaches the time travel clauses to the statements they belong to.
+pub(crate) fn attach_time_travels(
+    stmts: &mut [Statement],
+    time_travels: Vec<StatementTimeTravel>,
+) -> Result<()> {
+    for (index, time_travel) in time_travels {
+        match stmts.get_mut(index) {
+            Some(Statement::Query(query)) => {
+                ensure!(
+                    query
+                        .time_travels
+                        .iter()
+                        .all(|t| t.table != time_travel.table),
+                    error::InvalidSqlSnafu {
+                        msg: format!("Table {} has more than one AS OF clause", time_travel.table),
+                    }
+                );
+                query.time_travels.push(time_travel);
+            }
+            _ => {
+                return error::InvalidSqlSnafu {
+                    msg: format!(
+                        "{} is only supported for tables in queries",
+                        time_travel.as_of
+                    ),
+                }
+                .fail()
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses `AS OF <kind> <literal>` starting at `tokens[start]`, returns the clause and
+/// the index of the next token. Returns `None` if it isn't an `AS OF` clause.
+fn parse_as_of(tokens: &[TokenWithLocation], start: usize) -> Result<Option<(AsOf, usize)>> {
+    let Some(of) = next_non_whitespace(tokens, start + 1) else {
+        return Ok(None);
+    };
+    if !is_word(&tokens[of].token, "OF") {
+        return Ok(None);
+    }
+    let Some(kind) = next_non_whitespace(tokens, of + 1) else {
+        return Ok(None);
+    };
+    let Token::Word(kind_word) = &tokens[kind].token else {
+        return Ok(None);
+    };
+    let kind_name = kind_word.value.to_uppercase();
+    if !matches!(kind_name.as_str(), "VERSION" | "TIMESTAMP" | "SNAPSHOT") {
+        return Ok(None);
+    }
+
+    let literal = next_non_whitespace(tokens, kind + 1);
+    let as_of = match (kind_name.as_str(), literal.map(|i| &tokens[i].token)) {
+        ("VERSION", Some(Token::Number(n, false))) => AsOf::Version(n.parse().map_err(|_| {
+            error::InvalidSqlSnafu {
+                msg: format!("Invalid version in AS OF VERSION: {n}"),
+            }
+            .build()
+        })?),
+        ("TIMESTAMP", Some(Token::Number(n, false))) => {
+            AsOf::Timestamp(Value::Number(n.clone(), false))
+        }
+        ("TIMESTAMP", Some(Token::SingleQuotedString(s))) => {
+            AsOf::Timestamp(Value::SingleQuotedString(s.clone()))
+        }
+        ("SNAPSHOT", Some(Token::SingleQuotedString(s))) => AsOf::Snapshot(s.clone()),
+        (kind, found) => {
+            return error::InvalidSqlSnafu {
+                msg: format!(
+                    "Expect a literal after AS OF {kind}, found: {}",
+                    found.map(|t| t.to_string()).unwrap_or_default()
+                ),
+            }
+            .fail()
+        }
+    };
+
+    // The literal exists if we reach here.
+    Ok(Some((as_of, literal.unwrap() + 1)))
+}
+
+/// Returns the table name at the end of `tokens` if it follows `FROM`, `JOIN` or a comma.
+fn table_before(tokens: &[TokenWithLocation]) -> Option<ObjectName> {
+    let mut idents = Vec::new();
+    let mut i = tokens.len();
+    loop {
+        i = prev_non_whitespace(tokens, i)?;
+        let Token::Word(word) = &tokens[i].token else {
+            return None;
+        };
+        idents.push(Ident {
+            value: word.value.clone(),
+            quote_style: word.quote_style,
+        });
+
+        let prev = prev_non_whitespace(tokens, i)?;
+        if tokens[prev].token != Token::Period {
+            let is_table = match &tokens[prev].token {
+                Token::Word(w) => {
+                    w.quote_style.is_none() && matches!(w.keyword, Keyword::FROM | Keyword::JOIN)
+                }
+                Token::Comma => true,
+                _ => false,
+            };
+            if !is_table {
+                return None;
+            }
+            break;
+        }
+        i = prev;
+    }
+
+    idents.reverse();
+    Some(ParserContext::canonicalize_object_name(ObjectName(idents)))
+}
+
+fn is_word(token: &Token, value: &str) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(value))
+}
+
+fn next_non_whitespace(tokens: &[TokenWithLocation], start: usize) -> Option<usize> {
+    (start..tokens.len()).find(|i| !matches!(tokens[*i].token, Token::Whitespace(_)))
+}
+
+fn prev_non_whitespace(tokens: &[TokenWithLocation], end: usize) -> Option<usize> {
+    (0..end)
+        .rev()
+        .find(|i| !matches!(tokens[*i].token, Token::Whitespace(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Result<Vec<Statement>> {
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+    }
+
+    fn time_travels(sql: &str) -> Vec<TableAsOf> {
+        match parse(sql).unwrap().remove(0) {
+            Statement::Query(query) => query.time_travels,
+            _ => unreachable!(),
+        }
+    }
+
+    fn table_as_of(table: &[&str], as_of: AsOf) -> TableAsOf {
+        TableAsOf {
+            table: ObjectName(table.iter().map(|s| Ident::new(*s)).collect()),
+            as_of,
+        }
+    }
+
+    #[test]
+    fn test_parse_as_of() {
+        assert_eq!(
+            vec![table_as_of(&["t"], AsOf::Version(3))],
+            time_travels("SELECT * FROM t AS OF VERSION 3 WHERE a > 1")
+        );
+        assert_eq!(
+            vec![table_as_of(
+                &["public", "t"],
+                AsOf::Timestamp(Value::SingleQuotedString("2024-01-01 00:00:00".to_string()))
+            )],
+            time_travels("select * from Public.T as of timestamp '2024-01-01 00:00:00'")
+        );
+        assert_eq!(
+            vec![
+                table_as_of(&["a"], AsOf::Snapshot("s1".to_string())),
+                table_as_of(
+                    &["b"],
+                    AsOf::Timestamp(Value::Number("1000".to_string(), false))
+                ),
+            ],
+            time_travels(
+                "SELECT * FROM a AS OF SNAPSHOT 's1' JOIN b AS OF TIMESTAMP 1000 ON a.x = b.x"
+            )
+        );
+        // Aliases after the clause.
+        assert_eq!(
+            vec![table_as_of(&["t"], AsOf::Version(1))],
+            time_travels("SELECT x.a FROM t AS OF VERSION 1 AS x")
+        );
+        // A plain alias isn't a clause.
+        assert!(time_travels("SELECT * FROM t AS of_t").is_empty());
+    }
+
+    #[test]
+    fn test_parse_as_of_multiple_statements() {
+        let stmts = parse("SELECT 1;; SELECT * FROM t AS OF VERSION 2; SELECT * FROM t").unwrap();
+        assert_eq!(3, stmts.len());
+        let travels: Vec<_> = stmts
+            .iter()
+            .map(|stmt| match stmt {
+                Statement::Query(query) => query.time_travels.len(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(vec![0, 1, 0], travels);
+    }
+
+    #[test]
+    fn test_parse_invalid_as_of() {
+        assert!(parse("SELECT * FROM t AS OF VERSION 'a'").is_err());
+        assert!(parse("SELECT * FROM t AS OF SNAPSHOT 1").is_err());
+        assert!(parse("SELECT * FROM t AS OF VERSION 1, t AS OF VERSION 2").is_err());
+        assert!(parse("DELETE FROM t AS OF VERSION 1 WHERE a = 1").is_err());
+    }
+}
@@ -41,7 +41,7 @@ use common_time::Timestamp;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::constraint::{CURRENT_TIMESTAMP, CURRENT_TIMESTAMP_FN};
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, COMMENT_KEY};
-use datatypes::types::{cast, TimestampType};
+use datatypes::types::{cast, parse_string_to_jsonb, TimestampType};
 use datatypes::value::{OrderedF32, OrderedF64, Value};
 pub use option_map::OptionMap;
 use snafu::{ensure, OptionExt, ResultExt};
@@ -110,6 +110,16 @@ fn parse_string_to_value(
                 .fail()
             }
         }
+        ConcreteDataType::Json(_) => {
+            if let Ok(jsonb) = parse_string_to_jsonb(&s) {
+                Ok(Value::Binary(jsonb.into()))
+            } else {
+                ParseSqlValueSnafu {
+                    msg: format!("Failed to parse {s} to Json value"),
+                }
+                .fail()
+            }
+        }
         ConcreteDataType::Decimal128(_) => {
             if let Ok(val) = common_decimal::Decimal128::from_str(&s) {
                 Ok(Value::Decimal128(val))
@@ -485,6 +495,7 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
                 Ok(ConcreteDataType::decimal128_datatype(*p as u8, *s as i8))
             }
         },
+        SqlDataType::JSON => Ok(ConcreteDataType::json_datatype()),
         _ => error::SqlTypeNotSupportedSnafu {
             t: data_type.clone(),
         }
@@ -521,6 +532,7 @@ pub fn concrete_data_type_to_sql_data_type(data_type: &ConcreteDataType) -> Resu
         ConcreteDataType::Decimal128(d) => Ok(SqlDataType::Decimal(
             ExactNumberInfo::PrecisionAndScale(d.precision() as u64, d.scale() as u64),
         )),
+        ConcreteDataType::Json(_) => Ok(SqlDataType::JSON),
         ConcreteDataType::Duration(_)
         | ConcreteDataType::Null(_)
         | ConcreteDataType::List(_)
@@ -628,6 +640,7 @@ mod tests {
             SqlDataType::Interval,
             ConcreteDataType::interval_month_day_nano_datatype(),
         );
+        check_type(SqlDataType::JSON, ConcreteDataType::json_datatype());
     }
 
     #[test]
@@ -664,6 +677,22 @@ mod tests {
 
     #[test]
     fn test_sql_value_to_value() {
+        let sql_val = SqlValue::SingleQuotedString(r#"{"a":1}"#.to_string());
+        let v =
+            sql_value_to_value("a", &ConcreteDataType::json_datatype(), &sql_val, None).unwrap();
+        assert_eq!(
+            Value::Binary(
+                datatypes::types::parse_string_to_jsonb(r#"{"a":1}"#)
+                    .unwrap()
+                    .into()
+            ),
+            v
+        );
+        let sql_val = SqlValue::SingleQuotedString("not json".to_string());
+        assert!(
+            sql_value_to_value("a", &ConcreteDataType::json_datatype(), &sql_val, None).is_err()
+        );
+
         let sql_val = SqlValue::Null;
         assert_eq!(
             Value::Null,
@@ -14,7 +14,7 @@
 
 use std::fmt;
 
-use sqlparser::ast::Query as SpQuery;
+use sqlparser::ast::{ObjectName, Query as SpQuery, Value};
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::error::Error;
@@ -23,6 +23,37 @@ use crate::error::Error;
 #[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
 pub struct Query {
     pub inner: SpQuery,
+    /// Tables read at a historical state, from `<table> AS OF ...` clauses.
+    pub time_travels: Vec<TableAsOf>,
+}
+
+/// A `<table> AS OF ...` clause in a query.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub struct TableAsOf {
+    pub table: ObjectName,
+    pub as_of: AsOf,
+}
+
+/// The historical state in an `AS OF` clause.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut)]
+pub enum AsOf {
+    /// `AS OF VERSION <manifest version>`
+    Version(u64),
+    /// `AS OF TIMESTAMP <timestamp>`, either a quoted timestamp string or
+    /// milliseconds since the epoch.
+    Timestamp(Value),
+    /// `AS OF SNAPSHOT '<name>'`
+    Snapshot(String),
+}
+
+impl fmt::Display for AsOf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsOf::Version(version) => write!(f, "AS OF VERSION {version}"),
+            AsOf::Timestamp(timestamp) => write!(f, "AS OF TIMESTAMP {timestamp}"),
+            AsOf::Snapshot(name) => write!(f, "AS OF SNAPSHOT '{name}'"),
+        }
+    }
 }
 
 /// Automatically converts from sqlparser Query instance to SqlQuery.
@@ -30,7 +61,10 @@ impl TryFrom<SpQuery> for Query {
     type Error = Error;
 
     fn try_from(q: SpQuery) -> Result<Self, Self::Error> {
-        Ok(Query { inner: q })
+        Ok(Query {
+            inner: q,
+            time_travels: vec![],
+        })
     }
 }
 
@@ -20,11 +20,13 @@ use std::sync::Arc;
 
 use api::greptime_proto::v1::meta::{GrantedRegion as PbGrantedRegion, RegionRole as PbRegionRole};
 use async_trait::async_trait;
-use common_error::ext::BoxedError;
+use common_error::ext::{BoxedError, PlainError};
+use common_error::status_code::StatusCode;
 use common_recordbatch::SendableRecordBatchStream;
 use serde::{Deserialize, Serialize};
 
 use crate::logstore::entry;
+use crate::manifest::ManifestVersion;
 use crate::metadata::RegionMetadataRef;
 use crate::region_request::{AffectedRows, RegionRequest};
 use crate::storage::{RegionId, ScanRequest};
@@ -119,6 +121,66 @@ impl From<PbRegionRole> for RegionRole {
     }
 }
 
+/// A named snapshot of a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    /// Name of the snapshot.
+    pub name: String,
+    /// Manifest version the snapshot is taken at.
+    pub manifest_version: ManifestVersion,
+    /// Time in millis when the snapshot is created.
+    pub created_at: i64,
+}
+
+/// A file under the region directory that is not referenced by the region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanFileInfo {
+    /// Path of the file in the object store.
+    pub path: String,
+    /// Size of the file in bytes.
+    pub file_size: u64,
+}
+
+/// Orphan files found in a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanFilesInfo {
+    pub files: Vec<OrphanFileInfo>,
+    /// Whether the files are only reported but not deleted.
+    pub dry_run: bool,
+}
+
+/// Progress of the latest index build job of a region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexBuildProgressInfo {
+    /// Number of SSTs in the region when the job started.
+    pub total_files: u64,
+    /// Number of SSTs checked by the job.
+    pub processed_files: u64,
+    /// Number of SSTs whose new indexes are committed.
+    pub built_files: u64,
+    /// Number of SSTs that failed to build indexes.
+    pub failed_files: u64,
+    /// Start time of the job in millis.
+    pub start_time_millis: i64,
+    /// Whether the job is finished.
+    pub finished: bool,
+}
+
+/// Index build progress of the region `region_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionIndexBuildProgress {
+    pub region_id: RegionId,
+    pub progress: IndexBuildProgressInfo,
+}
+
+/// Returns the error for operations the engine doesn't support.
+fn unsupported(engine: &str, operation: &str) -> BoxedError {
+    BoxedError::new(PlainError::new(
+        format!("{operation} is not supported by engine {engine}"),
+        StatusCode::Unsupported,
+    ))
+}
+
 #[async_trait]
 pub trait RegionEngine: Send + Sync {
     /// Name of this engine
@@ -167,6 +229,58 @@ pub trait RegionEngine: Send + Sync {
     /// Returns the `None` if the region is not found.
     fn role(&self, region_id: RegionId) -> Option<RegionRole>;
 
+    /// Creates a snapshot named `name` of the region.
+    async fn create_snapshot(
+        &self,
+        _region_id: RegionId,
+        _name: &str,
+    ) -> Result<SnapshotInfo, BoxedError> {
+        Err(unsupported(self.name(), "create snapshot"))
+    }
+
+    /// Drops the snapshot named `name` of the region.
+    async fn drop_snapshot(&self, _region_id: RegionId, _name: &str) -> Result<(), BoxedError> {
+        Err(unsupported(self.name(), "drop snapshot"))
+    }
+
+    /// Lists snapshots of the region.
+    fn list_snapshots(&self, _region_id: RegionId) -> Result<Vec<SnapshotInfo>, BoxedError> {
+        Err(unsupported(self.name(), "list snapshots"))
+    }
+
+    /// Collects files under the region directory that are not referenced by the region.
+    ///
+    /// Only reports these files if `dry_run` is true, otherwise deletes them.
+    async fn gc_orphan_files(
+        &self,
+        _region_id: RegionId,
+        _dry_run: bool,
+    ) -> Result<OrphanFilesInfo, BoxedError> {
+        Err(unsupported(self.name(), "gc orphan files"))
+    }
+
+    /// Schedules a background job to build indexes for existing SSTs of the region.
+    ///
+    /// Rebuilds indexes of all SSTs if `rebuild_all` is true, otherwise only builds
+    /// missing or outdated indexes.
+    async fn build_index(
+        &self,
+        _region_id: RegionId,
+        _rebuild_all: bool,
+    ) -> Result<(), BoxedError> {
+        Err(unsupported(self.name(), "build index"))
+    }
+
+    /// Returns the progress of the latest index build job of the region.
+    ///
+    /// Returns `None` if the region never builds indexes.
+    fn index_build_progress(
+        &self,
+        _region_id: RegionId,
+    ) -> Result<Option<IndexBuildProgressInfo>, BoxedError> {
+        Err(unsupported(self.name(), "index build progress"))
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -23,6 +23,7 @@ use api::v1::region::{
 };
 use api::v1::{self, Rows, SemanticType};
 pub use common_base::AffectedRows;
+use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt};
 use strum::IntoStaticStr;
 
@@ -32,6 +33,7 @@ use crate::metadata::{
     RegionMetadata, Result,
 };
 use crate::path_utils::region_dir;
+use crate::region_engine::{OrphanFilesInfo, RegionIndexBuildProgress, SnapshotInfo};
 use crate::storage::{ColumnId, RegionId, ScanRequest};
 
 #[derive(Debug, IntoStaticStr)]
@@ -544,6 +546,63 @@ pub struct RegionCatchupRequest {
     pub entry_id: Option<entry::Id>,
 }
 
+/// Administrative actions on a region.
+///
+/// They are not in the gRPC region protocol so they are sent in JSON through the
+/// `do_action` API of the region server's Flight service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegionAction {
+    /// Creates a snapshot named `name`.
+    CreateSnapshot { region_id: RegionId, name: String },
+    /// Drops the snapshot named `name`.
+    DropSnapshot { region_id: RegionId, name: String },
+    /// Lists snapshots of the region.
+    ListSnapshots { region_id: RegionId },
+    /// Collects orphan files of the region, only reports them if `dry_run` is true.
+    GcOrphanFiles { region_id: RegionId, dry_run: bool },
+    /// Schedules a job to build indexes for existing SSTs, rebuilds all indexes
+    /// if `rebuild_all` is true.
+    BuildIndex {
+        region_id: RegionId,
+        rebuild_all: bool,
+    },
+    /// Gets the progress of the latest index build jobs of regions on a datanode.
+    ///
+    /// Regions that are not opened on the datanode or never build indexes are skipped.
+    IndexBuildProgress { region_ids: Vec<RegionId> },
+}
+
+impl RegionAction {
+    /// Type of the Flight action carrying [RegionAction]s.
+    pub const FLIGHT_ACTION_TYPE: &'static str = "region_action";
+
+    /// Returns the id of the region to take the action.
+    ///
+    /// Returns `None` if the action takes multiple regions.
+    pub fn region_id(&self) -> Option<RegionId> {
+        match self {
+            RegionAction::CreateSnapshot { region_id, .. }
+            | RegionAction::DropSnapshot { region_id, .. }
+            | RegionAction::ListSnapshots { region_id }
+            | RegionAction::GcOrphanFiles { region_id, .. }
+            | RegionAction::BuildIndex { region_id, .. } => Some(*region_id),
+            RegionAction::IndexBuildProgress { .. } => None,
+        }
+    }
+}
+
+/// Output of a [RegionAction].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RegionActionOutput {
+    /// The action has no output.
+    None,
+    Snapshot(SnapshotInfo),
+    Snapshots(Vec<SnapshotInfo>),
+    OrphanFiles(OrphanFilesInfo),
+    IndexBuildProgress(Vec<RegionIndexBuildProgress>),
+}
+
 impl fmt::Display for RegionRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -14,6 +14,9 @@
 
 use common_query::logical_plan::Expr;
 use common_recordbatch::OrderOption;
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::ManifestVersion;
 
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct ScanRequest {
@@ -29,4 +32,20 @@ pub struct ScanRequest {
     /// If set, it contains the amount of rows needed by the caller,
     /// The data source should return *at least* this number of rows if available.
     pub limit: Option<usize>,
+    /// Reads a historical state of the region instead of the latest one.
+    /// `None` to read the latest data.
+    pub time_travel: Option<TimeTravel>,
+}
+
+/// Historical state of a region to read.
+///
+/// A historical state only contains data persisted in SST files at that time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeTravel {
+    /// Reads the region as of the given manifest version.
+    Version(ManifestVersion),
+    /// Reads the region as of the given timestamp in milliseconds.
+    Timestamp(i64),
+    /// Reads a named snapshot of the region.
+    Snapshot(String),
 }
@@ -86,6 +86,15 @@ pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 pub const TTL_KEY: &str = "ttl";
 pub const REGIONS_KEY: &str = "regions";
 pub const STORAGE_KEY: &str = "storage";
+pub const APPEND_MODE_KEY: &str = "append_mode";
+/// Prefix of compaction options, e.g. `compaction.type`.
+pub const COMPACTION_OPTION_PREFIX: &str = "compaction.";
+/// Prefix of downsample options, e.g. `downsample.after`.
+pub const DOWNSAMPLE_OPTION_PREFIX: &str = "downsample.";
+/// Prefix of full-text index options, e.g. `index.full_text_index.column_ids`.
+pub const FULL_TEXT_INDEX_OPTION_PREFIX: &str = "index.full_text_index.";
+/// Prefix of bloom filter index options, e.g. `index.bloom_filter_index.column_ids`.
+pub const BLOOM_FILTER_INDEX_OPTION_PREFIX: &str = "index.bloom_filter_index.";
 
 impl TryFrom<&HashMap<String, String>> for TableOptions {
     type Error = error::Error;
@@ -325,9 +334,14 @@ pub fn valid_table_option(key: &str) -> bool {
             | TTL_KEY
             | REGIONS_KEY
             | STORAGE_KEY
+            | APPEND_MODE_KEY
             | PHYSICAL_TABLE_METADATA_KEY
             | LOGICAL_TABLE_METADATA_KEY
     ) | is_supported_in_s3(key)
+        | key.starts_with(COMPACTION_OPTION_PREFIX)
+        | key.starts_with(DOWNSAMPLE_OPTION_PREFIX)
+        | key.starts_with(FULL_TEXT_INDEX_OPTION_PREFIX)
+        | key.starts_with(BLOOM_FILTER_INDEX_OPTION_PREFIX)
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -353,6 +367,12 @@ mod tests {
         assert!(valid_table_option(REGIONS_KEY));
         assert!(valid_table_option(WRITE_BUFFER_SIZE_KEY));
         assert!(valid_table_option(STORAGE_KEY));
+        assert!(valid_table_option(APPEND_MODE_KEY));
+        assert!(valid_table_option("compaction.type"));
+        assert!(valid_table_option("compaction.stcs.min_threshold"));
+        assert!(valid_table_option("downsample.after"));
+        assert!(valid_table_option("index.full_text_index.column_ids"));
+        assert!(valid_table_option("index.bloom_filter_index.column_ids"));
         assert!(!valid_table_option("foo"));
     }
 
@@ -27,7 +27,7 @@ use datafusion_expr::expr::Expr as DfExpr;
 use datafusion_expr::TableProviderFilterPushDown as DfTableProviderFilterPushDown;
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalSortExpr;
-use store_api::storage::ScanRequest;
+use store_api::storage::{ScanRequest, TimeTravel};
 
 use super::scan::StreamScanAdapter;
 use crate::table::{TableRef, TableType};
@@ -54,6 +54,15 @@ impl DfTableProviderAdapter {
         self.scan_req.lock().unwrap().output_ordering = Some(order_opts.to_vec());
     }
 
+    /// Reads a historical state of the table.
+    pub fn with_time_travel(&self, time_travel: TimeTravel) {
+        self.scan_req.lock().unwrap().time_travel = Some(time_travel);
+    }
+
+    pub fn time_travel(&self) -> Option<TimeTravel> {
+        self.scan_req.lock().unwrap().time_travel.clone()
+    }
+
     #[cfg(feature = "testing")]
     pub fn get_scan_req(&self) -> ScanRequest {
         self.scan_req.lock().unwrap().clone()
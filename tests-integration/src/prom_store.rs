@@ -17,17 +17,20 @@ mod tests {
     use std::sync::Arc;
 
     use api::prom_store::remote::label_matcher::Type as MatcherType;
+    use api::prom_store::remote::read_request::ResponseType;
     use api::prom_store::remote::{
-        Label, LabelMatcher, Query, ReadRequest, ReadResponse, Sample, WriteRequest,
+        ChunkedReadResponse, Label, LabelMatcher, Query, ReadRequest, ReadResponse, Sample,
+        WriteRequest,
     };
     use common_catalog::consts::DEFAULT_CATALOG_NAME;
     use frontend::instance::Instance;
+    use futures::TryStreamExt;
     use prost::Message;
     use servers::http::prom_store::PHYSICAL_TABLE_PARAM;
     use servers::prom_store;
     use servers::query_handler::sql::SqlQueryHandler;
-    use servers::query_handler::PromStoreProtocolHandler;
-    use session::context::QueryContext;
+    use servers::query_handler::{PromStoreProtocolHandler, PromStoreResponseBody};
+    use session::context::{QueryContext, QueryContextRef};
 
     use crate::standalone::GreptimeDbStandaloneBuilder;
     use crate::tests;
@@ -145,10 +148,16 @@ mod tests {
             ..Default::default()
         };
 
-        let resp = instance.read(read_request, ctx.clone()).await.unwrap();
+        let resp = instance
+            .read(read_request.clone(), ctx.clone())
+            .await
+            .unwrap();
         assert_eq!(resp.content_type, "application/x-protobuf");
-        assert_eq!(resp.content_encoding, "snappy");
-        let body = prom_store::snappy_decompress(&resp.body).unwrap();
+        assert_eq!(resp.content_encoding.as_deref(), Some("snappy"));
+        let PromStoreResponseBody::Full(body) = resp.body else {
+            unreachable!()
+        };
+        let body = prom_store::snappy_decompress(&body).unwrap();
         let read_response = ReadResponse::decode(&body[..]).unwrap();
         let query_results = read_response.results;
         assert_eq!(2, query_results.len());
@@ -223,10 +232,59 @@ mod tests {
             ]
         );
 
+        test_prom_store_remote_read_streamed(instance, read_request, ctx.clone()).await;
+
         // check physical table if provided
         if let Some(physical_table) = physical_table {
             let sql = format!("DESC TABLE {physical_table};");
             instance.do_query(&sql, ctx).await[0].as_ref().unwrap();
         }
     }
+
+    async fn test_prom_store_remote_read_streamed(
+        instance: &Arc<Instance>,
+        mut read_request: ReadRequest,
+        ctx: QueryContextRef,
+    ) {
+        read_request.accepted_response_types = vec![ResponseType::StreamedXorChunks as i32];
+        let resp = instance.read(read_request, ctx).await.unwrap();
+        assert_eq!(
+            resp.content_type,
+            prom_store::STREAMED_XOR_CHUNKS_CONTENT_TYPE
+        );
+        assert!(resp.content_encoding.is_none());
+        let PromStoreResponseBody::Stream(stream) = resp.body else {
+            unreachable!()
+        };
+        let frames: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(2, frames.len());
+
+        let responses: Vec<_> = frames
+            .iter()
+            .map(|frame| {
+                let mut buf = &frame[..];
+                let len = prost::encoding::decode_varint(&mut buf).unwrap() as usize;
+                ChunkedReadResponse::decode(&buf[4..4 + len]).unwrap()
+            })
+            .collect();
+        assert_eq!(0, responses[0].query_index);
+        assert_eq!(1, responses[1].query_index);
+
+        // Labels of the chunked series are sorted by name.
+        let series = &responses[1].chunked_series;
+        assert_eq!(1, series.len());
+        assert_eq!(
+            vec!["__name__", "app", "idc"],
+            series[0]
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        let chunks = &series[0].chunks;
+        assert_eq!(1, chunks.len());
+        assert_eq!((1000, 3000), (chunks[0].min_time_ms, chunks[0].max_time_ms));
+        // The first 2 bytes of a XOR chunk is the number of samples.
+        assert_eq!(&[0, 3], &chunks[0].data[..2]);
+    }
 }
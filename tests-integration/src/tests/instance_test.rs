@@ -416,6 +416,55 @@ async fn test_execute_insert_by_select(instance: Arc<dyn MockInstance>) {
     check_output_stream(output, expected).await;
 }
 
+#[apply(both_instances_cases)]
+async fn test_execute_json(instance: Arc<dyn MockInstance>) {
+    let instance = instance.frontend();
+
+    let output = execute_sql(
+        &instance,
+        "create table jsons(j json, ts timestamp time index);",
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    // The JSON type is kept in the table metadata.
+    let output = execute_sql(&instance, "desc table jsons;").await;
+    let expected = "\
++--------+----------------------+-----+------+---------+---------------+
+| Column | Type                 | Key | Null | Default | Semantic Type |
++--------+----------------------+-----+------+---------+---------------+
+| j      | Json                 |     | YES  |         | FIELD         |
+| ts     | TimestampMillisecond | PRI | NO   |         | TIMESTAMP     |
++--------+----------------------+-----+------+---------+---------------+";
+    check_output_stream(output, expected).await;
+
+    let output = execute_sql(
+        &instance,
+        r#"insert into jsons(j, ts) values ('{"a": 1, "b": "x"}', 1), ('[1, 2, 3]', 2)"#,
+    )
+    .await;
+    assert!(matches!(output, Output::AffectedRows(2)));
+    assert!(
+        try_execute_sql(&instance, "insert into jsons(j, ts) values ('not json', 3)")
+            .await
+            .is_err()
+    );
+
+    let output = execute_sql(
+        &instance,
+        "select json_to_string(j) as j, ts from jsons order by ts",
+    )
+    .await;
+    let expected = "\
++-----------------+-------------------------+
+| j               | ts                      |
++-----------------+-------------------------+
+| {\"a\":1,\"b\":\"x\"} | 1970-01-01T00:00:00.001 |
+| [1,2,3]         | 1970-01-01T00:00:00.002 |
++-----------------+-------------------------+";
+    check_output_stream(output, expected).await;
+}
+
 #[apply(both_instances_cases)]
 async fn test_execute_query(instance: Arc<dyn MockInstance>) {
     let instance = instance.frontend();
@@ -773,6 +773,13 @@ auto_flush_interval = "30m"
 enable_experimental_write_cache = false
 experimental_write_cache_path = ""
 experimental_write_cache_size = "512MiB"
+enable_experimental_read_cache = false
+enable_cache_warm_up = false
+cache_warm_up_size = "256MiB"
+global_cache_warm_up_size = "1GiB"
+max_background_warm_ups = 1
+orphan_file_grace_period = "1h"
+max_background_index_builds = 1
 sst_write_buffer_size = "8MiB"
 parallel_scan_channel_size = 32
 allow_stale_entries = false
@@ -784,6 +791,7 @@ apply_on_query = "auto"
 write_buffer_size = "8MiB"
 mem_threshold_on_create = "64.0MiB"
 intermediate_path = ""
+compression = "none"
 
 [[datanode.region_engine]]
 